@@ -1,8 +1,13 @@
 //! Crossfade engine for smooth transitions between cues
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::time::{Duration, Instant};
 
+use super::cue::{CueState, EffectState, GlobalState, LayerState, PaintState};
+use super::snapshot::{CueValue, ModuleCueState, ParameterSnapshot};
+
 /// Fade curve types for crossfades
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FadeCurve {
@@ -14,12 +19,19 @@ pub enum FadeCurve {
 }
 
 /// Crossfade state tracker
+///
+/// A crossfade holds the outgoing and incoming [`CueState`] and blends every
+/// continuous value between them. Discrete values (integers, flags, text,
+/// visibility) switch once the fade passes the configured switch point.
 pub struct Crossfade {
     start_time: Instant,
     duration: Duration,
     curve: FadeCurve,
     from_cue_id: u32,
     to_cue_id: u32,
+    switch_point: f32,
+    from_state: CueState,
+    to_state: CueState,
 }
 
 impl Crossfade {
@@ -31,23 +43,36 @@ impl Crossfade {
             curve,
             from_cue_id,
             to_cue_id,
+            switch_point: 0.5,
+            from_state: CueState::default(),
+            to_state: CueState::default(),
         }
     }
 
+    /// Set the outgoing and incoming states to blend between
+    pub fn with_states(mut self, from: CueState, to: CueState) -> Self {
+        self.from_state = from;
+        self.to_state = to;
+        self
+    }
+
+    /// Set the progress (0.0 to 1.0) at which discrete values switch
+    pub fn with_switch_point(mut self, switch_point: f32) -> Self {
+        self.switch_point = switch_point.clamp(0.0, 1.0);
+        self
+    }
+
     /// Get the current progress (0.0 to 1.0)
     pub fn progress(&self) -> f32 {
-        let elapsed = self.start_time.elapsed();
-        if elapsed >= self.duration {
-            return 1.0;
-        }
-
-        let linear_progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
-        self.curve.apply(linear_progress)
+        self.progress_at(self.start_time.elapsed(), self.duration)
     }
 
     /// Check if the crossfade is complete
+    ///
+    /// Parameters with their own fade time may keep the crossfade running
+    /// past the cue fade duration.
     pub fn is_complete(&self) -> bool {
-        self.start_time.elapsed() >= self.duration
+        self.start_time.elapsed() >= self.total_duration()
     }
 
     /// Get the source cue ID
@@ -69,6 +94,124 @@ impl Crossfade {
     pub fn duration(&self) -> Duration {
         self.duration
     }
+
+    /// Get the switch point for discrete values
+    pub fn switch_point(&self) -> f32 {
+        self.switch_point
+    }
+
+    /// Duration until the slowest parameter has reached its target
+    pub fn total_duration(&self) -> Duration {
+        let module = &self.to_state.module_state;
+        module
+            .modules
+            .values()
+            .flat_map(|m| m.parts.values())
+            .chain(module.outputs.values())
+            .flat_map(|p| p.fade_times.values().copied())
+            .fold(self.duration, Duration::max)
+    }
+
+    /// Get the blended state for the current time
+    pub fn current_state(&self) -> CueState {
+        self.state_at(self.start_time.elapsed())
+    }
+
+    /// Get the blended state after `elapsed` time
+    pub fn state_at(&self, elapsed: Duration) -> CueState {
+        let progress = self.progress_at(elapsed, self.duration);
+        let from = &self.from_state;
+        let to = &self.to_state;
+
+        CueState {
+            layer_states: blend_maps(&from.layer_states, &to.layer_states, |a, b| {
+                blend_layer(a, b, progress, self.switch_point)
+            }),
+            paint_states: blend_maps(&from.paint_states, &to.paint_states, |a, b| PaintState {
+                parameters: blend_f32_map(&a.parameters, &b.parameters, progress),
+            }),
+            effect_states: blend_maps(&from.effect_states, &to.effect_states, |a, b| EffectState {
+                enabled: discrete(a.enabled, b.enabled, progress, self.switch_point),
+                parameters: blend_f32_map(&a.parameters, &b.parameters, progress),
+            }),
+            global_state: GlobalState {
+                playback_speed: interpolate_f32(
+                    from.global_state.playback_speed,
+                    to.global_state.playback_speed,
+                    progress,
+                ),
+                playback_position: interpolate_f32(
+                    from.global_state.playback_position,
+                    to.global_state.playback_position,
+                    progress,
+                ),
+                output_brightness: blend_f32_map(
+                    &from.global_state.output_brightness,
+                    &to.global_state.output_brightness,
+                    progress,
+                ),
+            },
+            module_state: self.blend_module_state(elapsed, progress),
+        }
+    }
+
+    fn blend_module_state(&self, elapsed: Duration, progress: f32) -> ModuleCueState {
+        let from = &self.from_state.module_state;
+        let to = &self.to_state.module_state;
+        let empty = ParameterSnapshot::new();
+
+        let mut blended = from.clone();
+        blended.active_module = discrete(
+            from.active_module,
+            to.active_module.or(from.active_module),
+            progress,
+            self.switch_point,
+        );
+
+        for (module_id, module) in &to.modules {
+            for (part_id, part) in &module.parts {
+                let outgoing = from.part(*module_id, *part_id).unwrap_or(&empty);
+                *blended.part_mut(*module_id, *part_id) =
+                    self.blend_parameters(outgoing, part, elapsed);
+            }
+        }
+
+        for (output_id, output) in &to.outputs {
+            let outgoing = from.outputs.get(output_id).unwrap_or(&empty);
+            *blended.output_mut(*output_id) = self.blend_parameters(outgoing, output, elapsed);
+        }
+
+        blended
+    }
+
+    /// Blend two parameter sets, honouring per-parameter fade times
+    fn blend_parameters(
+        &self,
+        from: &ParameterSnapshot,
+        to: &ParameterSnapshot,
+        elapsed: Duration,
+    ) -> ParameterSnapshot {
+        let mut blended = from.clone();
+        for (name, target) in &to.parameters {
+            let progress = self.progress_at(elapsed, to.fade_time(name, self.duration));
+            let value = match from.parameters.get(name) {
+                Some(source) => blend_value(source, target, progress, self.switch_point),
+                None => target.clone(),
+            };
+            blended.parameters.insert(name.clone(), value);
+        }
+        blended.fade_times = to.fade_times.clone();
+        blended
+    }
+
+    fn progress_at(&self, elapsed: Duration, duration: Duration) -> f32 {
+        if elapsed >= duration {
+            return 1.0;
+        }
+
+        let linear_progress = elapsed.as_secs_f32() / duration.as_secs_f32();
+        self.curve.apply(linear_progress)
+    }
 }
 
 impl FadeCurve {
@@ -105,6 +248,64 @@ pub fn interpolate_position(from: (f32, f32), to: (f32, f32), progress: f32) ->
     )
 }
 
+/// Pick the outgoing or incoming discrete value depending on the switch point
+pub fn discrete<T>(from: T, to: T, progress: f32, switch_point: f32) -> T {
+    if progress >= switch_point {
+        to
+    } else {
+        from
+    }
+}
+
+/// Blend two captured parameter values
+///
+/// Floats are interpolated. Everything else switches at `switch_point`,
+/// including integers, which are mostly ids and enum indices with no
+/// meaningful values in between.
+pub fn blend_value(from: &CueValue, to: &CueValue, progress: f32, switch_point: f32) -> CueValue {
+    match (from, to) {
+        (CueValue::Float(a), CueValue::Float(b)) => {
+            CueValue::Float(interpolate_f32(*a, *b, progress))
+        }
+        _ => discrete(from, to, progress, switch_point).clone(),
+    }
+}
+
+fn blend_layer(from: &LayerState, to: &LayerState, progress: f32, switch_point: f32) -> LayerState {
+    LayerState {
+        opacity: interpolate_f32(from.opacity, to.opacity, progress),
+        visible: discrete(from.visible, to.visible, progress, switch_point),
+        position: interpolate_position(from.position, to.position, progress),
+        rotation: interpolate_f32(from.rotation, to.rotation, progress),
+        scale: interpolate_f32(from.scale, to.scale, progress),
+    }
+}
+
+fn blend_f32_map<K: Eq + Hash + Clone>(
+    from: &HashMap<K, f32>,
+    to: &HashMap<K, f32>,
+    progress: f32,
+) -> HashMap<K, f32> {
+    blend_maps(from, to, |a, b| interpolate_f32(*a, *b, progress))
+}
+
+/// Blend two keyed maps; keys present on only one side are kept as they are
+fn blend_maps<K: Eq + Hash + Clone, V: Clone>(
+    from: &HashMap<K, V>,
+    to: &HashMap<K, V>,
+    blend: impl Fn(&V, &V) -> V,
+) -> HashMap<K, V> {
+    let mut blended = from.clone();
+    for (key, target) in to {
+        let value = match from.get(key) {
+            Some(source) => blend(source, target),
+            None => target.clone(),
+        };
+        blended.insert(key.clone(), value);
+    }
+    blended
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,6 +364,85 @@ mod tests {
         assert_eq!(crossfade.duration(), Duration::from_secs(2));
     }
 
+    fn states(from_opacity: f32, to_opacity: f32) -> (CueState, CueState) {
+        let mut from = CueState::default();
+        from.layer_states.insert(
+            0,
+            LayerState::new(from_opacity, false, (0.0, 0.0), 0.0, 1.0),
+        );
+        from.module_state
+            .part_mut(1, 1)
+            .set_parameter("speed", CueValue::Float(0.0));
+        from.module_state
+            .part_mut(1, 1)
+            .set_parameter("path", CueValue::Text("a.mp4".to_string()));
+
+        let mut to = CueState::default();
+        to.layer_states.insert(
+            0,
+            LayerState::new(to_opacity, true, (100.0, 0.0), 90.0, 2.0),
+        );
+        to.module_state
+            .part_mut(1, 1)
+            .set_parameter("speed", CueValue::Float(2.0));
+        to.module_state
+            .part_mut(1, 1)
+            .set_parameter("path", CueValue::Text("b.mp4".to_string()));
+        (from, to)
+    }
+
+    #[test]
+    fn test_state_interpolation() {
+        let (from, to) = states(0.0, 1.0);
+        let crossfade = Crossfade::new(0, 1, Duration::from_secs(2), FadeCurve::Linear)
+            .with_states(from, to)
+            .with_switch_point(0.75);
+
+        let half = crossfade.state_at(Duration::from_secs(1));
+        let layer = &half.layer_states[&0];
+        assert_eq!(layer.opacity, 0.5);
+        assert_eq!(layer.position, (50.0, 0.0));
+        assert_eq!(layer.scale, 1.5);
+        assert!(!layer.visible);
+
+        let part = half.module_state.part(1, 1).unwrap();
+        assert_eq!(part.parameters["speed"], CueValue::Float(1.0));
+        assert_eq!(part.parameters["path"], CueValue::Text("a.mp4".to_string()));
+
+        let late = crossfade.state_at(Duration::from_millis(1600));
+        assert!(late.layer_states[&0].visible);
+        let part = late.module_state.part(1, 1).unwrap();
+        assert_eq!(part.parameters["path"], CueValue::Text("b.mp4".to_string()));
+    }
+
+    #[test]
+    fn test_per_parameter_fade_time() {
+        let (from, mut to) = states(0.0, 1.0);
+        to.module_state
+            .part_mut(1, 1)
+            .set_fade_time("speed", Duration::from_secs(4));
+        let crossfade =
+            Crossfade::new(0, 1, Duration::from_secs(2), FadeCurve::Linear).with_states(from, to);
+
+        assert_eq!(crossfade.total_duration(), Duration::from_secs(4));
+
+        let state = crossfade.state_at(Duration::from_secs(2));
+        assert_eq!(state.layer_states[&0].opacity, 1.0);
+        let part = state.module_state.part(1, 1).unwrap();
+        assert_eq!(part.parameters["speed"], CueValue::Float(1.0));
+    }
+
+    #[test]
+    fn test_blend_value_int_switches() {
+        // Part ids must not pass through ids in between
+        let (from, to) = (CueValue::Int(2), CueValue::Int(9));
+        assert_eq!(blend_value(&from, &to, 0.4, 0.5), from);
+        assert_eq!(blend_value(&from, &to, 0.5, 0.5), to);
+
+        let blended = blend_value(&CueValue::Float(0.0), &CueValue::Float(3.0), 0.5, 0.5);
+        assert_eq!(blended, CueValue::Float(1.5));
+    }
+
     #[test]
     fn test_crossfade_progress() {
         let crossfade = Crossfade::new(0, 1, Duration::from_millis(100), FadeCurve::Linear);
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use super::crossfade::FadeCurve;
+use super::snapshot::ModuleCueState;
 use super::triggers::{MidiTrigger, OscTrigger, TimeTrigger};
 
 /// A cue stores a complete snapshot of project state
@@ -23,10 +24,16 @@ pub struct Cue {
     pub paint_states: HashMap<u32, PaintState>,
     pub effect_states: HashMap<u32, EffectState>,
     pub global_state: GlobalState,
+    /// Module part parameters, active module and output settings
+    #[serde(default)]
+    pub module_state: ModuleCueState,
 
     // Transition settings
     pub fade_duration: Duration,
     pub fade_curve: FadeCurve,
+    /// Fade progress (0.0 to 1.0) at which discrete values switch
+    #[serde(default = "default_switch_point")]
+    pub switch_point: f32,
 
     // Triggers
     pub auto_follow: Option<Duration>, // Auto-advance after duration
@@ -35,8 +42,22 @@ pub struct Cue {
     pub osc_trigger: Option<OscTrigger>,
 }
 
+fn default_switch_point() -> f32 {
+    0.5
+}
+
+/// Complete state carried by a live cue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CueState {
+    pub layer_states: HashMap<u32, LayerState>,
+    pub paint_states: HashMap<u32, PaintState>,
+    pub effect_states: HashMap<u32, EffectState>,
+    pub global_state: GlobalState,
+    pub module_state: ModuleCueState,
+}
+
 /// Snapshot of a layer's state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LayerState {
     /// Global opacity multiplier (0.0 to 1.0).
     pub opacity: f32,
@@ -50,20 +71,20 @@ pub struct LayerState {
 }
 
 /// Snapshot of a paint's state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PaintState {
     pub parameters: HashMap<String, f32>,
 }
 
 /// Snapshot of an effect's state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EffectState {
     pub enabled: bool,
     pub parameters: HashMap<String, f32>,
 }
 
 /// Global playback state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GlobalState {
     pub playback_speed: f32,
     pub playback_position: f32,
@@ -91,8 +112,10 @@ impl Cue {
             paint_states: HashMap::new(),
             effect_states: HashMap::new(),
             global_state: GlobalState::default(),
+            module_state: ModuleCueState::default(),
             fade_duration: Duration::from_secs(2),
             fade_curve: FadeCurve::Linear,
+            switch_point: default_switch_point(),
             auto_follow: None,
             midi_trigger: None,
             time_trigger: None,
@@ -120,6 +143,12 @@ impl Cue {
         self
     }
 
+    /// Set the fade progress at which discrete values switch
+    pub fn with_switch_point(mut self, switch_point: f32) -> Self {
+        self.switch_point = switch_point.clamp(0.0, 1.0);
+        self
+    }
+
     /// Set auto-follow duration
     pub fn with_auto_follow(mut self, duration: Duration) -> Self {
         self.auto_follow = Some(duration);
@@ -141,11 +170,28 @@ impl Cue {
         self.effect_states.insert(effect_id, state);
     }
 
+    /// Set the module state snapshot
+    pub fn set_module_state(&mut self, state: ModuleCueState) {
+        self.module_state = state;
+    }
+
+    /// Get the state stored in this cue
+    pub fn state(&self) -> CueState {
+        CueState {
+            layer_states: self.layer_states.clone(),
+            paint_states: self.paint_states.clone(),
+            effect_states: self.effect_states.clone(),
+            global_state: self.global_state.clone(),
+            module_state: self.module_state.clone(),
+        }
+    }
+
     /// Check if this cue has any state
     pub fn is_empty(&self) -> bool {
         self.layer_states.is_empty()
             && self.paint_states.is_empty()
             && self.effect_states.is_empty()
+            && self.module_state.is_empty()
     }
 }
 
@@ -235,6 +281,23 @@ mod tests {
         assert_eq!(cue.layer_states.len(), 1);
     }
 
+    #[test]
+    fn test_cue_with_module_state() {
+        use crate::cue::snapshot::CueValue;
+
+        let mut cue = Cue::new(0, "Test Cue".to_string());
+        let mut state = ModuleCueState::new();
+        state
+            .part_mut(1, 2)
+            .set_parameter("opacity", CueValue::Float(0.5));
+        cue.set_module_state(state);
+
+        assert!(!cue.is_empty());
+        let json = serde_json::to_string(&cue).unwrap();
+        let deserialized: Cue = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.module_state, cue.module_state);
+    }
+
     #[test]
     fn test_layer_state() {
         let state = LayerState::new(0.75, true, (100.0, 200.0), 45.0, 1.5);
@@ -3,7 +3,8 @@
 use std::time::Duration;
 
 use super::crossfade::Crossfade;
-use super::cue::Cue;
+use super::cue::{Cue, CueState};
+use super::snapshot::ModuleCueState;
 
 use crate::{error::ControlError, Result};
 
@@ -22,6 +23,7 @@ pub struct CueList {
     next_cue: Option<u32>,
     state: CueListState,
     current_crossfade: Option<Crossfade>,
+    tracking: bool,
 }
 
 impl CueList {
@@ -33,13 +35,25 @@ impl CueList {
             next_cue: None,
             state: CueListState::Idle,
             current_crossfade: None,
+            tracking: true,
         }
     }
 
+    /// Enable or disable tracking (cues store only changed module values)
+    pub fn set_tracking(&mut self, tracking: bool) {
+        self.tracking = tracking;
+    }
+
+    /// Check if tracking is enabled
+    pub fn is_tracking(&self) -> bool {
+        self.tracking
+    }
+
     /// Add a cue to the list
     pub fn add_cue(&mut self, cue: Cue) {
         self.cues.push(cue);
         self.sort_cues();
+        self.update_next_cue();
     }
 
     /// Remove a cue by ID
@@ -61,6 +75,47 @@ impl CueList {
         self.cues.iter_mut().find(|c| c.id == id)
     }
 
+    /// Store a captured module state in a cue
+    ///
+    /// With tracking enabled only the values that differ from the resolved
+    /// state of the preceding cue are stored.
+    pub fn store_module_state(&mut self, id: u32, state: ModuleCueState) -> Result<()> {
+        let stored = if self.tracking {
+            match self.previous_cue_id(id) {
+                Some(prev_id) => {
+                    let previous = self.resolved_module_state(prev_id).unwrap_or_default();
+                    state.diff(&previous)
+                }
+                None => state,
+            }
+        } else {
+            state
+        };
+
+        let cue = self
+            .get_cue_mut(id)
+            .ok_or_else(|| ControlError::TargetNotFound(format!("Cue {} not found", id)))?;
+        cue.set_module_state(stored);
+        Ok(())
+    }
+
+    /// Resolve the complete module state that is active when a cue is live
+    ///
+    /// With tracking enabled the values of all preceding cues are carried
+    /// forward until a later cue changes them.
+    pub fn resolved_module_state(&self, id: u32) -> Option<ModuleCueState> {
+        let index = self.cues.iter().position(|c| c.id == id)?;
+        if !self.tracking {
+            return Some(self.cues[index].module_state.clone());
+        }
+
+        let mut resolved = ModuleCueState::new();
+        for cue in &self.cues[..=index] {
+            resolved.merge(&cue.module_state);
+        }
+        Some(resolved)
+    }
+
     /// Get all cues
     pub fn cues(&self) -> &[Cue] {
         &self.cues
@@ -77,6 +132,10 @@ impl CueList {
     }
 
     /// Go to a specific cue
+    ///
+    /// If a crossfade is already running it is interrupted: the new fade
+    /// starts from the values that are currently live instead of jumping
+    /// back to the outgoing cue.
     pub fn goto_cue(&mut self, id: u32, fade_duration: Option<Duration>) -> Result<()> {
         let cue = self
             .get_cue(id)
@@ -84,10 +143,27 @@ impl CueList {
 
         let duration = fade_duration.unwrap_or(cue.fade_duration);
         let curve = cue.fade_curve;
-
-        if let Some(current) = self.current_cue {
+        let switch_point = cue.switch_point;
+        let to_state = self.cue_state(id).unwrap_or_default();
+
+        if let Some(running) = self.current_crossfade.take() {
+            // Interrupt the running fade and continue from its current values
+            let from_id = running.to_cue_id();
+            self.current_cue = Some(from_id);
+            self.current_crossfade = Some(
+                Crossfade::new(from_id, id, duration, curve)
+                    .with_states(running.current_state(), to_state)
+                    .with_switch_point(switch_point),
+            );
+            self.state = CueListState::Crossfading;
+        } else if let Some(current) = self.current_cue {
             // Start crossfade from current to target
-            self.current_crossfade = Some(Crossfade::new(current, id, duration, curve));
+            let from_state = self.cue_state(current).unwrap_or_default();
+            self.current_crossfade = Some(
+                Crossfade::new(current, id, duration, curve)
+                    .with_states(from_state, to_state)
+                    .with_switch_point(switch_point),
+            );
             self.state = CueListState::Crossfading;
         } else {
             // No current cue, just set it
@@ -99,6 +175,21 @@ impl CueList {
         Ok(())
     }
 
+    /// Get the complete state of a cue, with tracked module values resolved
+    pub fn cue_state(&self, id: u32) -> Option<CueState> {
+        let mut state = self.get_cue(id)?.state();
+        state.module_state = self.resolved_module_state(id)?;
+        Some(state)
+    }
+
+    /// Get the state that is live right now, blended while crossfading
+    pub fn live_state(&self) -> Option<CueState> {
+        match &self.current_crossfade {
+            Some(crossfade) => Some(crossfade.current_state()),
+            None => self.current_cue.and_then(|id| self.cue_state(id)),
+        }
+    }
+
     /// Go to the next cue in the list
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<()> {
@@ -113,7 +204,7 @@ impl CueList {
 
     /// Go to the previous cue in the list
     pub fn prev(&mut self) -> Result<()> {
-        if let Some(current_id) = self.current_cue {
+        if let Some(current_id) = self.playhead() {
             let current_index = self
                 .cues
                 .iter()
@@ -158,6 +249,14 @@ impl CueList {
         self.state
     }
 
+    /// Release the list, leaving no cue live
+    pub fn release(&mut self) {
+        self.current_cue = None;
+        self.current_crossfade = None;
+        self.state = CueListState::Idle;
+        self.update_next_cue();
+    }
+
     /// Clear all cues
     pub fn clear(&mut self) {
         self.cues.clear();
@@ -177,6 +276,20 @@ impl CueList {
         self.cues.is_empty()
     }
 
+    /// The cue the list is on or fading towards
+    fn playhead(&self) -> Option<u32> {
+        self.current_crossfade
+            .as_ref()
+            .map(|c| c.to_cue_id())
+            .or(self.current_cue)
+    }
+
+    /// Get the ID of the cue preceding `id` in list order
+    fn previous_cue_id(&self, id: u32) -> Option<u32> {
+        let index = self.cues.iter().position(|c| c.id == id)?;
+        index.checked_sub(1).map(|i| self.cues[i].id)
+    }
+
     /// Sort cues by ID
     fn sort_cues(&mut self) {
         self.cues.sort_by_key(|c| c.id);
@@ -184,7 +297,7 @@ impl CueList {
 
     /// Update the next cue based on current cue
     fn update_next_cue(&mut self) {
-        if let Some(current_id) = self.current_cue {
+        if let Some(current_id) = self.playhead() {
             if let Some(current_index) = self.cues.iter().position(|c| c.id == current_id) {
                 if current_index + 1 < self.cues.len() {
                     self.next_cue = Some(self.cues[current_index + 1].id);
@@ -207,6 +320,7 @@ impl Default for CueList {
 #[cfg(test)]
 mod tests {
     use super::super::crossfade::FadeCurve;
+    use super::super::test_util::cue_with_opacity;
     use super::*;
 
     #[test]
@@ -277,6 +391,77 @@ mod tests {
         assert_eq!(list.current_cue(), Some(0));
     }
 
+    #[test]
+    fn test_tracking_stores_only_changes() {
+        use crate::cue::snapshot::CueValue;
+
+        let mut list = CueList::new();
+        list.add_cue(Cue::new(0, "Cue 1".to_string()));
+        list.add_cue(Cue::new(1, "Cue 2".to_string()));
+
+        let mut first = ModuleCueState::new();
+        first
+            .part_mut(1, 1)
+            .set_parameter("opacity", CueValue::Float(1.0));
+        first
+            .part_mut(1, 1)
+            .set_parameter("speed", CueValue::Float(1.0));
+        list.store_module_state(0, first.clone()).unwrap();
+
+        let mut second = first.clone();
+        second
+            .part_mut(1, 1)
+            .set_parameter("opacity", CueValue::Float(0.0));
+        list.store_module_state(1, second.clone()).unwrap();
+
+        let stored = &list.get_cue(1).unwrap().module_state;
+        assert_eq!(stored.part(1, 1).unwrap().parameters.len(), 1);
+        assert_eq!(list.resolved_module_state(1), Some(second));
+        assert_eq!(list.resolved_module_state(0), Some(first));
+    }
+
+    fn fading_cue(id: u32, opacity: f32) -> Cue {
+        cue_with_opacity(id, 1, opacity)
+            .with_fade_duration(Duration::from_secs(60))
+            .with_fade_curve(FadeCurve::Linear)
+    }
+
+    #[test]
+    fn test_live_state_blends_during_crossfade() {
+        let mut list = CueList::new();
+        list.add_cue(fading_cue(0, 0.0));
+        list.add_cue(fading_cue(1, 1.0));
+
+        list.goto_cue(0, None).unwrap();
+        assert_eq!(list.live_state().unwrap().layer_states[&0].opacity, 0.0);
+
+        list.goto_cue(1, None).unwrap();
+        let opacity = list.live_state().unwrap().layer_states[&0].opacity;
+        assert!((0.0..0.1).contains(&opacity));
+    }
+
+    #[test]
+    fn test_interrupt_crossfade() {
+        let mut list = CueList::new();
+        list.add_cue(fading_cue(0, 0.0));
+        list.add_cue(fading_cue(1, 1.0));
+        list.add_cue(fading_cue(2, 0.5));
+
+        list.goto_cue(0, None).unwrap();
+        list.goto_cue(1, None).unwrap();
+        assert_eq!(list.next_cue(), Some(2));
+
+        // GO again while the first fade is still running
+        list.next().unwrap();
+        let crossfade = list.current_crossfade().unwrap();
+        assert_eq!(crossfade.from_cue_id(), 1);
+        assert_eq!(crossfade.to_cue_id(), 2);
+
+        // The new fade starts from the interrupted values, not from cue 1
+        let start = crossfade.state_at(Duration::ZERO);
+        assert!(start.layer_states[&0].opacity < 0.1);
+    }
+
     #[test]
     fn test_crossfade() {
         let mut list = CueList::new();
@@ -5,8 +5,12 @@
 //!
 //! ## Features
 //!
-//! - **Cues**: Snapshots of complete project state
-//! - **Crossfades**: Smooth transitions with configurable curves
+//! - **Cues**: Snapshots of complete project state, including module graphs
+//! - **Tracking**: Cues store only the values that changed
+//! - **Cue Stacks**: Multiple independent cue lists running in parallel
+//! - **Crossfades**: Every numeric value is blended with configurable curves,
+//!   discrete values switch at a configurable point, and a new GO interrupts
+//!   a running fade from its current values
 //! - **Triggers**: MIDI, OSC, and time-based cue activation
 //! - **Auto-follow**: Automatic progression through cue list
 //!
@@ -65,9 +69,15 @@ pub mod crossfade;
 #[allow(clippy::module_inception)]
 pub mod cue;
 pub mod cue_list;
+pub mod snapshot;
+pub mod stack;
+#[cfg(test)]
+mod test_util;
 pub mod triggers;
 
-pub use crossfade::{interpolate_f32, interpolate_position, Crossfade, FadeCurve};
-pub use cue::{Cue, EffectState, GlobalState, LayerState, PaintState};
+pub use crossfade::{blend_value, interpolate_f32, interpolate_position, Crossfade, FadeCurve};
+pub use cue::{Cue, CueState, EffectState, GlobalState, LayerState, PaintState};
 pub use cue_list::{CueList, CueListState};
+pub use snapshot::{CueValue, ModuleCueState, ModuleSnapshot, ParameterSnapshot};
+pub use stack::{CueStack, CueStacks};
 pub use triggers::{MidiTrigger, MidiTriggerType, OscTrigger, TimeTrigger};
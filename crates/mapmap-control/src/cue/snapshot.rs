@@ -0,0 +1,296 @@
+//! Module graph snapshots stored in cues
+//!
+//! The control crate does not know about `MapFlowModule` directly, so module
+//! state is captured as flat parameter maps keyed by module, part and output
+//! ids. The application is responsible for extracting and applying these
+//! values (see `mapmap::orchestration::cues`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A single captured parameter value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CueValue {
+    /// Continuous value
+    Float(f32),
+    /// Integer value (ids, counts, enum indices); switched, not interpolated
+    Int(i64),
+    /// Integer value above `i64::MAX`; switched, not interpolated
+    UInt(u64),
+    /// Boolean flag
+    Bool(bool),
+    /// Text value (paths, names, enum variants)
+    Text(String),
+}
+
+impl CueValue {
+    /// Returns the value as `f32` if it is numeric
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            CueValue::Float(v) => Some(*v),
+            CueValue::Int(v) => Some(*v as f32),
+            CueValue::UInt(v) => Some(*v as f32),
+            _ => None,
+        }
+    }
+
+    /// Check whether the value is a number; only floats are interpolated
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            CueValue::Float(_) | CueValue::Int(_) | CueValue::UInt(_)
+        )
+    }
+}
+
+/// Captured parameters of one part or output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParameterSnapshot {
+    /// Parameter values keyed by parameter path
+    pub parameters: HashMap<String, CueValue>,
+    /// Per-parameter fade times overriding the cue fade duration
+    #[serde(default)]
+    pub fade_times: HashMap<String, Duration>,
+}
+
+impl ParameterSnapshot {
+    /// Create an empty parameter snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter value
+    pub fn set_parameter(&mut self, name: impl Into<String>, value: CueValue) {
+        self.parameters.insert(name.into(), value);
+    }
+
+    /// Set the fade time for a single parameter
+    pub fn set_fade_time(&mut self, name: impl Into<String>, duration: Duration) {
+        self.fade_times.insert(name.into(), duration);
+    }
+
+    /// Get the fade time for a parameter, falling back to `default`
+    pub fn fade_time(&self, name: &str, default: Duration) -> Duration {
+        self.fade_times.get(name).copied().unwrap_or(default)
+    }
+
+    /// Check if the snapshot holds no values
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Return only the values that differ from `previous`
+    pub fn diff(&self, previous: &ParameterSnapshot) -> ParameterSnapshot {
+        let mut changed = ParameterSnapshot::new();
+        for (name, value) in &self.parameters {
+            if previous.parameters.get(name) != Some(value) {
+                changed.parameters.insert(name.clone(), value.clone());
+                if let Some(fade) = self.fade_times.get(name) {
+                    changed.fade_times.insert(name.clone(), *fade);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Overlay the values of `other` on top of this snapshot
+    pub fn merge(&mut self, other: &ParameterSnapshot) {
+        for (name, value) in &other.parameters {
+            self.parameters.insert(name.clone(), value.clone());
+        }
+        for (name, fade) in &other.fade_times {
+            self.fade_times.insert(name.clone(), *fade);
+        }
+    }
+}
+
+/// Captured part parameters of one module
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleSnapshot {
+    /// Part snapshots keyed by part id
+    pub parts: HashMap<u64, ParameterSnapshot>,
+}
+
+impl ModuleSnapshot {
+    /// Create an empty module snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if the snapshot holds no values
+    pub fn is_empty(&self) -> bool {
+        self.parts.values().all(|p| p.is_empty())
+    }
+
+    /// Return only the part values that differ from `previous`
+    pub fn diff(&self, previous: &ModuleSnapshot) -> ModuleSnapshot {
+        let empty = ParameterSnapshot::new();
+        let parts = self
+            .parts
+            .iter()
+            .filter_map(|(id, part)| {
+                let changed = part.diff(previous.parts.get(id).unwrap_or(&empty));
+                (!changed.is_empty()).then_some((*id, changed))
+            })
+            .collect();
+        ModuleSnapshot { parts }
+    }
+
+    /// Overlay the values of `other` on top of this snapshot
+    pub fn merge(&mut self, other: &ModuleSnapshot) {
+        for (id, part) in &other.parts {
+            self.parts.entry(*id).or_default().merge(part);
+        }
+    }
+}
+
+/// Module-graph state captured by a cue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleCueState {
+    /// Module to select when the cue fires
+    pub active_module: Option<u64>,
+    /// Part parameters keyed by module id
+    pub modules: HashMap<u64, ModuleSnapshot>,
+    /// Output settings keyed by output id
+    pub outputs: HashMap<u64, ParameterSnapshot>,
+}
+
+impl ModuleCueState {
+    /// Create an empty module cue state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if the state holds no values
+    pub fn is_empty(&self) -> bool {
+        self.active_module.is_none()
+            && self.modules.values().all(|m| m.is_empty())
+            && self.outputs.values().all(|o| o.is_empty())
+    }
+
+    /// Get the captured parameters of a part
+    pub fn part(&self, module_id: u64, part_id: u64) -> Option<&ParameterSnapshot> {
+        self.modules.get(&module_id)?.parts.get(&part_id)
+    }
+
+    /// Get or create the parameter snapshot of a part
+    pub fn part_mut(&mut self, module_id: u64, part_id: u64) -> &mut ParameterSnapshot {
+        self.modules
+            .entry(module_id)
+            .or_default()
+            .parts
+            .entry(part_id)
+            .or_default()
+    }
+
+    /// Get or create the parameter snapshot of an output
+    pub fn output_mut(&mut self, output_id: u64) -> &mut ParameterSnapshot {
+        self.outputs.entry(output_id).or_default()
+    }
+
+    /// Return only the values that differ from `previous` (tracking)
+    pub fn diff(&self, previous: &ModuleCueState) -> ModuleCueState {
+        let empty_module = ModuleSnapshot::new();
+        let empty_params = ParameterSnapshot::new();
+
+        let modules = self
+            .modules
+            .iter()
+            .filter_map(|(id, module)| {
+                let changed = module.diff(previous.modules.get(id).unwrap_or(&empty_module));
+                (!changed.is_empty()).then_some((*id, changed))
+            })
+            .collect();
+
+        let outputs = self
+            .outputs
+            .iter()
+            .filter_map(|(id, output)| {
+                let changed = output.diff(previous.outputs.get(id).unwrap_or(&empty_params));
+                (!changed.is_empty()).then_some((*id, changed))
+            })
+            .collect();
+
+        ModuleCueState {
+            active_module: if self.active_module != previous.active_module {
+                self.active_module
+            } else {
+                None
+            },
+            modules,
+            outputs,
+        }
+    }
+
+    /// Overlay the values of `other` on top of this state
+    pub fn merge(&mut self, other: &ModuleCueState) {
+        if other.active_module.is_some() {
+            self.active_module = other.active_module;
+        }
+        for (id, module) in &other.modules {
+            self.modules.entry(*id).or_default().merge(module);
+        }
+        for (id, output) in &other.outputs {
+            self.outputs.entry(*id).or_default().merge(output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_opacity(opacity: f32, speed: f32) -> ModuleCueState {
+        let mut state = ModuleCueState::new();
+        state.active_module = Some(1);
+        let part = state.part_mut(1, 10);
+        part.set_parameter("opacity", CueValue::Float(opacity));
+        part.set_parameter("speed", CueValue::Float(speed));
+        state
+    }
+
+    #[test]
+    fn test_diff_keeps_only_changed_values() {
+        let previous = state_with_opacity(1.0, 1.0);
+        let current = state_with_opacity(0.5, 1.0);
+
+        let tracked = current.diff(&previous);
+        let part = tracked.part(1, 10).unwrap();
+        assert_eq!(part.parameters.len(), 1);
+        assert_eq!(part.parameters.get("opacity"), Some(&CueValue::Float(0.5)));
+        assert_eq!(tracked.active_module, None);
+    }
+
+    #[test]
+    fn test_diff_of_identical_state_is_empty() {
+        let state = state_with_opacity(1.0, 1.0);
+        assert!(state.diff(&state).is_empty());
+    }
+
+    #[test]
+    fn test_merge_restores_full_state() {
+        let base = state_with_opacity(1.0, 1.0);
+        let current = state_with_opacity(0.25, 2.0);
+
+        let mut resolved = base.clone();
+        resolved.merge(&current.diff(&base));
+        assert_eq!(resolved, current);
+    }
+
+    #[test]
+    fn test_per_parameter_fade_time() {
+        let mut part = ParameterSnapshot::new();
+        part.set_parameter("opacity", CueValue::Float(0.0));
+        part.set_fade_time("opacity", Duration::from_secs(5));
+
+        assert_eq!(
+            part.fade_time("opacity", Duration::from_secs(2)),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            part.fade_time("speed", Duration::from_secs(2)),
+            Duration::from_secs(2)
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Cue stacks - multiple independent cue lists running in parallel
+//!
+//! Each stack owns its own [`CueList`] with its own playhead and crossfade.
+//! When several stacks are live, their module states are layered in stack
+//! order so that later stacks take precedence (latest takes precedence per
+//! parameter, not per cue).
+
+use super::cue::Cue;
+use super::cue_list::CueList;
+use super::snapshot::ModuleCueState;
+
+use crate::{error::ControlError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// A named cue list that runs independently of other stacks
+pub struct CueStack {
+    /// Unique identifier for this entity.
+    pub id: u32,
+    /// Human-readable display name.
+    pub name: String,
+    /// The cues of this stack
+    pub list: CueList,
+}
+
+impl CueStack {
+    /// Create a new empty cue stack
+    pub fn new(id: u32, name: String) -> Self {
+        Self {
+            id,
+            name,
+            list: CueList::new(),
+        }
+    }
+
+    /// Module state that is live right now, blended while crossfading
+    pub fn live_module_state(&self) -> Option<ModuleCueState> {
+        self.list.live_state().map(|state| state.module_state)
+    }
+}
+
+/// Saved form of a cue stack: its cues without playback state
+#[derive(Serialize, Deserialize)]
+struct SavedStack {
+    id: u32,
+    name: String,
+    #[serde(default)]
+    tracking: bool,
+    cues: Vec<Cue>,
+}
+
+/// Collection of cue stacks
+pub struct CueStacks {
+    stacks: Vec<CueStack>,
+    next_id: u32,
+}
+
+impl CueStacks {
+    /// Create an empty stack collection
+    pub fn new() -> Self {
+        Self {
+            stacks: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Add a new stack and return its ID
+    pub fn add_stack(&mut self, name: String) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.stacks.push(CueStack::new(id, name));
+        id
+    }
+
+    /// Remove a stack by ID
+    pub fn remove_stack(&mut self, id: u32) -> Option<CueStack> {
+        let index = self.stacks.iter().position(|s| s.id == id)?;
+        Some(self.stacks.remove(index))
+    }
+
+    /// Get a stack by ID
+    pub fn get(&self, id: u32) -> Option<&CueStack> {
+        self.stacks.iter().find(|s| s.id == id)
+    }
+
+    /// Get a mutable reference to a stack by ID
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut CueStack> {
+        self.stacks.iter_mut().find(|s| s.id == id)
+    }
+
+    /// Get all stacks in precedence order
+    pub fn stacks(&self) -> &[CueStack] {
+        &self.stacks
+    }
+
+    /// Advance a stack to its next cue
+    pub fn go(&mut self, id: u32) -> Result<()> {
+        self.get_mut(id)
+            .ok_or_else(|| ControlError::TargetNotFound(format!("Cue stack {} not found", id)))?
+            .list
+            .next()
+    }
+
+    /// Release a stack so it no longer contributes to the output
+    pub fn release(&mut self, id: u32) -> Result<()> {
+        let stack = self
+            .get_mut(id)
+            .ok_or_else(|| ControlError::TargetNotFound(format!("Cue stack {} not found", id)))?;
+        stack.list.release();
+        Ok(())
+    }
+
+    /// Update all stacks (call this regularly to handle crossfades)
+    pub fn update(&mut self) {
+        for stack in &mut self.stacks {
+            stack.list.update();
+        }
+    }
+
+    /// Module state of all live stacks layered in precedence order
+    pub fn live_module_state(&self) -> ModuleCueState {
+        let mut resolved = ModuleCueState::new();
+        for stack in &self.stacks {
+            if let Some(state) = stack.live_module_state() {
+                resolved.merge(&state);
+            }
+        }
+        resolved
+    }
+
+    /// Load stacks from a JSON file; all stacks start released
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let saved: Vec<SavedStack> = serde_json::from_str(&json)?;
+        let mut stacks = Self::new();
+        for saved in saved {
+            let mut stack = CueStack::new(saved.id, saved.name);
+            stack.list.set_tracking(saved.tracking);
+            for cue in saved.cues {
+                stack.list.add_cue(cue);
+            }
+            stacks.next_id = stacks.next_id.max(saved.id + 1);
+            stacks.stacks.push(stack);
+        }
+        info!("Loaded {} cue stacks", stacks.len());
+        Ok(stacks)
+    }
+
+    /// Save stacks to a JSON file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let saved: Vec<SavedStack> = self
+            .stacks
+            .iter()
+            .map(|stack| SavedStack {
+                id: stack.id,
+                name: stack.name.clone(),
+                tracking: stack.list.is_tracking(),
+                cues: stack.list.cues().to_vec(),
+            })
+            .collect();
+        std::fs::write(path, serde_json::to_string_pretty(&saved)?)?;
+        Ok(())
+    }
+
+    /// Get the number of stacks
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Check if there are no stacks
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+}
+
+impl Default for CueStacks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cue::snapshot::CueValue;
+    use crate::cue::test_util::cue_with_opacity;
+
+    #[test]
+    fn test_stacks_run_independently() {
+        let mut stacks = CueStacks::new();
+        let a = stacks.add_stack("Background".to_string());
+        let b = stacks.add_stack("Accents".to_string());
+
+        let stack_a = stacks.get_mut(a).unwrap();
+        stack_a.list.add_cue(cue_with_opacity(1, 10, 1.0));
+        stack_a.list.add_cue(cue_with_opacity(2, 10, 0.5));
+        stacks
+            .get_mut(b)
+            .unwrap()
+            .list
+            .add_cue(cue_with_opacity(1, 20, 0.25));
+
+        stacks.go(a).unwrap();
+        stacks.go(b).unwrap();
+
+        assert_eq!(stacks.get(a).unwrap().list.current_cue(), Some(1));
+        assert_eq!(stacks.get(b).unwrap().list.current_cue(), Some(1));
+
+        let live = stacks.live_module_state();
+        assert_eq!(
+            live.part(1, 10).unwrap().parameters.get("opacity"),
+            Some(&CueValue::Float(1.0))
+        );
+        assert_eq!(
+            live.part(1, 20).unwrap().parameters.get("opacity"),
+            Some(&CueValue::Float(0.25))
+        );
+    }
+
+    #[test]
+    fn test_later_stack_takes_precedence() {
+        let mut stacks = CueStacks::new();
+        let a = stacks.add_stack("A".to_string());
+        let b = stacks.add_stack("B".to_string());
+        stacks
+            .get_mut(a)
+            .unwrap()
+            .list
+            .add_cue(cue_with_opacity(1, 10, 1.0));
+        stacks
+            .get_mut(b)
+            .unwrap()
+            .list
+            .add_cue(cue_with_opacity(1, 10, 0.0));
+
+        stacks.go(a).unwrap();
+        stacks.go(b).unwrap();
+
+        let live = stacks.live_module_state();
+        assert_eq!(
+            live.part(1, 10).unwrap().parameters.get("opacity"),
+            Some(&CueValue::Float(0.0))
+        );
+
+        stacks.release(b).unwrap();
+        let live = stacks.live_module_state();
+        assert_eq!(
+            live.part(1, 10).unwrap().parameters.get("opacity"),
+            Some(&CueValue::Float(1.0))
+        );
+    }
+
+    #[test]
+    fn test_save_load_file() {
+        let mut stacks = CueStacks::new();
+        stacks.add_stack("Background".to_string());
+        let b = stacks.add_stack("Accents".to_string());
+        let stack_b = stacks.get_mut(b).unwrap();
+        stack_b.list.set_tracking(true);
+        stack_b.list.add_cue(cue_with_opacity(1, 20, 0.25));
+        stacks.go(b).unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("mapflow_cue_stacks_{}.json", uuid::Uuid::new_v4()));
+        stacks.save_to_file(&path).unwrap();
+        let mut loaded = CueStacks::load_from_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.len(), 2);
+        let stack_b = loaded.get(b).unwrap();
+        assert_eq!(stack_b.name, "Accents");
+        assert!(stack_b.list.is_tracking());
+        assert_eq!(stack_b.list.len(), 1);
+        // Playback state is not saved
+        assert_eq!(stack_b.list.current_cue(), None);
+        // New stacks don't reuse saved IDs
+        assert_eq!(loaded.add_stack("New".to_string()), b + 1);
+    }
+}
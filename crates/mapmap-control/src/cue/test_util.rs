@@ -0,0 +1,17 @@
+//! Shared fixtures for the cue system tests
+
+use super::snapshot::{CueValue, ModuleCueState};
+use super::{Cue, LayerState};
+
+/// Build a cue that sets `opacity` on layer 0 and on part `part_id` of module 1
+pub(crate) fn cue_with_opacity(id: u32, part_id: u64, opacity: f32) -> Cue {
+    let mut cue = Cue::new(id, format!("Cue {}", id));
+    cue.add_layer_state(0, LayerState::new(opacity, true, (0.0, 0.0), 0.0, 1.0));
+
+    let mut state = ModuleCueState::new();
+    state
+        .part_mut(1, part_id)
+        .set_parameter("opacity", CueValue::Float(opacity));
+    cue.set_module_state(state);
+    cue
+}
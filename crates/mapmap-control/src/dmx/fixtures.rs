@@ -220,6 +220,135 @@ impl Fixture {
     }
 }
 
+/// Arrangement of the pixels of an LED strip on the canvas
+///
+/// Coordinates are normalized (0.0 - 1.0, origin top left) in the image of
+/// the output the strip is mapped on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LedLayout {
+    /// `count` pixels evenly spaced from `start` to `end`.
+    Line {
+        start: (f32, f32),
+        end: (f32, f32),
+        count: u32,
+    },
+    /// `columns` x `rows` pixels filling the rectangle; every row is wired
+    /// left to right.
+    Matrix {
+        top_left: (f32, f32),
+        bottom_right: (f32, f32),
+        columns: u32,
+        rows: u32,
+    },
+    /// Like `Matrix`, but every second row is wired right to left, as in
+    /// zig-zag soldered panels.
+    Serpentine {
+        top_left: (f32, f32),
+        bottom_right: (f32, f32),
+        columns: u32,
+        rows: u32,
+    },
+}
+
+impl LedLayout {
+    /// Number of pixels in the layout
+    pub fn pixel_count(&self) -> usize {
+        match self {
+            LedLayout::Line { count, .. } => *count as usize,
+            LedLayout::Matrix { columns, rows, .. }
+            | LedLayout::Serpentine { columns, rows, .. } => *columns as usize * *rows as usize,
+        }
+    }
+
+    /// Canvas position of every pixel, in wiring order
+    pub fn positions(&self) -> Vec<(f32, f32)> {
+        match self {
+            LedLayout::Line { start, end, count } => {
+                let count = *count as usize;
+                (0..count)
+                    .map(|i| {
+                        let t = if count > 1 {
+                            i as f32 / (count - 1) as f32
+                        } else {
+                            0.0
+                        };
+                        (
+                            start.0 + (end.0 - start.0) * t,
+                            start.1 + (end.1 - start.1) * t,
+                        )
+                    })
+                    .collect()
+            }
+            LedLayout::Matrix {
+                top_left,
+                bottom_right,
+                columns,
+                rows,
+            }
+            | LedLayout::Serpentine {
+                top_left,
+                bottom_right,
+                columns,
+                rows,
+            } => {
+                let serpentine = matches!(self, LedLayout::Serpentine { .. });
+                let (columns, rows) = (*columns as usize, *rows as usize);
+                // Pixels sit in the centers of their cells
+                let cell = (
+                    (bottom_right.0 - top_left.0) / columns.max(1) as f32,
+                    (bottom_right.1 - top_left.1) / rows.max(1) as f32,
+                );
+                let mut positions = Vec::with_capacity(columns * rows);
+                for row in 0..rows {
+                    for i in 0..columns {
+                        let column = if serpentine && row % 2 == 1 {
+                            columns - 1 - i
+                        } else {
+                            i
+                        };
+                        positions.push((
+                            top_left.0 + (column as f32 + 0.5) * cell.0,
+                            top_left.1 + (row as f32 + 0.5) * cell.1,
+                        ));
+                    }
+                }
+                positions
+            }
+        }
+    }
+}
+
+/// An LED strip or matrix on a pixel controller, the counterpart of a
+/// [`Fixture`] on a DMX universe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedStrip {
+    /// Unique identifier for this entity.
+    pub id: u32,
+    /// Human-readable display name.
+    pub name: String,
+    pub layout: LedLayout,
+    pub start_pixel: u32, // 0-based index on the controller
+}
+
+impl LedStrip {
+    /// Create a new strip starting at `start_pixel`
+    pub fn new(id: u32, name: String, layout: LedLayout, start_pixel: u32) -> Self {
+        Self {
+            id,
+            name,
+            layout,
+            start_pixel,
+        }
+    }
+
+    /// Get the last pixel index of this strip
+    pub fn end_pixel(&self) -> u32 {
+        self.start_pixel
+            .saturating_add(self.layout.pixel_count() as u32)
+            .saturating_sub(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,4 +390,41 @@ mod tests {
         assert_eq!(dmx_data[1], 128); // Green
         assert_eq!(dmx_data[2], 64); // Blue
     }
+
+    #[test]
+    fn test_led_layouts() {
+        let line = LedLayout::Line {
+            start: (0.0, 0.5),
+            end: (1.0, 0.5),
+            count: 5,
+        };
+        assert_eq!(line.positions()[1], (0.25, 0.5));
+        assert_eq!(line.positions()[4], (1.0, 0.5));
+
+        let rect = ((0.0, 0.0), (1.0, 1.0));
+        let matrix = LedLayout::Matrix {
+            top_left: rect.0,
+            bottom_right: rect.1,
+            columns: 2,
+            rows: 2,
+        };
+        let serpentine = LedLayout::Serpentine {
+            top_left: rect.0,
+            bottom_right: rect.1,
+            columns: 2,
+            rows: 2,
+        };
+        assert_eq!(
+            matrix.positions(),
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+        // The second row runs back from right to left
+        assert_eq!(
+            serpentine.positions(),
+            vec![(0.25, 0.25), (0.75, 0.25), (0.75, 0.75), (0.25, 0.75)]
+        );
+
+        let strip = LedStrip::new(0, "Panel".to_string(), serpentine, 10);
+        assert_eq!(strip.end_pixel(), 13);
+    }
 }
@@ -77,5 +77,5 @@ pub mod sacn;
 
 pub use artnet::ArtNetSender;
 pub use channels::{ChannelAssignment, DmxChannel};
-pub use fixtures::{ChannelType, Fixture, FixtureChannel, FixtureProfile};
+pub use fixtures::{ChannelType, Fixture, FixtureChannel, FixtureProfile, LedLayout, LedStrip};
 pub use sacn::SacnSender;
@@ -44,7 +44,7 @@ impl HueClient {
             generateclientkey: true,
         };
 
-        let url = format!("https://{}/api", ip);
+        let url = format!("{}/api", super::base_url(ip));
         let resp = client.post(&url).json(&body).send().await?;
 
         let items: Vec<RegisterResponseItem> = resp.json().await?;
@@ -85,7 +85,7 @@ impl HueClient {
             .danger_accept_invalid_certs(true)
             .build()?;
 
-        let url = format!("https://{}/auth/v1", ip);
+        let url = format!("{}/auth/v1", super::base_url(ip));
         let resp = client
             .get(&url)
             .header("hue-application-key", username)
@@ -1,7 +1,16 @@
+//! Hue bridge discovery
+//!
+//! Bridges are looked up on the local network first (mDNS and SSDP at the same
+//! time), so discovery also works in venues without internet. Only when
+//! nothing answers locally is the cloud endpoint asked.
+
 use super::error::HueError;
+use super::{mdns, ssdp};
 use reqwest::Client;
 use serde::Deserialize;
+use std::net::SocketAddr;
 use std::time::Duration;
+use tracing::{debug, warn};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct DiscoveredBridge {
@@ -11,15 +20,90 @@ pub struct DiscoveredBridge {
     pub id: String,
 }
 
-/// Discover Hue Bridges using the meethue.com N-UPnP API
-/// Returns all discovered bridges, sorted by reachability
+/// Where and how long to look for bridges
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    /// Destination of the mDNS query
+    pub mdns_addr: SocketAddr,
+    /// Destination of the SSDP search
+    pub ssdp_addr: SocketAddr,
+    /// How long local discovery listens for answers
+    pub timeout: Duration,
+    /// Cloud endpoint asked when no bridge answers locally (`None` stays offline)
+    pub cloud_url: Option<String>,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            mdns_addr: mdns::MDNS_ADDR,
+            ssdp_addr: ssdp::SSDP_ADDR,
+            timeout: Duration::from_secs(3),
+            cloud_url: Some("https://discovery.meethue.com".to_string()),
+        }
+    }
+}
+
+/// Discover Hue Bridges on the local network, falling back to the
+/// meethue.com N-UPnP API
 pub async fn discover_bridges() -> Result<Vec<DiscoveredBridge>, HueError> {
+    discover_bridges_with(&DiscoveryConfig::default()).await
+}
+
+/// Discover Hue Bridges with explicit endpoints and timeout
+pub async fn discover_bridges_with(
+    config: &DiscoveryConfig,
+) -> Result<Vec<DiscoveredBridge>, HueError> {
+    let bridges = discover_local(config).await;
+    if !bridges.is_empty() {
+        return Ok(bridges);
+    }
+
+    match &config.cloud_url {
+        Some(url) => discover_cloud(url).await,
+        None => Err(HueError::DiscoveryFailed),
+    }
+}
+
+/// Ask via mDNS and SSDP at the same time and merge the answers
+///
+/// Failures of either protocol (e.g. no multicast route) are logged and
+/// leave the other one's results.
+pub async fn discover_local(config: &DiscoveryConfig) -> Vec<DiscoveredBridge> {
+    let (mdns, ssdp) = tokio::join!(
+        mdns::discover(config.mdns_addr, config.timeout),
+        ssdp::discover(config.ssdp_addr, config.timeout)
+    );
+
+    let mut bridges: Vec<DiscoveredBridge> = Vec::new();
+    for (protocol, result) in [("mDNS", mdns), ("SSDP", ssdp)] {
+        match result {
+            Ok(found) => {
+                debug!("{} discovery found {} bridges", protocol, found.len());
+                for bridge in found {
+                    if !bridges
+                        .iter()
+                        .any(|b| b.id == bridge.id || b.ip == bridge.ip)
+                    {
+                        bridges.push(bridge);
+                    }
+                }
+            }
+            Err(e) => warn!("{} bridge discovery failed: {}", protocol, e),
+        }
+    }
+    bridges
+}
+
+/// Discover Hue Bridges using the N-UPnP API at `url`
+/// Returns all discovered bridges, sorted by reachability
+pub async fn discover_cloud(url: &str) -> Result<Vec<DiscoveredBridge>, HueError> {
     let client = Client::builder()
         .timeout(Duration::from_secs(10))
         .build()
         .map_err(HueError::Network)?;
 
-    let resp = client.get("https://discovery.meethue.com").send().await?;
+    let resp = client.get(url).send().await?;
 
     let devices: Vec<DiscoveredBridge> = resp.json().await?;
 
@@ -79,3 +163,59 @@ pub async fn discover_bridge() -> Result<String, HueError> {
         .map(|b| b.ip.clone())
         .ok_or(HueError::DiscoveryFailed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UdpSocket;
+
+    /// A responder that ignores every query, standing in for a silent network
+    async fn silent_responder() -> (UdpSocket, SocketAddr) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        (socket, addr)
+    }
+
+    #[tokio::test]
+    async fn test_offline_without_bridges_fails() {
+        let (_mdns, mdns_addr) = silent_responder().await;
+        let (_ssdp, ssdp_addr) = silent_responder().await;
+        let config = DiscoveryConfig {
+            mdns_addr,
+            ssdp_addr,
+            timeout: Duration::from_millis(100),
+            cloud_url: None,
+        };
+        assert!(matches!(
+            discover_bridges_with(&config).await,
+            Err(HueError::DiscoveryFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_answers_are_merged() {
+        let (_mdns, mdns_addr) = silent_responder().await;
+        let (ssdp, ssdp_addr) = silent_responder().await;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            let (_, from) = ssdp.recv_from(&mut buf).await.unwrap();
+            let answer = "HTTP/1.1 200 OK\r\n\
+                          LOCATION: http://127.0.0.1:80/description.xml\r\n\
+                          hue-bridgeid: 001788FFFE000001\r\n\r\n";
+            // A bridge on two interfaces answers twice
+            ssdp.send_to(answer.as_bytes(), from).await.unwrap();
+            ssdp.send_to(answer.as_bytes(), from).await.unwrap();
+        });
+
+        let config = DiscoveryConfig {
+            mdns_addr,
+            ssdp_addr,
+            timeout: Duration::from_millis(300),
+            // Never reached: the local answer wins
+            cloud_url: Some("http://127.0.0.1:9/unreachable".to_string()),
+        };
+        let bridges = discover_bridges_with(&config).await.unwrap();
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].id, "001788fffe000001");
+    }
+}
@@ -82,8 +82,8 @@ pub async fn get_entertainment_groups(config: &HueConfig) -> Result<Vec<GroupInf
 
     // Use v2 API to get entertainment configurations with channels
     let url = format!(
-        "https://{}/clip/v2/resource/entertainment_configuration",
-        config.bridge_ip
+        "{}/clip/v2/resource/entertainment_configuration",
+        super::base_url(&config.bridge_ip)
     );
 
     let resp = client
@@ -144,8 +144,9 @@ pub async fn set_stream_active(
     let client = build_client()?;
 
     let url = format!(
-        "https://{}/clip/v2/resource/entertainment_configuration/{}",
-        config.bridge_ip, entertainment_config_id
+        "{}/clip/v2/resource/entertainment_configuration/{}",
+        super::base_url(&config.bridge_ip),
+        entertainment_config_id
     );
 
     let body = StreamAction {
@@ -190,7 +191,11 @@ pub async fn set_stream_active(
 /// Flash a light using the v1 API (for testing connectivity)
 pub async fn flash_light(config: &HueConfig, light_id: &str) -> Result<(), HueError> {
     let client = build_client()?;
-    let url = format!("https://{}/api/lights/{}/state", config.bridge_ip, light_id);
+    let url = format!(
+        "{}/api/lights/{}/state",
+        super::base_url(&config.bridge_ip),
+        light_id
+    );
 
     let body = serde_json::json!({
         "alert": "select"
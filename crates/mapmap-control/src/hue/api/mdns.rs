@@ -0,0 +1,309 @@
+//! mDNS discovery of Hue bridges (`_hue._tcp.local`)
+//!
+//! The query is sent from an ephemeral port, which makes it a "legacy unicast"
+//! query (RFC 6762, section 6.7): responders answer directly to the sender,
+//! so no socket has to join the multicast group or bind port 5353.
+
+use super::discovery::DiscoveredBridge;
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// Well-known mDNS multicast address and port
+pub const MDNS_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)), 5353);
+/// Service type announced by Hue bridges
+pub const HUE_SERVICE: &str = "_hue._tcp.local";
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+/// Compressed-name pointers followed before a name counts as malformed
+const MAX_POINTERS: usize = 16;
+
+/// Ask for Hue bridges on `target` and collect the answers until `timeout`
+pub async fn discover(target: SocketAddr, timeout: Duration) -> io::Result<Vec<DiscoveredBridge>> {
+    let socket = UdpSocket::bind(bind_addr(target)).await?;
+    socket.send_to(&build_query(HUE_SERVICE), target).await?;
+
+    let deadline = Instant::now() + timeout;
+    let mut bridges: Vec<DiscoveredBridge> = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        for bridge in parse_response(&buf[..len], from.ip()) {
+            if !bridges.iter().any(|b| b.id == bridge.id) {
+                bridges.push(bridge);
+            }
+        }
+    }
+    Ok(bridges)
+}
+
+/// Unspecified address of the same family as `target`, on an ephemeral port
+pub(crate) fn bind_addr(target: SocketAddr) -> SocketAddr {
+    match target {
+        SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(std::net::Ipv6Addr::UNSPECIFIED), 0),
+    }
+}
+
+/// A PTR query for `service`
+pub fn build_query(service: &str) -> Vec<u8> {
+    // ID 0, standard query, one question
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    write_name(&mut packet, service);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    packet
+}
+
+fn write_name(packet: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        packet.push(label.len().min(63) as u8);
+        packet.extend_from_slice(&label.as_bytes()[..label.len().min(63)]);
+    }
+    packet.push(0);
+}
+
+/// Resource records of one response, as far as discovery needs them
+#[derive(Default)]
+struct Records {
+    /// Service instances announced for the Hue service
+    instances: Vec<String>,
+    /// Instance -> target host
+    srv: HashMap<String, String>,
+    /// Instance -> TXT entries
+    txt: HashMap<String, Vec<String>>,
+    /// Host -> address
+    a: HashMap<String, Ipv4Addr>,
+}
+
+/// Extract the bridges announced in an mDNS response from `sender`
+pub fn parse_response(packet: &[u8], sender: IpAddr) -> Vec<DiscoveredBridge> {
+    let Some(records) = parse_records(packet) else {
+        return Vec::new();
+    };
+
+    records
+        .instances
+        .iter()
+        .map(|instance| {
+            let key = instance.to_ascii_lowercase();
+            let ip = records
+                .srv
+                .get(&key)
+                .and_then(|host| records.a.get(&host.to_ascii_lowercase()))
+                .map(|ip| IpAddr::V4(*ip))
+                .unwrap_or(sender);
+            let id = records
+                .txt
+                .get(&key)
+                .and_then(|entries| {
+                    entries
+                        .iter()
+                        .find_map(|entry| entry.strip_prefix("bridgeid="))
+                })
+                .map(str::to_ascii_lowercase)
+                .unwrap_or_else(|| instance.split('.').next().unwrap_or(instance).to_string());
+            DiscoveredBridge {
+                ip: ip.to_string(),
+                id,
+            }
+        })
+        .collect()
+}
+
+fn parse_records(packet: &[u8]) -> Option<Records> {
+    let flags = read_u16(packet, 2)?;
+    if flags & 0x8000 == 0 {
+        // A query, not a response
+        return None;
+    }
+    let questions = read_u16(packet, 4)?;
+    let answers = read_u16(packet, 6)? as usize
+        + read_u16(packet, 8)? as usize
+        + read_u16(packet, 10)? as usize;
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        let (_, next) = read_name(packet, offset)?;
+        offset = next + 4;
+    }
+
+    let mut records = Records::default();
+    for _ in 0..answers {
+        let (owner, next) = read_name(packet, offset)?;
+        let kind = read_u16(packet, next)?;
+        let len = read_u16(packet, next + 8)? as usize;
+        let data = next + 10;
+        if data + len > packet.len() {
+            return None;
+        }
+        let owner = owner.to_ascii_lowercase();
+        match kind {
+            TYPE_PTR if owner == HUE_SERVICE => {
+                records.instances.push(read_name(packet, data)?.0);
+            }
+            TYPE_SRV => {
+                let (host, _) = read_name(packet, data + 6)?;
+                records.srv.insert(owner, host);
+            }
+            TYPE_TXT => {
+                let mut entries = Vec::new();
+                let mut pos = data;
+                while pos < data + len {
+                    let entry_len = packet[pos] as usize;
+                    let entry = packet.get(pos + 1..pos + 1 + entry_len)?;
+                    entries.push(String::from_utf8_lossy(entry).into_owned());
+                    pos += 1 + entry_len;
+                }
+                records.txt.insert(owner, entries);
+            }
+            TYPE_A if len == 4 => {
+                let ip = Ipv4Addr::new(
+                    packet[data],
+                    packet[data + 1],
+                    packet[data + 2],
+                    packet[data + 3],
+                );
+                records.a.insert(owner, ip);
+            }
+            _ => {}
+        }
+        offset = data + len;
+    }
+    Some(records)
+}
+
+fn read_u16(packet: &[u8], offset: usize) -> Option<u16> {
+    let bytes = packet.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Read a (possibly compressed) name, returning it and the offset after it
+fn read_name(packet: &[u8], offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut pos = offset;
+    let mut end = None;
+    let mut pointers = 0;
+    loop {
+        let len = *packet.get(pos)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            pointers += 1;
+            if pointers > MAX_POINTERS {
+                return None;
+            }
+            end.get_or_insert(pos + 2);
+            pos = (read_u16(packet, pos)? & 0x3FFF) as usize;
+            continue;
+        }
+        let label = packet.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bridge's answer as sent by a Hue bridge: PTR, then SRV, TXT and A
+    /// records, using name compression like real responders do
+    fn bridge_response(ip: [u8; 4]) -> Vec<u8> {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 3];
+        let record = |packet: &mut Vec<u8>, kind: u16, data: &[u8]| {
+            packet.extend_from_slice(&kind.to_be_bytes());
+            packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+            packet.extend_from_slice(&120u32.to_be_bytes());
+            packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            packet.extend_from_slice(data);
+        };
+
+        // PTR _hue._tcp.local -> Hue Bridge - 123456._hue._tcp.local
+        let service = packet.len() as u16;
+        write_name(&mut packet, HUE_SERVICE);
+        let label = b"Hue Bridge - 123456";
+        let mut instance = vec![label.len() as u8];
+        instance.extend_from_slice(label);
+        let instance_at = packet.len() as u16 + 10;
+        instance.extend_from_slice(&(0xC000 | service).to_be_bytes());
+        record(&mut packet, TYPE_PTR, &instance);
+
+        // SRV instance -> 001788fffe123456.local:443
+        packet.extend_from_slice(&(0xC000 | instance_at).to_be_bytes());
+        let mut srv = vec![0, 0, 0, 0, 0x01, 0xBB];
+        write_name(&mut srv, "001788fffe123456.local");
+        record(&mut packet, TYPE_SRV, &srv);
+
+        // TXT instance -> bridgeid, modelid
+        packet.extend_from_slice(&(0xC000 | instance_at).to_be_bytes());
+        let mut txt = Vec::new();
+        for entry in ["bridgeid=001788FFFE123456", "modelid=BSB002"] {
+            txt.push(entry.len() as u8);
+            txt.extend_from_slice(entry.as_bytes());
+        }
+        record(&mut packet, TYPE_TXT, &txt);
+
+        // A 001788fffe123456.local -> ip
+        write_name(&mut packet, "001788fffe123456.local");
+        record(&mut packet, TYPE_A, &ip);
+        packet
+    }
+
+    #[test]
+    fn test_query_format() {
+        let query = build_query(HUE_SERVICE);
+        assert_eq!(&query[4..6], &[0, 1]);
+        assert_eq!(&query[12..17], b"\x04_hue");
+        assert_eq!(read_name(&query, 12).unwrap().0, HUE_SERVICE);
+        assert!(parse_response(&query, IpAddr::V4(Ipv4Addr::LOCALHOST)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_bridge_response() {
+        let packet = bridge_response([192, 168, 1, 20]);
+        let bridges = parse_response(&packet, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].ip, "192.168.1.20");
+        assert_eq!(bridges[0].id, "001788fffe123456");
+    }
+
+    #[test]
+    fn test_truncated_response_is_ignored() {
+        let packet = bridge_response([192, 168, 1, 20]);
+        for len in [0, 5, 12, 40, packet.len() - 1] {
+            // Must not panic; partial records yield nothing usable
+            let _ = parse_response(&packet[..len], IpAddr::V4(Ipv4Addr::LOCALHOST));
+        }
+        let looping = [0, 0, 0x84, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0xC0, 12];
+        assert!(parse_response(&looping, IpAddr::V4(Ipv4Addr::LOCALHOST)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_discover_against_loopback_responder() {
+        let responder = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = responder.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            let (len, from) = responder.recv_from(&mut buf).await.unwrap();
+            assert_eq!(read_name(&buf[..len], 12).unwrap().0, HUE_SERVICE);
+            let answer = bridge_response([127, 0, 0, 1]);
+            // Answer twice, as responders on several interfaces would
+            responder.send_to(&answer, from).await.unwrap();
+            responder.send_to(&answer, from).await.unwrap();
+        });
+
+        let bridges = discover(target, Duration::from_millis(300)).await.unwrap();
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].ip, "127.0.0.1");
+        assert_eq!(bridges[0].id, "001788fffe123456");
+    }
+}
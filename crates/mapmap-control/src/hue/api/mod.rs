@@ -2,3 +2,64 @@ pub mod client;
 pub mod discovery;
 pub mod error;
 pub mod groups;
+pub mod mdns;
+pub mod ssdp;
+
+use std::net::{SocketAddr, ToSocketAddrs};
+
+/// Base URL of the bridge REST API.
+///
+/// Real bridges are addressed by IP and only serve HTTPS; a bridge given as a
+/// full `http://host:port` URL (the mock bridge) is used as is.
+pub(crate) fn base_url(bridge_ip: &str) -> String {
+    if bridge_ip.contains("://") {
+        bridge_ip.trim_end_matches('/').to_string()
+    } else {
+        format!("https://{}", bridge_ip)
+    }
+}
+
+/// Address of an unencrypted entertainment stream sink.
+///
+/// Only a bridge given as an `http://host:port` URL on the loopback interface
+/// (the mock bridge) streams over plain UDP, on the same port number; real
+/// bridges require DTLS.
+pub(crate) fn plain_stream_addr(bridge_ip: &str) -> Option<SocketAddr> {
+    let authority = bridge_ip.strip_prefix("http://")?.trim_end_matches('/');
+    authority
+        .to_socket_addrs()
+        .ok()?
+        .find(|addr| addr.ip().is_loopback())
+}
+
+/// Rejects bridge addresses that would send light data in the clear over the network.
+pub(crate) fn check_bridge_addr(bridge_ip: &str) -> Result<(), String> {
+    if bridge_ip.starts_with("http://") && plain_stream_addr(bridge_ip).is_none() {
+        return Err(format!(
+            "Refusing unencrypted bridge address '{}': use the bridge IP or https://",
+            bridge_ip
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bridge_addressing() {
+        assert_eq!(base_url("192.168.1.20"), "https://192.168.1.20");
+        assert_eq!(base_url("http://127.0.0.1:8080/"), "http://127.0.0.1:8080");
+        assert!(plain_stream_addr("192.168.1.20").is_none());
+        assert!(plain_stream_addr("https://192.168.1.20").is_none());
+        assert_eq!(
+            plain_stream_addr("http://127.0.0.1:8080"),
+            Some("127.0.0.1:8080".parse().unwrap())
+        );
+        assert!(plain_stream_addr("http://192.168.1.20:80").is_none());
+        assert!(check_bridge_addr("http://192.168.1.20").is_err());
+        assert!(check_bridge_addr("http://127.0.0.1:8080").is_ok());
+        assert!(check_bridge_addr("192.168.1.20").is_ok());
+    }
+}
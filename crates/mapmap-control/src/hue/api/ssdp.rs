@@ -0,0 +1,159 @@
+//! SSDP/UPnP discovery of Hue bridges
+//!
+//! Hue bridges answer an `M-SEARCH` with a `hue-bridgeid` header and a
+//! `LOCATION` pointing at their `description.xml`.
+
+use super::discovery::DiscoveredBridge;
+use super::mdns::bind_addr;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// Well-known SSDP multicast address and port
+pub const SSDP_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// Search for Hue bridges on `target` and collect the answers until `timeout`
+pub async fn discover(target: SocketAddr, timeout: Duration) -> io::Result<Vec<DiscoveredBridge>> {
+    let socket = UdpSocket::bind(bind_addr(target)).await?;
+    let mx = timeout.as_secs().clamp(1, 5);
+    socket
+        .send_to(build_search(target, mx).as_bytes(), target)
+        .await?;
+
+    let deadline = Instant::now() + timeout;
+    let mut bridges: Vec<DiscoveredBridge> = Vec::new();
+    let mut buf = [0u8; 2048];
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        if let Some(bridge) = parse_response(&String::from_utf8_lossy(&buf[..len]), from.ip()) {
+            if !bridges.iter().any(|b| b.id == bridge.id) {
+                bridges.push(bridge);
+            }
+        }
+    }
+    Ok(bridges)
+}
+
+/// An `M-SEARCH` request for root devices; responders wait up to `mx` seconds
+pub fn build_search(target: SocketAddr, mx: u64) -> String {
+    format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: {}\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         MX: {}\r\n\
+         ST: upnp:rootdevice\r\n\
+         \r\n",
+        target, mx
+    )
+}
+
+/// The bridge announced in an SSDP response from `sender`, if it is a Hue bridge
+pub fn parse_response(response: &str, sender: IpAddr) -> Option<DiscoveredBridge> {
+    let mut lines = response.lines();
+    if !lines.next()?.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+
+    let mut bridge_id = None;
+    let mut location = None;
+    let mut is_hue = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "hue-bridgeid" => {
+                bridge_id = Some(value.to_ascii_lowercase());
+                is_hue = true;
+            }
+            "server" if value.contains("IpBridge") => is_hue = true,
+            "location" => location = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if !is_hue {
+        return None;
+    }
+
+    let ip = location
+        .as_deref()
+        .and_then(location_host)
+        .unwrap_or_else(|| sender.to_string());
+    Some(DiscoveredBridge {
+        id: bridge_id.unwrap_or_else(|| ip.clone()),
+        ip,
+    })
+}
+
+/// Host of a `http://host:port/path` location
+fn location_host(location: &str) -> Option<String> {
+    let rest = location.split_once("://")?.1;
+    let authority = rest.split('/').next()?;
+    let host = authority
+        .rsplit_once(':')
+        .map_or(authority, |(host, _)| host);
+    host.parse::<IpAddr>().ok().map(|ip| ip.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge_response(ip: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             CACHE-CONTROL: max-age=100\r\n\
+             LOCATION: http://{}:80/description.xml\r\n\
+             SERVER: Hue/1.0 UPnP/1.0 IpBridge/1.60.0\r\n\
+             hue-bridgeid: 001788FFFE654321\r\n\
+             ST: upnp:rootdevice\r\n\
+             USN: uuid:2f402f80-da50-11e1-9b23-001788654321::upnp:rootdevice\r\n\
+             \r\n",
+            ip
+        )
+    }
+
+    #[test]
+    fn test_parse_bridge_response() {
+        let sender = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let bridge = parse_response(&bridge_response("192.168.1.30"), sender).unwrap();
+        assert_eq!(bridge.ip, "192.168.1.30");
+        assert_eq!(bridge.id, "001788fffe654321");
+    }
+
+    #[test]
+    fn test_other_devices_are_ignored() {
+        let sender = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let router = "HTTP/1.1 200 OK\r\n\
+                      LOCATION: http://192.168.1.1:5000/rootDesc.xml\r\n\
+                      SERVER: Linux UPnP/1.0 MiniUPnPd/2.1\r\n\r\n";
+        assert!(parse_response(router, sender).is_none());
+        assert!(parse_response("NOTIFY * HTTP/1.1\r\n\r\n", sender).is_none());
+        assert!(parse_response("", sender).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_discover_against_loopback_responder() {
+        let responder = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = responder.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            let (len, from) = responder.recv_from(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..len]).into_owned();
+            assert!(request.starts_with("M-SEARCH * HTTP/1.1\r\n"));
+            assert!(request.contains("MAN: \"ssdp:discover\""));
+            let answer = bridge_response("127.0.0.1");
+            responder.send_to(answer.as_bytes(), from).await.unwrap();
+        });
+
+        let bridges = discover(target, Duration::from_millis(300)).await.unwrap();
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].ip, "127.0.0.1");
+        assert_eq!(bridges[0].id, "001788fffe654321");
+    }
+}
@@ -4,6 +4,8 @@ use super::stream::{
     dtls::HueStreamer,
     manager::{run_stream_loop, LightState},
 };
+use crate::light::LightSink;
+use crate::ControlError;
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -83,6 +85,7 @@ impl HueController {
         if self.config.bridge_ip.is_empty() {
             return Err("Bridge IP is missing".to_string());
         }
+        api::check_bridge_addr(&self.config.bridge_ip)?;
 
         // 1. Fetch application ID if missing (needed for DTLS Identity)
         if self.config.application_id.is_empty() {
@@ -134,12 +137,16 @@ impl HueController {
             .await
             .map_err(|e| e.to_string())?;
 
-        // 4. Start DTLS Stream (Synchronous)
-        let streamer = match HueStreamer::connect(
-            &self.config.bridge_ip,
-            &self.config.application_id,
-            &self.config.client_key,
-        ) {
+        // 4. Start DTLS Stream (Synchronous); the mock bridge takes plain UDP
+        let streamer = match api::plain_stream_addr(&self.config.bridge_ip) {
+            Some(addr) => HueStreamer::connect_plain(addr),
+            None => HueStreamer::connect(
+                &self.config.bridge_ip,
+                &self.config.application_id,
+                &self.config.client_key,
+            ),
+        };
+        let streamer = match streamer {
             Ok(s) => s,
             Err(e) => {
                 error!("Failed to connect to Hue Bridge DTLS: {}. Check if Windows Firewall blocks UDP port 2100.", e);
@@ -248,7 +255,6 @@ impl HueController {
         let b = rgb.blue;
 
         // 2. Identify targets
-        let tx = self.sender.as_ref().unwrap();
         let mut stream_updates = Vec::new();
 
         if let Some(target_ids) = ids {
@@ -276,17 +282,48 @@ impl HueController {
         }
 
         if !stream_updates.is_empty() {
-            // Use try_send for sync context. If channel is full, we drop the frame (better than blocking)
-            if let Err(e) = tx.try_send(stream_updates) {
-                use tokio::sync::mpsc::error::TrySendError;
-                match e {
-                    TrySendError::Full(_) => {
-                        // warn!("Hue stream buffer full, dropping frame");
-                    }
-                    TrySendError::Closed(_) => {
-                        error!("Hue stream channel closed");
-                        self.is_connected = false;
-                    }
+            self.try_send(stream_updates);
+        }
+    }
+
+    /// Send per-lamp colors from the main loop (Sync wrapper of `process_updates`)
+    /// `updates` is a list of (LightID, R, G, B)
+    pub fn update_lamp_colors(&mut self, updates: &[(String, f32, f32, f32)]) {
+        if !self.is_connected || self.sender.is_none() {
+            return;
+        }
+
+        let stream_updates: Vec<LightState> = updates
+            .iter()
+            .filter_map(|(light_id, r, g, b)| {
+                self.nodes.get(light_id).map(|node| LightState {
+                    id: node.channel_id,
+                    r: (r * 255.0).clamp(0.0, 255.0) as u8,
+                    g: (g * 255.0).clamp(0.0, 255.0) as u8,
+                    b: (b * 255.0).clamp(0.0, 255.0) as u8,
+                })
+            })
+            .collect();
+
+        if !stream_updates.is_empty() {
+            self.try_send(stream_updates);
+        }
+    }
+
+    /// Queue a frame without blocking. If the channel is full, we drop the frame (better than blocking)
+    fn try_send(&mut self, stream_updates: Vec<LightState>) {
+        let Some(tx) = &self.sender else {
+            return;
+        };
+        if let Err(e) = tx.try_send(stream_updates) {
+            use tokio::sync::mpsc::error::TrySendError;
+            match e {
+                TrySendError::Full(_) => {
+                    // warn!("Hue stream buffer full, dropping frame");
+                }
+                TrySendError::Closed(_) => {
+                    error!("Hue stream channel closed");
+                    self.is_connected = false;
                 }
             }
         }
@@ -297,3 +334,32 @@ impl HueController {
         self.nodes.get(light_id).map(|n| (n.x, n.y, n.z))
     }
 }
+
+impl LightSink for HueController {
+    fn nodes(&self) -> Vec<LightNode> {
+        let mut nodes: Vec<LightNode> = self.nodes.values().cloned().collect();
+        nodes.sort_by_key(|n| n.channel_id);
+        nodes
+    }
+
+    fn send(&mut self, colors: &HashMap<u8, (u8, u8, u8)>) -> crate::Result<()> {
+        if !self.is_connected {
+            return Err(ControlError::HttpError(
+                "Hue entertainment stream is not connected".to_string(),
+            ));
+        }
+        let stream_updates: Vec<LightState> = colors
+            .iter()
+            .map(|(id, (r, g, b))| LightState {
+                id: *id,
+                r: *r,
+                g: *g,
+                b: *b,
+            })
+            .collect();
+        if !stream_updates.is_empty() {
+            self.try_send(stream_updates);
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use super::audio_interface::AudioSpectrum;
-use super::effects::LightEffect;
 use super::models::LightNode;
 use super::stream::manager::LightState;
+use crate::light::effects::LightEffect;
+use std::time::Instant;
 use tokio::sync::mpsc;
 
 pub struct EntertainmentEngine {
@@ -36,10 +37,14 @@ impl EntertainmentEngine {
     }
 
     pub async fn run(&mut self) {
+        let mut last_update = Instant::now();
         loop {
             match self.audio_rx.recv().await {
                 Ok(audio) => {
-                    let updates_map = self.effect.update(&audio, &self.nodes);
+                    let now = Instant::now();
+                    let dt = now.duration_since(last_update).as_secs_f32();
+                    last_update = now;
+                    let updates_map = self.effect.update(&audio, &self.nodes, dt);
                     let mut updates_vec = Vec::new();
                     for (id, (r, g, b)) in updates_map {
                         updates_vec.push(LightState { id, r, g, b });
@@ -0,0 +1,597 @@
+//! In-process fake Hue bridge
+//!
+//! Serves the CLIP v2 endpoints used by [`api::client`](super::api::client) and
+//! [`api::groups`](super::api::groups) over plain HTTP and accepts the
+//! entertainment stream over plain UDP on the same port number. Point a
+//! [`HueController`](super::controller::HueController) at [`MockBridge::hue_config`]
+//! to test or preview light programming without hardware.
+
+use super::models::{HueConfig, LightNode};
+use super::stream::protocol;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::task::JoinHandle;
+
+/// Largest request head/body the mock bridge accepts
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+/// Attempts to find a port number free for both TCP and UDP
+const BIND_ATTEMPTS: usize = 16;
+
+/// An entertainment area served by the mock bridge.
+#[derive(Debug, Clone)]
+pub struct MockArea {
+    /// Entertainment configuration UUID (36 characters)
+    pub id: String,
+    pub name: String,
+    /// Lamps, with positions in the bridge's room coordinates (-1.0 - 1.0)
+    pub lights: Vec<LightNode>,
+}
+
+impl MockArea {
+    /// An area with one lamp in each corner of the room.
+    pub fn four_corners(id: &str, name: &str) -> Self {
+        let corners = [(-0.8, 0.8), (0.8, 0.8), (-0.8, -0.8), (0.8, -0.8)];
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            lights: corners
+                .iter()
+                .enumerate()
+                .map(|(i, (x, y))| LightNode {
+                    id: format!("virtual-light-{}", i + 1),
+                    channel_id: i as u8,
+                    x: *x,
+                    y: *y,
+                    z: 0.0,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Credentials and areas of a mock bridge.
+#[derive(Debug, Clone)]
+pub struct MockBridgeConfig {
+    pub username: String,
+    pub client_key: String,
+    pub application_id: String,
+    pub areas: Vec<MockArea>,
+    /// Whether registration succeeds (link button pressed) or fails with error 101
+    pub link_button_pressed: bool,
+}
+
+impl Default for MockBridgeConfig {
+    fn default() -> Self {
+        Self {
+            username: "mapflow-virtual-user".to_string(),
+            client_key: "00112233445566778899AABBCCDDEEFF".to_string(),
+            application_id: "mapflow-virtual-app".to_string(),
+            areas: vec![MockArea::four_corners(
+                "6b1c1e1a-0000-4000-8000-000000000001",
+                "Virtual Area",
+            )],
+            link_button_pressed: true,
+        }
+    }
+}
+
+/// A lamp of the mock bridge with the color it currently shows.
+#[derive(Debug, Clone)]
+pub struct VirtualLamp {
+    pub node: LightNode,
+    pub color: (u8, u8, u8),
+}
+
+struct MockState {
+    config: MockBridgeConfig,
+    streaming_area: Option<String>,
+    colors: HashMap<u8, (u8, u8, u8)>,
+    frames: u64,
+}
+
+/// A running mock bridge; stops when dropped.
+pub struct MockBridge {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockBridge {
+    /// Starts a mock bridge on a free localhost port.
+    pub async fn start(config: MockBridgeConfig) -> io::Result<Self> {
+        let (listener, socket) = bind_pair().await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState {
+            config,
+            streaming_area: None,
+            colors: HashMap::new(),
+            frames: 0,
+        }));
+
+        let http_state = state.clone();
+        let http = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = http_state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, &state).await {
+                        tracing::debug!("Mock Hue bridge connection failed: {}", e);
+                    }
+                });
+            }
+        });
+
+        let stream_state = state.clone();
+        let stream = tokio::spawn(async move {
+            let mut buf = [0u8; 2048];
+            while let Ok(len) = socket.recv(&mut buf).await {
+                let Some(message) = protocol::parse_message(&buf[..len]) else {
+                    continue;
+                };
+                let mut state = lock(&stream_state);
+                if state.streaming_area.as_deref() == Some(message.area_id.as_str()) {
+                    state.colors.extend(message.lights);
+                    state.frames += 1;
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            tasks: vec![http, stream],
+        })
+    }
+
+    /// Bridge address to use as `bridge_ip` (`http://127.0.0.1:port`).
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A configuration that connects to this bridge's first area without registering.
+    pub fn hue_config(&self) -> HueConfig {
+        let state = lock(&self.state);
+        HueConfig {
+            bridge_ip: self.url(),
+            username: state.config.username.clone(),
+            client_key: state.config.client_key.clone(),
+            application_id: state.config.application_id.clone(),
+            entertainment_group_id: state
+                .config
+                .areas
+                .first()
+                .map(|area| area.id.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn set_link_button(&self, pressed: bool) {
+        lock(&self.state).config.link_button_pressed = pressed;
+    }
+
+    /// The area currently streaming, if any.
+    pub fn streaming_area(&self) -> Option<String> {
+        lock(&self.state).streaming_area.clone()
+    }
+
+    /// Stream frames received for the streaming area.
+    pub fn frames_received(&self) -> u64 {
+        lock(&self.state).frames
+    }
+
+    /// Channel ID -> last streamed color
+    pub fn light_colors(&self) -> HashMap<u8, (u8, u8, u8)> {
+        lock(&self.state).colors.clone()
+    }
+
+    /// Lamps of the streaming area (or the first area) with their current colors.
+    pub fn lamps(&self) -> Vec<VirtualLamp> {
+        let state = lock(&self.state);
+        let area = state
+            .streaming_area
+            .as_ref()
+            .and_then(|id| state.config.areas.iter().find(|area| &area.id == id))
+            .or_else(|| state.config.areas.first());
+        area.map(|area| {
+            area.lights
+                .iter()
+                .map(|node| VirtualLamp {
+                    node: node.clone(),
+                    color: state
+                        .colors
+                        .get(&node.channel_id)
+                        .copied()
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+}
+
+impl Drop for MockBridge {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A TCP listener and a UDP socket on the same localhost port number
+async fn bind_pair() -> io::Result<(TcpListener, UdpSocket)> {
+    let mut last_error = None;
+    for _ in 0..BIND_ATTEMPTS {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        match UdpSocket::bind(listener.local_addr()?).await {
+            Ok(socket) => return Ok((listener, socket)),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::other("no free port")))
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Value,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+}
+
+async fn serve_connection(mut stream: TcpStream, state: &Mutex<MockState>) -> io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    let response = handle(&request, &mut lock(state));
+
+    let body = response.body.to_string();
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        _ => "Not Found",
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason,
+        body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let len = stream.read(&mut buf).await?;
+        if len == 0 || data.len() > MAX_REQUEST_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete request",
+            ));
+        }
+        data.extend_from_slice(&buf[..len]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|len| len.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_SIZE);
+    let mut body = data[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let len = stream.read(&mut buf).await?;
+        if len == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..len]);
+    }
+
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn handle(request: &Request, state: &mut MockState) -> Response {
+    let segments: Vec<&str> = request
+        .path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if request.method == "POST" && segments == ["api"] {
+        return register(state);
+    }
+
+    let key = request
+        .headers
+        .get("hue-application-key")
+        .or_else(|| request.headers.get("x-hue-username"));
+    if key != Some(&state.config.username) {
+        return Response::json(
+            403,
+            json!({ "errors": [{ "description": "unauthorized user" }], "data": [] }),
+        );
+    }
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["auth", "v1"]) => Response {
+            status: 200,
+            headers: vec![("hue-application-id", state.config.application_id.clone())],
+            body: json!({}),
+        },
+        ("GET", ["clip", "v2", "resource", "entertainment_configuration"]) => {
+            let data: Vec<Value> = state
+                .config
+                .areas
+                .iter()
+                .map(|area| area_json(area, state.streaming_area.as_ref() == Some(&area.id)))
+                .collect();
+            Response::json(200, json!({ "errors": [], "data": data }))
+        }
+        ("PUT", ["clip", "v2", "resource", "entertainment_configuration", id]) => {
+            set_streaming(state, id, &request.body)
+        }
+        ("PUT", ["api", "lights", id, "state"]) => Response::json(
+            200,
+            json!([{ "success": { format!("/lights/{}/state/alert", id): "select" } }]),
+        ),
+        _ => Response::json(
+            404,
+            json!({ "errors": [{ "description": "resource not found" }], "data": [] }),
+        ),
+    }
+}
+
+fn register(state: &MockState) -> Response {
+    if state.config.link_button_pressed {
+        Response::json(
+            200,
+            json!([{ "success": {
+                "username": state.config.username,
+                "clientkey": state.config.client_key,
+            } }]),
+        )
+    } else {
+        Response::json(
+            200,
+            json!([{ "error": {
+                "type": 101,
+                "address": "",
+                "description": "link button not pressed",
+            } }]),
+        )
+    }
+}
+
+fn set_streaming(state: &mut MockState, id: &str, body: &[u8]) -> Response {
+    if !state.config.areas.iter().any(|area| area.id == id) {
+        return Response::json(
+            404,
+            json!({ "errors": [{ "description": "resource not found" }], "data": [] }),
+        );
+    }
+    let action = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|body| body.get("action")?.as_str().map(str::to_string));
+    match action.as_deref() {
+        Some("start") => state.streaming_area = Some(id.to_string()),
+        Some("stop") => {
+            if state.streaming_area.as_deref() == Some(id) {
+                state.streaming_area = None;
+            }
+        }
+        _ => {
+            return Response::json(
+                400,
+                json!({ "errors": [{ "description": "invalid action" }], "data": [] }),
+            )
+        }
+    }
+    Response::json(
+        200,
+        json!({ "errors": [], "data": [{ "rid": id, "rtype": "entertainment_configuration" }] }),
+    )
+}
+
+fn area_json(area: &MockArea, active: bool) -> Value {
+    let channels: Vec<Value> = area
+        .lights
+        .iter()
+        .map(|light| {
+            json!({
+                "channel_id": light.channel_id,
+                "position": { "x": light.x, "y": light.y, "z": light.z },
+                "members": [{
+                    "service": { "rid": light.id, "rtype": "entertainment" },
+                    "index": 0,
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "id": area.id,
+        "type": "entertainment_configuration",
+        "metadata": { "name": area.name },
+        "configuration_type": "screen",
+        "status": if active { "active" } else { "inactive" },
+        "channels": channels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hue::api::{client::HueClient, error::HueError, groups};
+    use crate::hue::audio_interface::AudioSpectrum;
+    use crate::hue::controller::HueController;
+    use crate::hue::engine::EntertainmentEngine;
+    use crate::hue::stream::{dtls::HueStreamer, manager::run_stream_loop};
+    use crate::light::effects::PulseEffect;
+    use std::time::Duration;
+    use tokio::sync::{broadcast, mpsc};
+
+    /// Poll `condition` for up to two seconds
+    async fn eventually(mut condition: impl FnMut() -> bool) -> bool {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_rest_api_against_mock() {
+        let bridge = MockBridge::start(MockBridgeConfig::default())
+            .await
+            .unwrap();
+        let url = bridge.url();
+
+        bridge.set_link_button(false);
+        assert!(matches!(
+            HueClient::register_user(&url, "test").await,
+            Err(HueError::LinkButtonNotPressed)
+        ));
+        bridge.set_link_button(true);
+        let mut config = HueClient::register_user(&url, "test").await.unwrap();
+        assert_eq!(config.username, "mapflow-virtual-user");
+
+        config.application_id = HueClient::get_application_id(&url, &config.username)
+            .await
+            .unwrap();
+        assert_eq!(config.application_id, "mapflow-virtual-app");
+
+        let areas = groups::get_entertainment_groups(&config).await.unwrap();
+        assert_eq!(areas.len(), 1);
+        assert_eq!(areas[0].lights.len(), 4);
+        assert_eq!(areas[0].lights[1].id, "virtual-light-2");
+
+        groups::set_stream_active(&config, &areas[0].id, true)
+            .await
+            .unwrap();
+        assert_eq!(bridge.streaming_area(), Some(areas[0].id.clone()));
+        assert!(groups::set_stream_active(&config, "unknown", true)
+            .await
+            .is_err());
+        groups::flash_light(&config, "1").await.unwrap();
+
+        config.username = "intruder".to_string();
+        assert!(groups::get_entertainment_groups(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_controller_streams_to_mock() {
+        let bridge = MockBridge::start(MockBridgeConfig::default())
+            .await
+            .unwrap();
+        let mut controller = HueController::new(bridge.hue_config());
+        controller.connect().await.unwrap();
+        assert!(controller.is_connected());
+
+        controller.update_lamp_colors(&[("virtual-light-3".to_string(), 0.0, 1.0, 0.0)]);
+        assert!(eventually(|| bridge.light_colors().get(&2) == Some(&(0, 255, 0))).await);
+
+        controller.update_from_command(None, 1.0, Some(0.0), Some(1.0), None);
+        assert!(eventually(|| bridge.lamps().iter().all(|lamp| lamp.color == (255, 0, 0))).await);
+
+        controller.disconnect().await;
+        assert!(bridge.streaming_area().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_engine_streams_to_mock() {
+        let bridge = MockBridge::start(MockBridgeConfig::default())
+            .await
+            .unwrap();
+        let config = bridge.hue_config();
+        groups::set_stream_active(&config, &config.entertainment_group_id, true)
+            .await
+            .unwrap();
+        let nodes = groups::get_entertainment_groups(&config).await.unwrap()[0]
+            .lights
+            .clone();
+
+        let streamer = HueStreamer::connect_plain(
+            crate::hue::api::plain_stream_addr(&config.bridge_ip).unwrap(),
+        )
+        .unwrap();
+        let (dtls_tx, dtls_rx) = mpsc::channel(8);
+        let area = config.entertainment_group_id.clone();
+        tokio::spawn(async move { run_stream_loop(streamer, dtls_rx, &area).await });
+
+        let (audio_tx, audio_rx) = broadcast::channel(8);
+        let mut engine = EntertainmentEngine::new(
+            audio_rx,
+            dtls_tx,
+            nodes,
+            Box::new(PulseEffect::new((0, 0, 200))),
+        );
+        tokio::spawn(async move { engine.run().await });
+
+        let full = AudioSpectrum {
+            bass: 1.0,
+            energy: 1.0,
+            ..Default::default()
+        };
+        assert!(
+            eventually(|| {
+                let _ = audio_tx.send(full);
+                bridge.lamps().iter().all(|lamp| lamp.color == (0, 0, 200))
+            })
+            .await
+        );
+        assert!(bridge.frames_received() > 0);
+    }
+}
@@ -1,7 +1,7 @@
 pub mod api;
 pub mod audio_interface;
 pub mod controller;
-pub mod effects;
 pub mod engine;
+pub mod mock;
 pub mod models;
 pub mod stream;
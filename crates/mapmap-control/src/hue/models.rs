@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+pub use crate::light::LightNode;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HueConfig {
     pub bridge_ip: String,
@@ -8,15 +10,3 @@ pub struct HueConfig {
     pub application_id: String, // Used as PSK Identity for DTLS (from /auth/v1)
     pub entertainment_group_id: String,
 }
-
-/// Represents a light channel in an entertainment configuration.
-/// Note: `channel_id` is the streaming ID (0, 1, 2...), NOT the light's REST API ID.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LightNode {
-    /// Unique identifier for this entity.
-    pub id: String, // REST API light ID (for reference)
-    pub channel_id: u8, // Streaming channel ID (0-based index for DTLS messages)
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
@@ -1,6 +1,10 @@
 use anyhow::{anyhow, Result};
+use std::net::{SocketAddr, UdpSocket};
 
-pub struct HueStreamer {}
+pub struct HueStreamer {
+    /// Unencrypted transport, only accepted by the mock bridge
+    plain: Option<UdpSocket>,
+}
 
 impl HueStreamer {
     /// Connects to the Hue Bridge via DTLS for entertainment streaming.
@@ -10,7 +14,27 @@ impl HueStreamer {
         Err(anyhow!("Hue Entertainment streaming is currently disabled because OpenSSL support is not compiled in (build hang avoidance)."))
     }
 
-    pub fn write_all(&mut self, _buf: &[u8]) -> Result<()> {
-        Err(anyhow!("Hue Entertainment streaming is disabled."))
+    /// Streams without encryption to `addr`, as the mock bridge expects.
+    pub fn connect_plain(addr: SocketAddr) -> Result<Self> {
+        let bind_addr: SocketAddr = if addr.is_ipv4() {
+            "0.0.0.0:0".parse()?
+        } else {
+            "[::]:0".parse()?
+        };
+        let socket = UdpSocket::bind(bind_addr)?;
+        socket.connect(addr)?;
+        Ok(Self {
+            plain: Some(socket),
+        })
+    }
+
+    pub fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        match &self.plain {
+            Some(socket) => {
+                socket.send(buf)?;
+                Ok(())
+            }
+            None => Err(anyhow!("Hue Entertainment streaming is disabled.")),
+        }
     }
 }
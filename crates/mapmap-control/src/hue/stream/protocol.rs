@@ -79,3 +79,66 @@ pub fn create_message(area_id: &str, lights: &HashMap<u8, (u8, u8, u8)>) -> Vec<
 
     buffer
 }
+
+/// A decoded Hue Entertainment streaming message (RGB color space).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamMessage {
+    pub sequence: u8,
+    /// Entertainment Area ID (UUID string)
+    pub area_id: String,
+    /// Channel ID -> 8-bit RGB
+    pub lights: HashMap<u8, (u8, u8, u8)>,
+}
+
+/// Decodes a message built by [`create_message`].
+///
+/// Returns `None` for anything that is not a complete v2 RGB message.
+pub fn parse_message(data: &[u8]) -> Option<StreamMessage> {
+    if data.len() < 16 + 36 || &data[..9] != b"HueStream" || data[9..11] != [0x02, 0x00] {
+        return None;
+    }
+    if data[14] != 0x00 {
+        // XY+Brightness is not supported
+        return None;
+    }
+    let channels = data[16 + 36..].chunks_exact(7);
+    if !channels.remainder().is_empty() {
+        return None;
+    }
+
+    let area_id = String::from_utf8_lossy(&data[16..16 + 36])
+        .trim_end_matches('\0')
+        .to_string();
+    let lights = channels
+        .map(|chunk| {
+            // 16-bit back to 8-bit: the high byte is exact for val * 257
+            (chunk[0], (chunk[1], chunk[3], chunk[5]))
+        })
+        .collect();
+
+    Some(StreamMessage {
+        sequence: data[11],
+        area_id,
+        lights,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_round_trip() {
+        let area = "1a8d99cc-967b-44f2-9202-43f976c0fa6b";
+        let lights = HashMap::from([(0, (255, 0, 0)), (3, (12, 34, 56))]);
+        let msg = create_message(area, &lights);
+        assert_eq!(msg.len(), 16 + 36 + 2 * 7);
+
+        let parsed = parse_message(&msg).unwrap();
+        assert_eq!(parsed.area_id, area);
+        assert_eq!(parsed.lights, lights);
+
+        assert!(parse_message(&msg[..msg.len() - 1]).is_none());
+        assert!(parse_message(b"HueStream").is_none());
+    }
+}
@@ -4,8 +4,10 @@
 //! - **MIDI**: Input/output, learn mode, controller profiles, clock sync
 //! - **OSC**: Server/client for TouchOSC, Lemur, and custom apps
 //! - **DMX**: Art-Net and sACN output for lighting control
+//! - **Lights**: Vendor-neutral light outputs (Hue, DMX, WLED/DDP pixels, Nanoleaf)
 //! - **Web API**: REST API and WebSocket for remote control
 //! - **Cue System**: Automated shows with crossfades and triggers
+//! - **Scheduler**: Calendar-based show playback for permanent installations
 //!
 //! ## Feature Flags
 //!
@@ -29,8 +31,10 @@
 //! - [`midi`]: MIDI input/output system
 //! - [`osc`]: OSC server and client
 //! - [`dmx`]: DMX output via Art-Net and sACN
+//! - [`light`]: Light sinks with shared effects and spatial mapping
 //! - `web`: Web API and WebSocket
 //! - [`cue`]: Cue system for show automation
+//! - [`scheduler`]: Calendar and sun-relative show scheduling
 //! - [`shortcuts`]: Keyboard shortcuts and macros
 //! - [`target`]: Control target abstraction
 //! - [`error`]: Error types
@@ -46,6 +50,7 @@ pub mod midi;
 
 pub mod dmx;
 pub mod hue;
+pub mod light;
 
 #[cfg(feature = "osc")]
 pub mod osc;
@@ -57,6 +62,7 @@ pub mod link;
 pub mod web;
 
 pub mod cue;
+pub mod scheduler;
 pub mod shortcuts;
 
 // Re-exports
@@ -70,6 +76,7 @@ pub use midi::MidiMessage;
 pub use dmx::{ArtNetSender, ChannelAssignment, DmxChannel, Fixture, FixtureProfile, SacnSender};
 pub use hue::controller::HueController;
 pub use hue::models::HueConfig;
+pub use light::{LightNode, LightSink};
 
 #[cfg(feature = "osc")]
 pub use osc::{OscClient, OscMapping, OscServer};
@@ -80,10 +87,11 @@ pub use link::AbletonLinkHandle;
 #[cfg(feature = "http-api")]
 pub use web::{WebServer, WebServerConfig};
 
-pub use cue::{Cue, CueList, FadeCurve, LayerState};
+pub use cue::{Cue, CueList, CueStacks, FadeCurve, LayerState, ModuleCueState};
+pub use scheduler::{Schedule, ScheduleAction, Scheduler};
 pub use shortcuts::{
-    Action, Key, KeyBindings, Macro, MacroPlayer, MacroRecorder, Modifiers, Shortcut,
-    ShortcutContext,
+    Action, Key, KeyBindings, Macro, MacroBinding, MacroPlayer, MacroRecorder, MacroTrigger,
+    Modifiers, Shortcut, ShortcutContext,
 };
 
 #[cfg(test)]
@@ -0,0 +1,163 @@
+//! DMX fixtures as a light sink
+
+use super::{LightNode, LightSink, MAX_NODES};
+use crate::dmx::{ArtNetSender, ChannelType, Fixture, SacnSender};
+use crate::{error::ControlError, Result};
+use std::collections::HashMap;
+
+/// How the universe leaves the machine.
+pub enum DmxTransport {
+    /// Art-Net to `target` (unicast or broadcast address with port).
+    ArtNet {
+        sender: ArtNetSender,
+        target: String,
+    },
+    /// sACN multicast.
+    Sacn(SacnSender),
+}
+
+/// One DMX universe of color fixtures; each fixture is one node.
+pub struct DmxLightSink {
+    transport: DmxTransport,
+    fixtures: Vec<(Fixture, LightNode)>,
+    data: [u8; 512],
+}
+
+impl DmxLightSink {
+    /// Art-Net output of `universe` to `target`
+    pub fn art_net(universe: u16, target: &str) -> Result<Self> {
+        Ok(Self::new(DmxTransport::ArtNet {
+            sender: ArtNetSender::new(universe, target)?,
+            target: target.to_string(),
+        }))
+    }
+
+    /// sACN output of `universe`
+    pub fn sacn(universe: u16, source_name: &str) -> Result<Self> {
+        Ok(Self::new(DmxTransport::Sacn(SacnSender::new(
+            universe,
+            source_name,
+        )?)))
+    }
+
+    pub fn new(transport: DmxTransport) -> Self {
+        Self {
+            transport,
+            fixtures: Vec::new(),
+            data: [0; 512],
+        }
+    }
+
+    /// Patch `fixture` at room position `(x, y)`; returns its node.
+    pub fn add_fixture(&mut self, fixture: Fixture, x: f64, y: f64) -> Result<LightNode> {
+        if self.fixtures.len() >= MAX_NODES {
+            return Err(ControlError::DmxError(format!(
+                "A light output addresses at most {} fixtures",
+                MAX_NODES
+            )));
+        }
+        if fixture.start_address == 0 || fixture.end_address() > 512 {
+            return Err(ControlError::DmxError(format!(
+                "Fixture '{}' does not fit into the universe (address {}-{})",
+                fixture.name,
+                fixture.start_address,
+                fixture.end_address()
+            )));
+        }
+        let node = LightNode::new(fixture.id.to_string(), self.fixtures.len() as u8, x, y);
+        self.fixtures.push((fixture, node.clone()));
+        Ok(node)
+    }
+
+    /// The current universe, as sent last.
+    pub fn dmx_data(&self) -> &[u8; 512] {
+        &self.data
+    }
+}
+
+/// Write `(r, g, b)` into whatever color channels the fixture has.
+fn apply_color(fixture: &Fixture, data: &mut [u8; 512], (r, g, b): (u8, u8, u8)) {
+    let has = |kind| {
+        fixture
+            .profile
+            .channels
+            .iter()
+            .any(|c| c.channel_type == kind)
+    };
+
+    if !has(ChannelType::Red) {
+        // Plain dimmer: brightness only
+        fixture.set_dimmer(data, r.max(g).max(b));
+        return;
+    }
+
+    if has(ChannelType::White) {
+        // Move the common part of all three channels to the white emitter
+        let w = r.min(g).min(b);
+        fixture.set_rgbw(data, r - w, g - w, b - w, w);
+    } else {
+        fixture.set_rgb(data, r, g, b);
+    }
+    fixture.set_dimmer(data, 255);
+}
+
+impl LightSink for DmxLightSink {
+    fn nodes(&self) -> Vec<LightNode> {
+        self.fixtures.iter().map(|(_, node)| node.clone()).collect()
+    }
+
+    fn send(&mut self, colors: &HashMap<u8, (u8, u8, u8)>) -> Result<()> {
+        for (fixture, node) in &self.fixtures {
+            if let Some(color) = colors.get(&node.channel_id) {
+                apply_color(fixture, &mut self.data, *color);
+            }
+        }
+        match &mut self.transport {
+            DmxTransport::ArtNet { sender, target } => sender.send_dmx(&self.data, target),
+            DmxTransport::Sacn(sender) => sender.send_dmx(&self.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dmx::FixtureProfile;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    #[test]
+    fn test_fixtures_over_art_net() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+
+        let mut sink = DmxLightSink::art_net(0, &target).unwrap();
+        let par = Fixture::new(1, "Par".into(), FixtureProfile::rgb_par(), 0, 1);
+        let wash = Fixture::new(2, "Wash".into(), FixtureProfile::rgbw_par(), 0, 10);
+        let dimmer = Fixture::new(3, "Dimmer".into(), FixtureProfile::generic_dimmer(), 0, 20);
+        sink.add_fixture(par, -1.0, 0.0).unwrap();
+        sink.add_fixture(wash, 1.0, 0.0).unwrap();
+        sink.add_fixture(dimmer, 0.0, 0.0).unwrap();
+        let overflow = Fixture::new(4, "Late".into(), FixtureProfile::rgb_par(), 0, 511);
+        assert!(sink.add_fixture(overflow, 0.0, 0.0).is_err());
+        assert_eq!(sink.nodes().len(), 3);
+
+        // The sender rate-limits to 30 Hz; let the first interval pass
+        std::thread::sleep(Duration::from_millis(40));
+        let colors = HashMap::from([(0, (255, 0, 0)), (1, (255, 128, 64)), (2, (10, 200, 30))]);
+        sink.send(&colors).unwrap();
+
+        let data = sink.dmx_data();
+        assert_eq!(&data[0..3], &[255, 0, 0]);
+        assert_eq!(&data[9..13], &[191, 64, 0, 64]);
+        assert_eq!(data[19], 200);
+
+        let mut buf = [0u8; 600];
+        let len = listener.recv(&mut buf).unwrap();
+        assert!(buf.starts_with(b"Art-Net\0"));
+        assert_eq!(&buf[len - 512..len - 509], &[255, 0, 0]);
+    }
+}
@@ -0,0 +1,92 @@
+use super::{scale, spatial_order, LightEffect};
+use crate::light::AudioSpectrum;
+use crate::light::LightNode;
+use std::collections::HashMap;
+
+/// A light running through the lamps from left to right, faster with louder audio.
+pub struct ChaseEffect {
+    /// RGB color of the running light.
+    pub color: (u8, u8, u8),
+    /// Lamps per second at silence; full energy doubles the speed.
+    pub speed: f32,
+    /// Number of lamps lit around the head (fading towards the edges).
+    pub width: f32,
+    position: f32,
+}
+
+impl ChaseEffect {
+    /// Creates a chase of `color` moving `speed` lamps per second.
+    pub fn new(color: (u8, u8, u8), speed: f32) -> Self {
+        Self {
+            color,
+            speed,
+            width: 1.0,
+            position: 0.0,
+        }
+    }
+}
+
+impl LightEffect for ChaseEffect {
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
+        let mut result = HashMap::new();
+        if nodes.is_empty() {
+            return result;
+        }
+
+        let count = nodes.len() as f32;
+        let speed = self.speed * (1.0 + audio.energy.clamp(0.0, 1.0));
+        self.position = (self.position + speed * dt).rem_euclid(count);
+
+        let width = self.width.max(0.01);
+        for (i, node) in spatial_order(nodes).into_iter().enumerate() {
+            // Distance to the head, wrapping around the end of the row
+            let distance = (i as f32 - self.position).abs();
+            let distance = distance.min(count - distance);
+            let brightness = 1.0 - distance / width;
+            result.insert(node.channel_id, scale(self.color, brightness));
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(count: u8) -> Vec<LightNode> {
+        (0..count)
+            .map(|i| LightNode {
+                id: i.to_string(),
+                channel_id: i,
+                x: -1.0 + i as f64,
+                y: 0.0,
+                z: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chase_moves_through_lamps() {
+        let nodes = row(4);
+        let mut chase = ChaseEffect::new((255, 0, 0), 2.0);
+        let silence = AudioSpectrum::default();
+
+        let frame = chase.update(&silence, &nodes, 0.0);
+        assert_eq!(frame[&0], (255, 0, 0));
+        assert_eq!(frame[&1], (0, 0, 0));
+
+        // Half a second at two lamps per second
+        let frame = chase.update(&silence, &nodes, 0.5);
+        assert_eq!(frame[&0], (0, 0, 0));
+        assert_eq!(frame[&1], (255, 0, 0));
+
+        // Wraps around after the last lamp
+        let frame = chase.update(&silence, &nodes, 1.5);
+        assert_eq!(frame[&0], (255, 0, 0));
+    }
+}
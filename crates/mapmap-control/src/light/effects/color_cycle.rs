@@ -0,0 +1,89 @@
+use super::{spatial_order, LightEffect};
+use crate::light::AudioSpectrum;
+use crate::light::LightNode;
+use palette::{FromColor, Hsv, Srgb};
+use std::collections::HashMap;
+
+/// Hue rotating over time, spread across the lamps as a rainbow.
+pub struct ColorCycleEffect {
+    /// Full color cycles per second.
+    pub speed: f32,
+    /// Hue offset between the first and the last lamp, in cycles (0.0 = all lamps the same).
+    pub spread: f32,
+    /// Color saturation (0.0 - 1.0).
+    pub saturation: f32,
+    /// Brightness (0.0 - 1.0).
+    pub brightness: f32,
+    phase: f32,
+}
+
+impl ColorCycleEffect {
+    /// Creates a cycle running `speed` cycles per second with `spread` across the lamps.
+    pub fn new(speed: f32, spread: f32) -> Self {
+        Self {
+            speed,
+            spread,
+            saturation: 1.0,
+            brightness: 1.0,
+            phase: 0.0,
+        }
+    }
+}
+
+impl LightEffect for ColorCycleEffect {
+    fn update(
+        &mut self,
+        _audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
+        self.phase = (self.phase + self.speed * dt).rem_euclid(1.0);
+
+        let count = nodes.len().max(1) as f32;
+        spatial_order(nodes)
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let hue = (self.phase + self.spread * i as f32 / count).rem_euclid(1.0);
+                let hsv = Hsv::new(
+                    hue * 360.0,
+                    self.saturation.clamp(0.0, 1.0),
+                    self.brightness.clamp(0.0, 1.0),
+                );
+                let rgb: Srgb = Srgb::from_color(hsv);
+                let rgb = rgb.into_format::<u8>();
+                (node.channel_id, (rgb.red, rgb.green, rgb.blue))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_rotates_and_spreads() {
+        let nodes: Vec<LightNode> = (0..3)
+            .map(|i| LightNode {
+                id: i.to_string(),
+                channel_id: i,
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            })
+            .collect();
+        let audio = AudioSpectrum::default();
+        let mut cycle = ColorCycleEffect::new(1.0, 1.0);
+
+        let frame = cycle.update(&audio, &nodes, 0.0);
+        assert_eq!(frame[&0], (255, 0, 0));
+        assert_eq!(frame[&1], (0, 255, 0));
+        assert_eq!(frame[&2], (0, 0, 255));
+
+        // A third of a cycle later every lamp took its neighbour's color
+        let frame = cycle.update(&audio, &nodes, 1.0 / 3.0);
+        assert_eq!(frame[&0], (0, 255, 0));
+        assert_eq!(frame[&2], (255, 0, 0));
+    }
+}
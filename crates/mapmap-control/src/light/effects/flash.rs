@@ -0,0 +1,102 @@
+use super::{scale, LightEffect};
+use crate::light::AudioSpectrum;
+use crate::light::LightNode;
+use std::collections::HashMap;
+
+/// Upper flash rate; photosensitivity guidelines (WCAG 2.3.1, ITU-R BT.1702)
+/// allow no more than three flashes per second.
+pub const MAX_FLASHES_PER_SECOND: f32 = 3.0;
+
+/// Shortest fade-out, so no flash is a hard on/off edge.
+const MIN_DECAY: f32 = 0.1;
+
+/// All lamps flash on bass hits and fade out, never faster than
+/// [`MAX_FLASHES_PER_SECOND`] no matter how dense the hits are.
+pub struct FlashEffect {
+    /// RGB color of a flash.
+    pub color: (u8, u8, u8),
+    /// Bass level (0.0 - 1.0) that triggers a flash.
+    pub threshold: f32,
+    /// Seconds a flash takes to fade out.
+    pub decay: f32,
+    level: f32,
+    since_flash: f32,
+    armed: bool,
+}
+
+impl FlashEffect {
+    /// Creates a flash of `color` triggered when the bass crosses `threshold`.
+    pub fn new(color: (u8, u8, u8), threshold: f32) -> Self {
+        Self {
+            color,
+            threshold,
+            decay: 0.25,
+            level: 0.0,
+            since_flash: 1.0 / MAX_FLASHES_PER_SECOND,
+            armed: true,
+        }
+    }
+}
+
+impl LightEffect for FlashEffect {
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
+        self.since_flash += dt;
+        self.level = (self.level - dt / self.decay.max(MIN_DECAY)).max(0.0);
+
+        if audio.bass >= self.threshold {
+            // Only the rising edge of a hit flashes, and only when the last
+            // flash is long enough ago
+            if self.armed && self.since_flash >= 1.0 / MAX_FLASHES_PER_SECOND {
+                self.level = 1.0;
+                self.since_flash = 0.0;
+            }
+            self.armed = false;
+        } else {
+            self.armed = true;
+        }
+
+        let color = scale(self.color, self.level);
+        nodes.iter().map(|node| (node.channel_id, color)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flash_rate_is_limited() {
+        let nodes = vec![LightNode {
+            id: "1".to_string(),
+            channel_id: 0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }];
+        let mut flash = FlashEffect::new((255, 255, 255), 0.5);
+        let hit = AudioSpectrum {
+            bass: 1.0,
+            ..Default::default()
+        };
+        let silence = AudioSpectrum::default();
+
+        // Hits on every other frame at 60 fps: 30 hits per second
+        let mut flashes = 0;
+        let mut last = 0;
+        for frame in 0..120 {
+            let audio = if frame % 2 == 0 { &hit } else { &silence };
+            let level = flash.update(audio, &nodes, 1.0 / 60.0)[&0].0;
+            if level == 255 && last < 255 {
+                flashes += 1;
+            }
+            last = level;
+        }
+        assert!(flashes > 0);
+        assert!(flashes as f32 <= 2.0 * MAX_FLASHES_PER_SECOND);
+    }
+}
@@ -1,12 +1,52 @@
-use crate::hue::audio_interface::AudioSpectrum;
-use crate::hue::models::LightNode;
+use crate::light::AudioSpectrum;
+use crate::light::LightNode;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
+mod chase;
+mod color_cycle;
+mod flash;
+mod sparkle;
+
+pub use chase::ChaseEffect;
+pub use color_cycle::ColorCycleEffect;
+pub use flash::{FlashEffect, MAX_FLASHES_PER_SECOND};
+pub use sparkle::SparkleEffect;
+
 /// Trait for light effects that map audio to colors.
 /// The returned HashMap uses channel_id (u8) as key, not the REST API light ID.
+/// `dt` is the time in seconds since the previous update.
 pub trait LightEffect: Send + Sync {
-    fn update(&mut self, audio: &AudioSpectrum, nodes: &[LightNode]) -> HashMap<u8, (u8, u8, u8)>;
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)>;
+}
+
+/// Nodes in room order: left to right when positions are known, else by channel.
+pub(crate) fn spatial_order(nodes: &[LightNode]) -> Vec<&LightNode> {
+    let mut ordered: Vec<&LightNode> = nodes.iter().collect();
+    if nodes
+        .iter()
+        .any(|n| n.x.abs() > 0.001 || n.y.abs() > 0.001 || n.z.abs() > 0.001)
+    {
+        ordered.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal));
+    } else {
+        ordered.sort_by_key(|n| n.channel_id);
+    }
+    ordered
+}
+
+/// `color` dimmed to `brightness` (0.0 - 1.0).
+pub(crate) fn scale(color: (u8, u8, u8), brightness: f32) -> (u8, u8, u8) {
+    let brightness = brightness.clamp(0.0, 1.0);
+    (
+        (color.0 as f32 * brightness) as u8,
+        (color.1 as f32 * brightness) as u8,
+        (color.2 as f32 * brightness) as u8,
+    )
 }
 
 pub struct PulseEffect {
@@ -22,7 +62,12 @@ impl PulseEffect {
 }
 
 impl LightEffect for PulseEffect {
-    fn update(&mut self, audio: &AudioSpectrum, nodes: &[LightNode]) -> HashMap<u8, (u8, u8, u8)> {
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        _dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
         let brightness = (audio.bass * audio.energy).clamp(0.0, 1.0);
         let r = (self.color.0 as f32 * brightness) as u8;
         let g = (self.color.1 as f32 * brightness) as u8;
@@ -53,7 +98,12 @@ impl Default for MultiBandEffect {
 }
 
 impl LightEffect for MultiBandEffect {
-    fn update(&mut self, audio: &AudioSpectrum, nodes: &[LightNode]) -> HashMap<u8, (u8, u8, u8)> {
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        _dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
         let mut result = HashMap::new();
         if nodes.is_empty() {
             return result;
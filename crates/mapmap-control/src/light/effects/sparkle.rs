@@ -0,0 +1,109 @@
+use super::{scale, LightEffect};
+use crate::light::AudioSpectrum;
+use crate::light::LightNode;
+use std::collections::HashMap;
+
+/// Random lamps light up and fade out; the highs drive how often they sparkle.
+pub struct SparkleEffect {
+    /// RGB color of a sparkle.
+    pub color: (u8, u8, u8),
+    /// Sparkles per second at full highs (a quarter of that at silence).
+    pub rate: f32,
+    /// Seconds a sparkle takes to fade out.
+    pub decay: f32,
+    levels: HashMap<u8, f32>,
+    pending: f32,
+    seed: u32,
+}
+
+impl SparkleEffect {
+    /// Creates a sparkle of `color` with `rate` sparkles per second.
+    pub fn new(color: (u8, u8, u8), rate: f32) -> Self {
+        Self::with_seed(color, rate, 0x9E37_79B9)
+    }
+
+    /// Same as [`SparkleEffect::new`] with a fixed random sequence.
+    pub fn with_seed(color: (u8, u8, u8), rate: f32, seed: u32) -> Self {
+        Self {
+            color,
+            rate,
+            decay: 0.4,
+            levels: HashMap::new(),
+            pending: 0.0,
+            seed: seed.max(1),
+        }
+    }
+
+    /// xorshift32
+    fn next_random(&mut self) -> u32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed
+    }
+}
+
+impl LightEffect for SparkleEffect {
+    fn update(
+        &mut self,
+        audio: &AudioSpectrum,
+        nodes: &[LightNode],
+        dt: f32,
+    ) -> HashMap<u8, (u8, u8, u8)> {
+        let fade = dt / self.decay.max(0.01);
+        for level in self.levels.values_mut() {
+            *level = (*level - fade).max(0.0);
+        }
+
+        if !nodes.is_empty() {
+            self.pending += self.rate * (0.25 + 0.75 * audio.highs.clamp(0.0, 1.0)) * dt;
+            while self.pending >= 1.0 {
+                self.pending -= 1.0;
+                let index = self.next_random() as usize % nodes.len();
+                self.levels.insert(nodes[index].channel_id, 1.0);
+            }
+        }
+
+        nodes
+            .iter()
+            .map(|node| {
+                let level = self.levels.get(&node.channel_id).copied().unwrap_or(0.0);
+                (node.channel_id, scale(self.color, level))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkles_fire_and_fade() {
+        let nodes: Vec<LightNode> = (0..3)
+            .map(|i| LightNode {
+                id: i.to_string(),
+                channel_id: i,
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            })
+            .collect();
+        let mut sparkle = SparkleEffect::with_seed((0, 0, 255), 10.0, 7);
+        let loud = AudioSpectrum {
+            highs: 1.0,
+            ..Default::default()
+        };
+
+        // 10 sparkles per second -> one sparkle after 0.1s
+        let frame = sparkle.update(&loud, &nodes, 0.1);
+        assert_eq!(frame.values().filter(|c| **c == (0, 0, 255)).count(), 1);
+
+        // Without new sparkles everything fades out
+        sparkle.rate = 0.0;
+        let frame = sparkle.update(&loud, &nodes, 0.2);
+        assert!(frame.values().all(|c| c.2 < 255));
+        let frame = sparkle.update(&loud, &nodes, 0.2);
+        assert!(frame.values().all(|c| *c == (0, 0, 0)));
+    }
+}
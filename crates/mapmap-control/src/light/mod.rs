@@ -0,0 +1,142 @@
+//! Vendor-neutral light output
+//!
+//! Every light system (Hue entertainment areas, DMX fixtures, WLED strips,
+//! Nanoleaf panels) is exposed as a [`LightSink`]: a list of [`LightNode`]s
+//! with a room position and a way to send one color per node. Effects and
+//! spatial sampling only work on nodes, so a light program runs unchanged on
+//! any backend.
+//!
+//! ```rust,no_run
+//! use mapmap_control::light::effects::{ChaseEffect, LightEffect};
+//! use mapmap_control::light::{AudioSpectrum, LightSink, WledLightSink};
+//!
+//! # fn main() -> mapmap_control::Result<()> {
+//! let mut strip = WledLightSink::new("192.168.1.50:21324", 60)?;
+//! let mut chase = ChaseEffect::new((255, 0, 128), 1.0);
+//! let colors = chase.update(&AudioSpectrum::default(), &strip.nodes(), 1.0 / 60.0);
+//! strip.send(&colors)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub mod effects;
+pub mod spatial;
+
+mod dmx;
+mod nanoleaf;
+mod wled;
+
+pub use dmx::{DmxLightSink, DmxTransport};
+pub use nanoleaf::{NanoleafLightSink, NanoleafPanel, NANOLEAF_API_PORT, NANOLEAF_STREAM_PORT};
+pub use wled::{WledLightSink, WledProtocol, DDP_PORT, DRGB_MAX_LEDS, WLED_REALTIME_PORT};
+
+/// Nodes one sink can address (`channel_id` is a `u8`).
+pub const MAX_NODES: usize = 256;
+
+/// Represents a light channel of a light output.
+/// Note: `channel_id` is the streaming ID (0, 1, 2...), NOT the light's REST API ID.
+/// Positions use Hue room coordinates (-1.0 - 1.0): x left to right,
+/// y back to front, z floor to ceiling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightNode {
+    /// Unique identifier for this entity.
+    pub id: String, // Vendor light ID (for reference)
+    pub channel_id: u8, // Streaming channel ID (0-based index into the sink's frame)
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl LightNode {
+    /// A node at `(x, y)` at height 0.
+    pub fn new(id: impl Into<String>, channel_id: u8, x: f64, y: f64) -> Self {
+        Self {
+            id: id.into(),
+            channel_id,
+            x,
+            y,
+            z: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioSpectrum {
+    pub bass: f32,
+    pub mids: f32,
+    pub highs: f32,
+    pub energy: f32,
+}
+
+pub trait AudioProcessor {
+    fn process(&mut self, samples: &[f32]) -> AudioSpectrum;
+}
+
+/// A light system addressed through its nodes.
+pub trait LightSink: Send {
+    /// The addressable lights, with their room positions.
+    fn nodes(&self) -> Vec<LightNode>;
+
+    /// Send one color per node, keyed by `channel_id`. Nodes without an
+    /// entry keep their last color.
+    fn send(&mut self, colors: &HashMap<u8, (u8, u8, u8)>) -> Result<()>;
+}
+
+/// Convert per-lamp colors (0.0 - 1.0, keyed by node id as produced by
+/// [`spatial::sample_lamps`]) into the channel map a [`LightSink`] takes.
+pub fn colors_by_channel(
+    nodes: &[LightNode],
+    updates: &[(String, f32, f32, f32)],
+) -> HashMap<u8, (u8, u8, u8)> {
+    let to_u8 = |v: f32| (v * 255.0).clamp(0.0, 255.0) as u8;
+    updates
+        .iter()
+        .filter_map(|(id, r, g, b)| {
+            nodes
+                .iter()
+                .find(|node| &node.id == id)
+                .map(|node| (node.channel_id, (to_u8(*r), to_u8(*g), to_u8(*b))))
+        })
+        .collect()
+}
+
+/// `count` nodes spread evenly from left to right at `y`, named `{prefix}-{n}`.
+pub(crate) fn line_nodes(prefix: &str, count: usize, y: f64) -> Vec<LightNode> {
+    let count = count.min(MAX_NODES);
+    (0..count)
+        .map(|i| {
+            let x = if count > 1 {
+                i as f64 / (count - 1) as f64 * 2.0 - 1.0
+            } else {
+                0.0
+            };
+            LightNode::new(format!("{}-{}", prefix, i + 1), i as u8, x, y)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_by_channel() {
+        let nodes = line_nodes("lamp", 3, 0.0);
+        assert_eq!(nodes[0].x, -1.0);
+        assert_eq!(nodes[2].x, 1.0);
+
+        let colors = colors_by_channel(
+            &nodes,
+            &[
+                ("lamp-3".to_string(), 1.0, 0.5, 0.0),
+                ("unknown".to_string(), 1.0, 1.0, 1.0),
+            ],
+        );
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[&2], (255, 127, 0));
+    }
+}
@@ -0,0 +1,204 @@
+//! Nanoleaf panels as a light sink (External Control v2)
+//!
+//! Streaming is enabled through the OpenAPI on port 16021; the controller then
+//! takes UDP frames on port 60222 that set every listed panel at once.
+
+use super::wled::resolve;
+use super::{LightNode, LightSink, MAX_NODES};
+use crate::{error::ControlError, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+
+/// Port of the Nanoleaf OpenAPI
+pub const NANOLEAF_API_PORT: u16 = 16021;
+/// Port External Control frames are sent to
+pub const NANOLEAF_STREAM_PORT: u16 = 60222;
+/// Shape types without LEDs (Shapes and Lines controllers, power supply)
+const SHAPES_WITHOUT_LIGHT: [u8; 3] = [12, 16, 18];
+/// Transition time of every frame, in 100 ms steps
+const TRANSITION_TIME: u16 = 1;
+
+/// A panel as reported by `panelLayout/layout`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NanoleafPanel {
+    #[serde(rename = "panelId")]
+    pub panel_id: u16,
+    pub x: i32,
+    pub y: i32,
+    #[serde(rename = "shapeType", default)]
+    pub shape_type: u8,
+}
+
+#[derive(Deserialize)]
+struct Layout {
+    #[serde(rename = "positionData")]
+    position_data: Vec<NanoleafPanel>,
+}
+
+/// The panels of one Nanoleaf controller; each lit panel is one node, placed
+/// by its position in the layout.
+pub struct NanoleafLightSink {
+    socket: UdpSocket,
+    target: SocketAddr,
+    nodes: Vec<LightNode>,
+    panel_ids: Vec<u16>,
+    colors: Vec<(u8, u8, u8)>,
+}
+
+impl NanoleafLightSink {
+    /// Enable External Control on the controller at `host` and read its layout.
+    pub async fn connect(host: &str, auth_token: &str) -> Result<Self> {
+        let api = format!(
+            "http://{}:{}/api/v1/{}",
+            host, NANOLEAF_API_PORT, auth_token
+        );
+        let client = reqwest::Client::new();
+        let http_error = |e: reqwest::Error| ControlError::HttpError(e.to_string());
+
+        client
+            .put(format!("{}/effects", api))
+            .json(&serde_json::json!({
+                "write": {
+                    "command": "display",
+                    "animType": "extControl",
+                    "extControlVersion": "v2"
+                }
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(http_error)?;
+
+        let layout: Layout = client
+            .get(format!("{}/panelLayout/layout", api))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(http_error)?
+            .json()
+            .await
+            .map_err(http_error)?;
+
+        let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+        Self::with_panels(
+            &format!("{}:{}", host, NANOLEAF_STREAM_PORT),
+            layout.position_data,
+        )
+    }
+
+    /// Stream to `target` for an already known layout.
+    pub fn with_panels(target: &str, panels: Vec<NanoleafPanel>) -> Result<Self> {
+        let target = resolve(target, NANOLEAF_STREAM_PORT)?;
+        let panels: Vec<NanoleafPanel> = panels
+            .into_iter()
+            .filter(|p| !SHAPES_WITHOUT_LIGHT.contains(&p.shape_type))
+            .take(MAX_NODES)
+            .collect();
+
+        // Layout units are arbitrary; fit them into the room square,
+        // keeping the aspect ratio.
+        let (min_x, max_x) = extent(panels.iter().map(|p| p.x));
+        let (min_y, max_y) = extent(panels.iter().map(|p| p.y));
+        let half = ((max_x - min_x).max(max_y - min_y) as f64 / 2.0).max(1.0);
+        let center = ((min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0);
+        let nodes = panels
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                LightNode::new(
+                    p.panel_id.to_string(),
+                    i as u8,
+                    (p.x as f64 - center.0) / half,
+                    (p.y as f64 - center.1) / half,
+                )
+            })
+            .collect();
+
+        let socket = UdpSocket::bind(if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        })?;
+        Ok(Self {
+            socket,
+            target,
+            nodes,
+            colors: vec![(0, 0, 0); panels.len()],
+            panel_ids: panels.iter().map(|p| p.panel_id).collect(),
+        })
+    }
+}
+
+fn extent(values: impl Iterator<Item = i32>) -> (i32, i32) {
+    values.fold((i32::MAX, i32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+/// An External Control v2 frame: panel count, then per panel its id, RGBW and
+/// transition time (all 16-bit values big-endian)
+fn frame(panel_ids: &[u16], colors: &[(u8, u8, u8)]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(2 + panel_ids.len() * 8);
+    packet.extend_from_slice(&(panel_ids.len() as u16).to_be_bytes());
+    for (id, (r, g, b)) in panel_ids.iter().zip(colors) {
+        packet.extend_from_slice(&id.to_be_bytes());
+        packet.extend_from_slice(&[*r, *g, *b, 0]);
+        packet.extend_from_slice(&TRANSITION_TIME.to_be_bytes());
+    }
+    packet
+}
+
+impl LightSink for NanoleafLightSink {
+    fn nodes(&self) -> Vec<LightNode> {
+        self.nodes.clone()
+    }
+
+    fn send(&mut self, colors: &HashMap<u8, (u8, u8, u8)>) -> Result<()> {
+        for (channel, color) in colors {
+            if let Some(slot) = self.colors.get_mut(*channel as usize) {
+                *slot = *color;
+            }
+        }
+        self.socket
+            .send_to(&frame(&self.panel_ids, &self.colors), self.target)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_layout_and_frame() {
+        let layout: Layout = serde_json::from_str(
+            r#"{"numPanels":3,"sideLength":100,"positionData":[
+                {"panelId":10,"x":0,"y":0,"o":0,"shapeType":7},
+                {"panelId":0,"x":50,"y":0,"o":0,"shapeType":12},
+                {"panelId":20,"x":200,"y":100,"o":60,"shapeType":7}
+            ]}"#,
+        )
+        .unwrap();
+
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        let mut sink = NanoleafLightSink::with_panels(&target, layout.position_data).unwrap();
+
+        // The controller is skipped; the panels span the room horizontally
+        let nodes = sink.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!((nodes[0].x, nodes[0].y), (-1.0, -0.5));
+        assert_eq!((nodes[1].x, nodes[1].y), (1.0, 0.5));
+
+        sink.send(&HashMap::from([(1, (255, 10, 20))])).unwrap();
+        let mut buf = [0u8; 64];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(
+            &buf[..len],
+            &[0, 2, 0, 10, 0, 0, 0, 0, 0, 1, 0, 20, 255, 10, 20, 0, 0, 1]
+        );
+    }
+}
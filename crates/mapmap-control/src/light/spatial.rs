@@ -0,0 +1,191 @@
+//! Spatial mapping: lamp colors sampled from the rendered image
+//!
+//! Every lamp has a normalized position (0.0 - 1.0, origin top left) in the
+//! image of the layer feeding the light output. The lamp takes the average color
+//! of a small area around that point, so the lights continue the projection
+//! into the room.
+
+use crate::dmx::LedStrip;
+use std::collections::HashMap;
+
+/// A frame read back from the GPU.
+pub struct PixelFrame<'a> {
+    /// 8-bit RGBA or BGRA pixels.
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    /// Bytes per row including padding (GPU read-backs pad rows to 256 bytes).
+    pub bytes_per_row: u32,
+    /// Pixels are stored as BGRA (surface formats) instead of RGBA.
+    pub bgra: bool,
+}
+
+impl PixelFrame<'_> {
+    /// Average color (0.0 - 1.0) of the pixels within `radius` around the
+    /// normalized position `(x, y)`. `None` if the frame is empty or too short.
+    pub fn sample(&self, x: f32, y: f32, radius: u32) -> Option<(f32, f32, f32)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let cx = (x.clamp(0.0, 1.0) * (self.width - 1) as f32).round() as u32;
+        let cy = (y.clamp(0.0, 1.0) * (self.height - 1) as f32).round() as u32;
+
+        let mut sum = [0u32; 3];
+        let mut count = 0;
+        for py in cy.saturating_sub(radius)..=(cy + radius).min(self.height - 1) {
+            for px in cx.saturating_sub(radius)..=(cx + radius).min(self.width - 1) {
+                let offset = (py * self.bytes_per_row + px * 4) as usize;
+                let pixel = self.data.get(offset..offset + 4)?;
+                for (channel, value) in sum.iter_mut().zip(pixel) {
+                    *channel += *value as u32;
+                }
+                count += 1;
+            }
+        }
+
+        let [first, green, third] = sum.map(|c| c as f32 / (count as f32 * 255.0));
+        Some(if self.bgra {
+            (third, green, first)
+        } else {
+            (first, green, third)
+        })
+    }
+}
+
+/// Colors for each lamp in `positions` (light ID -> normalized position),
+/// as (LightID, R, G, B) updates for the Hue controller.
+pub fn sample_lamps(
+    frame: &PixelFrame<'_>,
+    positions: &HashMap<String, (f32, f32)>,
+    radius: u32,
+) -> Vec<(String, f32, f32, f32)> {
+    positions
+        .iter()
+        .filter_map(|(id, (x, y))| {
+            let (r, g, b) = frame.sample(*x, *y, radius)?;
+            Some((id.clone(), r, g, b))
+        })
+        .collect()
+}
+
+/// One color per controller pixel for `pixel_count` pixels, with the pixels
+/// of every strip sampled along its layout. Pixels no strip covers stay black.
+pub fn sample_strips(
+    frame: &PixelFrame<'_>,
+    strips: &[LedStrip],
+    pixel_count: usize,
+    radius: u32,
+) -> Vec<(u8, u8, u8)> {
+    let mut pixels = vec![(0, 0, 0); pixel_count];
+    for strip in strips {
+        let start = strip.start_pixel as usize;
+        for (pixel, (x, y)) in pixels.iter_mut().skip(start).zip(strip.layout.positions()) {
+            if let Some((r, g, b)) = frame.sample(x, y, radius) {
+                *pixel = ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+            }
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x2 frame: left half red, right half blue; rows padded to 20 bytes
+    fn split_frame(bgra: bool) -> Vec<u8> {
+        let red = if bgra {
+            [0, 0, 255, 255]
+        } else {
+            [255, 0, 0, 255]
+        };
+        let blue = if bgra {
+            [255, 0, 0, 255]
+        } else {
+            [0, 0, 255, 255]
+        };
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            for x in 0..4 {
+                data.extend_from_slice(if x < 2 { &red } else { &blue });
+            }
+            data.extend_from_slice(&[0; 4]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_lamps_take_color_at_their_position() {
+        for bgra in [false, true] {
+            let data = split_frame(bgra);
+            let frame = PixelFrame {
+                data: &data,
+                width: 4,
+                height: 2,
+                bytes_per_row: 20,
+                bgra,
+            };
+            let positions = HashMap::from([
+                ("left".to_string(), (0.0, 0.5)),
+                ("right".to_string(), (1.0, 0.5)),
+            ]);
+            let colors: HashMap<String, (f32, f32, f32)> = sample_lamps(&frame, &positions, 0)
+                .into_iter()
+                .map(|(id, r, g, b)| (id, (r, g, b)))
+                .collect();
+            assert_eq!(colors["left"], (1.0, 0.0, 0.0));
+            assert_eq!(colors["right"], (0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn test_sample_averages_area() {
+        let data = split_frame(false);
+        let frame = PixelFrame {
+            data: &data,
+            width: 4,
+            height: 2,
+            bytes_per_row: 20,
+            bgra: false,
+        };
+        // Columns 1..=3 around the centre: one red, two blue
+        let (r, g, b) = frame.sample(0.5, 0.0, 1).unwrap();
+        assert!((r - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(g, 0.0);
+        assert!((b - 2.0 / 3.0).abs() < 1e-6);
+
+        let short = PixelFrame {
+            data: &data[..10],
+            ..frame
+        };
+        assert!(short.sample(1.0, 1.0, 0).is_none());
+    }
+
+    #[test]
+    fn test_strips_fill_controller_pixels() {
+        let data = split_frame(false);
+        let frame = PixelFrame {
+            data: &data,
+            width: 4,
+            height: 2,
+            bytes_per_row: 20,
+            bgra: false,
+        };
+        let line = LedStrip::new(
+            0,
+            "Top".to_string(),
+            crate::dmx::LedLayout::Line {
+                start: (0.0, 0.0),
+                end: (1.0, 0.0),
+                count: 2,
+            },
+            1,
+        );
+        // Pixel 0 is unpatched; the strip runs off the end of the controller
+        assert_eq!(
+            sample_strips(&frame, std::slice::from_ref(&line), 4, 0),
+            vec![(0, 0, 0), (255, 0, 0), (0, 0, 255), (0, 0, 0)]
+        );
+        assert_eq!(sample_strips(&frame, &[line], 2, 0).len(), 2);
+    }
+}
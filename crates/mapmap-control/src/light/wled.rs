@@ -0,0 +1,348 @@
+//! WLED and other ESP32 pixel controllers as a light sink
+//!
+//! Pixels go out with the WLED UDP realtime protocols (DRGB, DNRGB) or with
+//! DDP, which WLED and most other pixel firmwares understand. WLED takes over
+//! its LEDs while realtime packets arrive and returns to its own effect
+//! `timeout` seconds after the last one.
+
+use super::{line_nodes, LightNode, LightSink, MAX_NODES};
+use crate::{error::ControlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// Default port of the WLED UDP realtime protocols
+pub const WLED_REALTIME_PORT: u16 = 21324;
+/// Default port of DDP
+pub const DDP_PORT: u16 = 4048;
+/// LEDs that fit into one DRGB packet
+pub const DRGB_MAX_LEDS: usize = 490;
+/// Protocol byte of DRGB packets
+const DRGB: u8 = 2;
+/// Protocol byte of DNRGB packets
+const DNRGB: u8 = 4;
+/// LEDs per DNRGB packet
+const DNRGB_LEDS_PER_PACKET: usize = 489;
+/// LEDs addressable by the 16-bit DNRGB start index
+pub const DNRGB_MAX_LEDS: usize = u16::MAX as usize + 1;
+/// Seconds WLED waits after the last packet before resuming its own effect
+const REALTIME_TIMEOUT_SECS: u8 = 2;
+/// DDP flags: protocol version 1
+const DDP_VERSION_1: u8 = 0x40;
+/// DDP flags: show the frame once this packet is in
+const DDP_PUSH: u8 = 0x01;
+/// DDP data type: RGB, 8 bits per channel
+const DDP_TYPE_RGB24: u8 = 0x0B;
+/// DDP destination: the default output device
+const DDP_ID_DISPLAY: u8 = 1;
+/// LEDs per DDP packet; 1440 data bytes keep packets below the Ethernet MTU
+const DDP_LEDS_PER_PACKET: usize = 480;
+
+/// Wire protocol of a pixel controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum WledProtocol {
+    /// WLED realtime, all LEDs in one packet (at most [`DRGB_MAX_LEDS`]).
+    #[default]
+    Drgb,
+    /// WLED realtime with a start index, split over several packets.
+    Dnrgb,
+    /// Distributed Display Protocol.
+    Ddp,
+}
+
+impl WledProtocol {
+    /// Port used when the target doesn't name one
+    pub fn default_port(&self) -> u16 {
+        match self {
+            WledProtocol::Drgb | WledProtocol::Dnrgb => WLED_REALTIME_PORT,
+            WledProtocol::Ddp => DDP_PORT,
+        }
+    }
+}
+
+/// A pixel controller; the first [`MAX_NODES`] LEDs are also nodes, spread
+/// left to right.
+pub struct WledLightSink {
+    socket: UdpSocket,
+    target: SocketAddr,
+    protocol: WledProtocol,
+    nodes: Vec<LightNode>,
+    pixels: Vec<(u8, u8, u8)>,
+    /// DDP sequence number (1-15)
+    sequence: u8,
+}
+
+impl WledLightSink {
+    /// Stream DRGB to the controller at `target` (`host` or `host:port`),
+    /// which drives `led_count` LEDs.
+    pub fn new(target: &str, led_count: usize) -> Result<Self> {
+        Self::with_protocol(target, led_count, WledProtocol::Drgb)
+    }
+
+    /// Stream with `protocol` to the controller at `target`.
+    pub fn with_protocol(target: &str, led_count: usize, protocol: WledProtocol) -> Result<Self> {
+        if protocol == WledProtocol::Drgb && led_count > DRGB_MAX_LEDS {
+            return Err(ControlError::InvalidParameter(format!(
+                "DRGB carries at most {} LEDs, got {}",
+                DRGB_MAX_LEDS, led_count
+            )));
+        }
+        if protocol == WledProtocol::Dnrgb && led_count > DNRGB_MAX_LEDS {
+            return Err(ControlError::InvalidParameter(format!(
+                "DNRGB addresses at most {} LEDs, got {}",
+                DNRGB_MAX_LEDS, led_count
+            )));
+        }
+        let target = resolve(target, protocol.default_port())?;
+        let socket = UdpSocket::bind(if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        })?;
+        Ok(Self {
+            socket,
+            target,
+            protocol,
+            nodes: line_nodes("led", led_count.min(MAX_NODES), 0.0),
+            pixels: vec![(0, 0, 0); led_count],
+            sequence: 0,
+        })
+    }
+
+    /// Number of LEDs on the controller
+    pub fn led_count(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Send a frame starting at the first LED. LEDs past the end of
+    /// `pixels` keep their last color; extra pixels are dropped.
+    pub fn send_pixels(&mut self, pixels: &[(u8, u8, u8)]) -> Result<()> {
+        let len = pixels.len().min(self.pixels.len());
+        self.pixels[..len].copy_from_slice(&pixels[..len]);
+        self.flush()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let packets = match self.protocol {
+            WledProtocol::Drgb => vec![drgb_packet(&self.pixels)],
+            WledProtocol::Dnrgb => dnrgb_packets(&self.pixels),
+            WledProtocol::Ddp => {
+                self.sequence = self.sequence % 15 + 1;
+                ddp_packets(&self.pixels, self.sequence)
+            }
+        };
+        for packet in packets {
+            self.socket.send_to(&packet, self.target)?;
+        }
+        Ok(())
+    }
+}
+
+/// `host` or `host:port` as a socket address, with `default_port` if omitted.
+/// IPv6 addresses take a port only in brackets (`[::1]:4048`).
+pub(crate) fn resolve(target: &str, default_port: u16) -> Result<SocketAddr> {
+    if let Ok(addr) = target.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let bare = target
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(target);
+    if let Ok(ip) = bare.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, default_port));
+    }
+    let with_port = if target.contains(':') {
+        target.to_string()
+    } else {
+        format!("{}:{}", target, default_port)
+    };
+    with_port
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| ControlError::InvalidParameter(format!("Invalid address: {}", target)))
+}
+
+fn push_rgb(packet: &mut Vec<u8>, pixels: &[(u8, u8, u8)]) {
+    for (r, g, b) in pixels {
+        packet.extend_from_slice(&[*r, *g, *b]);
+    }
+}
+
+/// A DRGB packet with one RGB triple per LED
+fn drgb_packet(pixels: &[(u8, u8, u8)]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(2 + pixels.len() * 3);
+    packet.push(DRGB);
+    packet.push(REALTIME_TIMEOUT_SECS);
+    push_rgb(&mut packet, pixels);
+    packet
+}
+
+/// DNRGB packets, each with the index of its first LED (big-endian); LEDs
+/// past the last addressable start index are not sent
+fn dnrgb_packets(pixels: &[(u8, u8, u8)]) -> Vec<Vec<u8>> {
+    pixels
+        .chunks(DNRGB_LEDS_PER_PACKET)
+        .enumerate()
+        .map_while(|(i, chunk)| {
+            let start = u16::try_from(i * DNRGB_LEDS_PER_PACKET).ok()?;
+            let mut packet = Vec::with_capacity(4 + chunk.len() * 3);
+            packet.push(DNRGB);
+            packet.push(REALTIME_TIMEOUT_SECS);
+            packet.extend_from_slice(&start.to_be_bytes());
+            push_rgb(&mut packet, chunk);
+            Some(packet)
+        })
+        .collect()
+}
+
+/// DDP packets: a 10-byte header with the byte offset and length of the data
+/// (big-endian); only the last packet of a frame pushes it to the LEDs.
+fn ddp_packets(pixels: &[(u8, u8, u8)], sequence: u8) -> Vec<Vec<u8>> {
+    let last = pixels.len().saturating_sub(1) / DDP_LEDS_PER_PACKET;
+    pixels
+        .chunks(DDP_LEDS_PER_PACKET)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let flags = if i == last {
+                DDP_VERSION_1 | DDP_PUSH
+            } else {
+                DDP_VERSION_1
+            };
+            let offset = (i * DDP_LEDS_PER_PACKET * 3) as u32;
+            let mut packet = Vec::with_capacity(10 + chunk.len() * 3);
+            packet.extend_from_slice(&[flags, sequence & 0x0F, DDP_TYPE_RGB24, DDP_ID_DISPLAY]);
+            packet.extend_from_slice(&offset.to_be_bytes());
+            packet.extend_from_slice(&((chunk.len() * 3) as u16).to_be_bytes());
+            push_rgb(&mut packet, chunk);
+            packet
+        })
+        .collect()
+}
+
+impl LightSink for WledLightSink {
+    fn nodes(&self) -> Vec<LightNode> {
+        self.nodes.clone()
+    }
+
+    fn send(&mut self, colors: &HashMap<u8, (u8, u8, u8)>) -> Result<()> {
+        for (channel, color) in colors {
+            if let Some(pixel) = self.pixels.get_mut(*channel as usize) {
+                *pixel = *color;
+            }
+        }
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn listener() -> (UdpSocket, String) {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        (listener, target)
+    }
+
+    #[test]
+    fn test_drgb_to_local_listener() {
+        let (listener, target) = listener();
+        let mut sink = WledLightSink::new(&target, 4).unwrap();
+        assert_eq!(sink.nodes().len(), 4);
+        sink.send(&HashMap::from([(1, (10, 20, 30)), (3, (255, 0, 0))]))
+            .unwrap();
+
+        let mut buf = [0u8; 64];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..2], &[DRGB, REALTIME_TIMEOUT_SECS]);
+        assert_eq!(&buf[2..len], &[0, 0, 0, 10, 20, 30, 0, 0, 0, 255, 0, 0]);
+
+        assert!(WledLightSink::new(&target, DRGB_MAX_LEDS + 1).is_err());
+    }
+
+    #[test]
+    fn test_dnrgb_splits_long_strips() {
+        let (listener, target) = listener();
+        let mut sink = WledLightSink::with_protocol(&target, 600, WledProtocol::Dnrgb).unwrap();
+        assert_eq!(sink.nodes().len(), MAX_NODES);
+        let mut frame = vec![(0, 0, 0); 600];
+        frame[489] = (1, 2, 3);
+        sink.send_pixels(&frame).unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..4], &[DNRGB, REALTIME_TIMEOUT_SECS, 0, 0]);
+        assert_eq!(len, 4 + 489 * 3);
+
+        // The second packet starts at LED 489
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[2..4], &489u16.to_be_bytes());
+        assert_eq!(&buf[4..7], &[1, 2, 3]);
+        assert_eq!(len, 4 + 111 * 3);
+    }
+
+    #[test]
+    fn test_resolve_ports() {
+        let v6 = "::1".parse::<IpAddr>().unwrap();
+        assert_eq!(
+            resolve("::1", DDP_PORT).unwrap(),
+            SocketAddr::new(v6, DDP_PORT)
+        );
+        assert_eq!(
+            resolve("[::1]", DDP_PORT).unwrap(),
+            SocketAddr::new(v6, DDP_PORT)
+        );
+        assert_eq!(resolve("[::1]:9000", DDP_PORT).unwrap().port(), 9000);
+        assert_eq!(resolve("127.0.0.1", DDP_PORT).unwrap().port(), DDP_PORT);
+        assert_eq!(resolve("127.0.0.1:9000", DDP_PORT).unwrap().port(), 9000);
+    }
+
+    #[test]
+    fn test_dnrgb_start_index_fits() {
+        assert!(
+            WledLightSink::with_protocol("127.0.0.1", DNRGB_MAX_LEDS + 1, WledProtocol::Dnrgb)
+                .is_err()
+        );
+        let packets = dnrgb_packets(&vec![(0, 0, 0); DNRGB_MAX_LEDS + 1000]);
+        let last = packets.last().unwrap();
+        let start = u16::from_be_bytes([last[2], last[3]]) as usize;
+        assert_eq!(
+            packets.len(),
+            DNRGB_MAX_LEDS.div_ceil(DNRGB_LEDS_PER_PACKET)
+        );
+        assert_eq!(start, (packets.len() - 1) * DNRGB_LEDS_PER_PACKET);
+    }
+
+    #[test]
+    fn test_ddp_offsets_and_push() {
+        let (listener, target) = listener();
+        let mut sink = WledLightSink::with_protocol(&target, 500, WledProtocol::Ddp).unwrap();
+        let mut frame = vec![(0, 0, 0); 500];
+        frame[0] = (9, 8, 7);
+        frame[480] = (4, 5, 6);
+        sink.send_pixels(&frame).unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(
+            &buf[..4],
+            &[DDP_VERSION_1, 1, DDP_TYPE_RGB24, DDP_ID_DISPLAY]
+        );
+        assert_eq!(&buf[4..8], &0u32.to_be_bytes());
+        assert_eq!(&buf[8..10], &1440u16.to_be_bytes());
+        assert_eq!(&buf[10..13], &[9, 8, 7]);
+        assert_eq!(len, 10 + 1440);
+
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(buf[0], DDP_VERSION_1 | DDP_PUSH);
+        assert_eq!(&buf[4..8], &1440u32.to_be_bytes());
+        assert_eq!(&buf[8..10], &60u16.to_be_bytes());
+        assert_eq!(&buf[10..13], &[4, 5, 6]);
+        assert_eq!(len, 10 + 60);
+    }
+}
@@ -6,16 +6,20 @@
 //! Refactored to remove legacy learn modes and use simplified mapping.
 
 use crate::error::{ControlError, Result};
-use crate::shortcuts::{Action, Key, KeyBindings, Modifiers};
+use crate::shortcuts::{
+    Action, Key, KeyBindings, Macro, MacroPlayer, MacroTrigger, Modifiers, PlaybackState,
+};
 use crate::target::{ControlTarget, ControlValue};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tracing::{info, warn};
 
 #[cfg(feature = "midi")]
-use crate::midi::MidiInputHandler;
+use crate::midi::{MidiClock, MidiInputHandler};
 
-use crate::cue::CueList;
+use crate::cue::{CueList, CueStacks};
 use crate::dmx::{ArtNetSender, SacnSender};
+use crate::scheduler::{ScheduleAction, Scheduler};
 
 #[cfg(feature = "osc")]
 use crate::osc::{OscClient, OscMapping, OscServer};
@@ -25,6 +29,9 @@ pub struct ControlManager {
     #[cfg(feature = "midi")]
     /// Handler for processing incoming MIDI messages.
     pub midi_input: Option<MidiInputHandler>,
+    #[cfg(feature = "midi")]
+    /// Tempo and transport of an external MIDI clock.
+    pub midi_clock: MidiClock,
 
     #[cfg(feature = "osc")]
     /// Server for receiving OSC messages from external controllers.
@@ -43,8 +50,21 @@ pub struct ControlManager {
 
     /// Managed list of automated show cues.
     pub cue_list: CueList,
+    /// Additional cue stacks running in parallel to the main cue list.
+    pub cue_stacks: CueStacks,
     /// Map of keyboard shortcuts to application actions.
     pub key_bindings: KeyBindings,
+    /// Calendar-based show scheduler.
+    pub scheduler: Scheduler,
+    /// Scheduled actions that require application handling (modules, outputs, projects)
+    pub scheduled_actions: Vec<ScheduleAction>,
+    /// Players for macros started by shortcuts, MIDI notes, OSC addresses or beats.
+    /// Each running macro has its own player so bound macros play side by side.
+    pub macro_players: Vec<MacroPlayer>,
+    /// Actions from shortcuts and macros that require application handling
+    pub pending_actions: Vec<Action>,
+    /// Tempo controls (tap, BPM, nudge, resync) for the application's tempo service
+    pub tempo_controls: Vec<(ControlTarget, ControlValue)>,
 
     /// Raw MIDI events collected during update (channel, note/cc)
     pub raw_midi_events: Vec<(u8, u8)>,
@@ -64,6 +84,8 @@ impl ControlManager {
             #[cfg(feature = "midi")]
             // Handler for processing incoming MIDI messages.
             midi_input: None,
+            #[cfg(feature = "midi")]
+            midi_clock: MidiClock::new(),
 
             #[cfg(feature = "osc")]
             // Server for receiving OSC messages from external controllers.
@@ -79,7 +101,13 @@ impl ControlManager {
             sacn_sender: None,
 
             cue_list: CueList::new(),
+            cue_stacks: CueStacks::new(),
             key_bindings: KeyBindings::new(),
+            scheduler: Scheduler::new(),
+            scheduled_actions: Vec::new(),
+            macro_players: Vec::new(),
+            pending_actions: Vec::new(),
+            tempo_controls: Vec::new(),
 
             raw_midi_events: Vec::new(),
             raw_osc_events: Vec::new(),
@@ -204,27 +232,76 @@ impl ControlManager {
             osc_events = Vec::new();
         }
 
+        // Run scheduled entries and macros before updating cues so triggered fades start this frame
+        self.process_scheduled_actions();
+        let macro_actions: Vec<Action> = self
+            .macro_players
+            .iter_mut()
+            .flat_map(|player| player.update())
+            .collect();
+        self.macro_players
+            .retain(|player| player.get_state() != PlaybackState::Idle);
+        for action in macro_actions {
+            self.execute_action(action);
+        }
+
         // Update cue system
         self.cue_list.update();
+        self.cue_stacks.update();
 
         (midi_events, osc_events)
     }
 
+    /// Execute due schedule entries
+    fn process_scheduled_actions(&mut self) {
+        for action in self.scheduler.update() {
+            match action {
+                ScheduleAction::TriggerCue { stack: None, cue } => {
+                    if let Err(e) = self.cue_list.goto_cue(cue, None) {
+                        warn!("Scheduled cue failed: {}", e);
+                    }
+                }
+                ScheduleAction::TriggerCue {
+                    stack: Some(stack_id),
+                    cue,
+                } => {
+                    let result = match self.cue_stacks.get_mut(stack_id) {
+                        Some(stack) => stack.list.goto_cue(cue, None),
+                        None => Err(ControlError::TargetNotFound(format!(
+                            "Cue stack {} not found",
+                            stack_id
+                        ))),
+                    };
+                    if let Err(e) = result {
+                        warn!("Scheduled cue failed: {}", e);
+                    }
+                }
+                other => self.scheduled_actions.push(other),
+            }
+        }
+    }
+
     /// Process MIDI messages
     #[cfg(feature = "midi")]
     fn process_midi_messages(&mut self) -> Vec<crate::midi::MidiMessage> {
         // Collect messages to process to avoid borrow checker issues
         let mut controls_to_apply = Vec::new();
         let mut events = Vec::new();
+        let mut triggers = Vec::new();
 
         if let Some(midi_input) = &self.midi_input {
             while let Some(message) = midi_input.poll_message() {
                 events.push(message);
+                self.midi_clock.process_message(message);
 
                 // Record raw event
                 match &message {
                     crate::midi::MidiMessage::NoteOn { channel, note, .. } => {
                         self.raw_midi_events.push((*channel, *note));
+                        triggers.push(MacroTrigger::MidiNote {
+                            channel: *channel,
+                            note: *note,
+                        });
                     }
                     crate::midi::MidiMessage::ControlChange {
                         channel,
@@ -250,6 +327,10 @@ impl ControlManager {
             self.apply_control(target, value);
         }
 
+        for trigger in triggers {
+            self.notify_macro_trigger(&trigger);
+        }
+
         events
     }
 
@@ -258,6 +339,7 @@ impl ControlManager {
     fn process_osc_messages(&mut self) -> Vec<rosc::OscPacket> {
         let mut controls_to_apply = Vec::new();
         let mut events = Vec::new();
+        let mut triggers = Vec::new();
 
         if let Some(osc_server) = &mut self.osc_server {
             while let Some(packet) = osc_server.poll_packet() {
@@ -266,6 +348,7 @@ impl ControlManager {
                 // Record raw event
                 if let rosc::OscPacket::Message(msg) = &packet {
                     self.raw_osc_events.push(msg.addr.clone());
+                    triggers.push(MacroTrigger::Osc(msg.addr.clone()));
                 }
 
                 // Try to map and apply the control
@@ -290,9 +373,46 @@ impl ControlManager {
             self.apply_control(target, value);
         }
 
+        for trigger in triggers {
+            self.notify_macro_trigger(&trigger);
+        }
+
         events
     }
 
+    /// Forward an external trigger to the macro system
+    ///
+    /// Completes a pending wait of the playing macro and starts macros bound
+    /// to the trigger. MIDI notes and OSC addresses are forwarded
+    /// automatically; beats are reported by the application.
+    pub fn notify_macro_trigger(&mut self, trigger: &MacroTrigger) {
+        for player in &mut self.macro_players {
+            player.notify(trigger);
+        }
+
+        let bindings: Vec<_> = self
+            .key_bindings
+            .find_macro_bindings(trigger)
+            .into_iter()
+            .map(|b| (b.macro_name.clone(), b.parameters.clone()))
+            .collect();
+        for (name, parameters) in bindings {
+            if let Some(macro_def) = self.key_bindings.get_macro(&name).cloned() {
+                info!("Starting macro '{}' from {:?}", name, trigger);
+                self.start_macro(macro_def, parameters);
+            }
+        }
+    }
+
+    /// Start a macro, restarting it if it is already playing
+    fn start_macro(&mut self, macro_def: Macro, parameters: HashMap<String, u32>) {
+        self.macro_players
+            .retain(|player| player.current_macro_name() != Some(macro_def.name.as_str()));
+        let mut player = MacroPlayer::new();
+        player.play_macro_with(macro_def, parameters);
+        self.macro_players.push(player);
+    }
+
     /// Validate control value for security issues (e.g. path traversal)
     fn validate_security(&self, target: &ControlTarget, value: &ControlValue) -> Result<()> {
         if let ControlValue::String(s) = value {
@@ -339,6 +459,10 @@ impl ControlManager {
 
         info!("Control change: {:?} = {:?}", target, value);
 
+        if target.is_tempo() {
+            self.tempo_controls.push((target.clone(), value.clone()));
+        }
+
         // Call the control callback if set
         if let Some(callback) = &self.control_callback {
             if let Ok(mut cb) = callback.lock() {
@@ -373,9 +497,19 @@ impl ControlManager {
             Action::GotoCue(id) => {
                 let _ = self.cue_list.goto_cue(id, None);
             }
+            Action::GoCueStack(id) => {
+                let _ = self.cue_stacks.go(id);
+            }
+            Action::ReleaseCueStack(id) => {
+                let _ = self.cue_stacks.release(id);
+            }
+            Action::ExecuteMacro(name) => match self.key_bindings.get_macro(&name) {
+                Some(macro_def) => self.start_macro(macro_def.clone(), HashMap::new()),
+                None => warn!("Macro '{}' not found", name),
+            },
             _ => {
-                // Other actions would be handled by the application
-                info!("Action requires application handling: {:?}", action);
+                // Other actions are handled by the application
+                self.pending_actions.push(action);
             }
         }
     }
@@ -416,6 +550,7 @@ impl ControlManager {
             ControlTarget::LayerVisibility(1),
             ControlTarget::MasterOpacity,
             ControlTarget::MasterBlackout,
+            ControlTarget::TapTempo,
         ]
     }
 
@@ -468,6 +603,22 @@ mod tests {
         assert!(called.load(Ordering::SeqCst));
     }
 
+    #[test]
+    fn test_tempo_controls_are_collected() {
+        let mut manager = ControlManager::new();
+        manager.apply_control(ControlTarget::TapTempo, ControlValue::Bool(true));
+        manager.apply_control(ControlTarget::MasterOpacity, ControlValue::Float(0.5));
+        manager.apply_control(ControlTarget::TempoBpm, ControlValue::Float(128.0));
+
+        assert_eq!(
+            manager.tempo_controls,
+            vec![
+                (ControlTarget::TapTempo, ControlValue::Bool(true)),
+                (ControlTarget::TempoBpm, ControlValue::Float(128.0)),
+            ]
+        );
+    }
+
     #[test]
     fn test_cue_execution() {
         let mut manager = ControlManager::new();
@@ -525,4 +676,64 @@ mod tests {
         );
         assert!(!called.load(std::sync::atomic::Ordering::SeqCst));
     }
+
+    #[test]
+    fn test_macro_execution() {
+        use crate::shortcuts::{MacroAction, MacroBinding};
+        use std::time::Duration;
+
+        let mut manager = ControlManager::new();
+        manager.cue_list.add_cue(
+            crate::cue::Cue::new(1, "Cue 1".to_string())
+                .with_fade_duration(Duration::from_millis(0)),
+        );
+        manager.key_bindings.add_macro(Macro::new(
+            "Show".to_string(),
+            String::new(),
+            vec![
+                MacroAction::new(Action::GotoCue(1), Duration::ZERO),
+                MacroAction::new(Action::SelectLayer(0), Duration::ZERO).with_parameter("layer"),
+            ],
+        ));
+        manager.key_bindings.add_macro_binding(
+            MacroBinding::new(
+                MacroTrigger::Osc("/show/start".to_string()),
+                "Show".to_string(),
+            )
+            .with_parameter("layer", 4),
+        );
+
+        manager.notify_macro_trigger(&MacroTrigger::Osc("/show/start".to_string()));
+        manager.update();
+
+        assert_eq!(manager.cue_list.current_cue(), Some(1));
+        assert_eq!(manager.pending_actions, vec![Action::SelectLayer(4)]);
+    }
+
+    #[test]
+    fn test_all_bound_macros_start() {
+        use crate::shortcuts::{MacroAction, MacroBinding};
+        use std::time::Duration;
+
+        let mut manager = ControlManager::new();
+        for (name, layer) in [("A", 1), ("B", 2)] {
+            manager.key_bindings.add_macro(Macro::new(
+                name.to_string(),
+                String::new(),
+                vec![MacroAction::new(Action::SelectLayer(layer), Duration::ZERO)],
+            ));
+            manager
+                .key_bindings
+                .add_macro_binding(MacroBinding::new(MacroTrigger::Beat, name.to_string()));
+        }
+
+        manager.notify_macro_trigger(&MacroTrigger::Beat);
+        assert_eq!(manager.macro_players.len(), 2);
+        manager.update();
+
+        assert_eq!(
+            manager.pending_actions,
+            vec![Action::SelectLayer(1), Action::SelectLayer(2)]
+        );
+    }
 }
@@ -123,6 +123,14 @@ impl MidiClock {
         self.state
     }
 
+    /// Whether the clock is playing and ticks are still arriving
+    pub fn is_running(&self) -> bool {
+        self.state == ClockState::Playing
+            && self
+                .last_clock_time
+                .is_some_and(|t| t.elapsed() < Duration::from_millis(500))
+    }
+
     /// Get current beat position (0-based)
     pub fn get_beat_position(&self) -> f32 {
         self.clock_count as f32 / Self::TICKS_PER_BEAT as f32
@@ -176,6 +184,7 @@ mod tests {
     fn test_beat_position() {
         let mut clock = MidiClock::new();
         clock.process_message(MidiMessage::Start);
+        assert!(!clock.is_running());
 
         assert_eq!(clock.get_beat_position(), 0.0);
 
@@ -184,6 +193,7 @@ mod tests {
         }
 
         assert_eq!(clock.get_beat_position(), 1.0);
+        assert!(clock.is_running());
     }
 
     #[test]
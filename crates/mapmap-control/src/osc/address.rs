@@ -22,6 +22,10 @@ const MAX_NAME_LENGTH: usize = 256;
 /// - `/mapmap/playback/speed` - Playback speed
 /// - `/mapmap/playback/position` - Playback position
 /// - `/mapmap/output/{id}/brightness` - Output brightness
+/// - `/mapmap/tempo/tap` - Tap tempo
+/// - `/mapmap/tempo/bpm` - Manual tempo (BPM)
+/// - `/mapmap/tempo/nudge` - Tempo nudge (beats)
+/// - `/mapmap/tempo/resync` - Make the current moment the downbeat
 pub fn parse_osc_address(address: &str) -> Result<ControlTarget> {
     if address.len() > MAX_OSC_ADDRESS_LENGTH {
         return Err(ControlError::InvalidMessage(format!(
@@ -53,6 +57,7 @@ pub fn parse_osc_address(address: &str) -> Result<ControlTarget> {
         "effect" => parse_effect_address(&parts[2..]),
         "playback" => parse_playback_address(&parts[2..]),
         "output" => parse_output_address(&parts[2..]),
+        "tempo" => parse_tempo_address(&parts[2..]),
         _ => Err(ControlError::InvalidMessage(format!(
             "Unknown OSC category: {}",
             parts[1]
@@ -176,6 +181,25 @@ fn parse_playback_address(parts: &[&str]) -> Result<ControlTarget> {
     }
 }
 
+fn parse_tempo_address(parts: &[&str]) -> Result<ControlTarget> {
+    if parts.is_empty() {
+        return Err(ControlError::InvalidMessage(
+            "Missing tempo parameter".to_string(),
+        ));
+    }
+
+    match parts[0] {
+        "tap" => Ok(ControlTarget::TapTempo),
+        "bpm" => Ok(ControlTarget::TempoBpm),
+        "nudge" => Ok(ControlTarget::TempoNudge),
+        "resync" => Ok(ControlTarget::TempoResync),
+        _ => Err(ControlError::InvalidMessage(format!(
+            "Unknown tempo parameter: {}",
+            parts[0]
+        ))),
+    }
+}
+
 fn parse_output_address(parts: &[&str]) -> Result<ControlTarget> {
     if parts.is_empty() {
         return Err(ControlError::InvalidMessage(
@@ -224,6 +248,10 @@ pub fn control_target_to_address(target: &ControlTarget) -> String {
         }
         ControlTarget::MasterOpacity => "/mapmap/master/opacity".to_string(),
         ControlTarget::MasterBlackout => "/mapmap/master/blackout".to_string(),
+        ControlTarget::TapTempo => "/mapmap/tempo/tap".to_string(),
+        ControlTarget::TempoBpm => "/mapmap/tempo/bpm".to_string(),
+        ControlTarget::TempoNudge => "/mapmap/tempo/nudge".to_string(),
+        ControlTarget::TempoResync => "/mapmap/tempo/resync".to_string(),
         ControlTarget::Custom(name) => format!("/mapmap/custom/{}", name),
     }
 }
@@ -376,6 +404,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_round_trip_tempo_targets() {
+        let targets = vec![
+            ControlTarget::TapTempo,
+            ControlTarget::TempoBpm,
+            ControlTarget::TempoNudge,
+            ControlTarget::TempoResync,
+        ];
+
+        for target in targets {
+            let address = control_target_to_address(&target);
+            let parsed = parse_osc_address(&address).unwrap();
+            assert_eq!(parsed, target);
+        }
+        assert!(parse_osc_address("/mapmap/tempo").is_err());
+        assert!(parse_osc_address("/mapmap/tempo/unknown").is_err());
+    }
+
     #[test]
     fn test_invalid_category() {
         assert!(parse_osc_address("/mapmap/unknown/test").is_err());
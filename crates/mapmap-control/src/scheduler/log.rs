@@ -0,0 +1,114 @@
+//! Persistent log of executed schedule events
+//!
+//! Events are appended to a JSON-lines file, one event per line, so the log
+//! survives restarts and can be inspected with standard tools.
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use super::schedule::ScheduleAction;
+use crate::Result;
+
+/// A schedule entry that was executed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// Local time at which the entry fired
+    pub timestamp: DateTime<FixedOffset>,
+    pub entry_id: u32,
+    pub entry_name: String,
+    pub action: ScheduleAction,
+}
+
+/// Log of executed schedule events
+#[derive(Debug, Default)]
+pub struct EventLog {
+    path: Option<PathBuf>,
+    events: Vec<LoggedEvent>,
+}
+
+impl EventLog {
+    /// Create an in-memory log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a log file, loading previously recorded events
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut events = Vec::new();
+
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line) {
+                    Ok(event) => events.push(event),
+                    Err(e) => warn!("Skipping malformed schedule log entry: {}", e),
+                }
+            }
+        }
+
+        Ok(Self {
+            path: Some(path),
+            events,
+        })
+    }
+
+    /// Record an event, appending it to the log file if one is open
+    pub fn record(&mut self, event: LoggedEvent) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(&event)?)?;
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// Get all recorded events, oldest first
+    pub fn events(&self) -> &[LoggedEvent] {
+        &self.events
+    }
+
+    /// Get the path of the log file
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_persists_events() {
+        let path = std::env::temp_dir().join(format!(
+            "mapflow_schedule_log_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let timestamp = DateTime::parse_from_rfc3339("2024-06-21T20:00:00+02:00").unwrap();
+
+        let mut log = EventLog::open(&path).unwrap();
+        log.record(LoggedEvent {
+            timestamp,
+            entry_id: 1,
+            entry_name: "Evening show".to_string(),
+            action: ScheduleAction::StartModule(3),
+        })
+        .unwrap();
+
+        let reopened = EventLog::open(&path).unwrap();
+        assert_eq!(reopened.events().len(), 1);
+        assert_eq!(reopened.events()[0].timestamp, timestamp);
+        assert_eq!(reopened.events()[0].action, ScheduleAction::StartModule(3));
+
+        let _ = fs::remove_file(&path);
+    }
+}
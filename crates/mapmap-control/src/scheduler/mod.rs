@@ -0,0 +1,214 @@
+//! Scheduled show playback
+//!
+//! Runs an installation unattended from a calendar:
+//! - Weekly and daily entries at fixed times or relative to sunrise/sunset
+//! - Date exceptions (holidays, special events) that replace or extend the
+//!   regular schedule
+//! - Actions that start/stop modules, trigger cues, blank outputs or load a
+//!   project
+//! - A persistent log of every executed event
+//!
+//! Sunrise and sunset are computed locally from the configured coordinates,
+//! so the scheduler works without network access.
+
+pub mod log;
+pub mod schedule;
+pub mod solar;
+
+pub use log::{EventLog, LoggedEvent};
+pub use schedule::{DateException, Schedule, ScheduleAction, ScheduleEntry, ScheduleTime};
+pub use solar::{GeoLocation, SunEvent};
+
+use chrono::{DateTime, FixedOffset, Local};
+use tracing::{info, warn};
+
+/// Fires schedule entries as wall-clock time passes
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    /// The schedule to run
+    pub schedule: Schedule,
+    /// Log of executed events
+    pub log: EventLog,
+    enabled: bool,
+    last_check: Option<DateTime<FixedOffset>>,
+}
+
+impl Scheduler {
+    /// Create a new, disabled scheduler with an empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a scheduler for the given schedule
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Use the given event log
+    pub fn with_log(mut self, log: EventLog) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// Enable or disable automatic playback
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last_check = None;
+    }
+
+    /// Check if automatic playback is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Fire all entries that became due since the last check, using the local clock
+    pub fn update(&mut self) -> Vec<ScheduleAction> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        self.poll(Local::now().fixed_offset())
+    }
+
+    /// Fire all entries whose time lies in `(last check, now]`
+    ///
+    /// The first call only records the current time, so entries that are
+    /// already in the past when playback starts are not replayed.
+    pub fn poll(&mut self, now: DateTime<FixedOffset>) -> Vec<ScheduleAction> {
+        let Some(last) = self.last_check.replace(now) else {
+            return Vec::new();
+        };
+        if now <= last {
+            return Vec::new();
+        }
+
+        let start = last.naive_local();
+        let end = now.naive_local();
+        let mut due = Vec::new();
+
+        for date in start.date().iter_days().take_while(|d| *d <= end.date()) {
+            for (time, entry) in self.schedule.occurrences_on(date, *now.offset()) {
+                if time > start && time <= end {
+                    due.push((time, entry.id, entry.name.clone(), entry.action.clone()));
+                }
+            }
+        }
+
+        let mut actions = Vec::with_capacity(due.len());
+        for (time, entry_id, entry_name, action) in due {
+            info!("Schedule: '{}' fired at {}", entry_name, time);
+            let timestamp = time
+                .and_local_timezone(*now.offset())
+                .single()
+                .unwrap_or(now);
+            if let Err(e) = self.log.record(LoggedEvent {
+                timestamp,
+                entry_id,
+                entry_name,
+                action: action.clone(),
+            }) {
+                warn!("Failed to write schedule log: {}", e);
+            }
+            actions.push(action);
+        }
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cue::TimeTrigger;
+    use chrono::Weekday;
+
+    fn at(hour: u8, minute: u8) -> ScheduleTime {
+        ScheduleTime::At(TimeTrigger::new(hour, minute, 0).unwrap())
+    }
+
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn scheduler() -> Scheduler {
+        let mut schedule = Schedule::new();
+        schedule.add_entry(ScheduleEntry::new(
+            1,
+            "Open".to_string(),
+            at(9, 0),
+            ScheduleAction::BlankOutputs(false),
+        ));
+        schedule.add_entry(ScheduleEntry::new(
+            2,
+            "Close".to_string(),
+            at(22, 0),
+            ScheduleAction::BlankOutputs(true),
+        ));
+        schedule.add_entry(
+            ScheduleEntry::new(
+                3,
+                "Weekend cue".to_string(),
+                at(21, 0),
+                ScheduleAction::TriggerCue {
+                    stack: None,
+                    cue: 5,
+                },
+            )
+            .on_days(vec![Weekday::Sat]),
+        );
+        Scheduler::new().with_schedule(schedule)
+    }
+
+    #[test]
+    fn test_first_poll_does_not_replay_past_entries() {
+        let mut scheduler = scheduler();
+        assert!(scheduler.poll(time("2024-06-21T12:00:00+02:00")).is_empty());
+        assert!(scheduler.poll(time("2024-06-21T12:00:01+02:00")).is_empty());
+    }
+
+    #[test]
+    fn test_fires_entries_in_window() {
+        let mut scheduler = scheduler();
+        scheduler.poll(time("2024-06-21T08:59:59+02:00"));
+
+        let actions = scheduler.poll(time("2024-06-21T09:00:00+02:00"));
+        assert_eq!(actions, vec![ScheduleAction::BlankOutputs(false)]);
+
+        // Does not fire twice
+        assert!(scheduler.poll(time("2024-06-21T09:00:01+02:00")).is_empty());
+        assert_eq!(scheduler.log.events().len(), 1);
+        assert_eq!(scheduler.log.events()[0].entry_id, 1);
+    }
+
+    #[test]
+    fn test_window_spanning_midnight() {
+        let mut scheduler = scheduler();
+        // Friday evening to Saturday morning
+        scheduler.poll(time("2024-06-21T21:30:00+02:00"));
+        let actions = scheduler.poll(time("2024-06-22T09:30:00+02:00"));
+        assert_eq!(
+            actions,
+            vec![
+                ScheduleAction::BlankOutputs(true),
+                ScheduleAction::BlankOutputs(false),
+            ]
+        );
+
+        // Saturday-only entry
+        let actions = scheduler.poll(time("2024-06-22T21:00:00+02:00"));
+        assert_eq!(
+            actions,
+            vec![ScheduleAction::TriggerCue {
+                stack: None,
+                cue: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn test_update_is_noop_when_disabled() {
+        let mut scheduler = scheduler();
+        assert!(!scheduler.is_enabled());
+        assert!(scheduler.update().is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! Schedule definition: weekly/daily entries and date exceptions
+
+use chrono::{
+    Datelike, Duration as ChronoDuration, FixedOffset, NaiveDate, NaiveDateTime, Weekday,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::solar::{GeoLocation, SunEvent};
+use crate::cue::TimeTrigger;
+use crate::Result;
+
+/// What happens when a schedule entry fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleAction {
+    /// Select and start a module
+    StartModule(u64),
+    /// Stop a module
+    StopModule(u64),
+    /// Go to a cue in the main cue list (`stack: None`) or a cue stack
+    TriggerCue { stack: Option<u32>, cue: u32 },
+    /// Blank (`true`) or unblank (`false`) all outputs
+    BlankOutputs(bool),
+    /// Load a project file
+    LoadProject(PathBuf),
+}
+
+/// Time of day at which an entry fires
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleTime {
+    /// Fixed local time
+    At(TimeTrigger),
+    /// Local sunrise plus an offset in minutes (may be negative)
+    Sunrise { offset_minutes: i32 },
+    /// Local sunset plus an offset in minutes (may be negative)
+    Sunset { offset_minutes: i32 },
+}
+
+impl ScheduleTime {
+    /// Resolve the local time at which this entry fires on `date`
+    ///
+    /// Sun-relative times need a location and return `None` without one, or
+    /// on days where the sun does not rise or set. The sun event is the one
+    /// falling on `date` in local time, before the entry's offset is added.
+    pub fn resolve(
+        &self,
+        date: NaiveDate,
+        location: Option<&GeoLocation>,
+        utc_offset: FixedOffset,
+    ) -> Option<NaiveDateTime> {
+        let (event, offset_minutes) = match *self {
+            ScheduleTime::At(trigger) => {
+                return date.and_hms_opt(
+                    trigger.hour as u32,
+                    trigger.minute as u32,
+                    trigger.second as u32,
+                );
+            }
+            ScheduleTime::Sunrise { offset_minutes } => (SunEvent::Sunrise, offset_minutes),
+            ScheduleTime::Sunset { offset_minutes } => (SunEvent::Sunset, offset_minutes),
+        };
+
+        // The solar date at the location usually matches the local date; time
+        // zones far from the location's longitude may shift it by a day
+        let location = location?;
+        let local = [date, date.pred_opt()?, date.succ_opt()?]
+            .into_iter()
+            .filter_map(|solar_date| location.sun_event_utc(solar_date, event))
+            .map(|utc| utc + ChronoDuration::seconds(utc_offset.local_minus_utc() as i64))
+            .find(|local| local.date() == date)?;
+        Some(local + ChronoDuration::minutes(offset_minutes as i64))
+    }
+}
+
+/// A recurring schedule entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Unique identifier for this entity.
+    pub id: u32,
+    /// Human-readable display name.
+    pub name: String,
+    pub enabled: bool,
+    /// Weekdays on which the entry runs (empty = every day)
+    pub days: Vec<Weekday>,
+    pub time: ScheduleTime,
+    pub action: ScheduleAction,
+}
+
+impl ScheduleEntry {
+    /// Create a new daily entry
+    pub fn new(id: u32, name: String, time: ScheduleTime, action: ScheduleAction) -> Self {
+        Self {
+            id,
+            name,
+            enabled: true,
+            days: Vec::new(),
+            time,
+            action,
+        }
+    }
+
+    /// Restrict the entry to specific weekdays
+    pub fn on_days(mut self, days: Vec<Weekday>) -> Self {
+        self.days = days;
+        self
+    }
+
+    /// Check if the entry runs on the given date
+    pub fn runs_on(&self, date: NaiveDate) -> bool {
+        self.enabled && (self.days.is_empty() || self.days.contains(&date.weekday()))
+    }
+}
+
+/// Deviation from the regular schedule on a specific date
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateException {
+    pub date: NaiveDate,
+    /// Human-readable display name.
+    pub name: String,
+    /// Suppress all regular entries on this date
+    pub skip_regular: bool,
+    /// Additional entries that only run on this date
+    pub entries: Vec<ScheduleEntry>,
+}
+
+impl DateException {
+    /// Create an exception that skips the regular schedule (e.g. a holiday)
+    pub fn closed(date: NaiveDate, name: String) -> Self {
+        Self {
+            date,
+            name,
+            skip_regular: true,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// Complete schedule of an installation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub entries: Vec<ScheduleEntry>,
+    pub exceptions: Vec<DateException>,
+    /// Location used for sunrise/sunset entries
+    pub location: Option<GeoLocation>,
+}
+
+impl Schedule {
+    /// Create an empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a recurring entry
+    pub fn add_entry(&mut self, entry: ScheduleEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Add a date exception
+    pub fn add_exception(&mut self, exception: DateException) {
+        self.exceptions.push(exception);
+    }
+
+    /// Load from JSON file
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let schedule: Self = serde_json::from_str(&json)?;
+        info!(
+            "Loaded schedule with {} entries and {} exceptions",
+            schedule.entries.len(),
+            schedule.exceptions.len()
+        );
+        Ok(schedule)
+    }
+
+    /// Save to JSON file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Get the entries that run on a date, with exceptions applied
+    pub fn entries_for(&self, date: NaiveDate) -> Vec<&ScheduleEntry> {
+        let exceptions: Vec<&DateException> =
+            self.exceptions.iter().filter(|e| e.date == date).collect();
+        let skip_regular = exceptions.iter().any(|e| e.skip_regular);
+
+        let regular = self
+            .entries
+            .iter()
+            .filter(|e| !skip_regular && e.runs_on(date));
+        let extra = exceptions
+            .iter()
+            .flat_map(|e| e.entries.iter())
+            .filter(|e| e.enabled);
+
+        regular.chain(extra).collect()
+    }
+
+    /// Get the entries that fire on a date together with their local times
+    pub fn occurrences_on(
+        &self,
+        date: NaiveDate,
+        utc_offset: FixedOffset,
+    ) -> Vec<(NaiveDateTime, &ScheduleEntry)> {
+        let mut occurrences: Vec<_> = self
+            .entries_for(date)
+            .into_iter()
+            .filter_map(|entry| {
+                entry
+                    .time
+                    .resolve(date, self.location.as_ref(), utc_offset)
+                    .map(|time| (time, entry))
+            })
+            .collect();
+        occurrences.sort_by_key(|(time, _)| *time);
+        occurrences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn at(hour: u8, minute: u8) -> ScheduleTime {
+        ScheduleTime::At(TimeTrigger::new(hour, minute, 0).unwrap())
+    }
+
+    #[test]
+    fn test_weekday_filter() {
+        let entry = ScheduleEntry::new(
+            1,
+            "Weekend show".to_string(),
+            at(20, 0),
+            ScheduleAction::StartModule(1),
+        )
+        .on_days(vec![Weekday::Sat, Weekday::Sun]);
+
+        // 2024-06-22 is a Saturday
+        assert!(entry.runs_on(date(2024, 6, 22)));
+        assert!(!entry.runs_on(date(2024, 6, 24)));
+    }
+
+    #[test]
+    fn test_exception_replaces_regular_entries() {
+        let mut schedule = Schedule::new();
+        schedule.add_entry(ScheduleEntry::new(
+            1,
+            "Daily".to_string(),
+            at(9, 0),
+            ScheduleAction::BlankOutputs(false),
+        ));
+
+        let mut holiday = DateException::closed(date(2024, 12, 25), "Christmas".to_string());
+        holiday.entries.push(ScheduleEntry::new(
+            2,
+            "Special".to_string(),
+            at(18, 0),
+            ScheduleAction::StartModule(7),
+        ));
+        schedule.add_exception(holiday);
+
+        let regular_day = schedule.entries_for(date(2024, 12, 24));
+        assert_eq!(regular_day.len(), 1);
+        assert_eq!(regular_day[0].id, 1);
+
+        let holiday = schedule.entries_for(date(2024, 12, 25));
+        assert_eq!(holiday.len(), 1);
+        assert_eq!(holiday[0].id, 2);
+    }
+
+    #[test]
+    fn test_sunset_offset_in_local_time() {
+        let mut schedule = Schedule::new();
+        schedule.location = Some(GeoLocation::new(52.52, 13.405));
+        schedule.add_entry(ScheduleEntry::new(
+            1,
+            "Lights on".to_string(),
+            ScheduleTime::Sunset {
+                offset_minutes: -30,
+            },
+            ScheduleAction::StartModule(1),
+        ));
+
+        // CEST: UTC+2, sunset 19:33 UTC -> 21:33 local, minus 30 minutes
+        let cest = FixedOffset::east_opt(2 * 3600).unwrap();
+        let occurrences = schedule.occurrences_on(date(2024, 6, 21), cest);
+        assert_eq!(occurrences.len(), 1);
+
+        let expected = date(2024, 6, 21).and_hms_opt(21, 3, 0).unwrap();
+        let diff = (occurrences[0].0 - expected).num_minutes().abs();
+        assert!(diff <= 3);
+    }
+
+    #[test]
+    fn test_sun_times_far_from_greenwich() {
+        let day = date(2024, 6, 21);
+
+        // Los Angeles (PDT, UTC-7): sunset 20:08 local, 03:08 UTC the next day
+        let pdt = FixedOffset::west_opt(7 * 3600).unwrap();
+        let sunset = ScheduleTime::Sunset { offset_minutes: 0 }
+            .resolve(day, Some(&GeoLocation::new(34.05, -118.24)), pdt)
+            .unwrap();
+        let expected = day.and_hms_opt(20, 8, 0).unwrap();
+        assert!((sunset - expected).num_minutes().abs() <= 3);
+
+        // Sydney (AEST, UTC+10): sunrise 07:00 local, 21:00 UTC the day before
+        let aest = FixedOffset::east_opt(10 * 3600).unwrap();
+        let sunrise = ScheduleTime::Sunrise { offset_minutes: 0 }
+            .resolve(day, Some(&GeoLocation::new(-33.87, 151.21)), aest)
+            .unwrap();
+        let expected = day.and_hms_opt(7, 0, 0).unwrap();
+        assert!((sunrise - expected).num_minutes().abs() <= 3);
+    }
+
+    #[test]
+    fn test_sun_time_without_location() {
+        let time = ScheduleTime::Sunrise { offset_minutes: 0 };
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert!(time.resolve(date(2024, 6, 21), None, utc).is_none());
+    }
+}
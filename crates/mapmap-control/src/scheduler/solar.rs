@@ -0,0 +1,150 @@
+//! Local sunrise/sunset calculation
+//!
+//! Uses the NOAA sunrise equation, which is accurate to about a minute for
+//! latitudes below the polar circles. No network access is needed.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Official zenith for sunrise/sunset (includes refraction and sun radius)
+const ZENITH_DEG: f64 = 90.833;
+
+/// Geographic coordinates of the installation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoLocation {
+    /// Latitude in degrees (north positive)
+    pub latitude: f64,
+    /// Longitude in degrees (east positive)
+    pub longitude: f64,
+}
+
+/// Sun event to compute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+impl GeoLocation {
+    /// Create a new location
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Compute the UTC time of a sun event on the given date
+    ///
+    /// The date is taken at the location, so far from Greenwich the UTC time
+    /// may fall on the day before or after (e.g. sunset in the Americas).
+    /// Returns `None` during polar day or polar night.
+    pub fn sun_event_utc(&self, date: NaiveDate, event: SunEvent) -> Option<NaiveDateTime> {
+        let day_of_year = date.ordinal() as f64;
+        let lng_hour = self.longitude / 15.0;
+
+        let approx_hour = match event {
+            SunEvent::Sunrise => 6.0,
+            SunEvent::Sunset => 18.0,
+        };
+        let t = day_of_year + (approx_hour - lng_hour) / 24.0;
+
+        // Sun's mean anomaly and true longitude
+        let mean_anomaly = 0.9856 * t - 3.289;
+        let true_longitude = (mean_anomaly
+            + 1.916 * sin_deg(mean_anomaly)
+            + 0.020 * sin_deg(2.0 * mean_anomaly)
+            + 282.634)
+            .rem_euclid(360.0);
+
+        // Right ascension, moved into the same quadrant as the true longitude
+        let mut right_ascension = atan_deg(0.91764 * tan_deg(true_longitude)).rem_euclid(360.0);
+        right_ascension +=
+            (true_longitude / 90.0).floor() * 90.0 - (right_ascension / 90.0).floor() * 90.0;
+        let right_ascension = right_ascension / 15.0;
+
+        // Declination and local hour angle
+        let sin_dec = 0.39782 * sin_deg(true_longitude);
+        let cos_dec = sin_dec.asin().cos();
+        let cos_hour = (cos_deg(ZENITH_DEG) - sin_dec * sin_deg(self.latitude))
+            / (cos_dec * cos_deg(self.latitude));
+        if !(-1.0..=1.0).contains(&cos_hour) {
+            return None;
+        }
+
+        let hour_angle = match event {
+            SunEvent::Sunrise => 360.0 - cos_hour.acos().to_degrees(),
+            SunEvent::Sunset => cos_hour.acos().to_degrees(),
+        } / 15.0;
+
+        let local_mean_time = (hour_angle + right_ascension - 0.06571 * t - 6.622).rem_euclid(24.0);
+        let utc_hours = local_mean_time - lng_hour;
+
+        let seconds = (utc_hours * 3600.0).round() as i64;
+        Some(date.and_time(NaiveTime::MIN) + Duration::seconds(seconds))
+    }
+}
+
+fn sin_deg(deg: f64) -> f64 {
+    deg.to_radians().sin()
+}
+
+fn cos_deg(deg: f64) -> f64 {
+    deg.to_radians().cos()
+}
+
+fn tan_deg(deg: f64) -> f64 {
+    deg.to_radians().tan()
+}
+
+fn atan_deg(x: f64) -> f64 {
+    x.atan().to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    fn minutes(time: NaiveDateTime) -> i64 {
+        time.hour() as i64 * 60 + time.minute() as i64
+    }
+
+    #[test]
+    fn test_berlin_summer_solstice() {
+        let berlin = GeoLocation::new(52.52, 13.405);
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+
+        // Reference: sunrise 02:43 UTC, sunset 19:33 UTC
+        let sunrise = berlin.sun_event_utc(date, SunEvent::Sunrise).unwrap();
+        let sunset = berlin.sun_event_utc(date, SunEvent::Sunset).unwrap();
+        assert!((minutes(sunrise) - (2 * 60 + 43)).abs() <= 3);
+        assert!((minutes(sunset) - (19 * 60 + 33)).abs() <= 3);
+    }
+
+    #[test]
+    fn test_utc_day_overflow() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+
+        // Los Angeles: sunset 20:08 PDT is 03:08 UTC on the next day
+        let los_angeles = GeoLocation::new(34.05, -118.24);
+        let sunset = los_angeles.sun_event_utc(date, SunEvent::Sunset).unwrap();
+        assert_eq!(sunset.date(), date.succ_opt().unwrap());
+        assert!((minutes(sunset) - (3 * 60 + 8)).abs() <= 3);
+
+        // Sydney: sunrise 07:00 AEST is 21:00 UTC on the day before
+        let sydney = GeoLocation::new(-33.87, 151.21);
+        let sunrise = sydney.sun_event_utc(date, SunEvent::Sunrise).unwrap();
+        assert_eq!(sunrise.date(), date.pred_opt().unwrap());
+        assert!((minutes(sunrise) - 21 * 60).abs() <= 3);
+    }
+
+    #[test]
+    fn test_polar_night_has_no_sunrise() {
+        let longyearbyen = GeoLocation::new(78.22, 15.65);
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert!(longyearbyen
+            .sun_event_utc(date, SunEvent::Sunrise)
+            .is_none());
+    }
+}
@@ -1,6 +1,9 @@
 //! Keyboard shortcut bindings manager
 
-use super::{Action, DefaultShortcuts, Key, Macro, Modifiers, Shortcut, ShortcutContext};
+use super::{
+    Action, DefaultShortcuts, Key, Macro, MacroBinding, MacroTrigger, Modifiers, Shortcut,
+    ShortcutContext,
+};
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +14,7 @@ use tracing::info;
 pub struct KeyBindings {
     shortcuts: Vec<Shortcut>,
     macros: HashMap<String, Macro>,
+    macro_bindings: Vec<MacroBinding>,
     context: ShortcutContext,
 }
 
@@ -19,6 +23,8 @@ pub struct KeyBindings {
 pub struct KeyBindingsData {
     pub shortcuts: Vec<Shortcut>,
     pub macros: HashMap<String, Macro>,
+    #[serde(default)]
+    pub macro_bindings: Vec<MacroBinding>,
 }
 
 impl KeyBindings {
@@ -27,6 +33,7 @@ impl KeyBindings {
         Self {
             shortcuts: DefaultShortcuts::all(),
             macros: HashMap::new(),
+            macro_bindings: Vec::new(),
             context: ShortcutContext::Global,
         }
     }
@@ -36,6 +43,7 @@ impl KeyBindings {
         Self {
             shortcuts: Vec::new(),
             macros: HashMap::new(),
+            macro_bindings: Vec::new(),
             context: ShortcutContext::Global,
         }
     }
@@ -52,27 +60,44 @@ impl KeyBindings {
 
     /// Find action for a key press
     pub fn find_action(&self, key: Key, modifiers: &Modifiers) -> Option<Action> {
-        for shortcut in &self.shortcuts {
-            if !shortcut.enabled {
-                continue;
-            }
-
-            // Check context match
-            let context_match = match shortcut.context {
-                ShortcutContext::Global => true,
-                _ => shortcut.context == self.context,
-            };
-
-            if !context_match {
-                continue;
-            }
-
-            if shortcut.matches(key, modifiers) {
-                return Some(shortcut.action.clone());
-            }
-        }
+        self.find_action_in_context(key, modifiers, self.context)
+    }
+
+    /// Find action for a key press in a specific context
+    ///
+    /// Shortcuts of the given context take precedence over global ones.
+    pub fn find_action_in_context(
+        &self,
+        key: Key,
+        modifiers: &Modifiers,
+        context: ShortcutContext,
+    ) -> Option<Action> {
+        let find = |wanted: ShortcutContext| {
+            self.shortcuts
+                .iter()
+                .find(|s| s.context == wanted && s.matches(key, modifiers))
+                .map(|s| s.action.clone())
+        };
 
-        None
+        find(context).or_else(|| find(ShortcutContext::Global))
+    }
+
+    /// Search the shortcuts available in a context, e.g. for a command palette
+    ///
+    /// Every whitespace-separated term of the query must appear in the
+    /// description or action name (case-insensitive). An empty query returns
+    /// all available shortcuts.
+    pub fn search(&self, query: &str, context: ShortcutContext) -> Vec<&Shortcut> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+        self.shortcuts
+            .iter()
+            .filter(|s| s.enabled && (s.context == context || s.context == ShortcutContext::Global))
+            .filter(|s| {
+                let haystack = format!("{} {:?}", s.description, s.action).to_lowercase();
+                terms.iter().all(|term| haystack.contains(term.as_str()))
+            })
+            .collect()
     }
 
     /// Add a new shortcut
@@ -143,6 +168,35 @@ impl KeyBindings {
         &self.macros
     }
 
+    /// Bind a macro to a MIDI note, OSC address or beat
+    ///
+    /// Keyboard shortcuts start macros via [`Action::ExecuteMacro`].
+    pub fn add_macro_binding(&mut self, binding: MacroBinding) {
+        self.macro_bindings.push(binding);
+    }
+
+    /// Remove a macro binding by index
+    pub fn remove_macro_binding(&mut self, index: usize) -> Option<MacroBinding> {
+        if index < self.macro_bindings.len() {
+            Some(self.macro_bindings.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Get all macro bindings
+    pub fn get_macro_bindings(&self) -> &[MacroBinding] {
+        &self.macro_bindings
+    }
+
+    /// Find the macro bindings that are started by a trigger
+    pub fn find_macro_bindings(&self, trigger: &MacroTrigger) -> Vec<&MacroBinding> {
+        self.macro_bindings
+            .iter()
+            .filter(|b| b.trigger == *trigger && self.macros.contains_key(&b.macro_name))
+            .collect()
+    }
+
     /// Reset to default shortcuts
     pub fn reset_to_defaults(&mut self) {
         self.shortcuts = DefaultShortcuts::all();
@@ -163,6 +217,7 @@ impl KeyBindings {
         Ok(Self {
             shortcuts: data.shortcuts,
             macros: data.macros,
+            macro_bindings: data.macro_bindings,
             context: ShortcutContext::Global,
         })
     }
@@ -172,6 +227,7 @@ impl KeyBindings {
         let data = KeyBindingsData {
             shortcuts: self.shortcuts.clone(),
             macros: self.macros.clone(),
+            macro_bindings: self.macro_bindings.clone(),
         };
 
         let json = serde_json::to_string_pretty(&data)?;
@@ -191,6 +247,7 @@ impl KeyBindings {
         let data = KeyBindingsData {
             shortcuts: self.shortcuts.clone(),
             macros: self.macros.clone(),
+            macro_bindings: self.macro_bindings.clone(),
         };
 
         Ok(serde_json::to_string_pretty(&data)?)
@@ -203,6 +260,7 @@ impl KeyBindings {
         Ok(Self {
             shortcuts: data.shortcuts,
             macros: data.macros,
+            macro_bindings: data.macro_bindings,
             context: ShortcutContext::Global,
         })
     }
@@ -278,12 +336,7 @@ mod tests {
     fn test_macro_management() {
         let mut bindings = KeyBindings::new();
 
-        let macro_def = Macro {
-            name: "Test Macro".to_string(),
-            description: "Test".to_string(),
-            actions: vec![],
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-        };
+        let macro_def = Macro::new("Test Macro".to_string(), "Test".to_string(), vec![]);
 
         bindings.add_macro(macro_def.clone());
         assert!(bindings.get_macro("Test Macro").is_some());
@@ -301,4 +354,50 @@ mod tests {
 
         assert_eq!(bindings.get_shortcuts().len(), loaded.get_shortcuts().len());
     }
+
+    #[test]
+    fn test_find_action_in_context() {
+        let bindings = KeyBindings::new();
+
+        let action = bindings.find_action_in_context(
+            Key::D,
+            &Modifiers::ctrl(),
+            ShortcutContext::ModuleCanvas,
+        );
+        assert_eq!(action, Some(Action::DuplicateSelectedParts));
+
+        // Canvas shortcuts are not active elsewhere, global ones are
+        let action = bindings.find_action_in_context(
+            Key::D,
+            &Modifiers::ctrl(),
+            ShortcutContext::MainWindow,
+        );
+        assert!(action.is_none());
+        let action = bindings.find_action_in_context(
+            Key::Z,
+            &Modifiers::ctrl(),
+            ShortcutContext::ModuleCanvas,
+        );
+        assert_eq!(action, Some(Action::Undo));
+    }
+
+    #[test]
+    fn test_search() {
+        let bindings = KeyBindings::new();
+
+        let results = bindings.search("align left", ShortcutContext::ModuleCanvas);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].action,
+            Action::AlignSelectedParts(crate::shortcuts::PartAlignment::Left)
+        );
+
+        // Case-insensitive, matches action names too
+        assert!(!bindings
+            .search("ADD source", ShortcutContext::ModuleCanvas)
+            .is_empty());
+        assert!(bindings
+            .search("duplicate", ShortcutContext::Timeline)
+            .is_empty());
+    }
 }
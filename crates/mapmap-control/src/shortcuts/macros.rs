@@ -1,8 +1,14 @@
 //! Macro recording and playback system
+//!
+//! Macros replay a list of [`Action`]s with their recorded (or edited)
+//! delays. Steps can additionally wait for an external trigger (OSC, MIDI
+//! note, beat), macros can loop, and named parameters can replace the id
+//! argument of an action so one macro works for any layer, cue or output.
 
 use super::Action;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// A recorded macro
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +18,12 @@ pub struct Macro {
     pub description: String,
     pub actions: Vec<MacroAction>,
     pub created_at: String,
+    /// How often the macro is repeated
+    #[serde(default)]
+    pub loop_mode: MacroLoop,
+    /// Parameters that can be passed when the macro is started
+    #[serde(default)]
+    pub parameters: Vec<MacroParameter>,
 }
 
 /// A single action within a macro
@@ -20,6 +32,95 @@ pub struct MacroAction {
     pub action: Action,
     /// Delay before executing this action (relative to previous action)
     pub delay: Duration,
+    /// Trigger to wait for after the delay, before executing the action
+    #[serde(default)]
+    pub wait_for: Option<MacroTrigger>,
+    /// Macro parameter that replaces the id argument of the action
+    #[serde(default)]
+    pub parameter: Option<String>,
+}
+
+impl MacroAction {
+    /// Create a new macro step
+    pub fn new(action: Action, delay: Duration) -> Self {
+        Self {
+            action,
+            delay,
+            wait_for: None,
+            parameter: None,
+        }
+    }
+
+    /// Wait for a trigger before executing the action
+    pub fn waiting_for(mut self, trigger: MacroTrigger) -> Self {
+        self.wait_for = Some(trigger);
+        self
+    }
+
+    /// Take the id argument of the action from a macro parameter
+    pub fn with_parameter(mut self, name: impl Into<String>) -> Self {
+        self.parameter = Some(name.into());
+        self
+    }
+}
+
+/// External event that a macro can wait for or be bound to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MacroTrigger {
+    /// OSC message with the given address
+    Osc(String),
+    /// MIDI note-on on the given channel
+    MidiNote { channel: u8, note: u8 },
+    /// Beat of the shared tempo clock
+    Beat,
+}
+
+/// Repetition of a macro
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MacroLoop {
+    /// Play once
+    #[default]
+    Once,
+    /// Play the given number of times
+    Count(u32),
+    /// Repeat until stopped
+    Forever,
+}
+
+/// A named macro parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacroParameter {
+    /// Human-readable display name.
+    pub name: String,
+    /// Value used when the parameter is not passed
+    pub default: u32,
+}
+
+/// Starts a macro when a trigger is received
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroBinding {
+    pub trigger: MacroTrigger,
+    pub macro_name: String,
+    /// Parameter values passed to the macro
+    #[serde(default)]
+    pub parameters: HashMap<String, u32>,
+}
+
+impl MacroBinding {
+    /// Create a new binding without parameters
+    pub fn new(trigger: MacroTrigger, macro_name: String) -> Self {
+        Self {
+            trigger,
+            macro_name,
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// Pass a parameter value to the macro
+    pub fn with_parameter(mut self, name: impl Into<String>, value: u32) -> Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
 }
 
 /// Macro recorder state
@@ -74,12 +175,7 @@ impl MacroRecorder {
             return None;
         }
 
-        let macro_def = Macro {
-            name,
-            description,
-            actions: self.recorded_actions.clone(),
-            created_at: chrono::Utc::now().to_rfc3339(),
-        };
+        let macro_def = Macro::new(name, description, self.recorded_actions.clone());
 
         self.recorded_actions.clear();
         self.last_action_time = None;
@@ -122,7 +218,7 @@ impl MacroRecorder {
             Duration::ZERO
         };
 
-        self.recorded_actions.push(MacroAction { action, delay });
+        self.recorded_actions.push(MacroAction::new(action, delay));
         self.last_action_time = Some(now);
     }
 
@@ -147,8 +243,13 @@ impl Default for MacroRecorder {
 pub struct MacroPlayer {
     state: PlaybackState,
     current_macro: Option<Macro>,
+    parameters: HashMap<String, u32>,
     current_action_index: usize,
-    action_start_time: Option<std::time::Instant>,
+    loops_completed: u32,
+    action_start_time: Option<Instant>,
+    paused_elapsed: Duration,
+    waiting: bool,
+    trigger_received: bool,
 }
 
 impl MacroPlayer {
@@ -157,69 +258,157 @@ impl MacroPlayer {
         Self {
             state: PlaybackState::Idle,
             current_macro: None,
+            parameters: HashMap::new(),
             current_action_index: 0,
+            loops_completed: 0,
             action_start_time: None,
+            paused_elapsed: Duration::ZERO,
+            waiting: false,
+            trigger_received: false,
         }
     }
 
     /// Start playing a macro
     pub fn play_macro(&mut self, macro_def: Macro) {
+        self.play_macro_with(macro_def, HashMap::new());
+    }
+
+    /// Start playing a macro with parameter values
+    ///
+    /// Parameters that are not passed use their default value.
+    pub fn play_macro_with(&mut self, macro_def: Macro, mut parameters: HashMap<String, u32>) {
+        for param in &macro_def.parameters {
+            parameters
+                .entry(param.name.clone())
+                .or_insert(param.default);
+        }
+
         self.current_macro = Some(macro_def);
+        self.parameters = parameters;
         self.current_action_index = 0;
+        self.loops_completed = 0;
         self.state = PlaybackState::Playing;
-        self.action_start_time = Some(std::time::Instant::now());
+        self.action_start_time = Some(Instant::now());
+        self.waiting = false;
+        self.trigger_received = false;
     }
 
     /// Stop playback
     pub fn stop(&mut self) {
         self.state = PlaybackState::Idle;
         self.current_macro = None;
+        self.parameters.clear();
         self.current_action_index = 0;
+        self.loops_completed = 0;
         self.action_start_time = None;
+        self.waiting = false;
+        self.trigger_received = false;
     }
 
     /// Pause playback
     pub fn pause(&mut self) {
         if self.state == PlaybackState::Playing {
             self.state = PlaybackState::Paused;
+            self.paused_elapsed = self
+                .action_start_time
+                .map(|start| start.elapsed())
+                .unwrap_or_default();
         }
     }
 
-    /// Resume playback
+    /// Resume playback, keeping the time already waited for the current step
     pub fn resume(&mut self) {
         if self.state == PlaybackState::Paused {
             self.state = PlaybackState::Playing;
-            self.action_start_time = Some(std::time::Instant::now());
+            self.action_start_time = Instant::now().checked_sub(self.paused_elapsed);
         }
     }
 
-    /// Update playback and return next action to execute
-    pub fn update(&mut self) -> Option<Action> {
-        if self.state != PlaybackState::Playing {
-            return None;
+    /// Notify the player of an external trigger
+    ///
+    /// Only completes a wait that is already pending; triggers that arrive
+    /// before the step's delay has elapsed are ignored.
+    pub fn notify(&mut self, trigger: &MacroTrigger) {
+        if !self.waiting || self.state != PlaybackState::Playing {
+            return;
         }
+        let expected = self
+            .current_macro
+            .as_ref()
+            .and_then(|m| m.actions.get(self.current_action_index))
+            .and_then(|step| step.wait_for.as_ref());
+        if expected == Some(trigger) {
+            self.trigger_received = true;
+        }
+    }
 
-        let macro_def = self.current_macro.as_ref()?;
+    /// Update playback and return the actions to execute now
+    pub fn update(&mut self) -> Vec<Action> {
+        self.update_at(Instant::now())
+    }
 
-        if self.current_action_index >= macro_def.actions.len() {
-            // Playback complete
-            self.stop();
-            return None;
-        }
+    /// Update playback at the given time and return the actions to execute
+    pub fn update_at(&mut self, now: Instant) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        while self.state == PlaybackState::Playing {
+            let Some(macro_def) = self.current_macro.as_ref() else {
+                break;
+            };
+            // At most one pass per update, so zero-delay loops cannot spin
+            if actions.len() >= macro_def.actions.len() {
+                break;
+            }
+
+            if self.current_action_index >= macro_def.actions.len() {
+                if self.next_loop() {
+                    continue;
+                }
+                // Playback complete
+                self.stop();
+                break;
+            }
+
+            let step = &macro_def.actions[self.current_action_index];
+            let start = *self.action_start_time.get_or_insert(now);
+            let due = start + step.delay;
+            if now < due {
+                break;
+            }
+
+            if step.wait_for.is_some() && !self.trigger_received {
+                self.waiting = true;
+                break;
+            }
 
-        let action = &macro_def.actions[self.current_action_index];
-        let start_time = self.action_start_time?;
-        let elapsed = std::time::Instant::now().duration_since(start_time);
+            actions.push(macro_def.resolve_action(step, &self.parameters));
 
-        if elapsed >= action.delay {
-            // Time to execute this action
-            let result = action.action.clone();
+            // Waiting steps restart timing from the trigger, others keep the
+            // recorded rhythm even if the update rate is coarse
+            self.action_start_time = Some(if self.waiting { now } else { due });
             self.current_action_index += 1;
-            self.action_start_time = Some(std::time::Instant::now());
-            Some(result)
-        } else {
-            None
+            self.waiting = false;
+            self.trigger_received = false;
+        }
+
+        actions
+    }
+
+    /// Advance to the next loop iteration, if the loop mode allows it
+    fn next_loop(&mut self) -> bool {
+        let Some(macro_def) = &self.current_macro else {
+            return false;
+        };
+        let repeat = match macro_def.loop_mode {
+            MacroLoop::Once => false,
+            MacroLoop::Count(count) => self.loops_completed + 1 < count,
+            MacroLoop::Forever => !macro_def.actions.is_empty(),
+        };
+        if repeat {
+            self.loops_completed += 1;
+            self.current_action_index = 0;
         }
+        repeat
     }
 
     /// Get current playback state
@@ -227,6 +416,21 @@ impl MacroPlayer {
         self.state
     }
 
+    /// Get the name of the macro being played
+    pub fn current_macro_name(&self) -> Option<&str> {
+        self.current_macro.as_ref().map(|m| m.name.as_str())
+    }
+
+    /// Check if playback is waiting for a trigger
+    pub fn is_waiting(&self) -> bool {
+        self.waiting
+    }
+
+    /// Get the number of completed loop iterations
+    pub fn loops_completed(&self) -> u32 {
+        self.loops_completed
+    }
+
     /// Get current playback progress (0.0-1.0)
     pub fn get_progress(&self) -> f32 {
         if let Some(macro_def) = &self.current_macro {
@@ -248,6 +452,61 @@ impl Default for MacroPlayer {
 }
 
 impl Macro {
+    /// Create a new macro that plays once
+    pub fn new(name: String, description: String, actions: Vec<MacroAction>) -> Self {
+        Self {
+            name,
+            description,
+            actions,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            loop_mode: MacroLoop::Once,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Set the loop mode
+    pub fn with_loop(mut self, loop_mode: MacroLoop) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
+    /// Declare a parameter with its default value
+    pub fn with_parameter(mut self, name: impl Into<String>, default: u32) -> Self {
+        self.parameters.push(MacroParameter {
+            name: name.into(),
+            default,
+        });
+        self
+    }
+
+    /// Edit the delay of a step
+    pub fn set_delay(&mut self, index: usize, delay: Duration) -> bool {
+        match self.actions.get_mut(index) {
+            Some(step) => {
+                step.delay = delay;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scale all delays, e.g. 0.5 to play twice as fast
+    pub fn scale_timing(&mut self, factor: f32) {
+        let factor = factor.max(0.0) as f64;
+        for step in &mut self.actions {
+            let nanos = (step.delay.as_nanos() as f64 * factor).round();
+            step.delay = Duration::from_nanos(nanos as u64);
+        }
+    }
+
+    /// Get the action of a step with its parameter applied
+    pub fn resolve_action(&self, step: &MacroAction, values: &HashMap<String, u32>) -> Action {
+        match step.parameter.as_ref().and_then(|name| values.get(name)) {
+            Some(id) => step.action.with_id(*id),
+            None => step.action.clone(),
+        }
+    }
+
     /// Load from JSON
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
@@ -264,27 +523,6 @@ impl Macro {
     }
 }
 
-// For compatibility with systems that don't have chrono
-mod chrono {
-    use std::time::SystemTime;
-
-    pub struct Utc;
-
-    impl Utc {
-        pub fn now() -> DateTime {
-            DateTime(SystemTime::now())
-        }
-    }
-
-    pub struct DateTime(SystemTime);
-
-    impl DateTime {
-        pub fn to_rfc3339(&self) -> String {
-            format!("{:?}", self.0)
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,21 +549,14 @@ mod tests {
 
     #[test]
     fn test_macro_playback() {
-        let macro_def = Macro {
-            name: "Test".to_string(),
-            description: "Test".to_string(),
-            actions: vec![
-                MacroAction {
-                    action: Action::Play,
-                    delay: Duration::ZERO,
-                },
-                MacroAction {
-                    action: Action::Stop,
-                    delay: Duration::from_millis(10),
-                },
+        let macro_def = Macro::new(
+            "Test".to_string(),
+            "Test".to_string(),
+            vec![
+                MacroAction::new(Action::Play, Duration::ZERO),
+                MacroAction::new(Action::Stop, Duration::from_millis(10)),
             ],
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-        };
+        );
 
         let mut player = MacroPlayer::new();
         player.play_macro(macro_def);
@@ -333,21 +564,17 @@ mod tests {
         assert_eq!(player.get_state(), PlaybackState::Playing);
 
         // First action should execute immediately
-        let action = player.update();
-        assert!(matches!(action, Some(Action::Play)));
+        let actions = player.update();
+        assert!(matches!(actions.as_slice(), [Action::Play]));
     }
 
     #[test]
     fn test_macro_serialization() {
-        let macro_def = Macro {
-            name: "Test".to_string(),
-            description: "Test macro".to_string(),
-            actions: vec![MacroAction {
-                action: Action::Play,
-                delay: Duration::ZERO,
-            }],
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-        };
+        let macro_def = Macro::new(
+            "Test".to_string(),
+            "Test macro".to_string(),
+            vec![MacroAction::new(Action::Play, Duration::ZERO)],
+        );
 
         let json = macro_def.to_json().unwrap();
         let loaded = Macro::from_json(&json).unwrap();
@@ -355,4 +582,138 @@ mod tests {
         assert_eq!(macro_def.name, loaded.name);
         assert_eq!(macro_def.actions.len(), loaded.actions.len());
     }
+
+    fn macro_of(actions: Vec<MacroAction>) -> Macro {
+        Macro::new("Test".to_string(), String::new(), actions)
+    }
+
+    #[test]
+    fn test_playback_follows_delays() {
+        let mut player = MacroPlayer::new();
+        player.play_macro(macro_of(vec![
+            MacroAction::new(Action::Play, Duration::ZERO),
+            MacroAction::new(Action::NextCue, Duration::from_millis(100)),
+            MacroAction::new(Action::Stop, Duration::from_millis(100)),
+        ]));
+
+        let start = Instant::now();
+        assert_eq!(player.update_at(start), vec![Action::Play]);
+        assert!(player
+            .update_at(start + Duration::from_millis(50))
+            .is_empty());
+
+        // A late update catches up without losing the recorded rhythm
+        assert_eq!(
+            player.update_at(start + Duration::from_millis(250)),
+            vec![Action::NextCue, Action::Stop]
+        );
+        player.update_at(start + Duration::from_millis(260));
+        assert_eq!(player.get_state(), PlaybackState::Idle);
+    }
+
+    #[test]
+    fn test_wait_for_trigger() {
+        let trigger = MacroTrigger::MidiNote {
+            channel: 0,
+            note: 60,
+        };
+        let mut player = MacroPlayer::new();
+        player.play_macro(macro_of(vec![MacroAction::new(
+            Action::Play,
+            Duration::ZERO,
+        )
+        .waiting_for(trigger.clone())]));
+
+        let start = Instant::now();
+        assert!(player.update_at(start).is_empty());
+        assert!(player.is_waiting());
+
+        player.notify(&MacroTrigger::Osc("/other".to_string()));
+        assert!(player.update_at(start).is_empty());
+
+        player.notify(&trigger);
+        assert_eq!(player.update_at(start), vec![Action::Play]);
+        assert!(!player.is_waiting());
+    }
+
+    #[test]
+    fn test_loop_count() {
+        let mut player = MacroPlayer::new();
+        player.play_macro(
+            macro_of(vec![MacroAction::new(
+                Action::NextCue,
+                Duration::from_millis(10),
+            )])
+            .with_loop(MacroLoop::Count(3)),
+        );
+
+        let start = Instant::now();
+        let mut executed = 0;
+        for step in 1..=10 {
+            executed += player
+                .update_at(start + Duration::from_millis(10 * step))
+                .len();
+        }
+        assert_eq!(executed, 3);
+        assert_eq!(player.get_state(), PlaybackState::Idle);
+    }
+
+    #[test]
+    fn test_forever_loop_with_zero_delay_does_not_spin() {
+        let mut player = MacroPlayer::new();
+        player.play_macro(
+            macro_of(vec![MacroAction::new(Action::NextCue, Duration::ZERO)])
+                .with_loop(MacroLoop::Forever),
+        );
+
+        let now = Instant::now();
+        assert_eq!(player.update_at(now).len(), 1);
+        assert_eq!(player.update_at(now).len(), 1);
+        assert_eq!(player.get_state(), PlaybackState::Playing);
+    }
+
+    #[test]
+    fn test_parameters() {
+        let macro_def = macro_of(vec![MacroAction::new(
+            Action::ToggleLayerVisibility(0),
+            Duration::ZERO,
+        )
+        .with_parameter("layer")])
+        .with_parameter("layer", 1);
+
+        let mut player = MacroPlayer::new();
+        player.play_macro(macro_def.clone());
+        assert_eq!(player.update(), vec![Action::ToggleLayerVisibility(1)]);
+
+        let mut params = HashMap::new();
+        params.insert("layer".to_string(), 7);
+        player.play_macro_with(macro_def, params);
+        assert_eq!(player.update(), vec![Action::ToggleLayerVisibility(7)]);
+    }
+
+    #[test]
+    fn test_edit_timing() {
+        let mut macro_def = macro_of(vec![
+            MacroAction::new(Action::Play, Duration::from_millis(100)),
+            MacroAction::new(Action::Stop, Duration::from_millis(300)),
+        ]);
+        assert!(macro_def.set_delay(0, Duration::from_millis(200)));
+        assert!(!macro_def.set_delay(5, Duration::ZERO));
+
+        macro_def.scale_timing(0.5);
+        assert_eq!(macro_def.total_duration(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_legacy_macro_json() {
+        let json = r#"{
+            "name": "Old",
+            "description": "",
+            "actions": [{"action": "Play", "delay": {"secs": 0, "nanos": 0}}],
+            "created_at": "2024-01-01T00:00:00Z"
+        }"#;
+        let macro_def = Macro::from_json(json).unwrap();
+        assert_eq!(macro_def.loop_mode, MacroLoop::Once);
+        assert!(macro_def.actions[0].wait_for.is_none());
+    }
 }
@@ -165,6 +165,8 @@ pub enum Action {
     PrevCue,
     GotoCue(u32),
     RecordCue,
+    GoCueStack(u32),
+    ReleaseCueStack(u32),
 
     // Layer control
     ToggleLayerVisibility(u32),
@@ -205,6 +207,20 @@ pub enum Action {
     ShowPreferences,
     ShowHelp,
 
+    // Module canvas
+    /// Add a part by type name (e.g. "Source", "Layer")
+    AddModulePart(String),
+    /// Connect the selected parts in selection order
+    ConnectSelectedParts,
+    DuplicateSelectedParts,
+    /// Group the selected parts, or ungroup them if they already share a group
+    GroupSelectedParts,
+    AlignSelectedParts(PartAlignment),
+    DistributeSelectedParts(DistributeAxis),
+    JumpToModule(u64),
+    TogglePartBypass,
+    ShowCommandPalette,
+
     // Macros
     ExecuteMacro(String),
 
@@ -212,6 +228,52 @@ pub enum Action {
     Custom(String),
 }
 
+/// Edge that selected parts are aligned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PartAlignment {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Axis along which selected parts are spaced evenly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DistributeAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// Part types that can be added from a shortcut, in default binding order (Shift+1..8)
+pub const MODULE_PART_TYPES: &[&str] = &[
+    "Trigger",
+    "Source",
+    "Mask",
+    "Modulator",
+    "Mesh",
+    "Layer",
+    "Hue",
+    "Output",
+];
+
+impl Action {
+    /// Replace the id argument of an action that targets a cue, stack, layer or output
+    ///
+    /// Actions without an id argument are returned unchanged.
+    pub fn with_id(&self, id: u32) -> Action {
+        match self {
+            Action::GotoCue(_) => Action::GotoCue(id),
+            Action::GoCueStack(_) => Action::GoCueStack(id),
+            Action::ReleaseCueStack(_) => Action::ReleaseCueStack(id),
+            Action::ToggleLayerVisibility(_) => Action::ToggleLayerVisibility(id),
+            Action::SelectLayer(_) => Action::SelectLayer(id),
+            Action::ToggleOutput(_) => Action::ToggleOutput(id),
+            Action::JumpToModule(_) => Action::JumpToModule(id as u64),
+            other => other.clone(),
+        }
+    }
+}
+
 /// Shortcut context - where the shortcut is active
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ShortcutContext {
@@ -227,6 +289,8 @@ pub enum ShortcutContext {
     Timeline,
     /// Only when layer panel is focused
     LayerPanel,
+    /// Only when the module canvas is focused
+    ModuleCanvas,
 }
 
 /// A keyboard shortcut definition
@@ -295,7 +359,7 @@ pub struct DefaultShortcuts;
 
 impl DefaultShortcuts {
     pub fn all() -> Vec<Shortcut> {
-        vec![
+        let mut shortcuts = vec![
             // Playback
             Shortcut::new(
                 Key::Space,
@@ -471,7 +535,118 @@ impl DefaultShortcuts {
                 ShortcutContext::Global,
                 "Quit application".to_string(),
             ),
-        ]
+        ];
+        shortcuts.extend(Self::module_canvas());
+        shortcuts
+    }
+
+    /// Shortcuts that are only active in the module canvas
+    pub fn module_canvas() -> Vec<Shortcut> {
+        let number_keys = [
+            Key::Key1,
+            Key::Key2,
+            Key::Key3,
+            Key::Key4,
+            Key::Key5,
+            Key::Key6,
+            Key::Key7,
+            Key::Key8,
+        ];
+        let mut shortcuts: Vec<Shortcut> = MODULE_PART_TYPES
+            .iter()
+            .zip(number_keys)
+            .map(|(part_type, key)| {
+                Shortcut::new(
+                    key,
+                    Modifiers::shift(),
+                    Action::AddModulePart(part_type.to_string()),
+                    ShortcutContext::ModuleCanvas,
+                    format!("Add {} part", part_type),
+                )
+            })
+            .collect();
+
+        shortcuts.extend([
+            Shortcut::new(
+                Key::L,
+                Modifiers::ctrl(),
+                Action::ConnectSelectedParts,
+                ShortcutContext::ModuleCanvas,
+                "Connect selected parts".to_string(),
+            ),
+            Shortcut::new(
+                Key::D,
+                Modifiers::ctrl(),
+                Action::DuplicateSelectedParts,
+                ShortcutContext::ModuleCanvas,
+                "Duplicate selected parts".to_string(),
+            ),
+            Shortcut::new(
+                Key::G,
+                Modifiers::ctrl(),
+                Action::GroupSelectedParts,
+                ShortcutContext::ModuleCanvas,
+                "Group/ungroup selected parts".to_string(),
+            ),
+            Shortcut::new(
+                Key::ArrowLeft,
+                Modifiers::alt(),
+                Action::AlignSelectedParts(PartAlignment::Left),
+                ShortcutContext::ModuleCanvas,
+                "Align selected parts left".to_string(),
+            ),
+            Shortcut::new(
+                Key::ArrowRight,
+                Modifiers::alt(),
+                Action::AlignSelectedParts(PartAlignment::Right),
+                ShortcutContext::ModuleCanvas,
+                "Align selected parts right".to_string(),
+            ),
+            Shortcut::new(
+                Key::ArrowUp,
+                Modifiers::alt(),
+                Action::AlignSelectedParts(PartAlignment::Top),
+                ShortcutContext::ModuleCanvas,
+                "Align selected parts top".to_string(),
+            ),
+            Shortcut::new(
+                Key::ArrowDown,
+                Modifiers::alt(),
+                Action::AlignSelectedParts(PartAlignment::Bottom),
+                ShortcutContext::ModuleCanvas,
+                "Align selected parts bottom".to_string(),
+            ),
+            Shortcut::new(
+                Key::H,
+                Modifiers::alt(),
+                Action::DistributeSelectedParts(DistributeAxis::Horizontal),
+                ShortcutContext::ModuleCanvas,
+                "Distribute selected parts horizontally".to_string(),
+            ),
+            Shortcut::new(
+                Key::V,
+                Modifiers::alt(),
+                Action::DistributeSelectedParts(DistributeAxis::Vertical),
+                ShortcutContext::ModuleCanvas,
+                "Distribute selected parts vertically".to_string(),
+            ),
+            Shortcut::new(
+                Key::B,
+                Modifiers::new(),
+                Action::TogglePartBypass,
+                ShortcutContext::ModuleCanvas,
+                "Toggle bypass on selected parts".to_string(),
+            ),
+            Shortcut::new(
+                Key::P,
+                Modifiers::ctrl(),
+                Action::ShowCommandPalette,
+                ShortcutContext::ModuleCanvas,
+                "Show command palette".to_string(),
+            ),
+        ]);
+
+        shortcuts
     }
 }
 
@@ -526,4 +701,24 @@ mod tests {
         assert!(str_repr.contains("Shift"));
         assert!(str_repr.contains("S"));
     }
+
+    #[test]
+    fn test_module_canvas_shortcuts_do_not_shadow_global() {
+        let all = DefaultShortcuts::all();
+        for canvas in all
+            .iter()
+            .filter(|s| s.context == ShortcutContext::ModuleCanvas)
+        {
+            let clash = all.iter().any(|s| {
+                s.context == ShortcutContext::Global
+                    && s.key == canvas.key
+                    && s.modifiers == canvas.modifiers
+            });
+            assert!(
+                !clash,
+                "{} clashes with a global shortcut",
+                canvas.description
+            );
+        }
+    }
 }
@@ -34,6 +34,14 @@ pub enum ControlTarget {
     MasterOpacity,
     /// Master blackout
     MasterBlackout,
+    /// Tap tempo (any non-zero value is a tap)
+    TapTempo,
+    /// Manual tempo (BPM)
+    TempoBpm,
+    /// Move the beats earlier (positive) or later (negative) by the value in beats
+    TempoNudge,
+    /// Make the current moment the downbeat (any non-zero value)
+    TempoResync,
     /// Custom parameter (name)
     Custom(String),
 }
@@ -56,6 +64,10 @@ impl ControlTarget {
             ControlTarget::OutputEdgeBlend(id, _) => format!("Output {} Edge Blend", id),
             ControlTarget::MasterOpacity => "Master Opacity".to_string(),
             ControlTarget::MasterBlackout => "Master Blackout".to_string(),
+            ControlTarget::TapTempo => "Tap Tempo".to_string(),
+            ControlTarget::TempoBpm => "Tempo BPM".to_string(),
+            ControlTarget::TempoNudge => "Tempo Nudge".to_string(),
+            ControlTarget::TempoResync => "Tempo Resync".to_string(),
             ControlTarget::Custom(name) => name.clone(),
         }
     }
@@ -80,10 +92,25 @@ impl ControlTarget {
             ControlTarget::OutputEdgeBlend(id, edge) => format!("output/{}/blend/{:?}", id, edge),
             ControlTarget::MasterOpacity => "master/opacity".to_string(),
             ControlTarget::MasterBlackout => "master/blackout".to_string(),
+            ControlTarget::TapTempo => "tempo/tap".to_string(),
+            ControlTarget::TempoBpm => "tempo/bpm".to_string(),
+            ControlTarget::TempoNudge => "tempo/nudge".to_string(),
+            ControlTarget::TempoResync => "tempo/resync".to_string(),
             ControlTarget::Custom(name) => format!("custom/{}", name),
         }
     }
 
+    /// Whether the target addresses the global tempo clock
+    pub fn is_tempo(&self) -> bool {
+        matches!(
+            self,
+            ControlTarget::TapTempo
+                | ControlTarget::TempoBpm
+                | ControlTarget::TempoNudge
+                | ControlTarget::TempoResync
+        )
+    }
+
     /// Validate the target (e.g. check string lengths)
     pub fn validate(&self) -> Result<(), String> {
         const MAX_NAME_LEN: usize = 256;
@@ -349,6 +376,7 @@ mod tests {
             ControlTarget::MasterOpacity.to_id_string(),
             "master/opacity"
         );
+        assert_eq!(ControlTarget::TapTempo.to_id_string(), "tempo/tap");
         assert_eq!(
             ControlTarget::Custom("my_param".into()).to_id_string(),
             "custom/my_param"
@@ -3,6 +3,7 @@
 //! This module provides a working audio analyzer using rustfft directly,
 //! with proper sample buffering and FFT processing.
 
+use super::features::{FeatureExtractor, MusicalKey};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use num_complex::Complex;
 use rustfft::{Fft, FftPlanner};
@@ -10,6 +11,19 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 use tracing::{debug, trace};
 
+/// Frequency ranges (Hz) of the 9 analysis bands, SubBass to Air
+pub const BAND_RANGES: [(f32, f32); 9] = [
+    (20.0, 60.0),       // SubBass
+    (60.0, 250.0),      // Bass
+    (250.0, 500.0),     // LowMid
+    (500.0, 1000.0),    // Mid
+    (1000.0, 2000.0),   // HighMid
+    (2000.0, 4000.0),   // UpperMid
+    (4000.0, 6000.0),   // Presence
+    (6000.0, 12000.0),  // Brilliance
+    (12000.0, 20000.0), // Air
+];
+
 /// Audio analysis results from V2 analyzer
 #[derive(Debug, Clone)]
 pub struct AudioAnalysisV2 {
@@ -31,6 +45,24 @@ pub struct AudioAnalysisV2 {
     pub waveform: Arc<Vec<f32>>,
     /// Estimated tempo in BPM (None if not enough data)
     pub tempo_bpm: Option<f32>,
+    /// Spectral-flux onset detected this frame
+    pub onset_detected: bool,
+    /// Onset strength (0.0 - 1.0)
+    pub onset_strength: f32,
+    /// Spectral-flux onset per frequency band this frame
+    pub band_onsets: [bool; 9],
+    /// Spectral centroid in Hz ("brightness")
+    pub spectral_centroid: f32,
+    /// Spectral flatness (0.0 = tonal, 1.0 = noise)
+    pub spectral_flatness: f32,
+    /// Frequency in Hz below which 85% of the energy lies
+    pub spectral_rolloff: f32,
+    /// Energy per pitch class C to B (0.0 - 1.0)
+    pub chroma: [f32; 12],
+    /// Estimated musical key (None until enough tonal audio was heard)
+    pub key: Option<MusicalKey>,
+    /// Confidence of the key estimate (0.0 - 1.0)
+    pub key_confidence: f32,
 }
 
 impl Default for AudioAnalysisV2 {
@@ -45,6 +77,15 @@ impl Default for AudioAnalysisV2 {
             beat_strength: 0.0,
             waveform: Arc::new(Vec::new()),
             tempo_bpm: None,
+            onset_detected: false,
+            onset_strength: 0.0,
+            band_onsets: [false; 9],
+            spectral_centroid: 0.0,
+            spectral_flatness: 0.0,
+            spectral_rolloff: 0.0,
+            chroma: [0.0; 12],
+            key: None,
+            key_confidence: 0.0,
         }
     }
 }
@@ -159,6 +200,31 @@ pub struct AudioAnalyzerV2 {
 
     /// Minimum time between beats (prevents double-triggers) - ~200ms = 300 BPM max
     min_beat_interval: f64,
+
+    // === Spectral Features ===
+    /// Onset, timbre and pitch feature extraction
+    features: FeatureExtractor,
+
+    /// Onsets of all FFT frames since the last analysis
+    frame_onset: bool,
+
+    /// Strongest onset since the last analysis
+    frame_onset_strength: f32,
+
+    /// Band onsets of all FFT frames since the last analysis
+    frame_band_onsets: [bool; 9],
+
+    /// Smoothed spectral centroid (Hz)
+    spectral_centroid: f32,
+
+    /// Smoothed spectral flatness
+    spectral_flatness: f32,
+
+    /// Smoothed spectral rolloff (Hz)
+    spectral_rolloff: f32,
+
+    /// Smoothed chroma vector
+    chroma: [f32; 12],
 }
 
 impl AudioAnalyzerV2 {
@@ -220,6 +286,15 @@ impl AudioAnalyzerV2 {
             estimated_bpm: None,
             time_since_last_beat: 1.0, // Start ready for beat
             min_beat_interval: 0.2,    // 300 BPM max
+            // Spectral Features
+            features: FeatureExtractor::new(),
+            frame_onset: false,
+            frame_onset_strength: 0.0,
+            frame_band_onsets: [false; 9],
+            spectral_centroid: 0.0,
+            spectral_flatness: 0.0,
+            spectral_rolloff: 0.0,
+            chroma: [0.0; 12],
         }
     }
 
@@ -269,6 +344,9 @@ impl AudioAnalyzerV2 {
         }
 
         // 3. Add samples to ring buffer and perform FFT when ready
+        self.frame_onset = false;
+        self.frame_onset_strength = 0.0;
+        self.frame_band_onsets = [false; 9];
         for &sample in samples {
             self.input_buffer[self.buffer_write_pos] = sample;
             self.buffer_write_pos = (self.buffer_write_pos + 1) % self.config.fft_size;
@@ -287,6 +365,10 @@ impl AudioAnalyzerV2 {
         let (beat_detected, beat_strength) = self.detect_beat(self.current_time);
 
         // 5. Create analysis result
+        let (key, key_confidence) = match self.features.key() {
+            Some((key, confidence)) => (Some(key), confidence),
+            None => (None, 0.0),
+        };
         let analysis = AudioAnalysisV2 {
             timestamp: self.current_time,
             rms_volume: self.smoothed_rms,
@@ -297,6 +379,15 @@ impl AudioAnalyzerV2 {
             beat_strength,
             waveform: Arc::new(self.waveform_buffer.clone()),
             tempo_bpm: self.estimated_bpm,
+            onset_detected: self.frame_onset,
+            onset_strength: self.frame_onset_strength,
+            band_onsets: self.frame_band_onsets,
+            spectral_centroid: self.spectral_centroid,
+            spectral_flatness: self.spectral_flatness,
+            spectral_rolloff: self.spectral_rolloff,
+            chroma: self.chroma,
+            key,
+            key_confidence,
         };
 
         // Store and send
@@ -336,6 +427,9 @@ impl AudioAnalyzerV2 {
         // Update band energies
         self.update_band_energies();
 
+        // Onsets use the raw spectrum; smoothing would blur the transients
+        self.update_spectral_features();
+
         // Trace log every 100 FFTs
         if self.fft_count % 100 == 0 {
             trace!(
@@ -359,20 +453,7 @@ impl AudioAnalyzerV2 {
     fn update_band_energies(&mut self) {
         let bin_width = self.config.sample_rate as f32 / self.config.fft_size as f32;
 
-        // 9 frequency bands covering 20Hz - 20kHz
-        let band_ranges: [(f32, f32); 9] = [
-            (20.0, 60.0),       // SubBass
-            (60.0, 250.0),      // Bass
-            (250.0, 500.0),     // LowMid
-            (500.0, 1000.0),    // Mid
-            (1000.0, 2000.0),   // HighMid
-            (2000.0, 4000.0),   // UpperMid
-            (4000.0, 6000.0),   // Presence
-            (6000.0, 12000.0),  // Brilliance
-            (12000.0, 20000.0), // Air
-        ];
-
-        for (i, (min_freq, max_freq)) in band_ranges.iter().enumerate() {
+        for (i, (min_freq, max_freq)) in BAND_RANGES.iter().enumerate() {
             let min_bin = (*min_freq / bin_width) as usize;
             let max_bin = ((*max_freq / bin_width) as usize)
                 .min(self.smoothed_magnitudes.len().saturating_sub(1));
@@ -389,6 +470,26 @@ impl AudioAnalyzerV2 {
         }
     }
 
+    /// Extract onsets, spectral shape and chroma from the latest FFT frame
+    fn update_spectral_features(&mut self) {
+        let bin_width = self.config.sample_rate as f32 / self.config.fft_size as f32;
+        let frame = self.features.process(&self.magnitude_buffer, bin_width);
+
+        self.frame_onset |= frame.onset;
+        self.frame_onset_strength = self.frame_onset_strength.max(frame.onset_strength);
+        for (acc, onset) in self.frame_band_onsets.iter_mut().zip(frame.band_onsets) {
+            *acc |= onset;
+        }
+
+        let s = self.config.smoothing;
+        self.spectral_centroid = self.spectral_centroid * s + frame.centroid * (1.0 - s);
+        self.spectral_flatness = self.spectral_flatness * s + frame.flatness * (1.0 - s);
+        self.spectral_rolloff = self.spectral_rolloff * s + frame.rolloff * (1.0 - s);
+        for (acc, value) in self.chroma.iter_mut().zip(frame.chroma) {
+            *acc = *acc * s + value * (1.0 - s);
+        }
+    }
+
     /// Simple beat detection based on energy spike
     fn detect_beat(&mut self, timestamp: f64) -> (bool, f32) {
         // Use bass band (60-250Hz) for beat detection
@@ -535,6 +636,14 @@ impl AudioAnalyzerV2 {
         self.latest_analysis = AudioAnalysisV2::default();
         self.total_samples = 0;
         self.fft_count = 0;
+        self.features.reset();
+        self.frame_onset = false;
+        self.frame_onset_strength = 0.0;
+        self.frame_band_onsets = [false; 9];
+        self.spectral_centroid = 0.0;
+        self.spectral_flatness = 0.0;
+        self.spectral_rolloff = 0.0;
+        self.chroma = [0.0; 12];
 
         debug!("AudioAnalyzerV2 reset");
     }
@@ -666,6 +775,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_spectral_flux_onsets() {
+        let config = AudioAnalyzerV2Config {
+            fft_size: 1024,
+            sample_rate: 44100,
+            ..Default::default()
+        };
+        let mut analyzer = AudioAnalyzerV2::new(config);
+        let sample_rate = 44100.0;
+
+        // A steady A3 tone: onsets only while it starts up
+        let tone = |i: usize| (2.0 * std::f32::consts::PI * 220.0 * i as f32 / sample_rate).sin();
+        let mut steady_onsets = 0;
+        for block in 0..40 {
+            let samples: Vec<f32> = (block * 512..(block + 1) * 512)
+                .map(|i| tone(i) * 0.5)
+                .collect();
+            analyzer.process_samples(&samples, block as f64 * 0.0116);
+            if block >= 10 && analyzer.get_latest_analysis().onset_detected {
+                steady_onsets += 1;
+            }
+        }
+        assert_eq!(steady_onsets, 0, "a steady tone has no onsets");
+        let analysis = analyzer.get_latest_analysis();
+        assert!((analysis.spectral_centroid - 220.0).abs() < 100.0);
+        assert_eq!(
+            analysis.chroma[9],
+            analysis.chroma.iter().cloned().fold(0.0, f32::max)
+        );
+
+        // A noise burst on top of the tone is an onset in the high bands
+        let mut seed = 1u32;
+        let burst: Vec<f32> = (40 * 512..42 * 512)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                tone(i) * 0.5 + ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.5
+            })
+            .collect();
+        analyzer.process_samples(&burst, 40.0 * 0.0116);
+        let analysis = analyzer.get_latest_analysis();
+        assert!(analysis.onset_detected);
+        assert!(
+            analysis.band_onsets[7],
+            "brilliance band should see the burst"
+        );
+    }
+
     #[test]
     fn test_reset() {
         let config = AudioAnalyzerV2Config::default();
@@ -34,6 +34,9 @@ pub enum AudioError {
     /// Error: Device initialization timed out.
     /// Error: Device initialization timed out.
     Timeout,
+    /// An audio file could not be read or decoded
+    #[error("Failed to decode audio file: {0}")]
+    Decode(String),
 }
 
 /// Audio backend abstraction
@@ -42,8 +45,16 @@ pub trait AudioBackend {
     fn start(&mut self) -> Result<(), AudioError>;
     /// Stop capturing audio
     fn stop(&mut self);
-    /// Get the latest audio samples
+    /// Get the latest audio samples, interleaved if there are several channels
     fn get_samples(&mut self) -> Vec<f32>;
+    /// Number of interleaved channels in the samples from `get_samples`
+    fn channels(&self) -> u16 {
+        1
+    }
+    /// Follow an external playback position in seconds
+    ///
+    /// Only meaningful for file-based backends; live inputs ignore it.
+    fn sync_position(&mut self, _seconds: f64) {}
 }
 
 /// CPAL implementation of the audio backend
@@ -64,6 +75,7 @@ pub mod cpal_backend {
         command_sender: Sender<Command>,
         #[allow(dead_code)]
         stream: cpal::Stream,
+        channels: u16,
     }
 
     impl CpalBackend {
@@ -74,7 +86,7 @@ pub mod cpal_backend {
             let (command_tx, command_rx) = unbounded::<Command>();
 
             // Build stream directly in main thread (cpal::Stream is not Send)
-            let stream = Self::build_stream(device_name, sample_tx)?;
+            let (stream, channels) = Self::build_stream(device_name, sample_tx)?;
 
             // Spawn command processing thread
             std::thread::Builder::new()
@@ -89,14 +101,17 @@ pub mod cpal_backend {
                 sample_receiver: sample_rx,
                 command_sender: command_tx,
                 stream,
+                channels,
             })
         }
 
         /// Build the audio stream (must be called from main thread)
+        ///
+        /// Returns the stream and its channel count.
         fn build_stream(
             device_name: Option<String>,
             sample_tx: Sender<Vec<f32>>,
-        ) -> Result<cpal::Stream, AudioError> {
+        ) -> Result<(cpal::Stream, u16), AudioError> {
             let host = cpal::default_host();
 
             // Get device
@@ -155,6 +170,7 @@ pub mod cpal_backend {
             );
 
             let err_fn = |err| tracing::error!("Audio stream error: {}", err);
+            let channels = config.channels();
 
             // Build stream
             let stream = match config.sample_format() {
@@ -270,7 +286,7 @@ pub mod cpal_backend {
                             e
                         )));
                     }
-                    Ok(stream)
+                    Ok((stream, channels))
                 }
                 Err(e) => Err(AudioError::StreamBuildError(e.to_string())),
             }
@@ -327,6 +343,10 @@ pub mod cpal_backend {
         fn get_samples(&mut self) -> Vec<f32> {
             self.sample_receiver.try_iter().flatten().collect()
         }
+
+        fn channels(&self) -> u16 {
+            self.channels
+        }
     }
 
     impl Drop for CpalBackend {
@@ -0,0 +1,464 @@
+//! Beat Grid
+//!
+//! A phase-locked musical grid (beat within the bar, bar count, phrase) on top
+//! of a tempo. The grid keeps running between beats, so beat-divided triggers
+//! stay on time while audio beats, taps or clock ticks only nudge its phase.
+
+use serde::{Deserialize, Serialize};
+
+/// Slowest tempo the grid accepts
+pub(crate) const MIN_BPM: f32 = 40.0;
+/// Fastest tempo the grid accepts
+pub(crate) const MAX_BPM: f32 = 300.0;
+/// Audio beats further off the grid than this (in beats) are syncopation, not drift
+const MAX_PHASE_ERROR: f64 = 0.25;
+/// Per-beat decay of the accent accumulated for each beat of the bar
+const ACCENT_DECAY: f32 = 0.9;
+/// The most accented beat must be this much stronger than beat 1 to become the downbeat
+const DOWNBEAT_MARGIN: f32 = 1.5;
+/// Audio beats heard before the downbeat may move automatically
+const MIN_DOWNBEAT_BEATS: u32 = 8;
+/// Last-fired position that makes the boundary at position 0 fire
+const REARMED: f64 = -1e-9;
+/// Slot of the single beat after the six divisions
+const BEAT_SLOT: usize = 6;
+
+/// Musical length between two beat-grid triggers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BeatDivision {
+    /// A quarter bar (one beat in 4/4)
+    QuarterBar,
+    /// Half a bar
+    HalfBar,
+    /// One bar
+    Bar,
+    /// Two bars
+    TwoBars,
+    /// Four bars
+    FourBars,
+    /// One phrase (`BeatGridConfig::phrase_bars` bars)
+    Phrase,
+}
+
+impl BeatDivision {
+    /// All divisions, shortest first
+    pub fn all() -> [BeatDivision; 6] {
+        [
+            BeatDivision::QuarterBar,
+            BeatDivision::HalfBar,
+            BeatDivision::Bar,
+            BeatDivision::TwoBars,
+            BeatDivision::FourBars,
+            BeatDivision::Phrase,
+        ]
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            BeatDivision::QuarterBar => "1/4 Bar",
+            BeatDivision::HalfBar => "1/2 Bar",
+            BeatDivision::Bar => "1 Bar",
+            BeatDivision::TwoBars => "2 Bars",
+            BeatDivision::FourBars => "4 Bars",
+            BeatDivision::Phrase => "Phrase",
+        }
+    }
+
+    /// Length in beats for the given grid layout
+    pub fn beats(&self, config: &BeatGridConfig) -> f64 {
+        let bar = config.beats_per_bar.max(1) as f64;
+        match self {
+            BeatDivision::QuarterBar => bar / 4.0,
+            BeatDivision::HalfBar => bar / 2.0,
+            BeatDivision::Bar => bar,
+            BeatDivision::TwoBars => bar * 2.0,
+            BeatDivision::FourBars => bar * 4.0,
+            BeatDivision::Phrase => bar * config.phrase_bars.max(1) as f64,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            BeatDivision::QuarterBar => 0,
+            BeatDivision::HalfBar => 1,
+            BeatDivision::Bar => 2,
+            BeatDivision::TwoBars => 3,
+            BeatDivision::FourBars => 4,
+            BeatDivision::Phrase => 5,
+        }
+    }
+}
+
+/// Layout and locking behavior of the beat grid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeatGridConfig {
+    /// Beats per bar (time signature numerator)
+    pub beats_per_bar: u32,
+    /// Bars per phrase, typically 8, 16 or 32
+    pub phrase_bars: u32,
+    /// How far each audio beat pulls the grid phase towards it (0.0 - 1.0)
+    pub phase_correction: f32,
+    /// Move the downbeat to the most accented beat of the bar
+    pub auto_downbeat: bool,
+}
+
+impl Default for BeatGridConfig {
+    fn default() -> Self {
+        Self {
+            beats_per_bar: 4,
+            phrase_bars: 16,
+            phase_correction: 0.3,
+            auto_downbeat: true,
+        }
+    }
+}
+
+/// Snapshot of the grid after the latest update
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BeatGridState {
+    /// Tempo driving the grid in BPM (0.0 while stopped)
+    pub bpm: f32,
+    /// Whether the grid has a tempo and is advancing
+    pub running: bool,
+    /// Beats since the grid started
+    pub position: f64,
+    /// Beat within the bar (0 = downbeat)
+    pub beat: u32,
+    /// Bars since the grid started
+    pub bar: u64,
+    /// Bar within the phrase (0-based)
+    pub bar_in_phrase: u32,
+    /// Phrases since the grid started
+    pub phrase: u64,
+    /// Progress through the current beat (0.0 - 1.0)
+    pub beat_phase: f32,
+    /// Progress through the current bar (0.0 - 1.0)
+    pub bar_phase: f32,
+    /// Progress through the current phrase (0.0 - 1.0)
+    pub phrase_phase: f32,
+    /// Division boundaries (and the beat) crossed during the latest update
+    fired: [bool; 7],
+}
+
+impl BeatGridState {
+    /// Whether a boundary of `division` was crossed during the latest update
+    pub fn fired(&self, division: BeatDivision) -> bool {
+        self.fired[division.index()]
+    }
+
+    /// Whether a beat started during the latest update, whatever the bar length
+    pub fn beat(&self) -> bool {
+        self.fired[BEAT_SLOT]
+    }
+}
+
+/// Phase-locked beat, bar and phrase tracker
+#[derive(Debug, Clone)]
+pub struct BeatGrid {
+    /// Grid layout and locking behavior
+    pub config: BeatGridConfig,
+    bpm: Option<f32>,
+    /// Beats since start; may briefly go negative after the downbeat moves
+    position: f64,
+    /// Position at which each division and the beat last fired
+    last_fired: [f64; 7],
+    /// Accumulated audio beat strength per beat of the bar
+    accents: Vec<f32>,
+    audio_beats: u32,
+    state: BeatGridState,
+}
+
+impl Default for BeatGrid {
+    fn default() -> Self {
+        Self::new(BeatGridConfig::default())
+    }
+}
+
+impl BeatGrid {
+    /// Create a stopped grid
+    pub fn new(config: BeatGridConfig) -> Self {
+        Self {
+            config,
+            bpm: None,
+            position: 0.0,
+            last_fired: [REARMED; 7],
+            accents: Vec::new(),
+            audio_beats: 0,
+            state: BeatGridState::default(),
+        }
+    }
+
+    /// State after the latest `advance`
+    pub fn state(&self) -> &BeatGridState {
+        &self.state
+    }
+
+    /// Current tempo, `None` while stopped
+    pub fn bpm(&self) -> Option<f32> {
+        self.bpm
+    }
+
+    /// Set the tempo; the grid keeps its phase
+    pub fn set_tempo(&mut self, bpm: f32) {
+        if bpm.is_finite() && bpm > 0.0 {
+            self.bpm = Some(bpm.clamp(MIN_BPM, MAX_BPM));
+        }
+    }
+
+    /// Stop the grid; it keeps its position until a tempo is set again
+    pub fn stop(&mut self) {
+        self.bpm = None;
+    }
+
+    /// Restart at the downbeat of bar 1, firing all divisions on the next update
+    pub fn restart(&mut self) {
+        self.position = 0.0;
+        self.last_fired = [REARMED; 7];
+        self.accents.clear();
+        self.audio_beats = 0;
+    }
+
+    /// Advance the grid by `dt` seconds and record the boundaries crossed
+    pub fn advance(&mut self, dt: f32) -> &BeatGridState {
+        if let Some(bpm) = self.bpm {
+            self.position += dt.max(0.0) as f64 * bpm as f64 / 60.0;
+        }
+
+        let running = self.bpm.is_some();
+        let lengths = BeatDivision::all().map(|division| division.beats(&self.config));
+        for (i, length) in lengths.into_iter().chain([1.0]).enumerate() {
+            let crossed = (self.position / length).floor() > (self.last_fired[i] / length).floor();
+            self.state.fired[i] = running && crossed;
+            if self.state.fired[i] {
+                self.last_fired[i] = self.position;
+            }
+        }
+
+        let beats_per_bar = self.config.beats_per_bar.max(1) as f64;
+        let phrase_bars = self.config.phrase_bars.max(1) as u64;
+        let position = self.position.max(0.0);
+        self.state.bpm = self.bpm.unwrap_or(0.0);
+        self.state.running = running;
+        self.state.position = position;
+        self.state.beat = (position.floor() % beats_per_bar) as u32;
+        self.state.bar = (position / beats_per_bar).floor() as u64;
+        self.state.bar_in_phrase = (self.state.bar % phrase_bars) as u32;
+        self.state.phrase = self.state.bar / phrase_bars;
+        self.state.beat_phase = position.fract() as f32;
+        self.state.bar_phase = (position / beats_per_bar).fract() as f32;
+        self.state.phrase_phase = (position / (beats_per_bar * phrase_bars as f64)).fract() as f32;
+        &self.state
+    }
+
+    /// Move the nearest beat to the current moment
+    pub fn align(&mut self) {
+        self.position = self.position.round();
+    }
+
+    /// Move the beats `beats` earlier (positive) or later (negative) in time
+    pub fn nudge(&mut self, beats: f64) {
+        self.position += beats;
+    }
+
+    /// Pull the grid towards a beat detected in the audio
+    ///
+    /// `strength` (0.0 - 1.0) also accumulates per beat of the bar so that
+    /// the most accented beat can become the downbeat.
+    pub fn audio_beat(&mut self, strength: f32) {
+        if self.bpm.is_none() {
+            return;
+        }
+        let nearest = self.position.round();
+        let error = nearest - self.position;
+        if error.abs() > MAX_PHASE_ERROR {
+            return;
+        }
+        self.position += error * self.config.phase_correction.clamp(0.0, 1.0) as f64;
+
+        let beats_per_bar = self.config.beats_per_bar.max(1) as usize;
+        if self.accents.len() != beats_per_bar {
+            self.accents = vec![0.0; beats_per_bar];
+        }
+        for accent in &mut self.accents {
+            *accent *= ACCENT_DECAY;
+        }
+        let slot = (nearest as i64).rem_euclid(beats_per_bar as i64) as usize;
+        self.accents[slot] += strength.max(0.0);
+        self.audio_beats = self.audio_beats.saturating_add(1);
+
+        if !self.config.auto_downbeat || self.audio_beats < MIN_DOWNBEAT_BEATS {
+            return;
+        }
+        let (strongest, accent) =
+            self.accents
+                .iter()
+                .copied()
+                .enumerate()
+                .fold(
+                    (0, 0.0),
+                    |best, (i, a)| if a > best.1 { (i, a) } else { best },
+                );
+        if strongest != 0 && accent > self.accents[0] * DOWNBEAT_MARGIN {
+            let shift = if strongest * 2 <= beats_per_bar {
+                -(strongest as f64)
+            } else {
+                (beats_per_bar - strongest) as f64
+            };
+            self.shift(shift);
+            self.accents.rotate_left(strongest);
+            self.audio_beats = 0;
+        }
+    }
+
+    /// Make the nearest beat the downbeat of its bar
+    pub fn downbeat(&mut self) {
+        let beats_per_bar = self.config.beats_per_bar.max(1) as f64;
+        let nearest = self.position.round();
+        let bar_start = (nearest / beats_per_bar).round() * beats_per_bar;
+        self.shift(bar_start - nearest);
+        self.accents.clear();
+        self.audio_beats = 0;
+    }
+
+    /// Follow an external clock (e.g. MIDI clock) at `beat_position` beats since its start
+    pub fn sync_clock(&mut self, beat_position: f64, bpm: f32) {
+        self.set_tempo(bpm);
+        if (beat_position - self.position).abs() > 1.0 {
+            // Transport jump (start or song position): fire from the new position
+            self.last_fired = [beat_position + REARMED; 7];
+        }
+        self.position = beat_position;
+    }
+
+    /// Renumber the beats without moving them in time
+    fn shift(&mut self, beats: f64) {
+        self.position += beats;
+        for last in &mut self.last_fired {
+            *last += beats;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Advance in 10ms steps and collect the positions at which `division` fired
+    fn run(grid: &mut BeatGrid, seconds: f32, division: BeatDivision) -> Vec<f64> {
+        let mut fired = Vec::new();
+        for _ in 0..(seconds * 100.0).round() as usize {
+            let state = grid.advance(0.01);
+            if state.fired(division) {
+                fired.push(state.position);
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn test_stopped_grid_never_fires() {
+        let mut grid = BeatGrid::default();
+        assert!(run(&mut grid, 2.0, BeatDivision::QuarterBar).is_empty());
+        assert!(!grid.state().running);
+    }
+
+    #[test]
+    fn test_divisions_and_bar_position() {
+        let mut grid = BeatGrid::new(BeatGridConfig {
+            phrase_bars: 8,
+            ..Default::default()
+        });
+        grid.set_tempo(120.0);
+
+        // Just under 16 seconds at 120 BPM = 32 beats = 8 bars = one phrase
+        let beats = run(&mut grid.clone(), 15.9, BeatDivision::QuarterBar);
+        assert_eq!(beats.len(), 32);
+        let bars = run(&mut grid.clone(), 15.9, BeatDivision::Bar);
+        assert_eq!(bars.len(), 8);
+        let four_bars = run(&mut grid.clone(), 15.9, BeatDivision::FourBars);
+        assert_eq!(four_bars.len(), 2);
+        assert_eq!(run(&mut grid, 16.25, BeatDivision::Phrase).len(), 2);
+
+        let state = grid.state();
+        assert_eq!(state.bar, 8);
+        assert_eq!(state.bar_in_phrase, 0);
+        assert_eq!(state.phrase, 1);
+        assert_eq!(state.beat, 0);
+        assert!((state.beat_phase - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_beats_in_three_four() {
+        let mut grid = BeatGrid::new(BeatGridConfig {
+            beats_per_bar: 3,
+            ..Default::default()
+        });
+        grid.set_tempo(120.0);
+        let beats = (0..290).filter(|_| grid.advance(0.01).beat()).count();
+        assert_eq!(beats, 6);
+        assert_eq!(run(&mut grid, 2.9, BeatDivision::QuarterBar).len(), 8);
+    }
+
+    #[test]
+    fn test_align_and_nudge() {
+        let mut grid = BeatGrid::default();
+        grid.set_tempo(120.0);
+        run(&mut grid, 1.1, BeatDivision::QuarterBar);
+
+        grid.align();
+        assert!(grid.advance(0.0).beat_phase < 0.01);
+        grid.nudge(0.5);
+        let state = *grid.advance(0.0);
+        assert_eq!(state.beat, 2);
+        assert!((state.beat_phase - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_audio_beats_correct_phase_and_downbeat() {
+        let mut grid = BeatGrid::default();
+        grid.set_tempo(120.0);
+        // Start the grid 0.1 beats ahead of the music
+        grid.advance(0.05);
+
+        // Music accenting the third beat of our bars, ending on an accent
+        for beat in 0..22 {
+            run(&mut grid, 0.5, BeatDivision::QuarterBar);
+            grid.audio_beat(if beat % 4 == 1 { 1.0 } else { 0.3 });
+        }
+
+        let state = *grid.advance(0.0);
+        let drift = state.beat_phase.min(1.0 - state.beat_phase);
+        assert!(
+            drift < 0.02,
+            "phase should lock to the audio, off by {drift}"
+        );
+        assert_eq!(state.beat, 0, "the accented beat became the downbeat");
+    }
+
+    #[test]
+    fn test_downbeat_fires_bar_without_refiring_beat() {
+        let mut grid = BeatGrid::default();
+        grid.set_tempo(120.0);
+        run(&mut grid, 1.1, BeatDivision::QuarterBar); // just past beat 2
+
+        grid.downbeat();
+        let state = *grid.advance(0.01);
+        assert!(state.fired(BeatDivision::Bar));
+        assert!(!state.fired(BeatDivision::QuarterBar));
+        assert_eq!(state.beat, 0);
+    }
+
+    #[test]
+    fn test_clock_sync() {
+        let mut grid = BeatGrid::default();
+        grid.sync_clock(0.0, 128.0);
+        assert!(grid.advance(0.0).fired(BeatDivision::Phrase));
+
+        grid.sync_clock(9.5, 128.0);
+        let state = *grid.advance(0.0);
+        assert_eq!(state.bar, 2);
+        assert_eq!(state.beat, 1);
+        assert_eq!(state.bpm, 128.0);
+    }
+}
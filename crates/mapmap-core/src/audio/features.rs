@@ -0,0 +1,397 @@
+//! Spectral Features
+//!
+//! Per-frame descriptors derived from the FFT magnitude spectrum:
+//! spectral-flux onsets (overall and per band), centroid, flatness, rolloff,
+//! chroma and a running musical key estimate.
+
+use super::analyzer_v2::BAND_RANGES;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Number of past flux values the adaptive onset threshold averages over
+const FLUX_HISTORY: usize = 16;
+/// Flux must exceed the recent average by this factor to count as an onset
+const ONSET_RATIO: f32 = 1.5;
+/// Absolute flux floor so that noise in near-silence does not fire onsets
+const ONSET_MIN_FLUX: f32 = 0.002;
+/// Minimum number of frames between two onsets of the same detector
+const MIN_ONSET_FRAMES: usize = 3;
+/// Fraction of the spectral energy below the rolloff frequency
+const ROLLOFF_FRACTION: f32 = 0.85;
+/// Per-frame decay of the chroma accumulated for key estimation (~2s at 86fps)
+const KEY_DECAY: f32 = 0.995;
+/// Frequency range folded into the chroma vector
+const CHROMA_RANGE: (f32, f32) = (55.0, 5000.0);
+/// Frames with less total power than this are treated as silence
+const SILENCE_POWER: f32 = 1e-8;
+
+const PITCH_CLASS_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Krumhansl-Kessler key profiles, tonic first
+const MAJOR_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Name of a pitch class (0 = C, 11 = B)
+pub fn pitch_class_name(pitch_class: usize) -> &'static str {
+    PITCH_CLASS_NAMES[pitch_class % 12]
+}
+
+/// Map a frequency to 0.0-1.0 on a log scale from 20 Hz to 20 kHz
+pub fn normalize_frequency(hz: f32) -> f32 {
+    if hz <= 20.0 {
+        return 0.0;
+    }
+    ((hz / 20.0).ln() / 1000.0_f32.ln()).clamp(0.0, 1.0)
+}
+
+/// Estimated musical key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MusicalKey {
+    /// Pitch class of the tonic (0 = C, 11 = B)
+    pub tonic: u8,
+    /// Minor mode (otherwise major)
+    pub minor: bool,
+}
+
+impl MusicalKey {
+    /// Index in 0..24: major keys C to B, then minor keys C to B
+    pub fn index(&self) -> usize {
+        self.tonic as usize % 12 + if self.minor { 12 } else { 0 }
+    }
+
+    /// Display name, e.g. "F# minor"
+    pub fn name(&self) -> String {
+        format!(
+            "{} {}",
+            pitch_class_name(self.tonic as usize),
+            if self.minor { "minor" } else { "major" }
+        )
+    }
+}
+
+/// Features of one spectrum frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpectralFrame {
+    /// Broadband spectral-flux onset
+    pub onset: bool,
+    /// How far the broadband flux exceeded its threshold (0.0-1.0)
+    pub onset_strength: f32,
+    /// Spectral-flux onset per frequency band
+    pub band_onsets: [bool; 9],
+    /// Spectral centroid in Hz
+    pub centroid: f32,
+    /// Spectral flatness (0.0 = tonal, 1.0 = noise)
+    pub flatness: f32,
+    /// Rolloff frequency in Hz below which 85% of the energy lies
+    pub rolloff: f32,
+    /// Energy per pitch class, normalized to a maximum of 1.0
+    pub chroma: [f32; 12],
+}
+
+/// Adaptive-threshold peak picker for one flux series
+#[derive(Debug, Clone, Default)]
+struct OnsetDetector {
+    history: VecDeque<f32>,
+    frames_since_onset: usize,
+}
+
+impl OnsetDetector {
+    fn detect(&mut self, flux: f32) -> (bool, f32) {
+        let ready = self.history.len() >= 4;
+        let mean = if self.history.is_empty() {
+            0.0
+        } else {
+            self.history.iter().sum::<f32>() / self.history.len() as f32
+        };
+        let threshold = mean * ONSET_RATIO + ONSET_MIN_FLUX;
+
+        self.frames_since_onset = self.frames_since_onset.saturating_add(1);
+        let onset = ready && flux > threshold && self.frames_since_onset >= MIN_ONSET_FRAMES;
+        if onset {
+            self.frames_since_onset = 0;
+        }
+
+        self.history.push_back(flux);
+        if self.history.len() > FLUX_HISTORY {
+            self.history.pop_front();
+        }
+
+        let strength = if onset {
+            (flux / threshold - 1.0).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (onset, strength)
+    }
+}
+
+/// Stateful feature extractor, fed one magnitude spectrum per FFT frame
+#[derive(Debug, Clone, Default)]
+pub struct FeatureExtractor {
+    /// Log-compressed magnitudes of the previous frame
+    previous: Vec<f32>,
+    /// Per-bin positive flux of the current frame
+    rises: Vec<f32>,
+    onset: OnsetDetector,
+    band_onsets: [OnsetDetector; 9],
+    /// Chroma accumulated over the last few seconds
+    key_chroma: [f32; 12],
+    /// Pitch class of every bin in the chroma range, cached per spectrum layout
+    chroma_bins: Vec<(usize, usize)>,
+    chroma_layout: (usize, f32),
+}
+
+impl FeatureExtractor {
+    /// Create a new extractor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget all history
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Analyze one magnitude spectrum whose bins are `bin_width` Hz apart
+    pub fn process(&mut self, magnitudes: &[f32], bin_width: f32) -> SpectralFrame {
+        let mut frame = SpectralFrame::default();
+        if magnitudes.len() < 2 || bin_width <= 0.0 {
+            return frame;
+        }
+
+        self.detect_onsets(magnitudes, bin_width, &mut frame);
+
+        // Skip DC; it carries no pitch or timbre information
+        let bins = &magnitudes[1..];
+        let total_power: f32 = bins.iter().map(|m| m * m).sum();
+        if total_power < SILENCE_POWER {
+            return frame;
+        }
+
+        let magnitude_sum: f32 = bins.iter().sum();
+        let weighted: f32 = bins
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i + 1) as f32 * bin_width * m)
+            .sum();
+        frame.centroid = weighted / magnitude_sum;
+
+        let mean_power = total_power / bins.len() as f32;
+        let mean_log_power =
+            bins.iter().map(|m| (m * m + 1e-12).ln()).sum::<f32>() / bins.len() as f32;
+        frame.flatness = (mean_log_power.exp() / mean_power).clamp(0.0, 1.0);
+
+        let mut cumulative = 0.0;
+        for (i, m) in bins.iter().enumerate() {
+            cumulative += m * m;
+            if cumulative >= total_power * ROLLOFF_FRACTION {
+                frame.rolloff = (i + 1) as f32 * bin_width;
+                break;
+            }
+        }
+
+        self.update_chroma_bins(magnitudes.len(), bin_width);
+        for &(bin, pitch_class) in &self.chroma_bins {
+            frame.chroma[pitch_class] += magnitudes[bin] * magnitudes[bin];
+        }
+        let max = frame.chroma.iter().cloned().fold(0.0, f32::max);
+        if max > 0.0 {
+            for value in &mut frame.chroma {
+                *value /= max;
+            }
+            for (acc, value) in self.key_chroma.iter_mut().zip(frame.chroma) {
+                *acc = *acc * KEY_DECAY + value * (1.0 - KEY_DECAY);
+            }
+        }
+
+        frame
+    }
+
+    fn detect_onsets(&mut self, magnitudes: &[f32], bin_width: f32, frame: &mut SpectralFrame) {
+        if self.previous.len() != magnitudes.len() {
+            self.previous = vec![0.0; magnitudes.len()];
+            self.rises = vec![0.0; magnitudes.len()];
+        }
+
+        // Half-wave rectified difference of log-compressed magnitudes
+        let rises = &mut self.rises;
+        for ((rise, &m), previous) in rises
+            .iter_mut()
+            .zip(magnitudes)
+            .zip(self.previous.iter_mut())
+        {
+            let current = m.ln_1p();
+            *rise = (current - *previous).max(0.0);
+            *previous = current;
+        }
+
+        let flux = rises.iter().sum::<f32>() / rises.len() as f32;
+        (frame.onset, frame.onset_strength) = self.onset.detect(flux);
+
+        for (i, (min_freq, max_freq)) in BAND_RANGES.iter().enumerate() {
+            let min_bin = (*min_freq / bin_width) as usize;
+            let max_bin = ((*max_freq / bin_width) as usize).min(rises.len() - 1);
+            if max_bin <= min_bin {
+                continue;
+            }
+            let band = &rises[min_bin..=max_bin];
+            let band_flux = band.iter().sum::<f32>() / band.len() as f32;
+            frame.band_onsets[i] = self.band_onsets[i].detect(band_flux).0;
+        }
+    }
+
+    fn update_chroma_bins(&mut self, bin_count: usize, bin_width: f32) {
+        if self.chroma_layout == (bin_count, bin_width) {
+            return;
+        }
+        self.chroma_layout = (bin_count, bin_width);
+        self.chroma_bins = (1..bin_count)
+            .filter_map(|bin| {
+                let freq = bin as f32 * bin_width;
+                if !(CHROMA_RANGE.0..=CHROMA_RANGE.1).contains(&freq) {
+                    return None;
+                }
+                let midi = 69.0 + 12.0 * (freq / 440.0).log2();
+                Some((bin, (midi.round() as i32).rem_euclid(12) as usize))
+            })
+            .collect();
+    }
+
+    /// Most likely key of the recent audio and the correlation of its
+    /// profile with the accumulated chroma (0.0-1.0)
+    pub fn key(&self) -> Option<(MusicalKey, f32)> {
+        if self.key_chroma.iter().sum::<f32>() < 1e-3 {
+            return None;
+        }
+
+        let mut best: Option<(MusicalKey, f32)> = None;
+        for minor in [false, true] {
+            let profile = if minor {
+                &MINOR_PROFILE
+            } else {
+                &MAJOR_PROFILE
+            };
+            for tonic in 0..12 {
+                let rotated: [f32; 12] = std::array::from_fn(|pc| profile[(pc + 12 - tonic) % 12]);
+                let score = correlation(&self.key_chroma, &rotated);
+                if best.map_or(true, |(_, s)| score > s) {
+                    best = Some((
+                        MusicalKey {
+                            tonic: tonic as u8,
+                            minor,
+                        },
+                        score,
+                    ));
+                }
+            }
+        }
+        best.map(|(key, score)| (key, score.clamp(0.0, 1.0)))
+    }
+}
+
+/// Pearson correlation of two pitch-class vectors
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let mut covariance = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a) * (x - mean_a);
+        var_b += (y - mean_b) * (y - mean_b);
+    }
+    if var_a <= f32::EPSILON || var_b <= f32::EPSILON {
+        return 0.0;
+    }
+    covariance / (var_a * var_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIN_WIDTH: f32 = 44100.0 / 1024.0;
+
+    /// Magnitude spectrum with peaks at the given frequencies
+    fn spectrum(peaks: &[(f32, f32)]) -> Vec<f32> {
+        let mut magnitudes = vec![0.0; 512];
+        for &(freq, magnitude) in peaks {
+            magnitudes[(freq / BIN_WIDTH).round() as usize] += magnitude;
+        }
+        magnitudes
+    }
+
+    #[test]
+    fn test_onset_on_sudden_energy() {
+        let mut extractor = FeatureExtractor::new();
+        let quiet = spectrum(&[(100.0, 0.01)]);
+        for _ in 0..10 {
+            assert!(!extractor.process(&quiet, BIN_WIDTH).onset);
+        }
+
+        let hit = spectrum(&[(100.0, 5.0), (3000.0, 2.0)]);
+        let frame = extractor.process(&hit, BIN_WIDTH);
+        assert!(frame.onset);
+        assert!(frame.onset_strength > 0.0);
+        assert!(frame.band_onsets[1], "bass band should see the hit");
+        assert!(frame.band_onsets[5], "upper mid band should see the hit");
+        assert!(!frame.band_onsets[8], "air band is silent");
+
+        // A sustained note is not a new onset
+        for _ in 0..5 {
+            assert!(!extractor.process(&hit, BIN_WIDTH).onset);
+        }
+    }
+
+    #[test]
+    fn test_spectral_shape() {
+        let mut extractor = FeatureExtractor::new();
+        let low = extractor.process(&spectrum(&[(200.0, 1.0)]), BIN_WIDTH);
+        let high = extractor.process(&spectrum(&[(8000.0, 1.0)]), BIN_WIDTH);
+        assert!((low.centroid - 200.0).abs() < BIN_WIDTH);
+        assert!(high.centroid > 7000.0);
+        assert!(low.rolloff < high.rolloff);
+        assert!(low.flatness < 0.01, "a pure tone is not flat");
+
+        let noise = extractor.process(&vec![1.0; 512], BIN_WIDTH);
+        assert!(noise.flatness > 0.99);
+        assert!(normalize_frequency(noise.centroid) > normalize_frequency(low.centroid));
+    }
+
+    #[test]
+    fn test_chroma_and_key() {
+        let mut extractor = FeatureExtractor::new();
+        // A minor triad: A3, C4, E4
+        let chord = spectrum(&[(220.0, 1.0), (261.63, 0.8), (329.63, 0.8)]);
+        let mut frame = SpectralFrame::default();
+        for _ in 0..200 {
+            frame = extractor.process(&chord, BIN_WIDTH);
+        }
+        let loudest = (0..12)
+            .max_by(|&a, &b| frame.chroma[a].total_cmp(&frame.chroma[b]))
+            .unwrap();
+        assert_eq!(pitch_class_name(loudest), "A");
+        assert_eq!(frame.chroma[loudest], 1.0);
+
+        let (key, confidence) = extractor.key().unwrap();
+        assert_eq!(key.name(), "A minor");
+        assert_eq!(key.index(), 21);
+        assert!(confidence > 0.5);
+    }
+
+    #[test]
+    fn test_silence_has_no_features() {
+        let mut extractor = FeatureExtractor::new();
+        let frame = extractor.process(&[0.0; 512], BIN_WIDTH);
+        assert_eq!(frame.centroid, 0.0);
+        assert_eq!(frame.chroma, [0.0; 12]);
+        assert!(extractor.key().is_none());
+    }
+}
@@ -0,0 +1,476 @@
+//! File-based audio backend
+//!
+//! Decodes an audio file (or the soundtrack of a video file) into memory and
+//! feeds it to the analyzer as if it were a live input. Playback either runs
+//! on its own clock or follows an external position, e.g. the media player
+//! that shows the video, so analysis stays in sync with what is on screen.
+//!
+//! WAV is always supported. FLAC, MP3, OGG/Vorbis and the AAC/PCM soundtracks
+//! of MP4/MOV/MKV files require the `audio-file` feature.
+
+use super::backend::{AudioBackend, AudioError};
+use std::path::Path;
+use std::time::Instant;
+
+/// Largest chunk delivered in one call, in seconds
+///
+/// If the caller stalls for longer, playback skips ahead instead of flooding
+/// the analyzer, so it stays in sync with the clock.
+const MAX_CATCH_UP_SECS: f64 = 1.0;
+
+/// Decoded, interleaved audio data
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAudio {
+    /// Interleaved samples in the range -1.0..=1.0
+    pub samples: Vec<f32>,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Number of interleaved channels
+    pub channels: u16,
+}
+
+impl DecodedAudio {
+    /// Mix all channels down to mono
+    pub fn to_mono(&self) -> Vec<f32> {
+        let channels = self.channels.max(1) as usize;
+        if channels == 1 {
+            return self.samples.clone();
+        }
+        self.samples
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+}
+
+/// Decode an audio file, or the first audio track of a video file
+pub fn decode_file(path: impl AsRef<Path>) -> Result<DecodedAudio, AudioError> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    if extension == "wav" || extension == "wave" {
+        return decode_wav(path);
+    }
+
+    #[cfg(feature = "audio-file")]
+    {
+        decode_with_symphonia(path)
+    }
+    #[cfg(not(feature = "audio-file"))]
+    {
+        Err(AudioError::Decode(format!(
+            "Unsupported audio file '{}': only WAV is available without the audio-file feature",
+            path.display()
+        )))
+    }
+}
+
+fn decode_wav(path: &Path) -> Result<DecodedAudio, AudioError> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| AudioError::Decode(format!("{}: {}", path.display(), e)))?;
+    let spec = reader.spec();
+
+    let samples: Result<Vec<f32>, hound::Error> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect()
+        }
+    };
+
+    Ok(DecodedAudio {
+        samples: samples.map_err(|e| AudioError::Decode(format!("{}: {}", path.display(), e)))?,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+#[cfg(feature = "audio-file")]
+fn decode_with_symphonia(path: &Path) -> Result<DecodedAudio, AudioError> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let decode_err =
+        |e: &dyn std::fmt::Display| AudioError::Decode(format!("{}: {}", path.display(), e));
+
+    let file = std::fs::File::open(path).map_err(|e| decode_err(&e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| decode_err(&e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| decode_err(&"no audio track"))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track
+        .codec_params
+        .channels
+        .map(|c| c.count() as u16)
+        .unwrap_or(0);
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| decode_err(&e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(Error::ResetRequired) => break,
+            Err(e) => return Err(decode_err(&e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                sample_rate = spec.rate;
+                channels = spec.channels.count() as u16;
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            // Corrupt packets are skipped, like a player would
+            Err(Error::DecodeError(e)) => tracing::warn!("Skipping audio packet: {}", e),
+            Err(e) => return Err(decode_err(&e)),
+        }
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err(decode_err(&"unknown sample rate or channel layout"));
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
+
+/// Audio backend that plays a decoded file into the analyzer
+pub struct FileBackend {
+    /// Mono samples
+    samples: Vec<f32>,
+    sample_rate: u32,
+    /// Index of the next sample to deliver
+    cursor: usize,
+    looping: bool,
+    playing: bool,
+    /// Free-running clock: start time and samples delivered since then
+    clock: Option<(Instant, u64)>,
+    /// Position to catch up to when following an external position
+    sync_target: Option<usize>,
+    synced: bool,
+}
+
+impl FileBackend {
+    /// Decode a file and create a backend for it
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AudioError> {
+        let decoded = decode_file(path.as_ref())?;
+        tracing::info!(
+            "Audio: Loaded '{}' ({} Hz, {} channels, {:.1}s)",
+            path.as_ref().display(),
+            decoded.sample_rate,
+            decoded.channels,
+            decoded.samples.len() as f64
+                / decoded.sample_rate.max(1) as f64
+                / decoded.channels.max(1) as f64
+        );
+        Ok(Self::from_decoded(&decoded))
+    }
+
+    /// Create a backend from already decoded audio
+    pub fn from_decoded(decoded: &DecodedAudio) -> Self {
+        Self::from_samples(decoded.to_mono(), decoded.sample_rate)
+    }
+
+    /// Create a backend from mono samples
+    pub fn from_samples(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            samples,
+            sample_rate: sample_rate.max(1),
+            cursor: 0,
+            looping: false,
+            playing: false,
+            clock: None,
+            sync_target: None,
+            synced: false,
+        }
+    }
+
+    /// Loop back to the start at the end of the file (free-running playback only)
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Sample rate of the decoded audio in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Length of the audio in seconds
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+
+    /// Position of the next delivered sample in seconds
+    pub fn position(&self) -> f64 {
+        self.cursor as f64 / self.sample_rate as f64
+    }
+
+    /// Check if playback reached the end of a non-looping file
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.cursor >= self.samples.len()
+    }
+
+    /// Jump to a position in seconds without delivering the skipped audio
+    pub fn seek(&mut self, seconds: f64) {
+        self.cursor = self.index_at(seconds);
+        self.sync_target = None;
+        if self.playing && !self.synced {
+            self.clock = Some((Instant::now(), 0));
+        }
+    }
+
+    /// Read the next samples regardless of the clock, e.g. for offline rendering
+    pub fn read(&mut self, count: usize) -> Vec<f32> {
+        let mut out = Vec::with_capacity(count.min(self.samples.len()));
+        while out.len() < count {
+            if self.cursor >= self.samples.len() {
+                if self.looping && !self.samples.is_empty() {
+                    self.cursor = 0;
+                } else {
+                    break;
+                }
+            }
+            let n = (count - out.len()).min(self.samples.len() - self.cursor);
+            out.extend_from_slice(&self.samples[self.cursor..self.cursor + n]);
+            self.cursor += n;
+        }
+        out
+    }
+
+    fn index_at(&self, seconds: f64) -> usize {
+        ((seconds.max(0.0) * self.sample_rate as f64) as usize).min(self.samples.len())
+    }
+
+    fn max_chunk(&self) -> usize {
+        (MAX_CATCH_UP_SECS * self.sample_rate as f64) as usize
+    }
+}
+
+impl AudioBackend for FileBackend {
+    fn start(&mut self) -> Result<(), AudioError> {
+        self.playing = true;
+        if !self.synced {
+            self.clock = Some((Instant::now(), 0));
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.playing = false;
+        self.clock = None;
+    }
+
+    fn get_samples(&mut self) -> Vec<f32> {
+        if !self.playing {
+            return Vec::new();
+        }
+
+        if self.synced {
+            let Some(target) = self.sync_target.take() else {
+                return Vec::new();
+            };
+            return self.read(target.saturating_sub(self.cursor));
+        }
+
+        let Some((start, delivered)) = self.clock else {
+            return Vec::new();
+        };
+        let expected = (start.elapsed().as_secs_f64() * self.sample_rate as f64) as u64;
+        let due = expected.saturating_sub(delivered) as usize;
+        self.clock = Some((start, delivered + due as u64));
+
+        let max_chunk = self.max_chunk();
+        if due > max_chunk {
+            // Skip what we fell behind on
+            self.read(due - max_chunk);
+            return self.read(max_chunk);
+        }
+        self.read(due)
+    }
+
+    fn sync_position(&mut self, seconds: f64) {
+        self.synced = true;
+        self.clock = None;
+
+        let target = self.index_at(seconds);
+        if target < self.cursor || target - self.cursor > self.max_chunk() {
+            // The followed player seeked or looped
+            self.cursor = target;
+            self.sync_target = None;
+        } else {
+            self.sync_target = Some(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::analyzer_v2::{AudioAnalyzerV2, AudioAnalyzerV2Config};
+
+    fn write_wav(path: &Path, samples: &[f32], sample_rate: u32, channels: u16) {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for &s in samples {
+            writer.write_sample((s * i16::MAX as f32) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_decode_wav_mixes_to_mono() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stereo.wav");
+        // Left 0.5, right -0.5 -> mono silence; then both 0.5
+        write_wav(&path, &[0.5, -0.5, 0.5, 0.5], 48000, 2);
+
+        let decoded = decode_file(&path).unwrap();
+        assert_eq!(decoded.sample_rate, 48000);
+        assert_eq!(decoded.channels, 2);
+
+        let mono = decoded.to_mono();
+        assert_eq!(mono.len(), 2);
+        assert!(mono[0].abs() < 1e-3);
+        assert!((mono[1] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_read_and_loop() {
+        let mut backend = FileBackend::from_samples(vec![1.0, 2.0, 3.0], 3);
+        assert_eq!(backend.read(2), vec![1.0, 2.0]);
+        assert_eq!(backend.read(5), vec![3.0]);
+        assert!(backend.is_finished());
+
+        let mut looping = FileBackend::from_samples(vec![1.0, 2.0, 3.0], 3).with_looping(true);
+        assert_eq!(looping.read(5), vec![1.0, 2.0, 3.0, 1.0, 2.0]);
+        assert!(!looping.is_finished());
+    }
+
+    #[test]
+    fn test_sync_position_follows_player() {
+        let samples: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let mut backend = FileBackend::from_samples(samples, 100);
+        backend.start().unwrap();
+
+        // Nothing is delivered until a position is known
+        backend.sync_position(0.0);
+        assert!(backend.get_samples().is_empty());
+
+        backend.sync_position(0.5);
+        let chunk = backend.get_samples();
+        assert_eq!(chunk.len(), 50);
+        assert_eq!(chunk[0], 0.0);
+
+        // Same position again delivers nothing
+        backend.sync_position(0.5);
+        assert!(backend.get_samples().is_empty());
+
+        // A jump (seek/loop in the player) skips instead of replaying
+        backend.sync_position(8.0);
+        assert!(backend.get_samples().is_empty());
+        assert_eq!(backend.position(), 8.0);
+        backend.sync_position(8.1);
+        let expected: Vec<f32> = (800..810).map(|i| i as f32).collect();
+        assert_eq!(backend.get_samples(), expected);
+
+        // Paused backends deliver nothing
+        backend.stop();
+        backend.sync_position(9.0);
+        assert!(backend.get_samples().is_empty());
+    }
+
+    #[test]
+    fn test_recorded_file_drives_analyzer() {
+        let sample_rate = 44100;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kick.wav");
+
+        // Four 60 Hz bursts, half a second apart
+        let samples: Vec<f32> = (0..sample_rate * 2)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let in_burst = (t % 0.5) < 0.1;
+                if in_burst {
+                    (t * 60.0 * std::f32::consts::TAU).sin() * 0.9
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        write_wav(&path, &samples, sample_rate, 1);
+
+        let mut backend = FileBackend::open(&path).unwrap();
+        let mut analyzer = AudioAnalyzerV2::new(AudioAnalyzerV2Config {
+            sample_rate: backend.sample_rate(),
+            ..Default::default()
+        });
+
+        // Feed in 10 ms blocks, like a 100 fps render loop
+        let block = backend.sample_rate() as usize / 100;
+        let mut peak_rms = 0.0f32;
+        let mut blocks = 0;
+        loop {
+            let chunk = backend.read(block);
+            if chunk.is_empty() {
+                break;
+            }
+            analyzer.process_samples(&chunk, backend.position());
+            peak_rms = peak_rms.max(analyzer.get_latest_analysis().rms_volume);
+            blocks += 1;
+        }
+
+        assert_eq!(blocks, 200);
+        assert!(backend.is_finished());
+        assert!(peak_rms > 0.1, "peak rms {}", peak_rms);
+    }
+}
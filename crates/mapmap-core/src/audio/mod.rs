@@ -5,10 +5,14 @@
 
 pub mod analyzer_v2;
 pub mod backend;
+pub mod beat_grid;
+pub mod features;
+pub mod file_backend;
+pub mod routing;
+pub mod tempo;
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
 use std::sync::Arc;
 
 /// Audio analysis configuration
@@ -40,6 +44,18 @@ pub struct AudioConfig {
 
     /// High frequency band gain multiplier
     pub high_band_gain: f32,
+
+    /// Named analyzers fed by individual input channels
+    #[serde(default)]
+    pub channel_routes: Vec<routing::AudioChannelRoute>,
+
+    /// Bar and phrase layout of the beat grid
+    #[serde(default)]
+    pub beat_grid: beat_grid::BeatGridConfig,
+
+    /// Tempo source priorities and manual tempo
+    #[serde(default)]
+    pub tempo: tempo::TempoConfig,
 }
 
 impl Default for AudioConfig {
@@ -54,6 +70,9 @@ impl Default for AudioConfig {
             low_band_gain: 1.0,
             mid_band_gain: 1.0,
             high_band_gain: 1.0,
+            channel_routes: Vec::new(),
+            beat_grid: beat_grid::BeatGridConfig::default(),
+            tempo: tempo::TempoConfig::default(),
         }
     }
 }
@@ -97,6 +116,21 @@ impl FrequencyBand {
         }
     }
 
+    /// Index of this band in `band_energies` and `band_onsets`
+    pub fn index(&self) -> usize {
+        match self {
+            FrequencyBand::SubBass => 0,
+            FrequencyBand::Bass => 1,
+            FrequencyBand::LowMid => 2,
+            FrequencyBand::Mid => 3,
+            FrequencyBand::HighMid => 4,
+            FrequencyBand::UpperMid => 5,
+            FrequencyBand::Presence => 6,
+            FrequencyBand::Brilliance => 7,
+            FrequencyBand::Air => 8,
+        }
+    }
+
     /// Get all frequency bands
     pub fn all() -> Vec<FrequencyBand> {
         vec![
@@ -145,6 +179,38 @@ pub struct AudioAnalysis {
 
     /// Raw waveform data (latest samples, for visualization)
     pub waveform: Arc<Vec<f32>>,
+
+    /// Onset strength (0.0-1.0)
+    #[serde(default)]
+    pub onset_strength: f32,
+
+    /// Onset detected per frequency band
+    #[serde(default)]
+    pub band_onsets: [bool; 9],
+
+    /// Spectral centroid in Hz
+    #[serde(default)]
+    pub spectral_centroid: f32,
+
+    /// Spectral flatness (0.0 = tonal, 1.0 = noise)
+    #[serde(default)]
+    pub spectral_flatness: f32,
+
+    /// Spectral rolloff frequency in Hz
+    #[serde(default)]
+    pub spectral_rolloff: f32,
+
+    /// Energy per pitch class C to B (0.0-1.0)
+    #[serde(default)]
+    pub chroma: [f32; 12],
+
+    /// Estimated musical key
+    #[serde(default)]
+    pub key: Option<features::MusicalKey>,
+
+    /// Confidence of the key estimate (0.0-1.0)
+    #[serde(default)]
+    pub key_confidence: f32,
 }
 
 impl Default for AudioAnalysis {
@@ -160,6 +226,14 @@ impl Default for AudioAnalysis {
             onset_detected: false,
             tempo_bpm: None,
             waveform: Arc::new(vec![0.0; 512]),
+            onset_strength: 0.0,
+            band_onsets: [false; 9],
+            spectral_centroid: 0.0,
+            spectral_flatness: 0.0,
+            spectral_rolloff: 0.0,
+            chroma: [0.0; 12],
+            key: None,
+            key_confidence: 0.0,
         }
     }
 }
@@ -172,8 +246,6 @@ pub struct AudioAnalyzer {
     pub config: AudioConfig,
     /// V2 analyzer instance
     pub v2: AudioAnalyzerV2,
-    // Onset detection history (V2 doesn't provide onset)
-    onset_history: VecDeque<f32>,
     // Last analysis for caching
     last_analysis: AudioAnalysis,
     // Channel for async access (kept for API compatibility)
@@ -200,7 +272,6 @@ impl AudioAnalyzer {
         Self {
             config: config.clone(),
             v2,
-            onset_history: VecDeque::with_capacity(10),
             last_analysis: AudioAnalysis::default(),
             analysis_sender: tx,
             analysis_receiver: rx,
@@ -228,7 +299,6 @@ impl AudioAnalyzer {
     /// Reset all buffers and state
     pub fn reset(&mut self) {
         self.v2.reset();
-        self.onset_history.clear();
         self.last_analysis = AudioAnalysis::default();
         // Drain channel
         while self.analysis_receiver.try_recv().is_ok() {}
@@ -252,26 +322,6 @@ impl AudioAnalyzer {
         self.v2.process_samples(&self.scratch_buffer, timestamp);
         let v2_analysis = self.v2.get_latest_analysis();
 
-        // Calculate Onset (V2 doesn't do it)
-        // Simple onset detection based on sudden energy increase
-        let current_energy = v2_analysis.rms_volume;
-        self.onset_history.push_back(current_energy);
-        if self.onset_history.len() > 8 {
-            self.onset_history.pop_front();
-        }
-
-        let onset_detected = if self.onset_history.len() >= 4 {
-            let recent_avg: f32 = self
-                .onset_history
-                .iter()
-                .take(self.onset_history.len() - 1)
-                .sum::<f32>()
-                / (self.onset_history.len() - 1) as f32;
-            current_energy > recent_avg * 1.5 && current_energy > 0.05
-        } else {
-            false
-        };
-
         // Map 9 bands (V2) to 9 bands (now 1:1)
         let b = v2_analysis.band_energies;
         let mapped_bands = [
@@ -294,9 +344,17 @@ impl AudioAnalyzer {
             peak_volume: v2_analysis.peak_volume,
             beat_detected: v2_analysis.beat_detected,
             beat_strength: v2_analysis.beat_strength,
-            onset_detected,
+            onset_detected: v2_analysis.onset_detected,
             tempo_bpm: v2_analysis.tempo_bpm,
             waveform: v2_analysis.waveform,
+            onset_strength: v2_analysis.onset_strength,
+            band_onsets: v2_analysis.band_onsets,
+            spectral_centroid: v2_analysis.spectral_centroid,
+            spectral_flatness: v2_analysis.spectral_flatness,
+            spectral_rolloff: v2_analysis.spectral_rolloff,
+            chroma: v2_analysis.chroma,
+            key: v2_analysis.key,
+            key_confidence: v2_analysis.key_confidence,
         };
 
         self.last_analysis = analysis.clone();
@@ -321,7 +379,7 @@ impl AudioAnalyzer {
 }
 
 /// Audio input source type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AudioSource {
     /// System audio input (microphone/line-in)
     SystemInput,
@@ -329,6 +387,8 @@ pub enum AudioSource {
     VideoAudio,
     /// External audio file
     AudioFile,
+    /// Named channel analyzer (see [`routing::AudioChannelRoute`])
+    Analyzer(String),
 }
 
 /// Audio reactive parameter mapping
@@ -381,6 +441,18 @@ pub enum AudioMappingType {
     Tempo,
     /// Specific FFT bin
     FFTBin(usize),
+    /// Onset in a specific frequency band
+    BandOnset,
+    /// Spectral centroid ("brightness"), log-scaled 20 Hz - 20 kHz
+    SpectralCentroid,
+    /// Spectral flatness (tonal vs. noisy)
+    SpectralFlatness,
+    /// Spectral rolloff frequency, log-scaled 20 Hz - 20 kHz
+    SpectralRolloff,
+    /// Energy of a pitch class (0 = C, 11 = B)
+    Chroma(usize),
+    /// Estimated key: major C..B then minor C..B, spread over 0.0-1.0
+    Key,
 }
 
 impl AudioReactiveMapping {
@@ -390,24 +462,9 @@ impl AudioReactiveMapping {
         let raw_value = match self.mapping_type {
             AudioMappingType::Volume => analysis.rms_volume,
             AudioMappingType::Peak => analysis.peak_volume,
-            AudioMappingType::BandEnergy => {
-                if let Some(band) = self.frequency_band {
-                    let index = match band {
-                        FrequencyBand::SubBass => 0,
-                        FrequencyBand::Bass => 1,
-                        FrequencyBand::LowMid => 2,
-                        FrequencyBand::Mid => 3,
-                        FrequencyBand::HighMid => 4,
-                        FrequencyBand::UpperMid => 5,
-                        FrequencyBand::Presence => 6,
-                        FrequencyBand::Brilliance => 7,
-                        FrequencyBand::Air => 8,
-                    };
-                    analysis.band_energies[index]
-                } else {
-                    0.0
-                }
-            }
+            AudioMappingType::BandEnergy => self
+                .frequency_band
+                .map_or(0.0, |band| analysis.band_energies[band.index()]),
             AudioMappingType::Beat => {
                 if analysis.beat_detected {
                     1.0
@@ -427,6 +484,27 @@ impl AudioReactiveMapping {
             AudioMappingType::FFTBin(bin) => {
                 analysis.fft_magnitudes.get(bin).copied().unwrap_or(0.0)
             }
+            AudioMappingType::BandOnset => {
+                let onset = self
+                    .frequency_band
+                    .is_some_and(|band| analysis.band_onsets[band.index()]);
+                if onset {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            AudioMappingType::SpectralCentroid => {
+                features::normalize_frequency(analysis.spectral_centroid)
+            }
+            AudioMappingType::SpectralFlatness => analysis.spectral_flatness,
+            AudioMappingType::SpectralRolloff => {
+                features::normalize_frequency(analysis.spectral_rolloff)
+            }
+            AudioMappingType::Chroma(pitch_class) => {
+                analysis.chroma.get(pitch_class).copied().unwrap_or(0.0)
+            }
+            AudioMappingType::Key => analysis.key.map_or(0.0, |key| key.index() as f32 / 23.0),
         };
 
         // Apply attack/release envelope
@@ -648,6 +726,54 @@ mod tests {
         assert!(band_value >= 0.0);
     }
 
+    #[test]
+    fn test_spectral_mapping_types() {
+        let mut band_onsets = [false; 9];
+        band_onsets[FrequencyBand::Bass.index()] = true;
+        let analysis = AudioAnalysis {
+            band_onsets,
+            spectral_centroid: 20_000.0,
+            spectral_flatness: 0.25,
+            spectral_rolloff: 20.0,
+            chroma: [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.5],
+            key: Some(features::MusicalKey {
+                tonic: 11,
+                minor: true,
+            }),
+            ..Default::default()
+        };
+        // Instant attack and release so the raw value comes through
+        let value = |mapping_type, frequency_band| {
+            AudioReactiveMapping {
+                parameter_name: "test".to_string(),
+                source: AudioSource::SystemInput,
+                mapping_type,
+                frequency_band,
+                output_min: 0.0,
+                output_max: 1.0,
+                smoothing: 0.0,
+                attack: 0.001,
+                release: 0.001,
+            }
+            .apply(&analysis, 0.5, 1.0)
+        };
+
+        let bass = Some(FrequencyBand::Bass);
+        assert_eq!(value(AudioMappingType::BandOnset, bass), 1.0);
+        assert_eq!(
+            value(AudioMappingType::BandOnset, Some(FrequencyBand::Air)),
+            0.0
+        );
+        assert_eq!(value(AudioMappingType::SpectralCentroid, None), 1.0);
+        assert_eq!(value(AudioMappingType::SpectralFlatness, None), 0.25);
+        assert_eq!(value(AudioMappingType::SpectralRolloff, None), 0.0);
+        assert_eq!(value(AudioMappingType::Chroma(9), None), 1.0);
+        assert_eq!(value(AudioMappingType::Chroma(11), None), 0.5);
+        assert_eq!(value(AudioMappingType::Chroma(12), None), 0.0);
+        // B minor is the last of the 24 keys
+        assert_eq!(value(AudioMappingType::Key, None), 1.0);
+    }
+
     #[test]
     fn test_audio_analysis_default() {
         let analysis = AudioAnalysis::default();
@@ -0,0 +1,261 @@
+//! Input channel routing
+//!
+//! Multi-channel interfaces often carry separate stems (kick, snare, vocals)
+//! on their own inputs. A route picks one or more channels of the interleaved
+//! input, mixes them to mono and feeds its own named analyzer, which can then
+//! be selected as the source of audio-reactive mappings and modulizers.
+
+use super::analyzer_v2::AudioAnalysisV2;
+use super::{AudioAnalysis, AudioAnalyzer, AudioConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A named analyzer fed by a subset of the input channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioChannelRoute {
+    /// Name used to select this analyzer as an audio source
+    pub name: String,
+    /// Zero-based input channels mixed into this analyzer (empty = all)
+    pub channels: Vec<u16>,
+}
+
+impl AudioChannelRoute {
+    /// Create a route for the given channels
+    pub fn new(name: impl Into<String>, channels: Vec<u16>) -> Self {
+        Self {
+            name: name.into(),
+            channels,
+        }
+    }
+}
+
+/// Mix the selected channels of an interleaved buffer down to mono
+///
+/// An empty selection mixes all channels. Channels the input does not have
+/// are ignored, so a selection without any valid channel yields no samples.
+pub fn downmix(samples: &[f32], channels: u16, selection: &[u16]) -> Vec<f32> {
+    let mut out = Vec::new();
+    downmix_into(&mut out, samples, channels, selection);
+    out
+}
+
+fn downmix_into(out: &mut Vec<f32>, samples: &[f32], channels: u16, selection: &[u16]) {
+    out.clear();
+    let channels = channels.max(1) as usize;
+    if selection.is_empty() {
+        let scale = 1.0 / channels as f32;
+        out.extend(
+            samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() * scale),
+        );
+        return;
+    }
+
+    let valid = selection
+        .iter()
+        .filter(|&&c| (c as usize) < channels)
+        .count();
+    if valid == 0 {
+        return;
+    }
+    let scale = 1.0 / valid as f32;
+    out.extend(samples.chunks_exact(channels).map(|frame| {
+        selection
+            .iter()
+            .filter_map(|&c| frame.get(c as usize))
+            .sum::<f32>()
+            * scale
+    }));
+}
+
+struct RoutedAnalyzer {
+    route: AudioChannelRoute,
+    analyzer: AudioAnalyzer,
+    buffer: Vec<f32>,
+}
+
+/// Set of named analyzers, one per channel route
+pub struct AudioRouter {
+    config: AudioConfig,
+    routes: Vec<RoutedAnalyzer>,
+    latest: HashMap<String, AudioAnalysis>,
+}
+
+impl AudioRouter {
+    /// Create analyzers for the routes in the configuration
+    pub fn new(config: &AudioConfig) -> Self {
+        let mut router = Self {
+            config: config.clone(),
+            routes: Vec::new(),
+            latest: HashMap::new(),
+        };
+        router.rebuild();
+        router
+    }
+
+    /// Apply a new configuration
+    ///
+    /// Analyzers whose route name is unchanged keep their state; analyzers
+    /// for removed routes are dropped. Does nothing if the configuration is
+    /// unchanged.
+    pub fn configure(&mut self, config: &AudioConfig) {
+        if self.config == *config {
+            return;
+        }
+        self.config = config.clone();
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        let mut old = std::mem::take(&mut self.routes);
+        for route in &self.config.channel_routes {
+            // Names are lookup keys, so the first route with a name wins
+            if self.routes.iter().any(|r| r.route.name == route.name) {
+                continue;
+            }
+            let routed = match old.iter().position(|r| r.route.name == route.name) {
+                Some(index) => {
+                    let mut routed = old.swap_remove(index);
+                    routed.route = route.clone();
+                    routed.analyzer.update_config(self.config.clone());
+                    routed
+                }
+                None => RoutedAnalyzer {
+                    route: route.clone(),
+                    analyzer: AudioAnalyzer::new(self.config.clone()),
+                    buffer: Vec::new(),
+                },
+            };
+            self.routes.push(routed);
+        }
+
+        let routes = &self.routes;
+        self.latest
+            .retain(|name, _| routes.iter().any(|r| &r.route.name == name));
+    }
+
+    /// Feed an interleaved block of input samples to every route
+    pub fn process(&mut self, samples: &[f32], channels: u16, timestamp: f64) {
+        for routed in &mut self.routes {
+            downmix_into(
+                &mut routed.buffer,
+                samples,
+                channels,
+                &routed.route.channels,
+            );
+            if routed.buffer.is_empty() {
+                continue;
+            }
+            routed.analyzer.process_samples(&routed.buffer, timestamp);
+            let analysis = routed.analyzer.get_latest_analysis();
+            if let Some(latest) = self.latest.get_mut(&routed.route.name) {
+                *latest = analysis;
+            } else {
+                self.latest.insert(routed.route.name.clone(), analysis);
+            }
+        }
+    }
+
+    /// Reset all analyzers
+    pub fn reset(&mut self) {
+        for routed in &mut self.routes {
+            routed.analyzer.reset();
+        }
+        self.latest.clear();
+    }
+
+    /// Names of the configured analyzers, in route order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.routes.iter().map(|r| r.route.name.as_str())
+    }
+
+    /// Latest analysis of a named analyzer
+    pub fn analysis(&self, name: &str) -> Option<&AudioAnalysis> {
+        self.latest.get(name)
+    }
+
+    /// Latest analyses of all analyzers that have received input, by name
+    pub fn analyses(&self) -> &HashMap<String, AudioAnalysis> {
+        &self.latest
+    }
+
+    /// Latest raw V2 analyses, by name
+    pub fn analyses_v2(&self) -> impl Iterator<Item = (&str, AudioAnalysisV2)> {
+        self.routes
+            .iter()
+            .map(|r| (r.route.name.as_str(), r.analyzer.v2.get_latest_analysis()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn config_with(routes: Vec<AudioChannelRoute>) -> AudioConfig {
+        AudioConfig {
+            channel_routes: routes,
+            ..AudioConfig::default()
+        }
+    }
+
+    /// Two channels: a loud 60 Hz tone on the left, silence on the right
+    fn stereo_block(frames: usize, offset: usize) -> Vec<f32> {
+        (offset..offset + frames)
+            .flat_map(|i| {
+                let t = i as f32 / 44100.0;
+                [(2.0 * PI * 60.0 * t).sin() * 0.8, 0.0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_downmix_selects_channels() {
+        let samples = [1.0, 2.0, 3.0, 5.0, 6.0, 7.0];
+        assert_eq!(downmix(&samples, 3, &[]), vec![2.0, 6.0]);
+        assert_eq!(downmix(&samples, 3, &[2]), vec![3.0, 7.0]);
+        assert_eq!(downmix(&samples, 3, &[0, 1]), vec![1.5, 5.5]);
+        // Missing channels are ignored rather than counted as silence
+        assert_eq!(downmix(&samples, 3, &[1, 9]), vec![2.0, 6.0]);
+        assert!(downmix(&samples, 3, &[9]).is_empty());
+    }
+
+    #[test]
+    fn test_router_analyzes_each_route() {
+        let mut router = AudioRouter::new(&config_with(vec![
+            AudioChannelRoute::new("kick", vec![0]),
+            AudioChannelRoute::new("vocals", vec![1]),
+        ]));
+        assert_eq!(router.names().collect::<Vec<_>>(), vec!["kick", "vocals"]);
+
+        for block in 0..8 {
+            router.process(&stereo_block(1024, block * 1024), 2, block as f64 * 0.02);
+        }
+
+        let kick = router.analysis("kick").unwrap();
+        let vocals = router.analysis("vocals").unwrap();
+        assert!(kick.rms_volume > 0.1);
+        assert!(vocals.rms_volume < 0.001);
+        assert!(router.analysis("snare").is_none());
+        assert_eq!(router.analyses_v2().count(), 2);
+    }
+
+    #[test]
+    fn test_configure_keeps_unchanged_routes() {
+        let mut router = AudioRouter::new(&config_with(vec![
+            AudioChannelRoute::new("kick", vec![0]),
+            AudioChannelRoute::new("kick", vec![1]),
+            AudioChannelRoute::new("vocals", vec![1]),
+        ]));
+        // Duplicate names are dropped
+        assert_eq!(router.names().count(), 2);
+        router.process(&stereo_block(1024, 0), 2, 0.0);
+        assert!(router.analysis("vocals").is_some());
+
+        router.configure(&config_with(vec![AudioChannelRoute::new("kick", vec![0])]));
+        assert_eq!(router.names().collect::<Vec<_>>(), vec!["kick"]);
+        assert!(router.analysis("kick").is_some());
+        assert!(router.analysis("vocals").is_none());
+    }
+}
@@ -0,0 +1,453 @@
+//! Tempo Service
+//!
+//! The single authority for the project tempo. Manual tempo, tap tempo, the
+//! audio analysis and MIDI clock all report here; the highest priority source
+//! that is still live drives the shared [`BeatGrid`], so every beat-synced
+//! feature follows one clock. Without a live source the grid stops.
+
+use super::beat_grid::{BeatGrid, BeatGridConfig, BeatGridState, MAX_BPM, MIN_BPM};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Number of tap intervals averaged for tap tempo
+const TAP_HISTORY: usize = 4;
+/// Taps further apart than this (seconds) start a new tap sequence
+const TAP_TIMEOUT: f64 = 2.0;
+
+/// Where a tempo comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TempoSource {
+    /// Tempo typed in by the user, used only when listed in the priority
+    #[default]
+    Manual,
+    /// Tempo from the latest tap sequence
+    Tap,
+    /// Tempo and beats detected in the audio input
+    Audio,
+    /// External MIDI clock
+    MidiClock,
+}
+
+impl TempoSource {
+    /// All sources
+    pub fn all() -> [TempoSource; 4] {
+        [
+            TempoSource::Manual,
+            TempoSource::Tap,
+            TempoSource::Audio,
+            TempoSource::MidiClock,
+        ]
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            TempoSource::Manual => "Manual",
+            TempoSource::Tap => "Tap",
+            TempoSource::Audio => "Audio",
+            TempoSource::MidiClock => "MIDI Clock",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            TempoSource::Manual => 0,
+            TempoSource::Tap => 1,
+            TempoSource::Audio => 2,
+            TempoSource::MidiClock => 3,
+        }
+    }
+}
+
+/// Tempo source arbitration settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TempoConfig {
+    /// Sources in descending priority; the first live one drives the clock.
+    /// `Manual` is always live, so it is only listed once the user picks it
+    pub priority: Vec<TempoSource>,
+    /// Tempo of the manual source
+    pub manual_bpm: f32,
+    /// Seconds without a report after which audio or MIDI clock count as lost
+    pub source_timeout: f32,
+    /// Seconds after the last tap after which the tap tempo is dropped
+    pub tap_timeout: f32,
+}
+
+impl Default for TempoConfig {
+    fn default() -> Self {
+        Self {
+            priority: vec![TempoSource::MidiClock, TempoSource::Tap, TempoSource::Audio],
+            manual_bpm: 120.0,
+            source_timeout: 2.0,
+            tap_timeout: 30.0,
+        }
+    }
+}
+
+/// Latest report of an external source
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    bpm: f32,
+    /// Beats since the source's start, for sources that carry a transport
+    beat_position: Option<f64>,
+    /// Service time of the report
+    time: f64,
+}
+
+/// Global tempo clock arbitrating between all tempo sources
+#[derive(Debug, Clone)]
+pub struct TempoService {
+    /// Source priorities and manual tempo
+    pub config: TempoConfig,
+    grid: BeatGrid,
+    /// Latest report per source (indexed by `TempoSource::index`)
+    readings: [Option<Reading>; 4],
+    taps: VecDeque<f64>,
+    /// Seconds advanced so far
+    time: f64,
+    /// Added to the beat position of transport sources by nudge and resync
+    clock_offset: f64,
+    active: Option<TempoSource>,
+}
+
+impl Default for TempoService {
+    fn default() -> Self {
+        Self::new(TempoConfig::default(), BeatGridConfig::default())
+    }
+}
+
+impl TempoService {
+    /// Create a stopped service; the first `advance` picks up a live source
+    pub fn new(config: TempoConfig, grid: BeatGridConfig) -> Self {
+        Self {
+            config,
+            grid: BeatGrid::new(grid),
+            readings: [None; 4],
+            taps: VecDeque::with_capacity(TAP_HISTORY + 1),
+            time: 0.0,
+            clock_offset: 0.0,
+            active: None,
+        }
+    }
+
+    /// The shared beat grid
+    pub fn grid(&self) -> &BeatGrid {
+        &self.grid
+    }
+
+    /// The shared beat grid, e.g. to change its layout
+    pub fn grid_mut(&mut self) -> &mut BeatGrid {
+        &mut self.grid
+    }
+
+    /// Grid state after the latest `advance`
+    pub fn state(&self) -> &BeatGridState {
+        self.grid.state()
+    }
+
+    /// Source that drove the latest `advance`, `None` while stopped
+    pub fn active_source(&self) -> Option<TempoSource> {
+        self.active
+    }
+
+    /// Tempo currently reported by `source`, `None` if it is not live
+    pub fn source_bpm(&self, source: TempoSource) -> Option<f32> {
+        match source {
+            TempoSource::Manual => Some(self.config.manual_bpm),
+            TempoSource::Tap => self.readings[source.index()]
+                .filter(|r| self.time - r.time <= self.config.tap_timeout.max(0.0) as f64)
+                .map(|r| r.bpm),
+            _ => self.live_reading(source).map(|r| r.bpm),
+        }
+    }
+
+    /// Set the manual tempo
+    pub fn set_manual_bpm(&mut self, bpm: f32) {
+        if bpm.is_finite() {
+            self.config.manual_bpm = bpm.clamp(MIN_BPM, MAX_BPM);
+        }
+    }
+
+    /// Register a tap: two or more taps set the tap tempo, and while the tap
+    /// source drives the clock each tap lands on a beat
+    pub fn tap(&mut self) {
+        let now = self.time;
+        if self
+            .taps
+            .back()
+            .is_some_and(|&last| now - last > TAP_TIMEOUT)
+        {
+            self.taps.clear();
+        }
+        self.taps.push_back(now);
+        if self.taps.len() > TAP_HISTORY + 1 {
+            self.taps.pop_front();
+        }
+
+        if self.taps.len() >= 2 {
+            let interval = (now - self.taps[0]) / (self.taps.len() - 1) as f64;
+            if interval > 0.0 {
+                self.report(TempoSource::Tap, (60.0 / interval) as f32, None);
+            }
+        }
+        if self.select_source() == Some(TempoSource::Tap) {
+            if let Some(reading) = self.readings[TempoSource::Tap.index()] {
+                self.grid.set_tempo(reading.bpm);
+            }
+            self.grid.align();
+        }
+    }
+
+    /// Forget the tap tempo so lower priority sources take over again
+    pub fn clear_tap(&mut self) {
+        self.taps.clear();
+        self.readings[TempoSource::Tap.index()] = None;
+    }
+
+    /// Report the audio analysis: its tempo estimate and, on a detected beat,
+    /// the beat strength (0.0 - 1.0)
+    ///
+    /// The estimate is only refreshed on beats, so the audio source is lost
+    /// once the music stops instead of running on at the last tempo.
+    pub fn audio(&mut self, bpm: Option<f32>, beat_strength: Option<f32>) {
+        let Some(strength) = beat_strength else {
+            return;
+        };
+        if let Some(bpm) = bpm {
+            self.report(TempoSource::Audio, bpm, None);
+        }
+        if self.active == Some(TempoSource::Audio) {
+            self.grid.audio_beat(strength);
+        }
+    }
+
+    /// Report a running MIDI clock at `beat_position` beats since its start
+    pub fn midi_clock(&mut self, bpm: f32, beat_position: f64) {
+        self.report(TempoSource::MidiClock, bpm, Some(beat_position));
+    }
+
+    /// Move the beats `beats` earlier (positive) or later (negative) in time
+    pub fn nudge(&mut self, beats: f64) {
+        if self.transport().is_some() {
+            self.clock_offset += beats;
+        } else {
+            self.grid.nudge(beats);
+        }
+    }
+
+    /// Make the current moment the downbeat of bar 1
+    pub fn resync(&mut self) {
+        match self.transport() {
+            Some(position) => self.clock_offset -= position,
+            None => self.grid.restart(),
+        }
+    }
+
+    /// Follow the highest priority live source and advance the grid by `dt` seconds
+    ///
+    /// The grid stops while no source is live.
+    pub fn advance(&mut self, dt: f32) -> &BeatGridState {
+        self.time += dt.max(0.0) as f64;
+        let source = self.select_source();
+        if source != self.active {
+            self.active = source;
+            self.clock_offset = 0.0;
+        }
+
+        let Some(bpm) = source.and_then(|source| self.source_bpm(source)) else {
+            self.grid.stop();
+            return self.grid.advance(dt);
+        };
+        match self.transport() {
+            Some(position) => {
+                self.grid.sync_clock(position, bpm);
+                self.grid.advance(0.0);
+            }
+            None => {
+                self.grid.set_tempo(bpm);
+                self.grid.advance(dt);
+            }
+        }
+        self.grid.state()
+    }
+
+    /// Highest priority source with a live tempo
+    fn select_source(&self) -> Option<TempoSource> {
+        self.config
+            .priority
+            .iter()
+            .copied()
+            .find(|source| self.source_bpm(*source).is_some())
+    }
+
+    /// Current beat position of the active source if it carries a transport
+    fn transport(&self) -> Option<f64> {
+        let reading = self.live_reading(self.active?)?;
+        let position = reading.beat_position?;
+        let elapsed = self.time - reading.time;
+        Some(position + elapsed * reading.bpm as f64 / 60.0 + self.clock_offset)
+    }
+
+    fn live_reading(&self, source: TempoSource) -> Option<Reading> {
+        let reading = self.readings[source.index()]?;
+        let timeout = self.config.source_timeout.max(0.0) as f64;
+        (self.time - reading.time <= timeout).then_some(reading)
+    }
+
+    fn report(&mut self, source: TempoSource, bpm: f32, beat_position: Option<f64>) {
+        if bpm.is_finite() && bpm > 0.0 {
+            self.readings[source.index()] = Some(Reading {
+                bpm: bpm.clamp(MIN_BPM, MAX_BPM),
+                beat_position,
+                time: self.time,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::beat_grid::BeatDivision;
+
+    /// Advance in 10ms steps and count the beats fired
+    fn run(tempo: &mut TempoService, seconds: f32) -> usize {
+        (0..(seconds * 100.0).round() as usize)
+            .filter(|_| tempo.advance(0.01).fired(BeatDivision::QuarterBar))
+            .count()
+    }
+
+    /// A service that falls back to the manual tempo
+    fn with_manual() -> TempoService {
+        let mut tempo = TempoService::default();
+        tempo.config.priority.push(TempoSource::Manual);
+        tempo
+    }
+
+    #[test]
+    fn test_stopped_without_source() {
+        let mut tempo = TempoService::default();
+        assert!(!tempo.advance(0.5).running);
+        assert_eq!(tempo.active_source(), None);
+        assert_eq!(run(&mut tempo, 2.0), 0);
+    }
+
+    #[test]
+    fn test_manual_fallback() {
+        let mut tempo = with_manual();
+        tempo.set_manual_bpm(90.0);
+        tempo.advance(0.0);
+        assert_eq!(tempo.active_source(), Some(TempoSource::Manual));
+        assert_eq!(tempo.state().bpm, 90.0);
+        assert!(tempo.state().running);
+    }
+
+    #[test]
+    fn test_tap_tempo() {
+        let mut tempo = TempoService::default();
+        tempo.tap();
+        assert_eq!(tempo.source_bpm(TempoSource::Tap), None);
+        for _ in 0..4 {
+            run(&mut tempo, 0.5);
+            tempo.tap();
+        }
+        let state = *tempo.advance(0.0);
+        assert_eq!(tempo.active_source(), Some(TempoSource::Tap));
+        assert!((state.bpm - 120.0).abs() < 0.5);
+        assert!(state.beat_phase < 0.01, "a tap lands on a beat");
+
+        // A pause starts a new tap sequence
+        run(&mut tempo, 3.0);
+        tempo.tap();
+        run(&mut tempo, 0.25);
+        tempo.tap();
+        assert!((tempo.source_bpm(TempoSource::Tap).unwrap() - 240.0).abs() < 1.0);
+
+        tempo.clear_tap();
+        tempo.advance(0.0);
+        assert_eq!(tempo.active_source(), None);
+    }
+
+    #[test]
+    fn test_tap_tempo_expires() {
+        let mut tempo = TempoService::default();
+        for _ in 0..3 {
+            tempo.tap();
+            run(&mut tempo, 0.5);
+        }
+        assert_eq!(tempo.active_source(), Some(TempoSource::Tap));
+
+        // Audio takes over once the taps are older than the tap timeout
+        for _ in 0..40 {
+            tempo.audio(Some(128.0), Some(1.0));
+            tempo.advance(1.0);
+        }
+        assert_eq!(tempo.active_source(), Some(TempoSource::Audio));
+        assert_eq!(tempo.source_bpm(TempoSource::Tap), None);
+    }
+
+    #[test]
+    fn test_priority_and_timeout() {
+        let mut tempo = TempoService::default();
+        tempo.audio(Some(128.0), Some(1.0));
+        tempo.advance(0.0);
+        assert_eq!(tempo.active_source(), Some(TempoSource::Audio));
+
+        // MIDI clock outranks audio while it keeps reporting
+        for _ in 0..10 {
+            tempo.audio(Some(128.0), Some(1.0));
+            tempo.midi_clock(100.0, 0.0);
+            tempo.advance(0.1);
+        }
+        assert_eq!(tempo.active_source(), Some(TempoSource::MidiClock));
+        assert_eq!(tempo.state().bpm, 100.0);
+
+        // A silent clock times out and audio takes over again
+        for _ in 0..25 {
+            tempo.audio(Some(128.0), Some(1.0));
+            tempo.advance(0.1);
+        }
+        assert_eq!(tempo.active_source(), Some(TempoSource::Audio));
+        assert_eq!(tempo.state().bpm, 128.0);
+
+        // Without beats the stale estimate times out and the grid stops
+        for _ in 0..25 {
+            tempo.audio(Some(128.0), None);
+            tempo.advance(0.1);
+        }
+        assert_eq!(tempo.active_source(), None);
+        assert!(!tempo.state().running);
+    }
+
+    #[test]
+    fn test_clock_transport_nudge_and_resync() {
+        let mut tempo = TempoService::default();
+        tempo.midi_clock(120.0, 9.0);
+        let state = *tempo.advance(0.0);
+        assert_eq!(state.bar, 2);
+        assert_eq!(state.beat, 1);
+
+        // Between clock reports the position is extrapolated
+        tempo.advance(0.25);
+        assert!((tempo.advance(0.0).beat_phase - 0.5).abs() < 0.01);
+
+        tempo.nudge(0.25);
+        assert!((tempo.advance(0.0).beat_phase - 0.75).abs() < 0.01);
+
+        tempo.resync();
+        let state = *tempo.advance(0.0);
+        assert_eq!(state.bar, 0);
+        assert_eq!(state.beat, 0);
+    }
+
+    #[test]
+    fn test_free_running_resync() {
+        let mut tempo = with_manual();
+        assert_eq!(run(&mut tempo, 1.2), 3);
+        tempo.resync();
+        let state = *tempo.advance(0.01);
+        assert!(state.fired(BeatDivision::Phrase));
+        assert_eq!(state.bar, 0);
+    }
+}
@@ -1,7 +1,10 @@
 //! Data structures for audio-reactive components.
 
 use crate::animation::{AnimValue, AnimationClip, AnimationPlayer};
-use crate::audio::{AudioAnalysis, AudioMappingType, AudioReactiveMapping, FrequencyBand};
+use crate::audio::features::MusicalKey;
+use crate::audio::{
+    AudioAnalysis, AudioMappingType, AudioReactiveMapping, AudioSource, FrequencyBand,
+};
 use crate::shader_graph::{NodeId, ParameterValue, ShaderGraph};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -21,6 +24,20 @@ pub struct AudioTriggerData {
     pub beat_strength: f32,
     /// Detected Beats Per Minute (if confident)
     pub bpm: Option<f32>,
+    /// Whether a spectral-flux onset was detected in this frame
+    pub onset_detected: bool,
+    /// Onsets detected per frequency band in this frame
+    pub band_onsets: [bool; 9],
+    /// Spectral centroid in Hz
+    pub spectral_centroid: f32,
+    /// Spectral flatness (0.0 = tonal, 1.0 = noise-like)
+    pub spectral_flatness: f32,
+    /// Frequency below which 85% of the spectral energy lies, in Hz
+    pub spectral_rolloff: f32,
+    /// Energy per pitch class, C to B (0.0-1.0)
+    pub chroma: [f32; 12],
+    /// Estimated musical key
+    pub key: Option<MusicalKey>,
 }
 
 /// Audio-reactive parameter controller
@@ -36,6 +53,10 @@ pub struct AudioReactiveController {
     #[serde(skip)]
     empty_values: HashMap<String, f32>,
 
+    /// Stand-in for named analyzers that have no analysis yet
+    #[serde(skip)]
+    silence: AudioAnalysis,
+
     /// Last update time
     last_update_time: f64,
 
@@ -49,6 +70,7 @@ impl Default for AudioReactiveController {
             mappings: HashMap::new(),
             previous_values: HashMap::new(),
             empty_values: HashMap::new(),
+            silence: AudioAnalysis::default(),
             last_update_time: 0.0,
             enabled: true,
         }
@@ -74,6 +96,17 @@ impl AudioReactiveController {
 
     /// Update all parameters based on audio analysis
     pub fn update(&mut self, audio: &AudioAnalysis, current_time: f64) -> &HashMap<String, f32> {
+        self.update_routed(audio, &HashMap::new(), current_time)
+    }
+
+    /// Update all parameters, reading [`AudioSource::Analyzer`] mappings from
+    /// the named channel analyses and all others from `audio`
+    pub fn update_routed(
+        &mut self,
+        audio: &AudioAnalysis,
+        routed: &HashMap<String, AudioAnalysis>,
+        current_time: f64,
+    ) -> &HashMap<String, f32> {
         if !self.enabled {
             return &self.empty_values;
         }
@@ -87,7 +120,11 @@ impl AudioReactiveController {
 
         for (param_path, mapping) in &self.mappings {
             let previous = self.previous_values.get(param_path).copied().unwrap_or(0.0);
-            let new_value = mapping.apply(audio, previous, delta_time);
+            let analysis = match &mapping.source {
+                AudioSource::Analyzer(name) => routed.get(name).unwrap_or(&self.silence),
+                _ => audio,
+            };
+            let new_value = mapping.apply(analysis, previous, delta_time);
 
             // Optimization: Update existing value without cloning key if possible
             if let Some(val) = self.previous_values.get_mut(param_path) {
@@ -492,6 +529,49 @@ mod tests {
         assert!(values.contains_key("1.opacity"));
     }
 
+    #[test]
+    fn test_controller_reads_named_analyzer() {
+        let mut controller = AudioReactiveController::new();
+        let mapping = |source| AudioReactiveMapping {
+            parameter_name: "opacity".to_string(),
+            source,
+            mapping_type: AudioMappingType::Volume,
+            frequency_band: None,
+            output_min: 0.0,
+            output_max: 1.0,
+            smoothing: 0.0,
+            attack: 0.001,
+            release: 0.001,
+        };
+        controller.add_mapping("1.opacity".to_string(), mapping(AudioSource::SystemInput));
+        controller.add_mapping(
+            "2.opacity".to_string(),
+            mapping(AudioSource::Analyzer("kick".to_string())),
+        );
+        controller.add_mapping(
+            "3.opacity".to_string(),
+            mapping(AudioSource::Analyzer("missing".to_string())),
+        );
+
+        let main = AudioAnalysis {
+            rms_volume: 0.2,
+            ..Default::default()
+        };
+        let mut routed = HashMap::new();
+        routed.insert(
+            "kick".to_string(),
+            AudioAnalysis {
+                rms_volume: 0.9,
+                ..Default::default()
+            },
+        );
+
+        let values = controller.update_routed(&main, &routed, 1.0);
+        assert!((values["1.opacity"] - 0.2).abs() < 1e-6);
+        assert!((values["2.opacity"] - 0.9).abs() < 1e-6);
+        assert_eq!(values["3.opacity"], 0.0);
+    }
+
     #[test]
     fn test_preset_mappings() {
         let mut controller = AudioReactiveController::new();
@@ -0,0 +1,447 @@
+//! Multi-Point Color Matching Across Projectors
+//!
+//! Projectors of different models disagree on primaries, white point and tone
+//! response, which brightness/contrast/gamma alone cannot fix. Each output
+//! shows a grid of RGB test patches that are measured with a colorimeter
+//! (entered by hand or imported from CSV); the measured response is kept as a
+//! [`Lut3D`] from drive values to CIE XYZ. [`common_target`] picks a response
+//! every output can reach, and [`solve_correction_lut`] inverts the response
+//! of each output towards it, giving the correction LUT applied by the
+//! output's color calibration pass.
+
+use crate::lut::{Lut3D, LutError};
+use glam::{Mat3, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Patch levels per channel measured by default (5^3 = 125 patches)
+pub const DEFAULT_PATCH_LEVELS: usize = 5;
+
+/// Test patch grid of an output and the CIE XYZ values measured for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchMeasurements {
+    /// Patch levels per channel (the grid has `levels^3` patches)
+    pub levels: usize,
+    /// Measured XYZ per patch, `None` until measured.
+    /// Same order as LUT data: red varies fastest, then green, then blue
+    pub values: Vec<Option<Vec3>>,
+}
+
+impl PatchMeasurements {
+    /// Create an unmeasured patch grid
+    pub fn new(levels: usize) -> Self {
+        let levels = levels.max(2);
+        Self {
+            levels,
+            values: vec![None; levels.pow(3)],
+        }
+    }
+
+    /// Number of test patches
+    pub fn patch_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Drive value (0.0-1.0 RGB) shown for a patch
+    pub fn patch_color(&self, index: usize) -> Vec3 {
+        let step = (self.levels - 1) as f32;
+        let r = index % self.levels;
+        let g = (index / self.levels) % self.levels;
+        let b = index / (self.levels * self.levels);
+        Vec3::new(r as f32, g as f32, b as f32) / step
+    }
+
+    /// Patch showing a drive value, if it lies on the grid
+    pub fn patch_index(&self, color: Vec3) -> Option<usize> {
+        let step = (self.levels - 1) as f32;
+        let mut index = 0;
+        let mut stride = 1;
+        for channel in color.to_array() {
+            let scaled = channel * step;
+            let level = scaled.round();
+            if (scaled - level).abs() > 0.25 || level < 0.0 || level > step {
+                return None;
+            }
+            index += level as usize * stride;
+            stride *= self.levels;
+        }
+        Some(index)
+    }
+
+    /// Number of patches measured so far
+    pub fn measured_count(&self) -> usize {
+        self.values.iter().filter(|value| value.is_some()).count()
+    }
+
+    /// Whether every patch has been measured
+    pub fn is_complete(&self) -> bool {
+        self.values.iter().all(Option::is_some)
+    }
+
+    /// Measured response as a LUT from drive values to XYZ; `None` until
+    /// every patch is measured
+    pub fn response(&self) -> Option<Lut3D> {
+        let data = self
+            .values
+            .iter()
+            .map(|value| value.map(|xyz| xyz.to_array()))
+            .collect::<Option<Vec<_>>>()?
+            .concat();
+        Some(Lut3D {
+            name: "Measured Response".to_string(),
+            size: self.levels,
+            data,
+            file_path: None,
+        })
+    }
+
+    /// Import measurements from a colorimeter CSV export.
+    ///
+    /// Columns are found by header (`R`, `G`, `B`, `X`, `Y`, `Z`, also
+    /// `RGB_R`/`XYZ_X` style); without a header the first six columns are
+    /// used in that order. Drive values may be 0-1 or 0-255, and rows whose
+    /// color is not on the patch grid are skipped.
+    pub fn from_csv(content: &str, levels: usize) -> Result<Self, LutError> {
+        let mut columns: Option<[usize; 6]> = None;
+        let mut rows = Vec::new();
+
+        for (line_index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line
+                .split([',', ';', '\t'])
+                .map(|field| field.trim().trim_matches('"'))
+                .collect();
+
+            if columns.is_none() && rows.is_empty() {
+                if let Some(header) = header_columns(&fields) {
+                    columns = Some(header);
+                    continue;
+                }
+            }
+
+            let mut values = [0.0f32; 6];
+            for (value, column) in values.iter_mut().zip(columns.unwrap_or([0, 1, 2, 3, 4, 5])) {
+                let field = fields.get(column).copied().unwrap_or_default();
+                *value = field.parse().map_err(|_| {
+                    LutError::ParseError(format!(
+                        "Line {}: invalid value '{}'",
+                        line_index + 1,
+                        field
+                    ))
+                })?;
+            }
+            rows.push((
+                Vec3::new(values[0], values[1], values[2]),
+                Vec3::new(values[3], values[4], values[5]),
+            ));
+        }
+
+        // 8-bit drive values
+        let scale = if rows.iter().any(|(rgb, _)| rgb.max_element() > 1.0) {
+            1.0 / 255.0
+        } else {
+            1.0
+        };
+
+        let mut measurements = Self::new(levels);
+        let mut matched = 0;
+        for (rgb, xyz) in rows {
+            if let Some(index) = measurements.patch_index(rgb * scale) {
+                measurements.values[index] = Some(xyz);
+                matched += 1;
+            }
+        }
+        if matched == 0 {
+            return Err(LutError::ParseError(format!(
+                "No rows match the {}-level patch grid",
+                measurements.levels
+            )));
+        }
+        Ok(measurements)
+    }
+
+    /// Export the measured patches as CSV (8-bit drive values and XYZ),
+    /// readable by [`PatchMeasurements::from_csv`]
+    pub fn to_csv(&self) -> String {
+        let mut content = String::from("R,G,B,X,Y,Z\n");
+        for (index, value) in self.values.iter().enumerate() {
+            if let Some(xyz) = value {
+                let rgb = (self.patch_color(index) * 255.0).round();
+                content.push_str(&format!(
+                    "{},{},{},{:.6},{:.6},{:.6}\n",
+                    rgb.x, rgb.y, rgb.z, xyz.x, xyz.y, xyz.z
+                ));
+            }
+        }
+        content
+    }
+}
+
+/// Column indices of R, G, B, X, Y and Z if the fields are a header row
+fn header_columns(fields: &[&str]) -> Option<[usize; 6]> {
+    let names: [&[&str]; 6] = [
+        &["r", "red", "rgb_r"],
+        &["g", "green", "rgb_g"],
+        &["b", "blue", "rgb_b"],
+        &["x", "xyz_x"],
+        &["y", "xyz_y"],
+        &["z", "xyz_z"],
+    ];
+    let mut columns = [0; 6];
+    for (column, aliases) in columns.iter_mut().zip(names) {
+        *column = fields
+            .iter()
+            .position(|field| aliases.contains(&field.to_lowercase().as_str()))?;
+    }
+    Some(columns)
+}
+
+/// Response every output can reproduce: the average of the measured
+/// responses, lifted to the brightest black and scaled down until its white
+/// is inside every output's gamut.
+///
+/// Returns `None` without responses or if a response has degenerate
+/// primaries.
+pub fn common_target(responses: &[Lut3D]) -> Option<Lut3D> {
+    let first = responses.first()?;
+    let count = responses.len() as f32;
+    let average = |color: Vec3| -> Vec3 {
+        responses
+            .iter()
+            .map(|response| response.apply(color))
+            .sum::<Vec3>()
+            / count
+    };
+
+    let black = responses
+        .iter()
+        .map(|response| response.apply(Vec3::ZERO))
+        .max_by(|a, b| a.y.total_cmp(&b.y))?;
+    let average_black = average(Vec3::ZERO);
+    let average_range = average(Vec3::ONE) - average_black;
+
+    // Largest white along the averaged white direction that every output
+    // reaches from the target black, assuming additive primaries
+    let mut scale = f32::INFINITY;
+    for response in responses {
+        let own_black = response.apply(Vec3::ZERO);
+        let primaries = Mat3::from_cols(
+            response.apply(Vec3::X) - own_black,
+            response.apply(Vec3::Y) - own_black,
+            response.apply(Vec3::Z) - own_black,
+        );
+        if primaries.determinant().abs() <= f32::EPSILON {
+            return None;
+        }
+        let inverse = primaries.inverse();
+        let offset = inverse * (black - own_black);
+        let needed = inverse * average_range;
+        for (offset, needed) in offset.to_array().into_iter().zip(needed.to_array()) {
+            if needed > 0.0 {
+                scale = scale.min((1.0 - offset) / needed);
+            }
+        }
+    }
+    if !scale.is_finite() || scale <= 0.0 {
+        return None;
+    }
+
+    let mut target = Lut3D::identity(first.size);
+    target.name = "Color Match Target".to_string();
+    for entry in target.data.chunks_exact_mut(3) {
+        let color = Vec3::from_slice(entry);
+        let xyz = black + (average(color) - average_black) * scale;
+        entry.copy_from_slice(&xyz.to_array());
+    }
+    Some(target)
+}
+
+/// Solve the correction LUT of an output: for every LUT entry, the drive
+/// value whose measured response is closest to the target response
+pub fn solve_correction_lut(response: &Lut3D, target: &Lut3D, size: usize) -> Lut3D {
+    let mut lut = Lut3D::identity(size);
+    lut.name = "Color Match".to_string();
+    for entry in lut.data.chunks_exact_mut(3) {
+        let color = Vec3::from_slice(entry);
+        let drive = invert_response(response, target.apply(color), color);
+        entry.copy_from_slice(&drive.to_array());
+    }
+    lut
+}
+
+/// Damped least squares (Levenberg-Marquardt) inversion of a response,
+/// constrained to drive values in 0.0-1.0
+fn invert_response(response: &Lut3D, wanted: Vec3, start: Vec3) -> Vec3 {
+    const MAX_ITERATIONS: usize = 32;
+    const STEP: f32 = 1e-3;
+
+    let mut drive = start;
+    let mut residual = response.apply(drive) - wanted;
+    let mut damping = 1e-3;
+
+    for _ in 0..MAX_ITERATIONS {
+        // Finite differences, stepping inwards at the gamut boundary
+        let current = response.apply(drive);
+        let columns = [Vec3::X, Vec3::Y, Vec3::Z].map(|axis| {
+            let step = if drive.dot(axis) + STEP <= 1.0 {
+                STEP
+            } else {
+                -STEP
+            };
+            (response.apply(drive + axis * step) - current) / step
+        });
+        let jacobian = Mat3::from_cols(columns[0], columns[1], columns[2]);
+        let normal = jacobian.transpose() * jacobian;
+        let gradient = jacobian.transpose() * residual;
+        let diagonal = Vec3::new(normal.x_axis.x, normal.y_axis.y, normal.z_axis.z);
+
+        let damped = normal + Mat3::from_diagonal(diagonal * damping + Vec3::splat(1e-9));
+        if damped.determinant().abs() <= f32::MIN_POSITIVE {
+            break;
+        }
+        let candidate = (drive - damped.inverse() * gradient).clamp(Vec3::ZERO, Vec3::ONE);
+        let candidate_residual = response.apply(candidate) - wanted;
+
+        if candidate_residual.length_squared() < residual.length_squared() {
+            let moved = candidate.distance(drive);
+            drive = candidate;
+            residual = candidate_residual;
+            damping = (damping / 3.0).max(1e-6);
+            if moved < 1e-5 {
+                break;
+            }
+        } else {
+            damping *= 4.0;
+            if damping > 1e6 {
+                break;
+            }
+        }
+    }
+    drive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Projector with its own primaries, white level and tone curve
+    struct SyntheticProjector {
+        primaries: Mat3,
+        gamma: f32,
+        black: Vec3,
+    }
+
+    impl SyntheticProjector {
+        fn xyz(&self, drive: Vec3) -> Vec3 {
+            let linear = drive.clamp(Vec3::ZERO, Vec3::ONE).powf(self.gamma);
+            self.black + self.primaries * linear
+        }
+
+        fn measure(&self, levels: usize) -> PatchMeasurements {
+            let mut measurements = PatchMeasurements::new(levels);
+            for index in 0..measurements.patch_count() {
+                let xyz = self.xyz(measurements.patch_color(index));
+                measurements.values[index] = Some(xyz);
+            }
+            measurements
+        }
+    }
+
+    #[test]
+    fn test_patch_grid_indices() {
+        let measurements = PatchMeasurements::new(3);
+        assert_eq!(measurements.patch_count(), 27);
+        for index in 0..measurements.patch_count() {
+            let color = measurements.patch_color(index);
+            assert_eq!(measurements.patch_index(color), Some(index));
+        }
+        assert_eq!(measurements.patch_color(1), Vec3::new(0.5, 0.0, 0.0));
+        assert_eq!(measurements.patch_index(Vec3::new(0.3, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_csv_import() {
+        let csv = "SAMPLE_ID;RGB_R;RGB_G;RGB_B;XYZ_X;XYZ_Y;XYZ_Z\n\
+                   A1;0;0;0;0.5;0.52;0.6\n\
+                   A2;255;255;255;95.0;100.0;108.9\n\
+                   A3;100;0;0;1;1;1\n";
+        let measurements = PatchMeasurements::from_csv(csv, 2).unwrap();
+        assert_eq!(measurements.measured_count(), 2);
+        assert_eq!(measurements.values[0], Some(Vec3::new(0.5, 0.52, 0.6)));
+        assert_eq!(measurements.values[7], Some(Vec3::new(95.0, 100.0, 108.9)));
+
+        // Headerless rows, round-tripped through the export format
+        let mut complete = PatchMeasurements::new(3);
+        for index in 0..complete.patch_count() {
+            complete.values[index] = Some(complete.patch_color(index) * 80.0);
+        }
+        let body: String = complete
+            .to_csv()
+            .lines()
+            .skip(1)
+            .map(|l| l.to_string() + "\n")
+            .collect();
+        let imported = PatchMeasurements::from_csv(&body, 3).unwrap();
+        assert!(imported.is_complete());
+        for (a, b) in imported.values.iter().zip(&complete.values) {
+            assert!(a.unwrap().abs_diff_eq(b.unwrap(), 1e-3));
+        }
+
+        assert!(PatchMeasurements::from_csv("R,G,B,X,Y,Z\n1,2,oops,4,5,6\n", 3).is_err());
+        assert!(PatchMeasurements::from_csv("R,G,B,X,Y,Z\n", 3).is_err());
+    }
+
+    #[test]
+    fn test_match_two_projectors() {
+        let bright = SyntheticProjector {
+            primaries: Mat3::from_cols(
+                Vec3::new(41.2, 21.3, 1.9),
+                Vec3::new(35.8, 71.5, 11.9),
+                Vec3::new(18.0, 7.2, 95.0),
+            ) * 1.3,
+            gamma: 2.2,
+            black: Vec3::new(0.3, 0.3, 0.35),
+        };
+        let dim = SyntheticProjector {
+            primaries: Mat3::from_cols(
+                Vec3::new(45.0, 23.0, 1.5),
+                Vec3::new(32.0, 68.0, 14.0),
+                Vec3::new(17.0, 9.0, 90.0),
+            ),
+            gamma: 2.5,
+            black: Vec3::new(0.5, 0.5, 0.6),
+        };
+
+        let responses = [
+            bright.measure(9).response().unwrap(),
+            dim.measure(9).response().unwrap(),
+        ];
+        let target = common_target(&responses).unwrap();
+        let luts = [
+            solve_correction_lut(&responses[0], &target, 17),
+            solve_correction_lut(&responses[1], &target, 17),
+        ];
+
+        // Both outputs now run from the brighter black to the dimmer white
+        let white = dim.xyz(Vec3::ONE).y;
+        let mut worst = 0.0f32;
+        for step in 0..=10 {
+            for color in [
+                Vec3::splat(step as f32 / 10.0),
+                Vec3::new(step as f32 / 10.0, 0.4, 0.2),
+                Vec3::new(0.7, step as f32 / 10.0, 0.5),
+            ] {
+                let a = bright.xyz(luts[0].apply(color));
+                let b = dim.xyz(luts[1].apply(color));
+                worst = worst.max((a - b).abs().max_element() / white);
+            }
+        }
+        assert!(worst < 0.02, "corrected outputs differ by {}", worst);
+
+        // Without correction they differ far more
+        let raw = (bright.xyz(Vec3::splat(0.5)) - dim.xyz(Vec3::splat(0.5))).y / white;
+        assert!(raw.abs() > 0.05);
+    }
+}
@@ -0,0 +1,389 @@
+//! Dome and Fulldome Output
+//!
+//! An output in dome mode treats its composed 2D content as a spherical
+//! image (equirectangular, a cubemap cross, or a fisheye domemaster) and
+//! resamples it into a fisheye domemaster, an equirectangular panorama, or
+//! the perspective slice of the dome lit by one projector.
+//!
+//! The resampling runs through a warp mesh: like [`MeshType::Sphere`], the
+//! mesh is a latitude/longitude (or rectangular) grid, but each vertex carries
+//! the view direction it shows instead of a texture coordinate. The renderer
+//! interpolates the directions and looks up the source per pixel, so seams
+//! and poles of the source projection don't tear the mesh.
+//!
+//! Dome space: +Y is the zenith, -Z the front of the dome, +X its right.
+//!
+//! [`MeshType::Sphere`]: crate::module::MeshType::Sphere
+
+use glam::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+/// How the composed content of a dome output maps onto the sphere
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DomeSource {
+    /// Latitude/longitude panorama: 360° across, zenith at the top edge
+    Equirectangular,
+    /// Horizontal cubemap cross (4x3 cells): top face above the front face,
+    /// then left, front, right and back faces, bottom face below the front
+    CubemapCross,
+    /// Fisheye domemaster with the zenith in the center and the front at
+    /// the bottom edge
+    Fisheye {
+        /// Field of view of the fisheye circle in degrees
+        fov: f32,
+    },
+}
+
+/// Projection written to a dome output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DomeProjection {
+    /// Fisheye domemaster: circle centered in the output, zenith in the
+    /// middle, front at the bottom edge
+    Fisheye {
+        /// Field of view of the fisheye circle in degrees (180 for a hemisphere)
+        fov: f32,
+    },
+    /// Latitude/longitude panorama covering the whole sphere
+    Equirectangular,
+    /// Perspective view of the part of the dome covered by one projector
+    Slice {
+        /// Direction to the right of the front, in degrees
+        yaw: f32,
+        /// Elevation above the horizon, in degrees
+        pitch: f32,
+        /// Clockwise rotation of the image, in degrees
+        roll: f32,
+        /// Horizontal field of view in degrees
+        h_fov: f32,
+        /// Vertical field of view in degrees
+        v_fov: f32,
+    },
+}
+
+/// Dome mode of an output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomeConfig {
+    /// Projection of the composed content
+    pub source: DomeSource,
+    /// Projection written to the output
+    pub projection: DomeProjection,
+    /// Forward tilt of the dome in degrees (0 for a level dome)
+    pub tilt: f32,
+    /// Rotation of the content around the zenith in degrees
+    pub rotation: f32,
+    /// Warp mesh resolution (latitude rings or grid rows)
+    pub segments: u32,
+}
+
+impl Default for DomeConfig {
+    fn default() -> Self {
+        Self {
+            source: DomeSource::Equirectangular,
+            projection: DomeProjection::Fisheye { fov: 180.0 },
+            tilt: 0.0,
+            rotation: 0.0,
+            segments: 32,
+        }
+    }
+}
+
+/// Vertex of a dome warp mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomeVertex {
+    /// Position on the output (0.0-1.0, origin top-left)
+    pub position: Vec2,
+    /// Direction shown at this position, in content space
+    pub direction: Vec3,
+}
+
+/// Warp mesh resampling the content of a dome output
+#[derive(Debug, Clone, Default)]
+pub struct DomeWarp {
+    /// Mesh vertices
+    pub vertices: Vec<DomeVertex>,
+    /// Triangle list indices
+    pub indices: Vec<u32>,
+}
+
+impl DomeConfig {
+    /// Rotation from dome space into content space
+    pub fn orientation(&self) -> Quat {
+        Quat::from_rotation_y(-self.rotation.to_radians())
+            * Quat::from_rotation_x(-self.tilt.to_radians())
+    }
+
+    /// Build the warp mesh for an output with the given aspect ratio
+    /// (width / height)
+    pub fn warp(&self, aspect: f32) -> DomeWarp {
+        let segments = self.segments.max(4);
+        let orientation = self.orientation();
+        let mut warp = match self.projection {
+            DomeProjection::Fisheye { fov } => fisheye_warp(fov, aspect, segments),
+            DomeProjection::Equirectangular => {
+                grid_warp(segments * 2, segments, equirectangular_direction)
+            }
+            DomeProjection::Slice {
+                yaw,
+                pitch,
+                roll,
+                h_fov,
+                v_fov,
+            } => {
+                let view = Quat::from_rotation_y(-yaw.to_radians())
+                    * Quat::from_rotation_x(pitch.to_radians())
+                    * Quat::from_rotation_z(-roll.to_radians());
+                let half = Vec2::new(
+                    (h_fov.clamp(1.0, 179.0).to_radians() / 2.0).tan(),
+                    (v_fov.clamp(1.0, 179.0).to_radians() / 2.0).tan(),
+                );
+                grid_warp(segments, segments, |uv| {
+                    let ray = Vec3::new(
+                        (uv.x * 2.0 - 1.0) * half.x,
+                        (1.0 - uv.y * 2.0) * half.y,
+                        -1.0,
+                    );
+                    view * ray.normalize()
+                })
+            }
+        };
+        for vertex in &mut warp.vertices {
+            vertex.direction = orientation * vertex.direction;
+        }
+        warp
+    }
+}
+
+/// Direction from the angle to the zenith and the azimuth (clockwise from
+/// the front, seen from inside)
+fn dome_direction(zenith_angle: f32, azimuth: f32) -> Vec3 {
+    let (sin_z, cos_z) = zenith_angle.sin_cos();
+    let (sin_a, cos_a) = azimuth.sin_cos();
+    Vec3::new(sin_z * sin_a, cos_z, -sin_z * cos_a)
+}
+
+fn equirectangular_direction(uv: Vec2) -> Vec3 {
+    dome_direction(uv.y * PI, (uv.x - 0.5) * TAU)
+}
+
+/// Polar grid of a fisheye circle, rings from the zenith outwards
+fn fisheye_warp(fov: f32, aspect: f32, segments: u32) -> DomeWarp {
+    let max_angle = fov.clamp(1.0, 360.0).to_radians() / 2.0;
+    // Circle inscribed in the output
+    let radius = if aspect >= 1.0 {
+        Vec2::new(0.5 / aspect, 0.5)
+    } else {
+        Vec2::new(0.5, 0.5 * aspect)
+    };
+    let rings = segments;
+    let sectors = segments * 2;
+
+    let mut warp = DomeWarp::default();
+    for ring in 0..=rings {
+        let t = ring as f32 / rings as f32;
+        for sector in 0..=sectors {
+            let azimuth = sector as f32 / sectors as f32 * TAU;
+            let (sin_a, cos_a) = azimuth.sin_cos();
+            warp.vertices.push(DomeVertex {
+                position: Vec2::new(0.5, 0.5) + Vec2::new(sin_a, cos_a) * radius * t,
+                direction: dome_direction(t * max_angle, azimuth),
+            });
+        }
+    }
+    push_grid_indices(&mut warp.indices, sectors, rings);
+    warp
+}
+
+/// Rectangular grid over the output
+fn grid_warp(cols: u32, rows: u32, direction: impl Fn(Vec2) -> Vec3) -> DomeWarp {
+    let mut warp = DomeWarp::default();
+    for row in 0..=rows {
+        for col in 0..=cols {
+            let position = Vec2::new(col as f32 / cols as f32, row as f32 / rows as f32);
+            warp.vertices.push(DomeVertex {
+                position,
+                direction: direction(position),
+            });
+        }
+    }
+    push_grid_indices(&mut warp.indices, cols, rows);
+    warp
+}
+
+fn push_grid_indices(indices: &mut Vec<u32>, cols: u32, rows: u32) {
+    for row in 0..rows {
+        for col in 0..cols {
+            let first = row * (cols + 1) + col;
+            let second = first + cols + 1;
+            indices.extend_from_slice(&[first, second, first + 1, second, second + 1, first + 1]);
+        }
+    }
+}
+
+impl DomeSource {
+    /// Texture coordinate of the content showing a direction; `None` outside
+    /// a fisheye source circle. Mirrors the lookup in `dome.wgsl`.
+    pub fn sample_uv(&self, direction: Vec3) -> Option<Vec2> {
+        let d = direction.normalize();
+        match *self {
+            DomeSource::Equirectangular => {
+                let azimuth = d.x.atan2(-d.z);
+                let elevation = d.y.clamp(-1.0, 1.0).asin();
+                Some(Vec2::new(azimuth / TAU + 0.5, 0.5 - elevation / PI))
+            }
+            DomeSource::Fisheye { fov } => {
+                let zenith_angle = d.y.clamp(-1.0, 1.0).acos();
+                let max_angle = fov.clamp(1.0, 360.0).to_radians() / 2.0;
+                if zenith_angle > max_angle {
+                    return None;
+                }
+                let radius = zenith_angle / max_angle * 0.5;
+                let azimuth = d.x.atan2(-d.z);
+                Some(Vec2::new(0.5, 0.5) + Vec2::new(azimuth.sin(), azimuth.cos()) * radius)
+            }
+            DomeSource::CubemapCross => {
+                let a = d.abs();
+                // (cell column, cell row, right, up) of the face
+                let (col, row, s, t) = if a.x >= a.y && a.x >= a.z {
+                    if d.x > 0.0 {
+                        (2.0, 1.0, d.z / a.x, d.y / a.x)
+                    } else {
+                        (0.0, 1.0, -d.z / a.x, d.y / a.x)
+                    }
+                } else if a.y >= a.z {
+                    if d.y > 0.0 {
+                        (1.0, 0.0, d.x / a.y, d.z / a.y)
+                    } else {
+                        (1.0, 2.0, d.x / a.y, -d.z / a.y)
+                    }
+                } else if d.z < 0.0 {
+                    (1.0, 1.0, d.x / a.z, d.y / a.z)
+                } else {
+                    (3.0, 1.0, -d.x / a.z, d.y / a.z)
+                };
+                Some(Vec2::new(
+                    (col + (s + 1.0) / 2.0) / 4.0,
+                    (row + (1.0 - t) / 2.0) / 3.0,
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Elevation in degrees above the dome horizon
+    fn elevation(direction: Vec3) -> f32 {
+        direction.normalize().y.clamp(-1.0, 1.0).asin().to_degrees()
+    }
+
+    fn barycentric_direction(warp: &DomeWarp, point: Vec2) -> Option<Vec3> {
+        for triangle in warp.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| warp.vertices[triangle[i] as usize]);
+            let v0 = b.position - a.position;
+            let v1 = c.position - a.position;
+            let v2 = point - a.position;
+            let denom = v0.perp_dot(v1);
+            if denom.abs() < 1e-12 {
+                continue;
+            }
+            let v = v2.perp_dot(v1) / denom;
+            let w = v0.perp_dot(v2) / denom;
+            let u = 1.0 - v - w;
+            if u >= -1e-5 && v >= -1e-5 && w >= -1e-5 {
+                return Some((a.direction * u + b.direction * v + c.direction * w).normalize());
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_fisheye_domemaster_layout() {
+        let warp = DomeConfig::default().warp(1.0);
+        let zenith = barycentric_direction(&warp, Vec2::new(0.5, 0.5)).unwrap();
+        assert!(zenith.abs_diff_eq(Vec3::Y, 1e-4));
+
+        // Front at the bottom edge, right on the right, horizon on the circle
+        let front = barycentric_direction(&warp, Vec2::new(0.5, 0.999)).unwrap();
+        assert!(front.z < -0.99 && elevation(front).abs() < 1.0);
+        let right = barycentric_direction(&warp, Vec2::new(0.999, 0.5)).unwrap();
+        assert!(right.x > 0.99);
+
+        // Corners are outside the circle
+        assert!(barycentric_direction(&warp, Vec2::new(0.02, 0.02)).is_none());
+
+        // Wide outputs keep the circle round
+        let wide = DomeConfig::default().warp(2.0);
+        assert!(barycentric_direction(&wide, Vec2::new(0.1, 0.5)).is_none());
+        assert!(barycentric_direction(&wide, Vec2::new(0.5, 0.02)).is_some());
+    }
+
+    #[test]
+    fn test_slice_and_orientation() {
+        let config = DomeConfig {
+            projection: DomeProjection::Slice {
+                yaw: 90.0,
+                pitch: 30.0,
+                roll: 0.0,
+                h_fov: 60.0,
+                v_fov: 40.0,
+            },
+            ..DomeConfig::default()
+        };
+        let warp = config.warp(1.5);
+        let center = barycentric_direction(&warp, Vec2::new(0.5, 0.5)).unwrap();
+        assert!(center.x > 0.8 && (elevation(center) - 30.0).abs() < 0.5);
+        // Top of the slice looks higher up the dome
+        let top = barycentric_direction(&warp, Vec2::new(0.5, 0.0)).unwrap();
+        assert!((elevation(top) - 50.0).abs() < 0.5);
+
+        // Rotating the content turns the zenith view, tilting leans it forward
+        let tilted = DomeConfig {
+            tilt: 20.0,
+            ..DomeConfig::default()
+        };
+        let zenith = tilted.orientation() * Vec3::Y;
+        assert!(zenith.z < 0.0 && (elevation(zenith) - 70.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_source_lookup() {
+        let equirect = DomeSource::Equirectangular;
+        let front = equirect.sample_uv(-Vec3::Z).unwrap();
+        assert!(front.abs_diff_eq(Vec2::new(0.5, 0.5), 1e-5));
+        let zenith = equirect.sample_uv(Vec3::Y).unwrap();
+        assert!((zenith.y).abs() < 1e-5);
+        // Equirectangular output and source are inverses
+        for uv in [
+            Vec2::new(0.2, 0.3),
+            Vec2::new(0.8, 0.7),
+            Vec2::new(0.55, 0.1),
+        ] {
+            let back = equirect.sample_uv(equirectangular_direction(uv)).unwrap();
+            assert!(back.abs_diff_eq(uv, 1e-4), "{} -> {}", uv, back);
+        }
+
+        let fisheye = DomeSource::Fisheye { fov: 180.0 };
+        let bottom = fisheye.sample_uv(-Vec3::Z).unwrap();
+        assert!(bottom.abs_diff_eq(Vec2::new(0.5, 1.0), 1e-5));
+        assert!(fisheye.sample_uv(-Vec3::Y).is_none());
+
+        let cross = DomeSource::CubemapCross;
+        let cells = [
+            (-Vec3::Z, Vec2::new(1.5 / 4.0, 1.5 / 3.0)),
+            (Vec3::X, Vec2::new(2.5 / 4.0, 1.5 / 3.0)),
+            (-Vec3::X, Vec2::new(0.5 / 4.0, 1.5 / 3.0)),
+            (Vec3::Z, Vec2::new(3.5 / 4.0, 1.5 / 3.0)),
+            (Vec3::Y, Vec2::new(1.5 / 4.0, 0.5 / 3.0)),
+            (-Vec3::Y, Vec2::new(1.5 / 4.0, 2.5 / 3.0)),
+        ];
+        for (direction, uv) in cells {
+            assert!(cross.sample_uv(direction).unwrap().abs_diff_eq(uv, 1e-5));
+        }
+        // The top face meets the front face along its bottom edge
+        let edge = cross.sample_uv(Vec3::new(0.0, 1.0, -1.0001)).unwrap();
+        assert!(edge.abs_diff_eq(Vec2::new(1.5 / 4.0, 1.0 / 3.0), 1e-3));
+    }
+}
@@ -0,0 +1,351 @@
+//! HDR and Wide-Gamut Output Transforms
+//!
+//! Layer effect chains, masks and the output stage (mesh mapping, dome,
+//! slicing, color calibration and edge blending) run in linear Rec.709 light
+//! at 16-bit float precision, with 1.0 as SDR reference white, so values
+//! above 1.0 survive until the output transform. Only the sources are still
+//! 8-bit sRGB textures, decoded to linear light when sampled. The output
+//! transform of each output then turns that light into the signal its
+//! display expects: SDR outputs are optionally tone-mapped and encoded as
+//! sRGB or Rec.709, HDR outputs are converted to the Rec.2020 gamut and
+//! encoded with the PQ (SMPTE ST 2084) or HLG curve.
+//! HDR outputs get a 10-bit window surface that takes the PQ or HLG signal
+//! as it is, or a float surface that takes linear (scRGB) light, where the
+//! display and GPU offer one. Targets that can't carry an HDR signal (8-bit
+//! sRGB window surfaces and previews) get the sRGB encoding instead.
+//!
+//! The functions here are the reference for `output_transform.wgsl`.
+
+use glam::{Mat3, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Signal encoding of an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputTransform {
+    /// SDR, sRGB primaries and transfer curve (monitors, most projectors)
+    #[default]
+    Srgb,
+    /// SDR, Rec.709 primaries and camera transfer curve (broadcast gear)
+    Rec709,
+    /// HDR10: Rec.2020 primaries, PQ transfer curve
+    Rec2020Pq,
+    /// Hybrid log-gamma: Rec.2020 primaries, HLG transfer curve
+    Hlg,
+}
+
+impl OutputTransform {
+    /// All output transforms
+    pub const ALL: [OutputTransform; 4] = [Self::Srgb, Self::Rec709, Self::Rec2020Pq, Self::Hlg];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::Rec709 => "Rec.709",
+            Self::Rec2020Pq => "Rec.2020 PQ",
+            Self::Hlg => "HLG",
+        }
+    }
+
+    /// Whether the transform carries light above SDR reference white
+    pub fn is_hdr(&self) -> bool {
+        matches!(self, Self::Rec2020Pq | Self::Hlg)
+    }
+}
+
+/// Tone-mapping curve compressing HDR light into the SDR range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ToneMapping {
+    /// Clip everything above reference white
+    #[default]
+    None,
+    /// Reinhard curve, `c / (1 + c)`
+    Reinhard,
+    /// ACES filmic curve (Narkowicz fit)
+    Aces,
+}
+
+impl ToneMapping {
+    /// All tone-mapping curves
+    pub const ALL: [ToneMapping; 3] = [Self::None, Self::Reinhard, Self::Aces];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "None (Clip)",
+            Self::Reinhard => "Reinhard",
+            Self::Aces => "ACES Filmic",
+        }
+    }
+
+    /// Apply the curve to linear light
+    pub fn apply(&self, color: Vec3) -> Vec3 {
+        let color = color.max(Vec3::ZERO);
+        match self {
+            Self::None => color,
+            Self::Reinhard => color / (Vec3::ONE + color),
+            Self::Aces => {
+                let numerator = color * (color * 2.51 + 0.03);
+                let denominator = color * (color * 2.43 + 0.59) + 0.14;
+                numerator / denominator
+            }
+        }
+    }
+}
+
+/// Output transform settings of one output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputTransformConfig {
+    /// Signal encoding
+    pub transform: OutputTransform,
+    /// Tone mapping; only applies to SDR transforms
+    pub tone_mapping: ToneMapping,
+    /// Exposure adjustment in stops, applied before tone mapping
+    pub exposure: f32,
+    /// Luminance of SDR reference white on HDR outputs, in nits
+    pub paper_white_nits: f32,
+    /// Peak luminance of the HDR display, in nits
+    pub peak_nits: f32,
+}
+
+impl Default for OutputTransformConfig {
+    fn default() -> Self {
+        Self {
+            transform: OutputTransform::Srgb,
+            tone_mapping: ToneMapping::None,
+            exposure: 0.0,
+            paper_white_nits: 203.0, // ITU-R BT.2408 reference white
+            peak_nits: 1000.0,
+        }
+    }
+}
+
+/// Luminance of 1.0 on a float (scRGB) surface
+const SCRGB_WHITE_NITS: f32 = 80.0;
+
+/// Rec.709 to Rec.2020 primaries, for linear light (ITU-R BT.2087)
+const REC709_TO_REC2020: Mat3 = Mat3::from_cols_array(&[
+    0.6274, 0.0691, 0.0164, // column 0
+    0.3293, 0.9195, 0.0880, // column 1
+    0.0433, 0.0114, 0.8956, // column 2
+]);
+
+/// Rec.2020 luminance weights
+const REC2020_LUMA: Vec3 = Vec3::new(0.2627, 0.6780, 0.0593);
+
+impl OutputTransformConfig {
+    /// Exposure as a linear gain
+    pub fn exposure_gain(&self) -> f32 {
+        self.exposure.exp2()
+    }
+
+    /// Whether the transform leaves sRGB content unchanged, so the output
+    /// can be drawn straight to an sRGB target
+    pub fn is_identity(&self) -> bool {
+        self.transform == OutputTransform::Srgb
+            && self.tone_mapping == ToneMapping::None
+            && self.exposure == 0.0
+    }
+
+    /// The transform for a target that can only carry SDR: HDR encodings
+    /// fall back to sRGB, keeping the exposure
+    pub fn for_sdr_target(&self) -> Self {
+        if self.transform.is_hdr() {
+            Self {
+                transform: OutputTransform::Srgb,
+                ..self.clone()
+            }
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Encode linear Rec.709 light (1.0 = reference white) into the signal
+    /// of this output, every channel in 0.0-1.0
+    pub fn encode(&self, linear: Vec3) -> Vec3 {
+        let color = linear.max(Vec3::ZERO) * self.exposure_gain();
+        match self.transform {
+            OutputTransform::Srgb => self.tone_mapped(color).map(srgb_oetf),
+            OutputTransform::Rec709 => self.tone_mapped(color).map(rec709_oetf),
+            OutputTransform::Rec2020Pq => {
+                let nits = self.to_rec2020_nits(color);
+                (nits / 10_000.0).map(pq_oetf)
+            }
+            OutputTransform::Hlg => {
+                let display = self.to_rec2020_nits(color) / self.peak_nits.max(1.0);
+                hlg_inverse_ootf(display, self.peak_nits.max(1.0)).map(hlg_oetf)
+            }
+        }
+    }
+
+    /// Linear light for a float (scRGB) surface, whose display encodes it
+    /// itself: Rec.709 primaries in units of 80 nits, limited to the peak
+    pub fn encode_scrgb(&self, linear: Vec3) -> Vec3 {
+        let nits = linear.max(Vec3::ZERO) * self.exposure_gain() * self.paper_white_nits.max(1.0);
+        nits.min(Vec3::splat(self.peak_nits.max(1.0))) / SCRGB_WHITE_NITS
+    }
+
+    fn tone_mapped(&self, color: Vec3) -> Vec3 {
+        self.tone_mapping.apply(color).clamp(Vec3::ZERO, Vec3::ONE)
+    }
+
+    /// Rec.2020 display light in nits, limited to the display's peak
+    fn to_rec2020_nits(&self, color: Vec3) -> Vec3 {
+        let nits = REC709_TO_REC2020 * color * self.paper_white_nits.max(1.0);
+        nits.clamp(Vec3::ZERO, Vec3::splat(self.peak_nits.max(1.0)))
+    }
+}
+
+/// sRGB transfer curve (IEC 61966-2-1)
+pub fn srgb_oetf(v: f32) -> f32 {
+    if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Rec.709 camera transfer curve (ITU-R BT.709)
+pub fn rec709_oetf(v: f32) -> f32 {
+    if v < 0.018 {
+        v * 4.5
+    } else {
+        1.099 * v.powf(0.45) - 0.099
+    }
+}
+
+/// PQ inverse EOTF (SMPTE ST 2084); `v` is display light over 10,000 nits
+pub fn pq_oetf(v: f32) -> f32 {
+    const M1: f32 = 2610.0 / 16384.0;
+    const M2: f32 = 2523.0 / 4096.0 * 128.0;
+    const C1: f32 = 3424.0 / 4096.0;
+    const C2: f32 = 2413.0 / 4096.0 * 32.0;
+    const C3: f32 = 2392.0 / 4096.0 * 32.0;
+    let p = v.clamp(0.0, 1.0).powf(M1);
+    ((C1 + C2 * p) / (1.0 + C3 * p)).powf(M2)
+}
+
+/// HLG transfer curve (ITU-R BT.2100); `v` is normalized scene light
+pub fn hlg_oetf(v: f32) -> f32 {
+    const A: f32 = 0.178_832_77;
+    const B: f32 = 0.284_668_92;
+    const C: f32 = 0.559_910_7;
+    let v = v.clamp(0.0, 1.0);
+    if v <= 1.0 / 12.0 {
+        (3.0 * v).sqrt()
+    } else {
+        A * (12.0 * v - B).ln() + C
+    }
+}
+
+/// Scene light of normalized HLG display light, undoing the system gamma
+/// of a display with the given peak luminance (ITU-R BT.2100)
+fn hlg_inverse_ootf(display: Vec3, peak_nits: f32) -> Vec3 {
+    let gamma = 1.2 + 0.42 * (peak_nits / 1000.0).log10();
+    let luminance = display.dot(REC2020_LUMA);
+    if luminance <= 0.0 {
+        return Vec3::ZERO;
+    }
+    display * luminance.powf((1.0 - gamma) / gamma)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sdr_transforms() {
+        let config = OutputTransformConfig::default();
+        let encoded = config.encode(Vec3::new(0.0, 0.5, 1.0));
+        assert!(encoded.x.abs() < 1e-6);
+        assert!((encoded.y - 0.7354).abs() < 0.001);
+        assert!((encoded.z - 1.0).abs() < 1e-5);
+        // Without tone mapping everything above white clips
+        assert_eq!(config.encode(Vec3::splat(4.0)), config.encode(Vec3::ONE));
+
+        let rec709 = OutputTransformConfig {
+            transform: OutputTransform::Rec709,
+            ..Default::default()
+        };
+        assert!((rec709.encode(Vec3::splat(0.01)).x - 0.045).abs() < 1e-5);
+
+        // One stop up doubles the light
+        let brighter = OutputTransformConfig {
+            exposure: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            brighter.encode(Vec3::splat(0.25)),
+            config.encode(Vec3::splat(0.5))
+        );
+    }
+
+    #[test]
+    fn test_identity_and_sdr_fallback() {
+        assert!(OutputTransformConfig::default().is_identity());
+        let exposed = OutputTransformConfig {
+            exposure: 0.5,
+            ..Default::default()
+        };
+        assert!(!exposed.is_identity());
+
+        let pq = OutputTransformConfig {
+            transform: OutputTransform::Rec2020Pq,
+            ..Default::default()
+        };
+        assert!(!pq.is_identity());
+        assert_eq!(pq.for_sdr_target(), OutputTransformConfig::default());
+        assert_eq!(exposed.for_sdr_target(), exposed);
+    }
+
+    #[test]
+    fn test_tone_mapping_keeps_highlights() {
+        for tone_mapping in [ToneMapping::Reinhard, ToneMapping::Aces] {
+            let config = OutputTransformConfig {
+                tone_mapping,
+                ..Default::default()
+            };
+            let levels = [0.5, 1.0, 2.0, 4.0, 16.0].map(|v| config.encode(Vec3::splat(v)).x);
+            assert!(levels.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(levels.iter().all(|level| *level <= 1.0));
+        }
+    }
+
+    #[test]
+    fn test_hdr_transforms() {
+        let pq = OutputTransformConfig {
+            transform: OutputTransform::Rec2020Pq,
+            paper_white_nits: 100.0,
+            peak_nits: 10_000.0,
+            ..Default::default()
+        };
+        // 100 nits is a PQ signal of about 0.508, 10,000 nits is full scale
+        assert!((pq.encode(Vec3::ONE).x - 0.5081).abs() < 0.001);
+        assert!((pq.encode(Vec3::splat(100.0)).x - 1.0).abs() < 1e-4);
+        // Light above the display's peak is limited to the peak
+        let limited = OutputTransformConfig {
+            peak_nits: 1000.0,
+            ..pq.clone()
+        };
+        assert_eq!(
+            limited.encode(Vec3::splat(50.0)),
+            limited.encode(Vec3::splat(10.0))
+        );
+
+        // BT.2408: reference white at 75% HLG signal on a 1000 nit display
+        let hlg = OutputTransformConfig {
+            transform: OutputTransform::Hlg,
+            ..Default::default()
+        };
+        assert!((hlg.encode(Vec3::ONE).x - 0.75).abs() < 0.005);
+        assert!(OutputTransform::Hlg.is_hdr() && !OutputTransform::Rec709.is_hdr());
+
+        // Pure Rec.709 red is inside Rec.2020, so green and blue stay non-zero
+        let red = pq.encode(Vec3::X);
+        assert!(red.x > red.y && red.y > 0.0 && red.z > 0.0);
+
+        // Float surfaces take linear light, 1.0 being 80 nits
+        assert_eq!(pq.encode_scrgb(Vec3::ONE), Vec3::splat(1.25));
+        assert_eq!(limited.encode_scrgb(Vec3::splat(50.0)), Vec3::splat(12.5));
+    }
+}
@@ -18,11 +18,16 @@ pub mod layer;
 pub use layer::{BlendMode, Composition, Layer, LayerManager, ResizeMode, Transform};
 
 // Phase 2: Multi-output and projection mapping
+pub mod color_match;
+pub mod dome;
+pub mod hdr;
 pub mod mapping;
 pub mod mesh;
 pub mod monitor;
 pub mod output;
 pub mod paint;
+pub mod projection3d;
+pub mod structured_light;
 
 // Phase 3: Effects Pipeline
 pub mod animation;
@@ -69,6 +74,9 @@ pub use effect_animation::{
 pub use assignment::{Assignment, AssignmentManager, ControlSource, ControlTarget};
 
 // Audio System
+pub use audio::beat_grid::{BeatDivision, BeatGrid, BeatGridConfig, BeatGridState};
+pub use audio::routing::{AudioChannelRoute, AudioRouter};
+pub use audio::tempo::{TempoConfig, TempoService, TempoSource};
 pub use audio::{
     AudioAnalysis, AudioAnalyzer, AudioConfig, AudioMappingType, AudioReactiveMapping, AudioSource,
     FrequencyBand,
@@ -90,8 +98,13 @@ pub use recent_effect_configs::{
 };
 
 // Geometry & Meshes
-pub use mesh::{keystone, BezierPatch, Mesh, MeshType, MeshVertex, VertexId};
+pub use mesh::{
+    keystone, BezierPatch, Mesh, MeshType, MeshVertex, VertexId, WarpInterpolation, WarpSurface,
+    DEFAULT_WARP_SUBDIVISIONS,
+};
 pub use paint::{Paint, PaintId, PaintManager, PaintType};
+pub use projection3d::{ProjectorCalibration, SceneModel};
+pub use structured_light::{GrayCodePattern, ProjectorWarp};
 
 // Logging & Diagnostics
 pub use logging::LogConfig;
@@ -102,11 +115,14 @@ pub use module_eval::{
 };
 
 // Output & Display
+pub use color_match::PatchMeasurements;
+pub use dome::{DomeConfig, DomeProjection, DomeSource};
+pub use hdr::{OutputTransform, OutputTransformConfig, ToneMapping};
 pub use mapping::{Mapping, MappingId, MappingManager};
 pub use monitor::{MonitorInfo, MonitorTopology};
 pub use output::{
     CanvasRegion, ColorCalibration, EdgeBlendConfig, EdgeBlendZone, OutputConfig, OutputId,
-    OutputManager,
+    OutputManager, OutputSlice, SliceCrop, SliceLayout, SliceRect, SliceRotation,
 };
 
 // Shader Graph & Codegen
@@ -53,7 +53,7 @@ pub struct Mesh {
     /// List of vertices defining the mesh geometry
     pub vertices: Vec<MeshVertex>,
     /// Triangle indices (3 per triangle)
-    pub indices: Vec<u16>,
+    pub indices: Vec<u32>,
     /// Revision counter for detecting changes (not serialized)
     #[serde(skip)]
     pub revision: u64,
@@ -134,12 +134,12 @@ impl Mesh {
         let mut indices = Vec::with_capacity((segments * 3) as usize);
         for i in 1..segments {
             indices.push(0); // Center
-            indices.push(i as u16);
-            indices.push(((i % segments) + 1) as u16);
+            indices.push(i);
+            indices.push((i % segments) + 1);
         }
         // Close the fan
         indices.push(0);
-        indices.push(segments as u16);
+        indices.push(segments);
         indices.push(1);
 
         Self {
@@ -171,6 +171,13 @@ impl Mesh {
         self.indices.len() / 3
     }
 
+    /// Whether the mesh needs 32-bit indices on the GPU
+    ///
+    /// Meshes with up to 65,536 vertices are uploaded with 16-bit indices.
+    pub fn needs_u32_indices(&self) -> bool {
+        self.vertices.len() > u16::MAX as usize + 1
+    }
+
     /// Select/deselect all vertices
     pub fn select_all(&mut self, selected: bool) {
         for vertex in &mut self.vertices {
@@ -265,9 +272,9 @@ impl Mesh {
         // Create triangle indices (two triangles per grid cell)
         for row in 0..rows {
             for col in 0..cols {
-                let top_left = (row * (cols + 1) + col) as u16;
+                let top_left = row * (cols + 1) + col;
                 let top_right = top_left + 1;
-                let bottom_left = ((row + 1) * (cols + 1) + col) as u16;
+                let bottom_left = (row + 1) * (cols + 1) + col;
                 let bottom_right = bottom_left + 1;
 
                 // First triangle (top-left, bottom-left, top-right)
@@ -404,6 +411,276 @@ impl Default for BezierPatch {
     }
 }
 
+/// How the control lattice of a [`WarpSurface`] shapes the surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WarpInterpolation {
+    /// Bicubic Bezier patches sharing their edge points; the lattice holds
+    /// 3k + 1 points per axis and handles at patch seams are kept opposite
+    #[default]
+    Bezier,
+    /// Catmull-Rom spline passing through every lattice point
+    CatmullRom,
+}
+
+/// Default number of mesh cells per patch along each axis
+pub const DEFAULT_WARP_SUBDIVISIONS: u32 = 8;
+
+/// Multi-patch warp surface over an arbitrary control lattice
+///
+/// Neighbouring patches meet with matching tangents (C1), and the mesh
+/// density is set by `subdivisions` independently of the lattice size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarpSurface {
+    /// Control points per lattice row
+    pub columns: u32,
+    /// Control points per lattice column
+    pub rows: u32,
+    /// Control points, row by row
+    pub points: Vec<Vec2>,
+    /// How the lattice is interpolated
+    pub interpolation: WarpInterpolation,
+    /// Mesh cells per patch along each axis
+    pub subdivisions: u32,
+}
+
+impl WarpSurface {
+    /// Create a flat surface over the unit square
+    ///
+    /// The lattice size is rounded up to the nearest size valid for the
+    /// interpolation.
+    pub fn new(columns: u32, rows: u32, interpolation: WarpInterpolation) -> Self {
+        let columns = Self::valid_size(columns, interpolation);
+        let rows = Self::valid_size(rows, interpolation);
+        let mut points = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for col in 0..columns {
+                points.push(Vec2::new(
+                    col as f32 / (columns - 1) as f32,
+                    row as f32 / (rows - 1) as f32,
+                ));
+            }
+        }
+
+        Self {
+            columns,
+            rows,
+            points,
+            interpolation,
+            subdivisions: DEFAULT_WARP_SUBDIVISIONS,
+        }
+    }
+
+    /// Smallest lattice size of at least `size` points valid for `interpolation`
+    pub fn valid_size(size: u32, interpolation: WarpInterpolation) -> u32 {
+        match interpolation {
+            WarpInterpolation::Bezier => (size.max(4) - 1).div_ceil(3) * 3 + 1,
+            WarpInterpolation::CatmullRom => size.max(2),
+        }
+    }
+
+    /// Lattice size along one axis holding `patches` patches
+    pub fn lattice_size(patches: u32, interpolation: WarpInterpolation) -> u32 {
+        match interpolation {
+            WarpInterpolation::Bezier => patches.max(1) * 3 + 1,
+            WarpInterpolation::CatmullRom => patches.max(1) + 1,
+        }
+    }
+
+    /// Create a Catmull-Rom surface passing through the vertices of a grid
+    /// mesh made by [`Mesh::create_grid`] with `rows` x `cols` cells
+    ///
+    /// Returns `None` if the vertex count does not match the grid size.
+    pub fn from_grid_mesh(mesh: &Mesh, rows: u32, cols: u32) -> Option<Self> {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        if mesh.vertices.len() != ((rows + 1) * (cols + 1)) as usize {
+            return None;
+        }
+
+        Some(Self {
+            columns: cols + 1,
+            rows: rows + 1,
+            points: mesh.vertices.iter().map(|v| v.position).collect(),
+            interpolation: WarpInterpolation::CatmullRom,
+            subdivisions: DEFAULT_WARP_SUBDIVISIONS,
+        })
+    }
+
+    /// Whether the lattice size matches the point count and interpolation
+    pub fn is_valid(&self) -> bool {
+        self.points.len() == (self.columns * self.rows) as usize
+            && self.columns == Self::valid_size(self.columns, self.interpolation)
+            && self.rows == Self::valid_size(self.rows, self.interpolation)
+    }
+
+    /// Number of patches along each axis (horizontal, vertical)
+    pub fn patch_counts(&self) -> (u32, u32) {
+        match self.interpolation {
+            WarpInterpolation::Bezier => ((self.columns - 1) / 3, (self.rows - 1) / 3),
+            WarpInterpolation::CatmullRom => (self.columns - 1, self.rows - 1),
+        }
+    }
+
+    /// Lattice point, extrapolated linearly past the lattice edges
+    fn lattice(&self, col: i64, row: i64) -> Vec2 {
+        let (columns, rows) = (self.columns as i64, self.rows as i64);
+        if col < 0 {
+            return self.lattice(0, row) * 2.0 - self.lattice(1, row);
+        }
+        if col >= columns {
+            return self.lattice(columns - 1, row) * 2.0 - self.lattice(columns - 2, row);
+        }
+        if row < 0 {
+            return self.lattice(col, 0) * 2.0 - self.lattice(col, 1);
+        }
+        if row >= rows {
+            return self.lattice(col, rows - 1) * 2.0 - self.lattice(col, rows - 2);
+        }
+        self.points[(row * columns + col) as usize]
+    }
+
+    /// Bezier control point with the handles next to interior patch seams
+    /// made opposite and equally long, so both patches share the tangent
+    fn continuous(&self, col: i64, row: i64) -> Vec2 {
+        let along_row = |col: i64, row: i64| match seam_handle(col, self.columns as i64) {
+            Some((seam, side)) => {
+                let tangent = (self.lattice(seam + 1, row) - self.lattice(seam - 1, row)) * 0.5;
+                self.lattice(seam, row) + tangent * side
+            }
+            None => self.lattice(col, row),
+        };
+        match seam_handle(row, self.rows as i64) {
+            Some((seam, side)) => {
+                let tangent = (along_row(col, seam + 1) - along_row(col, seam - 1)) * 0.5;
+                along_row(col, seam) + tangent * side
+            }
+            None => along_row(col, row),
+        }
+    }
+
+    /// Bicubic Bezier patch at patch column `px`, patch row `py`
+    pub fn patch(&self, px: u32, py: u32) -> BezierPatch {
+        let mut patch = BezierPatch::new();
+        let (px, py) = (px as i64, py as i64);
+        match self.interpolation {
+            WarpInterpolation::Bezier => {
+                for (i, row) in patch.control_points.iter_mut().enumerate() {
+                    for (j, point) in row.iter_mut().enumerate() {
+                        *point = self.continuous(px * 3 + j as i64, py * 3 + i as i64);
+                    }
+                }
+            }
+            WarpInterpolation::CatmullRom => {
+                let mut horizontal = [[Vec2::ZERO; 4]; 4];
+                for (i, row) in horizontal.iter_mut().enumerate() {
+                    let span: [Vec2; 4] =
+                        std::array::from_fn(|j| self.lattice(px - 1 + j as i64, py - 1 + i as i64));
+                    *row = catmull_rom_to_bezier(span);
+                }
+                #[allow(clippy::needless_range_loop)]
+                for j in 0..4 {
+                    let column = catmull_rom_to_bezier(std::array::from_fn(|i| horizontal[i][j]));
+                    for (i, point) in column.into_iter().enumerate() {
+                        patch.control_points[i][j] = point;
+                    }
+                }
+            }
+        }
+        patch
+    }
+
+    /// Patch index and local parameter of a surface parameter along one axis
+    fn locate(t: f32, patches: u32) -> (u32, f32) {
+        let scaled = t.clamp(0.0, 1.0) * patches as f32;
+        let index = (scaled.floor() as u32).min(patches - 1);
+        (index, scaled - index as f32)
+    }
+
+    /// Evaluate the surface at parametric coordinates (u, v) in [0, 1]
+    pub fn evaluate(&self, u: f32, v: f32) -> Vec2 {
+        let (patches_x, patches_y) = self.patch_counts();
+        let (px, tu) = Self::locate(u, patches_x);
+        let (py, tv) = Self::locate(v, patches_y);
+        self.patch(px, py).evaluate(tu, tv)
+    }
+
+    /// Write the seam handles that [`Self::patch`] uses back into the
+    /// lattice, so the stored handles match the rendered surface
+    pub fn enforce_continuity(&mut self) {
+        if self.interpolation != WarpInterpolation::Bezier || !self.is_valid() {
+            return;
+        }
+        let columns = self.columns as i64;
+        self.points = (0..self.points.len() as i64)
+            .map(|i| self.continuous(i % columns, i / columns))
+            .collect();
+    }
+
+    /// Resample the surface onto a new lattice size and interpolation,
+    /// keeping its shape as closely as the new lattice allows
+    pub fn resample(&self, columns: u32, rows: u32, interpolation: WarpInterpolation) -> Self {
+        let mut surface = Self::new(columns, rows, interpolation);
+        surface.subdivisions = self.subdivisions;
+        if self.is_valid() {
+            for point in &mut surface.points {
+                *point = self.evaluate(point.x, point.y);
+            }
+        }
+        surface
+    }
+
+    /// The control lattice as a grid mesh, for display and editing
+    pub fn control_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::create_grid(self.rows.max(2) - 1, self.columns.max(2) - 1);
+        for (vertex, point) in mesh.vertices.iter_mut().zip(&self.points) {
+            vertex.position = *point;
+        }
+        mesh
+    }
+
+    /// Tessellate the surface into a grid mesh with `subdivisions` cells
+    /// per patch; a plain quad if the lattice is invalid
+    pub fn to_mesh(&self) -> Mesh {
+        if !self.is_valid() {
+            return Mesh::quad();
+        }
+
+        let (patches_x, patches_y) = self.patch_counts();
+        let patches: Vec<BezierPatch> = (0..patches_y)
+            .flat_map(|py| (0..patches_x).map(move |px| (px, py)))
+            .map(|(px, py)| self.patch(px, py))
+            .collect();
+
+        let subdivisions = self.subdivisions.max(1);
+        let mut mesh = Mesh::create_grid(patches_y * subdivisions, patches_x * subdivisions);
+        for vertex in &mut mesh.vertices {
+            let (px, tu) = Self::locate(vertex.tex_coords.x, patches_x);
+            let (py, tv) = Self::locate(vertex.tex_coords.y, patches_y);
+            vertex.position = patches[(py * patches_x + px) as usize].evaluate(tu, tv);
+        }
+        mesh
+    }
+}
+
+/// Seam and side (-1 before, 1 after) of a Bezier handle next to an
+/// interior patch seam
+fn seam_handle(index: i64, size: i64) -> Option<(i64, f32)> {
+    match index % 3 {
+        2 if index + 1 < size - 1 => Some((index + 1, -1.0)),
+        1 if index > 1 => Some((index - 1, 1.0)),
+        _ => None,
+    }
+}
+
+/// Bezier control points of the Catmull-Rom segment between `p[1]` and `p[2]`
+fn catmull_rom_to_bezier(p: [Vec2; 4]) -> [Vec2; 4] {
+    [
+        p[1],
+        p[1] + (p[2] - p[0]) / 6.0,
+        p[2] - (p[3] - p[1]) / 6.0,
+        p[2],
+    ]
+}
+
 /// Keystone correction utilities
 pub mod keystone {
     use super::*;
@@ -479,6 +756,20 @@ mod phase2_tests {
         let mesh = Mesh::create_grid(3, 3);
         assert_eq!(mesh.vertex_count(), 16); // 4x4 vertices
         assert_eq!(mesh.triangle_count(), 18); // 3x3 cells * 2 triangles
+        assert!(!mesh.needs_u32_indices());
+    }
+
+    #[test]
+    fn test_dense_grid_uses_u32_indices() {
+        // 301 x 301 = 90,601 vertices, past the 16-bit range
+        let mesh = Mesh::create_grid(300, 300);
+        assert!(mesh.needs_u32_indices());
+        let last = mesh.vertex_count() as u32 - 1;
+        assert_eq!(mesh.indices.iter().max(), Some(&last));
+        assert!(mesh
+            .indices
+            .iter()
+            .all(|i| (*i as usize) < mesh.vertex_count()));
     }
 
     #[test]
@@ -515,6 +806,81 @@ mod phase2_tests {
         assert!((patch.evaluate(0.0, 1.0) - corners[3]).length() < 0.001);
     }
 
+    #[test]
+    fn test_warp_surface_flat() {
+        for interpolation in [WarpInterpolation::Bezier, WarpInterpolation::CatmullRom] {
+            let surface = WarpSurface::new(5, 3, interpolation);
+            assert!(surface.is_valid());
+            let p = surface.evaluate(0.3, 0.6);
+            assert!((p - Vec2::new(0.3, 0.6)).length() < 0.001);
+
+            let (patches_x, patches_y) = surface.patch_counts();
+            let mesh = surface.to_mesh();
+            let cells = DEFAULT_WARP_SUBDIVISIONS;
+            assert_eq!(
+                mesh.vertex_count() as u32,
+                (patches_x * cells + 1) * (patches_y * cells + 1)
+            );
+        }
+
+        let surface = WarpSurface::new(5, 3, WarpInterpolation::Bezier);
+        assert_eq!((surface.columns, surface.rows), (7, 4));
+        assert_eq!(surface.patch_counts(), (2, 1));
+        assert_eq!(WarpSurface::lattice_size(2, WarpInterpolation::Bezier), 7);
+        assert_eq!(
+            WarpSurface::lattice_size(2, WarpInterpolation::CatmullRom),
+            3
+        );
+    }
+
+    #[test]
+    fn test_warp_surface_c1_continuity() {
+        let tangent_jump = |surface: &WarpSurface, seam: f32| {
+            let h = 0.0005;
+            let before = (surface.evaluate(seam, 0.4) - surface.evaluate(seam - h, 0.4)) / h;
+            let after = (surface.evaluate(seam + h, 0.4) - surface.evaluate(seam, 0.4)) / h;
+            (after - before).length()
+        };
+
+        let mut catmull_rom = WarpSurface::new(4, 3, WarpInterpolation::CatmullRom);
+        catmull_rom.points[5] += Vec2::new(0.1, -0.05);
+        catmull_rom.points[2] += Vec2::new(-0.08, 0.04);
+        assert!(tangent_jump(&catmull_rom, 1.0 / 3.0) < 0.05);
+        assert!(tangent_jump(&catmull_rom, 2.0 / 3.0) < 0.05);
+
+        // Drag only the handle before the seam; the patch after follows
+        let mut bezier = WarpSurface::new(7, 4, WarpInterpolation::Bezier);
+        bezier.points[7 + 2] += Vec2::new(-0.05, 0.1);
+        assert!(tangent_jump(&bezier, 0.5) < 0.05);
+
+        let before = bezier.evaluate(0.7, 0.4);
+        bezier.enforce_continuity();
+        assert!((bezier.evaluate(0.7, 0.4) - before).length() < 0.001);
+    }
+
+    #[test]
+    fn test_warp_surface_grid_round_trip() {
+        let mut grid = Mesh::create_grid(2, 3);
+        grid.vertices[5].position += Vec2::new(0.05, 0.02);
+        assert!(WarpSurface::from_grid_mesh(&grid, 3, 3).is_none());
+
+        let mut surface = WarpSurface::from_grid_mesh(&grid, 2, 3).unwrap();
+        assert_eq!((surface.columns, surface.rows), (4, 3));
+        assert_eq!(surface.patch_counts(), (3, 2));
+
+        // Catmull-Rom passes through every grid vertex
+        surface.subdivisions = 1;
+        let mesh = surface.to_mesh();
+        for (a, b) in mesh.vertices.iter().zip(&grid.vertices) {
+            assert!((a.position - b.position).length() < 0.001);
+        }
+        assert_eq!(surface.control_mesh().vertices.len(), grid.vertices.len());
+
+        let resampled = surface.resample(7, 7, WarpInterpolation::Bezier);
+        assert!(resampled.is_valid());
+        assert!((resampled.evaluate(0.0, 0.0) - surface.evaluate(0.0, 0.0)).length() < 0.001);
+    }
+
     #[test]
     fn test_keystone_application() {
         let mut mesh = Mesh::quad();
@@ -0,0 +1,281 @@
+//!
+//! Vendor-neutral light output settings.
+//!
+
+use serde::{Deserialize, Serialize};
+
+/// Light system a light output drives
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LightBackend {
+    /// Entertainment area of the Hue bridge configured in the settings.
+    Hue,
+    /// Color fixtures in one DMX universe.
+    Dmx {
+        /// Network protocol.
+        protocol: DmxProtocol,
+        /// Art-Net destination (`host:port`); unused for sACN multicast.
+        target: String,
+        /// DMX universe.
+        universe: u16,
+        /// Patched fixtures.
+        fixtures: Vec<LightFixturePatch>,
+    },
+    /// WLED or other ESP32 pixel controller.
+    Wled {
+        /// Controller address (`host` or `host:port`).
+        host: String,
+        /// Number of LEDs on the controller.
+        led_count: u32,
+        /// Wire protocol.
+        #[serde(default)]
+        protocol: WledProtocol,
+        /// Strips mapped onto the canvas; without strips the LEDs are lamps
+        /// in a row.
+        #[serde(default)]
+        strips: Vec<LedStripPatch>,
+    },
+    /// Nanoleaf panels via External Control.
+    Nanoleaf {
+        /// Controller address.
+        host: String,
+        /// OpenAPI auth token.
+        auth_token: String,
+    },
+}
+
+impl LightBackend {
+    /// One unconfigured backend of every kind, in UI order.
+    pub fn presets() -> [LightBackend; 4] {
+        [
+            LightBackend::Hue,
+            LightBackend::Dmx {
+                protocol: DmxProtocol::ArtNet,
+                target: "255.255.255.255:6454".to_string(),
+                universe: 0,
+                fixtures: Vec::new(),
+            },
+            LightBackend::Wled {
+                host: String::new(),
+                led_count: 30,
+                protocol: WledProtocol::Drgb,
+                strips: Vec::new(),
+            },
+            LightBackend::Nanoleaf {
+                host: String::new(),
+                auth_token: String::new(),
+            },
+        ]
+    }
+
+    /// Short vendor name for the UI.
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightBackend::Hue => "Philips Hue",
+            LightBackend::Dmx { .. } => "DMX",
+            LightBackend::Wled { .. } => "WLED",
+            LightBackend::Nanoleaf { .. } => "Nanoleaf",
+        }
+    }
+}
+
+/// DMX network protocol
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum DmxProtocol {
+    /// Art-Net (UDP port 6454).
+    #[default]
+    ArtNet,
+    /// sACN / E1.31 multicast.
+    Sacn,
+}
+
+/// Wire protocol of a pixel controller
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WledProtocol {
+    /// WLED realtime, all LEDs in one packet (at most 490).
+    #[default]
+    Drgb,
+    /// WLED realtime with a start index, for longer strips.
+    Dnrgb,
+    /// Distributed Display Protocol (UDP port 4048).
+    Ddp,
+}
+
+impl WledProtocol {
+    /// All protocols, in UI order.
+    pub const ALL: [WledProtocol; 3] = [WledProtocol::Drgb, WledProtocol::Dnrgb, WledProtocol::Ddp];
+
+    /// Display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            WledProtocol::Drgb => "DRGB",
+            WledProtocol::Dnrgb => "DNRGB",
+            WledProtocol::Ddp => "DDP",
+        }
+    }
+
+    /// Most LEDs that can be addressed.
+    pub fn max_leds(&self) -> u32 {
+        match self {
+            WledProtocol::Drgb => 490,
+            // DNRGB start indices are 16-bit; DDP is held to the same
+            WledProtocol::Dnrgb | WledProtocol::Ddp => u16::MAX as u32,
+        }
+    }
+}
+
+/// An LED strip mapped from the canvas onto a pixel controller
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LedStripPatch {
+    /// Unique identifier.
+    pub id: String,
+    /// Controller pixel of the first LED (0-based).
+    pub start_pixel: u32,
+    /// Arrangement on the canvas.
+    pub layout: LedStripLayout,
+}
+
+/// Arrangement of a strip's LEDs on the canvas
+///
+/// Coordinates are normalized (0.0 - 1.0, origin top left).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LedStripLayout {
+    /// `count` LEDs evenly spaced from `start` to `end`.
+    Line {
+        /// First LED.
+        start: (f32, f32),
+        /// Last LED.
+        end: (f32, f32),
+        /// Number of LEDs.
+        count: u32,
+    },
+    /// A grid with every row wired left to right.
+    Matrix {
+        /// Top left corner of the area.
+        top_left: (f32, f32),
+        /// Bottom right corner of the area.
+        bottom_right: (f32, f32),
+        /// LEDs per row.
+        columns: u32,
+        /// Number of rows.
+        rows: u32,
+    },
+    /// A grid with every second row wired right to left.
+    Serpentine {
+        /// Top left corner of the area.
+        top_left: (f32, f32),
+        /// Bottom right corner of the area.
+        bottom_right: (f32, f32),
+        /// LEDs per row.
+        columns: u32,
+        /// Number of rows.
+        rows: u32,
+    },
+}
+
+impl LedStripLayout {
+    /// Number of LEDs.
+    pub fn pixel_count(&self) -> u32 {
+        match self {
+            LedStripLayout::Line { count, .. } => *count,
+            LedStripLayout::Matrix { columns, rows, .. }
+            | LedStripLayout::Serpentine { columns, rows, .. } => columns.saturating_mul(*rows),
+        }
+    }
+
+    /// Kind name for the UI.
+    pub fn label(&self) -> &'static str {
+        match self {
+            LedStripLayout::Line { .. } => "Line",
+            LedStripLayout::Matrix { .. } => "Matrix",
+            LedStripLayout::Serpentine { .. } => "Serpentine",
+        }
+    }
+}
+
+/// A fixture patched into a DMX light output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LightFixturePatch {
+    /// Unique identifier; the key of the fixture in `lamp_positions`.
+    pub id: String,
+    /// First DMX address (1-512).
+    pub start_address: u16,
+    /// Channel layout.
+    pub layout: LightFixtureLayout,
+}
+
+/// Channel layout of a DMX fixture
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LightFixtureLayout {
+    /// Single dimmer channel.
+    Dimmer,
+    /// Red, green, blue.
+    #[default]
+    Rgb,
+    /// Red, green, blue, amber.
+    Rgba,
+    /// Red, green, blue, white.
+    Rgbw,
+}
+
+impl LightFixtureLayout {
+    /// DMX channels the fixture occupies.
+    pub fn channel_count(&self) -> u16 {
+        match self {
+            LightFixtureLayout::Dimmer => 1,
+            LightFixtureLayout::Rgb => 3,
+            LightFixtureLayout::Rgba | LightFixtureLayout::Rgbw => 4,
+        }
+    }
+}
+
+/// How a light output gets its colors
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LightMappingMode {
+    /// Lamps take the color of the connected layer at their position.
+    #[default]
+    Spatial,
+    /// Lamps are driven by an audio-reactive effect.
+    Effect,
+}
+
+/// Audio-reactive effect of a light output
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LightEffectKind {
+    /// All lamps pulse with the bass.
+    #[default]
+    Pulse,
+    /// Bass, mids and highs on separate lamps.
+    MultiBand,
+    /// A light running across the lamps from left to right.
+    Chase,
+    /// Random lamps sparkle, driven by the highs.
+    Sparkle,
+    /// A rainbow rotating across the lamps over time.
+    ColorCycle,
+    /// Rate-limited flashes on bass hits.
+    Flash,
+}
+
+impl LightEffectKind {
+    /// All effects, in UI order.
+    pub const ALL: [LightEffectKind; 6] = [
+        LightEffectKind::Pulse,
+        LightEffectKind::MultiBand,
+        LightEffectKind::Chase,
+        LightEffectKind::Sparkle,
+        LightEffectKind::ColorCycle,
+        LightEffectKind::Flash,
+    ];
+
+    /// Display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightEffectKind::Pulse => "Pulse",
+            LightEffectKind::MultiBand => "Multi-Band",
+            LightEffectKind::Chase => "Chase",
+            LightEffectKind::Sparkle => "Sparkle",
+            LightEffectKind::ColorCycle => "Color Cycle",
+            LightEffectKind::Flash => "Flash",
+        }
+    }
+}
@@ -21,6 +21,8 @@ pub enum MaskType {
         /// Edge smoothness of the gradient transition.
         softness: f32,
     },
+    /// Free-form bezier or polygon mask with per-point feathering.
+    Bezier(BezierMask),
 }
 
 /// Available procedural shapes for masks.
@@ -37,3 +39,429 @@ pub enum MaskShape {
     /// Elliptical mask.
     Ellipse,
 }
+
+/// How a bezier mask combines with the masks before it in the chain.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum MaskOperation {
+    /// Union: content visible in either mask stays visible.
+    #[default]
+    Add,
+    /// Cut this mask's area out of the masks before it.
+    Subtract,
+    /// Keep only the area covered by both.
+    Intersect,
+}
+
+/// A point of a bezier mask outline.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MaskPoint {
+    /// Anchor position in content coordinates (0.0-1.0, origin top-left).
+    pub position: (f32, f32),
+    /// Incoming tangent handle, relative to the anchor.
+    pub handle_in: (f32, f32),
+    /// Outgoing tangent handle, relative to the anchor.
+    pub handle_out: (f32, f32),
+    /// Width of the soft edge around this point, in content units.
+    pub feather: f32,
+}
+
+impl MaskPoint {
+    /// A sharp corner without tangent handles.
+    pub fn corner(x: f32, y: f32) -> Self {
+        Self {
+            position: (x, y),
+            handle_in: (0.0, 0.0),
+            handle_out: (0.0, 0.0),
+            feather: 0.0,
+        }
+    }
+}
+
+/// Outline of a bezier mask at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaskKeyframe {
+    /// Time in seconds.
+    pub time: f32,
+    /// Outline points at this time.
+    pub points: Vec<MaskPoint>,
+}
+
+/// Free-form mask bounded by a closed bezier curve; a polygon when no point
+/// has tangent handles.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BezierMask {
+    /// Outline points in drawing order, used while there are no keyframes.
+    pub points: Vec<MaskPoint>,
+    /// Keep the content outside the outline instead of inside.
+    pub inverted: bool,
+    /// Combination with the masks before this one in the chain.
+    pub operation: MaskOperation,
+    /// Animated outlines, interpolated point by point.
+    #[serde(default)]
+    pub keyframes: Vec<MaskKeyframe>,
+    /// Repeat the keyframe animation once it reaches its last keyframe.
+    #[serde(default)]
+    pub loop_animation: bool,
+}
+
+impl Default for BezierMask {
+    fn default() -> Self {
+        Self {
+            points: vec![
+                MaskPoint::corner(0.25, 0.25),
+                MaskPoint::corner(0.75, 0.25),
+                MaskPoint::corner(0.75, 0.75),
+                MaskPoint::corner(0.25, 0.75),
+            ],
+            inverted: false,
+            operation: MaskOperation::Add,
+            keyframes: Vec::new(),
+            loop_animation: false,
+        }
+    }
+}
+
+/// Line segments per bezier curve when flattening an outline.
+const CURVE_STEPS: usize = 16;
+
+impl BezierMask {
+    /// Outline points at a time in seconds.
+    pub fn points_at(&self, time: f32) -> Vec<MaskPoint> {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return self.points.clone();
+        };
+        let time = if self.loop_animation && last.time > first.time {
+            first.time + (time - first.time).rem_euclid(last.time - first.time)
+        } else {
+            time
+        };
+
+        let next = self.keyframes.partition_point(|key| key.time <= time);
+        if next == 0 {
+            return first.points.clone();
+        }
+        let (from, Some(to)) = (&self.keyframes[next - 1], self.keyframes.get(next)) else {
+            return last.points.clone();
+        };
+        // Outlines with different point counts can't be blended
+        if from.points.len() != to.points.len() {
+            return from.points.clone();
+        }
+        let t = (time - from.time) / (to.time - from.time).max(f32::EPSILON);
+        let lerp = |a: (f32, f32), b: (f32, f32)| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        from.points
+            .iter()
+            .zip(&to.points)
+            .map(|(a, b)| MaskPoint {
+                position: lerp(a.position, b.position),
+                handle_in: lerp(a.handle_in, b.handle_in),
+                handle_out: lerp(a.handle_out, b.handle_out),
+                feather: a.feather + (b.feather - a.feather) * t,
+            })
+            .collect()
+    }
+
+    /// Store the current outline as a keyframe, replacing one at the same time.
+    pub fn set_keyframe(&mut self, time: f32) {
+        let points = self.points.clone();
+        match self
+            .keyframes
+            .iter_mut()
+            .find(|key| (key.time - time).abs() < 1e-3)
+        {
+            Some(key) => key.points = points,
+            None => {
+                let index = self.keyframes.partition_point(|key| key.time < time);
+                self.keyframes.insert(index, MaskKeyframe { time, points });
+            }
+        }
+    }
+
+    /// Closed outline as a polyline with the feather width at each vertex.
+    fn flatten(&self) -> Vec<(glam::Vec2, f32)> {
+        use glam::Vec2;
+        let vec = |p: (f32, f32)| Vec2::new(p.0, p.1);
+        let mut outline = Vec::new();
+        for (index, start) in self.points.iter().enumerate() {
+            let end = &self.points[(index + 1) % self.points.len()];
+            let p0 = vec(start.position);
+            let p1 = p0 + vec(start.handle_out);
+            let p3 = vec(end.position);
+            let p2 = p3 + vec(end.handle_in);
+            let straight = p1 == p0 && p2 == p3;
+            let steps = if straight { 1 } else { CURVE_STEPS };
+            for step in 0..steps {
+                let t = step as f32 / steps as f32;
+                let u = 1.0 - t;
+                let point = p0 * (u * u * u)
+                    + p1 * (3.0 * u * u * t)
+                    + p2 * (3.0 * u * t * t)
+                    + p3 * (t * t * t);
+                outline.push((point, start.feather + (end.feather - start.feather) * t));
+            }
+        }
+        outline
+    }
+}
+
+/// Flattened bezier mask ready for coverage queries.
+struct MaskOutline {
+    vertices: Vec<(glam::Vec2, f32)>,
+    min: glam::Vec2,
+    max: glam::Vec2,
+    inverted: bool,
+}
+
+impl MaskOutline {
+    fn new(mask: &BezierMask) -> Self {
+        let vertices = mask.flatten();
+        let margin = vertices.iter().map(|(_, f)| *f).fold(0.0f32, f32::max) * 0.5;
+        let (min, max) = vertices.iter().fold(
+            (glam::Vec2::splat(f32::MAX), glam::Vec2::splat(f32::MIN)),
+            |(min, max), (p, _)| (min.min(*p), max.max(*p)),
+        );
+        Self {
+            vertices,
+            min: min - margin,
+            max: max + margin,
+            inverted: mask.inverted,
+        }
+    }
+
+    /// Coverage (0.0-1.0) per pixel (`width * height`, row by row), feathered
+    /// around the outline. The inside is scanline filled; distances to the
+    /// outline are only measured within the feather band of each edge.
+    fn rasterize(&self, width: u32, height: u32) -> Vec<f32> {
+        let (w, h) = (width as usize, height as usize);
+        let mut coverage = vec![0.0f32; w * h];
+        let count = self.vertices.len();
+        if count >= 3 {
+            let edges = || {
+                (0..count).map(|index| (self.vertices[index], self.vertices[(index + 1) % count]))
+            };
+
+            // Even-odd fill between the outline crossings of each pixel row
+            let mut crossings = Vec::new();
+            for y in 0..h {
+                let py = (y as f32 + 0.5) / height as f32;
+                if py < self.min.y || py > self.max.y {
+                    continue;
+                }
+                crossings.clear();
+                crossings.extend(edges().filter_map(|((a, _), (b, _))| {
+                    ((a.y > py) != (b.y > py)).then(|| a.x + (py - a.y) / (b.y - a.y) * (b.x - a.x))
+                }));
+                crossings.sort_by(f32::total_cmp);
+                for span in crossings.chunks_exact(2) {
+                    let start = first_pixel_at(span[0], width);
+                    let end = first_pixel_at(span[1], width);
+                    coverage[y * w + start..y * w + end].fill(1.0);
+                }
+            }
+
+            // Soft edge centered on the outline; beyond half the widest
+            // feather every pixel keeps its hard coverage
+            let band = self.vertices.iter().map(|(_, f)| *f).fold(0.0f32, f32::max) * 0.5;
+            if band > f32::EPSILON {
+                let mut nearest = vec![(f32::MAX, 0.0f32); w * h];
+                for ((a, feather_a), (b, feather_b)) in edges() {
+                    let min = a.min(b) - band;
+                    let max = a.max(b) + band;
+                    let edge = b - a;
+                    for y in first_pixel_at(min.y, height)..first_pixel_at(max.y, height) {
+                        for x in first_pixel_at(min.x, width)..first_pixel_at(max.x, width) {
+                            let point = glam::Vec2::new(
+                                (x as f32 + 0.5) / width as f32,
+                                (y as f32 + 0.5) / height as f32,
+                            );
+                            let t = ((point - a).dot(edge)
+                                / edge.length_squared().max(f32::EPSILON))
+                            .clamp(0.0, 1.0);
+                            let distance = point.distance(a + edge * t);
+                            let nearest = &mut nearest[y * w + x];
+                            if distance < nearest.0 {
+                                *nearest = (distance, feather_a + (feather_b - feather_a) * t);
+                            }
+                        }
+                    }
+                }
+                for (coverage, (distance, feather)) in coverage.iter_mut().zip(nearest) {
+                    if feather > f32::EPSILON {
+                        let signed = if *coverage > 0.5 { distance } else { -distance };
+                        let x = (signed / feather + 0.5).clamp(0.0, 1.0);
+                        *coverage = x * x * (3.0 - 2.0 * x);
+                    }
+                }
+            }
+        }
+        if self.inverted {
+            for coverage in &mut coverage {
+                *coverage = 1.0 - *coverage;
+            }
+        }
+        coverage
+    }
+}
+
+/// Index of the first pixel whose center lies at or after a content position,
+/// clamped to `0..=size`.
+fn first_pixel_at(position: f32, size: u32) -> usize {
+    (position * size as f32 - 0.5)
+        .ceil()
+        .clamp(0.0, size as f32) as usize
+}
+
+impl MaskType {
+    /// The mask with animated points resolved at a time in seconds.
+    pub fn at_time(&self, time: f32) -> MaskType {
+        match self {
+            MaskType::Bezier(mask) if !mask.keyframes.is_empty() => MaskType::Bezier(BezierMask {
+                points: mask.points_at(time),
+                keyframes: Vec::new(),
+                ..mask.clone()
+            }),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Rasterize the bezier masks of a mask chain into a coverage image
+/// (`width * height` bytes, row by row), combining each with the masks before
+/// it by its operation. File, shape and gradient masks don't take part.
+/// Returns `None` if the chain has no bezier mask.
+pub fn rasterize_masks(masks: &[MaskType], width: u32, height: u32) -> Option<Vec<u8>> {
+    let mut accumulated: Option<Vec<f32>> = None;
+    for mask in masks {
+        let MaskType::Bezier(mask) = mask else {
+            continue;
+        };
+        let coverage = MaskOutline::new(mask).rasterize(width, height);
+        accumulated = Some(match accumulated {
+            None => coverage,
+            Some(mut previous) => {
+                for (previous, coverage) in previous.iter_mut().zip(coverage) {
+                    *previous = match mask.operation {
+                        MaskOperation::Add => f32::max(*previous, coverage),
+                        MaskOperation::Subtract => *previous * (1.0 - coverage),
+                        MaskOperation::Intersect => f32::min(*previous, coverage),
+                    };
+                }
+                previous
+            }
+        });
+    }
+    accumulated.map(|coverage| {
+        coverage
+            .into_iter()
+            .map(|c| (c * 255.0).round() as u8)
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: f32, max: f32) -> BezierMask {
+        BezierMask {
+            points: vec![
+                MaskPoint::corner(min, min),
+                MaskPoint::corner(max, min),
+                MaskPoint::corner(max, max),
+                MaskPoint::corner(min, max),
+            ],
+            ..BezierMask::default()
+        }
+    }
+
+    fn at(data: &[u8], size: u32, x: f32, y: f32) -> u8 {
+        data[((y * size as f32) as u32 * size + (x * size as f32) as u32) as usize]
+    }
+
+    #[test]
+    fn test_bezier_mask_boolean_operations() {
+        let masks = vec![
+            MaskType::Bezier(square(0.0, 0.5)),
+            MaskType::Bezier(BezierMask {
+                operation: MaskOperation::Add,
+                ..square(0.5, 1.0)
+            }),
+        ];
+        let data = rasterize_masks(&masks, 64, 64).unwrap();
+        assert_eq!(at(&data, 64, 0.25, 0.25), 255);
+        assert_eq!(at(&data, 64, 0.75, 0.75), 255);
+        assert_eq!(at(&data, 64, 0.75, 0.25), 0);
+
+        let masks = vec![
+            MaskType::Bezier(square(0.0, 1.0)),
+            MaskType::Bezier(BezierMask {
+                operation: MaskOperation::Subtract,
+                ..square(0.25, 0.75)
+            }),
+        ];
+        let data = rasterize_masks(&masks, 64, 64).unwrap();
+        assert_eq!(at(&data, 64, 0.5, 0.5), 0);
+        assert_eq!(at(&data, 64, 0.1, 0.1), 255);
+
+        let masks = vec![
+            MaskType::Bezier(square(0.0, 0.6)),
+            MaskType::Bezier(BezierMask {
+                operation: MaskOperation::Intersect,
+                inverted: true,
+                ..square(0.0, 0.3)
+            }),
+        ];
+        let data = rasterize_masks(&masks, 64, 64).unwrap();
+        assert_eq!(at(&data, 64, 0.1, 0.1), 0);
+        assert_eq!(at(&data, 64, 0.45, 0.45), 255);
+        assert_eq!(at(&data, 64, 0.8, 0.8), 0);
+
+        // Chains without bezier masks are left to the other mask types
+        assert!(rasterize_masks(&[MaskType::Shape(MaskShape::Circle)], 64, 64).is_none());
+    }
+
+    #[test]
+    fn test_bezier_mask_feather_and_curves() {
+        let mut mask = square(0.25, 0.75);
+        for point in &mut mask.points {
+            point.feather = 0.2;
+        }
+        let data = rasterize_masks(&[MaskType::Bezier(mask.clone())], 100, 100).unwrap();
+        // Half coverage on the outline, fading over the feather width
+        let edge = at(&data, 100, 0.25, 0.5);
+        assert!((100..=155).contains(&edge), "edge coverage {edge}");
+        assert!(at(&data, 100, 0.2, 0.5) > 0);
+        assert!(at(&data, 100, 0.3, 0.5) < 255);
+        assert_eq!(at(&data, 100, 0.5, 0.5), 255);
+
+        // Handles bulge the top edge outward
+        mask.points[0].feather = 0.0;
+        mask.points[1].feather = 0.0;
+        mask.points[0].handle_out = (0.0, -0.2);
+        mask.points[1].handle_in = (0.0, -0.2);
+        let data = rasterize_masks(&[MaskType::Bezier(mask)], 100, 100).unwrap();
+        assert_eq!(at(&data, 100, 0.5, 0.15), 255);
+    }
+
+    #[test]
+    fn test_bezier_mask_animation() {
+        let mut mask = square(0.0, 0.5);
+        mask.set_keyframe(0.0);
+        mask.points = square(0.5, 1.0).points;
+        mask.set_keyframe(2.0);
+        assert_eq!(mask.keyframes.len(), 2);
+
+        assert_eq!(mask.points_at(-1.0)[0].position, (0.0, 0.0));
+        assert_eq!(mask.points_at(1.0)[0].position, (0.25, 0.25));
+        assert_eq!(mask.points_at(5.0)[0].position, (0.5, 0.5));
+
+        mask.loop_animation = true;
+        assert_eq!(mask.points_at(3.0)[0].position, (0.25, 0.25));
+
+        let MaskType::Bezier(resolved) = MaskType::Bezier(mask).at_time(1.0) else {
+            unreachable!();
+        };
+        assert!(resolved.keyframes.is_empty());
+        assert_eq!(resolved.points[2].position, (0.75, 0.75));
+    }
+}
@@ -2,6 +2,7 @@
 //! Mesh data and definitions.
 //!
 
+use crate::mesh::{WarpInterpolation, WarpSurface, DEFAULT_WARP_SUBDIVISIONS};
 use serde::{Deserialize, Serialize};
 
 /// Mesh geometry definitions for projection mapping surfaces.
@@ -25,10 +26,22 @@ pub enum MeshType {
         /// Number of vertical divisions.
         cols: u32,
     },
-    /// A smooth surface defined by Bezier control points.
+    /// A smooth multi-patch surface defined by a lattice of control points.
     BezierSurface {
-        /// List of 2D control points influencing the surface curvature.
+        /// Lattice control points, row by row.
         control_points: Vec<(f32, f32)>,
+        /// Number of control points per lattice row.
+        #[serde(default = "default_lattice_size")]
+        columns: u32,
+        /// Number of control points per lattice column.
+        #[serde(default = "default_lattice_size")]
+        rows: u32,
+        /// How the lattice is interpolated between control points.
+        #[serde(default)]
+        interpolation: WarpInterpolation,
+        /// Number of mesh cells per patch along each axis.
+        #[serde(default = "default_subdivisions")]
+        subdivisions: u32,
     },
     /// An arbitrary flat shape defined by an ordered list of vertices.
     Polygon {
@@ -63,6 +76,30 @@ pub enum MeshType {
         /// Path to the mesh file.
         path: String,
     },
+    /// A 3D scene model (OBJ, glTF) rendered from the calibrated projector
+    /// of each output; flat front view on uncalibrated outputs.
+    Model3D {
+        /// Path to the model file.
+        path: String,
+    },
+    /// A grid whose texture coordinates were generated by structured-light
+    /// calibration.
+    CalibratedGrid {
+        /// Number of horizontal divisions.
+        rows: u32,
+        /// Number of vertical divisions.
+        cols: u32,
+        /// Content position sampled at each vertex, row by row.
+        tex_coords: Vec<(f32, f32)>,
+    },
+}
+
+fn default_lattice_size() -> u32 {
+    4
+}
+
+fn default_subdivisions() -> u32 {
+    DEFAULT_WARP_SUBDIVISIONS
 }
 
 impl Default for MeshType {
@@ -111,13 +148,23 @@ impl MeshType {
                 segments.hash(&mut hasher);
                 arc_angle.to_bits().hash(&mut hasher);
             }
-            MeshType::BezierSurface { control_points } => {
+            MeshType::BezierSurface {
+                control_points,
+                columns,
+                rows,
+                interpolation,
+                subdivisions,
+            } => {
                 4u8.hash(&mut hasher);
                 control_points.len().hash(&mut hasher);
                 for (x, y) in control_points {
                     x.to_bits().hash(&mut hasher);
                     y.to_bits().hash(&mut hasher);
                 }
+                columns.hash(&mut hasher);
+                rows.hash(&mut hasher);
+                (*interpolation as u8).hash(&mut hasher);
+                subdivisions.hash(&mut hasher);
             }
             MeshType::Polygon { vertices } => {
                 5u8.hash(&mut hasher);
@@ -144,10 +191,84 @@ impl MeshType {
                 8u8.hash(&mut hasher);
                 path.hash(&mut hasher);
             }
+            MeshType::Model3D { path } => {
+                9u8.hash(&mut hasher);
+                path.hash(&mut hasher);
+            }
+            MeshType::CalibratedGrid {
+                rows,
+                cols,
+                tex_coords,
+            } => {
+                10u8.hash(&mut hasher);
+                rows.hash(&mut hasher);
+                cols.hash(&mut hasher);
+                tex_coords.len().hash(&mut hasher);
+                for (u, v) in tex_coords {
+                    u.to_bits().hash(&mut hasher);
+                    v.to_bits().hash(&mut hasher);
+                }
+            }
         }
         hasher.finish()
     }
 
+    /// Builds a bezier surface mesh type from a warp surface.
+    pub fn from_warp_surface(surface: &WarpSurface) -> Self {
+        MeshType::BezierSurface {
+            control_points: surface.points.iter().map(|p| (p.x, p.y)).collect(),
+            columns: surface.columns,
+            rows: surface.rows,
+            interpolation: surface.interpolation,
+            subdivisions: surface.subdivisions,
+        }
+    }
+
+    /// The warp surface of a bezier surface mesh type.
+    pub fn warp_surface(&self) -> Option<WarpSurface> {
+        match self {
+            MeshType::BezierSurface {
+                control_points,
+                columns,
+                rows,
+                interpolation,
+                subdivisions,
+            } => Some(WarpSurface {
+                columns: *columns,
+                rows: *rows,
+                points: control_points
+                    .iter()
+                    .map(|p| glam::Vec2::new(p.0, p.1))
+                    .collect(),
+                interpolation: *interpolation,
+                subdivisions: *subdivisions,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Converts a grid into a flat Catmull-Rom bezier surface with one
+    /// control point per grid vertex.
+    pub fn to_bezier_surface(&self) -> Option<Self> {
+        match self {
+            MeshType::Grid { rows, cols } => {
+                let grid = crate::mesh::Mesh::create_grid(*rows, *cols);
+                WarpSurface::from_grid_mesh(&grid, *rows, *cols)
+                    .map(|surface| Self::from_warp_surface(&surface))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts a bezier surface into a grid with one cell per patch.
+    ///
+    /// Grids carry no vertex positions, so the warp itself is not kept.
+    pub fn to_grid(&self) -> Option<Self> {
+        let surface = self.warp_surface().filter(|s| s.is_valid())?;
+        let (cols, rows) = surface.patch_counts();
+        Some(MeshType::Grid { rows, cols })
+    }
+
     /// Converts the procedural definition into a concrete Mesh object for rendering.
     pub fn to_mesh(&self) -> crate::mesh::Mesh {
         use crate::mesh::Mesh;
@@ -170,22 +291,10 @@ impl MeshType {
             MeshType::Circle { segments, .. } => {
                 Mesh::ellipse(Vec2::new(0.5, 0.5), 0.5, 0.5, *segments)
             }
-            MeshType::BezierSurface { control_points } => {
-                if control_points.len() == 16 {
-                    let mut patch = crate::mesh::BezierPatch::new();
-                    for (i, p) in control_points.iter().take(16).enumerate() {
-                        let row = i / 4;
-                        let col = i % 4;
-                        patch.control_points[row][col] = Vec2::new(p.0, p.1);
-                    }
-
-                    let mut mesh = Mesh::create_grid(8, 8);
-                    patch.apply_to_mesh(&mut mesh);
-                    mesh
-                } else {
-                    Mesh::quad()
-                }
-            }
+            MeshType::BezierSurface { .. } => self
+                .warp_surface()
+                .map(|surface| surface.to_mesh())
+                .unwrap_or_else(Mesh::quad),
             MeshType::Polygon { vertices } => {
                 if vertices.len() < 3 {
                     Mesh::quad()
@@ -214,8 +323,8 @@ impl MeshType {
                     let mut indices = Vec::with_capacity(vertices.len() * 3);
                     for i in 0..vertices.len() {
                         indices.push(0);
-                        indices.push((i + 1) as u16);
-                        indices.push(((i + 1) % vertices.len() + 1) as u16);
+                        indices.push((i + 1) as u32);
+                        indices.push(((i + 1) % vertices.len() + 1) as u32);
                     }
 
                     Mesh {
@@ -263,8 +372,8 @@ impl MeshType {
 
                 for lat in 0..lat_segs {
                     for lon in 0..lon_segs {
-                        let first = (lat * (lon_segs + 1) + lon) as u16;
-                        let second = first + lon_segs as u16 + 1;
+                        let first = lat * (lon_segs + 1) + lon;
+                        let second = first + lon_segs + 1;
 
                         indices.push(first);
                         indices.push(second);
@@ -338,15 +447,10 @@ impl MeshType {
                                     .push(MeshVertex::new(Vec2::new(nx, ny), Vec2::new(u, v)));
                             }
 
-                            let mut indices = Vec::with_capacity(mesh.indices.len());
-                            for idx in &mesh.indices {
-                                indices.push(*idx as u16);
-                            }
-
                             Mesh {
                                 mesh_type: CoreMeshType::Custom,
                                 vertices: mesh_vertices,
-                                indices,
+                                indices: mesh.indices.clone(),
                                 revision: 0,
                             }
                         } else {
@@ -454,8 +558,8 @@ impl MeshType {
                                 let mut indices = Vec::with_capacity(norm_vertices.len() * 3);
                                 for i in 0..norm_vertices.len() {
                                     indices.push(0);
-                                    indices.push((i + 1) as u16);
-                                    indices.push(((i + 1) % norm_vertices.len() + 1) as u16);
+                                    indices.push((i + 1) as u32);
+                                    indices.push(((i + 1) % norm_vertices.len() + 1) as u32);
                                 }
 
                                 Mesh {
@@ -477,9 +581,90 @@ impl MeshType {
                     Mesh::quad()
                 }
             }
+            MeshType::Model3D { path } => match crate::projection3d::SceneModel::load(path) {
+                Ok(model) => front_view(&model).unwrap_or_else(Mesh::quad),
+                Err(e) => {
+                    tracing::warn!("Scene model '{}' could not be loaded: {}", path, e);
+                    Mesh::quad()
+                }
+            },
+            MeshType::CalibratedGrid {
+                rows,
+                cols,
+                tex_coords,
+            } => {
+                let mut mesh = Mesh::create_grid((*rows).max(1), (*cols).max(1));
+                if tex_coords.len() == mesh.vertices.len() {
+                    for (vertex, (u, v)) in mesh.vertices.iter_mut().zip(tex_coords) {
+                        vertex.tex_coords = Vec2::new(*u, *v);
+                    }
+                }
+                mesh
+            }
         };
 
         mesh.revision = self.compute_revision_hash();
         mesh
     }
 }
+
+/// The model seen from the front (X right, Y up), fitted into the output
+fn front_view(model: &crate::projection3d::SceneModel) -> Option<crate::mesh::Mesh> {
+    use crate::mesh::{Mesh, MeshType as CoreMeshType, MeshVertex};
+    use glam::Vec2;
+
+    let (min, max) = model.bounds()?;
+    let size = (max - min).max(glam::Vec3::splat(1e-6));
+    let vertices = model
+        .positions
+        .iter()
+        .zip(&model.tex_coords)
+        .map(|(p, uv)| {
+            let position = Vec2::new((p.x - min.x) / size.x, (max.y - p.y) / size.y);
+            MeshVertex::new(position, *uv)
+        })
+        .collect();
+    Some(Mesh {
+        mesh_type: CoreMeshType::Custom,
+        vertices,
+        indices: model.indices.clone(),
+        revision: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bezier_surface_grid_conversion() {
+        let grid = MeshType::Grid { rows: 2, cols: 3 };
+        let surface = grid.to_bezier_surface().unwrap();
+        assert!(matches!(
+            surface,
+            MeshType::BezierSurface {
+                columns: 4,
+                rows: 3,
+                interpolation: WarpInterpolation::CatmullRom,
+                ..
+            }
+        ));
+        assert_eq!(surface.to_grid(), Some(grid));
+        assert_eq!(surface.to_mesh().vertex_count(), (2 * 8 + 1) * (3 * 8 + 1));
+    }
+
+    #[test]
+    fn test_legacy_bezier_surface_is_single_patch() {
+        let points: Vec<(f32, f32)> = (0..16)
+            .map(|i| ((i % 4) as f32 / 3.0, (i / 4) as f32 / 3.0))
+            .collect();
+        let json = serde_json::json!({ "BezierSurface": { "control_points": points } });
+        let mesh: MeshType = serde_json::from_value(json).unwrap();
+
+        let surface = mesh.warp_surface().unwrap();
+        assert!(surface.is_valid());
+        assert_eq!(surface.interpolation, WarpInterpolation::Bezier);
+        assert_eq!(surface.patch_counts(), (1, 1));
+        assert_eq!(mesh.to_mesh().vertex_count(), 81);
+    }
+}
@@ -5,9 +5,11 @@
 pub mod connection;
 pub mod hue;
 pub mod layer;
+pub mod light;
 pub mod mask;
 pub mod mesh;
 pub mod module;
+mod module_tests;
 pub mod modulizer;
 pub mod node_link;
 pub mod output;
@@ -20,6 +22,7 @@ pub mod trigger;
 pub use connection::*;
 pub use hue::*;
 pub use layer::*;
+pub use light::*;
 pub use mask::*;
 pub use mesh::*;
 pub use module::*;
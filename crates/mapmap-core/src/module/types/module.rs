@@ -149,6 +149,8 @@ impl MapFlowModule {
                     output_fps: 60.0,
                     ndi_enabled: false,
                     ndi_stream_name: String::new(),
+                    calibration_3d: Default::default(),
+                    color_measurements: None,
                 })
             }
         };
@@ -162,6 +164,8 @@ impl MapFlowModule {
             inputs: vec![],
             outputs: vec![],
             trigger_targets: HashMap::new(),
+            bypass: false,
+            group: None,
         };
 
         let (inputs, outputs) = part.compute_sockets();
@@ -190,6 +194,8 @@ impl MapFlowModule {
             inputs: vec![],
             outputs: vec![],
             trigger_targets: HashMap::new(),
+            bypass: false,
+            group: None,
         };
 
         let (inputs, outputs) = part.compute_sockets();
@@ -269,6 +275,128 @@ impl MapFlowModule {
     pub fn update_part_outputs(&mut self, part_id: ModulePartId) {
         self.update_part_sockets(part_id);
     }
+
+    /// Duplicate parts, including the connections between them
+    ///
+    /// Returns the IDs of the new parts in the order of `part_ids`.
+    pub fn duplicate_parts(
+        &mut self,
+        part_ids: &[ModulePartId],
+        offset: (f32, f32),
+    ) -> Vec<ModulePartId> {
+        let mut id_map = HashMap::new();
+        let mut new_ids = Vec::with_capacity(part_ids.len());
+
+        for part_id in part_ids {
+            let Some(original) = self.parts.iter().find(|p| p.id == *part_id) else {
+                continue;
+            };
+            let mut copy = original.clone();
+            copy.id = self.next_part_id;
+            copy.position = (
+                original.position.0 + offset.0,
+                original.position.1 + offset.1,
+            );
+            copy.group = None;
+            self.next_part_id += 1;
+
+            id_map.insert(*part_id, copy.id);
+            new_ids.push(copy.id);
+            self.parts.push(copy);
+        }
+
+        let internal: Vec<ModuleConnection> = self
+            .connections
+            .iter()
+            .filter_map(|c| {
+                Some(ModuleConnection {
+                    from_part: *id_map.get(&c.from_part)?,
+                    from_socket: c.from_socket,
+                    to_part: *id_map.get(&c.to_part)?,
+                    to_socket: c.to_socket,
+                })
+            })
+            .collect();
+        self.connections.extend(internal);
+
+        new_ids
+    }
+
+    /// Connect the first compatible free socket pair from one part to another
+    ///
+    /// Returns the new connection, or `None` if the parts have no compatible
+    /// output/input pair whose input is not already connected.
+    pub fn connect_parts(
+        &mut self,
+        from_part: ModulePartId,
+        to_part: ModulePartId,
+    ) -> Option<ModuleConnection> {
+        if from_part == to_part {
+            return None;
+        }
+        let from = self.parts.iter().find(|p| p.id == from_part)?;
+        let to = self.parts.iter().find(|p| p.id == to_part)?;
+
+        let connection = from
+            .outputs
+            .iter()
+            .enumerate()
+            .find_map(|(from_socket, output)| {
+                to.inputs
+                    .iter()
+                    .enumerate()
+                    .find(|(to_socket, input)| {
+                        input.socket_type == output.socket_type
+                            && !self
+                                .connections
+                                .iter()
+                                .any(|c| c.to_part == to_part && c.to_socket == *to_socket)
+                    })
+                    .map(|(to_socket, _)| ModuleConnection {
+                        from_part,
+                        from_socket,
+                        to_part,
+                        to_socket,
+                    })
+            })?;
+
+        self.connections.push(connection.clone());
+        Some(connection)
+    }
+
+    /// Assign parts to a canvas group, or remove them from their group with `None`
+    pub fn set_part_group(&mut self, part_ids: &[ModulePartId], group: Option<String>) {
+        for part in self.parts.iter_mut().filter(|p| part_ids.contains(&p.id)) {
+            part.group = group.clone();
+        }
+    }
+
+    /// Generate a group name that is not used by any part yet
+    pub fn next_group_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Group {}", n))
+            .find(|name| !self.parts.iter().any(|p| p.group.as_ref() == Some(name)))
+            .unwrap_or_default()
+    }
+
+    /// Toggle bypass on parts
+    ///
+    /// If any of the parts is active, all are bypassed; otherwise all are
+    /// re-enabled. Parts that cannot be bypassed are left unchanged.
+    pub fn toggle_bypass(&mut self, part_ids: &[ModulePartId]) {
+        let bypass = self
+            .parts
+            .iter()
+            .filter(|p| part_ids.contains(&p.id) && p.part_type.supports_bypass())
+            .any(|p| !p.bypass);
+        for part in self
+            .parts
+            .iter_mut()
+            .filter(|p| part_ids.contains(&p.id) && p.part_type.supports_bypass())
+        {
+            part.bypass = bypass;
+        }
+    }
 }
 
 /// Defines how the module handles time and looping
@@ -87,4 +87,81 @@ mod tests {
         assert_eq!(module.connections.len(), 1);
         assert_eq!(module.connections[0].to_part, 3);
     }
+
+    fn empty_module() -> MapFlowModule {
+        MapFlowModule {
+            id: 1,
+            name: "Test".to_string(),
+            color: [1.0; 4],
+            parts: vec![],
+            connections: vec![],
+            playback_mode: ModulePlaybackMode::LoopUntilManualSwitch,
+            next_part_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_module_connect_parts_uses_first_free_compatible_socket() {
+        let mut module = empty_module();
+        let source = module.add_part(PartType::Source, (0.0, 0.0));
+        let layer = module.add_part(PartType::Layer, (300.0, 0.0));
+
+        let conn = module.connect_parts(source, layer).unwrap();
+        assert_eq!(conn.from_part, source);
+        assert_eq!(conn.to_part, layer);
+        assert_eq!(module.connections.len(), 1);
+
+        // The media input is taken now
+        assert!(module.connect_parts(source, layer).is_none());
+        // A layer output cannot feed a source
+        assert!(module.connect_parts(layer, source).is_none());
+    }
+
+    #[test]
+    fn test_module_duplicate_parts_copies_internal_connections() {
+        let mut module = empty_module();
+        let source = module.add_part(PartType::Source, (0.0, 0.0));
+        let layer = module.add_part(PartType::Layer, (300.0, 0.0));
+        let output = module.add_part(PartType::Output, (600.0, 0.0));
+        module.connect_parts(source, layer).unwrap();
+        module.connect_parts(layer, output).unwrap();
+        module.set_part_group(&[source, layer], Some("Group 1".to_string()));
+
+        let copies = module.duplicate_parts(&[source, layer], (20.0, 30.0));
+
+        assert_eq!(copies.len(), 2);
+        assert_eq!(module.parts.len(), 5);
+        let copy = module.parts.iter().find(|p| p.id == copies[0]).unwrap();
+        assert_eq!(copy.position, (20.0, 30.0));
+        assert_eq!(copy.group, None);
+
+        // Only the connection between the duplicated parts is copied
+        assert_eq!(module.connections.len(), 3);
+        assert!(module
+            .connections
+            .iter()
+            .any(|c| c.from_part == copies[0] && c.to_part == copies[1]));
+    }
+
+    #[test]
+    fn test_module_groups_and_bypass() {
+        let mut module = empty_module();
+        let a = module.add_part(PartType::Mask, (0.0, 0.0));
+        let b = module.add_part(PartType::Modulator, (0.0, 100.0));
+
+        assert_eq!(module.next_group_name(), "Group 1");
+        module.set_part_group(&[a, b], Some(module.next_group_name()));
+        assert_eq!(module.next_group_name(), "Group 2");
+
+        module.parts[0].bypass = true;
+        module.toggle_bypass(&[a, b]);
+        assert!(module.parts.iter().all(|p| p.bypass));
+        module.toggle_bypass(&[a, b]);
+        assert!(module.parts.iter().all(|p| !p.bypass));
+
+        // Layers always render, so they cannot be bypassed
+        let layer = module.add_part(PartType::Layer, (0.0, 200.0));
+        module.toggle_bypass(&[layer]);
+        assert!(!module.parts.iter().find(|p| p.id == layer).unwrap().bypass);
+    }
 }
@@ -23,6 +23,13 @@ pub enum ModulizerType {
     AudioReactive {
         /// Component property or field.
         source: String,
+        /// Named channel analyzer to read from (`None` = main input).
+        #[serde(default)]
+        analyzer: Option<String>,
+        /// Scale the layer opacity by the level of the source; off passes
+        /// the chain through unchanged.
+        #[serde(default)]
+        scale_opacity: bool,
     },
 }
 
@@ -2,6 +2,7 @@
 //! Output variations.
 //!
 
+use super::light::{LightBackend, LightEffectKind, LightMappingMode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -41,6 +42,12 @@ pub enum OutputType {
         #[serde(default)]
         /// Display name.
         ndi_stream_name: String,
+        #[serde(default)]
+        /// Projector lens and pose for 3D model layers.
+        calibration_3d: crate::projection3d::ProjectorCalibration,
+        #[serde(default)]
+        /// Colorimeter readings of the color matching test patches.
+        color_measurements: Option<crate::color_match::PatchMeasurements>,
     },
     /// Enumeration variant.
     NdiOutput {
@@ -68,6 +75,40 @@ pub enum OutputType {
         /// Component property or field.
         mapping_mode: HueMappingMode,
     },
+    /// Vendor-neutral light output (Hue, DMX, WLED, Nanoleaf).
+    Light {
+        /// Human-readable display name.
+        name: String,
+        /// Light system the output drives.
+        backend: LightBackend,
+        #[serde(default)]
+        /// Normalized lamp positions (0.0 - 1.0) by lamp ID; lamps without an
+        /// entry keep the position reported by the device.
+        lamp_positions: HashMap<String, (f32, f32)>,
+        #[serde(default)]
+        /// Where the colors come from.
+        mapping_mode: LightMappingMode,
+        #[serde(default)]
+        /// Effect used in [`LightMappingMode::Effect`].
+        effect: LightEffectKind,
+        #[serde(default = "crate::module::config::default_hue_color")]
+        /// Base color of the effect.
+        color: [f32; 3],
+    },
+}
+
+impl OutputType {
+    /// A spatially mapped light output for `backend`.
+    pub fn new_light(name: impl Into<String>, backend: LightBackend) -> Self {
+        OutputType::Light {
+            name: name.into(),
+            backend,
+            lamp_positions: HashMap::new(),
+            mapping_mode: LightMappingMode::Spatial,
+            effect: LightEffectKind::Pulse,
+            color: crate::module::config::default_hue_color(),
+        }
+    }
 }
 
 /// Mapping mode for Hue Entertainment
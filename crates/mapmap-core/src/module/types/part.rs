@@ -37,6 +37,12 @@ pub struct ModulePart {
     /// Trigger target configuration (Input Socket Index -> Target Parameter)
     #[serde(default)]
     pub trigger_targets: HashMap<usize, TriggerConfig>,
+    /// Bypassed parts pass their input through unchanged
+    #[serde(default)]
+    pub bypass: bool,
+    /// Name of the canvas group this part belongs to
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 impl ModulePart {
@@ -91,6 +97,17 @@ pub enum ModulePartType {
 }
 
 impl ModulePartType {
+    /// Whether bypassing this part has an effect
+    ///
+    /// Only processing parts (effects, masks, meshes) can pass their input
+    /// through unchanged; sources, layers and outputs always run.
+    pub fn supports_bypass(&self) -> bool {
+        matches!(
+            self,
+            ModulePartType::Modulizer(_) | ModulePartType::Mask(_) | ModulePartType::Mesh(_)
+        )
+    }
+
     /// Method implementation.
     pub fn get_default_sockets(&self) -> (Vec<ModuleSocket>, Vec<ModuleSocket>) {
         match self {
@@ -2,6 +2,8 @@
 //! Trigger variants.
 //!
 
+use crate::audio::beat_grid::BeatDivision;
+use crate::audio::features::pitch_class_name;
 use crate::module::types::socket::ModuleSocket;
 use crate::module::types::socket::ModuleSocketType;
 use rand::RngExt;
@@ -150,6 +152,9 @@ pub enum TriggerType {
         threshold: f32,
         /// Component property or field.
         output_config: AudioTriggerOutputConfig,
+        /// Named channel analyzer to read from (`None` = main input).
+        #[serde(default)]
+        analyzer: Option<String>,
     },
     /// Enumeration variant.
     Random {
@@ -190,6 +195,11 @@ pub enum TriggerType {
     },
     /// Enumeration variant.
     Beat,
+    /// Fires on boundaries of the phase-locked beat grid.
+    BeatGrid {
+        /// Musical length between two triggers.
+        division: BeatDivision,
+    },
 }
 
 /// Audio frequency bands for FFT trigger
@@ -231,10 +241,35 @@ pub struct AudioTriggerOutputConfig {
     /// Component property or field.
     pub bpm_output: bool,
     #[serde(default)]
+    /// Spectral-flux onsets: one broadband output plus one per band.
+    pub onset_outputs: bool,
+    #[serde(default)]
+    /// Spectral centroid, flatness and rolloff (continuous values).
+    pub spectral_outputs: bool,
+    #[serde(default)]
+    /// One chroma output per pitch class, C to B.
+    pub chroma_outputs: bool,
+    #[serde(default)]
+    /// Estimated musical key (continuous value, index / 23).
+    pub key_output: bool,
+    #[serde(default)]
     /// Component property or field.
     pub inverted_outputs: std::collections::HashSet<String>,
 }
 
+/// Names of the nine analyzer frequency bands, lowest first
+pub const AUDIO_BAND_NAMES: [&str; 9] = [
+    "SubBass",
+    "Bass",
+    "LowMid",
+    "Mid",
+    "HighMid",
+    "UpperMid",
+    "Presence",
+    "Brilliance",
+    "Air",
+];
+
 impl Default for AudioTriggerOutputConfig {
     fn default() -> Self {
         Self {
@@ -242,6 +277,10 @@ impl Default for AudioTriggerOutputConfig {
             volume_outputs: false,
             beat_output: true,
             bpm_output: false,
+            onset_outputs: false,
+            spectral_outputs: false,
+            chroma_outputs: false,
+            key_output: false,
             inverted_outputs: std::collections::HashSet::new(),
         }
     }
@@ -253,20 +292,9 @@ impl AudioTriggerOutputConfig {
         let mut outputs = Vec::new();
 
         if self.frequency_bands {
-            let bands = [
-                "SubBass Out",
-                "Bass Out",
-                "LowMid Out",
-                "Mid Out",
-                "HighMid Out",
-                "UpperMid Out",
-                "Presence Out",
-                "Brilliance Out",
-                "Air Out",
-            ];
-            for b in bands {
+            for band in AUDIO_BAND_NAMES {
                 outputs.push(ModuleSocket {
-                    name: b.to_string(),
+                    name: format!("{} Out", band),
                     socket_type: ModuleSocketType::Trigger,
                 });
             }
@@ -297,6 +325,44 @@ impl AudioTriggerOutputConfig {
             });
         }
 
+        if self.onset_outputs {
+            outputs.push(ModuleSocket {
+                name: "Onset Out".to_string(),
+                socket_type: ModuleSocketType::Trigger,
+            });
+            for band in AUDIO_BAND_NAMES {
+                outputs.push(ModuleSocket {
+                    name: format!("{} Onset", band),
+                    socket_type: ModuleSocketType::Trigger,
+                });
+            }
+        }
+
+        if self.spectral_outputs {
+            for name in ["Centroid Out", "Flatness Out", "Rolloff Out"] {
+                outputs.push(ModuleSocket {
+                    name: name.to_string(),
+                    socket_type: ModuleSocketType::Trigger,
+                });
+            }
+        }
+
+        if self.chroma_outputs {
+            for pitch_class in 0..12 {
+                outputs.push(ModuleSocket {
+                    name: format!("Chroma {}", pitch_class_name(pitch_class)),
+                    socket_type: ModuleSocketType::Trigger,
+                });
+            }
+        }
+
+        if self.key_output {
+            outputs.push(ModuleSocket {
+                name: "Key Out".to_string(),
+                socket_type: ModuleSocketType::Trigger,
+            });
+        }
+
         if outputs.is_empty() {
             outputs.push(ModuleSocket {
                 name: "Beat Out".to_string(),
@@ -1,10 +1,12 @@
 use crate::module::{BlendModeType, MaskType};
 
 use crate::audio::analyzer_v2::AudioAnalysisV2;
+use crate::audio::beat_grid::BeatGridState;
+use crate::audio::features::{normalize_frequency, pitch_class_name};
 use crate::audio_reactive::AudioTriggerData;
 use crate::module::{
     LayerType, LinkBehavior, LinkMode, MapFlowModule, MeshType, ModulePartId, ModulePartType,
-    ModulizerType, OutputType, SharedMediaState, SourceType, TriggerType,
+    ModulizerType, OutputType, SharedMediaState, SourceType, TriggerType, AUDIO_BAND_NAMES,
 };
 use rand::RngExt;
 use std::cell::RefCell;
@@ -18,6 +20,10 @@ use super::types::*;
 pub struct ModuleEvaluator {
     /// Current trigger data from audio analysis
     audio_trigger_data: AudioTriggerData,
+    /// Trigger data of the named channel analyzers
+    channel_audio_data: HashMap<String, AudioTriggerData>,
+    /// Beat grid position and the boundaries crossed this frame
+    beat_grid: BeatGridState,
     /// Creation time for timing calculations
     start_time: Instant,
     /// Per-node state for stateful triggers (e.g., Random)
@@ -65,6 +71,8 @@ impl ModuleEvaluator {
     pub fn new() -> Self {
         Self {
             audio_trigger_data: AudioTriggerData::default(),
+            channel_audio_data: HashMap::new(),
+            beat_grid: BeatGridState::default(),
             start_time: Instant::now(),
             trigger_states: HashMap::new(),
             cached_result: ModuleEvalResult::default(),
@@ -99,12 +107,57 @@ impl ModuleEvaluator {
 
     /// Update audio trigger data from analysis
     pub fn update_audio(&mut self, analysis: &AudioAnalysisV2) {
-        self.audio_trigger_data.band_energies = analysis.band_energies;
-        self.audio_trigger_data.rms_volume = analysis.rms_volume;
-        self.audio_trigger_data.peak_volume = analysis.peak_volume;
-        self.audio_trigger_data.beat_detected = analysis.beat_detected;
-        self.audio_trigger_data.beat_strength = analysis.beat_strength;
-        self.audio_trigger_data.bpm = analysis.tempo_bpm;
+        Self::copy_audio(&mut self.audio_trigger_data, analysis);
+    }
+
+    /// Replace the trigger data of the named channel analyzers
+    pub fn update_audio_channels<'a>(
+        &mut self,
+        analyses: impl IntoIterator<Item = (&'a str, AudioAnalysisV2)>,
+    ) {
+        let mut seen = std::collections::HashSet::new();
+        for (name, analysis) in analyses {
+            seen.insert(name);
+            if let Some(data) = self.channel_audio_data.get_mut(name) {
+                Self::copy_audio(data, &analysis);
+            } else {
+                let mut data = AudioTriggerData::default();
+                Self::copy_audio(&mut data, &analysis);
+                self.channel_audio_data.insert(name.to_string(), data);
+            }
+        }
+        self.channel_audio_data
+            .retain(|name, _| seen.contains(name.as_str()));
+    }
+
+    /// Update the beat grid used by beat-grid triggers
+    pub fn update_beat_grid(&mut self, state: &BeatGridState) {
+        self.beat_grid = *state;
+    }
+
+    /// Audio data for an audio source: the main input for `None`, otherwise
+    /// the named channel analyzer
+    pub fn audio_data(&self, analyzer: Option<&str>) -> Option<&AudioTriggerData> {
+        match analyzer {
+            None => Some(&self.audio_trigger_data),
+            Some(name) => self.channel_audio_data.get(name),
+        }
+    }
+
+    fn copy_audio(data: &mut AudioTriggerData, analysis: &AudioAnalysisV2) {
+        data.band_energies = analysis.band_energies;
+        data.rms_volume = analysis.rms_volume;
+        data.peak_volume = analysis.peak_volume;
+        data.beat_detected = analysis.beat_detected;
+        data.beat_strength = analysis.beat_strength;
+        data.bpm = analysis.tempo_bpm;
+        data.onset_detected = analysis.onset_detected;
+        data.band_onsets = analysis.band_onsets;
+        data.spectral_centroid = analysis.spectral_centroid;
+        data.spectral_flatness = analysis.spectral_flatness;
+        data.spectral_rolloff = analysis.spectral_rolloff;
+        data.chroma = analysis.chroma;
+        data.key = analysis.key;
     }
 
     /// Update active keyboard keys for evaluation.
@@ -163,6 +216,8 @@ impl ModuleEvaluator {
                     output_fps: 60.0,
                     ndi_enabled: false,
                     ndi_stream_name: String::new(),
+                    calibration_3d: Default::default(),
+                    color_measurements: None,
                 },
                 layer_part_id: 0,
                 mesh: MeshType::default(),
@@ -231,8 +286,17 @@ impl ModuleEvaluator {
         // keeping it as it was but maybe less frequently? leaving as is per instructions to preserve functionality)
 
         // Step 1: Evaluate all trigger nodes
+        let silence = AudioTriggerData::default();
         for part in &module.parts {
             if let ModulePartType::Trigger(trigger_type) = &part.part_type {
+                // Audio triggers bound to a channel analyzer read only that channel
+                let audio_data = match trigger_type {
+                    TriggerType::AudioFFT {
+                        analyzer: Some(name),
+                        ..
+                    } => self.channel_audio_data.get(name).unwrap_or(&silence),
+                    _ => &self.audio_trigger_data,
+                };
                 let state = self.trigger_states.entry(part.id).or_default();
                 let values = self
                     .cached_result
@@ -245,7 +309,8 @@ impl ModuleEvaluator {
                 Self::compute_trigger_output(
                     trigger_type,
                     state,
-                    &self.audio_trigger_data,
+                    audio_data,
+                    &self.beat_grid,
                     self.start_time,
                     shared_state,
                     &self.active_keys,
@@ -808,12 +873,36 @@ impl ModuleEvaluator {
                             }
                             break;
                         }
+                        // Bypassed parts are traversed but not applied
+                        ModulePartType::Modulizer(_)
+                        | ModulePartType::Mask(_)
+                        | ModulePartType::Mesh(_)
+                            if part.bypass =>
+                        {
+                            current_id = part.id;
+                        }
+                        ModulePartType::Modulizer(
+                            mod_type @ ModulizerType::AudioReactive {
+                                source,
+                                analyzer,
+                                scale_opacity: true,
+                            },
+                        ) => {
+                            // Opted-in audio reactive parts scale the chain by their input level
+                            op.opacity *= self
+                                .audio_data(analyzer.as_deref())
+                                .map_or(0.0, |data| audio_reactive_level(data, source));
+                            op.effects.push(mod_type.clone());
+                            current_id = part.id;
+                        }
                         ModulePartType::Modulizer(mod_type) => {
                             op.effects.push(mod_type.clone());
                             current_id = part.id;
                         }
                         ModulePartType::Mask(mask_type) => {
-                            op.masks.push(mask_type.clone());
+                            // Animated mask points are resolved per frame
+                            op.masks
+                                .push(mask_type.at_time(self.start_time.elapsed().as_secs_f32()));
                             current_id = part.id;
                         }
                         ModulePartType::Mesh(mesh_type) => {
@@ -850,6 +939,7 @@ impl ModuleEvaluator {
         trigger_type: &TriggerType,
         state: &mut TriggerState,
         audio_data: &AudioTriggerData,
+        beat_grid: &BeatGridState,
         start_time: Instant,
         shared_state: &SharedMediaState,
         active_keys: &std::collections::HashSet<String>,
@@ -903,9 +993,71 @@ impl ModuleEvaluator {
                         invert,
                     );
                 }
+                if output_config.bpm_output {
+                    // Same 0-200 BPM range as AudioMappingType::Tempo
+                    push_val_internal(
+                        (audio_data.bpm.unwrap_or(0.0) / 200.0).clamp(0.0, 1.0),
+                        output,
+                        output_config.inverted_outputs.contains("BPM Out"),
+                    );
+                }
+                if output_config.onset_outputs {
+                    push_val_internal(
+                        if audio_data.onset_detected { 1.0 } else { 0.0 },
+                        output,
+                        output_config.inverted_outputs.contains("Onset Out"),
+                    );
+                    for (band, onset) in AUDIO_BAND_NAMES.iter().zip(audio_data.band_onsets) {
+                        let invert = output_config
+                            .inverted_outputs
+                            .contains(&format!("{} Onset", band));
+                        push_val_internal(if onset { 1.0 } else { 0.0 }, output, invert);
+                    }
+                }
+                if output_config.spectral_outputs {
+                    push_val_internal(
+                        normalize_frequency(audio_data.spectral_centroid),
+                        output,
+                        output_config.inverted_outputs.contains("Centroid Out"),
+                    );
+                    push_val_internal(
+                        audio_data.spectral_flatness,
+                        output,
+                        output_config.inverted_outputs.contains("Flatness Out"),
+                    );
+                    push_val_internal(
+                        normalize_frequency(audio_data.spectral_rolloff),
+                        output,
+                        output_config.inverted_outputs.contains("Rolloff Out"),
+                    );
+                }
+                if output_config.chroma_outputs {
+                    for (pitch_class, &val) in audio_data.chroma.iter().enumerate() {
+                        let invert = output_config
+                            .inverted_outputs
+                            .contains(&format!("Chroma {}", pitch_class_name(pitch_class)));
+                        push_val_internal(if val > *threshold { val } else { 0.0 }, output, invert);
+                    }
+                }
+                if output_config.key_output {
+                    push_val_internal(
+                        audio_data.key.map_or(0.0, |key| key.index() as f32 / 23.0),
+                        output,
+                        output_config.inverted_outputs.contains("Key Out"),
+                    );
+                }
             }
             TriggerType::Beat => push_val_internal(
-                if audio_data.beat_detected { 1.0 } else { 0.0 },
+                if beat_grid.beat() || (!beat_grid.running && audio_data.beat_detected) {
+                    1.0
+                } else {
+                    0.0
+                },
+                output,
+                false,
+            ),
+            TriggerType::BeatGrid { division } => push_val_internal(
+                if beat_grid.fired(*division) { 1.0 } else { 0.0 },
                 output,
                 false,
             ),
@@ -1111,6 +1263,21 @@ impl ModuleEvaluator {
         }
     }
 }
+
+/// Level (0-1) of an audio reactive source ("Bass", "RMS", "Peak", "BPM", ...)
+fn audio_reactive_level(data: &AudioTriggerData, source: &str) -> f32 {
+    let level = match AUDIO_BAND_NAMES.iter().position(|band| *band == source) {
+        Some(band) => data.band_energies[band],
+        None => match source {
+            "Peak" => data.peak_volume,
+            // Same 0-200 BPM range as AudioMappingType::Tempo
+            "BPM" => data.bpm.unwrap_or(0.0) / 200.0,
+            _ => data.rms_volume,
+        },
+    };
+    level.clamp(0.0, 1.0)
+}
+
 #[cfg(test)]
 mod tests_evaluator {
     use super::*;
@@ -1193,6 +1360,7 @@ mod tests_evaluator {
             band: crate::module::AudioBand::Bass, // irrelevant here
             threshold: 0.5,
             output_config: config,
+            analyzer: None,
         });
 
         let part_id = module.add_part_with_type(trigger_part, (0.0, 0.0));
@@ -1210,6 +1378,130 @@ mod tests_evaluator {
         assert_eq!(values[2], 1.0); // Beat detected
     }
 
+    #[test]
+    fn test_audio_trigger_reads_its_channel() {
+        let mut evaluator = ModuleEvaluator::new();
+        let beat = AudioAnalysisV2 {
+            beat_detected: true,
+            ..Default::default()
+        };
+        evaluator.update_audio(&beat);
+        evaluator.update_audio_channels([("A", beat), ("B", AudioAnalysisV2::default())]);
+
+        let mut module = create_test_module();
+        let trigger = |analyzer: &str| {
+            ModulePartType::Trigger(TriggerType::AudioFFT {
+                band: crate::module::AudioBand::Bass,
+                threshold: 0.5,
+                output_config: AudioTriggerOutputConfig {
+                    beat_output: true,
+                    ..Default::default()
+                },
+                analyzer: Some(analyzer.to_string()),
+            })
+        };
+        let on_a = module.add_part_with_type(trigger("A"), (0.0, 0.0));
+        let on_b = module.add_part_with_type(trigger("B"), (0.0, 100.0));
+
+        let result = evaluator.evaluate(&module, &crate::module::SharedMediaState::default(), 0);
+        // The beat on channel A (and the main input) must not fire the node bound to B
+        assert_eq!(result.trigger_values[&on_a], vec![1.0]);
+        assert_eq!(result.trigger_values[&on_b], vec![0.0]);
+    }
+
+    #[test]
+    fn test_trigger_spectral_outputs() {
+        let mut evaluator = ModuleEvaluator::new();
+        let mut chroma = [0.0; 12];
+        chroma[9] = 0.9;
+        let analysis = AudioAnalysisV2 {
+            onset_detected: true,
+            band_onsets: [false, true, false, false, false, false, false, false, false],
+            spectral_flatness: 0.25,
+            chroma,
+            key: Some(crate::audio::features::MusicalKey {
+                tonic: 9,
+                minor: true,
+            }),
+            ..Default::default()
+        };
+        evaluator.update_audio(&analysis);
+
+        let mut module = create_test_module();
+        let mut config = AudioTriggerOutputConfig {
+            beat_output: false,
+            onset_outputs: true,
+            spectral_outputs: true,
+            chroma_outputs: true,
+            key_output: true,
+            ..Default::default()
+        };
+        config.inverted_outputs.insert("Bass Onset".to_string());
+        let expected = config.generate_outputs();
+        let part_id = module.add_part_with_type(
+            ModulePartType::Trigger(TriggerType::AudioFFT {
+                band: crate::module::AudioBand::Bass,
+                threshold: 0.5,
+                output_config: config,
+                analyzer: None,
+            }),
+            (0.0, 0.0),
+        );
+
+        let result = evaluator.evaluate(&module, &crate::module::SharedMediaState::default(), 0);
+        let values = &result.trigger_values[&part_id];
+        assert_eq!(values.len(), expected.len());
+
+        let value = |name: &str| values[expected.iter().position(|s| s.name == name).unwrap()];
+        assert_eq!(value("Onset Out"), 1.0);
+        assert_eq!(value("Bass Onset"), 0.0); // Inverted
+        assert_eq!(value("Mid Onset"), 0.0);
+        assert_eq!(value("Flatness Out"), 0.25);
+        assert_eq!(value("Chroma A"), 0.9);
+        assert_eq!(value("Chroma C"), 0.0);
+        assert_eq!(value("Key Out"), 21.0 / 23.0);
+    }
+
+    #[test]
+    fn test_trigger_beat_grid() {
+        let mut evaluator = ModuleEvaluator::new();
+        let mut module = create_test_module();
+        let part_id = module.add_part_with_type(
+            ModulePartType::Trigger(TriggerType::BeatGrid {
+                division: crate::audio::beat_grid::BeatDivision::Bar,
+            }),
+            (0.0, 0.0),
+        );
+        let shared = crate::module::SharedMediaState::default();
+
+        let mut grid = crate::audio::beat_grid::BeatGrid::default();
+        grid.set_tempo(120.0);
+        evaluator.update_beat_grid(grid.advance(0.0)); // Downbeat of bar 1
+        let result = evaluator.evaluate(&module, &shared, 0);
+        assert_eq!(result.trigger_values[&part_id][0], 1.0);
+
+        evaluator.update_beat_grid(grid.advance(0.5)); // Beat 2
+        let result = evaluator.evaluate(&module, &shared, 0);
+        assert_eq!(result.trigger_values[&part_id][0], 0.0);
+    }
+
+    #[test]
+    fn test_channel_audio_data() {
+        let mut evaluator = ModuleEvaluator::new();
+        let kick = AudioAnalysisV2 {
+            rms_volume: 0.7,
+            ..Default::default()
+        };
+        evaluator.update_audio_channels([("kick", kick.clone()), ("vocals", kick)]);
+        assert_eq!(evaluator.audio_data(Some("kick")).unwrap().rms_volume, 0.7);
+        assert_eq!(evaluator.audio_data(None).unwrap().rms_volume, 0.0);
+
+        // Analyzers that are no longer reported are dropped
+        evaluator.update_audio_channels([("vocals", AudioAnalysisV2::default())]);
+        assert!(evaluator.audio_data(Some("kick")).is_none());
+        assert!(evaluator.audio_data(Some("vocals")).is_some());
+    }
+
     #[test]
     fn test_evaluator_propagation() {
         let mut evaluator = ModuleEvaluator::new();
@@ -1305,6 +1597,42 @@ mod tests_evaluator {
         }
     }
 
+    #[test]
+    fn test_audio_reactive_opacity_is_opt_in() {
+        let render_opacity = |scale_opacity: bool, rms: f32| {
+            let mut evaluator = ModuleEvaluator::new();
+            evaluator.update_audio(&AudioAnalysisV2 {
+                rms_volume: rms,
+                ..Default::default()
+            });
+            let mut module = create_test_module();
+            let s_id = module.add_part(crate::module::PartType::Source, (0.0, 0.0));
+            let m_id = module.add_part_with_type(
+                ModulePartType::Modulizer(ModulizerType::AudioReactive {
+                    source: "RMS".to_string(),
+                    analyzer: None,
+                    scale_opacity,
+                }),
+                (100.0, 0.0),
+            );
+            let l_id = module.add_part(crate::module::PartType::Layer, (200.0, 0.0));
+            let o_id = module.add_part(crate::module::PartType::Output, (300.0, 0.0));
+            module.add_connection(s_id, 0, m_id, 0);
+            module.add_connection(m_id, 0, l_id, 0);
+            module.add_connection(l_id, 0, o_id, 0);
+
+            let result =
+                evaluator.evaluate(&module, &crate::module::SharedMediaState::default(), 0);
+            result.render_ops[0].opacity
+        };
+
+        // Without audio the chain passes through unless scaling is enabled
+        let full = render_opacity(false, 0.0);
+        assert!(full > 0.0);
+        assert_eq!(render_opacity(true, 0.0), 0.0);
+        assert!((render_opacity(true, 0.5) - full * 0.5).abs() < 1e-6);
+    }
+
     #[test]
     fn test_render_trace_prefers_layer_visual_input_over_trigger_input() {
         let mut evaluator = ModuleEvaluator::new();
@@ -2,6 +2,8 @@
 //!
 //! Phase 2 feature: Multiple independent output windows for multi-projector setups
 
+use crate::dome::DomeConfig;
+use crate::hdr::OutputTransformConfig;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
@@ -77,6 +79,25 @@ pub struct EdgeBlendConfig {
     pub bottom: EdgeBlendZone,
     /// Blend curve gamma (typically 2.2)
     pub gamma: f32,
+    /// Per-channel blend curve gamma (R, G, B), overriding `gamma`
+    #[serde(default)]
+    pub channel_gamma: Option<[f32; 3]>,
+    /// Grayscale mask image multiplied into the edge ramps, for curved screens
+    /// and irregular overlaps (painted, or computed by structured-light
+    /// calibration)
+    #[serde(default)]
+    pub mask: Option<String>,
+    /// Black level lift (R, G, B) outside the overlap zones, matching the
+    /// brighter black where projectors overlap
+    #[serde(default)]
+    pub black_level: [f32; 3],
+    /// Part of the blend ramp over which the black level lift fades out (0.0-1.0)
+    #[serde(default = "default_black_level_feather")]
+    pub black_level_feather: f32,
+}
+
+fn default_black_level_feather() -> f32 {
+    0.05
 }
 
 impl Default for EdgeBlendConfig {
@@ -87,10 +108,31 @@ impl Default for EdgeBlendConfig {
             top: EdgeBlendZone::default(),
             bottom: EdgeBlendZone::default(),
             gamma: 2.2,
+            channel_gamma: None,
+            mask: None,
+            black_level: [0.0; 3],
+            black_level_feather: default_black_level_feather(),
         }
     }
 }
 
+impl EdgeBlendConfig {
+    /// Whether the output needs a blend pass at all
+    pub fn is_active(&self) -> bool {
+        self.left.enabled
+            || self.right.enabled
+            || self.top.enabled
+            || self.bottom.enabled
+            || self.mask.is_some()
+            || self.black_level.iter().any(|level| *level > 0.0)
+    }
+
+    /// Blend curve gamma of each channel (R, G, B)
+    pub fn gamma_rgb(&self) -> [f32; 3] {
+        self.channel_gamma.unwrap_or([self.gamma; 3])
+    }
+}
+
 /// Configuration for one edge of the blend zone
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EdgeBlendZone {
@@ -115,6 +157,10 @@ impl Default for EdgeBlendZone {
 /// Color calibration for per-output color correction
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ColorCalibration {
+    /// Whether the calibration pass runs; off until the user opts in, as the
+    /// pass was disabled by default after black screen regressions
+    #[serde(default)]
+    pub enabled: bool,
     /// Brightness offset (-1.0 to 1.0)
     pub brightness: f32,
     /// Contrast multiplier (0.0 to 2.0)
@@ -127,21 +173,272 @@ pub struct ColorCalibration {
     pub color_temp: f32,
     /// Saturation multiplier (0.0 to 2.0)
     pub saturation: f32,
+    /// Correction 3D LUT (.cube) applied after the other adjustments,
+    /// e.g. solved by multi-point color matching
+    #[serde(default)]
+    pub lut: Option<String>,
 }
 
 impl Default for ColorCalibration {
     fn default() -> Self {
         Self {
+            enabled: false,
             brightness: 0.0,
             contrast: 1.0,
             gamma: Vec2::new(1.0, 1.0), // R, G
             gamma_b: 1.0,               // B
             color_temp: 6500.0,         // D65 standard
             saturation: 1.0,
+            lut: None,
         }
     }
 }
 
+impl ColorCalibration {
+    /// Whether the output needs a color calibration pass at all
+    pub fn is_active(&self) -> bool {
+        let neutral = Self {
+            enabled: true,
+            ..Self::default()
+        };
+        self.enabled && *self != neutral
+    }
+}
+
+/// Rotation of an output slice, clockwise in 90° steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SliceRotation {
+    /// Upright
+    #[default]
+    None,
+    /// Rotated 90° clockwise
+    Cw90,
+    /// Upside down
+    Cw180,
+    /// Rotated 90° counter-clockwise
+    Cw270,
+}
+
+impl SliceRotation {
+    /// All rotations in clockwise order
+    pub const ALL: [SliceRotation; 4] = [Self::None, Self::Cw90, Self::Cw180, Self::Cw270];
+
+    /// Number of clockwise quarter turns
+    pub fn quarter_turns(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Cw90 => 1,
+            Self::Cw180 => 2,
+            Self::Cw270 => 3,
+        }
+    }
+}
+
+/// Rectangle on the output raster in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SliceRect {
+    /// Left edge
+    pub x: u32,
+    /// Top edge
+    pub y: u32,
+    /// Width
+    pub width: u32,
+    /// Height
+    pub height: u32,
+}
+
+impl SliceRect {
+    /// Create a new pixel rectangle
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Check if a pixel lies inside this rectangle
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    /// Check if this rectangle shares pixels with another rectangle
+    pub fn intersects(&self, other: &SliceRect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+/// Crop inside the input region of a slice, as fractions of its size
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SliceCrop {
+    /// Cut from the left edge (0.0-1.0)
+    pub left: f32,
+    /// Cut from the top edge (0.0-1.0)
+    pub top: f32,
+    /// Cut from the right edge (0.0-1.0)
+    pub right: f32,
+    /// Cut from the bottom edge (0.0-1.0)
+    pub bottom: f32,
+}
+
+/// One input slice of an output, taken from the output's content and placed
+/// onto the output raster (e.g. one LED panel run or one head of a splitter)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputSlice {
+    /// User-friendly name
+    pub name: String,
+    /// Whether the slice is drawn
+    pub enabled: bool,
+    /// Region of the output's content shown by the slice (0-1)
+    pub input: CanvasRegion,
+    /// Crop applied to the input region
+    pub crop: SliceCrop,
+    /// Rotation of the input on the output raster
+    pub rotation: SliceRotation,
+    /// Mirror the input left to right (applied before rotation)
+    pub flip_horizontal: bool,
+    /// Mirror the input top to bottom (applied before rotation)
+    pub flip_vertical: bool,
+    /// Placement on the output raster
+    pub output: SliceRect,
+}
+
+impl OutputSlice {
+    /// Create a slice copying an input region to an output rectangle
+    pub fn new(name: String, input: CanvasRegion, output: SliceRect) -> Self {
+        Self {
+            name,
+            enabled: true,
+            input,
+            crop: SliceCrop::default(),
+            rotation: SliceRotation::None,
+            flip_horizontal: false,
+            flip_vertical: false,
+            output,
+        }
+    }
+
+    /// Content positions (0-1) shown at the top-left, top-right, bottom-right
+    /// and bottom-left corners of the output rectangle
+    pub fn source_corners(&self) -> [Vec2; 4] {
+        let crop = &self.crop;
+        let left = self.input.x + self.input.width * crop.left.clamp(0.0, 1.0);
+        let right = self.input.x + self.input.width * (1.0 - crop.right.clamp(0.0, 1.0));
+        let top = self.input.y + self.input.height * crop.top.clamp(0.0, 1.0);
+        let bottom = self.input.y + self.input.height * (1.0 - crop.bottom.clamp(0.0, 1.0));
+
+        let mut corners = [
+            Vec2::new(left, top),
+            Vec2::new(right, top),
+            Vec2::new(right, bottom),
+            Vec2::new(left, bottom),
+        ];
+        if self.flip_horizontal {
+            corners.swap(0, 1);
+            corners.swap(2, 3);
+        }
+        if self.flip_vertical {
+            corners.swap(0, 3);
+            corners.swap(1, 2);
+        }
+        // Turning the image clockwise brings the source's bottom-left
+        // corner to the top-left of the output
+        corners.rotate_right(self.rotation.quarter_turns());
+        corners
+    }
+
+    /// Corners of the output rectangle (top-left, top-right, bottom-right,
+    /// bottom-left) normalized to an output raster (0.0-1.0)
+    pub fn target_corners(&self, resolution: (u32, u32)) -> [Vec2; 4] {
+        let size = Vec2::new(resolution.0.max(1) as f32, resolution.1.max(1) as f32);
+        let rect = &self.output;
+        let min = Vec2::new(rect.x as f32, rect.y as f32) / size;
+        let max = Vec2::new(
+            rect.x.saturating_add(rect.width) as f32,
+            rect.y.saturating_add(rect.height) as f32,
+        ) / size;
+        [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+    }
+}
+
+/// Slice layout of an output: many regions of the output's content (0-1)
+/// composed onto one output window, for LED processors and multi-head
+/// splitters
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SliceLayout {
+    /// Slices in drawing order (later slices cover earlier ones)
+    pub slices: Vec<OutputSlice>,
+}
+
+impl SliceLayout {
+    /// Whether the output needs a slicing pass at all
+    pub fn is_active(&self) -> bool {
+        self.slices.iter().any(|slice| slice.enabled)
+    }
+
+    /// Append a slice and return its index
+    pub fn add_slice(&mut self, slice: OutputSlice) -> usize {
+        self.slices.push(slice);
+        self.slices.len() - 1
+    }
+
+    /// Remove a slice by index
+    pub fn remove_slice(&mut self, index: usize) -> Option<OutputSlice> {
+        (index < self.slices.len()).then(|| self.slices.remove(index))
+    }
+
+    /// Topmost enabled slice covering an output pixel
+    pub fn slice_at(&self, x: u32, y: u32) -> Option<usize> {
+        self.slices
+            .iter()
+            .rposition(|slice| slice.enabled && slice.output.contains(x, y))
+    }
+
+    /// Move a slice on the output raster, keeping it inside the raster
+    pub fn move_slice(&mut self, index: usize, dx: i64, dy: i64, resolution: (u32, u32)) {
+        let Some(slice) = self.slices.get_mut(index) else {
+            return;
+        };
+        let rect = &mut slice.output;
+        let max_x = resolution.0.saturating_sub(rect.width) as i64;
+        let max_y = resolution.1.saturating_sub(rect.height) as i64;
+        rect.x = (rect.x as i64 + dx).clamp(0, max_x) as u32;
+        rect.y = (rect.y as i64 + dy).clamp(0, max_y) as u32;
+    }
+
+    /// Pairs of enabled slices whose output rectangles overlap
+    pub fn overlaps(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, a) in self.slices.iter().enumerate() {
+            for (j, b) in self.slices.iter().enumerate().skip(i + 1) {
+                if a.enabled && b.enabled && a.output.intersects(&b.output) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Enabled slices that extend past the output raster
+    pub fn out_of_bounds(&self, resolution: (u32, u32)) -> Vec<usize> {
+        self.slices
+            .iter()
+            .enumerate()
+            .filter(|(_, slice)| {
+                let rect = &slice.output;
+                slice.enabled
+                    && (rect.x.saturating_add(rect.width) > resolution.0
+                        || rect.y.saturating_add(rect.height) > resolution.1)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
 /// Configuration for a single output window (projector)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct OutputConfig {
@@ -157,6 +454,18 @@ pub struct OutputConfig {
     pub edge_blend: EdgeBlendConfig,
     /// Color calibration settings
     pub color_calibration: ColorCalibration,
+    /// Dome mode: content is treated as spherical and resampled to a fisheye
+    /// domemaster, equirectangular panorama or projector slice
+    #[serde(default)]
+    pub dome: Option<DomeConfig>,
+    /// Slice layout composing regions of this output's content (0-1) onto
+    /// the window; the whole content is shown while the layout is empty
+    #[serde(default)]
+    pub slicing: SliceLayout,
+    /// Tone mapping and signal encoding applied to the linear float
+    /// composition as the last step
+    #[serde(default)]
+    pub output_transform: OutputTransformConfig,
     /// Whether to run in fullscreen exclusive mode
     pub fullscreen: bool,
 }
@@ -176,6 +485,9 @@ impl OutputConfig {
             resolution,
             edge_blend: EdgeBlendConfig::default(),
             color_calibration: ColorCalibration::default(),
+            dome: None,
+            slicing: SliceLayout::default(),
+            output_transform: OutputTransformConfig::default(),
             fullscreen: false,
         }
     }
@@ -323,7 +635,7 @@ impl OutputManager {
                             width: overlap,
                             offset: 0.0,
                         },
-                        gamma: 2.2,
+                        ..EdgeBlendConfig::default()
                     };
                 }
             }
@@ -389,4 +701,127 @@ mod tests {
             assert!(blend_count >= 2); // Corner projectors have 2 edges, others have more
         }
     }
+
+    #[test]
+    fn test_edge_blend_mask_and_black_level() {
+        // Configurations saved before masks and black levels existed
+        let json = r#"{"left":{"enabled":false,"width":0.1,"offset":0.0},
+            "right":{"enabled":false,"width":0.1,"offset":0.0},
+            "top":{"enabled":false,"width":0.1,"offset":0.0},
+            "bottom":{"enabled":false,"width":0.1,"offset":0.0},"gamma":1.8}"#;
+        let mut config: EdgeBlendConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.mask, None);
+        assert_eq!(config.black_level_feather, 0.05);
+        assert_eq!(config.gamma_rgb(), [1.8; 3]);
+        assert!(!config.is_active());
+
+        config.channel_gamma = Some([2.0, 2.2, 2.4]);
+        assert_eq!(config.gamma_rgb(), [2.0, 2.2, 2.4]);
+
+        // A mask or black level alone needs the blend pass
+        config.mask = Some("output_1_blend.png".to_string());
+        assert!(config.is_active());
+        config.mask = None;
+        config.black_level = [0.02, 0.02, 0.03];
+        assert!(config.is_active());
+    }
+
+    #[test]
+    fn test_color_calibration_lut() {
+        // Configurations saved before correction LUTs existed
+        let json = r#"{"brightness":0.0,"contrast":1.0,"gamma":[1.0,1.0],"gamma_b":1.0,
+            "color_temp":6500.0,"saturation":1.0}"#;
+        let mut calibration: ColorCalibration = serde_json::from_str(json).unwrap();
+        assert_eq!(calibration.lut, None);
+        assert!(!calibration.enabled);
+        assert!(!calibration.is_active());
+
+        // The pass only runs once enabled
+        calibration.lut = Some("output_1_color.cube".to_string());
+        assert!(!calibration.is_active());
+        calibration.enabled = true;
+        assert!(calibration.is_active());
+        calibration.lut = None;
+        assert!(!calibration.is_active());
+        calibration.saturation = 1.2;
+        assert!(calibration.is_active());
+    }
+
+    #[test]
+    fn test_slice_corners() {
+        let mut slice = OutputSlice::new(
+            "Panel".to_string(),
+            CanvasRegion::new(0.5, 0.0, 0.5, 0.5),
+            SliceRect::new(0, 100, 200, 100),
+        );
+        assert_eq!(
+            slice.target_corners((400, 400)),
+            [
+                Vec2::new(0.0, 0.25),
+                Vec2::new(0.5, 0.25),
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.0, 0.5),
+            ]
+        );
+
+        slice.crop.left = 0.5;
+        let [top_left, _, bottom_right, _] = slice.source_corners();
+        assert_eq!(top_left, Vec2::new(0.75, 0.0));
+        assert_eq!(bottom_right, Vec2::new(1.0, 0.5));
+
+        // A clockwise quarter turn shows the source's bottom-left corner at
+        // the top-left of the output
+        slice.rotation = SliceRotation::Cw90;
+        assert_eq!(slice.source_corners()[0], Vec2::new(0.75, 0.5));
+
+        slice.rotation = SliceRotation::None;
+        slice.flip_horizontal = true;
+        slice.flip_vertical = true;
+        assert_eq!(slice.source_corners()[0], Vec2::new(1.0, 0.5));
+    }
+
+    #[test]
+    fn test_slice_layout_editing() {
+        let mut layout = SliceLayout::default();
+        assert!(!layout.is_active());
+
+        let first = layout.add_slice(OutputSlice::new(
+            "A".to_string(),
+            CanvasRegion::new(0.0, 0.0, 0.5, 1.0),
+            SliceRect::new(0, 0, 100, 100),
+        ));
+        let second = layout.add_slice(OutputSlice::new(
+            "B".to_string(),
+            CanvasRegion::new(0.5, 0.0, 0.5, 1.0),
+            SliceRect::new(50, 50, 100, 100),
+        ));
+        assert!(layout.is_active());
+        assert_eq!(layout.slice_at(75, 75), Some(second));
+        assert_eq!(layout.slice_at(10, 10), Some(first));
+        assert_eq!(layout.overlaps(), vec![(first, second)]);
+
+        layout.move_slice(second, 500, -10, (200, 200));
+        assert_eq!(
+            layout.slices[second].output,
+            SliceRect::new(100, 40, 100, 100)
+        );
+        assert!(layout.overlaps().is_empty());
+        assert!(layout.out_of_bounds((200, 200)).is_empty());
+        assert_eq!(layout.out_of_bounds((150, 200)), vec![second]);
+
+        assert!(layout.remove_slice(first).is_some());
+        assert_eq!(layout.slices.len(), 1);
+
+        // Outputs saved before slicing existed
+        let mut output = OutputConfig::new(
+            1,
+            "Output".to_string(),
+            CanvasRegion::new(0.0, 0.0, 1.0, 1.0),
+            (1920, 1080),
+        );
+        let mut json = serde_json::to_value(&output).unwrap();
+        json.as_object_mut().unwrap().remove("slicing");
+        output = serde_json::from_value(json).unwrap();
+        assert!(!output.slicing.is_active());
+    }
 }
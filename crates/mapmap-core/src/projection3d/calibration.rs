@@ -0,0 +1,397 @@
+//! Projector calibration from 2D/3D point correspondences
+//!
+//! The projector is modelled as a pinhole camera. Its 3x4 projection matrix
+//! is solved with the normalized Direct Linear Transform from at least six
+//! points picked on the scene model and in the projector image, then split
+//! into intrinsics (focal length, principal point, skew) and pose.
+
+use super::Projection3dError;
+use glam::{DMat3, DVec3, Mat3, Mat4, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+/// Correspondences the DLT needs (11 unknowns, two equations per point)
+pub const MIN_CORRESPONDENCES: usize = 6;
+
+/// A point on the scene model and where it appears in the projector image
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PointCorrespondence {
+    /// Position in model coordinates.
+    pub world: Vec3,
+    /// Position in the projector image (normalized 0-1, origin top left).
+    pub image: Vec2,
+}
+
+impl PointCorrespondence {
+    /// Create a new correspondence
+    pub fn new(world: Vec3, image: Vec2) -> Self {
+        Self { world, image }
+    }
+}
+
+/// Intrinsics and pose of a calibrated projector
+///
+/// Intrinsics are in normalized image units: a focal length of 1.0 means the
+/// image is one unit wide at one unit of distance.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SolvedProjector {
+    /// Horizontal focal length.
+    pub fx: f32,
+    /// Vertical focal length.
+    pub fy: f32,
+    /// Principal point (lens center) in the image.
+    pub cx: f32,
+    /// Principal point (lens center) in the image.
+    pub cy: f32,
+    /// Skew between the image axes (0 for real lenses).
+    pub skew: f32,
+    /// Rotation from model to projector coordinates.
+    pub rotation: Mat3,
+    /// Translation from model to projector coordinates.
+    pub translation: Vec3,
+    /// RMS distance between picked and reprojected image points.
+    pub reprojection_error: f32,
+}
+
+impl SolvedProjector {
+    /// Projector position in model coordinates
+    pub fn position(&self) -> Vec3 {
+        -(self.rotation.transpose() * self.translation)
+    }
+
+    /// Image position of `world`, or `None` if it is behind the projector
+    pub fn project(&self, world: Vec3) -> Option<Vec2> {
+        let p = self.rotation * world + self.translation;
+        if p.z <= f32::EPSILON {
+            return None;
+        }
+        Some(Vec2::new(
+            (self.fx * p.x + self.skew * p.y) / p.z + self.cx,
+            self.fy * p.y / p.z + self.cy,
+        ))
+    }
+
+    /// Clip-space transform for rendering the model from the projector
+    ///
+    /// Depth is mapped to 0-1 between `near` and `far` (in model units).
+    pub fn clip_from_world(&self, near: f32, far: f32) -> Mat4 {
+        // Normalized image (0-1, y down) to NDC (-1-1, y up), with depth
+        let depth = far / (far - near);
+        let projection = Mat4::from_cols(
+            Vec4::new(2.0 * self.fx, 0.0, 0.0, 0.0),
+            Vec4::new(2.0 * self.skew, -2.0 * self.fy, 0.0, 0.0),
+            Vec4::new(2.0 * self.cx - 1.0, 1.0 - 2.0 * self.cy, depth, 1.0),
+            Vec4::new(0.0, 0.0, -near * depth, 0.0),
+        );
+        let view = Mat4::from_cols(
+            self.rotation.x_axis.extend(0.0),
+            self.rotation.y_axis.extend(0.0),
+            self.rotation.z_axis.extend(0.0),
+            self.translation.extend(1.0),
+        );
+        projection * view
+    }
+}
+
+/// Calibration state of one projector output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectorCalibration {
+    /// Points picked by the user.
+    pub correspondences: Vec<PointCorrespondence>,
+    /// Near clipping distance (model units).
+    pub near: f32,
+    /// Far clipping distance (model units).
+    pub far: f32,
+    /// Result of the last successful solve.
+    pub solved: Option<SolvedProjector>,
+}
+
+impl Default for ProjectorCalibration {
+    fn default() -> Self {
+        Self {
+            correspondences: Vec::new(),
+            near: 0.01,
+            far: 1000.0,
+            solved: None,
+        }
+    }
+}
+
+impl ProjectorCalibration {
+    /// Solve the projector from the current correspondences
+    pub fn solve(&mut self) -> Result<&SolvedProjector, Projection3dError> {
+        let solved = solve_projector(&self.correspondences)?;
+        Ok(self.solved.insert(solved))
+    }
+
+    /// Clip-space transform of the solved projector
+    pub fn clip_from_world(&self) -> Option<Mat4> {
+        self.solved
+            .as_ref()
+            .map(|solved| solved.clip_from_world(self.near, self.far))
+    }
+}
+
+/// Solve intrinsics and pose of a projector from point correspondences
+pub fn solve_projector(
+    points: &[PointCorrespondence],
+) -> Result<SolvedProjector, Projection3dError> {
+    if points.len() < MIN_CORRESPONDENCES {
+        return Err(Projection3dError::NotEnoughPoints(points.len()));
+    }
+
+    // Hartley normalization: center both point sets and scale them to an
+    // average distance of sqrt(2) / sqrt(3) for a well-conditioned system.
+    let image: Vec<DVec3> = points
+        .iter()
+        .map(|p| DVec3::new(p.image.x as f64, p.image.y as f64, 0.0))
+        .collect();
+    let world: Vec<DVec3> = points.iter().map(|p| p.world.as_dvec3()).collect();
+    let (image_center, image_scale) = normalization(&image, 2.0_f64.sqrt());
+    let (world_center, world_scale) = normalization(&world, 3.0_f64.sqrt());
+
+    // Two rows per point of A p = 0, accumulated directly into A^T A
+    let mut ata = [[0.0f64; 12]; 12];
+    for (img, wld) in image.iter().zip(&world) {
+        let x = (*img - image_center) * image_scale;
+        let w = ((*wld - world_center) * world_scale).extend(1.0);
+        let w = [w.x, w.y, w.z, w.w];
+        let mut row_u = [0.0; 12];
+        let mut row_v = [0.0; 12];
+        for k in 0..4 {
+            row_u[k] = w[k];
+            row_u[8 + k] = -x.x * w[k];
+            row_v[4 + k] = w[k];
+            row_v[8 + k] = -x.y * w[k];
+        }
+        for row in [row_u, row_v] {
+            for i in 0..12 {
+                for j in 0..12 {
+                    ata[i][j] += row[i] * row[j];
+                }
+            }
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(ata);
+    let mut order: Vec<usize> = (0..12).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+    // Coplanar or collinear points leave more than one solution
+    let largest = values[order[11]].max(f64::MIN_POSITIVE);
+    if values[order[1]] / largest < 1e-12 {
+        return Err(Projection3dError::Degenerate);
+    }
+    let p: Vec<f64> = (0..12).map(|i| vectors[i][order[0]]).collect();
+
+    // Undo the normalization: P = T^-1 * P_norm * U
+    let mut projection = [[0.0f64; 4]; 3];
+    for (r, row) in projection.iter_mut().enumerate() {
+        let n = &p[r * 4..r * 4 + 4];
+        let m = DVec3::new(n[0], n[1], n[2]) * world_scale;
+        let offset = n[3] - m.dot(world_center);
+        *row = [m.x, m.y, m.z, offset];
+    }
+    let [row_x, row_y, row_w] = &mut projection;
+    for ((x, y), w) in row_x.iter_mut().zip(row_y.iter_mut()).zip(row_w.iter()) {
+        *x = *x / image_scale + image_center.x * w;
+        *y = *y / image_scale + image_center.y * w;
+    }
+
+    let rows = projection.map(|row| DVec3::new(row[0], row[1], row[2]));
+    let mut sign = DMat3::from_cols(rows[0], rows[1], rows[2])
+        .transpose()
+        .determinant()
+        .signum();
+    if sign == 0.0 {
+        return Err(Projection3dError::Degenerate);
+    }
+
+    // RQ decomposition M = K R by Gram-Schmidt from the last row up
+    let m = rows.map(|row| row * sign);
+    let k33 = m[2].length();
+    let r3 = m[2] / k33;
+    let k23 = m[1].dot(r3);
+    let r2_raw = m[1] - r3 * k23;
+    let k22 = r2_raw.length();
+    let r2 = r2_raw / k22;
+    let k13 = m[0].dot(r3);
+    let k12 = m[0].dot(r2);
+    let r1_raw = m[0] - r3 * k13 - r2 * k12;
+    let k11 = r1_raw.length();
+    let r1 = r1_raw / k11;
+    if !(k11 > 0.0 && k22 > 0.0 && k33 > 0.0) {
+        return Err(Projection3dError::Degenerate);
+    }
+
+    // Scale so that K[2][2] = 1, then t = K^-1 p4
+    sign /= k33;
+    let (fx, skew, cx) = (k11 / k33, k12 / k33, k13 / k33);
+    let (fy, cy) = (k22 / k33, k23 / k33);
+    let p4 = DVec3::new(projection[0][3], projection[1][3], projection[2][3]) * sign;
+    let tz = p4.z;
+    let ty = (p4.y - cy * tz) / fy;
+    let tx = (p4.x - skew * ty - cx * tz) / fx;
+
+    let mut solved = SolvedProjector {
+        fx: fx as f32,
+        fy: fy as f32,
+        cx: cx as f32,
+        cy: cy as f32,
+        skew: skew as f32,
+        rotation: DMat3::from_cols(r1, r2, r3).transpose().as_mat3(),
+        translation: DVec3::new(tx, ty, tz).as_vec3(),
+        reprojection_error: 0.0,
+    };
+
+    let mut squared = 0.0;
+    for point in points {
+        let projected = solved
+            .project(point.world)
+            .ok_or(Projection3dError::PointsBehindProjector)?;
+        squared += projected.distance_squared(point.image);
+    }
+    solved.reprojection_error = (squared / points.len() as f32).sqrt();
+    Ok(solved)
+}
+
+/// Centroid and the scale that brings the average distance to `target`
+fn normalization(points: &[DVec3], target: f64) -> (DVec3, f64) {
+    let center = points.iter().copied().sum::<DVec3>() / points.len() as f64;
+    let mean = points.iter().map(|p| p.distance(center)).sum::<f64>() / points.len() as f64;
+    (center, if mean > 0.0 { target / mean } else { 1.0 })
+}
+
+/// Eigenvalues and eigenvectors (columns) of a symmetric matrix, by cyclic
+/// Jacobi rotations
+fn symmetric_eigen<const N: usize>(mut a: [[f64; N]; N]) -> ([f64; N], [[f64; N]; N]) {
+    let mut v = [[0.0; N]; N];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    let total: f64 = a.iter().flatten().map(|x| x * x).sum();
+    for _ in 0..100 {
+        let off: f64 = (0..N)
+            .flat_map(|i| (0..N).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off <= total * 1e-30 {
+            break;
+        }
+        for p in 0..N {
+            for q in p + 1..N {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+                for row in v.iter_mut() {
+                    let (vp, vq) = (row[p], row[q]);
+                    row[p] = c * vp - s * vq;
+                    row[q] = s * vp + c * vq;
+                }
+            }
+        }
+    }
+
+    let mut values = [0.0; N];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = a[i][i];
+    }
+    (values, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projector() -> SolvedProjector {
+        // Looking down -Z from (0.5, 1.0, 4.0), slightly turned and tilted
+        let rotation = Mat3::from_rotation_x(0.1) * Mat3::from_rotation_y(-0.15);
+        let rotation = Mat3::from_diagonal(Vec3::new(1.0, -1.0, -1.0)) * rotation;
+        let position = Vec3::new(0.5, 1.0, 4.0);
+        SolvedProjector {
+            fx: 1.4,
+            fy: 2.4,
+            cx: 0.5,
+            cy: 0.8,
+            skew: 0.0,
+            rotation,
+            translation: -(rotation * position),
+            reprojection_error: 0.0,
+        }
+    }
+
+    fn cube_points(truth: &SolvedProjector) -> Vec<PointCorrespondence> {
+        let mut points = Vec::new();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    let world = Vec3::new(x, y, z * 0.5);
+                    points.push(PointCorrespondence::new(
+                        world,
+                        truth.project(world).unwrap(),
+                    ));
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_solve_recovers_projector() {
+        let truth = projector();
+        let solved = solve_projector(&cube_points(&truth)).unwrap();
+
+        assert!(solved.reprojection_error < 1e-4);
+        assert!((solved.fx - truth.fx).abs() < 1e-3);
+        assert!((solved.fy - truth.fy).abs() < 1e-3);
+        assert!((solved.cx - truth.cx).abs() < 1e-3);
+        assert!((solved.cy - truth.cy).abs() < 1e-3);
+        assert!(solved.skew.abs() < 1e-3);
+        assert!(solved.position().distance(truth.position()) < 1e-3);
+
+        // The render transform lands on the same image points
+        let clip = solved.clip_from_world(0.1, 100.0);
+        let world = Vec3::new(0.3, -0.2, 0.1);
+        let ndc = clip.project_point3(world);
+        let image = truth.project(world).unwrap();
+        assert!((ndc.x - (image.x * 2.0 - 1.0)).abs() < 1e-3);
+        assert!((ndc.y - (1.0 - image.y * 2.0)).abs() < 1e-3);
+        assert!(ndc.z > 0.0 && ndc.z < 1.0);
+    }
+
+    #[test]
+    fn test_solve_rejects_bad_input() {
+        let truth = projector();
+        let points = cube_points(&truth);
+        assert!(matches!(
+            solve_projector(&points[..5]),
+            Err(Projection3dError::NotEnoughPoints(5))
+        ));
+
+        // All points on one plane
+        let flat: Vec<PointCorrespondence> = (0..8)
+            .map(|i| {
+                let world = Vec3::new((i % 4) as f32, (i / 4) as f32, 0.0);
+                PointCorrespondence::new(world, truth.project(world).unwrap())
+            })
+            .collect();
+        assert!(matches!(
+            solve_projector(&flat),
+            Err(Projection3dError::Degenerate)
+        ));
+    }
+}
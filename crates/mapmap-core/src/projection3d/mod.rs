@@ -0,0 +1,37 @@
+//! 3D projection mapping
+//!
+//! Content is mapped onto a scene model (OBJ or glTF) through its texture
+//! coordinates and rendered from a virtual projector. The projector's lens
+//! and pose are solved from points picked on the model and in the projector
+//! image, so the rendered model lines up with the real object.
+
+mod calibration;
+mod model;
+
+pub use calibration::{
+    solve_projector, PointCorrespondence, ProjectorCalibration, SolvedProjector,
+    MIN_CORRESPONDENCES,
+};
+pub use model::SceneModel;
+
+use thiserror::Error;
+
+/// Errors of scene model loading and projector calibration
+#[derive(Debug, Error)]
+pub enum Projection3dError {
+    /// The model file could not be read or parsed
+    #[error("Failed to load model: {0}")]
+    Load(String),
+    /// The model file format is not supported
+    #[error("Unsupported model format: {0}")]
+    UnsupportedFormat(String),
+    /// Fewer correspondences than the solver needs
+    #[error("At least {MIN_CORRESPONDENCES} point pairs are needed, got {0}")]
+    NotEnoughPoints(usize),
+    /// The points don't determine a projector (e.g. all on one plane)
+    #[error("Point pairs are degenerate; spread them over more than one plane")]
+    Degenerate,
+    /// The solved projector sees some of the points from behind
+    #[error("Some points end up behind the solved projector")]
+    PointsBehindProjector,
+}
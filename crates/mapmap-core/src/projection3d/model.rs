@@ -0,0 +1,273 @@
+//! Scene models for 3D projection mapping
+//!
+//! Unlike the flat `MeshType::Custom` import, positions keep all three axes
+//! in model units, and content is placed by the model's own texture
+//! coordinates.
+
+use super::Projection3dError;
+use glam::{Mat4, Vec2, Vec3};
+use std::path::Path;
+
+/// Triangulated geometry of a scene model
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneModel {
+    /// Vertex positions in model units.
+    pub positions: Vec<Vec3>,
+    /// Texture coordinates (origin top left), one per position.
+    pub tex_coords: Vec<Vec2>,
+    /// Triangle indices (3 per triangle).
+    pub indices: Vec<u32>,
+}
+
+impl SceneModel {
+    /// Load an OBJ, glTF or GLB file; all meshes of the file are merged.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Projection3dError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("obj") => Self::load_obj(path),
+            Some("gltf") | Some("glb") => Self::load_gltf(path),
+            _ => Err(Projection3dError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    /// Smallest and largest corner of the bounding box
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        let first = *self.positions.first()?;
+        Some(
+            self.positions
+                .iter()
+                .fold((first, first), |(min, max), p| (min.min(*p), max.max(*p))),
+        )
+    }
+
+    /// Orthographic view from the front (looking down -Z), fitting the
+    /// bounding box into the output; for outputs without calibration.
+    pub fn front_view_transform(&self) -> Mat4 {
+        let Some((min, max)) = self.bounds() else {
+            return Mat4::IDENTITY;
+        };
+        let size = (max - min).max(Vec3::splat(1e-6));
+        let margin = size.z * 0.01;
+        Mat4::orthographic_rh(
+            min.x,
+            min.x + size.x,
+            min.y,
+            min.y + size.y,
+            -max.z - margin,
+            -min.z + margin,
+        )
+    }
+
+    /// Number of triangles
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    fn load_obj(path: &Path) -> Result<Self, Projection3dError> {
+        let (models, _) = tobj::load_obj(
+            path,
+            &tobj::LoadOptions {
+                single_index: true,
+                triangulate: true,
+                ignore_points: true,
+                ignore_lines: true,
+            },
+        )
+        .map_err(|e| Projection3dError::Load(e.to_string()))?;
+
+        let mut builder = Builder::default();
+        for model in &models {
+            let mesh = &model.mesh;
+            let positions = mesh
+                .positions
+                .chunks_exact(3)
+                .map(|p| Vec3::new(p[0], p[1], p[2]));
+            // OBJ texture coordinates start at the bottom
+            let tex_coords = (mesh.texcoords.len() == mesh.positions.len() / 3 * 2).then(|| {
+                mesh.texcoords
+                    .chunks_exact(2)
+                    .map(|t| Vec2::new(t[0], 1.0 - t[1]))
+                    .collect::<Vec<_>>()
+            });
+            builder.push(positions, tex_coords, mesh.indices.iter().copied());
+        }
+        builder.finish()
+    }
+
+    fn load_gltf(path: &Path) -> Result<Self, Projection3dError> {
+        let gltf = gltf::Gltf::open(path).map_err(|e| Projection3dError::Load(e.to_string()))?;
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        let buffers = gltf
+            .buffers()
+            .map(|buffer| match buffer.source() {
+                gltf::buffer::Source::Bin => gltf
+                    .blob
+                    .clone()
+                    .ok_or_else(|| Projection3dError::Load("GLB without binary chunk".into())),
+                gltf::buffer::Source::Uri(uri) if uri.starts_with("data:") => Err(
+                    Projection3dError::UnsupportedFormat("embedded glTF buffers".into()),
+                ),
+                gltf::buffer::Source::Uri(uri) => std::fs::read(base.join(uri))
+                    .map_err(|e| Projection3dError::Load(format!("{}: {}", uri, e))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut builder = Builder::default();
+        let scene = gltf
+            .default_scene()
+            .or_else(|| gltf.scenes().next())
+            .ok_or_else(|| Projection3dError::Load("glTF file without scene".into()))?;
+        let mut stack: Vec<(gltf::Node, Mat4)> =
+            scene.nodes().map(|node| (node, Mat4::IDENTITY)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix());
+            if let Some(mesh) = node.mesh() {
+                for primitive in mesh.primitives() {
+                    if primitive.mode() != gltf::mesh::Mode::Triangles {
+                        continue;
+                    }
+                    let reader =
+                        primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+                    let Some(positions) = reader.read_positions() else {
+                        continue;
+                    };
+                    let positions: Vec<Vec3> = positions
+                        .map(|p| transform.transform_point3(Vec3::from(p)))
+                        .collect();
+                    let tex_coords = reader
+                        .read_tex_coords(0)
+                        .map(|t| t.into_f32().map(Vec2::from).collect::<Vec<_>>())
+                        .filter(|t| t.len() == positions.len());
+                    let indices: Vec<u32> = match reader.read_indices() {
+                        Some(indices) => indices.into_u32().collect(),
+                        None => (0..positions.len() as u32).collect(),
+                    };
+                    builder.push(positions.into_iter(), tex_coords, indices.into_iter());
+                }
+            }
+            stack.extend(node.children().map(|child| (child, transform)));
+        }
+        builder.finish()
+    }
+}
+
+/// Merges meshes into one model
+#[derive(Default)]
+struct Builder {
+    model: SceneModel,
+    /// Vertices that came without texture coordinates
+    missing_tex_coords: Vec<usize>,
+}
+
+impl Builder {
+    fn push(
+        &mut self,
+        positions: impl Iterator<Item = Vec3>,
+        tex_coords: Option<Vec<Vec2>>,
+        indices: impl Iterator<Item = u32>,
+    ) {
+        let offset = self.model.positions.len();
+        self.model.positions.extend(positions);
+        let count = self.model.positions.len() - offset;
+        match tex_coords {
+            Some(tex_coords) => self.model.tex_coords.extend(tex_coords),
+            None => {
+                self.missing_tex_coords.extend(offset..offset + count);
+                let len = self.model.tex_coords.len();
+                self.model.tex_coords.resize(len + count, Vec2::ZERO);
+            }
+        }
+        let indices: Vec<u32> = indices.collect();
+        for triangle in indices.chunks_exact(3) {
+            if triangle.iter().all(|i| (*i as usize) < count) {
+                self.model
+                    .indices
+                    .extend(triangle.iter().map(|i| i + offset as u32));
+            }
+        }
+    }
+
+    fn finish(mut self) -> Result<SceneModel, Projection3dError> {
+        let model = &mut self.model;
+        if model.indices.is_empty() {
+            return Err(Projection3dError::Load("model has no triangles".into()));
+        }
+
+        // Without texture coordinates, project the content onto the model
+        // from the front (across X and Y of the bounding box)
+        if let Some((min, max)) = model.bounds() {
+            let size = (max - min).max(Vec3::splat(1e-6));
+            for &i in &self.missing_tex_coords {
+                let p = (model.positions[i] - min) / size;
+                model.tex_coords[i] = Vec2::new(p.x, 1.0 - p.y);
+            }
+        }
+        Ok(self.model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_obj_keeps_depth() {
+        let mut file = tempfile::Builder::new().suffix(".obj").tempfile().unwrap();
+        writeln!(
+            file,
+            "v 0 0 0\nv 2 0 -1\nv 2 1 -1\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\nf 1/1 2/2 3/3 4/4"
+        )
+        .unwrap();
+
+        let model = SceneModel::load(file.path()).unwrap();
+        assert_eq!(model.triangle_count(), 2);
+        assert_eq!(model.positions[1], Vec3::new(2.0, 0.0, -1.0));
+        assert_eq!(
+            model.bounds(),
+            Some((Vec3::new(0.0, 0.0, -1.0), Vec3::new(2.0, 1.0, 0.0)))
+        );
+        // Flipped to a top-left origin
+        assert_eq!(model.tex_coords[3], Vec2::new(0.0, 0.0));
+        assert_eq!(model.tex_coords[0], Vec2::new(0.0, 1.0));
+
+        // The front view fills the output, nearest points at depth 0
+        let front = model.front_view_transform();
+        let near = front.project_point3(Vec3::new(0.0, 1.0, 0.0));
+        let far = front.project_point3(Vec3::new(2.0, 0.0, -1.0));
+        assert!((near.x + 1.0).abs() < 1e-5 && (near.y - 1.0).abs() < 1e-5);
+        assert!((far.x - 1.0).abs() < 1e-5 && (far.y + 1.0).abs() < 1e-5);
+        assert!(near.z < far.z && near.z >= 0.0 && far.z <= 1.0);
+    }
+
+    #[test]
+    fn test_missing_tex_coords_use_front_projection() {
+        let mut builder = Builder::default();
+        builder.push(
+            [
+                Vec3::ZERO,
+                Vec3::new(4.0, 0.0, 1.0),
+                Vec3::new(0.0, 2.0, 3.0),
+            ]
+            .into_iter(),
+            None,
+            [0, 1, 2, 0, 2, 7].into_iter(),
+        );
+        let model = builder.finish().unwrap();
+        // The triangle with an out-of-range index is dropped
+        assert_eq!(model.indices, vec![0, 1, 2]);
+        assert_eq!(model.tex_coords[1], Vec2::new(1.0, 1.0));
+        assert_eq!(model.tex_coords[2], Vec2::new(0.0, 0.0));
+
+        assert!(matches!(
+            SceneModel::load("scene.fbx"),
+            Err(Projection3dError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! Decoding captured Gray-code frames
+
+use super::gray_code::{from_gray, FrameKind, GrayCodePattern};
+use super::StructuredLightError;
+use glam::Vec2;
+use image::GrayImage;
+use std::path::Path;
+
+/// Thresholds for decoding captures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodeSettings {
+    /// Smallest difference between the white and black capture for a camera
+    /// pixel to count as lit by the projector.
+    pub min_contrast: u8,
+    /// Smallest difference between a pattern and its inverse for a bit to be
+    /// trusted.
+    pub min_bit_contrast: u8,
+}
+
+impl Default for DecodeSettings {
+    fn default() -> Self {
+        Self {
+            min_contrast: 24,
+            min_bit_contrast: 8,
+        }
+    }
+}
+
+/// Projector pixel seen by every camera pixel
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrespondenceMap {
+    /// Camera image width.
+    pub camera_width: u32,
+    /// Camera image height.
+    pub camera_height: u32,
+    /// Projector pattern width.
+    pub projector_width: u32,
+    /// Projector pattern height.
+    pub projector_height: u32,
+    /// Projector pixel center per camera pixel, row by row; `None` where the
+    /// projector is not visible.
+    pub points: Vec<Option<Vec2>>,
+}
+
+impl CorrespondenceMap {
+    /// Projector pixel seen at camera pixel (`x`, `y`)
+    pub fn get(&self, x: u32, y: u32) -> Option<Vec2> {
+        if x >= self.camera_width || y >= self.camera_height {
+            return None;
+        }
+        self.points[(y * self.camera_width + x) as usize]
+    }
+
+    /// Normalized projector position (0-1) seen at a camera position
+    pub fn projector_uv_at(&self, camera: Vec2) -> Option<Vec2> {
+        if camera.x < 0.0 || camera.y < 0.0 {
+            return None;
+        }
+        let point = self.get(camera.x as u32, camera.y as u32)?;
+        Some(point / Vec2::new(self.projector_width as f32, self.projector_height as f32))
+    }
+
+    /// Share of camera pixels that see the projector
+    pub fn coverage(&self) -> f32 {
+        if self.points.is_empty() {
+            return 0.0;
+        }
+        self.points.iter().filter(|p| p.is_some()).count() as f32 / self.points.len() as f32
+    }
+}
+
+/// Decode the captures of `pattern`, given in sequence order
+pub fn decode(
+    pattern: &GrayCodePattern,
+    frames: &[GrayImage],
+    settings: &DecodeSettings,
+) -> Result<CorrespondenceMap, StructuredLightError> {
+    if frames.len() != pattern.frame_count() {
+        return Err(StructuredLightError::FrameCount {
+            expected: pattern.frame_count(),
+            found: frames.len(),
+        });
+    }
+    let (width, height) = frames[0].dimensions();
+    if frames.iter().any(|f| f.dimensions() != (width, height)) {
+        return Err(StructuredLightError::FrameSize);
+    }
+
+    let mut points = vec![None; (width * height) as usize];
+    for (i, point) in points.iter_mut().enumerate() {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        let value = |frame: usize| frames[frame].get_pixel(x, y).0[0];
+        if value(0).saturating_sub(value(1)) < settings.min_contrast {
+            continue;
+        }
+
+        let mut code = [0u32; 2];
+        let mut valid = true;
+        for index in (2..frames.len()).step_by(2) {
+            let Some(FrameKind::Bit { vertical, bit, .. }) = pattern.frame_kind(index) else {
+                continue;
+            };
+            let (on, off) = (value(index), value(index + 1));
+            if on.abs_diff(off) < settings.min_bit_contrast {
+                valid = false;
+                break;
+            }
+            if on > off {
+                code[vertical as usize] |= 1 << bit;
+            }
+        }
+        let (column, row) = (from_gray(code[0]), from_gray(code[1]));
+        if valid && column < pattern.width && row < pattern.height {
+            *point = Some(Vec2::new(column as f32 + 0.5, row as f32 + 0.5));
+        }
+    }
+
+    Ok(CorrespondenceMap {
+        camera_width: width,
+        camera_height: height,
+        projector_width: pattern.width,
+        projector_height: pattern.height,
+        points,
+    })
+}
+
+/// Load captured frames from a folder, in file name order
+pub fn load_captures(dir: impl AsRef<Path>) -> Result<Vec<GrayImage>, StructuredLightError> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir.as_ref())
+        .map_err(|e| StructuredLightError::Capture(e.to_string()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .map(|e| {
+                    matches!(
+                        e.to_ascii_lowercase().as_str(),
+                        "png" | "jpg" | "jpeg" | "bmp" | "tif" | "tiff"
+                    )
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            image::open(path)
+                .map(|image| image.to_luma8())
+                .map_err(|e| StructuredLightError::Capture(format!("{}: {}", path.display(), e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use glam::Affine2;
+    use image::Luma;
+
+    /// Captures of a projector seen through `camera_to_projector`, with
+    /// ambient light and limited camera contrast
+    pub(in super::super) fn render_captures(
+        pattern: &GrayCodePattern,
+        (width, height): (u32, u32),
+        camera_to_projector: Affine2,
+    ) -> Vec<GrayImage> {
+        (0..pattern.frame_count())
+            .map(|index| {
+                let frame = pattern.frame(index).unwrap();
+                GrayImage::from_fn(width, height, |x, y| {
+                    let p = camera_to_projector
+                        .transform_point2(Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+                    let inside = p.x >= 0.0
+                        && p.y >= 0.0
+                        && p.x < pattern.width as f32
+                        && p.y < pattern.height as f32;
+                    if !inside {
+                        return Luma([15]);
+                    }
+                    let lit = frame.get_pixel(p.x as u32, p.y as u32).0[0] == 255;
+                    Luma([if lit { 230 } else { 20 }])
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_synthetic_captures() {
+        let pattern = GrayCodePattern::new(64, 48);
+        let to_projector =
+            Affine2::from_scale_angle_translation(Vec2::new(0.5, 0.6), 0.05, Vec2::new(-4.0, -3.0));
+        let frames = render_captures(&pattern, (160, 120), to_projector);
+        let map = decode(&pattern, &frames, &DecodeSettings::default()).unwrap();
+
+        let (mut checked, mut correct) = (0, 0);
+        for y in 0..120 {
+            for x in 0..160 {
+                let expected =
+                    to_projector.transform_point2(Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+                if expected.x < 0.0 || expected.y < 0.0 || expected.x >= 64.0 || expected.y >= 48.0
+                {
+                    assert_eq!(map.get(x, y), None);
+                    continue;
+                }
+                checked += 1;
+                if map.get(x, y) == Some(expected.floor() + Vec2::splat(0.5)) {
+                    correct += 1;
+                }
+            }
+        }
+        assert!(checked > 1000);
+        assert_eq!(correct, checked);
+        assert!(map.coverage() > 0.1 && map.coverage() < 1.0);
+
+        assert!(matches!(
+            decode(&pattern, &frames[1..], &DecodeSettings::default()),
+            Err(StructuredLightError::FrameCount { .. })
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Gray-code pattern sequence
+//!
+//! The sequence starts with a white and a black frame, which give the
+//! brightness range of every camera pixel. Each bit of the column and row
+//! index follows as a pattern and its inverse, so bits are decoded by
+//! comparing two captures instead of against a fixed threshold.
+
+use image::{GrayImage, Luma};
+
+/// Gray-code patterns for a projector of the given resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrayCodePattern {
+    /// Pattern width in pixels.
+    pub width: u32,
+    /// Pattern height in pixels.
+    pub height: u32,
+}
+
+impl GrayCodePattern {
+    /// Patterns for a `width` x `height` projector
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+        }
+    }
+
+    /// Bits needed for the column index
+    pub fn bits_x(&self) -> u32 {
+        bits_for(self.width)
+    }
+
+    /// Bits needed for the row index
+    pub fn bits_y(&self) -> u32 {
+        bits_for(self.height)
+    }
+
+    /// Number of frames in the sequence
+    pub fn frame_count(&self) -> usize {
+        2 + 2 * (self.bits_x() + self.bits_y()) as usize
+    }
+
+    /// Frame `index` of the sequence; `None` past the end
+    pub fn frame(&self, index: usize) -> Option<GrayImage> {
+        let (width, height) = (self.width, self.height);
+        match self.frame_kind(index)? {
+            FrameKind::White => Some(GrayImage::from_pixel(width, height, Luma([255]))),
+            FrameKind::Black => Some(GrayImage::from_pixel(width, height, Luma([0]))),
+            FrameKind::Bit {
+                vertical,
+                bit,
+                inverse,
+            } => Some(GrayImage::from_fn(width, height, |x, y| {
+                let coordinate = if vertical { y } else { x };
+                let on = (gray(coordinate) >> bit) & 1 == 1;
+                Luma([if on != inverse { 255 } else { 0 }])
+            })),
+        }
+    }
+
+    pub(super) fn frame_kind(&self, index: usize) -> Option<FrameKind> {
+        let bits_x = self.bits_x() as usize;
+        match index {
+            0 => Some(FrameKind::White),
+            1 => Some(FrameKind::Black),
+            _ if index < self.frame_count() => {
+                let pair = (index - 2) / 2;
+                let inverse = (index - 2) % 2 == 1;
+                let (vertical, position, bits) = if pair < bits_x {
+                    (false, pair, bits_x)
+                } else {
+                    (true, pair - bits_x, self.bits_y() as usize)
+                };
+                // Most significant bit first
+                Some(FrameKind::Bit {
+                    vertical,
+                    bit: (bits - 1 - position) as u32,
+                    inverse,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// What a frame of the sequence shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FrameKind {
+    White,
+    Black,
+    Bit {
+        /// Encodes rows instead of columns.
+        vertical: bool,
+        /// Bit of the Gray code.
+        bit: u32,
+        /// Shows the inverted pattern.
+        inverse: bool,
+    },
+}
+
+fn bits_for(size: u32) -> u32 {
+    (u32::BITS - (size.max(2) - 1).leading_zeros()).max(1)
+}
+
+/// Binary-reflected Gray code of `n`
+pub(super) fn gray(n: u32) -> u32 {
+    n ^ (n >> 1)
+}
+
+/// Index of the Gray code `g`
+pub(super) fn from_gray(mut g: u32) -> u32 {
+    let mut n = g;
+    while g > 1 {
+        g >>= 1;
+        n ^= g;
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_layout() {
+        let pattern = GrayCodePattern::new(1920, 1080);
+        assert_eq!(pattern.bits_x(), 11);
+        assert_eq!(pattern.bits_y(), 11);
+        assert_eq!(pattern.frame_count(), 46);
+        assert_eq!(GrayCodePattern::new(1024, 1).bits_x(), 10);
+
+        let small = GrayCodePattern::new(8, 2);
+        assert_eq!(small.frame(0).unwrap().get_pixel(3, 1).0, [255]);
+        assert_eq!(small.frame(1).unwrap().get_pixel(3, 1).0, [0]);
+        // Most significant column bit and its inverse
+        let msb = small.frame(2).unwrap();
+        let msb_inverse = small.frame(3).unwrap();
+        for x in 0..8 {
+            let on = gray(x) & 0b100 != 0;
+            assert_eq!(msb.get_pixel(x, 0).0[0] == 255, on);
+            assert_eq!(msb_inverse.get_pixel(x, 0).0[0] == 255, !on);
+        }
+        assert!(small.frame(small.frame_count()).is_none());
+
+        for n in 0..4096 {
+            assert_eq!(from_gray(gray(n)), n);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Structured Light - Camera-based projector calibration
+//!
+//! Every projector shows a sequence of Gray-code patterns that a camera
+//! captures. Decoding the captures tells, for each camera pixel, which
+//! projector pixel lights it. With the camera image as the shared canvas, this
+//! yields a warp grid per projector (where each projector vertex samples the
+//! content) and a blend mask that splits overlap zones between projectors.
+//!
+//! Captures are read from a folder of images, one folder per projector, with
+//! the frames in pattern order.
+
+mod decode;
+mod gray_code;
+mod warp;
+
+pub use decode::{decode, load_captures, CorrespondenceMap, DecodeSettings};
+pub use gray_code::GrayCodePattern;
+pub use warp::{generate_warps, ProjectorWarp, WarpSettings};
+
+use thiserror::Error;
+
+/// Errors of the structured light workflow
+#[derive(Debug, Error)]
+pub enum StructuredLightError {
+    /// A capture could not be read.
+    #[error("failed to read capture: {0}")]
+    Capture(String),
+    /// The number of captured frames does not match the pattern sequence.
+    #[error("expected {expected} captured frames, found {found}")]
+    FrameCount {
+        /// Frames of the pattern sequence.
+        expected: usize,
+        /// Frames found.
+        found: usize,
+    },
+    /// Captured frames differ in size.
+    #[error("captured frames differ in size")]
+    FrameSize,
+    /// Too few camera pixels could be decoded.
+    #[error("the camera sees too little of the projector")]
+    NoCoverage,
+}
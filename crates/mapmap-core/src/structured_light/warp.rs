@@ -0,0 +1,423 @@
+//! Warp grids and blend masks from decoded captures
+//!
+//! The camera image is the canvas: a projector vertex samples the content at
+//! the camera position it lights, so the projected image looks undistorted
+//! from the camera's point of view.
+
+use super::{CorrespondenceMap, StructuredLightError};
+use crate::mesh::Mesh;
+use glam::Vec2;
+use image::{GrayImage, Luma};
+
+/// Resolution of the generated warps and masks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarpSettings {
+    /// Grid cells from top to bottom.
+    pub rows: u32,
+    /// Grid cells from left to right.
+    pub cols: u32,
+    /// Blend mask width in pixels.
+    pub mask_width: u32,
+    /// Blend mask height in pixels.
+    pub mask_height: u32,
+    /// Projector gamma the blend masks compensate.
+    pub gamma: f32,
+}
+
+impl Default for WarpSettings {
+    fn default() -> Self {
+        Self {
+            rows: 16,
+            cols: 16,
+            mask_width: 512,
+            mask_height: 512,
+            gamma: 2.2,
+        }
+    }
+}
+
+/// Generated calibration of one projector
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectorWarp {
+    /// Grid cells from top to bottom.
+    pub rows: u32,
+    /// Grid cells from left to right.
+    pub cols: u32,
+    /// Canvas position (0-1) sampled at each grid vertex, row by row.
+    pub tex_coords: Vec<Vec2>,
+    /// Brightness share of the projector (gamma-encoded), stretched over the
+    /// whole output.
+    pub blend_mask: GrayImage,
+}
+
+impl ProjectorWarp {
+    /// Grid mesh over the output sampling the calibrated canvas positions
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::create_grid(self.rows, self.cols);
+        for (vertex, tex_coords) in mesh.vertices.iter_mut().zip(&self.tex_coords) {
+            vertex.tex_coords = *tex_coords;
+        }
+        mesh
+    }
+
+    /// Mesh type for a layer of the module graph
+    pub fn to_mesh_type(&self) -> crate::module::MeshType {
+        crate::module::MeshType::CalibratedGrid {
+            rows: self.rows,
+            cols: self.cols,
+            tex_coords: self.tex_coords.iter().map(|t| (t.x, t.y)).collect(),
+        }
+    }
+}
+
+/// Generate warp grids and blend masks for projectors seen by one camera
+pub fn generate_warps(
+    maps: &[CorrespondenceMap],
+    settings: &WarpSettings,
+) -> Result<Vec<ProjectorWarp>, StructuredLightError> {
+    let (rows, cols) = (settings.rows.max(1), settings.cols.max(1));
+    let mut grids: Vec<Vec<Option<Vec2>>> =
+        maps.iter().map(|map| fit_grid(map, rows, cols)).collect();
+
+    // The canvas spans everything the projectors light
+    let (min, max) = grids
+        .iter()
+        .flatten()
+        .flatten()
+        .fold(None, |bounds: Option<(Vec2, Vec2)>, p| {
+            Some(bounds.map_or((*p, *p), |(min, max)| (min.min(*p), max.max(*p))))
+        })
+        .ok_or(StructuredLightError::NoCoverage)?;
+    let size = (max - min).max(Vec2::splat(1.0));
+
+    let grids: Vec<Vec<Vec2>> = grids
+        .iter_mut()
+        .map(|grid| fill_missing(grid, rows, cols).ok_or(StructuredLightError::NoCoverage))
+        .collect::<Result<_, _>>()?;
+
+    Ok(grids
+        .iter()
+        .enumerate()
+        .map(|(index, grid)| ProjectorWarp {
+            rows,
+            cols,
+            tex_coords: grid.iter().map(|p| (*p - min) / size).collect(),
+            blend_mask: blend_mask(index, grid, maps, rows, cols, settings),
+        })
+        .collect())
+}
+
+/// Least-squares sums of an affine fit from projector to camera positions
+#[derive(Debug, Clone, Copy, Default)]
+struct AffineSums {
+    ata: [[f64; 3]; 3],
+    atb: [[f64; 2]; 3],
+    count: usize,
+}
+
+impl AffineSums {
+    fn add(&mut self, other: &AffineSums) {
+        for i in 0..3 {
+            for j in 0..3 {
+                self.ata[i][j] += other.ata[i][j];
+            }
+            self.atb[i][0] += other.atb[i][0];
+            self.atb[i][1] += other.atb[i][1];
+        }
+        self.count += other.count;
+    }
+
+    /// Fitted camera position at `a` (projector position in cell units)
+    fn evaluate(&self, a: [f64; 3]) -> Option<Vec2> {
+        if self.count < 6 {
+            return None;
+        }
+        let solution = solve3(self.ata, self.atb)?;
+        let mut result = [0.0; 2];
+        for (k, value) in result.iter_mut().enumerate() {
+            *value = (0..3).map(|i| a[i] * solution[i][k]).sum();
+        }
+        Some(Vec2::new(result[0] as f32, result[1] as f32))
+    }
+}
+
+/// Camera position of every grid vertex, fitted locally to the samples of
+/// the surrounding cells (unbiased at the borders, unlike an average).
+fn fit_grid(map: &CorrespondenceMap, rows: u32, cols: u32) -> Vec<Option<Vec2>> {
+    let mut cells = vec![AffineSums::default(); (rows * cols) as usize];
+    let scale = Vec2::new(
+        cols as f32 / map.projector_width as f32,
+        rows as f32 / map.projector_height as f32,
+    );
+    for (i, point) in map.points.iter().enumerate() {
+        let Some(point) = point else {
+            continue;
+        };
+        let uv = *point * scale;
+        let col = (uv.x as u32).min(cols - 1);
+        let row = (uv.y as u32).min(rows - 1);
+        let camera = [
+            (i as u32 % map.camera_width) as f64 + 0.5,
+            (i as u32 / map.camera_width) as f64 + 0.5,
+        ];
+        let a = [uv.x as f64, uv.y as f64, 1.0];
+        let cell = &mut cells[(row * cols + col) as usize];
+        for (ata_row, (atb_row, a_i)) in cell.ata.iter_mut().zip(cell.atb.iter_mut().zip(a)) {
+            for (value, a_j) in ata_row.iter_mut().zip(a) {
+                *value += a_i * a_j;
+            }
+            atb_row[0] += a_i * camera[0];
+            atb_row[1] += a_i * camera[1];
+        }
+        cell.count += 1;
+    }
+
+    let mut grid = Vec::with_capacity(((rows + 1) * (cols + 1)) as usize);
+    for row in 0..=rows {
+        for col in 0..=cols {
+            let mut sums = AffineSums::default();
+            for cell_row in row.saturating_sub(1)..(row + 1).min(rows) {
+                for cell_col in col.saturating_sub(1)..(col + 1).min(cols) {
+                    sums.add(&cells[(cell_row * cols + cell_col) as usize]);
+                }
+            }
+            grid.push(sums.evaluate([col as f64, row as f64, 1.0]));
+        }
+    }
+    grid
+}
+
+/// Extrapolate vertices the camera doesn't see from their neighbours;
+/// `None` if no vertex was seen.
+fn fill_missing(grid: &mut [Option<Vec2>], rows: u32, cols: u32) -> Option<Vec<Vec2>> {
+    let (rows, cols) = (rows as i64, cols as i64);
+    let at = |grid: &[Option<Vec2>], row: i64, col: i64| {
+        if (0..=rows).contains(&row) && (0..=cols).contains(&col) {
+            grid[(row * (cols + 1) + col) as usize]
+        } else {
+            None
+        }
+    };
+    grid.iter().any(Option::is_some).then_some(())?;
+
+    loop {
+        let mut filled = Vec::new();
+        for row in 0..=rows {
+            for col in 0..=cols {
+                if at(grid, row, col).is_some() {
+                    continue;
+                }
+                let mut linear = Vec::new();
+                let mut nearest = Vec::new();
+                for (dr, dc) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
+                    match (
+                        at(grid, row + dr, col + dc),
+                        at(grid, row + 2 * dr, col + 2 * dc),
+                    ) {
+                        (Some(near), Some(far)) => linear.push(near * 2.0 - far),
+                        (Some(near), None) => nearest.push(near),
+                        _ => {}
+                    }
+                }
+                let estimates = if linear.is_empty() { nearest } else { linear };
+                if !estimates.is_empty() {
+                    let sum: Vec2 = estimates.iter().sum();
+                    filled.push((row * (cols + 1) + col, sum / estimates.len() as f32));
+                }
+            }
+        }
+        if filled.is_empty() {
+            break;
+        }
+        for (index, value) in filled {
+            grid[index as usize] = Some(value);
+        }
+    }
+    Some(grid.iter().map(|p| p.unwrap_or_default()).collect())
+}
+
+/// Camera position at normalized output position `uv`, bilinear in the grid
+fn sample_grid(grid: &[Vec2], rows: u32, cols: u32, uv: Vec2) -> Vec2 {
+    let x = (uv.x * cols as f32).clamp(0.0, cols as f32);
+    let y = (uv.y * rows as f32).clamp(0.0, rows as f32);
+    let (col, row) = ((x as u32).min(cols - 1), (y as u32).min(rows - 1));
+    let (fx, fy) = (x - col as f32, y - row as f32);
+    let at = |r: u32, c: u32| grid[(r * (cols + 1) + c) as usize];
+    let top = at(row, col).lerp(at(row, col + 1), fx);
+    let bottom = at(row + 1, col).lerp(at(row + 1, col + 1), fx);
+    top.lerp(bottom, fy)
+}
+
+/// Distance to the nearest output edge; brightness weight in overlaps
+fn edge_weight(uv: Vec2) -> f32 {
+    uv.x.min(1.0 - uv.x).min(uv.y).min(1.0 - uv.y).max(0.0)
+}
+
+fn blend_mask(
+    index: usize,
+    grid: &[Vec2],
+    maps: &[CorrespondenceMap],
+    rows: u32,
+    cols: u32,
+    settings: &WarpSettings,
+) -> GrayImage {
+    let (width, height) = (settings.mask_width.max(1), settings.mask_height.max(1));
+    let inverse_gamma = 1.0 / settings.gamma.max(0.1);
+    GrayImage::from_fn(width, height, |x, y| {
+        let uv = Vec2::new(
+            (x as f32 + 0.5) / width as f32,
+            (y as f32 + 0.5) / height as f32,
+        );
+        let own = edge_weight(uv);
+        let camera = sample_grid(grid, rows, cols, uv);
+        let others: f32 = maps
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .filter_map(|(_, map)| map.projector_uv_at(camera))
+            .map(edge_weight)
+            .sum();
+        let share = if own + others > 0.0 {
+            own / (own + others)
+        } else {
+            1.0
+        };
+        Luma([(share.powf(inverse_gamma) * 255.0).round() as u8])
+    })
+}
+
+/// Solve `a x = b` for two right-hand sides (Gaussian elimination with
+/// partial pivoting); `None` if `a` is singular.
+fn solve3(mut a: [[f64; 3]; 3], mut b: [[f64; 2]; 3]) -> Option<[[f64; 2]; 3]> {
+    let scale = a
+        .iter()
+        .flatten()
+        .fold(0.0f64, |max, value| max.max(value.abs()));
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..3 {
+            let factor = a[row][col] / a[col][col];
+            let (pivot_a, pivot_b) = (a[col], b[col]);
+            for (value, pivot) in a[row].iter_mut().zip(pivot_a).skip(col) {
+                *value -= factor * pivot;
+            }
+            for (value, pivot) in b[row].iter_mut().zip(pivot_b) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+    let mut x = [[0.0; 2]; 3];
+    for row in (0..3).rev() {
+        for k in 0..2 {
+            let rest: f64 = (row + 1..3).map(|j| a[row][j] * x[j][k]).sum();
+            x[row][k] = (b[row][k] - rest) / a[row][row];
+        }
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode::tests::render_captures;
+    use super::super::{decode, DecodeSettings, GrayCodePattern};
+    use super::*;
+    use glam::{Affine2, Mat2};
+
+    #[test]
+    fn test_two_overlapping_projectors() {
+        let pattern = GrayCodePattern::new(64, 48);
+        let linear = Mat2::from_cols(Vec2::new(1.5, 0.1), Vec2::new(-0.05, 1.4));
+        let left = Affine2::from_mat2_translation(linear, Vec2::new(12.0, 9.0));
+        let right = Affine2::from_mat2_translation(linear, Vec2::new(70.0, 12.0));
+        let maps: Vec<_> = [left, right]
+            .iter()
+            .map(|to_camera| {
+                let frames = render_captures(&pattern, (200, 120), to_camera.inverse());
+                decode(&pattern, &frames, &DecodeSettings::default()).unwrap()
+            })
+            .collect();
+
+        let settings = WarpSettings {
+            rows: 4,
+            cols: 4,
+            mask_width: 64,
+            mask_height: 48,
+            gamma: 2.2,
+        };
+        let warps = generate_warps(&maps, &settings).unwrap();
+        assert_eq!(warps.len(), 2);
+        assert_eq!(warps[0].tex_coords.len(), 25);
+
+        // Vertices sample the canvas where the camera sees them
+        let size = Vec2::new(64.0, 48.0);
+        let corners = [Vec2::ZERO, size, Vec2::new(64.0, 0.0), Vec2::new(0.0, 48.0)];
+        let seen: Vec<Vec2> = [left, right]
+            .iter()
+            .flat_map(|a| corners.map(|c| a.transform_point2(c)))
+            .collect();
+        let min = seen.iter().fold(seen[0], |m, p| m.min(*p));
+        let max = seen.iter().fold(seen[0], |m, p| m.max(*p));
+        for (warp, to_camera) in warps.iter().zip([left, right]) {
+            for row in 0..=4 {
+                for col in 0..=4 {
+                    let uv = Vec2::new(col as f32, row as f32) / 4.0;
+                    let expected = (to_camera.transform_point2(uv * size) - min) / (max - min);
+                    let actual = warp.tex_coords[row * 5 + col];
+                    assert!(
+                        (actual - expected).length() < 0.02,
+                        "vertex {},{}: {} vs {}",
+                        col,
+                        row,
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            warps[0].to_mesh().vertices[24].tex_coords,
+            warps[0].tex_coords[24]
+        );
+        assert_eq!(
+            warps[0].to_mesh_type().to_mesh().vertices[7].tex_coords,
+            warps[0].tex_coords[7]
+        );
+
+        // Outside the overlap the left projector is at full brightness
+        let linear_share = |mask: &GrayImage, uv: Vec2| {
+            let (x, y) = ((uv.x * 64.0) as u32, (uv.y * 48.0) as u32);
+            (mask.get_pixel(x, y).0[0] as f32 / 255.0).powf(2.2)
+        };
+        assert!(linear_share(&warps[0].blend_mask, Vec2::new(0.2, 0.5)) > 0.99);
+
+        // In the overlap both shares add up to full brightness
+        let uv_left = Vec2::new(0.9, 0.5);
+        let camera = left.transform_point2(uv_left * size);
+        let uv_right = right.inverse().transform_point2(camera) / size;
+        let total = linear_share(&warps[0].blend_mask, uv_left)
+            + linear_share(&warps[1].blend_mask, uv_right);
+        assert!((total - 1.0).abs() < 0.1, "overlap sums to {}", total);
+        assert!(linear_share(&warps[1].blend_mask, uv_right) < 0.9);
+    }
+
+    #[test]
+    fn test_fill_missing_extrapolates() {
+        let mut grid = vec![
+            Some(Vec2::new(0.0, 0.0)),
+            Some(Vec2::new(1.0, 0.0)),
+            None,
+            Some(Vec2::new(0.0, 1.0)),
+            Some(Vec2::new(1.0, 1.0)),
+            None,
+        ];
+        let filled = fill_missing(&mut grid, 1, 2).unwrap();
+        assert_eq!(filled[2], Vec2::new(2.0, 0.0));
+        assert_eq!(filled[5], Vec2::new(2.0, 1.0));
+        assert!(fill_missing(&mut [None, None, None, None], 1, 1).is_none());
+    }
+}
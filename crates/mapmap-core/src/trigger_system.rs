@@ -1,5 +1,6 @@
 //! System for processing module triggers (e.g., AudioFFT)
 
+use crate::audio::beat_grid::BeatGridState;
 use crate::audio_reactive::AudioTriggerData;
 use crate::module::{ModuleManager, ModulePartType, TriggerType};
 use rand::RngExt;
@@ -36,6 +37,10 @@ pub struct TriggerSystem {
     ///
     /// Optimized to reduce hash lookups by storing timer and target together.
     states: HashMap<u64, TriggerState>,
+    /// Beat grid position and the boundaries crossed this frame
+    beat_grid: BeatGridState,
+    /// Audio data of the named channel analyzers
+    channel_audio: HashMap<String, AudioTriggerData>,
 }
 
 impl TriggerSystem {
@@ -44,6 +49,16 @@ impl TriggerSystem {
         Self::default()
     }
 
+    /// Set the beat grid state used by beat-grid triggers in the next update
+    pub fn update_beat_grid(&mut self, state: &BeatGridState) {
+        self.beat_grid = *state;
+    }
+
+    /// Set the audio data of the named channel analyzers used in the next update
+    pub fn update_audio_channels(&mut self, channels: HashMap<String, AudioTriggerData>) {
+        self.channel_audio = channels;
+    }
+
     /// Update the trigger states based on the current audio data and module configuration.
     pub fn update(
         &mut self,
@@ -58,6 +73,7 @@ impl TriggerSystem {
 
         // Track parts that actively use state to perform Garbage Collection
         let mut active_state_users = HashSet::new();
+        let silence = AudioTriggerData::default();
 
         for module in module_manager.modules() {
             for part in &module.parts {
@@ -67,7 +83,12 @@ impl TriggerSystem {
                             band: _,
                             threshold,
                             output_config,
+                            analyzer,
                         } => {
+                            let audio_data = match analyzer {
+                                Some(name) => self.channel_audio.get(name).unwrap_or(&silence),
+                                None => audio_data,
+                            };
                             let mut socket_index = 0;
                             let mut any_output_enabled = false;
 
@@ -116,6 +137,45 @@ impl TriggerSystem {
                                 socket_index += 1;
                             }
 
+                            // 5. Onset Outputs (broadband, then per band)
+                            if output_config.onset_outputs {
+                                any_output_enabled = true;
+                                if audio_data.onset_detected {
+                                    self.active_triggers.insert((part.id, socket_index));
+                                }
+                                socket_index += 1;
+                                for onset in audio_data.band_onsets {
+                                    if onset {
+                                        self.active_triggers.insert((part.id, socket_index));
+                                    }
+                                    socket_index += 1;
+                                }
+                            }
+
+                            // 6. Spectral Outputs (Reserved Indices)
+                            if output_config.spectral_outputs {
+                                any_output_enabled = true;
+                                // Centroid, flatness and rolloff are continuous values
+                                socket_index += 3;
+                            }
+
+                            // 7. Chroma Outputs
+                            if output_config.chroma_outputs {
+                                any_output_enabled = true;
+                                for energy in audio_data.chroma {
+                                    if energy > *threshold {
+                                        self.active_triggers.insert((part.id, socket_index));
+                                    }
+                                    socket_index += 1;
+                                }
+                            }
+
+                            // 8. Key Output (Reserved Index)
+                            if output_config.key_output {
+                                any_output_enabled = true;
+                                socket_index += 1;
+                            }
+
                             // Fallback: If no outputs are enabled, we default to a single Beat output (index 0)
                             if !any_output_enabled && audio_data.beat_detected {
                                 self.active_triggers.insert((part.id, 0));
@@ -125,7 +185,18 @@ impl TriggerSystem {
                             let _ = socket_index;
                         }
                         TriggerType::Beat => {
-                            if audio_data.beat_detected {
+                            // The shared tempo clock wins; raw audio beats only while it is stopped
+                            let beat = if self.beat_grid.running {
+                                self.beat_grid.beat()
+                            } else {
+                                audio_data.beat_detected
+                            };
+                            if beat {
+                                self.active_triggers.insert((part.id, 0));
+                            }
+                        }
+                        TriggerType::BeatGrid { division } => {
+                            if self.beat_grid.fired(*division) {
                                 self.active_triggers.insert((part.id, 0));
                             }
                         }
@@ -347,6 +418,10 @@ mod tests {
             volume_outputs: true,
             beat_output: true,
             bpm_output: true,
+            onset_outputs: true,
+            spectral_outputs: true,
+            chroma_outputs: true,
+            key_output: true,
             inverted_outputs: Default::default(),
         };
 
@@ -358,6 +433,7 @@ mod tests {
             band: crate::module::AudioBand::Bass, // Irrelevant for this test
             threshold: 0.0,                       // Low threshold to trigger everything
             output_config: config,
+            analyzer: None,
         });
 
         let part_id = manager
@@ -376,6 +452,9 @@ mod tests {
             beat_detected: true,
             peak_volume: 1.0,
             rms_volume: 1.0,
+            onset_detected: true,
+            band_onsets: [true; 9],
+            chroma: [1.0; 12],
             ..Default::default()
         };
         for i in 0..9 {
@@ -386,10 +465,18 @@ mod tests {
 
         // Check each expected socket index is active
         for (i, socket) in expected_sockets.iter().enumerate() {
-            if socket.name == "BPM Out" {
+            let continuous = [
+                "BPM Out",
+                "Centroid Out",
+                "Flatness Out",
+                "Rolloff Out",
+                "Key Out",
+            ];
+            if continuous.contains(&socket.name.as_str()) {
                 assert!(
                     !system.is_active(part_id, i),
-                    "BPM Out should NOT be active in TriggerSystem (handled separately)"
+                    "{} should NOT be active in TriggerSystem (handled separately)",
+                    socket.name
                 );
                 continue;
             }
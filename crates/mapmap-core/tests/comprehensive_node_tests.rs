@@ -37,6 +37,7 @@ fn test_trigger_inversion_logic() {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
 
     let t_id = module.add_part_with_type(trigger_type, (0.0, 0.0));
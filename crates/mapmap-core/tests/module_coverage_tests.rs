@@ -139,6 +139,7 @@ fn test_socket_consistency() {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: AudioTriggerOutputConfig::default(),
+        analyzer: None,
     });
     let (ins, outs) = fft.get_default_sockets();
     assert!(ins.is_empty());
@@ -184,6 +185,72 @@ fn test_hue_mapping_mode_serialization() {
     assert_eq!(mode, deserialized);
 }
 
+#[test]
+fn test_light_output_serialization() {
+    let output = OutputType::new_light(
+        "Bar",
+        LightBackend::Dmx {
+            protocol: DmxProtocol::ArtNet,
+            target: "10.0.0.20:6454".to_string(),
+            universe: 1,
+            fixtures: vec![LightFixturePatch {
+                id: "par-1".to_string(),
+                start_address: 1,
+                layout: LightFixtureLayout::Rgbw,
+            }],
+        },
+    );
+    let serialized = serde_json::to_string(&output).unwrap();
+    let deserialized: OutputType = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(output, deserialized);
+
+    // Optional fields fall back to their defaults
+    let minimal =
+        r#"{"Light":{"name":"Strip","backend":{"Wled":{"host":"wled.local","led_count":60}}}}"#;
+    match serde_json::from_str::<OutputType>(minimal).unwrap() {
+        OutputType::Light {
+            backend,
+            mapping_mode,
+            effect,
+            color,
+            ..
+        } => {
+            assert_eq!(mapping_mode, LightMappingMode::Spatial);
+            assert_eq!(effect, LightEffectKind::Pulse);
+            assert_eq!(color, [1.0, 1.0, 1.0]);
+            assert!(matches!(
+                backend,
+                LightBackend::Wled { protocol: WledProtocol::Drgb, strips, .. } if strips.is_empty()
+            ));
+        }
+        other => panic!("unexpected output {:?}", other),
+    }
+
+    let matrix = OutputType::new_light(
+        "Wall".to_string(),
+        LightBackend::Wled {
+            host: "10.0.0.5".to_string(),
+            led_count: 512,
+            protocol: WledProtocol::Ddp,
+            strips: vec![LedStripPatch {
+                id: "panel".to_string(),
+                start_pixel: 0,
+                layout: LedStripLayout::Serpentine {
+                    top_left: (0.0, 0.0),
+                    bottom_right: (1.0, 0.5),
+                    columns: 32,
+                    rows: 16,
+                },
+            }],
+        },
+    );
+    let serialized = serde_json::to_string(&matrix).unwrap();
+    assert_eq!(
+        matrix,
+        serde_json::from_str::<OutputType>(&serialized).unwrap()
+    );
+}
+
 #[test]
 fn test_next_part_id_default() {
     // Create JSON for MapFlowModule missing "next_part_id"
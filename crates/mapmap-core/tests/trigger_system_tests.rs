@@ -1,3 +1,5 @@
+use mapmap_core::audio::beat_grid::{BeatDivision, BeatGrid};
+use mapmap_core::audio::tempo::{TempoService, TempoSource};
 use mapmap_core::audio_reactive::AudioTriggerData;
 use mapmap_core::module::{
     AudioBand, AudioTriggerOutputConfig, ModuleManager, ModulePartType, TriggerType,
@@ -12,6 +14,7 @@ fn default_audio_data() -> AudioTriggerData {
         beat_detected: false,
         beat_strength: 0.0,
         bpm: None,
+        ..Default::default()
     }
 }
 
@@ -50,6 +53,7 @@ fn test_update_audio_fft_bands() {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -87,11 +91,13 @@ fn test_update_audio_volume_beat() {
         beat_output: true,
         bpm_output: false,
         inverted_outputs: Default::default(),
+        ..Default::default()
     };
     let part_type = ModulePartType::Trigger(TriggerType::AudioFFT {
         band: AudioBand::Peak, // Doesn't matter for specific outputs
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -141,11 +147,13 @@ fn test_update_clears_previous_state() {
         volume_outputs: false,
         bpm_output: false,
         inverted_outputs: Default::default(),
+        ..Default::default()
     };
     let part_type = ModulePartType::Trigger(TriggerType::AudioFFT {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -180,6 +188,7 @@ fn test_trigger_system_update_thresholds() {
         band: AudioBand::Bass,
         threshold: 0.8, // High threshold
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -215,11 +224,13 @@ fn test_dynamic_socket_indexing() {
         beat_output: true,
         bpm_output: true,
         inverted_outputs: Default::default(),
+        ..Default::default()
     };
     let part_type = ModulePartType::Trigger(TriggerType::AudioFFT {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -257,11 +268,13 @@ fn test_fallback_behavior() {
         beat_output: false,
         bpm_output: false,
         inverted_outputs: Default::default(),
+        ..Default::default()
     };
     let part_type = ModulePartType::Trigger(TriggerType::AudioFFT {
         band: AudioBand::Bass,
         threshold: 0.5,
         output_config: config,
+        analyzer: None,
     });
     let part_id = module.add_part_with_type(part_type, (0.0, 0.0));
 
@@ -277,3 +290,67 @@ fn test_fallback_behavior() {
         "Fallback Beat Output should be active"
     );
 }
+
+#[test]
+fn test_beat_grid_divisions() {
+    let mut system = TriggerSystem::new();
+    let mut module_manager = ModuleManager::new();
+    let module_id = module_manager.create_module("Test Module".to_string());
+    let module = module_manager.get_module_mut(module_id).unwrap();
+    let beat_id = module.add_part_with_type(
+        ModulePartType::Trigger(TriggerType::BeatGrid {
+            division: BeatDivision::QuarterBar,
+        }),
+        (0.0, 0.0),
+    );
+    let bar_id = module.add_part_with_type(
+        ModulePartType::Trigger(TriggerType::BeatGrid {
+            division: BeatDivision::Bar,
+        }),
+        (0.0, 100.0),
+    );
+    let audio_data = default_audio_data();
+
+    // 120 BPM: one beat every 0.5s, one bar every 2s
+    let mut grid = BeatGrid::default();
+    grid.set_tempo(120.0);
+    let mut beats = 0;
+    let mut bars = 0;
+    for _ in 0..390 {
+        system.update_beat_grid(grid.advance(0.01));
+        system.update(&module_manager, &audio_data, 0.01);
+        beats += system.is_active(beat_id, 0) as u32;
+        bars += system.is_active(bar_id, 0) as u32;
+    }
+    assert_eq!(beats, 8);
+    assert_eq!(bars, 2);
+}
+
+#[test]
+fn test_beat_follows_tempo_clock() {
+    let mut system = TriggerSystem::new();
+    let mut module_manager = ModuleManager::new();
+    let module_id = module_manager.create_module("Test Module".to_string());
+    let module = module_manager.get_module_mut(module_id).unwrap();
+    let beat_id = module.add_part_with_type(ModulePartType::Trigger(TriggerType::Beat), (0.0, 0.0));
+    let mut audio_data = default_audio_data();
+    audio_data.beat_detected = true;
+
+    // Off-grid audio beats are ignored while the tempo clock runs
+    let mut tempo = TempoService::default();
+    tempo.config.priority = vec![TempoSource::Manual];
+    tempo.set_manual_bpm(120.0);
+    let mut beats = 0;
+    for _ in 0..390 {
+        system.update_beat_grid(tempo.advance(0.01));
+        system.update(&module_manager, &audio_data, 0.01);
+        beats += system.is_active(beat_id, 0) as u32;
+    }
+    assert_eq!(beats, 8);
+
+    // Without a tempo source the clock stops and detected beats pass through
+    let mut tempo = TempoService::default();
+    system.update_beat_grid(tempo.advance(0.01));
+    system.update(&module_manager, &audio_data, 0.01);
+    assert!(system.is_active(beat_id, 0));
+}
@@ -20,6 +20,7 @@ pub struct WgpuBackend {
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
     pub adapter_info: wgpu::AdapterInfo,
+    adapter: wgpu::Adapter,
     #[allow(dead_code)]
     staging_belt: StagingBelt,
     texture_counter: u64,
@@ -118,6 +119,7 @@ impl WgpuBackend {
             device: Arc::new(device),
             queue: Arc::new(queue),
             adapter_info,
+            adapter,
             staging_belt,
             texture_counter: 0,
             shader_counter: 0,
@@ -152,6 +154,15 @@ impl WgpuBackend {
     pub fn surface_format(&self) -> wgpu::TextureFormat {
         wgpu::TextureFormat::Bgra8UnormSrgb
     }
+
+    /// HDR format a window surface can be configured with, if its display
+    /// path offers one of the [`crate::HDR_SURFACE_FORMATS`]
+    pub fn hdr_surface_format(&self, surface: &wgpu::Surface) -> Option<wgpu::TextureFormat> {
+        let formats = surface.get_capabilities(&self.adapter).formats;
+        crate::HDR_SURFACE_FORMATS
+            .into_iter()
+            .find(|format| formats.contains(format))
+    }
 }
 
 impl RenderBackend for WgpuBackend {
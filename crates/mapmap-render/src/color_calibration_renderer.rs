@@ -1,7 +1,8 @@
 //! Color Calibration Renderer for Per-Output Color Correction
 //!
 //! Provides GPU-accelerated color calibration including brightness, contrast,
-//! gamma, color temperature, and saturation adjustments
+//! gamma, color temperature, and saturation adjustments, followed by an
+//! optional correction 3D LUT
 
 use crate::Result;
 use bytemuck::{Pod, Zeroable};
@@ -21,7 +22,7 @@ struct ColorCalibrationUniforms {
     gamma_b: f32,
     color_temp: f32,
     saturation: f32,
-    _padding: f32,
+    lut_size: f32,
 }
 
 /// Vertex for fullscreen quad
@@ -83,6 +84,9 @@ pub struct ColorCalibrationRenderer {
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     sampler: wgpu::Sampler,
+    /// Bound when an output has no correction LUT
+    no_lut_view: wgpu::TextureView,
+    target_format: wgpu::TextureFormat,
     device: Arc<wgpu::Device>,
 }
 
@@ -124,6 +128,16 @@ impl ColorCalibrationRenderer {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -209,6 +223,24 @@ impl ColorCalibrationRenderer {
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        // Never sampled (the LUT size uniform is 0), so its contents don't matter
+        let no_lut_view = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("Color Calibration Empty LUT"),
+                size: wgpu::Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
         Ok(Self {
             pipeline,
             texture_bind_group_layout,
@@ -216,12 +248,29 @@ impl ColorCalibrationRenderer {
             vertex_buffer,
             index_buffer,
             sampler,
+            no_lut_view,
+            target_format,
             device,
         })
     }
 
+    /// Format of the textures this renderer draws into
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
     /// Create a texture bind group for the input texture
     pub fn create_texture_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.create_texture_bind_group_with_lut(texture_view, None)
+    }
+
+    /// Create a texture bind group for the input texture and a correction LUT
+    /// atlas (see [`mapmap_core::Lut3D::to_2d_texture_data`])
+    pub fn create_texture_bind_group_with_lut(
+        &self,
+        texture_view: &wgpu::TextureView,
+        lut_view: Option<&wgpu::TextureView>,
+    ) -> wgpu::BindGroup {
         self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Color Calibration Texture Bind Group"),
             layout: &self.texture_bind_group_layout,
@@ -234,13 +283,44 @@ impl ColorCalibrationRenderer {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(
+                        lut_view.unwrap_or(&self.no_lut_view),
+                    ),
+                },
             ],
         })
     }
 
-    /// Create a uniform buffer from color calibration configuration
-    pub fn create_uniform_buffer(&self, config: &ColorCalibration) -> wgpu::Buffer {
-        let uniforms = ColorCalibrationUniforms {
+    /// Create a uniform buffer from color calibration configuration;
+    /// `lut_size` is the size of the bound correction LUT, 0 for none
+    pub fn create_uniform_buffer(&self, config: &ColorCalibration, lut_size: u32) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Color Calibration Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[Self::uniforms(config, lut_size)]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    /// Update an existing uniform buffer
+    pub fn update_uniform_buffer(
+        &self,
+        queue: &wgpu::Queue,
+        buffer: &wgpu::Buffer,
+        config: &ColorCalibration,
+        lut_size: u32,
+    ) {
+        queue.write_buffer(
+            buffer,
+            0,
+            bytemuck::cast_slice(&[Self::uniforms(config, lut_size)]),
+        );
+    }
+
+    fn uniforms(config: &ColorCalibration, lut_size: u32) -> ColorCalibrationUniforms {
+        ColorCalibrationUniforms {
             brightness: config.brightness,
             contrast: config.contrast,
             gamma_r: config.gamma.x,
@@ -248,15 +328,8 @@ impl ColorCalibrationRenderer {
             gamma_b: config.gamma_b,
             color_temp: config.color_temp,
             saturation: config.saturation,
-            _padding: 0.0,
-        };
-
-        self.device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Color Calibration Uniform Buffer"),
-                contents: bytemuck::cast_slice(&[uniforms]),
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            })
+            lut_size: lut_size as f32,
+        }
     }
 
     /// Create a uniform bind group
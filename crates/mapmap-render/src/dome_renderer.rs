@@ -0,0 +1,317 @@
+//! Dome Renderer for Fulldome Outputs
+//!
+//! Resamples the composed content of an output, treated as a spherical image,
+//! into a fisheye domemaster, equirectangular panorama or projector slice
+//! through the warp mesh built by [`DomeConfig::warp`]
+
+use crate::Result;
+use bytemuck::{Pod, Zeroable};
+use mapmap_core::{DomeConfig, DomeSource};
+use std::sync::Arc;
+use tracing::info;
+use wgpu::util::DeviceExt;
+
+/// Dome uniform parameters matching the WGSL shader
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct DomeUniforms {
+    source_mode: u32,
+    source_fov: f32,
+    _padding: [f32; 2],
+}
+
+/// Warp mesh vertex: output position and view direction
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct Vertex {
+    position: [f32; 2],
+    direction: [f32; 3],
+}
+
+impl Vertex {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// GPU resources of one output's dome warp
+pub struct DomeWarpBuffers {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+/// Dome resampling renderer
+pub struct DomeRenderer {
+    pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    uniform_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    target_format: wgpu::TextureFormat,
+    device: Arc<wgpu::Device>,
+}
+
+impl DomeRenderer {
+    /// Create a new dome renderer
+    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Result<Self> {
+        info!("Creating dome renderer");
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Dome Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Dome Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Dome Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let shader_source = include_str!("../../../shaders/dome.wgsl");
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Dome Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Dome Pipeline Layout"),
+            bind_group_layouts: &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Dome Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Ok(Self {
+            pipeline,
+            texture_bind_group_layout,
+            uniform_bind_group_layout,
+            sampler,
+            target_format,
+            device,
+        })
+    }
+
+    /// Format of the textures this renderer draws into
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
+    /// Create a texture bind group for the content texture
+    pub fn create_texture_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Dome Texture Bind Group"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Build the warp mesh and uniforms of a dome configuration for an
+    /// output of the given size
+    pub fn create_warp(&self, config: &DomeConfig, (width, height): (u32, u32)) -> DomeWarpBuffers {
+        let warp = config.warp(width.max(1) as f32 / height.max(1) as f32);
+        let vertices: Vec<Vertex> = warp
+            .vertices
+            .iter()
+            .map(|vertex| Vertex {
+                position: vertex.position.to_array(),
+                direction: vertex.direction.to_array(),
+            })
+            .collect();
+
+        let vertex_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Dome Warp Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+        let index_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Dome Warp Index Buffer"),
+                contents: bytemuck::cast_slice(&warp.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+        let (source_mode, source_fov) = match config.source {
+            DomeSource::Equirectangular => (0, 0.0),
+            DomeSource::CubemapCross => (1, 0.0),
+            DomeSource::Fisheye { fov } => (2, fov.clamp(1.0, 360.0).to_radians()),
+        };
+        let uniforms = DomeUniforms {
+            source_mode,
+            source_fov,
+            _padding: [0.0; 2],
+        };
+        let uniform_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Dome Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[uniforms]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let uniform_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Dome Uniform Bind Group"),
+            layout: &self.uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        DomeWarpBuffers {
+            vertex_buffer,
+            index_buffer,
+            index_count: warp.indices.len() as u32,
+            uniform_bind_group,
+        }
+    }
+
+    /// Render dome resampling pass
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        texture_bind_group: &'a wgpu::BindGroup,
+        warp: &'a DomeWarpBuffers,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, texture_bind_group, &[]);
+        render_pass.set_bind_group(1, &warp.uniform_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, warp.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(warp.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..warp.index_count, 0, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dome_uniforms_size() {
+        assert_eq!(std::mem::size_of::<DomeUniforms>(), 16);
+    }
+
+    #[test]
+    fn test_vertex_size() {
+        assert_eq!(
+            std::mem::size_of::<Vertex>(),
+            20 // 5 floats * 4 bytes
+        );
+    }
+
+    #[test]
+    fn test_dome_renderer_creation() {
+        pollster::block_on(async {
+            let backend = crate::WgpuBackend::new(None).await;
+            if let Ok(backend) = backend {
+                let renderer =
+                    DomeRenderer::new(backend.device.clone(), wgpu::TextureFormat::Bgra8UnormSrgb);
+                assert!(renderer.is_ok());
+                let warp = renderer
+                    .unwrap()
+                    .create_warp(&DomeConfig::default(), (1920, 1080));
+                assert!(warp.index_count > 0);
+            }
+        });
+    }
+}
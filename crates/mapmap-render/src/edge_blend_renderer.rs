@@ -10,16 +10,13 @@ use tracing::info;
 use wgpu::util::DeviceExt;
 
 /// Edge blend uniform parameters matching the WGSL shader
-/// Total size: 48 bytes (std140 layout with vec3 alignment)
+/// Total size: 48 bytes (three vec4)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 struct EdgeBlendUniforms {
-    left_width: f32,    // offset 0-3
-    right_width: f32,   // offset 4-7
-    top_width: f32,     // offset 8-11
-    bottom_width: f32,  // offset 12-15
-    gamma: f32,         // offset 16-19
-    _padding: [f32; 7], // offset 20-47 (vec3 in WGSL needs 16-byte alignment)
+    widths: [f32; 4],      // offset 0-15 (left, right, top, bottom)
+    gamma: [f32; 4],       // offset 16-31 (r, g, b, unused)
+    black_level: [f32; 4], // offset 32-47 (r, g, b, feather)
 }
 
 /// Vertex for fullscreen quad
@@ -81,12 +78,19 @@ pub struct EdgeBlendRenderer {
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     sampler: wgpu::Sampler,
+    /// Stands in for the mask of outputs without one
+    white_mask_view: wgpu::TextureView,
+    target_format: wgpu::TextureFormat,
     device: Arc<wgpu::Device>,
 }
 
 impl EdgeBlendRenderer {
     /// Create a new edge blend renderer
-    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Result<Self> {
+    pub fn new(
+        device: Arc<wgpu::Device>,
+        queue: &wgpu::Queue,
+        target_format: wgpu::TextureFormat,
+    ) -> Result<Self> {
         info!("Creating edge blend renderer");
 
         // Create sampler
@@ -122,6 +126,16 @@ impl EdgeBlendRenderer {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -207,6 +221,27 @@ impl EdgeBlendRenderer {
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        let white_mask = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("Edge Blend White Mask"),
+                size: wgpu::Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &[255; 4],
+        );
+        let white_mask_view = white_mask.create_view(&wgpu::TextureViewDescriptor::default());
+
         Ok(Self {
             pipeline,
             texture_bind_group_layout,
@@ -214,12 +249,29 @@ impl EdgeBlendRenderer {
             vertex_buffer,
             index_buffer,
             sampler,
+            white_mask_view,
+            target_format,
             device,
         })
     }
 
+    /// Format of the textures this renderer draws into
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
     /// Create a texture bind group for the input texture
     pub fn create_texture_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.create_texture_bind_group_with_mask(texture_view, None)
+    }
+
+    /// Create a texture bind group for the input texture and a grayscale
+    /// blend mask (read from the red channel)
+    pub fn create_texture_bind_group_with_mask(
+        &self,
+        texture_view: &wgpu::TextureView,
+        mask_view: Option<&wgpu::TextureView>,
+    ) -> wgpu::BindGroup {
         self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Edge Blend Texture Bind Group"),
             layout: &self.texture_bind_group_layout,
@@ -232,6 +284,12 @@ impl EdgeBlendRenderer {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(
+                        mask_view.unwrap_or(&self.white_mask_view),
+                    ),
+                },
             ],
         })
     }
@@ -260,29 +318,18 @@ impl EdgeBlendRenderer {
     }
 
     fn config_to_uniforms(&self, config: &EdgeBlendConfig) -> EdgeBlendUniforms {
+        let width = |zone: &mapmap_core::EdgeBlendZone| if zone.enabled { zone.width } else { 0.0 };
+        let [r, g, b] = config.gamma_rgb();
+        let [black_r, black_g, black_b] = config.black_level;
         EdgeBlendUniforms {
-            left_width: if config.left.enabled {
-                config.left.width
-            } else {
-                0.0
-            },
-            right_width: if config.right.enabled {
-                config.right.width
-            } else {
-                0.0
-            },
-            top_width: if config.top.enabled {
-                config.top.width
-            } else {
-                0.0
-            },
-            bottom_width: if config.bottom.enabled {
-                config.bottom.width
-            } else {
-                0.0
-            },
-            gamma: config.gamma,
-            _padding: [0.0; 7],
+            widths: [
+                width(&config.left),
+                width(&config.right),
+                width(&config.top),
+                width(&config.bottom),
+            ],
+            gamma: [r, g, b, 0.0],
+            black_level: [black_r, black_g, black_b, config.black_level_feather],
         }
     }
 
@@ -322,7 +369,7 @@ mod tests {
     fn test_edge_blend_uniforms_size() {
         assert_eq!(
             std::mem::size_of::<EdgeBlendUniforms>(),
-            48 // 12 floats * 4 bytes (std140 layout with vec3 alignment)
+            48 // 12 floats * 4 bytes (three vec4)
         );
     }
 
@@ -341,6 +388,7 @@ mod tests {
             if let Ok(backend) = backend {
                 let renderer = EdgeBlendRenderer::new(
                     backend.device.clone(),
+                    &backend.queue,
                     wgpu::TextureFormat::Bgra8UnormSrgb,
                 );
                 assert!(renderer.is_ok());
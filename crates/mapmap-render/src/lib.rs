@@ -16,18 +16,22 @@ pub mod backend;
 pub mod color_calibration_renderer;
 pub mod compositor;
 pub mod compressed_texture;
+pub mod dome_renderer;
 pub mod edge_blend_renderer;
 pub mod effect_chain_renderer;
 pub mod hot_reload;
+pub mod mask_renderer;
 mod mesh_buffer_cache;
 pub mod mesh_renderer;
 pub mod oscillator_renderer;
+pub mod output_transform_renderer;
 pub mod paint_texture_cache;
 pub mod pipeline;
 pub mod preset;
 pub mod quad;
 pub mod shader;
 pub mod shader_graph_integration;
+pub mod slice_renderer;
 #[cfg(target_os = "windows")]
 pub mod spout;
 pub mod texture;
@@ -40,16 +44,20 @@ pub use compressed_texture::{
     check_bc_support, create_compressed_texture, upload_compressed_texture,
     CompressedTextureHandle, DxtFormat,
 };
+pub use dome_renderer::{DomeRenderer, DomeWarpBuffers};
 pub use edge_blend_renderer::EdgeBlendRenderer;
 pub use effect_chain_renderer::{EffectChainRenderer, EffectParams};
 pub use hot_reload::{HotReloadIntegration, ShaderChangeEvent, ShaderHotReload, ShaderStatus};
-pub use mesh_buffer_cache::MeshBufferCache;
-pub use mesh_renderer::MeshRenderer;
+pub use mask_renderer::MaskRenderer;
+pub use mesh_buffer_cache::{CachedModelBuffers, MeshBufferCache};
+pub use mesh_renderer::{MeshRenderer, DEPTH_FORMAT};
 pub use oscillator_renderer::OscillatorRenderer;
+pub use output_transform_renderer::{OutputTransformRenderer, HDR_FORMAT, HDR_SURFACE_FORMATS};
 pub use preset::{EffectPreset, PresetLibrary, PresetMetadata};
 pub use quad::QuadRenderer;
 pub use shader::{ShaderHandle, ShaderSource};
 pub use shader_graph_integration::{CompiledShaderGraph, ShaderGraphManager, ShaderGraphRendering};
+pub use slice_renderer::{SliceBuffers, SliceRenderer};
 pub use texture::{TextureDescriptor, TextureHandle, TexturePool};
 pub use uploader::WgpuFrameUploader;
 
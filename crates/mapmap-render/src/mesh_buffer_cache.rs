@@ -2,9 +2,12 @@
 //!
 //! Prevents re-allocating vertex and index buffers every frame for static geometry.
 
-use crate::mesh_renderer::GpuVertex;
-use mapmap_core::{mapping::MappingId, Mesh, MeshType};
+use crate::mesh_renderer::{index_data, index_format, GpuVertex};
+use glam::Mat4;
+use mapmap_core::{mapping::MappingId, Mesh, MeshType, SceneModel};
 use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::SystemTime;
 use wgpu::util::DeviceExt;
 
 /// Cached GPU buffers for a mesh
@@ -13,14 +16,77 @@ pub struct CachedMeshBuffers {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub index_count: u32,
+    pub index_format: wgpu::IndexFormat,
     pub mesh_revision: u64,
     pub mesh_type: MeshType,
     pub vertex_count: usize,
 }
 
+/// Cached GPU buffers for a 3D scene model (32-bit indices)
+#[derive(Debug)]
+pub struct CachedModelBuffers {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+    /// Clip-space transform for outputs without a calibrated projector
+    pub front_view: Mat4,
+}
+
+/// A scene model being loaded, uploaded, or failed to load
+enum ModelEntry {
+    Loading(mpsc::Receiver<Result<SceneModel, String>>),
+    Ready(CachedModelBuffers),
+    /// Modification time of the file that failed to load
+    Failed(Option<SystemTime>),
+}
+
+impl ModelEntry {
+    /// Start loading the model at `path` on a worker thread
+    fn load(path: &str) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let path = path.to_string();
+        std::thread::spawn(move || {
+            let _ = tx.send(SceneModel::load(&path).map_err(|e| e.to_string()));
+        });
+        ModelEntry::Loading(rx)
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Upload a loaded scene model
+fn model_buffers(device: &wgpu::Device, path: &str, model: &SceneModel) -> CachedModelBuffers {
+    let vertices: Vec<GpuVertex> = model
+        .positions
+        .iter()
+        .zip(&model.tex_coords)
+        .map(|(p, uv)| GpuVertex::from_model_vertex(*p, *uv))
+        .collect();
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(&format!("Model Vertex Buffer {}", path)),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(&format!("Model Index Buffer {}", path)),
+        contents: bytemuck::cast_slice(&model.indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+    CachedModelBuffers {
+        vertex_buffer,
+        index_buffer,
+        index_count: model.indices.len() as u32,
+        front_view: model.front_view_transform(),
+    }
+}
+
 /// Manages GPU buffers for meshes to avoid per-frame allocation
 pub struct MeshBufferCache {
     cache: HashMap<MappingId, CachedMeshBuffers>,
+    /// Scene models by file path
+    models: HashMap<String, ModelEntry>,
     scratch_vertices: Vec<GpuVertex>,
 }
 
@@ -29,18 +95,22 @@ impl MeshBufferCache {
     pub fn new() -> Self {
         Self {
             cache: HashMap::new(),
+            models: HashMap::new(),
             scratch_vertices: Vec::with_capacity(1024), // Pre-allocate some space
         }
     }
 
     /// Get buffers for a mapping, creating or updating them if necessary
+    ///
+    /// Returns vertex buffer, index buffer, index count and index format;
+    /// meshes past 65,536 vertices get 32-bit indices.
     pub fn get_buffers(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         mapping_id: MappingId,
         mesh: &Mesh,
-    ) -> (&wgpu::Buffer, &wgpu::Buffer, u32) {
+    ) -> (&wgpu::Buffer, &wgpu::Buffer, u32, wgpu::IndexFormat) {
         // Check if we can reuse the existing buffers (same topology)
         let can_reuse = if let Some(cached) = self.cache.get(&mapping_id) {
             cached.mesh_type == mesh.mesh_type
@@ -73,7 +143,7 @@ impl MeshBufferCache {
                 // Note: We assume indices might change if revision changes, to be safe.
                 // Optimally we'd only update if they actually differ, but that requires readback or shadow copy.
                 // Write is cheap enough.
-                queue.write_buffer(&cached.index_buffer, 0, bytemuck::cast_slice(&mesh.indices));
+                queue.write_buffer(&cached.index_buffer, 0, &index_data(mesh));
 
                 cached.mesh_revision = mesh.revision;
             }
@@ -82,6 +152,7 @@ impl MeshBufferCache {
                 &cached.vertex_buffer,
                 &cached.index_buffer,
                 cached.index_count,
+                cached.index_format,
             );
         }
 
@@ -104,7 +175,7 @@ impl MeshBufferCache {
 
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some(&format!("Mesh Index Buffer {}", mapping_id)),
-            contents: bytemuck::cast_slice(&mesh.indices),
+            contents: &index_data(mesh),
             usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
         });
 
@@ -114,6 +185,7 @@ impl MeshBufferCache {
             vertex_buffer,
             index_buffer,
             index_count,
+            index_format: index_format(mesh),
             mesh_revision: mesh.revision,
             mesh_type: mesh.mesh_type,
             vertex_count: mesh.vertices.len(),
@@ -128,9 +200,50 @@ impl MeshBufferCache {
             &cached_ref.vertex_buffer,
             &cached_ref.index_buffer,
             cached_ref.index_count,
+            cached_ref.index_format,
         )
     }
 
+    /// Get buffers for the scene model at `path`, loading it on first use
+    ///
+    /// Models load on a worker thread and are `None` until they are ready. A
+    /// failed load is retried once the file's modification time changes.
+    pub fn get_model_buffers(
+        &mut self,
+        device: &wgpu::Device,
+        path: &str,
+    ) -> Option<&CachedModelBuffers> {
+        let entry = self
+            .models
+            .entry(path.to_string())
+            .or_insert_with(|| ModelEntry::load(path));
+
+        match entry {
+            ModelEntry::Loading(rx) => match rx.try_recv() {
+                Ok(Ok(model)) => *entry = ModelEntry::Ready(model_buffers(device, path, &model)),
+                Ok(Err(e)) => {
+                    tracing::warn!("Scene model '{}' could not be loaded: {}", path, e);
+                    *entry = ModelEntry::Failed(modified(path));
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    *entry = ModelEntry::Failed(modified(path));
+                }
+            },
+            ModelEntry::Failed(failed_at) => {
+                if modified(path) != *failed_at {
+                    *entry = ModelEntry::load(path);
+                }
+            }
+            ModelEntry::Ready(_) => {}
+        }
+
+        match entry {
+            ModelEntry::Ready(buffers) => Some(buffers),
+            _ => None,
+        }
+    }
+
     /// Remove a mapping from the cache
     pub fn remove(&mut self, mapping_id: MappingId) {
         self.cache.remove(&mapping_id);
@@ -139,6 +252,7 @@ impl MeshBufferCache {
     /// Clear the cache
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.models.clear();
     }
 }
 
@@ -2,17 +2,21 @@
 //!
 //! Supports perspective-correct texture mapping for projection mapping applications.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 
 use bytemuck::{Pod, Zeroable};
-use glam::Mat4;
+use glam::{Mat4, Vec2, Vec3};
 use tracing::debug;
 use wgpu::util::DeviceExt;
 
 use crate::Result;
 use mapmap_core::{Mesh, MeshVertex};
 
+/// Depth buffer format of the 3D model pipeline
+pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
 /// Vertex format for mesh rendering (matches mesh_warp.wgsl)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -28,6 +32,36 @@ impl GpuVertex {
             tex_coords: [vertex.tex_coords.x, vertex.tex_coords.y],
         }
     }
+
+    /// Vertex of a 3D scene model, in model coordinates
+    pub fn from_model_vertex(position: Vec3, tex_coords: Vec2) -> Self {
+        Self {
+            position: position.to_array(),
+            tex_coords: tex_coords.to_array(),
+        }
+    }
+}
+
+/// GPU index format of a mesh, chosen by its vertex count
+pub fn index_format(mesh: &Mesh) -> wgpu::IndexFormat {
+    if mesh.needs_u32_indices() {
+        wgpu::IndexFormat::Uint32
+    } else {
+        wgpu::IndexFormat::Uint16
+    }
+}
+
+/// Index buffer contents of a mesh in its [`index_format`]
+pub(crate) fn index_data(mesh: &Mesh) -> Cow<'_, [u8]> {
+    match index_format(mesh) {
+        wgpu::IndexFormat::Uint32 => Cow::Borrowed(bytemuck::cast_slice(&mesh.indices)),
+        wgpu::IndexFormat::Uint16 => Cow::Owned(
+            mesh.indices
+                .iter()
+                .flat_map(|i| (*i as u16).to_ne_bytes())
+                .collect(),
+        ),
+    }
 }
 
 /// Uniforms for mesh rendering (matches mesh_warp.wgsl)
@@ -56,6 +90,8 @@ struct CachedMeshUniform {
 pub struct MeshRenderer {
     pipeline: wgpu::RenderPipeline,
     pipeline_simple: wgpu::RenderPipeline,
+    /// Depth-tested pipeline for 3D scene models
+    pipeline_3d: wgpu::RenderPipeline,
     uniform_bind_group_layout: wgpu::BindGroupLayout,
     texture_bind_group_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
@@ -237,9 +273,62 @@ impl MeshRenderer {
             cache: None,
         });
 
+        // Create 3D pipeline (depth-tested, 32-bit indices)
+        let pipeline_3d = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Render Pipeline (3D)"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<GpuVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x3,
+                        1 => Float32x2,
+                    ],
+                }],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
         Ok(Self {
             pipeline,
             pipeline_simple,
+            pipeline_3d,
             uniform_bind_group_layout,
             texture_bind_group_layout,
             sampler,
@@ -251,7 +340,7 @@ impl MeshRenderer {
         })
     }
 
-    /// Create GPU buffers from a mesh
+    /// Create GPU buffers from a mesh; indices are in [`index_format`]
     pub fn create_mesh_buffers(&self, mesh: &Mesh) -> (wgpu::Buffer, wgpu::Buffer) {
         // Convert mesh vertices to GPU format
         let vertices: Vec<GpuVertex> = mesh
@@ -272,7 +361,7 @@ impl MeshRenderer {
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Mesh Index Buffer"),
-                contents: bytemuck::cast_slice(&mesh.indices),
+                contents: &index_data(mesh),
                 usage: wgpu::BufferUsages::INDEX,
             });
 
@@ -339,56 +428,20 @@ impl MeshRenderer {
     ) -> Arc<wgpu::BindGroup> {
         let final_transform = self.normalization_matrix * transform;
 
-        let uniforms = MeshUniforms {
-            transform: final_transform.to_cols_array_2d(),
-            opacity,
-            flip_h: 0.0,
-            flip_v: 0.0,
-            brightness: 0.0,
-            contrast: 1.0,
-            saturation: 1.0,
-            hue_shift: 0.0,
-            _padding: 0.0,
-        };
-
-        // Expand cache if needed
-        if self.current_cache_index >= self.uniform_cache.len() {
-            let buffer = self
-                .device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Mesh Uniform Buffer"),
-                    contents: bytemuck::cast_slice(&[uniforms]),
-                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                });
-
-            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Mesh Uniform Bind Group"),
-                layout: &self.uniform_bind_group_layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: buffer.as_entire_binding(),
-                }],
-            });
-
-            self.uniform_cache.push(CachedMeshUniform {
-                buffer,
-                bind_group: Arc::new(bind_group),
-                last_uniforms: Some(uniforms),
-            });
-        }
-
-        // Update current buffer
-        let cache_entry = &mut self.uniform_cache[self.current_cache_index];
-
-        if cache_entry.last_uniforms != Some(uniforms) {
-            queue.write_buffer(&cache_entry.buffer, 0, bytemuck::cast_slice(&[uniforms]));
-            cache_entry.last_uniforms = Some(uniforms);
-        }
-
-        let bind_group = cache_entry.bind_group.clone();
-        self.current_cache_index += 1;
-
-        bind_group
+        self.cached_uniform_bind_group(
+            queue,
+            MeshUniforms {
+                transform: final_transform.to_cols_array_2d(),
+                opacity,
+                flip_h: 0.0,
+                flip_v: 0.0,
+                brightness: 0.0,
+                contrast: 1.0,
+                saturation: 1.0,
+                hue_shift: 0.0,
+                _padding: 0.0,
+            },
+        )
     }
 
     /// Get a uniform bind group with source properties (flip, color correction)
@@ -407,18 +460,55 @@ impl MeshRenderer {
     ) -> Arc<wgpu::BindGroup> {
         let final_transform = self.normalization_matrix * transform;
 
-        let uniforms = MeshUniforms {
-            transform: final_transform.to_cols_array_2d(),
+        self.get_clip_space_bind_group_with_source_props(
+            queue,
+            final_transform,
             opacity,
-            flip_h: if flip_h { 1.0 } else { 0.0 },
-            flip_v: if flip_v { 1.0 } else { 0.0 },
+            flip_h,
+            flip_v,
             brightness,
             contrast,
             saturation,
             hue_shift,
-            _padding: 0.0,
-        };
+        )
+    }
+
+    /// Like [`Self::get_uniform_bind_group_with_source_props`], but
+    /// `clip_from_world` maps vertices straight to clip space (3D models).
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_clip_space_bind_group_with_source_props(
+        &mut self,
+        queue: &wgpu::Queue,
+        clip_from_world: Mat4,
+        opacity: f32,
+        flip_h: bool,
+        flip_v: bool,
+        brightness: f32,
+        contrast: f32,
+        saturation: f32,
+        hue_shift: f32,
+    ) -> Arc<wgpu::BindGroup> {
+        self.cached_uniform_bind_group(
+            queue,
+            MeshUniforms {
+                transform: clip_from_world.to_cols_array_2d(),
+                opacity,
+                flip_h: if flip_h { 1.0 } else { 0.0 },
+                flip_v: if flip_v { 1.0 } else { 0.0 },
+                brightness,
+                contrast,
+                saturation,
+                hue_shift,
+                _padding: 0.0,
+            },
+        )
+    }
 
+    fn cached_uniform_bind_group(
+        &mut self,
+        queue: &wgpu::Queue,
+        uniforms: MeshUniforms,
+    ) -> Arc<wgpu::BindGroup> {
         // Expand cache if needed
         if self.current_cache_index >= self.uniform_cache.len() {
             let buffer = self
@@ -458,6 +548,7 @@ impl MeshRenderer {
 
         bind_group
     }
+
     /// Get a cached texture bind group or create a new one
     pub fn get_texture_bind_group(
         &mut self,
@@ -506,6 +597,7 @@ impl MeshRenderer {
         index_count: u32,
         uniform_bind_group: &'a wgpu::BindGroup,
         texture_bind_group: &'a wgpu::BindGroup,
+        index_format: wgpu::IndexFormat,
         use_perspective_correction: bool,
     ) {
         // Choose pipeline based on perspective correction setting
@@ -518,7 +610,26 @@ impl MeshRenderer {
         render_pass.set_bind_group(0, uniform_bind_group, &[]);
         render_pass.set_bind_group(1, texture_bind_group, &[]);
         render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.set_index_buffer(index_buffer.slice(..), index_format);
+        render_pass.draw_indexed(0..index_count, 0, 0..1);
+    }
+
+    /// Render a 3D scene model; the render pass needs a [`DEPTH_FORMAT`]
+    /// depth attachment.
+    pub fn draw_model<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        vertex_buffer: &'a wgpu::Buffer,
+        index_buffer: &'a wgpu::Buffer,
+        index_count: u32,
+        uniform_bind_group: &'a wgpu::BindGroup,
+        texture_bind_group: &'a wgpu::BindGroup,
+    ) {
+        render_pass.set_pipeline(&self.pipeline_3d);
+        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, texture_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_pass.draw_indexed(0..index_count, 0, 0..1);
     }
 }
@@ -540,6 +651,18 @@ mod tests {
         assert_eq!(gpu_vertex.tex_coords[1], 0.75);
     }
 
+    #[test]
+    fn test_index_format_by_vertex_count() {
+        let quad = Mesh::quad();
+        assert_eq!(index_format(&quad), wgpu::IndexFormat::Uint16);
+        assert_eq!(index_data(&quad).len(), quad.indices.len() * 2);
+        assert_eq!(&index_data(&quad)[..2], &0u16.to_ne_bytes());
+
+        let grid = Mesh::create_grid(300, 300);
+        assert_eq!(index_format(&grid), wgpu::IndexFormat::Uint32);
+        assert_eq!(index_data(&grid).len(), grid.indices.len() * 4);
+    }
+
     #[test]
     fn test_mesh_uniforms_size() {
         assert_eq!(
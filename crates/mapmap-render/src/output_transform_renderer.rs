@@ -0,0 +1,320 @@
+//! Output Transform Renderer for HDR and Wide-Gamut Outputs
+//!
+//! Outputs are composed and post-processed in linear light in
+//! [`HDR_FORMAT`] textures. This renderer runs last and writes each output's
+//! display signal (sRGB, Rec.709, Rec.2020 PQ or HLG, see
+//! [`mapmap_core::hdr`]) to the surface. HDR signals need an HDR surface in
+//! one of the [`HDR_SURFACE_FORMATS`].
+
+use crate::Result;
+use bytemuck::{Pod, Zeroable};
+use mapmap_core::{OutputTransform, OutputTransformConfig, ToneMapping};
+use std::sync::Arc;
+use tracing::info;
+use wgpu::util::DeviceExt;
+
+/// Format of the linear float textures outputs are composed in
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Surface formats that carry an HDR signal, in order of preference. The
+/// 10-bit format takes the PQ and HLG signals as they are encoded, the float
+/// format takes linear scRGB light.
+pub const HDR_SURFACE_FORMATS: [wgpu::TextureFormat; 2] = [
+    wgpu::TextureFormat::Rgb10a2Unorm,
+    wgpu::TextureFormat::Rgba16Float,
+];
+
+/// Output transform uniform parameters matching the WGSL shader
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct OutputTransformUniforms {
+    transform: u32,
+    tone_mapping: u32,
+    exposure_gain: f32,
+    paper_white_nits: f32,
+    peak_nits: f32,
+    target_encoding: u32,
+    _padding: [f32; 2],
+}
+
+/// Output transform renderer
+pub struct OutputTransformRenderer {
+    pipelines: Vec<(wgpu::TextureFormat, wgpu::RenderPipeline)>,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    uniform_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    target_format: wgpu::TextureFormat,
+    device: Arc<wgpu::Device>,
+}
+
+impl OutputTransformRenderer {
+    /// Create a new output transform renderer
+    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Result<Self> {
+        info!("Creating output transform renderer");
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Output Transform Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Output Transform Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Output Transform Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let shader_source = include_str!("../../../shaders/output_transform.wgsl");
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Output Transform Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Output Transform Pipeline Layout"),
+            bind_group_layouts: &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // The default target plus every HDR surface format an output window
+        // may be switched to
+        let mut formats = vec![target_format];
+        formats.extend(
+            HDR_SURFACE_FORMATS
+                .into_iter()
+                .filter(|format| *format != target_format),
+        );
+        let pipelines = formats
+            .into_iter()
+            .map(|format| {
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Output Transform Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader_module,
+                        entry_point: Some("vs_main"),
+                        buffers: &[],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader_module,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format,
+                            blend: Some(wgpu::BlendState::REPLACE),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        cull_mode: None,
+                        unclipped_depth: false,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        conservative: false,
+                    },
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState {
+                        count: 1,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                    cache: None,
+                });
+                (format, pipeline)
+            })
+            .collect();
+
+        Ok(Self {
+            pipelines,
+            texture_bind_group_layout,
+            uniform_bind_group_layout,
+            sampler,
+            target_format,
+            device,
+        })
+    }
+
+    /// Default format of the textures this renderer draws into
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
+    /// Create a texture bind group for the linear composition
+    pub fn create_texture_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Output Transform Texture Bind Group"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Create a uniform buffer from an output transform configuration for a
+    /// target of the given format
+    pub fn create_uniform_buffer(
+        &self,
+        config: &OutputTransformConfig,
+        target_format: wgpu::TextureFormat,
+    ) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Output Transform Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[Self::uniforms(config, target_format)]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    /// Update an existing uniform buffer
+    pub fn update_uniform_buffer(
+        &self,
+        queue: &wgpu::Queue,
+        buffer: &wgpu::Buffer,
+        config: &OutputTransformConfig,
+        target_format: wgpu::TextureFormat,
+    ) {
+        queue.write_buffer(
+            buffer,
+            0,
+            bytemuck::cast_slice(&[Self::uniforms(config, target_format)]),
+        );
+    }
+
+    fn uniforms(
+        config: &OutputTransformConfig,
+        target_format: wgpu::TextureFormat,
+    ) -> OutputTransformUniforms {
+        OutputTransformUniforms {
+            transform: match config.transform {
+                OutputTransform::Srgb => 0,
+                OutputTransform::Rec709 => 1,
+                OutputTransform::Rec2020Pq => 2,
+                OutputTransform::Hlg => 3,
+            },
+            tone_mapping: match config.tone_mapping {
+                ToneMapping::None => 0,
+                ToneMapping::Reinhard => 1,
+                ToneMapping::Aces => 2,
+            },
+            exposure_gain: config.exposure_gain(),
+            paper_white_nits: config.paper_white_nits,
+            peak_nits: config.peak_nits,
+            target_encoding: match target_format {
+                // Float surfaces take linear scRGB light
+                wgpu::TextureFormat::Rgba16Float => 2,
+                format if format.is_srgb() => 1,
+                _ => 0,
+            },
+            _padding: [0.0; 2],
+        }
+    }
+
+    /// Create a uniform bind group
+    pub fn create_uniform_bind_group(&self, buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Output Transform Uniform Bind Group"),
+            layout: &self.uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        })
+    }
+
+    /// Render output transform pass into a target of the given format
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        target_format: wgpu::TextureFormat,
+        texture_bind_group: &'a wgpu::BindGroup,
+        uniform_bind_group: &'a wgpu::BindGroup,
+    ) {
+        let pipeline = self
+            .pipelines
+            .iter()
+            .find(|(format, _)| *format == target_format)
+            .map_or(&self.pipelines[0].1, |(_, pipeline)| pipeline);
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, texture_bind_group, &[]);
+        render_pass.set_bind_group(1, uniform_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_transform_uniforms_size() {
+        assert_eq!(std::mem::size_of::<OutputTransformUniforms>(), 32);
+    }
+
+    #[test]
+    fn test_output_transform_renderer_creation() {
+        pollster::block_on(async {
+            let backend = crate::WgpuBackend::new(None).await;
+            if let Ok(backend) = backend {
+                let renderer = OutputTransformRenderer::new(
+                    backend.device.clone(),
+                    wgpu::TextureFormat::Bgra8UnormSrgb,
+                );
+                assert!(renderer.is_ok());
+                let renderer = renderer.unwrap();
+                let buffer = renderer.create_uniform_buffer(
+                    &OutputTransformConfig::default(),
+                    renderer.target_format(),
+                );
+                assert_eq!(buffer.size(), 32);
+            }
+        });
+    }
+}
@@ -0,0 +1,273 @@
+//! Slice Renderer for Output Slicing
+//!
+//! Composes the slices of a [`SliceLayout`] onto one output window: each
+//! enabled slice is drawn as a quad showing its (cropped, flipped and
+//! rotated) canvas region at its place on the output raster
+
+use crate::Result;
+use bytemuck::{Pod, Zeroable};
+use mapmap_core::SliceLayout;
+use std::sync::Arc;
+use tracing::info;
+use wgpu::util::DeviceExt;
+
+/// Slice quad vertex: output position and content position
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct Vertex {
+    position: [f32; 2],
+    uv: [f32; 2],
+}
+
+impl Vertex {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// GPU resources of one output's slice quads
+pub struct SliceBuffers {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+}
+
+/// Output slicing renderer
+pub struct SliceRenderer {
+    pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    target_format: wgpu::TextureFormat,
+    device: Arc<wgpu::Device>,
+}
+
+impl SliceRenderer {
+    /// Create a new slice renderer
+    pub fn new(device: Arc<wgpu::Device>, target_format: wgpu::TextureFormat) -> Result<Self> {
+        info!("Creating slice renderer");
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Slice Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Slice Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let shader_source = include_str!("../../../shaders/slice.wgsl");
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Slice Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Slice Pipeline Layout"),
+            bind_group_layouts: &[&texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Slice Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Ok(Self {
+            pipeline,
+            texture_bind_group_layout,
+            sampler,
+            target_format,
+            device,
+        })
+    }
+
+    /// Format of the textures this renderer draws into
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
+    /// Create a texture bind group for the content texture
+    pub fn create_texture_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Slice Texture Bind Group"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Build the quads of the enabled slices of a layout. Slice inputs
+    /// address the output's content texture directly.
+    pub fn create_slices(&self, layout: &SliceLayout, resolution: (u32, u32)) -> SliceBuffers {
+        let mut vertices = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        for slice in layout.slices.iter().filter(|slice| slice.enabled) {
+            let base = vertices.len() as u32;
+            for (position, source) in slice
+                .target_corners(resolution)
+                .into_iter()
+                .zip(slice.source_corners())
+            {
+                vertices.push(Vertex {
+                    position: position.to_array(),
+                    uv: source.to_array(),
+                });
+            }
+            indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+
+        let vertex_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Slice Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+        let index_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Slice Index Buffer"),
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+        SliceBuffers {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+        }
+    }
+
+    /// Render output slicing pass
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        texture_bind_group: &'a wgpu::BindGroup,
+        slices: &'a SliceBuffers,
+    ) {
+        if slices.index_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, texture_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, slices.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(slices.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..slices.index_count, 0, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mapmap_core::{CanvasRegion, OutputSlice, SliceRect};
+
+    #[test]
+    fn test_vertex_size() {
+        assert_eq!(
+            std::mem::size_of::<Vertex>(),
+            16 // 4 floats * 4 bytes
+        );
+    }
+
+    #[test]
+    fn test_slice_renderer_creation() {
+        pollster::block_on(async {
+            let backend = crate::WgpuBackend::new(None).await;
+            if let Ok(backend) = backend {
+                let renderer =
+                    SliceRenderer::new(backend.device.clone(), wgpu::TextureFormat::Bgra8UnormSrgb);
+                assert!(renderer.is_ok());
+
+                let mut layout = SliceLayout::default();
+                layout.add_slice(OutputSlice::new(
+                    "Panel".to_string(),
+                    CanvasRegion::new(0.0, 0.0, 0.5, 0.5),
+                    SliceRect::new(0, 0, 960, 540),
+                ));
+                let slices = renderer.unwrap().create_slices(&layout, (1920, 1080));
+                assert_eq!(slices.index_count, 6);
+            }
+        });
+    }
+}
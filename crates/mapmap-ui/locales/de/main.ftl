@@ -326,6 +326,12 @@ label-osc-value = OSC-Wert:
 check-osc-value = Wert prüfen
 btn-add-cue = Cue hinzufügen
 btn-remove-cue = Cue entfernen
+header-cue-stacks = Cue-Stacks
+label-no-cue-stacks = Keine Cue-Stacks
+label-stack-cues = { $count } Cues
+btn-release = Freigeben
+btn-record-cue = Cue aufnehmen
+btn-add-stack = Stack hinzufügen
 btn-go = Start
 btn-back = Zurück
 btn-stop = Stopp
@@ -371,6 +371,12 @@ label-osc-value = OSC Value:
 check-osc-value = Match Value
 btn-add-cue = Add Cue
 btn-remove-cue = Remove Cue
+header-cue-stacks = Cue Stacks
+label-no-cue-stacks = No cue stacks
+label-stack-cues = { $count } cues
+btn-release = Release
+btn-record-cue = Record Cue
+btn-add-stack = Add Stack
 btn-go = Go
 btn-back = Back
 btn-stop = Stop
@@ -144,6 +144,16 @@ pub enum UIAction {
     SetOutputColorCalibration(u64, mapmap_core::ColorCalibration),
     /// Create 2x2 projector array
     CreateProjectorArray2x2((u32, u32), f32),
+    /// Show a Gray-code pattern frame on an output (output ID, frame); `None` shows its content again
+    ShowCalibrationPattern(u64, Option<usize>),
+    /// Export the Gray-code patterns of an output to a folder
+    ExportCalibrationPatterns(u64),
+    /// Calibrate the projectors of a module from a folder of captures
+    RunStructuredLightCalibration(mapmap_core::module::ModuleId),
+    /// Show a color matching test patch on an output (output ID, RGB); `None` shows its content again
+    ShowColorPatch(u64, Option<[f32; 3]>),
+    /// Solve and apply color matching LUTs from the measurements stored on each projector output
+    SolveColorMatch,
 
     // View actions
     /// Toggle fullscreen mode
@@ -160,8 +170,20 @@ pub enum UIAction {
     // Audio actions
     /// Select audio input device
     SelectAudioDevice(String),
+    /// Analyze the selected live input device instead of a file
+    UseSystemAudioInput,
+    /// Analyze an audio file (empty path opens a file dialog)
+    SelectAudioFile(String),
+    /// Analyze the soundtrack of a media source (module ID, part ID) in sync with its playback
+    UseVideoAudio(u64, u64),
     /// Update audio configuration
     UpdateAudioConfig(mapmap_core::audio::AudioConfig),
+    /// Tap the tempo
+    TapTempo,
+    /// Forget the tap tempo so other sources take over again
+    ClearTapTempo,
+    /// Make the current moment the downbeat of the tempo clock
+    ResyncTempo,
     /// Toggle audio panel visibility
     ToggleAudioPanel,
 
@@ -228,6 +250,16 @@ pub enum UIAction {
     PrevCue,
     /// Stop current cue
     StopCue,
+    /// Add a cue stack with a name
+    AddCueStack(String),
+    /// Remove a cue stack by ID
+    RemoveCueStack(u32),
+    /// Record the current state as a new cue at the end of a cue stack
+    RecordStackCue(u32),
+    /// Advance a cue stack to its next cue
+    GoCueStack(u32),
+    /// Release a cue stack
+    ReleaseCueStack(u32),
 
     // Shader Graph (Phase 6b)
     /// Open shader graph editor
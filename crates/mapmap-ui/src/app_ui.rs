@@ -95,6 +95,12 @@ pub struct AppUI {
     pub audio_devices: Vec<String>,
     /// Currently selected audio device
     pub selected_audio_device: Option<String>,
+    /// Source currently feeding the audio analyzer
+    pub audio_source: mapmap_core::audio::AudioSource,
+    /// File name of the analyzed audio file or soundtrack, if any
+    pub audio_source_file: Option<String>,
+    /// Channel count of the current audio input
+    pub audio_input_channels: u16,
     /// Recent project files
     pub recent_files: Vec<String>,
     /// Pending UI actions to be processed
@@ -161,8 +167,12 @@ pub struct AppUI {
     pub show_controller_overlay: bool,
     /// Global flag for "Hover" MIDI Learn Mode (Way 1)
     pub is_midi_learn_mode: bool,
-    /// Current detected BPM (None if not detected yet)
+    /// Current tempo of the global tempo clock (None while stopped)
     pub current_bpm: Option<f32>,
+    /// Current beat grid position (bar, beat, phrase)
+    pub beat_grid: mapmap_core::audio::beat_grid::BeatGridState,
+    /// Tempo source driving the beat grid (None while stopped)
+    pub tempo_source: Option<mapmap_core::audio::tempo::TempoSource>,
     /// Preview panel for output thumbnails
     pub preview_panel: PreviewPanel,
     /// Show preview panel
@@ -243,6 +253,9 @@ impl Default for AppUI {
             audio_devices: vec!["None".to_string()],
             // Load selected audio device from user config
             selected_audio_device: saved_audio_device,
+            audio_source: mapmap_core::audio::AudioSource::SystemInput,
+            audio_source_file: None,
+            audio_input_channels: 1,
             recent_files: saved_recent_files,
             actions: Vec::new(),
             i18n: LocaleManager::new(&saved_language),
@@ -280,6 +293,8 @@ impl Default for AppUI {
             show_controller_overlay: saved_show_controller_overlay, // Load from config
             is_midi_learn_mode: false,
             current_bpm: None,
+            beat_grid: Default::default(),
+            tempo_source: Default::default(),
             preview_panel: PreviewPanel::default(),
             show_preview_panel: true,    // Show by default
             control_panel_height: 250.0, // Default height in pixels
@@ -269,6 +269,9 @@ pub struct HueConfig {
     /// Setup mode/auto-connect
     #[serde(default)]
     pub auto_connect: bool,
+    /// Connect to the built-in virtual bridge instead of hardware
+    #[serde(default)]
+    pub virtual_bridge: bool,
 }
 
 /// User configuration settings
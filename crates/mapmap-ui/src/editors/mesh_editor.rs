@@ -151,6 +151,41 @@ impl MeshEditor {
         });
     }
 
+    /// Load the triangles of a core mesh, positions scaled by `scale`
+    pub fn set_from_mesh(&mut self, mesh: &mapmap_core::Mesh, scale: f32) {
+        self.vertices = mesh
+            .vertices
+            .iter()
+            .map(|v| Vertex {
+                position: Pos2::new(v.position.x * scale, v.position.y * scale),
+                control_in: None,
+                control_out: None,
+                selected: false,
+            })
+            .collect();
+        self.faces = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|t| Face {
+                vertices: [t[0] as usize, t[1] as usize, t[2] as usize],
+            })
+            .collect();
+        self.dragging_element = None;
+    }
+
+    /// Write edited positions back into `mesh`; `false` if the vertex count
+    /// no longer matches.
+    pub fn apply_to_mesh(&self, mesh: &mut mapmap_core::Mesh, scale: f32) -> bool {
+        if mesh.vertices.len() != self.vertices.len() {
+            return false;
+        }
+        for (target, vertex) in mesh.vertices.iter_mut().zip(&self.vertices) {
+            target.position = glam::Vec2::new(vertex.position.x / scale, vertex.position.y / scale);
+        }
+        mesh.revision += 1;
+        true
+    }
+
     /// Get quad corners if the mesh is a simple quad
     pub fn get_quad_corners(&self) -> Option<(Pos2, Pos2, Pos2, Pos2)> {
         if self.vertices.len() == 4 {
@@ -165,70 +200,6 @@ impl MeshEditor {
         }
     }
 
-    /// Populate mesh from a flattened list of bezier points (Pos, In, Out)
-    pub fn set_from_bezier_points(&mut self, points: &[(f32, f32)]) {
-        self.vertices.clear();
-        self.faces.clear();
-
-        if points.is_empty() {
-            // Default if empty
-            return;
-        }
-
-        let num_verts = points.len() / 3;
-        for i in 0..num_verts {
-            let p_pos = points[i * 3];
-            let p_in = points[i * 3 + 1];
-            let p_out = points[i * 3 + 2];
-
-            let pos = Pos2::new(p_pos.0, p_pos.1);
-            // If control point == position, treat as None
-            let ctrl_in = if (p_in.0 - p_pos.0).abs() < 0.001 && (p_in.1 - p_pos.1).abs() < 0.001 {
-                None
-            } else {
-                Some(Vec2::new(p_in.0 - p_pos.0, p_in.1 - p_pos.1))
-            };
-            let ctrl_out = if (p_out.0 - p_pos.0).abs() < 0.001 && (p_out.1 - p_pos.1).abs() < 0.001
-            {
-                None
-            } else {
-                Some(Vec2::new(p_out.0 - p_pos.0, p_out.1 - p_pos.1))
-            };
-
-            self.vertices.push(Vertex {
-                position: pos,
-                control_in: ctrl_in,
-                control_out: ctrl_out,
-                selected: false,
-            });
-        }
-
-        // Rebuild simple quad faces if 4 vertices (Standard Quad topology)
-        if self.vertices.len() == 4 {
-            self.faces.push(Face {
-                vertices: [0, 1, 2],
-            });
-            self.faces.push(Face {
-                vertices: [0, 2, 3],
-            });
-        }
-    }
-
-    /// Serialize mesh to flattened list of bezier points (Pos, In, Out)
-    pub fn get_bezier_points(&self) -> Vec<(f32, f32)> {
-        let mut points = Vec::new();
-        for v in &self.vertices {
-            let pos = v.position;
-            let p_in = v.control_in.map(|o| pos + o).unwrap_or(pos);
-            let p_out = v.control_out.map(|o| pos + o).unwrap_or(pos);
-
-            points.push((pos.x, pos.y));
-            points.push((p_in.x, p_in.y));
-            points.push((p_out.x, p_out.y));
-        }
-        points
-    }
-
     /// Subdivide the mesh
     pub fn subdivide(&mut self) {
         // Catmull-Clark subdivision (simplified)
@@ -790,6 +761,22 @@ pub enum MeshEditorAction {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_dense_mesh_round_trip() {
+        let mut mesh = mapmap_core::Mesh::create_grid(300, 300);
+        assert!(mesh.needs_u32_indices());
+
+        let mut editor = MeshEditor::new();
+        editor.set_from_mesh(&mesh, 200.0);
+        assert_eq!(editor.faces.len(), mesh.triangle_count());
+        let last = mesh.vertex_count() - 1;
+        assert!(editor.faces.iter().any(|f| f.vertices.contains(&last)));
+
+        editor.vertices[last].position = Pos2::new(190.0, 180.0);
+        assert!(editor.apply_to_mesh(&mut mesh, 200.0));
+        assert_eq!(mesh.vertices[last].position, glam::Vec2::new(0.95, 0.9));
+    }
+
     #[test]
     fn test_hit_detection_and_dragging() {
         let mut editor = MeshEditor::new();
@@ -16,8 +16,9 @@ impl ModuleCanvas {
         manager: &mut ModuleManager,
         locale: &LocaleManager,
         actions: &mut Vec<crate::UIAction>,
+        key_bindings: &mapmap_control::shortcuts::KeyBindings,
         options: ModuleCanvasRenderOptions,
     ) {
-        renderer::show(self, ui, manager, locale, actions, options);
+        renderer::show(self, ui, manager, locale, actions, key_bindings, options);
     }
 }
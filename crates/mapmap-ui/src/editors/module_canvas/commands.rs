@@ -0,0 +1,384 @@
+//! Execution of module canvas shortcut and command palette actions.
+
+use super::state::ModuleCanvas;
+use super::types::CanvasAction;
+use super::utils;
+use mapmap_control::shortcuts::{Action, DistributeAxis, PartAlignment};
+use mapmap_core::module::{MapFlowModule, ModuleManager, ModulePart, ModulePartId, PartType};
+
+/// Offset of duplicated parts relative to the originals (canvas units)
+const DUPLICATE_OFFSET: (f32, f32) = (30.0, 30.0);
+
+/// Map a part type name as used by `Action::AddModulePart` to a part type
+pub fn part_type_from_name(name: &str) -> Option<PartType> {
+    match name {
+        "Trigger" => Some(PartType::Trigger),
+        "Source" => Some(PartType::Source),
+        "BevyParticles" => Some(PartType::BevyParticles),
+        "Bevy3DShape" => Some(PartType::Bevy3DShape),
+        "Mask" => Some(PartType::Mask),
+        "Modulator" => Some(PartType::Modulator),
+        "Mesh" => Some(PartType::Mesh),
+        "Layer" => Some(PartType::Layer),
+        "Hue" => Some(PartType::Hue),
+        "Output" => Some(PartType::Output),
+        _ => None,
+    }
+}
+
+/// Size of a part on the canvas, falling back to the automatic node size
+pub fn part_size(part: &ModulePart) -> (f32, f32) {
+    part.size.unwrap_or_else(|| {
+        let h = 80.0 + (part.inputs.len().max(part.outputs.len()) as f32) * 20.0;
+        (200.0, h)
+    })
+}
+
+/// Execute a module canvas action.
+///
+/// `view_center` is the canvas position new parts are placed around.
+/// Returns `false` if the action is not a canvas action or could not be applied.
+pub fn execute_action(
+    canvas: &mut ModuleCanvas,
+    manager: &mut ModuleManager,
+    action: &Action,
+    view_center: (f32, f32),
+) -> bool {
+    match action {
+        Action::JumpToModule(module_id) => {
+            if manager.get_module(*module_id).is_none() {
+                return false;
+            }
+            canvas.set_active_module(Some(*module_id));
+            return true;
+        }
+        Action::ShowCommandPalette => {
+            canvas.show_command_palette = true;
+            canvas.command_palette_query.clear();
+            canvas.command_palette_selected = 0;
+            return true;
+        }
+        _ => {}
+    }
+
+    let Some(module) = canvas
+        .active_module_id
+        .and_then(|id| manager.get_module_mut(id))
+    else {
+        return false;
+    };
+
+    let parts_before = module.parts.clone();
+    let connections_before = module.connections.len();
+
+    match action {
+        Action::AddModulePart(name) => {
+            let Some(part_type) = part_type_from_name(name) else {
+                tracing::warn!("Unknown part type '{}'", name);
+                return false;
+            };
+            let position = utils::find_free_position(&module.parts, view_center);
+            let part_id = module.add_part(part_type, position);
+            canvas.selected_parts = vec![part_id];
+        }
+        Action::ConnectSelectedParts => {
+            for pair in canvas.selected_parts.windows(2) {
+                if module.connect_parts(pair[0], pair[1]).is_none() {
+                    tracing::debug!("No free compatible sockets from {} to {}", pair[0], pair[1]);
+                }
+            }
+        }
+        Action::DuplicateSelectedParts => {
+            canvas.selected_parts =
+                module.duplicate_parts(&canvas.selected_parts, DUPLICATE_OFFSET);
+        }
+        Action::GroupSelectedParts => toggle_group(module, &canvas.selected_parts),
+        Action::AlignSelectedParts(alignment) => {
+            align_parts(module, &canvas.selected_parts, *alignment)
+        }
+        Action::DistributeSelectedParts(axis) => {
+            distribute_parts(module, &canvas.selected_parts, *axis)
+        }
+        Action::TogglePartBypass => module.toggle_bypass(&canvas.selected_parts),
+        _ => return false,
+    }
+
+    if let Some(undo) = undo_action(&parts_before, connections_before, module) {
+        canvas.undo_stack.push(undo);
+        canvas.redo_stack.clear();
+    }
+    true
+}
+
+/// Put the parts into a new group, or ungroup them if they already share one
+fn toggle_group(module: &mut MapFlowModule, part_ids: &[ModulePartId]) {
+    let groups: Vec<Option<&String>> = module
+        .parts
+        .iter()
+        .filter(|p| part_ids.contains(&p.id))
+        .map(|p| p.group.as_ref())
+        .collect();
+    let Some(first) = groups.first() else {
+        return;
+    };
+
+    if first.is_some() && groups.iter().all(|g| g == first) {
+        module.set_part_group(part_ids, None);
+    } else {
+        let name = module.next_group_name();
+        module.set_part_group(part_ids, Some(name));
+    }
+}
+
+/// Align the parts to the outermost edge of the selection
+pub fn align_parts(
+    module: &mut MapFlowModule,
+    part_ids: &[ModulePartId],
+    alignment: PartAlignment,
+) {
+    let edge = |part: &ModulePart| {
+        let (w, h) = part_size(part);
+        match alignment {
+            PartAlignment::Left => part.position.0,
+            PartAlignment::Right => part.position.0 + w,
+            PartAlignment::Top => part.position.1,
+            PartAlignment::Bottom => part.position.1 + h,
+        }
+    };
+    let edges = module
+        .parts
+        .iter()
+        .filter(|p| part_ids.contains(&p.id))
+        .map(edge);
+    let target = match alignment {
+        PartAlignment::Left | PartAlignment::Top => edges.fold(f32::INFINITY, f32::min),
+        PartAlignment::Right | PartAlignment::Bottom => edges.fold(f32::NEG_INFINITY, f32::max),
+    };
+    if !target.is_finite() {
+        return;
+    }
+
+    for part in module.parts.iter_mut().filter(|p| part_ids.contains(&p.id)) {
+        let (w, h) = part_size(part);
+        match alignment {
+            PartAlignment::Left => part.position.0 = target,
+            PartAlignment::Right => part.position.0 = target - w,
+            PartAlignment::Top => part.position.1 = target,
+            PartAlignment::Bottom => part.position.1 = target - h,
+        }
+    }
+}
+
+/// Space the part centers evenly between the outermost parts of the selection
+pub fn distribute_parts(
+    module: &mut MapFlowModule,
+    part_ids: &[ModulePartId],
+    axis: DistributeAxis,
+) {
+    let center = |part: &ModulePart| {
+        let (w, h) = part_size(part);
+        match axis {
+            DistributeAxis::Horizontal => part.position.0 + w / 2.0,
+            DistributeAxis::Vertical => part.position.1 + h / 2.0,
+        }
+    };
+
+    let mut ordered: Vec<(ModulePartId, f32)> = module
+        .parts
+        .iter()
+        .filter(|p| part_ids.contains(&p.id))
+        .map(|p| (p.id, center(p)))
+        .collect();
+    if ordered.len() < 3 {
+        return;
+    }
+    ordered.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let first = ordered[0].1;
+    let step = (ordered[ordered.len() - 1].1 - first) / (ordered.len() - 1) as f32;
+
+    for (i, (part_id, _)) in ordered.iter().enumerate() {
+        if let Some(part) = module.parts.iter_mut().find(|p| p.id == *part_id) {
+            let (w, h) = part_size(part);
+            let target = first + step * i as f32;
+            match axis {
+                DistributeAxis::Horizontal => part.position.0 = target - w / 2.0,
+                DistributeAxis::Vertical => part.position.1 = target - h / 2.0,
+            }
+        }
+    }
+}
+
+/// Build an undo entry from the changes made to a module since `parts_before`
+fn undo_action(
+    parts_before: &[ModulePart],
+    connections_before: usize,
+    module: &MapFlowModule,
+) -> Option<CanvasAction> {
+    let mut actions = Vec::new();
+
+    for part in &module.parts {
+        match parts_before.iter().find(|p| p.id == part.id) {
+            Some(before) if before != part => actions.push(CanvasAction::UpdatePart {
+                part_id: part.id,
+                before: Box::new(before.clone()),
+                after: Box::new(part.clone()),
+            }),
+            Some(_) => {}
+            None => actions.push(CanvasAction::AddPart {
+                part_id: part.id,
+                part_data: part.clone(),
+            }),
+        }
+    }
+
+    // Commands only ever append connections
+    for connection in module.connections.iter().skip(connections_before) {
+        actions.push(CanvasAction::AddConnection {
+            connection: connection.clone(),
+        });
+    }
+
+    (!actions.is_empty()).then_some(CanvasAction::Batch(actions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editors::module_canvas::controller;
+
+    fn setup() -> (ModuleCanvas, ModuleManager, u64) {
+        let mut manager = ModuleManager::new();
+        let module_id = manager.create_module("Test".to_string());
+        let mut canvas = ModuleCanvas::default();
+        canvas.set_active_module(Some(module_id));
+        (canvas, manager, module_id)
+    }
+
+    fn positions(manager: &ModuleManager, module_id: u64) -> Vec<(f32, f32)> {
+        manager
+            .get_module(module_id)
+            .unwrap()
+            .parts
+            .iter()
+            .map(|p| p.position)
+            .collect()
+    }
+
+    #[test]
+    fn test_add_part_selects_it() {
+        let (mut canvas, mut manager, module_id) = setup();
+        let action = Action::AddModulePart("Layer".to_string());
+
+        assert!(execute_action(
+            &mut canvas,
+            &mut manager,
+            &action,
+            (0.0, 0.0)
+        ));
+
+        let module = manager.get_module(module_id).unwrap();
+        assert_eq!(module.parts.len(), 1);
+        assert_eq!(canvas.selected_parts, vec![module.parts[0].id]);
+
+        let unknown = Action::AddModulePart("Teapot".to_string());
+        assert!(!execute_action(
+            &mut canvas,
+            &mut manager,
+            &unknown,
+            (0.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn test_align_and_distribute() {
+        let (mut canvas, mut manager, module_id) = setup();
+        let module = manager.get_module_mut(module_id).unwrap();
+        let a = module.add_part(PartType::Mask, (0.0, 10.0));
+        let b = module.add_part(PartType::Mask, (100.0, 50.0));
+        let c = module.add_part(PartType::Mask, (400.0, 0.0));
+        for part in &mut module.parts {
+            part.size = Some((100.0, 50.0));
+        }
+        canvas.selected_parts = vec![a, b, c];
+
+        let align = Action::AlignSelectedParts(PartAlignment::Bottom);
+        assert!(execute_action(
+            &mut canvas,
+            &mut manager,
+            &align,
+            (0.0, 0.0)
+        ));
+        assert_eq!(
+            positions(&manager, module_id),
+            vec![(0.0, 50.0), (100.0, 50.0), (400.0, 50.0)]
+        );
+
+        let distribute = Action::DistributeSelectedParts(DistributeAxis::Horizontal);
+        assert!(execute_action(
+            &mut canvas,
+            &mut manager,
+            &distribute,
+            (0.0, 0.0)
+        ));
+        assert_eq!(
+            positions(&manager, module_id),
+            vec![(0.0, 50.0), (200.0, 50.0), (400.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_is_undoable() {
+        let (mut canvas, mut manager, module_id) = setup();
+        let module = manager.get_module_mut(module_id).unwrap();
+        let source = module.add_part(PartType::Source, (0.0, 0.0));
+        let layer = module.add_part(PartType::Layer, (300.0, 0.0));
+        canvas.selected_parts = vec![source, layer];
+
+        let connect = Action::ConnectSelectedParts;
+        assert!(execute_action(
+            &mut canvas,
+            &mut manager,
+            &connect,
+            (0.0, 0.0)
+        ));
+        let duplicate = Action::DuplicateSelectedParts;
+        assert!(execute_action(
+            &mut canvas,
+            &mut manager,
+            &duplicate,
+            (0.0, 0.0)
+        ));
+
+        let module = manager.get_module_mut(module_id).unwrap();
+        assert_eq!(module.parts.len(), 4);
+        assert_eq!(module.connections.len(), 2);
+        assert!(!canvas.selected_parts.contains(&source));
+
+        let undo = canvas.undo_stack.pop().unwrap();
+        controller::apply_undo_action(module, &undo);
+        assert_eq!(module.parts.len(), 2);
+        assert_eq!(module.connections.len(), 1);
+    }
+
+    #[test]
+    fn test_group_toggles() {
+        let (mut canvas, mut manager, module_id) = setup();
+        let module = manager.get_module_mut(module_id).unwrap();
+        let a = module.add_part(PartType::Mask, (0.0, 0.0));
+        let b = module.add_part(PartType::Mask, (0.0, 200.0));
+        canvas.selected_parts = vec![a, b];
+
+        let group = Action::GroupSelectedParts;
+        execute_action(&mut canvas, &mut manager, &group, (0.0, 0.0));
+        let module = manager.get_module(module_id).unwrap();
+        assert!(module
+            .parts
+            .iter()
+            .all(|p| p.group.as_deref() == Some("Group 1")));
+
+        execute_action(&mut canvas, &mut manager, &group, (0.0, 0.0));
+        let module = manager.get_module(module_id).unwrap();
+        assert!(module.parts.iter().all(|p| p.group.is_none()));
+    }
+}
@@ -1,8 +1,9 @@
 use super::super::utils;
 use egui::Ui;
 use mapmap_core::module::{
-    BevyCameraMode, BlendModeType, EffectType, HueNodeType, LayerType, MaskShape, MaskType,
-    ModuleManager, ModulePartType, ModulizerType, OutputType, SourceType, TriggerType,
+    BevyCameraMode, BezierMask, BlendModeType, EffectType, HueNodeType, LayerType, LightBackend,
+    MaskShape, MaskType, ModuleManager, ModulePartType, ModulizerType, OutputType, SourceType,
+    TriggerType,
 };
 
 pub fn render_add_node_menu_content(
@@ -29,11 +30,18 @@ pub fn render_add_node_menu_content(
                 add_node(ModulePartType::Trigger(TriggerType::Beat));
                 ui.close();
             }
+            if ui.button("\u{1F4D0} Beat Grid").clicked() {
+                add_node(ModulePartType::Trigger(TriggerType::BeatGrid {
+                    division: mapmap_core::audio::beat_grid::BeatDivision::Bar,
+                }));
+                ui.close();
+            }
             if ui.button("\u{1F50A} Audio FFT").clicked() {
                 add_node(ModulePartType::Trigger(TriggerType::AudioFFT {
                     band: mapmap_core::module::AudioBand::Bass,
                     threshold: 0.5,
                     output_config: mapmap_core::module::AudioTriggerOutputConfig::default(),
+                    analyzer: None,
                 }));
                 ui.close();
             }
@@ -156,6 +164,12 @@ pub fn render_add_node_menu_content(
                 }));
                 ui.close();
             }
+            if ui.button("\u{270F} Bezier").clicked() {
+                add_node(ModulePartType::Mask(
+                    MaskType::Bezier(BezierMask::default()),
+                ));
+                ui.close();
+            }
         });
 
         ui.menu_button("🎛️ Modulators", |ui| {
@@ -245,8 +259,19 @@ pub fn render_add_node_menu_content(
                 output_fps: 60.0,
                 ndi_enabled: false,
                 ndi_stream_name: String::new(),
+                calibration_3d: Default::default(),
+                color_measurements: None,
             }));
             ui.close();
         }
+        ui.menu_button("\u{1F4A1} Light Output", |ui| {
+            for backend in LightBackend::presets() {
+                if ui.button(backend.label()).clicked() {
+                    let name = format!("{} Lights", backend.label());
+                    add_node(ModulePartType::Output(OutputType::new_light(name, backend)));
+                    ui.close();
+                }
+            }
+        });
     }
 }
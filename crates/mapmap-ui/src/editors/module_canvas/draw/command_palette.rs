@@ -0,0 +1,145 @@
+use super::super::state::ModuleCanvas;
+use egui::{Rect, Ui};
+use mapmap_control::shortcuts::{Action, KeyBindings, ShortcutContext};
+use mapmap_core::module::ModuleManager;
+
+/// Draw the command palette and return the chosen action, if any
+pub fn draw_command_palette(
+    canvas: &mut ModuleCanvas,
+    ui: &mut Ui,
+    canvas_rect: Rect,
+    manager: &ModuleManager,
+    key_bindings: &KeyBindings,
+) -> Option<Action> {
+    if !canvas.show_command_palette {
+        return None;
+    }
+
+    let mut entries: Vec<(String, String, Action)> = key_bindings
+        .search(&canvas.command_palette_query, ShortcutContext::ModuleCanvas)
+        .into_iter()
+        .filter(|s| s.context == ShortcutContext::ModuleCanvas)
+        .filter(|s| s.action != Action::ShowCommandPalette)
+        .map(|s| {
+            (
+                s.description.clone(),
+                s.to_shortcut_string(),
+                s.action.clone(),
+            )
+        })
+        .collect();
+
+    let query = canvas.command_palette_query.to_lowercase();
+    let mut modules = manager.list_modules();
+    modules.sort_by_key(|m| m.id);
+    for module in modules {
+        if Some(module.id) == canvas.active_module_id {
+            continue;
+        }
+        let label = format!("Jump to module {}", module.name);
+        if query
+            .split_whitespace()
+            .all(|term| label.to_lowercase().contains(term))
+        {
+            entries.push((label, String::new(), Action::JumpToModule(module.id)));
+        }
+    }
+
+    if entries.is_empty() {
+        canvas.command_palette_selected = 0;
+    } else if canvas.command_palette_selected >= entries.len() {
+        canvas.command_palette_selected = entries.len() - 1;
+    }
+
+    let mut commit = false;
+    let mut close = false;
+    if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
+        if !entries.is_empty() {
+            canvas.command_palette_selected = (canvas.command_palette_selected + 1) % entries.len();
+        }
+    } else if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
+        if !entries.is_empty() {
+            canvas.command_palette_selected = canvas
+                .command_palette_selected
+                .checked_sub(1)
+                .unwrap_or(entries.len() - 1);
+        }
+    } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        commit = true;
+    } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+        close = true;
+    }
+
+    let popup_width = 360.0;
+    let popup_pos = egui::pos2(
+        canvas_rect.center().x - popup_width / 2.0,
+        canvas_rect.min.y + 50.0,
+    );
+    egui::Area::new("command_palette_popup".into())
+        .fixed_pos(popup_pos)
+        .order(egui::Order::Foreground)
+        .constrain(true)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::menu(ui.style()).show(ui, |ui| {
+                ui.set_width(popup_width);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut canvas.command_palette_query)
+                        .hint_text("Type a command...")
+                        .lock_focus(true)
+                        .desired_width(f32::INFINITY),
+                );
+                if !response.has_focus() {
+                    response.request_focus();
+                }
+                ui.separator();
+                if entries.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No matching commands.")
+                            .weak()
+                            .italics(),
+                    );
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (i, (label, shortcut, _)) in entries.iter().enumerate() {
+                            let is_selected = i == canvas.command_palette_selected;
+                            let response = ui
+                                .horizontal(|ui| {
+                                    let response = ui.selectable_label(is_selected, label);
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| ui.label(egui::RichText::new(shortcut).weak()),
+                                    );
+                                    response
+                                })
+                                .inner;
+                            if response.clicked() {
+                                canvas.command_palette_selected = i;
+                                commit = true;
+                            }
+                            if is_selected {
+                                response.scroll_to_me(Some(egui::Align::Center));
+                            }
+                        }
+                    });
+            });
+        });
+
+    let chosen = if commit {
+        close = true;
+        entries
+            .into_iter()
+            .nth(canvas.command_palette_selected)
+            .map(|(_, _, action)| action)
+    } else {
+        None
+    };
+
+    if close {
+        canvas.show_command_palette = false;
+        canvas.command_palette_query.clear();
+    }
+    chosen
+}
@@ -0,0 +1,54 @@
+use super::super::{commands, state::ModuleCanvas};
+use egui::{Color32, Pos2, Rect, Stroke, Vec2};
+use mapmap_core::module::MapFlowModule;
+use std::collections::BTreeMap;
+
+/// Draw a labelled frame behind every group of parts
+pub fn draw_part_groups(
+    canvas: &ModuleCanvas,
+    painter: &egui::Painter,
+    canvas_rect: Rect,
+    module: &MapFlowModule,
+) {
+    let mut groups: BTreeMap<&str, Rect> = BTreeMap::new();
+    for part in &module.parts {
+        let Some(group) = part.group.as_deref() else {
+            continue;
+        };
+        let (w, h) = commands::part_size(part);
+        let min = Pos2::new(part.position.0, part.position.1);
+        let rect = Rect::from_min_size(min, Vec2::new(w, h));
+        groups
+            .entry(group)
+            .and_modify(|r| *r = r.union(rect))
+            .or_insert(rect);
+    }
+
+    let offset = canvas.pan_offset + canvas_rect.min.to_vec2();
+    for (name, rect) in groups {
+        let screen_rect = Rect::from_min_max(
+            rect.min * canvas.zoom + offset,
+            rect.max * canvas.zoom + offset,
+        )
+        .expand(16.0 * canvas.zoom);
+
+        painter.rect_filled(
+            screen_rect,
+            6.0,
+            Color32::from_rgba_unmultiplied(80, 120, 200, 20),
+        );
+        painter.rect_stroke(
+            screen_rect,
+            6.0,
+            Stroke::new(1.0, Color32::from_rgb(80, 120, 200)),
+            egui::StrokeKind::Middle,
+        );
+        painter.text(
+            screen_rect.left_top() + Vec2::new(6.0, -4.0) * canvas.zoom,
+            egui::Align2::LEFT_BOTTOM,
+            name,
+            egui::FontId::proportional(12.0 * canvas.zoom),
+            Color32::from_rgb(140, 170, 230),
+        );
+    }
+}
@@ -1,6 +1,8 @@
 pub mod add_node;
+pub mod command_palette;
 pub mod connections;
 pub mod grid;
+pub mod groups;
 pub mod mini_map;
 pub mod part;
 pub mod presets;
@@ -8,8 +10,10 @@ pub mod quick_create;
 pub mod search;
 
 pub use add_node::*;
+pub use command_palette::*;
 pub use connections::*;
 pub use grid::*;
+pub use groups::*;
 pub use mini_map::*;
 pub use part::*;
 pub use presets::*;
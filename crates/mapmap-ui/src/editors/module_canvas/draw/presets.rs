@@ -65,6 +65,8 @@ pub fn draw_presets_popup(
                                         outputs,
                                         link_data: mapmap_core::module::NodeLinkData::default(),
                                         trigger_targets: std::collections::HashMap::new(),
+                                        bypass: false,
+                                        group: None,
                                     });
                                     part_ids.push(id);
                                 }
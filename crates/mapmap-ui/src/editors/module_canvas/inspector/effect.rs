@@ -171,7 +171,7 @@ pub fn render_effect_ui(ui: &mut Ui, mod_type: &mut ModulizerType, part_id: Modu
                     .text("Opacity"),
             );
         }
-        ModulizerType::AudioReactive { source } => {
+        ModulizerType::AudioReactive { source, .. } => {
             ui.label("\u{1F50A} Audio Reactive");
             ui.horizontal(|ui| {
                 ui.label("Source:");
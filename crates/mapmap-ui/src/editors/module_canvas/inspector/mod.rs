@@ -1,21 +1,29 @@
 use super::mesh;
 use super::state::{LayerInspectorViewMode, ModuleCanvas};
-use super::types::MediaPlaybackCommand;
+use super::types::{CalibrationModel, MediaPlaybackCommand};
 use crate::theme::colors;
 use crate::widgets::{styled_drag_value, styled_slider};
 use crate::UIAction;
 use egui::{Color32, ProgressBar, Sense, Stroke, Ui, Vec2};
+use mapmap_core::color_match::{PatchMeasurements, DEFAULT_PATCH_LEVELS};
 use mapmap_core::module::{
-    BevyCameraMode, BlendModeType, EffectType, HueMappingMode, LayerType, MapFlowModule, MaskShape,
-    MaskType, ModuleId, ModulePart, ModulePartId, ModulePartType, ModulizerType, OutputType,
-    SourceType, TriggerMappingMode, TriggerTarget, TriggerType,
+    BevyCameraMode, BezierMask, BlendModeType, DmxProtocol, EffectType, HueMappingMode, LayerType,
+    LedStripLayout, LedStripPatch, LightBackend, LightEffectKind, LightFixtureLayout,
+    LightFixturePatch, LightMappingMode, MapFlowModule, MaskOperation, MaskPoint, MaskShape,
+    MaskType, MeshType, ModuleId, ModulePart, ModulePartId, ModulePartType, ModulizerType,
+    OutputType, SourceType, TriggerMappingMode, TriggerTarget, TriggerType, WledProtocol,
 };
+use mapmap_core::projection3d::{PointCorrespondence, MIN_CORRESPONDENCES};
+use mapmap_core::structured_light::GrayCodePattern;
+use mapmap_core::ProjectorCalibration;
 use std::collections::HashSet;
 
 #[derive(Debug, Clone, Default)]
 pub struct InspectorPreviewContext {
     pub output_ids: Vec<u64>,
     pub upstream_source_part_ids: Vec<ModulePartId>,
+    /// First 3D scene model feeding the part, for the projector calibration picker
+    pub model_path: Option<String>,
 }
 
 pub fn build_preview_context(
@@ -36,6 +44,7 @@ pub fn build_preview_context(
     InspectorPreviewContext {
         output_ids,
         upstream_source_part_ids: source_ids,
+        model_path: find_upstream_model_path(module, part_id, &mut HashSet::new()),
     }
 }
 
@@ -93,6 +102,39 @@ fn collect_upstream_source_ids(
     }
 }
 
+fn find_upstream_model_path(
+    module: &MapFlowModule,
+    part_id: ModulePartId,
+    visited: &mut HashSet<ModulePartId>,
+) -> Option<String> {
+    if !visited.insert(part_id) {
+        return None;
+    }
+
+    let mesh = module
+        .parts
+        .iter()
+        .find(|part| part.id == part_id)
+        .and_then(|part| match &part.part_type {
+            ModulePartType::Mesh(mesh) => Some(mesh),
+            ModulePartType::Layer(
+                LayerType::Single { mesh, .. } | LayerType::Group { mesh, .. },
+            ) => Some(mesh),
+            _ => None,
+        });
+    if let Some(MeshType::Model3D { path }) = mesh {
+        if !path.is_empty() {
+            return Some(path.clone());
+        }
+    }
+
+    module
+        .connections
+        .iter()
+        .filter(|conn| conn.to_part == part_id)
+        .find_map(|conn| find_upstream_model_path(module, conn.from_part, visited))
+}
+
 /// Sets default parameters for a given effect type
 pub fn set_default_effect_params(
     effect_type: EffectType,
@@ -319,9 +361,29 @@ pub fn render_inspector_for_part(
                             ui.label("🥁 Beat Sync");
                             ui.label("Triggers on BPM beat.");
                         }
-                        TriggerType::AudioFFT { band: _band, threshold, output_config } => {
+                        TriggerType::BeatGrid { division } => {
+                            ui.label("\u{1F4D0} Beat Grid");
+                            ui.label("Triggers on bar and phrase boundaries of the beat grid.");
+                            egui::ComboBox::from_id_salt("beat_grid_division")
+                                .selected_text(division.name())
+                                .show_ui(ui, |ui| {
+                                    for option in mapmap_core::audio::beat_grid::BeatDivision::all() {
+                                        ui.selectable_value(division, option, option.name());
+                                    }
+                                });
+                        }
+                        TriggerType::AudioFFT { band: _band, threshold, output_config, analyzer } => {
                             ui.label("\u{1F50A} Audio FFT");
-                            ui.label("Outputs 9 frequency bands, plus volume and beat.");
+                            ui.label("Outputs 9 frequency bands, volume, beat, onsets, spectral shape, chroma and key.");
+                            ui.horizontal(|ui| {
+                                ui.label("Input:");
+                                egui::ComboBox::from_id_salt("audio_fft_analyzer").selected_text(analyzer.as_deref().unwrap_or("Main")).show_ui(ui, |ui| {
+                                    if ui.selectable_label(analyzer.is_none(), "Main").clicked() { *analyzer = None; }
+                                    for name in &canvas.audio_analyzer_names {
+                                        if ui.selectable_label(analyzer.as_deref() == Some(name.as_str()), name).clicked() { *analyzer = Some(name.clone()); }
+                                    }
+                                });
+                            });
                             ui.add(
                                 egui::Slider::new(threshold, 0.0..=1.0)
                                     .text("Threshold"),
@@ -333,6 +395,10 @@ pub fn render_inspector_for_part(
                             ui.checkbox(&mut output_config.bpm_output, "⏱️ BPM");
                             ui.checkbox(&mut output_config.volume_outputs, "\u{1F4CA} Volume (RMS, Peak)");
                             ui.checkbox(&mut output_config.frequency_bands, "\u{1F3B5} Frequency Bands (9)");
+                            ui.checkbox(&mut output_config.onset_outputs, "⚡ Onsets (overall + 9 bands)");
+                            ui.checkbox(&mut output_config.spectral_outputs, "\u{1F308} Spectral Shape (Centroid, Flatness, Rolloff)");
+                            ui.checkbox(&mut output_config.chroma_outputs, "\u{1F3BC} Chroma (12 pitch classes)");
+                            ui.checkbox(&mut output_config.key_output, "\u{1F511} Musical Key");
 
                             ui.separator();
                             ui.collapsing("\u{1F504} Invert Signals (NOT Logic)", |ui| {
@@ -372,6 +438,30 @@ pub fn render_inspector_for_part(
                                     toggle_invert(ui, "Brilliance Out", "Brilliance (6-12kHz)");
                                     toggle_invert(ui, "Air Out", "Air (12-20kHz)");
                                 }
+                                if output_config.onset_outputs {
+                                    ui.label("Onsets:");
+                                    toggle_invert(ui, "Onset Out", "⚡ Onset Out");
+                                    for band in mapmap_core::module::AUDIO_BAND_NAMES {
+                                        let name = format!("{} Onset", band);
+                                        toggle_invert(ui, &name, &name);
+                                    }
+                                }
+                                if output_config.spectral_outputs {
+                                    ui.label("Spectral:");
+                                    toggle_invert(ui, "Centroid Out", "Centroid Out");
+                                    toggle_invert(ui, "Flatness Out", "Flatness Out");
+                                    toggle_invert(ui, "Rolloff Out", "Rolloff Out");
+                                }
+                                if output_config.chroma_outputs {
+                                    ui.label("Chroma:");
+                                    for pitch_class in 0..12 {
+                                        let name = format!("Chroma {}", mapmap_core::audio::features::pitch_class_name(pitch_class));
+                                        toggle_invert(ui, &name, &name);
+                                    }
+                                }
+                                if output_config.key_output {
+                                    toggle_invert(ui, "Key Out", "\u{1F511} Key Out");
+                                }
                             });
 
                             ui.label(
@@ -630,6 +720,9 @@ pub fn render_inspector_for_part(
                                             actions.push(UIAction::PickMediaFile(module_id, part_id, "".to_string()));
                                         }
                                     });
+                                    if ui.button("\u{1F50A} Analyze Soundtrack").on_hover_text("Feed this clip's audio to the audio analyzer, in sync with playback").clicked() {
+                                        actions.push(UIAction::UseVideoAudio(module_id, part_id));
+                                    }
                                 });
                             }
 
@@ -1010,6 +1103,10 @@ pub fn render_inspector_for_part(
                             ui.add(egui::Slider::new(angle, 0.0..=360.0).text("Angle Â°"));
                             ui.add(egui::Slider::new(softness, 0.0..=1.0).text("Softness"));
                         }
+                        MaskType::Bezier(bezier) => {
+                            ui.label("\u{270F} Bezier Mask");
+                            render_bezier_mask(ui, bezier, part_id);
+                        }
                     }
                 }
                 ModulePartType::Modulizer(mod_type) => {
@@ -1079,8 +1176,17 @@ pub fn render_inspector_for_part(
                             });
                             ui.add(egui::Slider::new(&mut 1.0_f32, 0.0..=1.0).text("Opacity"));
                         }
-                        ModulizerType::AudioReactive { source } => {
+                        ModulizerType::AudioReactive { source, analyzer, scale_opacity } => {
                             ui.label("\u{1F50A} Audio Reactive");
+                            ui.horizontal(|ui| {
+                                ui.label("Input:");
+                                egui::ComboBox::from_id_salt("audio_analyzer").selected_text(analyzer.as_deref().unwrap_or("Main")).show_ui(ui, |ui| {
+                                    if ui.selectable_label(analyzer.is_none(), "Main").clicked() { *analyzer = None; }
+                                    for name in &canvas.audio_analyzer_names {
+                                        if ui.selectable_label(analyzer.as_deref() == Some(name.as_str()), name).clicked() { *analyzer = Some(name.clone()); }
+                                    }
+                                });
+                            });
                             ui.horizontal(|ui| {
                                 ui.label("Source:");
                                 egui::ComboBox::from_id_salt("audio_source").selected_text(source.as_str()).show_ui(ui, |ui| {
@@ -1096,6 +1202,7 @@ pub fn render_inspector_for_part(
                                     if ui.selectable_label(source == "BPM", "BPM").clicked() { *source = "BPM".to_string(); }
                                 });
                             });
+                            ui.checkbox(scale_opacity, "Scale layer opacity").on_hover_text("Multiply the layer opacity by the source level");
                             ui.add(egui::Slider::new(&mut 0.1_f32, 0.0..=1.0).text("Smoothing"));
                         }
                     }
@@ -1133,7 +1240,7 @@ pub fn render_inspector_for_part(
                 ModulePartType::Output(output) => {
                     ui.label("Output:");
                     match output {
-                        OutputType::Projector { id, name, hide_cursor, target_screen, show_in_preview_panel, extra_preview_window, ndi_enabled: _ndi_enabled, ndi_stream_name: _ndi_stream_name, .. } => {
+                        OutputType::Projector { id, name, hide_cursor, target_screen, show_in_preview_panel, extra_preview_window, ndi_enabled: _ndi_enabled, ndi_stream_name: _ndi_stream_name, calibration_3d, color_measurements, output_width, output_height, .. } => {
                             ui.label("📽️ Projector Output");
                             ui.horizontal(|ui| { ui.label("Output #:"); ui.add(egui::DragValue::new(id).range(1..=8)); });
                             ui.horizontal(|ui| { ui.label("Name:"); ui.text_edit_singleline(name); });
@@ -1144,6 +1251,9 @@ pub fn render_inspector_for_part(
                             ui.separator(); ui.label("\u{1F4E1} NDI Broadcast");
                             #[cfg(feature = "ndi")] { ui.checkbox(_ndi_enabled, "Enable NDI Output"); if *_ndi_enabled { ui.horizontal(|ui| { ui.label("Stream Name:"); ui.text_edit_singleline(_ndi_stream_name); }); if _ndi_stream_name.is_empty() { ui.small(format!("Default: {}", name)); } } }
                             #[cfg(not(feature = "ndi"))] { ui.label("NDI feature disabled in build"); }
+                            ui.separator(); egui::CollapsingHeader::new("\u{1F9CA} 3D Calibration").id_salt(("calibration_3d", part_id)).show(ui, |ui| render_projector_calibration(ui, calibration_3d, &mut canvas.calibration_model, preview_context.model_path.as_deref(), (*output_width, *output_height)));
+                            egui::CollapsingHeader::new("\u{1F4F7} Structured Light").id_salt(("structured_light", part_id)).show(ui, |ui| render_structured_light(ui, actions, module_id, *id, (*output_width, *output_height)));
+                            egui::CollapsingHeader::new("\u{1F3A8} Color Matching").id_salt(("color_matching", part_id)).show(ui, |ui| render_color_matching(ui, actions, *id, color_measurements));
                         }
                         #[cfg(feature = "ndi")]
                         OutputType::NdiOutput { name } => { ui.label("\u{1F4E1} NDI Output"); ui.horizontal(|ui| { ui.label("Stream Name:"); ui.text_edit_singleline(name); }); }
@@ -1163,6 +1273,13 @@ pub fn render_inspector_for_part(
                             ui.collapsing("\u{1F3AD} Area & Mode", |ui| { ui.label("Entertainment Area:"); ui.text_edit_singleline(entertainment_area); ui.separator(); ui.label("Mapping Mode:"); ui.radio_value(mapping_mode, HueMappingMode::Ambient, "Ambient (Average Color)"); ui.radio_value(mapping_mode, HueMappingMode::Spatial, "Spatial (2D Map)"); ui.radio_value(mapping_mode, HueMappingMode::Trigger, "Trigger (Strobe/Pulse)"); });
                             if *mapping_mode == HueMappingMode::Spatial { ui.collapsing("🗺️ Spatial Editor", |ui| { ui.label("Position lamps in the virtual room:"); mesh::render_hue_spatial_editor(ui, lamp_positions); }); }
                         }
+                        OutputType::Light { name, backend, lamp_positions, mapping_mode, effect, color } => {
+                            ui.label(format!("\u{1F4A1} Light Output ({})", backend.label())); ui.separator();
+                            ui.horizontal(|ui| { ui.label("Name:"); ui.text_edit_singleline(name); });
+                            ui.collapsing("⚙️ Device", |ui| render_light_backend(ui, backend));
+                            ui.collapsing("\u{1F3AD} Mode", |ui| { ui.radio_value(mapping_mode, LightMappingMode::Spatial, "Spatial (2D Map)"); ui.radio_value(mapping_mode, LightMappingMode::Effect, "Audio Effect"); if *mapping_mode == LightMappingMode::Effect { egui::ComboBox::from_id_salt("light_effect").selected_text(effect.label()).show_ui(ui, |ui| { for kind in LightEffectKind::ALL { ui.selectable_value(effect, kind, kind.label()); } }); ui.horizontal(|ui| { ui.label("Color:"); ui.color_edit_button_rgb(color); }); } });
+                            ui.collapsing("🗺️ Spatial Editor", |ui| { ui.label("Position lamps in the virtual room; unplaced lamps keep their device position."); if ui.button("Place Patched Lamps").clicked() { let ids = light_lamp_ids(backend); for (i, id) in ids.iter().enumerate() { lamp_positions.entry(id.clone()).or_insert(((i as f32 + 0.5) / ids.len() as f32, 0.5)); } } mesh::render_hue_spatial_editor(ui, lamp_positions); });
+                        }
                     }
                 }
                 ModulePartType::Hue(_) => { ui.label("Hue Node Configuration"); }
@@ -1170,6 +1287,983 @@ pub fn render_inspector_for_part(
         });
 }
 
+fn render_light_backend(ui: &mut Ui, backend: &mut LightBackend) {
+    egui::ComboBox::from_id_salt("light_backend")
+        .selected_text(backend.label())
+        .show_ui(ui, |ui| {
+            for preset in LightBackend::presets() {
+                let selected = std::mem::discriminant(backend) == std::mem::discriminant(&preset);
+                if ui.selectable_label(selected, preset.label()).clicked() && !selected {
+                    *backend = preset;
+                }
+            }
+        });
+    ui.separator();
+
+    match backend {
+        LightBackend::Hue => {
+            ui.label("Uses the bridge and entertainment area from Settings → Philips Hue.");
+        }
+        LightBackend::Dmx {
+            protocol,
+            target,
+            universe,
+            fixtures,
+        } => {
+            ui.horizontal(|ui| {
+                ui.radio_value(protocol, DmxProtocol::ArtNet, "Art-Net");
+                ui.radio_value(protocol, DmxProtocol::Sacn, "sACN");
+            });
+            if *protocol == DmxProtocol::ArtNet {
+                ui.horizontal(|ui| {
+                    ui.label("Target:");
+                    ui.text_edit_singleline(target);
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Universe:");
+                let min = if *protocol == DmxProtocol::Sacn { 1 } else { 0 };
+                ui.add(egui::DragValue::new(universe).range(min..=32767));
+            });
+
+            ui.separator();
+            ui.label("Fixtures:");
+            let mut remove = None;
+            for (index, fixture) in fixtures.iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut fixture.id).desired_width(80.0));
+                        ui.label("@");
+                        ui.add(egui::DragValue::new(&mut fixture.start_address).range(1..=512));
+                        egui::ComboBox::from_id_salt("layout")
+                            .selected_text(format!("{:?}", fixture.layout))
+                            .show_ui(ui, |ui| {
+                                for layout in [
+                                    LightFixtureLayout::Dimmer,
+                                    LightFixtureLayout::Rgb,
+                                    LightFixtureLayout::Rgba,
+                                    LightFixtureLayout::Rgbw,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut fixture.layout,
+                                        layout,
+                                        format!("{:?}", layout),
+                                    );
+                                }
+                            });
+                        if ui.small_button("\u{1F5D1}").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                });
+            }
+            if let Some(index) = remove {
+                fixtures.remove(index);
+            }
+            if ui.button("\u{2795} Add Fixture").clicked() {
+                let start_address = fixtures
+                    .iter()
+                    .map(|f| f.start_address + f.layout.channel_count())
+                    .max()
+                    .unwrap_or(1)
+                    .min(512);
+                fixtures.push(LightFixturePatch {
+                    id: format!("fixture-{}", fixtures.len() + 1),
+                    start_address,
+                    layout: LightFixtureLayout::Rgb,
+                });
+            }
+        }
+        LightBackend::Wled {
+            host,
+            led_count,
+            protocol,
+            strips,
+        } => {
+            ui.horizontal(|ui| {
+                ui.label("Host:");
+                ui.text_edit_singleline(host);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Protocol:");
+                for option in WledProtocol::ALL {
+                    ui.radio_value(protocol, option, option.label());
+                }
+            });
+            *led_count = (*led_count).min(protocol.max_leds());
+            ui.horizontal(|ui| {
+                ui.label("LEDs:");
+                ui.add(egui::DragValue::new(led_count).range(1..=protocol.max_leds()));
+            });
+
+            ui.separator();
+            ui.label("Strips:");
+            let mut remove = None;
+            let last_pixel = led_count.saturating_sub(1);
+            for (index, strip) in strips.iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut strip.id).desired_width(80.0));
+                        ui.label("@");
+                        ui.add(egui::DragValue::new(&mut strip.start_pixel).range(0..=last_pixel));
+                        let count = strip.layout.pixel_count();
+                        egui::ComboBox::from_id_salt("strip_layout")
+                            .selected_text(strip.layout.label())
+                            .show_ui(ui, |ui| {
+                                for layout in led_strip_layouts(count) {
+                                    let selected = layout.label() == strip.layout.label();
+                                    if ui.selectable_label(selected, layout.label()).clicked()
+                                        && !selected
+                                    {
+                                        strip.layout = layout;
+                                    }
+                                }
+                            });
+                        if ui.small_button("\u{1F5D1}").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                    render_led_strip_layout(ui, &mut strip.layout);
+                });
+            }
+            if let Some(index) = remove {
+                strips.remove(index);
+            }
+            if ui.button("\u{2795} Add Strip").clicked() {
+                let start_pixel = strips
+                    .iter()
+                    .map(|s| s.start_pixel.saturating_add(s.layout.pixel_count()))
+                    .max()
+                    .unwrap_or(0)
+                    .min(last_pixel);
+                strips.push(LedStripPatch {
+                    id: format!("strip-{}", strips.len() + 1),
+                    start_pixel,
+                    layout: LedStripLayout::Line {
+                        start: (0.0, 0.5),
+                        end: (1.0, 0.5),
+                        count: led_count.saturating_sub(start_pixel).max(1),
+                    },
+                });
+            }
+            if strips.is_empty() {
+                ui.label("Without strips the LEDs are lamps in a row.");
+            }
+        }
+        LightBackend::Nanoleaf { host, auth_token } => {
+            ui.horizontal(|ui| {
+                ui.label("Host:");
+                ui.text_edit_singleline(host);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Auth Token:");
+                ui.add(egui::TextEdit::singleline(auth_token).password(true));
+            });
+        }
+    }
+}
+
+/// One layout of every kind, covering the canvas with about `count` LEDs.
+fn led_strip_layouts(count: u32) -> [LedStripLayout; 3] {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as u32;
+    let rows = count.div_ceil(columns).max(1);
+    [
+        LedStripLayout::Line {
+            start: (0.0, 0.5),
+            end: (1.0, 0.5),
+            count,
+        },
+        LedStripLayout::Matrix {
+            top_left: (0.0, 0.0),
+            bottom_right: (1.0, 1.0),
+            columns,
+            rows,
+        },
+        LedStripLayout::Serpentine {
+            top_left: (0.0, 0.0),
+            bottom_right: (1.0, 1.0),
+            columns,
+            rows,
+        },
+    ]
+}
+
+/// Canvas coordinates and LED counts of one strip.
+fn render_led_strip_layout(ui: &mut Ui, layout: &mut LedStripLayout) {
+    let point = |ui: &mut Ui, label: &str, (x, y): &mut (f32, f32)| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(x).speed(0.01).range(0.0..=1.0));
+        ui.add(egui::DragValue::new(y).speed(0.01).range(0.0..=1.0));
+    };
+    match layout {
+        LedStripLayout::Line { start, end, count } => {
+            ui.horizontal(|ui| {
+                point(ui, "From", start);
+                point(ui, "To", end);
+            });
+            ui.horizontal(|ui| {
+                ui.label("LEDs:");
+                ui.add(egui::DragValue::new(count).range(1..=u16::MAX as u32));
+            });
+        }
+        LedStripLayout::Matrix {
+            top_left,
+            bottom_right,
+            columns,
+            rows,
+        }
+        | LedStripLayout::Serpentine {
+            top_left,
+            bottom_right,
+            columns,
+            rows,
+        } => {
+            ui.horizontal(|ui| {
+                point(ui, "Top Left", top_left);
+                point(ui, "Bottom Right", bottom_right);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Columns:");
+                ui.add(egui::DragValue::new(columns).range(1..=256));
+                ui.label("Rows:");
+                ui.add(egui::DragValue::new(rows).range(1..=256));
+            });
+        }
+    }
+}
+
+/// Point correspondences and solve status of a projector's 3D calibration.
+///
+/// Points are picked by clicking the model (seen from the front) and the
+/// projector image; the model feeding the output loads in the background.
+fn render_projector_calibration(
+    ui: &mut Ui,
+    calibration: &mut ProjectorCalibration,
+    model: &mut Option<CalibrationModel>,
+    model_path: Option<&str>,
+    (width, height): (u32, u32),
+) {
+    if model.as_ref().map(|m| m.path.as_str()) != model_path {
+        *model = model_path.map(CalibrationModel::load);
+    }
+    if let Some(model) = model.as_mut() {
+        model.poll();
+        if model.rx.is_some() {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    ui.label(
+        egui::RichText::new(
+            "Select a point, then click it on the model and where it hits the projector image.",
+        )
+        .weak()
+        .small(),
+    );
+    let selected_id = ui.id().with("calibration_selected");
+    let mut selected = ui
+        .data(|d| d.get_temp::<Option<usize>>(selected_id))
+        .flatten()
+        .filter(|index| *index < calibration.correspondences.len());
+
+    let mut remove = None;
+    for (index, point) in calibration.correspondences.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .selectable_label(selected == Some(index), format!("{}.", index + 1))
+                .clicked()
+            {
+                selected = Some(index);
+            }
+            for value in [&mut point.world.x, &mut point.world.y, &mut point.world.z] {
+                ui.add(egui::DragValue::new(value).speed(0.01).max_decimals(3));
+            }
+            ui.label("\u{2192}");
+            for value in [&mut point.image.x, &mut point.image.y] {
+                ui.add(egui::DragValue::new(value).speed(0.001).range(0.0..=1.0));
+            }
+            if ui.small_button("\u{1F5D1}").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        calibration.correspondences.remove(index);
+        selected = None;
+    }
+    if ui.button("\u{2795} Add Point").clicked() {
+        calibration.correspondences.push(PointCorrespondence::new(
+            glam::Vec3::ZERO,
+            glam::Vec2::splat(0.5),
+        ));
+        selected = Some(calibration.correspondences.len() - 1);
+    }
+
+    let size = ui.available_width().min(220.0);
+    let picked_world = match model.as_ref().map(|m| &m.model) {
+        None => {
+            ui.label("No 3D model feeds this output: enter model points by hand.");
+            None
+        }
+        Some(None) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Loading model...");
+            });
+            None
+        }
+        Some(Some(Err(error))) => {
+            ui.colored_label(Color32::RED, format!("Model not loaded: {}", error));
+            None
+        }
+        Some(Some(Ok(scene))) => {
+            render_calibration_model_view(ui, scene, calibration, selected, size)
+        }
+    };
+    let aspect = if width > 0 && height > 0 {
+        height as f32 / width as f32
+    } else {
+        9.0 / 16.0
+    };
+    let picked_image =
+        render_calibration_image_view(ui, calibration, selected, Vec2::new(size, size * aspect));
+
+    if picked_world.is_some() || picked_image.is_some() {
+        let index = *selected.get_or_insert_with(|| {
+            calibration.correspondences.push(PointCorrespondence::new(
+                glam::Vec3::ZERO,
+                glam::Vec2::splat(0.5),
+            ));
+            calibration.correspondences.len() - 1
+        });
+        let point = &mut calibration.correspondences[index];
+        if let Some(world) = picked_world {
+            point.world = world;
+        }
+        if let Some(image) = picked_image {
+            point.image = image;
+        }
+    }
+    ui.data_mut(|d| d.insert_temp(selected_id, selected));
+
+    ui.horizontal(|ui| {
+        ui.label("Near:");
+        ui.add(
+            egui::DragValue::new(&mut calibration.near)
+                .speed(0.01)
+                .range(0.0001..=calibration.far),
+        );
+        ui.label("Far:");
+        ui.add(
+            egui::DragValue::new(&mut calibration.far)
+                .speed(1.0)
+                .range(calibration.near..=100_000.0),
+        );
+    });
+
+    let error_id = ui.id().with("calibration_error");
+    ui.horizontal(|ui| {
+        let enough = calibration.correspondences.len() >= MIN_CORRESPONDENCES;
+        if ui
+            .add_enabled(enough, egui::Button::new("Solve"))
+            .on_disabled_hover_text(format!("Needs at least {} points", MIN_CORRESPONDENCES))
+            .clicked()
+        {
+            let error = calibration.solve().err().map(|e| e.to_string());
+            ui.data_mut(|d| d.insert_temp(error_id, error));
+        }
+        if calibration.solved.is_some() && ui.button("Reset").clicked() {
+            calibration.solved = None;
+        }
+    });
+    if let Some(error) = ui
+        .data(|d| d.get_temp::<Option<String>>(error_id))
+        .flatten()
+    {
+        ui.colored_label(Color32::RED, error);
+    }
+    match &calibration.solved {
+        Some(solved) => {
+            ui.label(format!(
+                "Solved: error {:.4}, focal {:.2} x {:.2}",
+                solved.reprojection_error, solved.fx, solved.fy
+            ));
+            let position = solved.position();
+            ui.label(format!(
+                "Projector at ({:.2}, {:.2}, {:.2})",
+                position.x, position.y, position.z
+            ));
+        }
+        None => {
+            ui.label("Not calibrated: 3D models are shown from the front.");
+        }
+    }
+}
+
+/// The model seen from the front; a click returns the nearest vertex under the cursor.
+fn render_calibration_model_view(
+    ui: &mut Ui,
+    scene: &mapmap_core::SceneModel,
+    calibration: &ProjectorCalibration,
+    selected: Option<usize>,
+    size: f32,
+) -> Option<glam::Vec3> {
+    const MAX_DRAWN_VERTICES: usize = 4096;
+    const PICK_RADIUS: f32 = 8.0;
+
+    let (response, painter) = ui.allocate_painter(Vec2::splat(size), Sense::click());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, colors::DARKER_GREY);
+    let view = scene.front_view_transform();
+    let to_screen = |world: glam::Vec3| {
+        let ndc = view.project_point3(world);
+        rect.min + Vec2::new((ndc.x + 1.0) * 0.5, (1.0 - ndc.y) * 0.5) * size
+    };
+
+    let stride = (scene.positions.len() / MAX_DRAWN_VERTICES).max(1);
+    for world in scene.positions.iter().step_by(stride) {
+        painter.circle_filled(to_screen(*world), 1.0, Color32::GRAY);
+    }
+    for (index, point) in calibration.correspondences.iter().enumerate() {
+        let color = if selected == Some(index) {
+            Color32::YELLOW
+        } else {
+            Color32::LIGHT_BLUE
+        };
+        let pos = to_screen(point.world);
+        painter.circle_stroke(pos, 4.0, Stroke::new(1.5, color));
+        painter.text(
+            pos + Vec2::new(5.0, -5.0),
+            egui::Align2::LEFT_BOTTOM,
+            (index + 1).to_string(),
+            egui::FontId::proportional(10.0),
+            color,
+        );
+    }
+
+    // Of the vertices under the cursor, the one closest to the viewer (+Z)
+    let click = response
+        .interact_pointer_pos()
+        .filter(|_| response.clicked())?;
+    scene
+        .positions
+        .iter()
+        .filter(|world| to_screen(**world).distance(click) <= PICK_RADIUS)
+        .max_by(|a, b| a.z.total_cmp(&b.z))
+        .copied()
+}
+
+/// The projector image with the picked points and, once solved, where the
+/// solution projects them; a click returns the normalized image position.
+fn render_calibration_image_view(
+    ui: &mut Ui,
+    calibration: &ProjectorCalibration,
+    selected: Option<usize>,
+    size: Vec2,
+) -> Option<glam::Vec2> {
+    let (response, painter) = ui.allocate_painter(size, Sense::click());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, Color32::BLACK);
+    painter.rect_stroke(
+        rect,
+        0.0,
+        Stroke::new(1.0, Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+    let to_screen = |image: glam::Vec2| rect.min + Vec2::new(image.x * size.x, image.y * size.y);
+
+    for (index, point) in calibration.correspondences.iter().enumerate() {
+        let color = if selected == Some(index) {
+            Color32::YELLOW
+        } else {
+            Color32::LIGHT_BLUE
+        };
+        let pos = to_screen(point.image);
+        if let Some(projected) = calibration
+            .solved
+            .as_ref()
+            .and_then(|solved| solved.project(point.world))
+        {
+            painter.line_segment([pos, to_screen(projected)], Stroke::new(1.0, Color32::RED));
+        }
+        painter.circle_stroke(pos, 4.0, Stroke::new(1.5, color));
+        painter.text(
+            pos + Vec2::new(5.0, -5.0),
+            egui::Align2::LEFT_BOTTOM,
+            (index + 1).to_string(),
+            egui::FontId::proportional(10.0),
+            color,
+        );
+    }
+
+    let click = response
+        .interact_pointer_pos()
+        .filter(|_| response.clicked())?;
+    let image = glam::Vec2::new(
+        (click.x - rect.min.x) / size.x,
+        (click.y - rect.min.y) / size.y,
+    );
+    Some(image.clamp(glam::Vec2::ZERO, glam::Vec2::ONE))
+}
+
+/// Pattern projection and camera calibration of a projector output.
+fn render_structured_light(
+    ui: &mut Ui,
+    actions: &mut Vec<UIAction>,
+    module_id: ModuleId,
+    output_id: u64,
+    (width, height): (u32, u32),
+) {
+    ui.label(
+        egui::RichText::new(
+            "Capture every pattern frame with a fixed camera and your camera software, one output_<id> folder per projector.",
+        )
+        .weak()
+        .small(),
+    );
+    let frame_count =
+        (width > 0 && height > 0).then(|| GrayCodePattern::new(width, height).frame_count());
+    let frame_id = ui.id().with("pattern_frame");
+    let shown = ui.data(|d| d.get_temp::<Option<usize>>(frame_id)).flatten();
+    let mut frame = shown;
+    ui.horizontal(|ui| match shown {
+        None => {
+            if ui.button("\u{25B6} Show Patterns").clicked() {
+                frame = Some(0);
+            }
+        }
+        Some(index) => {
+            if ui
+                .add_enabled(index > 0, egui::Button::new("\u{23EE}"))
+                .clicked()
+            {
+                frame = Some(index - 1);
+            }
+            ui.label(match frame_count {
+                Some(count) => format!("Frame {} / {}", index + 1, count),
+                None => format!("Frame {}", index + 1),
+            });
+            let has_next = frame_count.map_or(true, |count| index + 1 < count);
+            if ui
+                .add_enabled(has_next, egui::Button::new("\u{23ED}"))
+                .clicked()
+            {
+                frame = Some(index + 1);
+            }
+            if ui.button("\u{23F9} Stop").clicked() {
+                frame = None;
+            }
+        }
+    });
+    if frame != shown {
+        ui.data_mut(|d| d.insert_temp(frame_id, frame));
+        actions.push(UIAction::ShowCalibrationPattern(output_id, frame));
+    }
+    if frame_count.is_none() {
+        ui.small("Patterns match the output window size.");
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button("\u{1F4BE} Export Patterns").clicked() {
+            actions.push(UIAction::ExportCalibrationPatterns(output_id));
+        }
+        if ui
+            .button("\u{1F4C2} Calibrate from Captures")
+            .on_hover_text("Replaces the meshes of the layers feeding each projector")
+            .clicked()
+        {
+            actions.push(UIAction::RunStructuredLightCalibration(module_id));
+        }
+    });
+}
+
+/// Outline editor of a bezier mask: anchors and tangent handles are dragged
+/// on a preview of the content area, keyframes store the outline over time.
+fn render_bezier_mask(ui: &mut Ui, mask: &mut BezierMask, part_id: ModulePartId) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(("mask_operation", part_id))
+            .selected_text(format!("{:?}", mask.operation))
+            .show_ui(ui, |ui| {
+                for operation in [
+                    MaskOperation::Add,
+                    MaskOperation::Subtract,
+                    MaskOperation::Intersect,
+                ] {
+                    ui.selectable_value(&mut mask.operation, operation, format!("{:?}", operation));
+                }
+            });
+        ui.checkbox(&mut mask.inverted, "Invert");
+    });
+
+    let size = ui.available_width().min(220.0);
+    let (response, painter) = ui.allocate_painter(Vec2::splat(size), Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, colors::DARKER_GREY);
+    let to_screen = |p: (f32, f32)| rect.min + Vec2::new(p.0, p.1) * size;
+    let offset = |p: (f32, f32), d: (f32, f32)| (p.0 + d.0, p.1 + d.1);
+
+    let count = mask.points.len();
+    for (index, point) in mask.points.iter().enumerate() {
+        let next = &mask.points[(index + 1) % count];
+        painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+            [
+                to_screen(point.position),
+                to_screen(offset(point.position, point.handle_out)),
+                to_screen(offset(next.position, next.handle_in)),
+                to_screen(next.position),
+            ],
+            false,
+            Color32::TRANSPARENT,
+            Stroke::new(1.5, colors::CYAN_ACCENT),
+        ));
+    }
+
+    // Anchors first so handles on top of them stay reachable
+    for index in 0..count {
+        let point = &mut mask.points[index];
+        let anchor = to_screen(point.position);
+        let response = ui.interact(
+            egui::Rect::from_center_size(anchor, Vec2::splat(10.0)),
+            ui.id().with(("mask_anchor", part_id, index)),
+            Sense::drag(),
+        );
+        if response.dragged() {
+            let delta = response.drag_delta() / size;
+            point.position = (
+                (point.position.0 + delta.x).clamp(0.0, 1.0),
+                (point.position.1 + delta.y).clamp(0.0, 1.0),
+            );
+        }
+        if point.feather > 0.0 {
+            painter.circle_stroke(
+                anchor,
+                point.feather * 0.5 * size,
+                Stroke::new(1.0, colors::STROKE_GREY),
+            );
+        }
+        painter.circle_filled(anchor, 4.0, colors::MINT_ACCENT);
+
+        for (handle, which) in [(&mut point.handle_in, 0), (&mut point.handle_out, 1)] {
+            if *handle == (0.0, 0.0) {
+                continue;
+            }
+            let pos = to_screen(offset(point.position, *handle));
+            painter.line_segment([anchor, pos], Stroke::new(1.0, colors::STROKE_GREY));
+            let response = ui.interact(
+                egui::Rect::from_center_size(pos, Vec2::splat(8.0)),
+                ui.id().with(("mask_handle", part_id, index, which)),
+                Sense::drag(),
+            );
+            if response.dragged() {
+                let delta = response.drag_delta() / size;
+                *handle = (handle.0 + delta.x, handle.1 + delta.y);
+            }
+            painter.circle_filled(pos, 3.0, colors::WARN_COLOR);
+        }
+    }
+
+    ui.collapsing("Points", |ui| {
+        let mut remove = None;
+        for (index, point) in mask.points.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}", index + 1));
+                ui.add(
+                    egui::DragValue::new(&mut point.position.0)
+                        .speed(0.001)
+                        .range(0.0..=1.0)
+                        .prefix("X: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut point.position.1)
+                        .speed(0.001)
+                        .range(0.0..=1.0)
+                        .prefix("Y: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut point.feather)
+                        .speed(0.001)
+                        .range(0.0..=0.5)
+                        .prefix("F: "),
+                );
+                let mut smooth = point.handle_in != (0.0, 0.0) || point.handle_out != (0.0, 0.0);
+                if ui.checkbox(&mut smooth, "Smooth").changed() {
+                    (point.handle_in, point.handle_out) = if smooth {
+                        ((-0.05, 0.0), (0.05, 0.0))
+                    } else {
+                        ((0.0, 0.0), (0.0, 0.0))
+                    };
+                }
+                if count > 3 && ui.small_button("\u{2716}").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            mask.points.remove(index);
+        }
+        if let (Some(&first), Some(&last)) = (mask.points.first(), mask.points.last()) {
+            if ui.button("\u{2795} Add Point").clicked() {
+                // Between the last and the first point, closing the outline
+                let mut point = MaskPoint::corner(
+                    (first.position.0 + last.position.0) * 0.5,
+                    (first.position.1 + last.position.1) * 0.5,
+                );
+                point.feather = (first.feather + last.feather) * 0.5;
+                mask.points.push(point);
+            }
+        }
+    });
+
+    ui.collapsing("Animation", |ui| {
+        let time_id = ui.id().with(("mask_key_time", part_id));
+        let mut time = ui.data(|d| d.get_temp::<f32>(time_id)).unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut time)
+                    .speed(0.05)
+                    .range(0.0..=3600.0)
+                    .suffix(" s"),
+            );
+            if ui
+                .button("\u{1F511} Set Keyframe")
+                .on_hover_text("Store the outline at this time")
+                .clicked()
+            {
+                mask.set_keyframe(time);
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(time_id, time));
+        ui.checkbox(&mut mask.loop_animation, "Loop");
+
+        let mut load = None;
+        let mut remove = None;
+        for (index, key) in mask.keyframes.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{:.2} s ({} points)", key.time, key.points.len()));
+                if ui
+                    .small_button("Edit")
+                    .on_hover_text("Load this outline for editing")
+                    .clicked()
+                {
+                    load = Some(index);
+                }
+                if ui.small_button("\u{2716}").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = load {
+            mask.points = mask.keyframes[index].points.clone();
+            ui.data_mut(|d| d.insert_temp(time_id, mask.keyframes[index].time));
+        }
+        if let Some(index) = remove {
+            mask.keyframes.remove(index);
+        }
+    });
+}
+
+/// Test patches and colorimeter readings of a projector output; the readings
+/// are stored with the output so they survive a restart.
+fn render_color_matching(
+    ui: &mut Ui,
+    actions: &mut Vec<UIAction>,
+    output_id: u64,
+    stored: &mut Option<PatchMeasurements>,
+) {
+    ui.label(
+        egui::RichText::new(
+            "Measure each patch with a colorimeter (CIE XYZ), or import its CSV export.",
+        )
+        .weak()
+        .small(),
+    );
+    let mut edited = stored
+        .clone()
+        .unwrap_or_else(|| PatchMeasurements::new(DEFAULT_PATCH_LEVELS));
+    let measurements = &mut edited;
+
+    let patch_id = ui.id().with("color_patch");
+    let entry_id = ui.id().with("color_entry");
+    let error_id = ui.id().with("color_error");
+    let shown = ui.data(|d| d.get_temp::<Option<usize>>(patch_id)).flatten();
+    let mut patch = shown;
+    let mut entry = ui
+        .data(|d| d.get_temp::<[f32; 3]>(entry_id))
+        .unwrap_or_default();
+    let mut error = ui
+        .data(|d| d.get_temp::<Option<String>>(error_id))
+        .flatten();
+
+    ui.horizontal(|ui| {
+        ui.label("Levels:");
+        let mut levels = measurements.levels;
+        ui.add(egui::DragValue::new(&mut levels).range(2..=9))
+            .on_hover_text("Patch levels per channel; changing it clears the measurements");
+        if levels != measurements.levels {
+            *measurements = PatchMeasurements::new(levels);
+            patch = patch.map(|_| 0);
+        }
+        ui.label(format!("({} patches)", measurements.patch_count()));
+    });
+
+    let count = measurements.patch_count();
+    let current = patch;
+    ui.horizontal(|ui| match current {
+        None => {
+            if ui.button("\u{25B6} Show Patches").clicked() {
+                patch = Some(0);
+            }
+        }
+        Some(index) => {
+            if ui
+                .add_enabled(index > 0, egui::Button::new("\u{23EE}"))
+                .clicked()
+            {
+                patch = Some(index - 1);
+            }
+            ui.label(format!("Patch {} / {}", index + 1, count));
+            if ui
+                .add_enabled(index + 1 < count, egui::Button::new("\u{23ED}"))
+                .clicked()
+            {
+                patch = Some(index + 1);
+            }
+            if ui.button("\u{23F9} Stop").clicked() {
+                patch = None;
+            }
+        }
+    });
+
+    if let Some(index) = patch {
+        let color = measurements.patch_color(index);
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(Vec2::new(24.0, 16.0), Sense::hover());
+            let to_u8 = |v: f32| (v * 255.0).round() as u8;
+            ui.painter().rect_filled(
+                rect,
+                2.0,
+                Color32::from_rgb(to_u8(color.x), to_u8(color.y), to_u8(color.z)),
+            );
+            ui.label(format!(
+                "RGB {} {} {}",
+                to_u8(color.x),
+                to_u8(color.y),
+                to_u8(color.z)
+            ));
+            if let Some(xyz) = measurements.values[index] {
+                ui.small(format!("measured {:.2} {:.2} {:.2}", xyz.x, xyz.y, xyz.z));
+            }
+        });
+        ui.horizontal(|ui| {
+            for (value, prefix) in entry.iter_mut().zip(["X: ", "Y: ", "Z: "]) {
+                ui.add(
+                    egui::DragValue::new(value)
+                        .speed(0.1)
+                        .range(0.0..=f32::MAX)
+                        .prefix(prefix),
+                );
+            }
+            if ui
+                .button("\u{2714} Record")
+                .on_hover_text("Store the measurement and show the next patch")
+                .clicked()
+            {
+                measurements.values[index] = Some(entry.into());
+                patch = Some((index + 1).min(count - 1));
+            }
+        });
+    }
+    if patch != shown {
+        ui.data_mut(|d| d.insert_temp(patch_id, patch));
+        let rgb = patch.map(|index| measurements.patch_color(index).to_array());
+        actions.push(UIAction::ShowColorPatch(output_id, rgb));
+    }
+    ui.data_mut(|d| d.insert_temp(entry_id, entry));
+
+    ui.label(format!(
+        "Measured {} / {}",
+        measurements.measured_count(),
+        count
+    ));
+    ui.horizontal(|ui| {
+        if ui.button("\u{1F4C2} Import CSV").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv", "txt"])
+                .pick_file()
+            {
+                let imported = std::fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| {
+                        PatchMeasurements::from_csv(&content, measurements.levels)
+                            .map_err(|e| e.to_string())
+                    });
+                match imported {
+                    Ok(imported) => {
+                        *measurements = imported;
+                        error = None;
+                    }
+                    Err(e) => error = Some(e),
+                }
+            }
+        }
+        if ui
+            .add_enabled(
+                measurements.measured_count() > 0,
+                egui::Button::new("\u{1F4BE} Save CSV"),
+            )
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .set_file_name(format!("output_{}_measurements.csv", output_id))
+                .save_file()
+            {
+                error = std::fs::write(&path, measurements.to_csv())
+                    .err()
+                    .map(|e| e.to_string());
+            }
+        }
+    });
+    if let Some(message) = &error {
+        ui.colored_label(colors::ERROR_COLOR, message);
+    }
+
+    if ui
+        .add_enabled(
+            measurements.is_complete(),
+            egui::Button::new("\u{1F3A8} Match Outputs"),
+        )
+        .on_hover_text("Solves a correction LUT for every fully measured output and applies it")
+        .on_disabled_hover_text("Measure every patch of this output first")
+        .clicked()
+    {
+        actions.push(UIAction::SolveColorMatch);
+    }
+
+    ui.data_mut(|d| d.insert_temp(error_id, error));
+    let changed = match stored.as_ref() {
+        Some(stored) => *stored != edited,
+        None => edited != PatchMeasurements::new(DEFAULT_PATCH_LEVELS),
+    };
+    if changed {
+        *stored = Some(edited);
+    }
+}
+
+/// Lamp IDs known from the output settings alone; Hue and Nanoleaf report
+/// theirs once connected.
+fn light_lamp_ids(backend: &LightBackend) -> Vec<String> {
+    match backend {
+        LightBackend::Dmx { fixtures, .. } => fixtures.iter().map(|f| f.id.clone()).collect(),
+        // Mapped strips are sampled LED by LED instead
+        LightBackend::Wled { strips, .. } if !strips.is_empty() => Vec::new(),
+        LightBackend::Wled { led_count, .. } => (1..=(*led_count).min(256))
+            .map(|n| format!("led-{}", n))
+            .collect(),
+        LightBackend::Hue | LightBackend::Nanoleaf { .. } => Vec::new(),
+    }
+}
+
 fn render_hue_bridge_discovery(canvas: &mut ModuleCanvas, ui: &mut Ui, current_ip: &mut String) {
     if ui.button("🔍 Discover Bridges").clicked() {
         let (tx, rx) = std::sync::mpsc::channel();
@@ -208,7 +208,7 @@ pub fn render_trigger_ui(canvas: &mut ModuleCanvas, ui: &mut Ui, trigger: &mut T
             ui.label("🥁 Beat Sync");
             ui.label("Triggers on BPM beat.");
         }
-        TriggerType::AudioFFT { band: _band, threshold, output_config } => {
+        TriggerType::AudioFFT { band: _band, threshold, output_config, .. } => {
             ui.label("\u{1F50A} Audio FFT");
             ui.label("Outputs 9 frequency bands, plus volume and beat.");
             ui.add(
@@ -1,5 +1,133 @@
+use crate::theme::colors;
 use egui::{Color32, Pos2, Sense, Stroke, Ui, Vec2};
 use mapmap_core::module::{LayerType, MeshType, ModulePart, ModulePartId, ModulePartType};
+use mapmap_core::{WarpInterpolation, WarpSurface};
+
+/// Load the control lattice of a bezier surface into the mesh editor;
+/// `false` if the mesh has no valid lattice
+fn load_warp_lattice(
+    mesh_editor: &mut crate::editors::mesh_editor::MeshEditor,
+    mesh: &MeshType,
+    scale: f32,
+) -> bool {
+    match mesh.warp_surface().filter(|surface| surface.is_valid()) {
+        Some(surface) => {
+            mesh_editor.set_from_mesh(&surface.control_mesh(), scale);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Write the edited control lattice back into a bezier surface
+fn store_warp_lattice(
+    mesh_editor: &crate::editors::mesh_editor::MeshEditor,
+    mesh: &mut MeshType,
+    scale: f32,
+) {
+    if let Some(mut surface) = mesh.warp_surface() {
+        let mut lattice = surface.control_mesh();
+        if mesh_editor.apply_to_mesh(&mut lattice, scale) {
+            surface.points = lattice.vertices.iter().map(|v| v.position).collect();
+            *mesh = MeshType::from_warp_surface(&surface);
+        }
+    }
+}
+
+/// Lattice size, interpolation and subdivision controls of a bezier surface
+fn render_warp_surface_settings(
+    ui: &mut Ui,
+    mesh: &mut MeshType,
+    last_mesh_edit_id: &mut Option<u64>,
+    id_salt: u64,
+) {
+    let Some(mut surface) = mesh.warp_surface() else {
+        return;
+    };
+    if !surface.is_valid() {
+        // Surfaces saved before control lattices existed have no points
+        ui.colored_label(
+            colors::WARN_COLOR,
+            "This Bezier surface has no valid control lattice.",
+        );
+        if ui.button("Reset to 4 x 4 Bezier").clicked() {
+            *mesh = MeshType::from_warp_surface(&WarpSurface::new(4, 4, WarpInterpolation::Bezier));
+            *last_mesh_edit_id = None; // Trigger resync
+        }
+        return;
+    }
+    let (mut patches_x, mut patches_y) = surface.patch_counts();
+    let mut interpolation = surface.interpolation;
+    let mut resample = false;
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Interpolation:");
+        egui::ComboBox::from_id_salt(format!("warp_interpolation_{}", id_salt))
+            .selected_text(match interpolation {
+                WarpInterpolation::Bezier => "Bezier",
+                WarpInterpolation::CatmullRom => "Catmull-Rom",
+            })
+            .show_ui(ui, |ui| {
+                resample |= ui
+                    .selectable_value(&mut interpolation, WarpInterpolation::Bezier, "Bezier")
+                    .changed();
+                resample |= ui
+                    .selectable_value(
+                        &mut interpolation,
+                        WarpInterpolation::CatmullRom,
+                        "Catmull-Rom",
+                    )
+                    .changed();
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Patches:");
+        resample |= ui
+            .add(egui::DragValue::new(&mut patches_x).range(1..=16))
+            .changed();
+        ui.label("x");
+        resample |= ui
+            .add(egui::DragValue::new(&mut patches_y).range(1..=16))
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Subdivisions:");
+        changed |= ui
+            .add(egui::DragValue::new(&mut surface.subdivisions).range(1..=32))
+            .on_hover_text("Mesh cells per patch along each axis")
+            .changed();
+    });
+    if interpolation == WarpInterpolation::Bezier
+        && ui
+            .button("Smooth Seams")
+            .on_hover_text("Align the handles on both sides of each patch seam")
+            .clicked()
+    {
+        surface.enforce_continuity();
+        changed = true;
+    }
+    ui.label(
+        egui::RichText::new(format!(
+            "{} x {} control points",
+            surface.columns, surface.rows
+        ))
+        .weak()
+        .small(),
+    );
+
+    if resample {
+        surface = surface.resample(
+            WarpSurface::lattice_size(patches_x, interpolation),
+            WarpSurface::lattice_size(patches_y, interpolation),
+            interpolation,
+        );
+    }
+    if resample || changed {
+        *mesh = MeshType::from_warp_surface(&surface);
+        *last_mesh_edit_id = None; // Trigger resync
+    }
+}
 
 pub fn sync_mesh_editor_to_current_selection(
     mesh_editor: &mut crate::editors::mesh_editor::MeshEditor,
@@ -34,13 +162,10 @@ pub fn sync_mesh_editor_to_current_selection(
                 egui::Pos2::new(bl.0 * scale, bl.1 * scale),
             );
         }
-        MeshType::BezierSurface { control_points } => {
-            // Deserialize scaled points
-            let points: Vec<(f32, f32)> = control_points
-                .iter()
-                .map(|(x, y)| (x * scale, y * scale))
-                .collect();
-            mesh_editor.set_from_bezier_points(&points);
+        MeshType::BezierSurface { .. } => {
+            if !load_warp_lattice(mesh_editor, mesh, scale) {
+                mesh_editor.create_quad(egui::Pos2::new(100.0, 100.0), 200.0);
+            }
         }
         // Fallback for unsupported types - reset to default quad for now
         _ => {
@@ -73,10 +198,7 @@ pub fn apply_mesh_editor_to_selection(
                 *bl = (p_bl.x / scale, p_bl.y / scale);
             }
         }
-        MeshType::BezierSurface { control_points } => {
-            let points = mesh_editor.get_bezier_points();
-            *control_points = points.iter().map(|(x, y)| (x / scale, y / scale)).collect();
-        }
+        MeshType::BezierSurface { .. } => store_warp_lattice(mesh_editor, mesh, scale),
         _ => {
             // Other types not yet supported for write-back
         }
@@ -107,6 +229,8 @@ pub fn render_mesh_editor_ui(
                 MeshType::Cylinder { .. } => "Cylinder",
                 MeshType::Sphere { .. } => "Sphere",
                 MeshType::Custom { .. } => "Custom",
+                MeshType::Model3D { .. } => "3D Model",
+                MeshType::CalibratedGrid { .. } => "Calibrated Grid",
             })
             .show_ui(ui, |ui| {
                 if ui
@@ -125,21 +249,63 @@ pub fn render_mesh_editor_ui(
                     .selectable_label(matches!(mesh, MeshType::Grid { .. }), "Grid")
                     .clicked()
                 {
-                    *mesh = MeshType::Grid { rows: 4, cols: 4 };
+                    *mesh = mesh
+                        .to_grid()
+                        .unwrap_or(MeshType::Grid { rows: 4, cols: 4 });
                     *last_mesh_edit_id = None; // Trigger resync
                 }
+                let is_bezier = matches!(mesh, MeshType::BezierSurface { .. });
+                if ui.selectable_label(is_bezier, "Bezier").clicked() && !is_bezier {
+                    // Grids keep their layout, anything else starts from a flat patch
+                    *mesh = mesh.to_bezier_surface().unwrap_or_else(|| {
+                        MeshType::from_warp_surface(&WarpSurface::new(
+                            4,
+                            4,
+                            WarpInterpolation::Bezier,
+                        ))
+                    });
+                    *last_mesh_edit_id = None;
+                }
                 if ui
-                    .selectable_label(matches!(mesh, MeshType::BezierSurface { .. }), "Bezier")
+                    .selectable_label(matches!(mesh, MeshType::Model3D { .. }), "3D Model")
                     .clicked()
                 {
-                    // Default bezier
-                    *mesh = MeshType::BezierSurface {
-                        control_points: vec![],
+                    *mesh = MeshType::Model3D {
+                        path: String::new(),
                     };
                     *last_mesh_edit_id = None;
                 }
             });
 
+        if let MeshType::Model3D { path } = mesh {
+            ui.horizontal(|ui| {
+                ui.label("Model:");
+                ui.add(egui::TextEdit::singleline(path).desired_width(160.0));
+                if ui
+                    .button("\u{1F4C2}")
+                    .on_hover_text("Select OBJ/glTF Model")
+                    .clicked()
+                {
+                    if let Some(picked) = rfd::FileDialog::new()
+                        .add_filter("3D Model", &["obj", "gltf", "glb"])
+                        .pick_file()
+                    {
+                        *path = picked.display().to_string();
+                    }
+                }
+            });
+            ui.label(
+                egui::RichText::new("Rendered from each projector's 3D calibration")
+                    .weak()
+                    .small(),
+            );
+            return;
+        }
+
+        if matches!(mesh, MeshType::BezierSurface { .. }) {
+            render_warp_surface_settings(ui, mesh, last_mesh_edit_id, id_salt);
+        }
+
         // Resync logic if type changed (handled by caller passing part, but here we just have mesh)
         if last_mesh_edit_id.is_none() {
             let scale = 200.0;
@@ -153,13 +319,10 @@ pub fn render_mesh_editor_ui(
                     );
                     *last_mesh_edit_id = Some(part_id);
                 }
-                MeshType::BezierSurface { control_points } => {
-                    // Deserialize scaled points
-                    let points: Vec<(f32, f32)> = control_points
-                        .iter()
-                        .map(|(x, y)| (x * scale, y * scale))
-                        .collect();
-                    mesh_editor.set_from_bezier_points(&points);
+                MeshType::BezierSurface { .. } => {
+                    if !load_warp_lattice(mesh_editor, mesh, scale) {
+                        mesh_editor.create_quad(egui::Pos2::new(100.0, 100.0), 200.0);
+                    }
                     *last_mesh_edit_id = Some(part_id);
                 }
                 _ => {
@@ -185,10 +348,7 @@ pub fn render_mesh_editor_ui(
                         *bl = (p_bl.x / scale, p_bl.y / scale);
                     }
                 }
-                MeshType::BezierSurface { control_points } => {
-                    let points = mesh_editor.get_bezier_points();
-                    *control_points = points.iter().map(|(x, y)| (x / scale, y / scale)).collect();
-                }
+                MeshType::BezierSurface { .. } => store_warp_lattice(mesh_editor, mesh, scale),
                 _ => {}
             }
         }
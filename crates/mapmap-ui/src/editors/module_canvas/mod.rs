@@ -1,4 +1,5 @@
 pub mod canvas_ui;
+pub mod commands;
 pub mod controller;
 pub mod diagnostics;
 pub mod draw;
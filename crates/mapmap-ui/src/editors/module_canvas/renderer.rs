@@ -1,3 +1,4 @@
+use super::commands;
 use super::controller;
 use super::diagnostics;
 use super::draw;
@@ -8,6 +9,7 @@ use super::ModuleCanvasRenderOptions;
 use crate::i18n::LocaleManager;
 use crate::UIAction;
 use egui::{Color32, Pos2, Rect, Sense, Stroke, Ui, Vec2};
+use mapmap_control::shortcuts::{KeyBindings, ShortcutContext};
 use mapmap_core::module::{ModuleId, ModuleManager, TriggerType};
 
 pub fn show(
@@ -16,6 +18,7 @@ pub fn show(
     manager: &mut ModuleManager,
     locale: &LocaleManager,
     actions: &mut Vec<UIAction>,
+    key_bindings: &KeyBindings,
     options: ModuleCanvasRenderOptions,
 ) {
     if !canvas.selected_parts.is_empty()
@@ -74,7 +77,16 @@ pub fn show(
     }
 
     if let Some(module_id) = canvas.active_module_id {
-        render_canvas(canvas, ui, manager, module_id, locale, actions, options);
+        render_canvas(
+            canvas,
+            ui,
+            manager,
+            module_id,
+            locale,
+            actions,
+            key_bindings,
+            options,
+        );
     } else {
         ui.centered_and_justified(|ui| {
             ui.vertical_centered(|ui| {
@@ -95,8 +107,46 @@ pub fn render_canvas(
     module_id: ModuleId,
     _locale: &LocaleManager,
     actions: &mut Vec<UIAction>,
+    key_bindings: &KeyBindings,
     options: ModuleCanvasRenderOptions,
 ) {
+    let view_center = {
+        let center = ui.available_rect_before_wrap().center();
+        let min = ui.available_rect_before_wrap().min.to_vec2();
+        let pos = (center - canvas.pan_offset - min) / canvas.zoom;
+        (pos.x, pos.y)
+    };
+
+    // Rebindable canvas shortcuts (see DefaultShortcuts::module_canvas)
+    if !ui.memory(|m| m.focused().is_some()) {
+        let pressed: Vec<_> = ui.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        repeat: false,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+                .collect()
+        });
+        for (key, modifiers) in pressed {
+            let Some(key) = crate::panels::shortcuts_panel::to_mapmap_key(key) else {
+                continue;
+            };
+            let modifiers = crate::panels::shortcuts_panel::to_mapmap_modifiers(modifiers);
+            if let Some(action) =
+                key_bindings.find_action_in_context(key, &modifiers, ShortcutContext::ModuleCanvas)
+            {
+                commands::execute_action(canvas, manager, &action, view_center);
+            }
+        }
+    }
+
     let module = if let Some(m) = manager.get_module_mut(module_id) {
         m
     } else {
@@ -174,6 +224,7 @@ pub fn render_canvas(
 
     // Draw grid
     draw::draw_grid(canvas, &painter, canvas_rect);
+    draw::draw_part_groups(canvas, &painter, canvas_rect, module);
 
     let zoom = canvas.zoom;
     let pan_offset = canvas.pan_offset;
@@ -323,6 +374,17 @@ pub fn render_canvas(
             animation_profile,
         );
 
+        if part.bypass && part.part_type.supports_bypass() {
+            painter.rect_filled(part_rect, 0.0, Color32::from_black_alpha(140));
+            painter.text(
+                part_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "BYPASS",
+                egui::FontId::proportional(14.0 * canvas.zoom),
+                Color32::from_rgb(255, 180, 0),
+            );
+        }
+
         let part_id = part.id;
 
         // 2.1 Handle Socket Interaction (Priority)
@@ -564,6 +626,11 @@ pub fn render_canvas(
 
     draw::draw_quick_create_popup(canvas, ui, canvas_rect, manager, canvas.active_module_id);
 
+    if let Some(action) = draw::draw_command_palette(canvas, ui, canvas_rect, manager, key_bindings)
+    {
+        commands::execute_action(canvas, manager, &action, view_center);
+    }
+
     if let Some(conn_idx) = canvas.context_menu_connection {
         if let Some(pos) = canvas.context_menu_pos {
             let menu_rect = Rect::from_min_size(pos, Vec2::new(150.0, 50.0));
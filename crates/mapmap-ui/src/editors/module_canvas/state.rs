@@ -70,6 +70,8 @@ pub struct ModuleCanvas {
     pub learned_midi: Option<(ModulePartId, u8, u8, bool)>,
     /// Live audio trigger data from AudioAnalyzerV2
     pub audio_trigger_data: AudioTriggerData,
+    /// Named channel analyzers available as audio sources
+    pub audio_analyzer_names: Vec<String>,
 
     /// Discovered NDI sources
     #[cfg(feature = "ndi")]
@@ -105,6 +107,8 @@ pub struct ModuleCanvas {
     >,
     /// Status message for Hue operations
     pub hue_status_message: Option<String>,
+    /// Scene model of the projector calibration picker
+    pub calibration_model: Option<CalibrationModel>,
     /// Last known trigger values for visualization (Part ID -> Value 0.0-1.0)
     pub last_trigger_values: std::collections::HashMap<ModulePartId, f32>,
     /// Whether inspector previews should be shown where available.
@@ -129,6 +133,14 @@ pub struct ModuleCanvas {
     /// Index of the currently selected item in the quick create list
     pub quick_create_selected_index: usize,
 
+    // Command Palette State
+    /// Whether the command palette is visible
+    pub show_command_palette: bool,
+    /// Search text for the command palette
+    pub command_palette_query: String,
+    /// Index of the highlighted command palette entry
+    pub command_palette_selected: usize,
+
     /// Snapshot of a part before editing, used to create Undo/Redo commands when an edit finishes.
     pub edit_snapshot: Option<mapmap_core::module::ModulePart>,
 }
@@ -161,6 +173,7 @@ impl Default for ModuleCanvas {
             plug_icons: std::collections::HashMap::new(),
             learned_midi: None,
             audio_trigger_data: AudioTriggerData::default(),
+            audio_analyzer_names: Vec::new(),
             #[cfg(feature = "ndi")]
             ndi_sources: Vec::new(),
             #[cfg(feature = "ndi")]
@@ -176,6 +189,7 @@ impl Default for ModuleCanvas {
             hue_bridges: Vec::new(),
             hue_discovery_rx: None,
             hue_status_message: None,
+            calibration_model: None,
             last_trigger_values: std::collections::HashMap::new(),
             show_inspector_previews: true,
             layer_inspector_view_mode: LayerInspectorViewMode::MeshEditor,
@@ -186,6 +200,9 @@ impl Default for ModuleCanvas {
             quick_create_filter: String::new(),
             quick_create_pos: Pos2::ZERO,
             quick_create_selected_index: 0,
+            show_command_palette: false,
+            command_palette_query: String::new(),
+            command_palette_selected: 0,
             edit_snapshot: None,
         }
     }
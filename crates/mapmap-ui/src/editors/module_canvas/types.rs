@@ -216,3 +216,48 @@ impl NodeTemplateTrait for MyNodeTemplate {
         // Mock
     }
 }
+
+/// Scene model shown by the projector calibration picker
+pub struct CalibrationModel {
+    /// File the model is loaded from; a different path starts a new load
+    pub path: String,
+    /// Receives the model from the loading thread until it arrives
+    pub rx: Option<std::sync::mpsc::Receiver<Result<mapmap_core::SceneModel, String>>>,
+    /// The loaded model, or why it could not be loaded
+    pub model: Option<Result<mapmap_core::SceneModel, String>>,
+}
+
+impl CalibrationModel {
+    /// Start loading the model at `path` on a background thread
+    pub fn load(path: &str) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let thread_path = path.to_string();
+        std::thread::spawn(move || {
+            let result = mapmap_core::SceneModel::load(&thread_path).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+        Self {
+            path: path.to_string(),
+            rx: Some(rx),
+            model: None,
+        }
+    }
+
+    /// Take the model from the loading thread once it is done
+    pub fn poll(&mut self) {
+        let Some(rx) = &self.rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(result) => {
+                self.model = Some(result);
+                self.rx = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.model = Some(Err("Loading thread stopped".to_string()));
+                self.rx = None;
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::types::*;
 use egui::{Color32, Pos2, Rect, TextureHandle, Vec2};
+use mapmap_core::audio::beat_grid::BeatDivision;
 use mapmap_core::module::{
     AudioBand, AudioTriggerOutputConfig, BevyCameraMode, BlendModeType, EffectType, HueNodeType,
     LayerType, MaskShape, MaskType, ModulePart, ModulePartType, ModuleSocket, ModuleSocketType,
@@ -22,6 +23,13 @@ pub fn build_node_catalog() -> Vec<NodeCatalogItem> {
             search_tags: "trigger time rhythm",
             part_type: ModulePartType::Trigger(TriggerType::Beat),
         },
+        NodeCatalogItem {
+            label: "📐 Beat Grid",
+            search_tags: "trigger bar phrase quantize downbeat",
+            part_type: ModulePartType::Trigger(TriggerType::BeatGrid {
+                division: BeatDivision::Bar,
+            }),
+        },
         NodeCatalogItem {
             label: "🔊 Audio FFT",
             search_tags: "trigger sound music reactive",
@@ -29,6 +37,7 @@ pub fn build_node_catalog() -> Vec<NodeCatalogItem> {
                 band: AudioBand::Bass,
                 threshold: 0.5,
                 output_config: AudioTriggerOutputConfig::default(),
+                analyzer: None,
             }),
         },
         NodeCatalogItem {
@@ -236,6 +245,8 @@ pub fn build_node_catalog() -> Vec<NodeCatalogItem> {
                 output_fps: 60.0,
                 ndi_enabled: false,
                 ndi_stream_name: String::new(),
+                calibration_3d: Default::default(),
+                color_measurements: None,
             }),
         },
     ])
@@ -329,6 +340,7 @@ pub fn get_part_style(
             let name = match trigger {
                 TriggerType::AudioFFT { .. } => "Audio FFT",
                 TriggerType::Beat => "Beat",
+                TriggerType::BeatGrid { .. } => "Beat Grid",
                 TriggerType::Midi { .. } => "MIDI",
                 TriggerType::Osc { .. } => "OSC",
                 TriggerType::Shortcut { .. } => "Shortcut",
@@ -418,6 +430,7 @@ pub fn get_part_style(
                     MaskShape::Ellipse => "Ellipse",
                 },
                 MaskType::Gradient { .. } => "Gradient",
+                MaskType::Bezier(_) => "Bezier",
             };
             (
                 Color32::from_rgb(60, 55, 70),
@@ -503,6 +516,7 @@ pub fn get_part_style(
                 #[cfg(target_os = "windows")]
                 OutputType::Spout { .. } => "Spout Output",
                 OutputType::Hue { .. } => "Philips Hue",
+                OutputType::Light { .. } => "Light Output",
             };
             (
                 Color32::from_rgb(70, 50, 50),
@@ -563,6 +577,7 @@ pub fn get_part_property_text(part_type: &ModulePartType) -> String {
             TriggerType::Osc { address } => format!("\u{1F4E1} {}", address),
             TriggerType::Shortcut { key_code, .. } => format!("âŒ¨ï¸  {}", key_code),
             TriggerType::Beat => "🥁 Beat".to_string(),
+            TriggerType::BeatGrid { division } => format!("📐 {}", division.name()),
         },
         ModulePartType::Source(source_type) => match source_type {
             SourceType::MediaFile { path, .. } => {
@@ -632,6 +647,7 @@ pub fn get_part_property_text(part_type: &ModulePartType) -> String {
             MaskType::Gradient { angle, .. } => {
                 format!("\u{1F308} Gradient {}Â°", *angle as i32)
             }
+            MaskType::Bezier(mask) => format!("\u{270F} Bezier ({} points)", mask.points.len()),
         },
         ModulePartType::Modulizer(modulizer_type) => match modulizer_type {
             ModulizerType::Effect {
@@ -639,7 +655,12 @@ pub fn get_part_property_text(part_type: &ModulePartType) -> String {
                 ..
             } => format!("\u{2728} {}", effect.name()),
             ModulizerType::BlendMode(blend) => format!("🔄 {}", blend.name()),
-            ModulizerType::AudioReactive { source } => format!("\u{1F50A} {}", source),
+            ModulizerType::AudioReactive {
+                source, analyzer, ..
+            } => match analyzer {
+                Some(name) => format!("\u{1F50A} {} ({})", source, name),
+                None => format!("\u{1F50A} {}", source),
+            },
         },
         ModulePartType::Mesh(_) => "🕸️ï¸  Mesh".to_string(),
         ModulePartType::Layer(layer_type) => {
@@ -662,6 +683,7 @@ pub fn get_part_property_text(part_type: &ModulePartType) -> String {
                     format!("\u{1F4A1} {}", bridge_ip)
                 }
             }
+            OutputType::Light { name, .. } => format!("\u{1F4A1} {}", name),
         },
         ModulePartType::Hue(hue) => match hue {
             HueNodeType::SingleLamp { name, .. } => {
@@ -804,6 +826,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (650.0, 100.0),
                     None,
@@ -846,6 +870,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (950.0, 100.0),
                     None,
@@ -861,6 +887,7 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         band: AudioBand::Bass,
                         threshold: 0.5,
                         output_config: AudioTriggerOutputConfig::default(),
+                        analyzer: None,
                     }),
                     (50.0, 100.0),
                     None,
@@ -900,6 +927,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (1250.0, 100.0),
                     None,
@@ -939,6 +968,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (950.0, 100.0),
                     None,
@@ -973,6 +1004,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (650.0, 100.0),
                     None,
@@ -1033,6 +1066,8 @@ pub fn default_presets() -> Vec<ModulePreset> {
                         output_fps: 60.0,
                         ndi_enabled: false,
                         ndi_stream_name: String::new(),
+                        calibration_3d: Default::default(),
+                        color_measurements: None,
                     }),
                     (650.0, 100.0),
                     None,
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use egui::{self, Button, ComboBox, RichText, ScrollArea, Slider, Ui};
 use mapmap_control::{
-    cue::{triggers::*, Cue, CueList},
+    cue::{triggers::*, Cue, CueList, CueStacks},
     ControlManager,
 };
 
@@ -21,6 +21,7 @@ pub struct CuePanel {
     pub visible: bool, // Allow visibility control
     selected_cue_id: Option<u32>,
     jump_target_id: String,
+    new_stack_name: String,
 }
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -55,6 +56,9 @@ impl CuePanel {
                 ui.add_space(8.0);
 
                 self.render_ui(ui, &control_manager.cue_list, i18n, actions, icon_manager);
+
+                ui.separator();
+                self.render_stacks(ui, &control_manager.cue_stacks, i18n, actions);
             });
         self.visible = open;
     }
@@ -220,6 +224,64 @@ impl CuePanel {
         });
     }
 
+    /// Cue stacks run next to the main cue list; recording appends the current
+    /// state as a new cue to a stack.
+    fn render_stacks(
+        &mut self,
+        ui: &mut egui::Ui,
+        stacks: &CueStacks,
+        i18n: &LocaleManager,
+        actions: &mut Vec<UIAction>,
+    ) {
+        ui.heading(i18n.t("header-cue-stacks"));
+        if stacks.is_empty() {
+            ui.label(i18n.t("label-no-cue-stacks"));
+        }
+        for stack in stacks.stacks() {
+            ui.horizontal(|ui| {
+                let live = stack.list.current_cue().is_some();
+                let mut name = RichText::new(&stack.name);
+                if live {
+                    name = name.color(ui.visuals().selection.stroke.color).strong();
+                }
+                ui.label(name);
+                let count = stack.list.len().to_string();
+                ui.weak(i18n.t_args("label-stack-cues", &[("count", count.as_str())]));
+
+                if ui
+                    .add_enabled(!stack.list.is_empty(), Button::new(i18n.t("btn-go")))
+                    .clicked()
+                {
+                    actions.push(UIAction::GoCueStack(stack.id));
+                }
+                if ui
+                    .add_enabled(live, Button::new(i18n.t("btn-release")))
+                    .clicked()
+                {
+                    actions.push(UIAction::ReleaseCueStack(stack.id));
+                }
+                if ui.button(i18n.t("btn-record-cue")).clicked() {
+                    actions.push(UIAction::RecordStackCue(stack.id));
+                }
+                if ui.button(i18n.t("btn-remove")).clicked() {
+                    actions.push(UIAction::RemoveCueStack(stack.id));
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_stack_name);
+            if ui.button(i18n.t("btn-add-stack")).clicked() {
+                let name = match self.new_stack_name.trim() {
+                    "" => format!("Stack {}", stacks.len() + 1),
+                    name => name.to_string(),
+                };
+                actions.push(UIAction::AddCueStack(name));
+                self.new_stack_name.clear();
+            }
+        });
+    }
+
     /// Renders the editor for a given cue's properties.
     /// Returns `true` if the cue was changed.
     fn render_cue_editor(
@@ -159,7 +159,7 @@ pub fn render_layer_inspector(
                                 }
                             }
                             _ => {
-                                mesh_editor.create_quad(egui::Pos2::new(100.0, 100.0), 200.0);
+                                mesh_editor.set_from_mesh(&mapping.mesh, scale);
                             }
                         }
                     }
@@ -185,6 +185,8 @@ pub fn render_layer_inspector(
                                     ));
                                 }
                             }
+                        } else if mesh_editor.apply_to_mesh(&mut new_mesh, scale) {
+                            action = Some(InspectorAction::UpdateMappingMesh(mapping.id, new_mesh));
                         }
                     }
 
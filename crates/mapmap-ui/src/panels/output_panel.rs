@@ -15,6 +15,8 @@ pub struct OutputPanel {
     pub selected_output_id: Option<u64>,
     /// Flag to control the visibility of the panel.
     pub visible: bool,
+    /// Slice of the selected output being edited in the slicing layout.
+    selected_slice: Option<usize>,
     /// A list of actions to be processed by the main application.
     actions: Vec<UIAction>,
 }
@@ -24,6 +26,7 @@ impl Default for OutputPanel {
         Self {
             selected_output_id: None,
             visible: true,
+            selected_slice: None,
             actions: Vec::new(),
         }
     }
@@ -204,32 +207,28 @@ impl OutputPanel {
                             );
                         });
 
-                        crate::widgets::custom::collapsing_header_with_reset(
+                        if crate::widgets::custom::collapsing_header_with_reset(
                             ui,
                             "Edge Blend",
                             false,
-                            |ui| {
-                                ui.label(format!(
-                                    "Left: {}",
-                                    updated_config.edge_blend.left.enabled
-                                ));
-                                ui.label(format!(
-                                    "Right: {}",
-                                    updated_config.edge_blend.right.enabled
-                                ));
-                                ui.label(format!("Top: {}", updated_config.edge_blend.top.enabled));
-                                ui.label(format!(
-                                    "Bottom: {}",
-                                    updated_config.edge_blend.bottom.enabled
-                                ));
-                            },
-                        );
+                            |ui| render_edge_blend(ui, &mut updated_config.edge_blend),
+                        ) {
+                            updated_config.edge_blend = mapmap_core::EdgeBlendConfig::default();
+                        }
 
                         crate::widgets::custom::collapsing_header_with_reset(
                             ui,
                             "Color Calibration",
                             false,
                             |ui| {
+                                ui.checkbox(
+                                    &mut updated_config.color_calibration.enabled,
+                                    "Apply calibration",
+                                )
+                                .on_hover_text(
+                                    "Runs the color calibration pass on this output; \
+                                     color matching turns it on",
+                                );
                                 ui.label(format!(
                                     "Brightness: {}",
                                     updated_config.color_calibration.brightness
@@ -242,9 +241,45 @@ impl OutputPanel {
                                     "Saturation: {}",
                                     updated_config.color_calibration.saturation
                                 ));
+                                render_color_lut(ui, &mut updated_config.color_calibration.lut);
                             },
                         );
 
+                        if crate::widgets::custom::collapsing_header_with_reset(
+                            ui,
+                            "Dome",
+                            false,
+                            |ui| render_dome(ui, &mut updated_config.dome),
+                        ) {
+                            updated_config.dome = None;
+                        }
+
+                        if crate::widgets::custom::collapsing_header_with_reset(
+                            ui,
+                            "Slicing",
+                            false,
+                            |ui| {
+                                render_slicing(
+                                    ui,
+                                    &mut updated_config.slicing,
+                                    updated_config.resolution,
+                                    &mut self.selected_slice,
+                                )
+                            },
+                        ) {
+                            updated_config.slicing = mapmap_core::SliceLayout::default();
+                        }
+
+                        if crate::widgets::custom::collapsing_header_with_reset(
+                            ui,
+                            "Output Transform",
+                            false,
+                            |ui| render_output_transform(ui, &mut updated_config.output_transform),
+                        ) {
+                            updated_config.output_transform =
+                                mapmap_core::OutputTransformConfig::default();
+                        }
+
                         if updated_config != *output {
                             *output = updated_config;
                             self.actions
@@ -263,3 +298,429 @@ impl OutputPanel {
             });
     }
 }
+
+/// Correction LUT of an output, usually solved by color matching in the
+/// projector inspector.
+fn render_color_lut(ui: &mut egui::Ui, lut: &mut Option<String>) {
+    ui.horizontal(|ui| {
+        ui.label("LUT:");
+        let mut path = lut.clone().unwrap_or_default();
+        if ui
+            .add(egui::TextEdit::singleline(&mut path).desired_width(160.0))
+            .changed()
+        {
+            *lut = (!path.is_empty()).then_some(path);
+        }
+        if ui
+            .button("\u{1F4C2}")
+            .on_hover_text("Select correction LUT")
+            .clicked()
+        {
+            if let Some(picked) = rfd::FileDialog::new()
+                .add_filter("LUT", &["cube", "png"])
+                .pick_file()
+            {
+                *lut = Some(picked.display().to_string());
+            }
+        }
+        if lut.is_some() && ui.small_button("\u{2716}").clicked() {
+            *lut = None;
+        }
+    });
+}
+
+/// Slice layout of an output: a raster preview where slices are picked and
+/// dragged, and the input region, crop, rotation and flips of the selected
+/// slice.
+fn render_slicing(
+    ui: &mut egui::Ui,
+    layout: &mut mapmap_core::SliceLayout,
+    resolution: (u32, u32),
+    selected: &mut Option<usize>,
+) {
+    use mapmap_core::{CanvasRegion, OutputSlice, SliceRect, SliceRotation};
+
+    let raster = egui::vec2(resolution.0.max(1) as f32, resolution.1.max(1) as f32);
+    let preview_width = ui.available_width().min(280.0);
+    let scale = preview_width / raster.x;
+    let (response, painter) = ui.allocate_painter(raster * scale, egui::Sense::click_and_drag());
+    let origin = response.rect.min;
+    painter.rect_filled(response.rect, CornerRadius::ZERO, colors::DARKER_GREY);
+
+    let to_pixel = |pos: egui::Pos2| {
+        let pixel = (pos - origin) / scale;
+        (pixel.x.max(0.0) as u32, pixel.y.max(0.0) as u32)
+    };
+    if response.drag_started() || response.clicked() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            let (x, y) = to_pixel(pos);
+            *selected = layout.slice_at(x, y);
+        }
+    }
+    if response.dragged() {
+        if let Some(index) = *selected {
+            let delta = response.drag_delta() / scale;
+            layout.move_slice(
+                index,
+                delta.x.round() as i64,
+                delta.y.round() as i64,
+                resolution,
+            );
+        }
+    }
+
+    let overlapping: Vec<usize> = layout
+        .overlaps()
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .collect();
+    for (index, slice) in layout.slices.iter().enumerate() {
+        if !slice.enabled {
+            continue;
+        }
+        let rect = egui::Rect::from_min_size(
+            origin + egui::vec2(slice.output.x as f32, slice.output.y as f32) * scale,
+            egui::vec2(slice.output.width as f32, slice.output.height as f32) * scale,
+        );
+        let color = if *selected == Some(index) {
+            colors::MINT_ACCENT
+        } else if overlapping.contains(&index) {
+            colors::WARN_COLOR
+        } else {
+            colors::CYAN_ACCENT
+        };
+        painter.rect_filled(rect, CornerRadius::ZERO, color.linear_multiply(0.15));
+        painter.rect_stroke(
+            rect,
+            CornerRadius::ZERO,
+            egui::Stroke::new(1.0, color),
+            egui::StrokeKind::Inside,
+        );
+        painter.text(
+            rect.left_top() + egui::vec2(3.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            &slice.name,
+            egui::FontId::proportional(10.0),
+            color,
+        );
+    }
+
+    if !overlapping.is_empty() {
+        ui.colored_label(colors::WARN_COLOR, "Overlapping slices");
+    }
+    if !layout.out_of_bounds(resolution).is_empty() {
+        ui.colored_label(colors::ERROR_COLOR, "Slices extend past the output raster");
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button("Add Slice").clicked() {
+            let name = format!("Slice {}", layout.slices.len() + 1);
+            let output = SliceRect::new(0, 0, resolution.0, resolution.1);
+            let input = CanvasRegion::new(0.0, 0.0, 1.0, 1.0);
+            *selected = Some(layout.add_slice(OutputSlice::new(name, input, output)));
+        }
+        if let Some(index) = *selected {
+            if ui.button("Remove Slice").clicked() {
+                layout.remove_slice(index);
+                *selected = None;
+            }
+        }
+    });
+
+    let Some(slice) = selected.and_then(|index| layout.slices.get_mut(index)) else {
+        return;
+    };
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut slice.enabled, "");
+        ui.add(egui::TextEdit::singleline(&mut slice.name).desired_width(160.0));
+    });
+
+    ui.label("Input (output content, 0-1):");
+    ui.horizontal(|ui| {
+        for (value, prefix) in [
+            (&mut slice.input.x, "X: "),
+            (&mut slice.input.y, "Y: "),
+            (&mut slice.input.width, "W: "),
+            (&mut slice.input.height, "H: "),
+        ] {
+            ui.add(
+                egui::DragValue::new(value)
+                    .speed(0.001)
+                    .range(0.0..=1.0)
+                    .prefix(prefix),
+            );
+        }
+    });
+
+    ui.label("Output (pixels):");
+    ui.horizontal(|ui| {
+        for (value, prefix, max) in [
+            (&mut slice.output.x, "X: ", resolution.0),
+            (&mut slice.output.y, "Y: ", resolution.1),
+            (&mut slice.output.width, "W: ", resolution.0),
+            (&mut slice.output.height, "H: ", resolution.1),
+        ] {
+            ui.add(
+                egui::DragValue::new(value)
+                    .speed(1.0)
+                    .range(0..=max)
+                    .prefix(prefix),
+            );
+        }
+    });
+
+    ui.label("Crop:");
+    ui.horizontal(|ui| {
+        for (value, prefix) in [
+            (&mut slice.crop.left, "L: "),
+            (&mut slice.crop.top, "T: "),
+            (&mut slice.crop.right, "R: "),
+            (&mut slice.crop.bottom, "B: "),
+        ] {
+            ui.add(
+                egui::DragValue::new(value)
+                    .speed(0.001)
+                    .range(0.0..=0.5)
+                    .prefix(prefix),
+            );
+        }
+    });
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("Rotation")
+            .selected_text(format!("{}°", slice.rotation.quarter_turns() * 90))
+            .show_ui(ui, |ui| {
+                for rotation in SliceRotation::ALL {
+                    ui.selectable_value(
+                        &mut slice.rotation,
+                        rotation,
+                        format!("{}°", rotation.quarter_turns() * 90),
+                    );
+                }
+            });
+        ui.checkbox(&mut slice.flip_horizontal, "Flip H");
+        ui.checkbox(&mut slice.flip_vertical, "Flip V");
+    });
+}
+
+/// Dome mode of an output: source and output projection, dome orientation
+/// and warp mesh resolution.
+fn render_dome(ui: &mut egui::Ui, dome: &mut Option<mapmap_core::DomeConfig>) {
+    use mapmap_core::{DomeProjection, DomeSource};
+
+    let mut enabled = dome.is_some();
+    if ui.checkbox(&mut enabled, "Dome mode").changed() {
+        *dome = enabled.then(mapmap_core::DomeConfig::default);
+    }
+    let Some(config) = dome else {
+        return;
+    };
+
+    let source_label = match config.source {
+        DomeSource::Equirectangular => "Equirectangular",
+        DomeSource::CubemapCross => "Cubemap Cross",
+        DomeSource::Fisheye { .. } => "Fisheye",
+    };
+    egui::ComboBox::from_label("Source")
+        .selected_text(source_label)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(
+                &mut config.source,
+                DomeSource::Equirectangular,
+                "Equirectangular",
+            );
+            ui.selectable_value(
+                &mut config.source,
+                DomeSource::CubemapCross,
+                "Cubemap Cross",
+            );
+            if !matches!(config.source, DomeSource::Fisheye { .. })
+                && ui.selectable_label(false, "Fisheye").clicked()
+            {
+                config.source = DomeSource::Fisheye { fov: 180.0 };
+            }
+        });
+    if let DomeSource::Fisheye { fov } = &mut config.source {
+        ui.add(egui::Slider::new(fov, 90.0..=360.0).text("Source FOV"));
+    }
+
+    let projection_label = match config.projection {
+        DomeProjection::Fisheye { .. } => "Fisheye",
+        DomeProjection::Equirectangular => "Equirectangular",
+        DomeProjection::Slice { .. } => "Projector Slice",
+    };
+    egui::ComboBox::from_label("Output")
+        .selected_text(projection_label)
+        .show_ui(ui, |ui| {
+            if !matches!(config.projection, DomeProjection::Fisheye { .. })
+                && ui.selectable_label(false, "Fisheye").clicked()
+            {
+                config.projection = DomeProjection::Fisheye { fov: 180.0 };
+            }
+            ui.selectable_value(
+                &mut config.projection,
+                DomeProjection::Equirectangular,
+                "Equirectangular",
+            );
+            if !matches!(config.projection, DomeProjection::Slice { .. })
+                && ui.selectable_label(false, "Projector Slice").clicked()
+            {
+                config.projection = DomeProjection::Slice {
+                    yaw: 0.0,
+                    pitch: 30.0,
+                    roll: 0.0,
+                    h_fov: 90.0,
+                    v_fov: 60.0,
+                };
+            }
+        });
+    match &mut config.projection {
+        DomeProjection::Fisheye { fov } => {
+            ui.add(egui::Slider::new(fov, 90.0..=360.0).text("FOV"));
+        }
+        DomeProjection::Equirectangular => {}
+        DomeProjection::Slice {
+            yaw,
+            pitch,
+            roll,
+            h_fov,
+            v_fov,
+        } => {
+            ui.add(egui::Slider::new(yaw, -180.0..=180.0).text("Yaw"));
+            ui.add(egui::Slider::new(pitch, -90.0..=90.0).text("Pitch"));
+            ui.add(egui::Slider::new(roll, -180.0..=180.0).text("Roll"));
+            ui.add(egui::Slider::new(h_fov, 10.0..=170.0).text("H FOV"));
+            ui.add(egui::Slider::new(v_fov, 10.0..=170.0).text("V FOV"));
+        }
+    }
+
+    ui.separator();
+    ui.add(egui::Slider::new(&mut config.tilt, 0.0..=90.0).text("Tilt"));
+    ui.add(egui::Slider::new(&mut config.rotation, -180.0..=180.0).text("Rotation"));
+    ui.add(egui::Slider::new(&mut config.segments, 8..=128).text("Segments"));
+}
+
+/// Signal encoding of an output: SDR transfer curve with optional tone
+/// mapping, or PQ/HLG with the luminance levels of the HDR display.
+fn render_output_transform(ui: &mut egui::Ui, config: &mut mapmap_core::OutputTransformConfig) {
+    use mapmap_core::{OutputTransform, ToneMapping};
+
+    egui::ComboBox::from_label("Transform")
+        .selected_text(config.transform.name())
+        .show_ui(ui, |ui| {
+            for transform in OutputTransform::ALL {
+                ui.selectable_value(&mut config.transform, transform, transform.name());
+            }
+        });
+    if config.transform.is_hdr() {
+        ui.colored_label(
+            colors::WARN_COLOR,
+            "Needs a display and GPU offering an HDR surface; otherwise this output is encoded as sRGB.",
+        );
+    }
+    ui.add(egui::Slider::new(&mut config.exposure, -4.0..=4.0).text("Exposure (stops)"));
+
+    if config.transform.is_hdr() {
+        ui.add(
+            egui::Slider::new(&mut config.paper_white_nits, 80.0..=500.0)
+                .text("Paper White (nits)"),
+        );
+        ui.add(
+            egui::Slider::new(&mut config.peak_nits, 400.0..=10_000.0)
+                .logarithmic(true)
+                .text("Peak (nits)"),
+        );
+    } else {
+        egui::ComboBox::from_label("Tone Mapping")
+            .selected_text(config.tone_mapping.name())
+            .show_ui(ui, |ui| {
+                for tone_mapping in ToneMapping::ALL {
+                    ui.selectable_value(
+                        &mut config.tone_mapping,
+                        tone_mapping,
+                        tone_mapping.name(),
+                    );
+                }
+            });
+    }
+}
+
+/// Edge ramps, blend mask, per-channel gamma and black level of an output.
+fn render_edge_blend(ui: &mut egui::Ui, config: &mut mapmap_core::EdgeBlendConfig) {
+    for (label, zone) in [
+        ("Left", &mut config.left),
+        ("Right", &mut config.right),
+        ("Top", &mut config.top),
+        ("Bottom", &mut config.bottom),
+    ] {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut zone.enabled, label);
+            if zone.enabled {
+                ui.add(egui::Slider::new(&mut zone.width, 0.0..=0.5).text("Width"));
+            }
+        });
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Mask:");
+        let mut path = config.mask.clone().unwrap_or_default();
+        if ui
+            .add(egui::TextEdit::singleline(&mut path).desired_width(160.0))
+            .changed()
+        {
+            config.mask = (!path.is_empty()).then_some(path);
+        }
+        if ui
+            .button("\u{1F4C2}")
+            .on_hover_text("Select grayscale blend mask")
+            .clicked()
+        {
+            if let Some(picked) = rfd::FileDialog::new()
+                .add_filter("Image", &["png", "jpg", "jpeg", "bmp", "tif", "tiff"])
+                .pick_file()
+            {
+                config.mask = Some(picked.display().to_string());
+            }
+        }
+        if config.mask.is_some() && ui.small_button("\u{2716}").clicked() {
+            config.mask = None;
+        }
+    });
+    ui.label(
+        egui::RichText::new("White keeps the image, black hides it; multiplied into the ramps.")
+            .weak()
+            .small(),
+    );
+
+    ui.separator();
+    let mut per_channel = config.channel_gamma.is_some();
+    if ui.checkbox(&mut per_channel, "Per-channel gamma").changed() {
+        config.channel_gamma = per_channel.then(|| config.gamma_rgb());
+    }
+    match &mut config.channel_gamma {
+        Some(gamma) => {
+            for (value, label) in gamma.iter_mut().zip(["Gamma R", "Gamma G", "Gamma B"]) {
+                ui.add(egui::Slider::new(value, 1.0..=3.0).text(label));
+            }
+        }
+        None => {
+            ui.add(egui::Slider::new(&mut config.gamma, 1.0..=3.0).text("Gamma"));
+        }
+    }
+
+    ui.separator();
+    ui.label("Black level (outside overlaps):");
+    ui.horizontal(|ui| {
+        for (value, prefix) in config.black_level.iter_mut().zip(["R: ", "G: ", "B: "]) {
+            ui.add(
+                egui::DragValue::new(value)
+                    .speed(0.001)
+                    .range(0.0..=0.25)
+                    .prefix(prefix),
+            );
+        }
+    });
+    ui.add(egui::Slider::new(&mut config.black_level_feather, 0.0..=1.0).text("Feather"));
+}
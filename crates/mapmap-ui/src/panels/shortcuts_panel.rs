@@ -250,7 +250,8 @@ impl ShortcutsPanel {
     }
 }
 
-fn to_mapmap_key(key: egui::Key) -> Option<mapmap_control::shortcuts::Key> {
+/// Convert an egui key to a shortcut key
+pub fn to_mapmap_key(key: egui::Key) -> Option<mapmap_control::shortcuts::Key> {
     use egui::Key::*;
     use mapmap_control::shortcuts::Key as Mk;
 
@@ -324,11 +325,43 @@ fn to_mapmap_key(key: egui::Key) -> Option<mapmap_control::shortcuts::Key> {
     }
 }
 
-fn to_mapmap_modifiers(modifiers: egui::Modifiers) -> mapmap_control::shortcuts::Modifiers {
+/// Convert egui modifiers to shortcut modifiers
+///
+/// Shortcuts bind the platform's command key as `ctrl`: egui reports it as
+/// `command`, which is Ctrl on Linux and Windows and Cmd on macOS. `meta` is
+/// only set for a Cmd key that isn't the command key.
+pub fn to_mapmap_modifiers(modifiers: egui::Modifiers) -> mapmap_control::shortcuts::Modifiers {
     mapmap_control::shortcuts::Modifiers {
-        ctrl: modifiers.ctrl,
+        ctrl: modifiers.ctrl || modifiers.command,
         alt: modifiers.alt,
         shift: modifiers.shift,
-        meta: modifiers.mac_cmd || modifiers.command,
+        meta: modifiers.mac_cmd && !modifiers.command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mapmap_control::shortcuts::{Action, ShortcutContext};
+
+    #[test]
+    fn test_command_key_matches_ctrl_bindings() {
+        let bindings = KeyBindings::new();
+        let key = to_mapmap_key(egui::Key::D).unwrap();
+
+        // Ctrl on Linux and Windows, Cmd on macOS
+        let linux_ctrl = egui::Modifiers::CTRL | egui::Modifiers::COMMAND;
+        let mac_cmd = egui::Modifiers::MAC_CMD | egui::Modifiers::COMMAND;
+        for modifiers in [linux_ctrl, mac_cmd] {
+            let modifiers = to_mapmap_modifiers(modifiers);
+            assert!(!modifiers.meta);
+            assert_eq!(
+                bindings.find_action_in_context(key, &modifiers, ShortcutContext::ModuleCanvas),
+                Some(Action::DuplicateSelectedParts)
+            );
+        }
+
+        let plain = to_mapmap_modifiers(egui::Modifiers::NONE);
+        assert!(plain.is_empty());
     }
 }
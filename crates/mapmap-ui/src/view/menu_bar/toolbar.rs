@@ -70,7 +70,48 @@ pub fn show(ui: &mut egui::Ui, ui_state: &mut AppUI) {
                             .strong(),
                     ))
                     .clone()
-                    .on_hover_text("Erkanntes Tempo (Beats per Minute)");
+                    .on_hover_text(format!(
+                        "Tempo (Beats per Minute), Quelle: {}",
+                        ui_state
+                            .tempo_source
+                            .map_or("keine", |source| source.name())
+                    ));
+
+                    let tap = ui
+                        .button("TAP")
+                        .on_hover_text("Tempo eintippen\nRechtsklick: Tap-Tempo verwerfen");
+                    if tap.clicked() {
+                        actions.push(UIAction::TapTempo);
+                    }
+                    if tap.secondary_clicked() {
+                        actions.push(UIAction::ClearTapTempo);
+                    }
+
+                    let grid = &ui_state.beat_grid;
+                    if grid.running {
+                        let resync = ui
+                            .add(
+                                egui::Label::new(
+                                    egui::RichText::new(format!(
+                                        "{}.{}",
+                                        grid.bar + 1,
+                                        grid.beat + 1
+                                    ))
+                                    .size(16.0)
+                                    .monospace(),
+                                )
+                                .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text(format!(
+                                "Beat-Raster: Takt {} von Phrase {}\nKlicken: jetzt ist die Eins",
+                                grid.bar_in_phrase + 1,
+                                grid.phrase + 1
+                            ))
+                            .clicked();
+                        if resync {
+                            actions.push(UIAction::ResyncTempo);
+                        }
+                    }
                     ui.separator();
                 }
 
@@ -1,14 +1,17 @@
 //! UI and Node action processing.
 
 use crate::app::core::app_struct::App;
-use crate::orchestration::node_logic::load_project_file;
+use crate::orchestration::color_match;
+use crate::orchestration::cues::capture_module_state;
+use crate::orchestration::node_logic::{load_project_file, save_project_file};
+use crate::orchestration::structured_light;
 use anyhow::Result;
-use mapmap_io::save_project;
+use mapmap_control::shortcuts::Action;
 use mapmap_mcp::McpAction;
 use mapmap_ui::{NodeEditorAction, UIAction};
 use rfd::FileDialog;
 use std::path::PathBuf;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 
 /// Handle global UI actions
 pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
@@ -45,13 +48,44 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
                     app.ui_state.selected_audio_device
                 );
             }
+            UIAction::UseSystemAudioInput => {
+                if let Err(e) = crate::orchestration::audio::use_system_input(app) {
+                    error!("Failed to switch to live audio input: {}", e);
+                }
+            }
+            UIAction::SelectAudioFile(path_str) => {
+                let path = if path_str.is_empty() {
+                    FileDialog::new()
+                        .add_filter("Audio", &["wav", "flac", "mp3", "ogg", "m4a", "aac"])
+                        .add_filter("Video", &["mp4", "mov", "mkv"])
+                        .pick_file()
+                } else {
+                    Some(PathBuf::from(path_str))
+                };
+                if let Some(path) = path {
+                    crate::orchestration::audio::use_audio_file(app, &path);
+                }
+            }
+            UIAction::UseVideoAudio(module_id, part_id) => {
+                if let Err(e) =
+                    crate::orchestration::audio::use_video_audio(app, module_id, part_id)
+                {
+                    error!("Failed to analyze soundtrack: {}", e);
+                }
+            }
             UIAction::UpdateAudioConfig(cfg) => {
                 app.state.audio_config = cfg.clone();
+                app.audio_router.configure(&cfg);
+                app.tempo.config = cfg.tempo.clone();
+                app.tempo.grid_mut().config = cfg.beat_grid.clone();
                 app.audio_analyzer.update_config(cfg);
                 app.state.dirty = true;
                 // Persistence fix for MF-035
                 let _ = app.ui_state.user_config.save();
             }
+            UIAction::TapTempo => app.tempo.tap(),
+            UIAction::ClearTapTempo => app.tempo.clear_tap(),
+            UIAction::ResyncTempo => app.tempo.resync(),
             // Settings
             UIAction::SetTargetFps(fps) => {
                 app.ui_state.user_config.target_fps = Some(fps);
@@ -172,7 +206,7 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
                     .set_file_name("project.mflow")
                     .save_file()
                 {
-                    if let Err(e) = save_project(&app.state, &path) {
+                    if let Err(e) = save_project_file(app, &path) {
                         error!("Failed to save project: {}", e);
                     } else {
                         info!("Project saved to {:?}", path);
@@ -195,7 +229,7 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
                 };
 
                 if !path.as_os_str().is_empty() {
-                    if let Err(e) = save_project(&app.state, &path) {
+                    if let Err(e) = save_project_file(app, &path) {
                         error!("Failed to save project: {}", e);
                     } else {
                         info!("Project saved to {:?}", path);
@@ -452,12 +486,31 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
             UIAction::ConnectHue => {
                 info!("Connecting to Philips Hue Bridge...");
                 let ui_hue = &app.ui_state.user_config.hue_config;
-                let control_hue = mapmap_control::hue::models::HueConfig {
-                    bridge_ip: ui_hue.bridge_ip.clone(),
-                    username: ui_hue.username.clone(),
-                    client_key: ui_hue.client_key.clone(),
-                    application_id: String::new(),
-                    entertainment_group_id: ui_hue.entertainment_area.clone(),
+                let control_hue = if ui_hue.virtual_bridge {
+                    if app.hue_virtual_bridge.is_none() {
+                        match app.tokio_runtime.block_on(
+                            mapmap_control::hue::mock::MockBridge::start(Default::default()),
+                        ) {
+                            Ok(bridge) => {
+                                info!("Virtual Hue Bridge running at {}", bridge.url());
+                                app.hue_virtual_bridge = Some(bridge);
+                            }
+                            Err(e) => error!("Failed to start virtual Hue Bridge: {}", e),
+                        }
+                    }
+                    match &app.hue_virtual_bridge {
+                        Some(bridge) => bridge.hue_config(),
+                        None => continue,
+                    }
+                } else {
+                    app.hue_virtual_bridge = None;
+                    mapmap_control::hue::models::HueConfig {
+                        bridge_ip: ui_hue.bridge_ip.clone(),
+                        username: ui_hue.username.clone(),
+                        client_key: ui_hue.client_key.clone(),
+                        application_id: String::new(),
+                        entertainment_group_id: ui_hue.entertainment_area.clone(),
+                    }
                 };
                 app.hue_controller.update_config(control_hue);
 
@@ -470,6 +523,7 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
             UIAction::DisconnectHue => {
                 info!("Disconnecting from Philips Hue Bridge...");
                 app.tokio_runtime.block_on(app.hue_controller.disconnect());
+                app.hue_virtual_bridge = None;
             }
             UIAction::DiscoverHueBridges => {
                 info!("Discovering Philips Hue Bridges...");
@@ -519,6 +573,37 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
                     app.state.dirty = true;
                 }
             }
+            UIAction::ShowCalibrationPattern(output_id, frame) => {
+                structured_light::show_pattern(app, output_id, frame);
+            }
+            UIAction::ExportCalibrationPatterns(output_id) => {
+                if let Some(dir) = FileDialog::new().pick_folder() {
+                    match structured_light::export_patterns(app, output_id, &dir) {
+                        Ok(count) => info!("Exported {} calibration patterns to {:?}", count, dir),
+                        Err(e) => error!("Failed to export calibration patterns: {}", e),
+                    }
+                }
+            }
+            UIAction::RunStructuredLightCalibration(module_id) => {
+                if let Some(dir) = FileDialog::new().pick_folder() {
+                    match structured_light::start_calibration(app, module_id, &dir) {
+                        Ok(()) => info!("Calibrating projectors from {:?}...", dir),
+                        Err(e) => error!("Structured light calibration failed: {:#}", e),
+                    }
+                }
+            }
+            UIAction::ShowColorPatch(output_id, rgb) => {
+                color_match::show_patch(app, output_id, rgb);
+            }
+            UIAction::SolveColorMatch => {
+                if let Some(dir) = FileDialog::new().pick_folder() {
+                    let measurements = color_match::stored_measurements(app);
+                    match color_match::solve(app, &measurements, &dir) {
+                        Ok(count) => info!("Color matched {} outputs into {:?}", count, dir),
+                        Err(e) => error!("Color matching failed: {:#}", e),
+                    }
+                }
+            }
             UIAction::CreateGroup => {
                 let count = app.state.layer_manager.len();
                 app.state
@@ -655,6 +740,70 @@ pub fn handle_ui_actions(app: &mut App) -> Result<bool> {
             UIAction::ManualTrigger(_module_id, part_id) => {
                 app.module_evaluator.trigger_node(part_id);
             }
+            UIAction::AddCue => {
+                let cue_list = &mut app.control_manager.cue_list;
+                let id = cue_list.cues().last().map_or(1, |c| c.id + 1);
+                cue_list.add_cue(mapmap_control::cue::Cue::new(id, format!("Cue {}", id)));
+                let state = capture_module_state(
+                    &app.state.module_manager,
+                    &app.state.output_manager,
+                    app.ui_state.module_canvas.active_module_id(),
+                );
+                if let Err(e) = cue_list.store_module_state(id, state) {
+                    error!("Failed to record cue {}: {}", id, e);
+                }
+            }
+            UIAction::RemoveCue(id) => {
+                app.control_manager.cue_list.remove_cue(id);
+            }
+            UIAction::UpdateCue(cue) => {
+                if let Some(existing) = app.control_manager.cue_list.get_cue_mut(cue.id) {
+                    *existing = *cue;
+                }
+            }
+            UIAction::GoCue(id) => {
+                let _ = app.control_manager.cue_list.goto_cue(id, None);
+            }
+            UIAction::NextCue => {
+                let _ = app.control_manager.cue_list.next();
+            }
+            UIAction::PrevCue => {
+                let _ = app.control_manager.cue_list.prev();
+            }
+            UIAction::StopCue => {
+                app.control_manager.cue_list.release();
+            }
+            UIAction::AddCueStack(name) => {
+                app.control_manager.cue_stacks.add_stack(name);
+                app.state.dirty = true;
+            }
+            UIAction::RemoveCueStack(id) => {
+                app.control_manager.cue_stacks.remove_stack(id);
+                app.state.dirty = true;
+            }
+            UIAction::RecordStackCue(stack_id) => {
+                let state = capture_module_state(
+                    &app.state.module_manager,
+                    &app.state.output_manager,
+                    app.ui_state.module_canvas.active_module_id(),
+                );
+                if let Some(stack) = app.control_manager.cue_stacks.get_mut(stack_id) {
+                    let id = stack.list.cues().last().map_or(1, |c| c.id + 1);
+                    stack
+                        .list
+                        .add_cue(mapmap_control::cue::Cue::new(id, format!("Cue {}", id)));
+                    if let Err(e) = stack.list.store_module_state(id, state) {
+                        error!("Failed to record cue {} of stack {}: {}", id, stack_id, e);
+                    }
+                    app.state.dirty = true;
+                }
+            }
+            UIAction::GoCueStack(id) => {
+                let _ = app.control_manager.cue_stacks.go(id);
+            }
+            UIAction::ReleaseCueStack(id) => {
+                let _ = app.control_manager.cue_stacks.release(id);
+            }
             UIAction::TimelineAction(timeline_action) => {
                 use mapmap_ui::TimelineAction;
                 match timeline_action {
@@ -773,6 +922,35 @@ fn handle_node_action(app: &mut App, action: NodeEditorAction) -> Result<()> {
     Ok(())
 }
 
+/// Translate shortcut and macro actions from the control system into UI actions
+pub fn handle_control_actions(app: &mut App) {
+    let actions = std::mem::take(&mut app.control_manager.pending_actions);
+
+    for action in actions {
+        let ui_action = match action {
+            Action::Play => UIAction::Play,
+            Action::Pause => UIAction::Pause,
+            Action::Stop => UIAction::Stop,
+            Action::ToggleFullscreen => UIAction::ToggleFullscreen,
+            Action::ToggleLayerVisibility(id) => {
+                match app.state.layer_manager.get_layer(id as u64) {
+                    Some(layer) => UIAction::SetLayerVisibility(id as u64, !layer.visible),
+                    None => continue,
+                }
+            }
+            Action::SelectLayer(id) => {
+                app.ui_state.selected_layer_id = Some(id as u64);
+                continue;
+            }
+            other => {
+                debug!("Control action not handled by the application: {:?}", other);
+                continue;
+            }
+        };
+        app.ui_state.actions.push(ui_action);
+    }
+}
+
 /// Process pending MCP actions
 pub fn handle_mcp_actions(app: &mut App) {
     while let Ok(action) = app.mcp_receiver.try_recv() {
@@ -11,14 +11,15 @@ use mapmap_control::hue::controller::HueController;
 use mapmap_control::midi::MidiInputHandler;
 use mapmap_control::ControlManager;
 use mapmap_core::{
-    audio::backend::cpal_backend::CpalBackend, media_library::MediaLibrary, module::ModulePartId,
-    AppState, History, ModuleEvaluator, RenderOp,
+    audio::backend::AudioBackend, media_library::MediaLibrary, module::ModulePartId, AppState,
+    History, ModuleEvaluator, RenderOp,
 };
 use mapmap_mcp::McpAction;
 // use mapmap_media::player::VideoPlayer;
 use mapmap_render::{
-    ColorCalibrationRenderer, Compositor, EdgeBlendRenderer, EffectChainRenderer, MeshBufferCache,
-    MeshRenderer, OscillatorRenderer, QuadRenderer, TexturePool, WgpuBackend,
+    ColorCalibrationRenderer, Compositor, DomeRenderer, EdgeBlendRenderer, EffectChainRenderer,
+    MaskRenderer, MeshBufferCache, MeshRenderer, OscillatorRenderer, OutputTransformRenderer,
+    QuadRenderer, SliceRenderer, TexturePool, WgpuBackend,
 };
 use mapmap_ui::AppUI;
 use std::collections::{HashMap, VecDeque};
@@ -71,6 +72,8 @@ pub struct App {
     pub preview_effect_chain_renderer: EffectChainRenderer,
     /// The mesh renderer.
     pub mesh_renderer: MeshRenderer,
+    /// Mesh renderer drawing into the linear float composition of outputs
+    pub hdr_mesh_renderer: MeshRenderer,
     /// Cache for mesh GPU buffers
     pub mesh_buffer_cache: MeshBufferCache,
     /// Quad renderer for passthrough.
@@ -83,10 +86,22 @@ pub struct App {
     pub state: AppState,
     /// Undo/Redo history
     pub history: History,
-    /// The audio backend.
-    pub audio_backend: Option<CpalBackend>,
+    /// The audio backend (live input or a decoded file).
+    pub audio_backend: Option<Box<dyn AudioBackend>>,
+    /// Media player ((ModuleID, PartID)) whose position the audio backend follows.
+    pub audio_sync_player: Option<(ModulePartId, ModulePartId)>,
+    /// Audio file decoding on a worker thread, replacing the backend when done.
+    pub audio_file_load: Option<crate::orchestration::audio::AudioFileLoad>,
     /// The audio analyzer.
     pub audio_analyzer: mapmap_core::audio::AudioAnalyzer,
+    /// Named analyzers fed by individual input channels.
+    pub audio_router: mapmap_core::audio::routing::AudioRouter,
+    /// Global tempo clock driving the beat grid for all beat-synced features.
+    pub tempo: mapmap_core::audio::tempo::TempoService,
+    /// Audio reactive parameter mappings, fed by the main input or a channel analyzer.
+    pub audio_reactive: mapmap_core::AudioReactiveController,
+    /// Project-wide trigger states (audio, beat grid and timed triggers).
+    pub trigger_system: mapmap_core::trigger_system::TriggerSystem,
     /// List of available audio devices.
     pub audio_devices: Vec<String>,
     /// The egui context.
@@ -111,6 +126,11 @@ pub struct App {
     pub action_sender: crossbeam_channel::Sender<McpAction>,
     /// Unified control manager
     pub control_manager: ControlManager,
+    /// Cue state (main list, merged module state) that was last written
+    pub applied_cue_state: (
+        mapmap_control::cue::CueState,
+        mapmap_control::cue::ModuleCueState,
+    ),
     /// Flag to track if exit was requested
     pub exit_requested: bool,
     /// Flag to track if restart was requested
@@ -179,10 +199,48 @@ pub struct App {
     pub edge_blend_renderer: Option<EdgeBlendRenderer>,
     /// Color calibration renderer for output windows
     pub color_calibration_renderer: Option<ColorCalibrationRenderer>,
+    /// Dome resampling renderer for output windows
+    pub dome_renderer: Option<DomeRenderer>,
+    /// Output slicing renderer for output windows
+    pub slice_renderer: Option<SliceRenderer>,
+    /// HDR/SDR output transform renderer for output windows
+    pub output_transform_renderer: Option<OutputTransformRenderer>,
+    /// Layer mask renderer
+    pub mask_renderer: Option<MaskRenderer>,
     /// Cache for edge blending resources (OutputID -> (UniformBuffer, UniformBindGroup, ConfigHash))
     pub edge_blend_cache: std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
     /// Cache for edge blending texture bind groups (OutputID -> TextureBindGroup)
     pub edge_blend_texture_cache: std::collections::HashMap<u64, wgpu::BindGroup>,
+    /// Blend mask image uploaded per output (OutputID -> (Path, Loaded))
+    pub edge_blend_masks: std::collections::HashMap<u64, (String, bool)>,
+    /// Cache for color calibration resources (OutputID -> (UniformBuffer, UniformBindGroup, ConfigHash))
+    pub color_calibration_cache:
+        std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
+    /// Correction LUT uploaded per output (OutputID -> (Path, LUT size if loaded))
+    pub color_calibration_luts: std::collections::HashMap<u64, (String, Option<u32>)>,
+    /// Dome warp meshes per output (OutputID -> (Config, Output Size, Buffers))
+    pub dome_cache: std::collections::HashMap<
+        u64,
+        (
+            mapmap_core::DomeConfig,
+            (u32, u32),
+            mapmap_render::DomeWarpBuffers,
+        ),
+    >,
+    /// Slice quads per output (OutputID -> (Layout, Output Size, Buffers))
+    pub slice_cache: std::collections::HashMap<
+        u64,
+        (
+            mapmap_core::SliceLayout,
+            (u32, u32),
+            mapmap_render::SliceBuffers,
+        ),
+    >,
+    /// Cache for output transform resources (OutputID -> (UniformBuffer, UniformBindGroup, ConfigHash))
+    pub output_transform_cache:
+        std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
+    /// Mask chains rasterized into coverage textures ((ModuleID, LayerPartID) -> Mask)
+    pub mask_textures: HashMap<(u64, ModulePartId), crate::app::loops::render::LayerMask>,
     /// Temporary textures for output rendering (OutputID -> Texture)
     pub output_temp_textures: std::collections::HashMap<u64, wgpu::Texture>,
     /// Cache for egui textures to avoid re-registering every frame ((ModuleId, PartId) -> (EguiId, View))
@@ -196,6 +254,19 @@ pub struct App {
     pub preview_quad_buffers: (wgpu::Buffer, wgpu::Buffer, u32),
     /// Philips Hue Controller
     pub hue_controller: HueController,
+    /// Virtual Hue bridge used instead of hardware
+    pub hue_virtual_bridge: Option<mapmap_control::hue::mock::MockBridge>,
+    /// Readbacks of spatial Hue and light outputs (OutputPartID -> Readback)
+    pub light_readbacks: HashMap<ModulePartId, crate::app::loops::render::LightReadback>,
+    /// Sinks and effects of light outputs (OutputPartID -> Runtime)
+    pub light_outputs: HashMap<ModulePartId, crate::orchestration::lights::LightOutputRuntime>,
+    /// Gray-code pattern shown instead of content during structured-light calibration
+    pub structured_light: Option<crate::orchestration::structured_light::PatternSession>,
+    /// Structured-light calibration decoding on a worker thread
+    pub structured_light_calibration:
+        Option<Receiver<crate::orchestration::structured_light::CalibrationResult>>,
+    /// Test patch shown instead of content during color matching (OutputID, RGB)
+    pub color_patch: Option<(u64, [f32; 3])>,
     /// Tokio runtime for async operations
     pub tokio_runtime: tokio::runtime::Runtime,
     /// Media Manager UI
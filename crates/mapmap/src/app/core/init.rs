@@ -19,8 +19,9 @@ use mapmap_core::{
 use mapmap_io::load_project;
 use mapmap_mcp::McpServer;
 use mapmap_render::{
-    ColorCalibrationRenderer, Compositor, EdgeBlendRenderer, EffectChainRenderer, MeshBufferCache,
-    MeshRenderer, OscillatorRenderer, QuadRenderer, TexturePool, WgpuBackend,
+    ColorCalibrationRenderer, Compositor, DomeRenderer, EdgeBlendRenderer, EffectChainRenderer,
+    MaskRenderer, MeshBufferCache, MeshRenderer, OscillatorRenderer, OutputTransformRenderer,
+    QuadRenderer, SliceRenderer, TexturePool, WgpuBackend, HDR_FORMAT,
 };
 use mapmap_ui::AppUI;
 use std::collections::{HashMap, VecDeque};
@@ -43,24 +44,22 @@ impl App {
 
         // Initialize renderers
         let texture_pool = TexturePool::new(backend.device.clone());
-        let compositor = Compositor::new(backend.device.clone(), backend.surface_format())?;
-        let effect_chain_renderer = EffectChainRenderer::new(
-            backend.device.clone(),
-            backend.queue.clone(),
-            backend.surface_format(),
-        )?;
-        let preview_effect_chain_renderer = EffectChainRenderer::new(
-            backend.device.clone(),
-            backend.queue.clone(),
-            backend.surface_format(),
-        )?;
+        // Layers and their effect chains carry linear light in float textures
+        let compositor = Compositor::new(backend.device.clone(), HDR_FORMAT)?;
+        let effect_chain_renderer =
+            EffectChainRenderer::new(backend.device.clone(), backend.queue.clone(), HDR_FORMAT)?;
+        let preview_effect_chain_renderer =
+            EffectChainRenderer::new(backend.device.clone(), backend.queue.clone(), HDR_FORMAT)?;
         let mesh_renderer = MeshRenderer::new(backend.device.clone(), backend.surface_format())?;
+        // Outputs are composed and post-processed in linear float; only the
+        // output transform writes to the surface
+        let hdr_mesh_renderer = MeshRenderer::new(backend.device.clone(), HDR_FORMAT)?;
         let mesh_buffer_cache = MeshBufferCache::new();
         let quad_renderer = QuadRenderer::new(&backend.device, backend.surface_format())?;
 
         // Initialize advanced output renderers
         let edge_blend_renderer =
-            EdgeBlendRenderer::new(backend.device.clone(), backend.surface_format())
+            EdgeBlendRenderer::new(backend.device.clone(), &backend.queue, HDR_FORMAT)
                 .map_err(|e| {
                     tracing::warn!("Failed to create edge blend renderer: {}", e);
                     e
@@ -68,13 +67,43 @@ impl App {
                 .ok();
 
         let color_calibration_renderer =
-            ColorCalibrationRenderer::new(backend.device.clone(), backend.surface_format())
+            ColorCalibrationRenderer::new(backend.device.clone(), HDR_FORMAT)
                 .map_err(|e| {
                     tracing::warn!("Failed to create color calibration renderer: {}", e);
                     e
                 })
                 .ok();
 
+        let dome_renderer = DomeRenderer::new(backend.device.clone(), HDR_FORMAT)
+            .map_err(|e| {
+                tracing::warn!("Failed to create dome renderer: {}", e);
+                e
+            })
+            .ok();
+
+        let slice_renderer = SliceRenderer::new(backend.device.clone(), HDR_FORMAT)
+            .map_err(|e| {
+                tracing::warn!("Failed to create slice renderer: {}", e);
+                e
+            })
+            .ok();
+
+        let output_transform_renderer =
+            OutputTransformRenderer::new(backend.device.clone(), backend.surface_format())
+                .map_err(|e| {
+                    tracing::warn!("Failed to create output transform renderer: {}", e);
+                    e
+                })
+                .ok();
+
+        // Masked sources stay in linear light like the effect chain output
+        let mask_renderer = MaskRenderer::new(backend.device.clone(), HDR_FORMAT)
+            .map_err(|e| {
+                tracing::warn!("Failed to create mask renderer: {}", e);
+                e
+            })
+            .ok();
+
         let mut window_manager = WindowManager::new();
 
         // Create Tokio runtime
@@ -119,14 +148,14 @@ impl App {
                 "layer_pong_0",
                 width,
                 height,
-                backend.surface_format(),
+                HDR_FORMAT,
                 wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
             ),
             texture_pool.create(
                 "layer_pong_1",
                 width,
                 height,
-                backend.surface_format(),
+                HDR_FORMAT,
                 wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
             ),
         ];
@@ -327,12 +356,12 @@ impl App {
         // Set the selected device in UI state
         ui_state.selected_audio_device = device_to_use.clone();
 
-        let mut audio_backend: Option<CpalBackend> = if is_automation {
+        let mut audio_backend: Option<Box<dyn AudioBackend>> = if is_automation {
             info!("Automation mode: Skipping audio backend initialization");
             None
         } else {
             match CpalBackend::new(device_to_use) {
-                Ok(backend) => Some(backend),
+                Ok(backend) => Some(Box::new(backend)),
                 Err(e) => {
                     error!("Failed to initialize audio backend: {}", e);
                     None
@@ -349,6 +378,11 @@ impl App {
 
         // Initialize Audio Analyzer (wrapper around V2 for compatibility)
         let audio_analyzer = mapmap_core::audio::AudioAnalyzer::new(state.audio_config.clone());
+        let audio_router = mapmap_core::audio::routing::AudioRouter::new(&state.audio_config);
+        let tempo = mapmap_core::audio::tempo::TempoService::new(
+            state.audio_config.tempo.clone(),
+            state.audio_config.beat_grid.clone(),
+        );
 
         // Start MCP Server in a separate thread
         let (mcp_sender, mcp_receiver) = unbounded();
@@ -434,21 +468,39 @@ impl App {
 
         // Initialize Hue Controller
         let ui_hue_conf = &ui_state.user_config.hue_config;
-        let control_hue_conf = mapmap_control::hue::models::HueConfig {
-            bridge_ip: ui_hue_conf.bridge_ip.clone(),
-            username: ui_hue_conf.username.clone(),
-            client_key: ui_hue_conf.client_key.clone(),
-            application_id: String::new(), // Will be fetched if needed
-            entertainment_group_id: ui_hue_conf.entertainment_area.clone(),
+        let hue_auto_connect = !is_automation
+            && ui_hue_conf.auto_connect
+            && (ui_hue_conf.virtual_bridge || !ui_hue_conf.bridge_ip.is_empty());
+
+        // The virtual bridge replaces the configured one and is only started when connecting
+        let mut hue_virtual_bridge = None;
+        if hue_auto_connect && ui_hue_conf.virtual_bridge {
+            match tokio_runtime.block_on(mapmap_control::hue::mock::MockBridge::start(
+                Default::default(),
+            )) {
+                Ok(bridge) => {
+                    info!("Virtual Hue Bridge running at {}", bridge.url());
+                    hue_virtual_bridge = Some(bridge);
+                }
+                Err(e) => warn!("Failed to start virtual Hue Bridge: {}", e),
+            }
+        }
+
+        let control_hue_conf = match &hue_virtual_bridge {
+            Some(bridge) => bridge.hue_config(),
+            None => mapmap_control::hue::models::HueConfig {
+                bridge_ip: ui_hue_conf.bridge_ip.clone(),
+                username: ui_hue_conf.username.clone(),
+                client_key: ui_hue_conf.client_key.clone(),
+                application_id: String::new(), // Will be fetched if needed
+                entertainment_group_id: ui_hue_conf.entertainment_area.clone(),
+            },
         };
 
         let mut hue_controller = HueController::new(control_hue_conf);
 
-        // Try to connect if IP is set and auto-connect is enabled
-        if !is_automation
-            && !ui_state.user_config.hue_config.bridge_ip.is_empty()
-            && ui_state.user_config.hue_config.auto_connect
-        {
+        // Try to connect if a bridge is set and auto-connect is enabled
+        if hue_auto_connect && (hue_virtual_bridge.is_some() || !ui_hue_conf.virtual_bridge) {
             info!("Initializing Hue Controller...");
             if let Err(e) = tokio_runtime.block_on(hue_controller.connect()) {
                 warn!("Hue Controller initial connection failed: {}", e);
@@ -457,7 +509,11 @@ impl App {
             info!("Automation mode: Skipping Hue Controller connection");
         }
 
-        let control_manager = ControlManager::new();
+        let mut control_manager = ControlManager::new();
+        control_manager.scheduler = crate::orchestration::schedule::load_scheduler();
+        if let Some(path) = &autosave_path {
+            control_manager.cue_stacks = crate::orchestration::cues::load_cue_stacks(path);
+        }
         let sys_info = sysinfo::System::new_all();
         let (dummy_texture, dummy_view) = {
             let texture = backend.device.create_texture(&wgpu::TextureDescriptor {
@@ -517,6 +573,7 @@ impl App {
             effect_chain_renderer,
             preview_effect_chain_renderer,
             mesh_renderer,
+            hdr_mesh_renderer,
             mesh_buffer_cache,
             _quad_renderer: quad_renderer,
             _composite_texture: composite_texture,
@@ -524,7 +581,13 @@ impl App {
             state,
             history: mapmap_core::History::default(),
             audio_backend,
+            audio_sync_player: None,
+            audio_file_load: None,
             audio_analyzer,
+            audio_router,
+            tempo,
+            audio_reactive: mapmap_core::AudioReactiveController::new(),
+            trigger_system: mapmap_core::trigger_system::TriggerSystem::new(),
             audio_devices,
             egui_context,
             egui_state,
@@ -537,6 +600,7 @@ impl App {
             mcp_receiver,
             action_sender,
             control_manager,
+            applied_cue_state: Default::default(),
             exit_requested: false,
             restart_requested: false,
             oscillator_renderer,
@@ -583,14 +647,31 @@ impl App {
             render_ops: Vec::new(),
             edge_blend_renderer,
             color_calibration_renderer,
+            dome_renderer,
+            slice_renderer,
+            output_transform_renderer,
+            mask_renderer,
             edge_blend_cache: std::collections::HashMap::new(),
             edge_blend_texture_cache: std::collections::HashMap::new(),
+            edge_blend_masks: std::collections::HashMap::new(),
+            color_calibration_cache: std::collections::HashMap::new(),
+            color_calibration_luts: std::collections::HashMap::new(),
+            dome_cache: std::collections::HashMap::new(),
+            slice_cache: std::collections::HashMap::new(),
+            output_transform_cache: std::collections::HashMap::new(),
+            mask_textures: std::collections::HashMap::new(),
             output_temp_textures: std::collections::HashMap::new(),
             preview_texture_cache: HashMap::new(),
             output_preview_cache: HashMap::new(),
             video_diagnostic_log_times: HashMap::new(),
             preview_quad_buffers,
             hue_controller,
+            hue_virtual_bridge,
+            light_readbacks: HashMap::new(),
+            light_outputs: HashMap::new(),
+            structured_light: None,
+            structured_light_calibration: None,
+            color_patch: None,
             tokio_runtime,
             media_manager_ui: MediaManagerUI::new(),
             media_library: {
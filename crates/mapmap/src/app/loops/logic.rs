@@ -1,20 +1,36 @@
-use crate::app::actions::{handle_mcp_actions, handle_ui_actions};
+use crate::app::actions::{handle_control_actions, handle_mcp_actions, handle_ui_actions};
 use crate::app::core::app_struct::App;
+use crate::orchestration::audio::poll_audio_file;
+use crate::orchestration::cues::sync_cue_state;
 use crate::orchestration::evaluation::perform_evaluation;
+use crate::orchestration::lights::update_light_outputs;
 use crate::orchestration::media::{sync_media_players, update_media_players};
+use crate::orchestration::node_logic::save_project_file;
 use crate::orchestration::outputs::sync_output_windows;
+use crate::orchestration::schedule::apply_scheduled_actions;
+use crate::orchestration::structured_light::poll_calibration;
+use crate::orchestration::tempo::{apply_tempo_controls, update_tempo};
 use anyhow::Result;
 use mapmap_core::audio::backend::AudioBackend;
-use mapmap_io::save_project;
+use mapmap_core::audio::routing::downmix;
 use std::collections::HashSet;
 
 /// Global update loop (physics/logic), independent of render rate per window.
 pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32) -> Result<()> {
-    // 1. Process internal MCP actions first
+    // 1. Process internal MCP actions and shortcut/macro actions first
     handle_mcp_actions(app);
+    handle_control_actions(app);
 
     // 2. Handle UI actions and check if they requested a structural sync
     let ui_needs_sync = handle_ui_actions(app).unwrap_or(false);
+    match poll_calibration(app) {
+        Some(Ok(count)) => tracing::info!("Calibrated {} projectors", count),
+        Some(Err(e)) => tracing::error!("Structured light calibration failed: {:#}", e),
+        None => {}
+    }
+    if let Some(Err(e)) = poll_audio_file(app) {
+        tracing::error!("Failed to load audio file: {:#}", e);
+    }
 
     // 3. Get all module IDs
     let all_module_ids: Vec<u64> = app
@@ -60,6 +76,9 @@ pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32)
 
     // --- Control System Update ---
     let (midi_events, osc_packets) = app.control_manager.update();
+    sync_cue_state(app);
+    apply_scheduled_actions(app);
+    apply_tempo_controls(app);
 
     // Update shared media state with active events for trigger nodes
     {
@@ -108,11 +127,21 @@ pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32)
     }
 
     // 5. Audio Analysis Update
+    crate::orchestration::audio::sync_audio_position(app);
     let timestamp = app.start_time.elapsed().as_secs_f64();
     if let Some(backend) = &mut app.audio_backend {
+        let channels = backend.channels();
+        app.ui_state.audio_input_channels = channels;
         let samples = backend.get_samples();
         if !samples.is_empty() {
-            app.audio_analyzer.process_samples(&samples, timestamp);
+            // The main analyzer hears all channels; routes pick their own
+            if channels > 1 {
+                let mono = downmix(&samples, channels, &[]);
+                app.audio_analyzer.process_samples(&mono, timestamp);
+            } else {
+                app.audio_analyzer.process_samples(&samples, timestamp);
+            }
+            app.audio_router.process(&samples, channels, timestamp);
         }
     }
 
@@ -120,8 +149,29 @@ pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32)
     let analysis_v1 = app.audio_analyzer.get_latest_analysis();
     let analysis_v2 = app.audio_analyzer.v2.get_latest_analysis();
 
+    // One tempo clock drives the beat grid for all beat-synced features
+    update_tempo(app, &analysis_v2, dt);
+
+    // Beats of the shared clock complete macro waits and start beat-bound macros
+    if app.tempo.state().beat() {
+        app.control_manager
+            .notify_macro_trigger(&mapmap_control::shortcuts::MacroTrigger::Beat);
+    }
+
     // Update evaluator with V2 analysis (9 bands)
     app.module_evaluator.update_audio(&analysis_v2);
+    app.module_evaluator
+        .update_audio_channels(app.audio_router.analyses_v2());
+    crate::orchestration::audio::update_audio_reactive(app, &analysis_v1, timestamp, dt);
+    let canvas_names = &app.ui_state.module_canvas.audio_analyzer_names;
+    if !app
+        .audio_router
+        .names()
+        .eq(canvas_names.iter().map(String::as_str))
+    {
+        app.ui_state.module_canvas.audio_analyzer_names =
+            app.audio_router.names().map(str::to_string).collect();
+    }
 
     // 6. Media & Animation Updates
     sync_media_players(app);
@@ -131,10 +181,10 @@ pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32)
     // 7. Graph Evaluation & Bevy Sync (MODULARIZED)
     let graph_dirty = app.state.module_manager.graph_revision != app.last_graph_revision;
     perform_evaluation(app, &modules_for_eval, &analysis_v1, graph_dirty);
+    update_light_outputs(app, &modules_for_eval, &analysis_v2, dt);
 
     // 8. UI State Sync
     app.ui_state.current_audio_level = analysis_v1.rms_volume;
-    app.ui_state.current_bpm = analysis_v1.tempo_bpm;
     app.ui_state
         .dashboard
         .set_audio_analysis(analysis_v1.clone());
@@ -184,7 +234,7 @@ pub fn update(app: &mut App, elwt: &winit::event_loop::ActiveEventLoop, dt: f32)
                 dirs::data_local_dir().map(|p| p.join("MapFlow").join("autosave.mflow"))
             {
                 let _ = std::fs::create_dir_all(path.parent().unwrap());
-                let _ = save_project(&app.state, &path);
+                let _ = save_project_file(app, &path);
             }
         }
         app.last_autosave = std::time::Instant::now();
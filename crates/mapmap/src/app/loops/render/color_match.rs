@@ -0,0 +1,30 @@
+//! Test patches shown on an output during color matching.
+
+/// Fills the output with a test patch. The patch is a drive value like the
+/// content colors the correction LUT receives, so it is not converted.
+pub(crate) fn render_patch(
+    encoder: &mut wgpu::CommandEncoder,
+    view: &wgpu::TextureView,
+    [r, g, b]: [f32; 3],
+) {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Color Match Patch Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            depth_slice: None,
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color {
+                    r: r as f64,
+                    g: g as f64,
+                    b: b as f64,
+                    a: 1.0,
+                }),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+}
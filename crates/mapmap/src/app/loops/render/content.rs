@@ -15,10 +15,38 @@ pub(crate) struct RenderContext<'a> {
     pub output_manager: &'a mapmap_core::output::OutputManager,
     pub edge_blend_renderer: &'a Option<mapmap_render::EdgeBlendRenderer>,
     pub color_calibration_renderer: &'a Option<mapmap_render::ColorCalibrationRenderer>,
+    pub dome_renderer: &'a Option<mapmap_render::DomeRenderer>,
+    pub slice_renderer: &'a Option<mapmap_render::SliceRenderer>,
+    pub output_transform_renderer: &'a Option<mapmap_render::OutputTransformRenderer>,
+    pub mask_renderer: &'a Option<mapmap_render::MaskRenderer>,
     pub edge_blend_cache:
         &'a mut std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
     pub edge_blend_texture_cache: &'a mut std::collections::HashMap<u64, wgpu::BindGroup>,
+    pub edge_blend_masks: &'a mut std::collections::HashMap<u64, (String, bool)>,
+    pub color_calibration_cache:
+        &'a mut std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
+    pub color_calibration_luts: &'a mut std::collections::HashMap<u64, (String, Option<u32>)>,
+    pub dome_cache: &'a mut std::collections::HashMap<
+        u64,
+        (
+            mapmap_core::DomeConfig,
+            (u32, u32),
+            mapmap_render::DomeWarpBuffers,
+        ),
+    >,
+    pub slice_cache: &'a mut std::collections::HashMap<
+        u64,
+        (
+            mapmap_core::SliceLayout,
+            (u32, u32),
+            mapmap_render::SliceBuffers,
+        ),
+    >,
+    pub output_transform_cache:
+        &'a mut std::collections::HashMap<u64, (wgpu::Buffer, wgpu::BindGroup, u64)>,
+    pub mask_textures: &'a mut std::collections::HashMap<(u64, u64), LayerMask>,
     pub mesh_renderer: &'a mut mapmap_render::MeshRenderer,
+    pub hdr_mesh_renderer: &'a mut mapmap_render::MeshRenderer,
     pub effect_chain_renderer: &'a mut mapmap_render::EffectChainRenderer,
     pub preview_effect_chain_renderer: &'a mut mapmap_render::EffectChainRenderer,
     pub shader_graph_manager: &'a mapmap_render::ShaderGraphManager,
@@ -44,7 +72,6 @@ pub(crate) fn render_content(
 ) -> Result<()> {
     let device = ctx.device;
     let queue = ctx.queue;
-    let mesh_renderer = ctx.mesh_renderer;
     let egui_renderer = ctx.egui_renderer;
     let video_log_times = ctx.video_diagnostic_log_times;
     let is_preview_output = (output_id & PREVIEW_FLAG) != 0;
@@ -107,21 +134,50 @@ pub(crate) fn render_content(
     }
 
     let output_config_opt = ctx.output_manager.get_output(real_output_id).cloned();
+    // HDR encodings need a target that carries them; sRGB window surfaces
+    // and previews get the sRGB encoding instead
+    let hdr_target = matches!(
+        view.texture().format(),
+        wgpu::TextureFormat::Rgba16Float | wgpu::TextureFormat::Rgb10a2Unorm
+    );
+    let output_transform = output_config_opt.as_ref().map(|cfg| {
+        if hdr_target {
+            cfg.output_transform.clone()
+        } else {
+            cfg.output_transform.for_sdr_target()
+        }
+    });
+    let use_output_transform = output_transform
+        .as_ref()
+        .is_some_and(|transform| !transform.is_identity() || hdr_target)
+        && ctx.output_transform_renderer.is_some();
     let use_edge_blend = output_config_opt
         .as_ref()
-        .map(|cfg| {
-            cfg.edge_blend.left.enabled
-                || cfg.edge_blend.right.enabled
-                || cfg.edge_blend.top.enabled
-                || cfg.edge_blend.bottom.enabled
-        })
+        .map(|cfg| cfg.edge_blend.is_active())
         .unwrap_or(false)
         && ctx.edge_blend_renderer.is_some();
-    // Currently we only support edge blending for post-processing safely.
-    // Color calibration is temporarily ignored here to prevent black screen regressions.
-    let _use_color_calib = output_config_opt.is_some() && ctx.color_calibration_renderer.is_some();
+    // Outputs with neutral calibration skip the pass entirely
+    let use_color_calib = output_config_opt
+        .as_ref()
+        .map(|cfg| cfg.color_calibration.is_active())
+        .unwrap_or(false)
+        && ctx.color_calibration_renderer.is_some();
+    let use_dome = output_config_opt
+        .as_ref()
+        .map(|cfg| cfg.dome.is_some())
+        .unwrap_or(false)
+        && ctx.dome_renderer.is_some();
+    let use_slicing = output_config_opt
+        .as_ref()
+        .map(|cfg| cfg.slicing.is_active())
+        .unwrap_or(false)
+        && ctx.slice_renderer.is_some();
 
-    let needs_post_processing = use_edge_blend;
+    // Configured outputs with any of these passes are composed in linear
+    // float and reach the surface through their output transform, which the
+    // other passes run before; plain sRGB outputs draw straight to it
+    let needs_post_processing = ctx.output_transform_renderer.is_some()
+        && (use_output_transform || use_edge_blend || use_color_calib || use_dome || use_slicing);
 
     let intermediate_tex_name = format!("output_{}_intermediate", output_id);
     let mesh_target_view_ref = if needs_post_processing {
@@ -130,7 +186,7 @@ pub(crate) fn render_content(
                 &intermediate_tex_name,
                 config.resolution.0,
                 config.resolution.1,
-                wgpu::TextureFormat::Rgba8Unorm, // Always supported for RENDER_ATTACHMENT and TEXTURE_BINDING
+                mapmap_render::HDR_FORMAT,
                 wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
             );
         }
@@ -139,10 +195,13 @@ pub(crate) fn render_content(
         None
     };
 
-    let target_view = if needs_post_processing {
-        mesh_target_view_ref.as_deref().unwrap()
+    let (target_view, mesh_renderer) = if needs_post_processing {
+        (
+            mesh_target_view_ref.as_deref().unwrap(),
+            ctx.hdr_mesh_renderer,
+        )
     } else {
-        view
+        (view, ctx.mesh_renderer)
     };
     // Clear Pass
     {
@@ -268,7 +327,7 @@ pub(crate) fn render_content(
                         &output_texture_name,
                         effect_width,
                         effect_height,
-                        wgpu::TextureFormat::Bgra8UnormSrgb,
+                        mapmap_render::HDR_FORMAT,
                         wgpu::TextureUsages::TEXTURE_BINDING
                             | wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::COPY_DST,
@@ -303,6 +362,95 @@ pub(crate) fn render_content(
                 }
             }
 
+            // Bezier masks cut the source before it is mapped
+            if let Some(mask_renderer) = ctx.mask_renderer {
+                if let Some(masked_view) = apply_layer_masks(
+                    ctx.texture_pool,
+                    queue,
+                    encoder,
+                    mask_renderer,
+                    ctx.mask_textures,
+                    (module_id, op.layer_part_id),
+                    &format!("output_{}_layer_{}", output_id, op.layer_part_id),
+                    &op.masks,
+                    &final_source_view,
+                ) {
+                    final_source_view = masked_view;
+                }
+            }
+
+            // 3D scene models are seen from the output's calibrated
+            // projector, or from the front if it has none
+            if let mapmap_core::module::MeshType::Model3D { path } = &op.mesh {
+                let Some(model) = ctx.mesh_buffer_cache.get_model_buffers(device, path) else {
+                    continue;
+                };
+                let clip_from_world = match &op.output_type {
+                    Projector { calibration_3d, .. } => calibration_3d.clip_from_world(),
+                    _ => None,
+                }
+                .unwrap_or(model.front_view);
+                let uniform_bind_group = mesh_renderer.get_clip_space_bind_group_with_source_props(
+                    queue,
+                    clip_from_world,
+                    op.opacity * op.source_props.opacity,
+                    op.source_props.flip_horizontal,
+                    op.source_props.flip_vertical,
+                    op.source_props.brightness,
+                    op.source_props.contrast,
+                    op.source_props.saturation,
+                    op.source_props.hue_shift,
+                );
+                let texture_bind_group = mesh_renderer.get_texture_bind_group(&final_source_view);
+
+                let size = target_view.texture().size();
+                let depth_tex_name = format!("output_{}_depth", output_id);
+                ctx.texture_pool.ensure_texture(
+                    &depth_tex_name,
+                    size.width,
+                    size.height,
+                    mapmap_render::DEPTH_FORMAT,
+                    wgpu::TextureUsages::RENDER_ATTACHMENT,
+                );
+                let depth_view = ctx.texture_pool.get_view(&depth_tex_name);
+
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Model Layer Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        depth_slice: None,
+                        view: target_view,
+                        resolve_target: None,
+
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    // Each model layer is composited like a flat one, so
+                    // depth only sorts the model's own triangles
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Discard,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                mesh_renderer.draw_model(
+                    &mut rpass,
+                    &model.vertex_buffer,
+                    &model.index_buffer,
+                    model.index_count,
+                    &uniform_bind_group,
+                    &texture_bind_group,
+                );
+                continue;
+            }
+
             let transform = glam::Mat4::IDENTITY;
             let uniform_bind_group = mesh_renderer.get_uniform_bind_group_with_source_props(
                 queue,
@@ -317,7 +465,7 @@ pub(crate) fn render_content(
             );
 
             let texture_bind_group = mesh_renderer.get_texture_bind_group(&final_source_view);
-            let (vb, ib, cnt) = ctx.mesh_buffer_cache.get_buffers(
+            let (vb, ib, cnt, index_format) = ctx.mesh_buffer_cache.get_buffers(
                 device,
                 queue,
                 op.layer_part_id,
@@ -348,6 +496,7 @@ pub(crate) fn render_content(
                 cnt,
                 &uniform_bind_group,
                 &texture_bind_group,
+                index_format,
                 true,
             );
         }
@@ -356,25 +505,240 @@ pub(crate) fn render_content(
     // --- POST PROCESSING PASSES ---
     if needs_post_processing {
         let intermediate_view = mesh_target_view_ref.as_ref().unwrap();
+        let mut stage_input = intermediate_view.clone();
+
+        // Dome resampling runs first so calibration and blending see the
+        // projected image rather than the spherical source
+        if let (Some(dome_renderer), Some(config)) = (
+            ctx.dome_renderer.as_ref().filter(|_| use_dome),
+            output_config_opt.as_ref(),
+        ) {
+            if let Some(dome) = &config.dome {
+                let cached = matches!(
+                    ctx.dome_cache.get(&output_id),
+                    Some((cached, size, _)) if cached == dome && *size == config.resolution
+                );
+                if !cached {
+                    let warp = dome_renderer.create_warp(dome, config.resolution);
+                    ctx.dome_cache
+                        .insert(output_id, (dome.clone(), config.resolution, warp));
+                }
+                let (_, _, warp) = &ctx.dome_cache[&output_id];
+                let texture_bind_group = dome_renderer.create_texture_bind_group(intermediate_view);
+
+                let tex_name = format!("output_{}_dome", output_id);
+                ctx.texture_pool.ensure_texture(
+                    &tex_name,
+                    config.resolution.0,
+                    config.resolution.1,
+                    dome_renderer.target_format(),
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                );
+                let dome_view = ctx.texture_pool.get_view(&tex_name);
+
+                {
+                    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("Dome Pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            depth_slice: None,
+                            view: &dome_view,
+                            resolve_target: None,
+
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+
+                    dome_renderer.render(&mut rpass, &texture_bind_group, warp);
+                }
+
+                stage_input = dome_view;
+            }
+        }
+
+        // Slices lay the output's content out onto the output raster; the
+        // passes after them act on the physical raster
+        if let (Some(slice_renderer), Some(config)) = (
+            ctx.slice_renderer.as_ref().filter(|_| use_slicing),
+            output_config_opt.as_ref(),
+        ) {
+            let cached = matches!(
+                ctx.slice_cache.get(&output_id),
+                Some((layout, size, _))
+                    if *layout == config.slicing && *size == config.resolution
+            );
+            if !cached {
+                let slices = slice_renderer.create_slices(&config.slicing, config.resolution);
+                ctx.slice_cache.insert(
+                    output_id,
+                    (config.slicing.clone(), config.resolution, slices),
+                );
+            }
+            let (_, _, slices) = &ctx.slice_cache[&output_id];
+            let texture_bind_group = slice_renderer.create_texture_bind_group(&stage_input);
+
+            let tex_name = format!("output_{}_sliced", output_id);
+            ctx.texture_pool.ensure_texture(
+                &tex_name,
+                config.resolution.0,
+                config.resolution.1,
+                slice_renderer.target_format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            );
+            let sliced_view = ctx.texture_pool.get_view(&tex_name);
+
+            {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Output Slicing Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        depth_slice: None,
+                        view: &sliced_view,
+                        resolve_target: None,
+
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                slice_renderer.render(&mut rpass, &texture_bind_group, slices);
+            }
+
+            stage_input = sliced_view;
+        }
+
+        // Color calibration keeps values above 1.0 for the output transform
+        if let (Some(color_calibration_renderer), Some(config)) = (
+            ctx.color_calibration_renderer
+                .as_ref()
+                .filter(|_| use_color_calib),
+            output_config_opt.as_ref(),
+        ) {
+            let calibration = &config.color_calibration;
+            let lut = calibration.lut.as_ref().and_then(|path| {
+                load_color_lut(
+                    ctx.texture_pool,
+                    queue,
+                    ctx.color_calibration_luts,
+                    output_id,
+                    path,
+                )
+            });
+            let lut_size = lut.as_ref().map_or(0, |(_, size)| *size);
+            let texture_bind_group = color_calibration_renderer.create_texture_bind_group_with_lut(
+                &stage_input,
+                lut.as_ref().map(|(view, _)| view.as_ref()),
+            );
+
+            use std::hash::Hasher;
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            for value in [
+                calibration.brightness,
+                calibration.contrast,
+                calibration.gamma.x,
+                calibration.gamma.y,
+                calibration.gamma_b,
+                calibration.color_temp,
+                calibration.saturation,
+            ] {
+                hasher.write(&value.to_le_bytes());
+            }
+            hasher.write_u32(lut_size);
+            let config_hash = hasher.finish();
+
+            let (uniform_buffer, uniform_bind_group, last_hash) = ctx
+                .color_calibration_cache
+                .entry(output_id)
+                .or_insert_with(|| {
+                    let buffer =
+                        color_calibration_renderer.create_uniform_buffer(calibration, lut_size);
+                    let bind_group = color_calibration_renderer.create_uniform_bind_group(&buffer);
+                    (buffer, bind_group, config_hash)
+                });
+            if *last_hash != config_hash {
+                color_calibration_renderer.update_uniform_buffer(
+                    queue,
+                    uniform_buffer,
+                    calibration,
+                    lut_size,
+                );
+                *last_hash = config_hash;
+            }
+
+            let tex_name = format!("output_{}_calibrated", output_id);
+            ctx.texture_pool.ensure_texture(
+                &tex_name,
+                config.resolution.0,
+                config.resolution.1,
+                color_calibration_renderer.target_format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            );
+            let calibrated_view = ctx.texture_pool.get_view(&tex_name);
+
+            {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Color Calibration Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        depth_slice: None,
+                        view: &calibrated_view,
+                        resolve_target: None,
+
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                color_calibration_renderer.render(
+                    &mut rpass,
+                    &texture_bind_group,
+                    uniform_bind_group,
+                );
+            }
+
+            stage_input = calibrated_view;
+        }
+
         // Re-create the texture bind group each frame since the intermediate texture may be re-allocated by the pool,
         // but we could optimize this later by checking if the texture's ID changed.
         // For now, creating a texture bind group is relatively cheap compared to buffers.
-        if let Some(edge_blend_renderer) = ctx.edge_blend_renderer.as_ref() {
+        if let (Some(edge_blend_renderer), Some(config)) = (
+            ctx.edge_blend_renderer.as_ref().filter(|_| use_edge_blend),
+            output_config_opt.as_ref(),
+        ) {
             let texture_bind_group = ctx
                 .edge_blend_texture_cache
                 .entry(output_id)
-                .or_insert_with(|| {
-                    edge_blend_renderer.create_texture_bind_group(intermediate_view)
-                });
+                .or_insert_with(|| edge_blend_renderer.create_texture_bind_group(&stage_input));
+            let config_to_use = &config.edge_blend;
+
+            let mask_view = config_to_use.mask.as_ref().and_then(|path| {
+                load_blend_mask(
+                    ctx.texture_pool,
+                    queue,
+                    ctx.edge_blend_masks,
+                    output_id,
+                    path,
+                )
+            });
+
             // Update texture bind group if view changed (TexturePool creates new textures on resize)
             // As a simple fix to avoid holding stale views across resizes, we just recreate it.
-            *texture_bind_group = edge_blend_renderer.create_texture_bind_group(intermediate_view);
-
-            let config_to_use = if use_edge_blend {
-                output_config_opt.map(|c| c.edge_blend).unwrap_or_default()
-            } else {
-                mapmap_core::EdgeBlendConfig::default()
-            };
+            *texture_bind_group = edge_blend_renderer
+                .create_texture_bind_group_with_mask(&stage_input, mask_view.as_deref());
 
             // Simple hash for config changes
             use std::hash::Hasher;
@@ -387,34 +751,114 @@ pub(crate) fn render_content(
             hasher.write(&config_to_use.right.width.to_le_bytes());
             hasher.write(&config_to_use.top.width.to_le_bytes());
             hasher.write(&config_to_use.bottom.width.to_le_bytes());
-            hasher.write(&config_to_use.gamma.to_le_bytes());
+            for gamma in config_to_use.gamma_rgb() {
+                hasher.write(&gamma.to_le_bytes());
+            }
+            for level in config_to_use.black_level {
+                hasher.write(&level.to_le_bytes());
+            }
+            hasher.write(&config_to_use.black_level_feather.to_le_bytes());
             let config_hash = hasher.finish();
 
             let (uniform_buffer, uniform_bind_group, last_hash) =
                 ctx.edge_blend_cache.entry(output_id).or_insert_with(|| {
-                    let buffer = edge_blend_renderer.create_uniform_buffer(&config_to_use);
+                    let buffer = edge_blend_renderer.create_uniform_buffer(config_to_use);
                     let bind_group = edge_blend_renderer.create_uniform_bind_group(&buffer);
                     (buffer, bind_group, config_hash)
                 });
 
             if *last_hash != config_hash {
-                edge_blend_renderer.update_uniform_buffer(queue, uniform_buffer, &config_to_use);
+                edge_blend_renderer.update_uniform_buffer(queue, uniform_buffer, config_to_use);
+                *last_hash = config_hash;
+            }
+
+            let tex_name = format!("output_{}_blended", output_id);
+            ctx.texture_pool.ensure_texture(
+                &tex_name,
+                config.resolution.0,
+                config.resolution.1,
+                edge_blend_renderer.target_format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            );
+            let blended_view = ctx.texture_pool.get_view(&tex_name);
+
+            {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Edge Blending Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        depth_slice: None,
+                        view: &blended_view,
+                        resolve_target: None,
+
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), // Clear previous if any
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                edge_blend_renderer.render(&mut rpass, texture_bind_group, uniform_bind_group);
+            }
+
+            stage_input = blended_view;
+        }
+
+        // The output transform encodes the linear composition for the
+        // output's display and is the only pass drawing to the surface
+        if let (Some(output_transform_renderer), Some(transform)) = (
+            ctx.output_transform_renderer.as_ref(),
+            output_transform.as_ref(),
+        ) {
+            let texture_bind_group =
+                output_transform_renderer.create_texture_bind_group(&stage_input);
+
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            hasher.write_u8(transform.transform as u8);
+            hasher.write_u8(transform.tone_mapping as u8);
+            for value in [
+                transform.exposure,
+                transform.paper_white_nits,
+                transform.peak_nits,
+            ] {
+                hasher.write(&value.to_le_bytes());
+            }
+            // The same output may switch between SDR and HDR surfaces
+            let target_format = view.texture().format();
+            target_format.hash(&mut hasher);
+            let config_hash = hasher.finish();
+
+            let (uniform_buffer, uniform_bind_group, last_hash) = ctx
+                .output_transform_cache
+                .entry(output_id)
+                .or_insert_with(|| {
+                    let buffer =
+                        output_transform_renderer.create_uniform_buffer(transform, target_format);
+                    let bind_group = output_transform_renderer.create_uniform_bind_group(&buffer);
+                    (buffer, bind_group, config_hash)
+                });
+            if *last_hash != config_hash {
+                output_transform_renderer.update_uniform_buffer(
+                    queue,
+                    uniform_buffer,
+                    transform,
+                    target_format,
+                );
                 *last_hash = config_hash;
             }
 
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some(if use_edge_blend {
-                    "Edge Blending Pass"
-                } else {
-                    "Passthrough Pass"
-                }),
+                label: Some("Output Transform Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     depth_slice: None,
                     view, // Draw to the final surface view
                     resolve_target: None,
 
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), // Clear previous if any
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -423,7 +867,12 @@ pub(crate) fn render_content(
                 occlusion_query_set: None,
             });
 
-            edge_blend_renderer.render(&mut rpass, texture_bind_group, uniform_bind_group);
+            output_transform_renderer.render(
+                &mut rpass,
+                target_format,
+                &texture_bind_group,
+                uniform_bind_group,
+            );
         }
     }
 
@@ -458,3 +907,200 @@ pub(crate) fn render_content(
     }
     Ok(())
 }
+
+/// Uploads an output's blend mask once per path; `None` if it can't be read.
+fn load_blend_mask(
+    texture_pool: &mapmap_render::TexturePool,
+    queue: &wgpu::Queue,
+    loaded: &mut std::collections::HashMap<u64, (String, bool)>,
+    output_id: u64,
+    path: &str,
+) -> Option<std::sync::Arc<wgpu::TextureView>> {
+    let tex_name = format!("output_{}_blend_mask", output_id);
+    let up_to_date = matches!(loaded.get(&output_id), Some((p, _)) if p == path);
+    if !up_to_date {
+        let ok = match image::open(path) {
+            Ok(image) => {
+                let mask = image.to_luma8();
+                let (width, height) = mask.dimensions();
+                let rgba: Vec<u8> = mask.pixels().flat_map(|p| [p.0[0]; 4]).collect();
+                texture_pool.ensure_texture(
+                    &tex_name,
+                    width,
+                    height,
+                    wgpu::TextureFormat::Rgba8Unorm,
+                    wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                );
+                texture_pool.upload_data(queue, &tex_name, &rgba, width, height);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Blend mask '{}' could not be loaded: {}", path, e);
+                false
+            }
+        };
+        loaded.insert(output_id, (path.to_string(), ok));
+    }
+    loaded
+        .get(&output_id)
+        .is_some_and(|(_, ok)| *ok)
+        .then(|| texture_pool.get_view(&tex_name))
+}
+
+/// Resolution of the coverage textures rasterized from mask chains
+const MASK_RESOLUTION: u32 = 512;
+
+/// Shortest time between two rasterizations of an animating mask chain
+const MASK_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(33);
+
+/// Coverage texture of a layer's mask chain, rasterized on a worker thread.
+#[derive(Default)]
+pub struct LayerMask {
+    /// Mask chain of the latest rasterization, finished or in flight
+    masks: Vec<mapmap_core::module::MaskType>,
+    /// When the latest rasterization started
+    started: Option<std::time::Instant>,
+    /// Coverage of the rasterization in flight
+    pending: Option<crossbeam_channel::Receiver<Option<Vec<u8>>>>,
+}
+
+/// Applies the bezier masks of a layer to its source; the masked source, or
+/// `None` if the layer has no bezier mask. Coverage is rasterized on a worker
+/// once per layer for all outputs, again only when the mask chain changes, and
+/// at most every [`MASK_REFRESH_INTERVAL`] while mask points animate. Until the
+/// first coverage arrives the layer is fully masked.
+#[allow(clippy::too_many_arguments)]
+fn apply_layer_masks(
+    texture_pool: &mapmap_render::TexturePool,
+    queue: &wgpu::Queue,
+    encoder: &mut wgpu::CommandEncoder,
+    mask_renderer: &mapmap_render::MaskRenderer,
+    layer_masks: &mut std::collections::HashMap<(u64, u64), LayerMask>,
+    layer: (u64, u64),
+    name: &str,
+    masks: &[mapmap_core::module::MaskType],
+    source_view: &wgpu::TextureView,
+) -> Option<std::sync::Arc<wgpu::TextureView>> {
+    use mapmap_core::module::MaskType;
+    if !masks.iter().any(|mask| matches!(mask, MaskType::Bezier(_))) {
+        return None;
+    }
+
+    let mask_tex_name = format!("module_{}_layer_{}_mask", layer.0, layer.1);
+    // New textures start out zeroed, i.e. without coverage
+    texture_pool.ensure_texture(
+        &mask_tex_name,
+        MASK_RESOLUTION,
+        MASK_RESOLUTION,
+        wgpu::TextureFormat::Rgba8Unorm,
+        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+    );
+    let layer_mask = layer_masks.entry(layer).or_default();
+    if let Some(rx) = &layer_mask.pending {
+        match rx.try_recv() {
+            Ok(coverage) => {
+                layer_mask.pending = None;
+                if let Some(coverage) = coverage {
+                    let rgba: Vec<u8> = coverage.into_iter().flat_map(|c| [c; 4]).collect();
+                    texture_pool.upload_data(
+                        queue,
+                        &mask_tex_name,
+                        &rgba,
+                        MASK_RESOLUTION,
+                        MASK_RESOLUTION,
+                    );
+                }
+            }
+            Err(crossbeam_channel::TryRecvError::Empty) => {}
+            Err(crossbeam_channel::TryRecvError::Disconnected) => layer_mask.pending = None,
+        }
+    }
+    let due = layer_mask
+        .started
+        .map_or(true, |at| at.elapsed() >= MASK_REFRESH_INTERVAL);
+    if layer_mask.pending.is_none() && layer_mask.masks.as_slice() != masks && due {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let chain = masks.to_vec();
+        std::thread::spawn(move || {
+            let _ = tx.send(mapmap_core::module::rasterize_masks(
+                &chain,
+                MASK_RESOLUTION,
+                MASK_RESOLUTION,
+            ));
+        });
+        layer_mask.masks = masks.to_vec();
+        layer_mask.started = Some(std::time::Instant::now());
+        layer_mask.pending = Some(rx);
+    }
+    let mask_view = texture_pool.get_view(&mask_tex_name);
+
+    let masked_tex_name = format!("{}_masked", name);
+    let size = source_view.texture().size();
+    texture_pool.ensure_texture(
+        &masked_tex_name,
+        size.width,
+        size.height,
+        mask_renderer.target_format(),
+        wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+    );
+    let masked_view = texture_pool.get_view(&masked_tex_name);
+    let bind_group = mask_renderer.create_bind_group(source_view, &mask_view);
+
+    {
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Layer Mask Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                depth_slice: None,
+                view: &masked_view,
+                resolve_target: None,
+
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        mask_renderer.render(&mut rpass, &bind_group);
+    }
+
+    Some(masked_view)
+}
+
+/// Uploads an output's correction LUT once per path; the LUT view and size,
+/// or `None` if it can't be read.
+fn load_color_lut(
+    texture_pool: &mapmap_render::TexturePool,
+    queue: &wgpu::Queue,
+    loaded: &mut std::collections::HashMap<u64, (String, Option<u32>)>,
+    output_id: u64,
+    path: &str,
+) -> Option<(std::sync::Arc<wgpu::TextureView>, u32)> {
+    let tex_name = format!("output_{}_color_lut", output_id);
+    let up_to_date = matches!(loaded.get(&output_id), Some((p, _)) if p == path);
+    if !up_to_date {
+        let size = match mapmap_core::Lut3D::from_file(path) {
+            Ok(lut) => {
+                let (data, width, height) = lut.to_2d_texture_data();
+                texture_pool.ensure_texture(
+                    &tex_name,
+                    width,
+                    height,
+                    wgpu::TextureFormat::Rgba8Unorm,
+                    wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                );
+                texture_pool.upload_data(queue, &tex_name, &data, width, height);
+                Some(lut.size as u32)
+            }
+            Err(e) => {
+                tracing::warn!("Color LUT '{}' could not be loaded: {}", path, e);
+                None
+            }
+        };
+        loaded.insert(output_id, (path.to_string(), size));
+    }
+    let size = loaded.get(&output_id)?.1?;
+    Some((texture_pool.get_view(&tex_name), size))
+}
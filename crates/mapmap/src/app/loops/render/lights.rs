@@ -0,0 +1,283 @@
+//! Spatial light mapping.
+//!
+//! The layer feeding a Hue or light output in spatial mode is rendered into a
+//! small offscreen texture and read back one frame later; every lamp then
+//! takes the color at its position, so the lights extend the projected image
+//! into the room. Pixel controllers with mapped strips get every LED sampled
+//! along its strip.
+
+use super::content::{render_content, RenderContext};
+use super::PREVIEW_FLAG;
+use crate::app::core::app_struct::App;
+use crate::orchestration::lights::{
+    sample_positions, send_sampled_colors, send_strip_pixels, strip_layout,
+};
+use mapmap_control::dmx::LedStrip;
+use mapmap_control::light::spatial::{sample_lamps, sample_strips, PixelFrame};
+use mapmap_core::module::{HueMappingMode, LightMappingMode, ModulePartId, OutputType};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Resolution the layer is sampled at; lamps only need the rough color of an area.
+const SAMPLE_WIDTH: u32 = 64;
+const SAMPLE_HEIGHT: u32 = 36;
+/// Pixels averaged around each lamp position (in every direction).
+const SAMPLE_RADIUS: u32 = 2;
+
+/// GPU resources of one spatial Hue or light output.
+pub struct LightReadback {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    buffer: wgpu::Buffer,
+    bytes_per_row: u32,
+    /// A copy into `buffer` was recorded and still has to be mapped after submit.
+    copy_recorded: bool,
+    /// `buffer` is being mapped; it can't be written until it is read and unmapped.
+    mapping: bool,
+    /// Set by the map callback once `buffer` can be read.
+    mapped: Arc<AtomicBool>,
+}
+
+impl LightReadback {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat, part_id: ModulePartId) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&format!("Light Spatial Tex {}", part_id)),
+            size: wgpu::Extent3d {
+                width: SAMPLE_WIDTH,
+                height: SAMPLE_HEIGHT,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let unpadded_bytes_per_row = SAMPLE_WIDTH * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Light Spatial Readback Buffer"),
+            size: (bytes_per_row * SAMPLE_HEIGHT) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            texture,
+            view,
+            buffer,
+            bytes_per_row,
+            copy_recorded: false,
+            mapping: false,
+            mapped: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Where the sampled colors of a spatial output go.
+enum Target {
+    /// The Hue controller, addressed by light ID.
+    Hue,
+    /// The sink of a light output.
+    Light,
+    /// A pixel controller; every LED of the strips is sampled (strips, LED count).
+    Pixels(Vec<LedStrip>, usize),
+}
+
+/// Colors sampled for one output.
+enum Sampled {
+    Lamps(Vec<(String, f32, f32, f32)>),
+    Pixels(Vec<(u8, u8, u8)>),
+}
+
+/// Send last frame's lamp colors to the lights and render this frame's layer
+/// images for all spatial Hue and light outputs.
+pub(crate) fn render_light_outputs(app: &mut App, encoder: &mut wgpu::CommandEncoder) {
+    let hue_connected = app.hue_controller.is_connected();
+    let mut spatial: HashMap<ModulePartId, (Target, HashMap<String, (f32, f32)>)> = HashMap::new();
+    for (_, op) in &app.render_ops {
+        match &op.output_type {
+            OutputType::Hue {
+                lamp_positions,
+                mapping_mode: HueMappingMode::Spatial,
+                ..
+            } if hue_connected => {
+                spatial
+                    .entry(op.output_part_id)
+                    .or_insert_with(|| (Target::Hue, lamp_positions.clone()));
+            }
+            OutputType::Light {
+                lamp_positions,
+                mapping_mode: LightMappingMode::Spatial,
+                ..
+            } => {
+                spatial
+                    .entry(op.output_part_id)
+                    .or_insert_with(|| (Target::Light, lamp_positions.clone()));
+            }
+            _ => {}
+        }
+    }
+    let mut outputs = HashMap::new();
+    for (part_id, (target, lamp_positions)) in spatial {
+        let (target, positions) = match target {
+            Target::Light => match strip_layout(app, part_id) {
+                Some((strips, led_count)) => (Target::Pixels(strips, led_count), HashMap::new()),
+                None => (
+                    Target::Light,
+                    sample_positions(app, part_id, &lamp_positions),
+                ),
+            },
+            target => (target, lamp_positions),
+        };
+        if !positions.is_empty() || matches!(target, Target::Pixels(..)) {
+            outputs.insert(part_id, (target, positions));
+        }
+    }
+    app.light_readbacks
+        .retain(|part_id, _| outputs.contains_key(part_id));
+    if outputs.is_empty() {
+        return;
+    }
+
+    let _ = app.backend.device.poll(wgpu::PollType::Poll);
+    let format = app.backend.surface_format();
+    let bgra = matches!(
+        format,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+
+    for (part_id, (target, lamp_positions)) in outputs {
+        let readback = app
+            .light_readbacks
+            .entry(part_id)
+            .or_insert_with(|| LightReadback::new(&app.backend.device, format, part_id));
+
+        let sampled = readback.mapped.swap(false, Ordering::SeqCst).then(|| {
+            let colors = {
+                let data = readback.buffer.slice(..).get_mapped_range();
+                let frame = PixelFrame {
+                    data: &data,
+                    width: SAMPLE_WIDTH,
+                    height: SAMPLE_HEIGHT,
+                    bytes_per_row: readback.bytes_per_row,
+                    bgra,
+                };
+                match &target {
+                    Target::Pixels(strips, led_count) => {
+                        Sampled::Pixels(sample_strips(&frame, strips, *led_count, SAMPLE_RADIUS))
+                    }
+                    _ => Sampled::Lamps(sample_lamps(&frame, &lamp_positions, SAMPLE_RADIUS)),
+                }
+            };
+            readback.buffer.unmap();
+            readback.mapping = false;
+            colors
+        });
+        match (sampled, target) {
+            (Some(Sampled::Lamps(colors)), Target::Hue) => {
+                app.hue_controller.update_lamp_colors(&colors)
+            }
+            (Some(Sampled::Lamps(colors)), _) => send_sampled_colors(app, part_id, &colors),
+            (Some(Sampled::Pixels(pixels)), _) => send_strip_pixels(app, part_id, &pixels),
+            (None, _) => {}
+        }
+
+        let Some(readback) = app.light_readbacks.get_mut(&part_id) else {
+            continue;
+        };
+        if readback.mapping {
+            // The GPU hasn't handed over the previous frame yet
+            continue;
+        }
+
+        if let Err(err) = render_content(
+            RenderContext {
+                device: &app.backend.device,
+                queue: &app.backend.queue,
+                render_ops: &app.render_ops,
+                output_manager: &app.state.output_manager,
+                edge_blend_renderer: &app.edge_blend_renderer,
+                color_calibration_renderer: &app.color_calibration_renderer,
+                dome_renderer: &app.dome_renderer,
+                slice_renderer: &app.slice_renderer,
+                output_transform_renderer: &app.output_transform_renderer,
+                mask_renderer: &app.mask_renderer,
+                edge_blend_cache: &mut app.edge_blend_cache,
+                edge_blend_texture_cache: &mut app.edge_blend_texture_cache,
+                edge_blend_masks: &mut app.edge_blend_masks,
+                color_calibration_cache: &mut app.color_calibration_cache,
+                color_calibration_luts: &mut app.color_calibration_luts,
+                dome_cache: &mut app.dome_cache,
+                slice_cache: &mut app.slice_cache,
+                output_transform_cache: &mut app.output_transform_cache,
+                mask_textures: &mut app.mask_textures,
+                mesh_renderer: &mut app.mesh_renderer,
+                hdr_mesh_renderer: &mut app.hdr_mesh_renderer,
+                effect_chain_renderer: &mut app.effect_chain_renderer,
+                preview_effect_chain_renderer: &mut app.preview_effect_chain_renderer,
+                shader_graph_manager: &app.shader_graph_manager,
+                texture_pool: &app.texture_pool,
+                _dummy_view: &app.dummy_view,
+                mesh_buffer_cache: &mut app.mesh_buffer_cache,
+                egui_renderer: &mut app.egui_renderer,
+                video_diagnostic_log_times: &mut app.video_diagnostic_log_times,
+            },
+            part_id | PREVIEW_FLAG,
+            encoder,
+            &readback.view,
+            None,
+        ) {
+            tracing::warn!("Light output {} could not be rendered: {}", part_id, err);
+            continue;
+        }
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &readback.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(readback.bytes_per_row),
+                    rows_per_image: Some(SAMPLE_HEIGHT),
+                },
+            },
+            wgpu::Extent3d {
+                width: SAMPLE_WIDTH,
+                height: SAMPLE_HEIGHT,
+                depth_or_array_layers: 1,
+            },
+        );
+        readback.copy_recorded = true;
+    }
+}
+
+/// Map the buffers copied in the submitted frame; they are read next frame.
+pub(crate) fn request_light_readbacks(app: &mut App) {
+    for readback in app.light_readbacks.values_mut() {
+        if !readback.copy_recorded {
+            continue;
+        }
+        readback.copy_recorded = false;
+        readback.mapping = true;
+        let mapped = readback.mapped.clone();
+        readback
+            .buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |res| {
+                if res.is_ok() {
+                    mapped.store(true, Ordering::SeqCst);
+                }
+            });
+    }
+}
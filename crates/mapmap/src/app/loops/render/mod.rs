@@ -7,15 +7,22 @@ use mapmap_core::OutputId;
 #[cfg(feature = "ndi")]
 use std::sync::atomic::{AtomicBool, Ordering};
 
+mod color_match;
 mod content;
 mod effects;
+mod lights;
 mod logging;
 mod previews;
+mod structured_light;
 mod texture_gen;
 
 use content::*;
+use lights::*;
 use previews::*;
 
+pub use content::LayerMask;
+pub use lights::LightReadback;
+
 pub(crate) const PREVIEW_FLAG: u64 = 1u64 << 63;
 
 /// Renders the UI or content for the given output ID.
@@ -29,12 +36,15 @@ pub fn render(app: &mut App, output_id: OutputId) -> Result<()> {
 
     // Batch render passes.
     app.mesh_renderer.begin_frame();
+    app.hdr_mesh_renderer.begin_frame();
     app.effect_chain_renderer.begin_frame();
     app.preview_effect_chain_renderer.begin_frame();
 
     if output_id == 0 {
         // Sync Texture Previews
         prepare_texture_previews(app, &mut encoder);
+        // Sample spatial Hue and light outputs
+        render_light_outputs(app, &mut encoder);
         // Update Bevy Texture
         if let Some(runner) = &app.bevy_runner {
             let runner: &mapmap_bevy::BevyRunner = runner;
@@ -124,32 +134,64 @@ pub fn render(app: &mut App, output_id: OutputId) -> Result<()> {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Render Content
-        render_content(
-            RenderContext {
-                device: &app.backend.device,
-                queue: &app.backend.queue,
-                render_ops: &app.render_ops,
-                output_manager: &app.state.output_manager,
-                edge_blend_renderer: &app.edge_blend_renderer,
-                color_calibration_renderer: &app.color_calibration_renderer,
-                edge_blend_cache: &mut app.edge_blend_cache,
-                edge_blend_texture_cache: &mut app.edge_blend_texture_cache,
-                mesh_renderer: &mut app.mesh_renderer,
-                effect_chain_renderer: &mut app.effect_chain_renderer,
-                preview_effect_chain_renderer: &mut app.preview_effect_chain_renderer,
-                shader_graph_manager: &app.shader_graph_manager,
-                texture_pool: &app.texture_pool,
-                _dummy_view: &app.dummy_view,
-                mesh_buffer_cache: &mut app.mesh_buffer_cache,
-                egui_renderer: &mut app.egui_renderer,
-                video_diagnostic_log_times: &mut app.video_diagnostic_log_times,
-            },
-            output_id,
-            &mut encoder,
-            &view,
-            egui_render_data.as_ref(),
-        )?;
+        // Calibration patterns and color patches replace the content of their output
+        let pattern_session = app
+            .structured_light
+            .as_mut()
+            .filter(|session| session.output_id == output_id);
+        let color_patch = app.color_patch.filter(|(id, _)| *id == output_id);
+        if let Some(session) = pattern_session {
+            structured_light::render_pattern(
+                session,
+                &app.backend.queue,
+                &app.texture_pool,
+                &mut app.mesh_renderer,
+                &app.preview_quad_buffers,
+                &mut encoder,
+                &view,
+            );
+        } else if let Some((_, rgb)) = color_patch {
+            color_match::render_patch(&mut encoder, &view, rgb);
+        } else {
+            // Render Content
+            render_content(
+                RenderContext {
+                    device: &app.backend.device,
+                    queue: &app.backend.queue,
+                    render_ops: &app.render_ops,
+                    output_manager: &app.state.output_manager,
+                    edge_blend_renderer: &app.edge_blend_renderer,
+                    color_calibration_renderer: &app.color_calibration_renderer,
+                    dome_renderer: &app.dome_renderer,
+                    slice_renderer: &app.slice_renderer,
+                    output_transform_renderer: &app.output_transform_renderer,
+                    mask_renderer: &app.mask_renderer,
+                    edge_blend_cache: &mut app.edge_blend_cache,
+                    edge_blend_texture_cache: &mut app.edge_blend_texture_cache,
+                    edge_blend_masks: &mut app.edge_blend_masks,
+                    color_calibration_cache: &mut app.color_calibration_cache,
+                    color_calibration_luts: &mut app.color_calibration_luts,
+                    dome_cache: &mut app.dome_cache,
+                    slice_cache: &mut app.slice_cache,
+                    output_transform_cache: &mut app.output_transform_cache,
+                    mask_textures: &mut app.mask_textures,
+                    mesh_renderer: &mut app.mesh_renderer,
+                    hdr_mesh_renderer: &mut app.hdr_mesh_renderer,
+                    effect_chain_renderer: &mut app.effect_chain_renderer,
+                    preview_effect_chain_renderer: &mut app.preview_effect_chain_renderer,
+                    shader_graph_manager: &app.shader_graph_manager,
+                    texture_pool: &app.texture_pool,
+                    _dummy_view: &app.dummy_view,
+                    mesh_buffer_cache: &mut app.mesh_buffer_cache,
+                    egui_renderer: &mut app.egui_renderer,
+                    video_diagnostic_log_times: &mut app.video_diagnostic_log_times,
+                },
+                output_id,
+                &mut encoder,
+                &view,
+                egui_render_data.as_ref(),
+            )?;
+        }
 
         // --- NDI Readback (if enabled) ---
         #[cfg(feature = "ndi")]
@@ -163,6 +205,9 @@ pub fn render(app: &mut App, output_id: OutputId) -> Result<()> {
                 }
                 None
             });
+            // HDR surfaces don't hold the 8-bit BGRA frames NDI sends
+            let part_id = part_id
+                .filter(|_| surface_texture.texture.format() == app.backend.surface_format());
 
             if let Some(pid) = part_id {
                 if let Some(sender) = app.ndi_senders.get_mut(&pid) {
@@ -280,5 +325,9 @@ pub fn render(app: &mut App, output_id: OutputId) -> Result<()> {
         surface_texture.present();
     }
 
+    if output_id == 0 {
+        request_light_readbacks(app);
+    }
+
     Ok(())
 }
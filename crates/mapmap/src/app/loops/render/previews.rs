@@ -106,9 +106,21 @@ pub(crate) fn prepare_texture_previews(app: &mut App, encoder: &mut wgpu::Comman
                 output_manager: &app.state.output_manager,
                 edge_blend_renderer: &app.edge_blend_renderer,
                 color_calibration_renderer: &app.color_calibration_renderer,
+                dome_renderer: &app.dome_renderer,
+                slice_renderer: &app.slice_renderer,
+                output_transform_renderer: &app.output_transform_renderer,
+                mask_renderer: &app.mask_renderer,
                 edge_blend_cache: &mut app.edge_blend_cache,
                 edge_blend_texture_cache: &mut app.edge_blend_texture_cache,
+                edge_blend_masks: &mut app.edge_blend_masks,
+                color_calibration_cache: &mut app.color_calibration_cache,
+                color_calibration_luts: &mut app.color_calibration_luts,
+                dome_cache: &mut app.dome_cache,
+                slice_cache: &mut app.slice_cache,
+                output_transform_cache: &mut app.output_transform_cache,
+                mask_textures: &mut app.mask_textures,
                 mesh_renderer: &mut app.mesh_renderer,
+                hdr_mesh_renderer: &mut app.hdr_mesh_renderer,
                 effect_chain_renderer: &mut app.effect_chain_renderer,
                 preview_effect_chain_renderer: &mut app.preview_effect_chain_renderer,
                 shader_graph_manager: &app.shader_graph_manager,
@@ -0,0 +1,67 @@
+//! Gray-code patterns shown on an output during structured-light calibration.
+
+use crate::orchestration::structured_light::PatternSession;
+use mapmap_render::{MeshRenderer, TexturePool};
+
+const PATTERN_TEXTURE: &str = "structured_light_pattern";
+
+/// Draws the current pattern frame over the whole output.
+pub(crate) fn render_pattern(
+    session: &mut PatternSession,
+    queue: &wgpu::Queue,
+    texture_pool: &TexturePool,
+    mesh_renderer: &mut MeshRenderer,
+    quad_buffers: &(wgpu::Buffer, wgpu::Buffer, u32),
+    encoder: &mut wgpu::CommandEncoder,
+    view: &wgpu::TextureView,
+) {
+    let (width, height) = (session.pattern.width, session.pattern.height);
+    texture_pool.ensure_texture(
+        PATTERN_TEXTURE,
+        width,
+        height,
+        wgpu::TextureFormat::Rgba8Unorm,
+        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+    );
+    if session.uploaded != Some(session.frame) {
+        if let Some(frame) = session.pattern.frame(session.frame) {
+            let rgba: Vec<u8> = frame
+                .pixels()
+                .flat_map(|p| [p.0[0], p.0[0], p.0[0], 255])
+                .collect();
+            texture_pool.upload_data(queue, PATTERN_TEXTURE, &rgba, width, height);
+            session.uploaded = Some(session.frame);
+        }
+    }
+
+    let pattern_view = texture_pool.get_view(PATTERN_TEXTURE);
+    let uniform_bind_group = mesh_renderer.get_uniform_bind_group(queue, glam::Mat4::IDENTITY, 1.0);
+    let texture_bind_group = mesh_renderer.get_texture_bind_group(&pattern_view);
+
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Structured Light Pattern Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            depth_slice: None,
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    let (vertex_buffer, index_buffer, index_count) = quad_buffers;
+    mesh_renderer.draw(
+        &mut rpass,
+        vertex_buffer,
+        index_buffer,
+        *index_count,
+        &uniform_bind_group,
+        &texture_bind_group,
+        wgpu::IndexFormat::Uint16,
+        false,
+    );
+}
@@ -691,6 +691,7 @@ pub fn show(ctx: &egui::Context, app: &mut App) {
                     std::sync::Arc::make_mut(&mut app.state.module_manager),
                     &app.ui_state.i18n,
                     &mut app.ui_state.actions,
+                    &app.control_manager.key_bindings,
                     ui::ModuleCanvasRenderOptions::from(&app.ui_state.user_config),
                 );
             } else {
@@ -779,6 +780,7 @@ pub fn show(ctx: &egui::Context, app: &mut App) {
             state: &mut app.state,
             backend: &app.backend,
             hue_controller: &mut app.hue_controller,
+            hue_virtual_bridge: &app.hue_virtual_bridge,
             #[cfg(feature = "midi")]
             midi_handler: &mut app.midi_handler,
             #[cfg(feature = "midi")]
@@ -0,0 +1,176 @@
+//! Audio analysis source switching.
+//!
+//! The analyzer is fed either by the live input device or by a decoded file.
+//! Files are decoded on a worker thread and replace the current source once
+//! they are ready. A file can run on its own clock or follow a media player,
+//! which keeps the analysis of a video soundtrack in sync with the picture.
+
+use crate::app::core::app_struct::App;
+use anyhow::{anyhow, Result};
+use mapmap_core::audio::backend::{cpal_backend::CpalBackend, AudioBackend};
+use mapmap_core::audio::file_backend::FileBackend;
+use mapmap_core::audio::{AudioAnalysis, AudioSource};
+use mapmap_core::module::{ModulePartId, ModulePartType, SourceType};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// An audio file being decoded on a worker thread.
+pub struct AudioFileLoad {
+    /// File being decoded
+    path: PathBuf,
+    /// Media player ((ModuleID, PartID)) to follow, `None` to run on its own clock
+    follow: Option<(ModulePartId, ModulePartId)>,
+    rx: crossbeam_channel::Receiver<Result<FileBackend>>,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Replace the audio backend and match the analyzer sample rate to it.
+fn install_backend(app: &mut App, mut backend: Box<dyn AudioBackend>, sample_rate: u32) {
+    if let Some(old) = &mut app.audio_backend {
+        old.stop();
+    }
+    if let Err(e) = backend.start() {
+        tracing::error!("Failed to start audio backend: {}", e);
+    }
+
+    if app.audio_analyzer.config.sample_rate != sample_rate {
+        let mut config = app.audio_analyzer.get_config();
+        config.sample_rate = sample_rate;
+        app.audio_analyzer.update_config(config);
+    }
+    app.audio_analyzer.v2.reset();
+    app.audio_router.configure(&app.audio_analyzer.config);
+    app.audio_router.reset();
+    app.audio_backend = Some(backend);
+}
+
+/// Analyze the selected live input device.
+pub fn use_system_input(app: &mut App) -> Result<()> {
+    let backend = CpalBackend::new(app.ui_state.selected_audio_device.clone())?;
+    let sample_rate = app.state.audio_config.sample_rate;
+    install_backend(app, Box::new(backend), sample_rate);
+
+    app.audio_file_load = None;
+    app.audio_sync_player = None;
+    app.ui_state.audio_source = AudioSource::SystemInput;
+    app.ui_state.audio_source_file = None;
+    info!("Audio analysis: live input");
+    Ok(())
+}
+
+/// Decode `path` on a worker thread; [`poll_audio_file`] switches to it once
+/// it is ready. A load still running is abandoned.
+fn start_file_load(app: &mut App, path: PathBuf, follow: Option<(ModulePartId, ModulePartId)>) {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    let worker_path = path.clone();
+    std::thread::spawn(move || {
+        let backend = FileBackend::open(&worker_path)
+            .map(|backend| backend.with_looping(follow.is_none()))
+            .map_err(|e| anyhow!("{}", e));
+        let _ = tx.send(backend);
+    });
+    app.audio_file_load = Some(AudioFileLoad { path, follow, rx });
+}
+
+/// Analyze an audio file on its own clock, looping at the end.
+pub fn use_audio_file(app: &mut App, path: &Path) {
+    info!("Audio analysis: decoding file {:?}", path);
+    start_file_load(app, path.to_path_buf(), None);
+}
+
+/// Analyze the soundtrack of a media source, following its player.
+pub fn use_video_audio(app: &mut App, module_id: u64, part_id: u64) -> Result<()> {
+    let path = app
+        .state
+        .module_manager
+        .get_module(module_id)
+        .and_then(|module| module.parts.iter().find(|p| p.id == part_id))
+        .and_then(|part| match &part.part_type {
+            ModulePartType::Source(SourceType::MediaFile { path, .. })
+            | ModulePartType::Source(SourceType::VideoUni { path, .. }) => {
+                Some(path.trim().to_string())
+            }
+            _ => None,
+        })
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| anyhow!("Part {} has no media file", part_id))?;
+
+    info!("Audio analysis: decoding soundtrack of {:?}", path);
+    start_file_load(app, PathBuf::from(path), Some((module_id, part_id)));
+    Ok(())
+}
+
+/// Switch to a decoded file once its worker is done; returns the outcome of
+/// a finished load.
+pub fn poll_audio_file(app: &mut App) -> Option<Result<()>> {
+    let received = app.audio_file_load.as_ref()?.rx.try_recv();
+    let backend = match received {
+        Ok(backend) => backend,
+        Err(crossbeam_channel::TryRecvError::Empty) => return None,
+        Err(crossbeam_channel::TryRecvError::Disconnected) => {
+            app.audio_file_load = None;
+            return Some(Err(anyhow!("The audio decoding thread stopped")));
+        }
+    };
+    let load = app.audio_file_load.take()?;
+    let backend = match backend {
+        Ok(backend) => backend,
+        Err(e) => return Some(Err(e.context(format!("{:?}", load.path)))),
+    };
+    let sample_rate = backend.sample_rate();
+    install_backend(app, Box::new(backend), sample_rate);
+
+    app.audio_sync_player = load.follow;
+    app.ui_state.audio_source = if load.follow.is_some() {
+        AudioSource::VideoAudio
+    } else {
+        AudioSource::AudioFile
+    };
+    app.ui_state.audio_source_file = Some(file_name(&load.path));
+    info!("Audio analysis: {:?}", load.path);
+    Some(Ok(()))
+}
+
+/// Feed this frame's analyses to the parameter mappings and the trigger
+/// system; mappings and triggers bound to a channel analyzer read only that
+/// channel. Runs after the module evaluator received the frame's audio.
+pub fn update_audio_reactive(app: &mut App, analysis: &AudioAnalysis, timestamp: f64, dt: f32) {
+    app.audio_reactive
+        .update_routed(analysis, app.audio_router.analyses(), timestamp);
+
+    let channels = app
+        .audio_router
+        .names()
+        .filter_map(|name| {
+            app.module_evaluator
+                .audio_data(Some(name))
+                .map(|data| (name.to_string(), data.clone()))
+        })
+        .collect();
+    let main = app
+        .module_evaluator
+        .audio_data(None)
+        .cloned()
+        .unwrap_or_default();
+    app.trigger_system.update_audio_channels(channels);
+    app.trigger_system.update_beat_grid(app.tempo.state());
+    app.trigger_system
+        .update(&app.state.module_manager, &main, dt);
+}
+
+/// Move a synced file backend to the position of the player it follows.
+pub fn sync_audio_position(app: &mut App) {
+    let Some(key) = app.audio_sync_player else {
+        return;
+    };
+    let (Some(backend), Some(player)) = (&mut app.audio_backend, app.media_players.get(&key))
+    else {
+        return;
+    };
+    backend.sync_position(player.position_secs());
+}
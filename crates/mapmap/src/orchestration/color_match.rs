@@ -0,0 +1,85 @@
+//! Multi-point color matching across projectors.
+//!
+//! An output shows the test patches stepped through in the inspector while
+//! they are measured; the readings are stored on the projector output part.
+//! Solving matches every output with complete
+//! measurements to a common target, writes one correction LUT per output
+//! (`output_<id>_color.cube`) and sets it in the output's color calibration.
+
+use crate::app::core::app_struct::App;
+use anyhow::{anyhow, Context, Result};
+use mapmap_core::color_match::{common_target, solve_correction_lut, PatchMeasurements};
+use mapmap_core::lut::LUT_SIZE_32;
+use mapmap_core::module::{ModulePartType, OutputType};
+use mapmap_core::OutputId;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::info;
+
+/// Show a test patch (drive value RGB) on an output; `None` returns the
+/// output to its content.
+pub fn show_patch(app: &mut App, output_id: OutputId, rgb: Option<[f32; 3]>) {
+    match rgb {
+        Some(rgb) => app.color_patch = Some((output_id, rgb)),
+        None => {
+            if app.color_patch.is_some_and(|(id, _)| id == output_id) {
+                app.color_patch = None;
+            }
+        }
+    }
+}
+
+/// Measurements stored on the projector outputs of all modules, by output ID.
+pub fn stored_measurements(app: &App) -> BTreeMap<OutputId, PatchMeasurements> {
+    app.state
+        .module_manager
+        .modules()
+        .into_iter()
+        .flat_map(|module| module.parts.iter())
+        .filter_map(|part| match &part.part_type {
+            ModulePartType::Output(OutputType::Projector {
+                id,
+                color_measurements: Some(measurements),
+                ..
+            }) => Some((*id, measurements.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Solve and apply correction LUTs, written to `dir`; returns the number of
+/// matched outputs.
+pub fn solve(
+    app: &mut App,
+    measurements: &BTreeMap<OutputId, PatchMeasurements>,
+    dir: &Path,
+) -> Result<usize> {
+    let (output_ids, responses): (Vec<_>, Vec<_>) = measurements
+        .iter()
+        .filter_map(|(output_id, patches)| Some((*output_id, patches.response()?)))
+        .unzip();
+    if responses.is_empty() {
+        return Err(anyhow!("No output has a complete set of measurements"));
+    }
+    let target = common_target(&responses)
+        .ok_or_else(|| anyhow!("Measured primaries are degenerate; check the measurements"))?;
+
+    std::fs::create_dir_all(dir)?;
+    for (output_id, response) in output_ids.iter().zip(&responses) {
+        let mut lut = solve_correction_lut(response, &target, LUT_SIZE_32);
+        lut.name = format!("Output {} Color Match", output_id);
+        let path = dir.join(format!("output_{}_color.cube", output_id));
+        lut.save_cube(&path)
+            .with_context(|| format!("Output {}", output_id))?;
+        info!("Output {}: correction LUT written to {:?}", output_id, path);
+
+        if let Some(output) = app.state.output_manager_mut().get_output_mut(*output_id) {
+            output.color_calibration.lut = Some(path.display().to_string());
+            output.color_calibration.enabled = true;
+        }
+        // Reload even if the path is unchanged
+        app.color_calibration_luts.remove(output_id);
+    }
+    app.state.dirty = true;
+    Ok(output_ids.len())
+}
@@ -0,0 +1,275 @@
+//! Cue capture and restore for module graphs.
+//!
+//! Part and output parameters are captured by flattening their serialized
+//! form into `/`-separated parameter paths (e.g. `Source/MediaFile/opacity`).
+//! Restoring writes the values back into the serialized form, so paths that
+//! no longer exist (for example after a part changed its type) are skipped.
+//!
+//! Cue stacks are saved next to the project file (`<project>.cues.json`).
+
+use crate::app::core::app_struct::App;
+use mapmap_control::cue::{CueStacks, CueState, CueValue, ModuleCueState, ParameterSnapshot};
+use mapmap_core::module::ModuleManager;
+use mapmap_core::{OutputManager, Vec2};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+
+/// Output fields that identify an output rather than describe its settings.
+const OUTPUT_IDENTITY_FIELDS: &[&str] = &["id", "name"];
+
+/// Capture the current module graph and output settings as a cue state.
+pub fn capture_module_state(
+    modules: &ModuleManager,
+    outputs: &OutputManager,
+    active_module: Option<u64>,
+) -> ModuleCueState {
+    let mut state = ModuleCueState::new();
+    state.active_module = active_module;
+
+    for module in modules.modules() {
+        for part in &module.parts {
+            if let Ok(value) = serde_json::to_value(&part.part_type) {
+                flatten_value("", &value, state.part_mut(module.id, part.id));
+            }
+        }
+    }
+
+    for output in outputs.outputs() {
+        if let Ok(Value::Object(fields)) = serde_json::to_value(output) {
+            let snapshot = state.output_mut(output.id);
+            for (key, value) in &fields {
+                if !OUTPUT_IDENTITY_FIELDS.contains(&key.as_str()) {
+                    flatten_value(key, value, snapshot);
+                }
+            }
+        }
+    }
+
+    state
+}
+
+/// Apply a cue state to the module graph and output settings.
+///
+/// Returns the module that should become active, if the cue selects one.
+pub fn apply_module_state(
+    modules: &mut ModuleManager,
+    outputs: &mut OutputManager,
+    state: &ModuleCueState,
+) -> Option<u64> {
+    for (module_id, snapshot) in &state.modules {
+        let Some(module) = modules.get_module_mut(*module_id) else {
+            continue;
+        };
+        for (part_id, params) in &snapshot.parts {
+            if let Some(part) = module.parts.iter_mut().find(|p| p.id == *part_id) {
+                apply_parameters(&mut part.part_type, params);
+            }
+        }
+    }
+
+    for (output_id, params) in &state.outputs {
+        if let Some(output) = outputs.get_output_mut(*output_id) {
+            apply_parameters(output, params);
+        }
+    }
+
+    if !state.modules.is_empty() {
+        modules.mark_dirty();
+    }
+
+    state.active_module
+}
+
+/// File the cue stacks of a project are saved to.
+fn cue_stacks_path(project: &Path) -> PathBuf {
+    project.with_extension("cues.json")
+}
+
+/// Save cue stacks next to a project file; a project without stacks leaves no
+/// file behind.
+pub fn save_cue_stacks(stacks: &CueStacks, project: &Path) -> anyhow::Result<()> {
+    let path = cue_stacks_path(project);
+    if !stacks.is_empty() {
+        stacks.save_to_file(&path)?;
+    } else if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+/// Load the cue stacks saved next to a project file; none if there is no file.
+pub fn load_cue_stacks(project: &Path) -> CueStacks {
+    let path = cue_stacks_path(project);
+    if !path.exists() {
+        return CueStacks::new();
+    }
+    CueStacks::load_from_file(&path).unwrap_or_else(|e| {
+        error!("Failed to load cue stacks {:?}: {}", path, e);
+        CueStacks::new()
+    })
+}
+
+/// Apply the live cue state of the main cue list and all cue stacks.
+///
+/// During a crossfade the blended state changes every frame and is written
+/// every frame. Otherwise the state is only written when it changed since
+/// the last call, so manual edits made while a cue is live are kept until
+/// the next cue fires.
+pub fn sync_cue_state(app: &mut App) {
+    let live = app
+        .control_manager
+        .cue_list
+        .live_state()
+        .unwrap_or_default();
+    let mut module_state = live.module_state.clone();
+    module_state.merge(&app.control_manager.cue_stacks.live_module_state());
+
+    if live == app.applied_cue_state.0 && module_state == app.applied_cue_state.1 {
+        return;
+    }
+
+    apply_legacy_state(app, &live);
+    let active = apply_module_state(
+        std::sync::Arc::make_mut(&mut app.state.module_manager),
+        std::sync::Arc::make_mut(&mut app.state.output_manager),
+        &module_state,
+    );
+    if let Some(module_id) = active {
+        app.ui_state
+            .module_canvas
+            .set_active_module(Some(module_id));
+    }
+    app.applied_cue_state = (live, module_state);
+}
+
+/// Apply layer and effect values keyed by legacy ids.
+fn apply_legacy_state(app: &mut App, state: &CueState) {
+    if state.layer_states.is_empty() && state.effect_states.is_empty() {
+        return;
+    }
+
+    let layers = app.state.layer_manager_mut();
+    for (id, layer_state) in &state.layer_states {
+        if let Some(layer) = layers.get_layer_mut(*id as u64) {
+            layer.opacity = layer_state.opacity;
+            layer.visible = layer_state.visible;
+            layer.transform.position = Vec2::new(layer_state.position.0, layer_state.position.1);
+            layer.transform.rotation.z = layer_state.rotation.to_radians();
+            layer.transform.scale = Vec2::splat(layer_state.scale);
+        }
+    }
+
+    let effects = app.state.effect_chain_mut();
+    for (id, effect_state) in &state.effect_states {
+        if let Some(effect) = effects.get_effect_mut(*id as u64) {
+            effect.enabled = effect_state.enabled;
+            for (name, value) in &effect_state.parameters {
+                effect.parameters.insert(name.clone(), *value);
+            }
+        }
+    }
+}
+
+/// Write captured parameters back into a serializable value.
+fn apply_parameters<T: Serialize + DeserializeOwned>(target: &mut T, params: &ParameterSnapshot) {
+    let Ok(mut value) = serde_json::to_value(&*target) else {
+        return;
+    };
+
+    let mut changed = false;
+    for (path, param) in &params.parameters {
+        if let Some(slot) = lookup_path_mut(&mut value, path) {
+            let new_value = to_json(param);
+            if *slot != new_value {
+                *slot = new_value;
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        match serde_json::from_value(value) {
+            Ok(updated) => *target = updated,
+            Err(e) => warn!("Failed to apply cue parameters: {}", e),
+        }
+    }
+}
+
+/// Flatten a JSON value into leaf parameters.
+fn flatten_value(prefix: &str, value: &Value, snapshot: &mut ParameterSnapshot) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", prefix, key)
+        }
+    };
+
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                flatten_value(&join(key), field, snapshot);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_value(&join(&index.to_string()), item, snapshot);
+            }
+        }
+        Value::Bool(b) => snapshot.set_parameter(prefix, CueValue::Bool(*b)),
+        Value::Number(n) => {
+            if n.is_f64() {
+                let f = n.as_f64().unwrap_or_default();
+                snapshot.set_parameter(prefix, CueValue::Float(f as f32));
+            } else if let Some(i) = n.as_i64() {
+                snapshot.set_parameter(prefix, CueValue::Int(i));
+            } else if let Some(u) = n.as_u64() {
+                snapshot.set_parameter(prefix, CueValue::UInt(u));
+            }
+        }
+        Value::String(s) => snapshot.set_parameter(prefix, CueValue::Text(s.clone())),
+        Value::Null => {}
+    }
+}
+
+/// Resolve a `/`-separated parameter path inside a JSON value.
+fn lookup_path_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('/')
+        .try_fold(value, |current, key| match current {
+            Value::Object(fields) => fields.get_mut(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        })
+}
+
+fn to_json(value: &CueValue) -> Value {
+    match value {
+        CueValue::Float(f) => serde_json::json!(f),
+        CueValue::Int(i) => serde_json::json!(i),
+        CueValue::UInt(u) => serde_json::json!(u),
+        CueValue::Bool(b) => Value::Bool(*b),
+        CueValue::Text(s) => Value::String(s.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_integers_round_trip() {
+        let value = serde_json::json!({ "id": u64::MAX, "offset": -3 });
+        let mut snapshot = ParameterSnapshot::default();
+        flatten_value("", &value, &mut snapshot);
+
+        assert_eq!(snapshot.parameters["id"], CueValue::UInt(u64::MAX));
+        assert_eq!(snapshot.parameters["offset"], CueValue::Int(-3));
+        assert_eq!(
+            to_json(&snapshot.parameters["id"]),
+            serde_json::json!(u64::MAX)
+        );
+    }
+}
@@ -56,6 +56,13 @@ pub fn perform_evaluation(
             beat_detected: analysis.beat_detected,
             beat_strength: analysis.beat_strength,
             bpm: analysis.tempo_bpm,
+            onset_detected: analysis.onset_detected,
+            band_onsets: analysis.band_onsets,
+            spectral_centroid: analysis.spectral_centroid,
+            spectral_flatness: analysis.spectral_flatness,
+            spectral_rolloff: analysis.spectral_rolloff,
+            chroma: analysis.chroma,
+            key: analysis.key,
         };
         runner.update(&trigger_data, &node_triggers);
     }
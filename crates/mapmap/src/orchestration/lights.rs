@@ -0,0 +1,382 @@
+//! Light outputs.
+//!
+//! Keeps one light sink per `OutputType::Light` part of the evaluated modules.
+//! Effect outputs are driven from the audio analysis here; spatial outputs
+//! get the colors sampled from their layer by the render loop.
+
+use crate::app::core::app_struct::App;
+use mapmap_control::dmx::{Fixture, FixtureProfile, LedLayout, LedStrip};
+use mapmap_control::light::effects::{
+    ChaseEffect, ColorCycleEffect, FlashEffect, LightEffect, MultiBandEffect, PulseEffect,
+    SparkleEffect,
+};
+use mapmap_control::light::{
+    colors_by_channel, AudioSpectrum, DmxLightSink, LightNode, LightSink, NanoleafLightSink,
+    WledLightSink,
+};
+use mapmap_core::audio::analyzer_v2::AudioAnalysisV2;
+use mapmap_core::module::{
+    DmxProtocol, LedStripLayout, LedStripPatch, LightBackend, LightEffectKind, LightFixtureLayout,
+    LightMappingMode, ModulePartId, ModulePartType, OutputType, WledProtocol,
+};
+use std::collections::{HashMap, HashSet};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+type SinkResult = mapmap_control::Result<Box<dyn LightSink>>;
+
+/// Connection state of one light output.
+enum SinkState {
+    /// Colors go to the shared Hue controller.
+    Hue,
+    /// The device is being set up in the background.
+    Connecting(JoinHandle<SinkResult>),
+    Ready(Box<dyn LightSink>),
+    /// A pixel controller with strips mapped onto the canvas.
+    Strips(WledLightSink, Vec<LedStrip>),
+    /// Setup failed; retried when the backend settings change.
+    Failed,
+}
+
+/// Runtime state of one light output part.
+pub struct LightOutputRuntime {
+    /// Settings the sink was created with.
+    backend: LightBackend,
+    sink: SinkState,
+    effect_kind: LightEffectKind,
+    color: [f32; 3],
+    effect: Box<dyn LightEffect>,
+}
+
+impl LightOutputRuntime {
+    fn new(
+        app: &App,
+        name: &str,
+        backend: &LightBackend,
+        effect_kind: LightEffectKind,
+        color: [f32; 3],
+    ) -> Self {
+        Self {
+            backend: backend.clone(),
+            sink: open_sink(app, name, backend),
+            effect_kind,
+            color,
+            effect: create_effect(effect_kind, color),
+        }
+    }
+}
+
+/// Start talking to the device configured by `backend`.
+fn open_sink(app: &App, name: &str, backend: &LightBackend) -> SinkState {
+    let result: SinkResult = match backend {
+        LightBackend::Hue => return SinkState::Hue,
+        LightBackend::Nanoleaf { host, auth_token } => {
+            let (host, auth_token) = (host.clone(), auth_token.clone());
+            return SinkState::Connecting(app.tokio_runtime.spawn(async move {
+                let sink = NanoleafLightSink::connect(&host, &auth_token).await?;
+                Ok(Box::new(sink) as Box<dyn LightSink>)
+            }));
+        }
+        LightBackend::Wled {
+            host,
+            led_count,
+            protocol,
+            strips,
+        } => {
+            let protocol = match protocol {
+                WledProtocol::Drgb => mapmap_control::light::WledProtocol::Drgb,
+                WledProtocol::Dnrgb => mapmap_control::light::WledProtocol::Dnrgb,
+                WledProtocol::Ddp => mapmap_control::light::WledProtocol::Ddp,
+            };
+            match WledLightSink::with_protocol(host, *led_count as usize, protocol) {
+                Ok(sink) if !strips.is_empty() => {
+                    return SinkState::Strips(sink, led_strips(strips));
+                }
+                result => result.map(|sink| Box::new(sink) as Box<dyn LightSink>),
+            }
+        }
+        LightBackend::Dmx {
+            protocol,
+            target,
+            universe,
+            fixtures,
+        } => open_dmx(name, *protocol, target, *universe, fixtures),
+    };
+    match result {
+        Ok(sink) => SinkState::Ready(sink),
+        Err(e) => {
+            error!("Light output '{}' could not be opened: {}", name, e);
+            SinkState::Failed
+        }
+    }
+}
+
+fn open_dmx(
+    name: &str,
+    protocol: DmxProtocol,
+    target: &str,
+    universe: u16,
+    fixtures: &[mapmap_core::module::LightFixturePatch],
+) -> SinkResult {
+    let mut sink = match protocol {
+        DmxProtocol::ArtNet => DmxLightSink::art_net(universe, target)?,
+        DmxProtocol::Sacn => DmxLightSink::sacn(universe, name)?,
+    };
+    for (index, patch) in fixtures.iter().enumerate() {
+        // Fixtures start in a row from left to right; lamp_positions moves them
+        let x = if fixtures.len() > 1 {
+            index as f64 / (fixtures.len() - 1) as f64 * 2.0 - 1.0
+        } else {
+            0.0
+        };
+        let profile = match patch.layout {
+            LightFixtureLayout::Dimmer => FixtureProfile::generic_dimmer(),
+            LightFixtureLayout::Rgb => FixtureProfile::rgb_par(),
+            LightFixtureLayout::Rgba => FixtureProfile::rgba_par(),
+            LightFixtureLayout::Rgbw => FixtureProfile::rgbw_par(),
+        };
+        let fixture = Fixture::new(
+            index as u32,
+            patch.id.clone(),
+            profile,
+            universe,
+            patch.start_address,
+        );
+        sink.add_fixture(fixture, x, 0.0)?;
+    }
+    Ok(Box::new(sink))
+}
+
+/// The strips of a pixel controller as the sampler expects them.
+fn led_strips(patches: &[LedStripPatch]) -> Vec<LedStrip> {
+    patches
+        .iter()
+        .enumerate()
+        .map(|(index, patch)| {
+            let layout = match patch.layout {
+                LedStripLayout::Line { start, end, count } => LedLayout::Line { start, end, count },
+                LedStripLayout::Matrix {
+                    top_left,
+                    bottom_right,
+                    columns,
+                    rows,
+                } => LedLayout::Matrix {
+                    top_left,
+                    bottom_right,
+                    columns,
+                    rows,
+                },
+                LedStripLayout::Serpentine {
+                    top_left,
+                    bottom_right,
+                    columns,
+                    rows,
+                } => LedLayout::Serpentine {
+                    top_left,
+                    bottom_right,
+                    columns,
+                    rows,
+                },
+            };
+            LedStrip::new(index as u32, patch.id.clone(), layout, patch.start_pixel)
+        })
+        .collect()
+}
+
+fn create_effect(kind: LightEffectKind, color: [f32; 3]) -> Box<dyn LightEffect> {
+    let color = (
+        (color[0] * 255.0).clamp(0.0, 255.0) as u8,
+        (color[1] * 255.0).clamp(0.0, 255.0) as u8,
+        (color[2] * 255.0).clamp(0.0, 255.0) as u8,
+    );
+    match kind {
+        LightEffectKind::Pulse => Box::new(PulseEffect::new(color)),
+        LightEffectKind::MultiBand => Box::new(MultiBandEffect::new()),
+        LightEffectKind::Chase => Box::new(ChaseEffect::new(color, 1.0)),
+        LightEffectKind::Sparkle => Box::new(SparkleEffect::new(color, 8.0)),
+        LightEffectKind::ColorCycle => Box::new(ColorCycleEffect::new(0.1, 1.0)),
+        LightEffectKind::Flash => Box::new(FlashEffect::new(color, 0.6)),
+    }
+}
+
+/// Bass, mids and highs of the 9-band analysis, as the light effects expect them.
+fn spectrum(analysis: &AudioAnalysisV2) -> AudioSpectrum {
+    let bands = &analysis.band_energies;
+    let average = |range: &[f32]| range.iter().sum::<f32>() / range.len() as f32;
+    AudioSpectrum {
+        bass: bands[0].max(bands[1]).clamp(0.0, 1.0),
+        mids: average(&bands[2..5]).clamp(0.0, 1.0),
+        highs: average(&bands[5..9]).clamp(0.0, 1.0),
+        energy: analysis.rms_volume.clamp(0.0, 1.0),
+    }
+}
+
+/// Nodes with the positions from `lamp_positions` (normalized, origin top
+/// left = front left) applied.
+fn placed_nodes(
+    mut nodes: Vec<LightNode>,
+    lamp_positions: &HashMap<String, (f32, f32)>,
+) -> Vec<LightNode> {
+    for node in &mut nodes {
+        if let Some((nx, ny)) = lamp_positions.get(&node.id) {
+            node.x = *nx as f64 * 2.0 - 1.0;
+            node.y = 1.0 - *ny as f64 * 2.0;
+        }
+    }
+    nodes
+}
+
+/// The connected sink, or the Hue controller for Hue outputs.
+fn sink_of<'a>(
+    state: &'a mut SinkState,
+    hue: &'a mut mapmap_control::HueController,
+) -> Option<&'a mut dyn LightSink> {
+    match state {
+        SinkState::Hue if hue.is_connected() => Some(hue),
+        SinkState::Ready(sink) => Some(sink.as_mut()),
+        SinkState::Strips(sink, _) => Some(sink),
+        _ => None,
+    }
+}
+
+/// Open, close and reconfigure light sinks for the light outputs in
+/// `modules`, and run the effect of every effect output.
+pub fn update_light_outputs(app: &mut App, modules: &[u64], analysis: &AudioAnalysisV2, dt: f32) {
+    let mut outputs = Vec::new();
+    for module_id in modules {
+        let Some(module) = app.state.module_manager.get_module(*module_id) else {
+            continue;
+        };
+        for part in &module.parts {
+            if let ModulePartType::Output(output @ OutputType::Light { .. }) = &part.part_type {
+                outputs.push((part.id, output.clone()));
+            }
+        }
+    }
+    let active: HashSet<ModulePartId> = outputs.iter().map(|(id, _)| *id).collect();
+    app.light_outputs.retain(|id, _| active.contains(id));
+
+    let spectrum = spectrum(analysis);
+    for (part_id, output) in outputs {
+        let OutputType::Light {
+            name,
+            backend,
+            lamp_positions,
+            mapping_mode,
+            effect,
+            color,
+        } = output
+        else {
+            continue;
+        };
+
+        let stale = app
+            .light_outputs
+            .get(&part_id)
+            .map_or(true, |runtime| runtime.backend != backend);
+        if stale {
+            info!("Opening {} light output '{}'", backend.label(), name);
+            let runtime = LightOutputRuntime::new(app, &name, &backend, effect, color);
+            app.light_outputs.insert(part_id, runtime);
+        }
+        let Some(runtime) = app.light_outputs.get_mut(&part_id) else {
+            continue;
+        };
+        if runtime.effect_kind != effect || runtime.color != color {
+            runtime.effect_kind = effect;
+            runtime.color = color;
+            runtime.effect = create_effect(effect, color);
+        }
+
+        if matches!(&runtime.sink, SinkState::Connecting(handle) if handle.is_finished()) {
+            if let SinkState::Connecting(handle) =
+                std::mem::replace(&mut runtime.sink, SinkState::Failed)
+            {
+                // Already finished, so this doesn't block
+                match app.tokio_runtime.block_on(handle) {
+                    Ok(Ok(sink)) => {
+                        info!("Light output '{}' connected", name);
+                        runtime.sink = SinkState::Ready(sink);
+                    }
+                    Ok(Err(e)) => error!("Light output '{}' could not connect: {}", name, e),
+                    Err(e) => error!("Light output '{}' setup task failed: {}", name, e),
+                }
+            }
+        }
+
+        if mapping_mode != LightMappingMode::Effect {
+            continue;
+        }
+        if let Some(sink) = sink_of(&mut runtime.sink, &mut app.hue_controller) {
+            let nodes = placed_nodes(sink.nodes(), &lamp_positions);
+            let colors = runtime.effect.update(&spectrum, &nodes, dt);
+            if let Err(e) = sink.send(&colors) {
+                warn!("Light output '{}': {}", name, e);
+            }
+        }
+    }
+}
+
+/// Normalized sampling position of every lamp of a spatial light output;
+/// lamps without an entry in `lamp_positions` use their device position.
+pub fn sample_positions(
+    app: &mut App,
+    part_id: ModulePartId,
+    lamp_positions: &HashMap<String, (f32, f32)>,
+) -> HashMap<String, (f32, f32)> {
+    let Some(runtime) = app.light_outputs.get_mut(&part_id) else {
+        return HashMap::new();
+    };
+    let Some(sink) = sink_of(&mut runtime.sink, &mut app.hue_controller) else {
+        return HashMap::new();
+    };
+    sink.nodes()
+        .into_iter()
+        .map(|node| {
+            let position = lamp_positions
+                .get(&node.id)
+                .copied()
+                .unwrap_or((((node.x + 1.0) / 2.0) as f32, ((1.0 - node.y) / 2.0) as f32));
+            (node.id, position)
+        })
+        .collect()
+}
+
+/// Strips and LED count of a spatial light output driving a pixel controller
+/// with mapped strips.
+pub fn strip_layout(app: &App, part_id: ModulePartId) -> Option<(Vec<LedStrip>, usize)> {
+    match &app.light_outputs.get(&part_id)?.sink {
+        SinkState::Strips(sink, strips) => Some((strips.clone(), sink.led_count())),
+        _ => None,
+    }
+}
+
+/// Send the pixels sampled along the strips of a light output.
+pub fn send_strip_pixels(app: &mut App, part_id: ModulePartId, pixels: &[(u8, u8, u8)]) {
+    if let Some(LightOutputRuntime {
+        sink: SinkState::Strips(sink, _),
+        ..
+    }) = app.light_outputs.get_mut(&part_id)
+    {
+        if let Err(e) = sink.send_pixels(pixels) {
+            warn!("Light output {}: {}", part_id, e);
+        }
+    }
+}
+
+/// Send the lamp colors sampled for a spatial light output.
+pub fn send_sampled_colors(
+    app: &mut App,
+    part_id: ModulePartId,
+    updates: &[(String, f32, f32, f32)],
+) {
+    let Some(runtime) = app.light_outputs.get_mut(&part_id) else {
+        return;
+    };
+    if let Some(sink) = sink_of(&mut runtime.sink, &mut app.hue_controller) {
+        let colors = colors_by_channel(&sink.nodes(), updates);
+        if let Err(e) = sink.send(&colors) {
+            warn!("Light output {}: {}", part_id, e);
+        }
+    }
+}
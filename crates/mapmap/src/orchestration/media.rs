@@ -4,6 +4,7 @@ use crossbeam_channel::Sender;
 use mapmap_core::module::{ModulePartType, SourceType};
 use mapmap_render::TexturePool;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
@@ -20,6 +21,15 @@ pub struct MediaPlayerHandle {
     pub command_tx: Sender<mapmap_media::PlaybackCommand>,
     /// Update channel to send delta time
     pub update_tx: Sender<f32>,
+    /// Playback position in seconds (`f64` bits), written by the player thread
+    pub position: Arc<AtomicU64>,
+}
+
+impl MediaPlayerHandle {
+    /// Current playback position in seconds.
+    pub fn position_secs(&self) -> f64 {
+        f64::from_bits(self.position.load(Ordering::Relaxed))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -151,6 +161,9 @@ pub fn create_player_handle(
         player.pause().map_err(anyhow::Error::from)?;
     }
 
+    let position = Arc::new(AtomicU64::new(0.0f64.to_bits()));
+    let thread_position = position.clone();
+
     std::thread::spawn(move || {
         loop {
             // Block until we get an update or command
@@ -180,6 +193,10 @@ pub fn create_player_handle(
                             );
                         }
                     }
+                    thread_position.store(
+                        player.current_time().as_secs_f64().to_bits(),
+                        Ordering::Relaxed,
+                    );
                 }
                 Err(_) => {
                     // Channel disconnected, stop the thread
@@ -196,6 +213,7 @@ pub fn create_player_handle(
         loop_enabled,
         command_tx: cmd_tx,
         update_tx: upd_tx,
+        position,
     })
 }
 
@@ -1,8 +1,22 @@
+/// Audio analysis source switching.
+pub mod audio;
+/// Multi-point color matching across projectors.
+pub mod color_match;
+/// Cue capture and restore for module graphs.
+pub mod cues;
 /// Node evaluation and module logic.
 pub mod evaluation;
+/// Vendor-neutral light outputs.
+pub mod lights;
 /// Media player orchestration.
 pub mod media;
 /// Specialized node logic (e.g. Bevy synchronization).
 pub mod node_logic;
 /// Output window management.
 pub mod outputs;
+/// Scheduled show playback.
+pub mod schedule;
+/// Structured-light projector calibration.
+pub mod structured_light;
+/// Global tempo clock.
+pub mod tempo;
@@ -1,4 +1,5 @@
 use crate::app::core::app_struct::App;
+use crate::orchestration::cues::{load_cue_stacks, save_cue_stacks};
 use anyhow::Result;
 use std::path::Path;
 
@@ -7,12 +8,20 @@ pub fn update_node_logic(app: &mut App) {
     // Sync audio config to analyzer
     app.audio_analyzer
         .update_config(app.state.audio_config.clone());
+    app.audio_router.configure(&app.state.audio_config);
+}
+
+/// Save the project and its cue stacks.
+pub fn save_project_file(app: &App, path: &Path) -> Result<()> {
+    mapmap_io::save_project(&app.state, path)?;
+    save_cue_stacks(&app.control_manager.cue_stacks, path)
 }
 
 /// Load a project file into the application.
 pub fn load_project_file(app: &mut App, path: &Path) -> Result<()> {
     let state = mapmap_io::load_project(path)?;
     app.state = state;
+    app.control_manager.cue_stacks = load_cue_stacks(path);
     app.history.clear();
 
     // Clear selections to avoid referencing deleted IDs
@@ -23,6 +32,7 @@ pub fn load_project_file(app: &mut App, path: &Path) -> Result<()> {
     // Notify subsystems of new state
     app.audio_analyzer
         .update_config(app.state.audio_config.clone());
+    app.audio_router.configure(&app.state.audio_config);
 
     Ok(())
 }
@@ -43,5 +43,22 @@ pub fn sync_output_windows(
         }
     }
 
+    // 2. HDR encodings get an HDR surface where the display offers one;
+    // calibration patterns and color patches are drawn for sRGB surfaces
+    for id in &active_window_ids {
+        let hdr = app.output_transform_renderer.is_some()
+            && app
+                .state
+                .output_manager
+                .get_output(*id)
+                .is_some_and(|output| output.output_transform.transform.is_hdr())
+            && !app
+                .structured_light
+                .as_ref()
+                .is_some_and(|session| session.output_id == *id)
+            && !app.color_patch.is_some_and(|(patch_id, _)| patch_id == *id);
+        app.window_manager.set_hdr_surface(&app.backend, *id, hdr);
+    }
+
     Ok(())
 }
@@ -0,0 +1,76 @@
+//! Scheduled show playback.
+//!
+//! The schedule lives next to the autosave in the local data directory
+//! (`MapFlow/schedule.json`) and every executed entry is appended to
+//! `MapFlow/schedule_log.jsonl`. Cue triggers are handled by the control
+//! manager itself; everything that touches application state is handled here.
+
+use crate::app::core::app_struct::App;
+use crate::orchestration::node_logic::load_project_file;
+use mapmap_control::scheduler::{EventLog, Schedule, ScheduleAction, Scheduler};
+use std::path::PathBuf;
+use tracing::{error, info, warn};
+
+fn data_file(name: &str) -> Option<PathBuf> {
+    dirs::data_local_dir().map(|p| p.join("MapFlow").join(name))
+}
+
+/// Create the scheduler from the saved schedule, if there is one.
+///
+/// Playback is only enabled when a schedule file exists, so a fresh install
+/// never acts on its own.
+pub fn load_scheduler() -> Scheduler {
+    let mut scheduler = Scheduler::new();
+
+    if let Some(path) = data_file("schedule_log.jsonl") {
+        match EventLog::open(&path) {
+            Ok(log) => scheduler = scheduler.with_log(log),
+            Err(e) => warn!("Failed to open schedule log {:?}: {}", path, e),
+        }
+    }
+
+    if let Some(path) = data_file("schedule.json").filter(|p| p.exists()) {
+        match Schedule::load_from_file(&path) {
+            Ok(schedule) => {
+                info!("Scheduled playback enabled from {:?}", path);
+                scheduler = scheduler.with_schedule(schedule);
+                scheduler.set_enabled(true);
+            }
+            Err(e) => error!("Failed to load schedule {:?}: {}", path, e),
+        }
+    }
+
+    scheduler
+}
+
+/// Execute scheduled actions that were collected by the control manager.
+pub fn apply_scheduled_actions(app: &mut App) {
+    let actions = std::mem::take(&mut app.control_manager.scheduled_actions);
+
+    for action in actions {
+        match action {
+            ScheduleAction::StartModule(id) => {
+                if app.state.module_manager.get_module(id).is_some() {
+                    app.ui_state.module_canvas.set_active_module(Some(id));
+                } else {
+                    warn!("Scheduled module {} not found", id);
+                }
+            }
+            ScheduleAction::StopModule(id) => {
+                if app.ui_state.module_canvas.active_module_id() == Some(id) {
+                    app.ui_state.module_canvas.set_active_module(None);
+                }
+            }
+            ScheduleAction::BlankOutputs(blank) => {
+                app.state.layer_manager_mut().composition.master_blackout = blank;
+            }
+            ScheduleAction::LoadProject(path) => {
+                if let Err(e) = load_project_file(app, &path) {
+                    error!("Scheduled project load {:?} failed: {}", path, e);
+                }
+            }
+            // Cue triggers are executed by the control manager
+            ScheduleAction::TriggerCue { .. } => {}
+        }
+    }
+}
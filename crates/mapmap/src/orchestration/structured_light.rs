@@ -0,0 +1,245 @@
+//! Structured-light projector calibration.
+//!
+//! A projector output shows the Gray-code sequence frame by frame (the frames
+//! can also be exported for other playback tools). Capturing is left to a
+//! camera tool outside the app: live inputs (`SourceType::LiveInput`) have no
+//! capture backend yet, so frames can't be grabbed in step with the pattern.
+//! The captures are decoded from one folder per output (`output_<id>`) on a
+//! worker thread, and the generated warps replace the meshes of the layers
+//! feeding each projector. Blend masks are written next to the captures and
+//! set as the edge blend mask of the matching outputs.
+
+use crate::app::core::app_struct::App;
+use anyhow::{anyhow, Context, Result};
+use mapmap_core::module::{
+    LayerType, MeshType, ModuleId, ModulePartId, ModulePartType, OutputType,
+};
+use mapmap_core::structured_light::{
+    decode, generate_warps, load_captures, DecodeSettings, GrayCodePattern, WarpSettings,
+};
+use mapmap_core::OutputId;
+use std::path::Path;
+use tracing::info;
+
+/// Pattern frame shown on an output instead of its content.
+pub struct PatternSession {
+    /// Output showing the pattern.
+    pub output_id: OutputId,
+    /// Pattern sequence sized to the output.
+    pub pattern: GrayCodePattern,
+    /// Frame of the sequence being shown.
+    pub frame: usize,
+    /// Frame currently uploaded to the pattern texture.
+    pub uploaded: Option<usize>,
+}
+
+/// Pattern sequence for an output: its configured resolution, else the
+/// window size.
+pub fn pattern_for(app: &App, output_id: OutputId) -> GrayCodePattern {
+    let configured = app
+        .state
+        .module_manager
+        .modules()
+        .into_iter()
+        .flat_map(|module| module.parts.iter())
+        .find_map(|part| match &part.part_type {
+            ModulePartType::Output(OutputType::Projector {
+                id,
+                output_width,
+                output_height,
+                ..
+            }) if *id == output_id && *output_width > 0 && *output_height > 0 => {
+                Some((*output_width, *output_height))
+            }
+            _ => None,
+        });
+    let (width, height) = configured
+        .or_else(|| {
+            app.window_manager
+                .get(output_id)
+                .map(|window| (window.surface_config.width, window.surface_config.height))
+        })
+        .unwrap_or((1920, 1080));
+    GrayCodePattern::new(width, height)
+}
+
+/// Show a frame of the pattern sequence on an output; `None` returns the
+/// output to its content.
+pub fn show_pattern(app: &mut App, output_id: OutputId, frame: Option<usize>) {
+    let Some(frame) = frame else {
+        if app
+            .structured_light
+            .as_ref()
+            .is_some_and(|session| session.output_id == output_id)
+        {
+            app.structured_light = None;
+        }
+        return;
+    };
+    let pattern = pattern_for(app, output_id);
+    let frame = frame.min(pattern.frame_count() - 1);
+    match &mut app.structured_light {
+        Some(session) if session.output_id == output_id && session.pattern == pattern => {
+            session.frame = frame;
+        }
+        session => {
+            *session = Some(PatternSession {
+                output_id,
+                pattern,
+                frame,
+                uploaded: None,
+            })
+        }
+    }
+}
+
+/// Write the pattern sequence of an output as `output_<id>/pattern_NN.png`.
+pub fn export_patterns(app: &App, output_id: OutputId, dir: &Path) -> Result<usize> {
+    let pattern = pattern_for(app, output_id);
+    let folder = dir.join(format!("output_{}", output_id));
+    std::fs::create_dir_all(&folder)?;
+    for index in 0..pattern.frame_count() {
+        if let Some(frame) = pattern.frame(index) {
+            frame.save(folder.join(format!("pattern_{:02}.png", index)))?;
+        }
+    }
+    Ok(pattern.frame_count())
+}
+
+/// Decoded warps of a module, sent back by the calibration thread.
+pub struct CalibrationResult {
+    /// Module being calibrated.
+    pub module_id: ModuleId,
+    /// (Output part, output ID, blend mask path, warp mesh) of every
+    /// calibrated projector, or why the calibration failed.
+    pub projectors: Result<Vec<(ModulePartId, OutputId, String, MeshType)>>,
+}
+
+/// Start calibrating the projector outputs of a module from the capture
+/// folders in `root`. Decoding runs on a worker thread; the result is applied
+/// by [`poll_calibration`].
+pub fn start_calibration(app: &mut App, module_id: ModuleId, root: &Path) -> Result<()> {
+    if app.structured_light_calibration.is_some() {
+        return Err(anyhow!("A calibration is already running"));
+    }
+    let module = app
+        .state
+        .module_manager
+        .get_module(module_id)
+        .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+
+    // (Output part, output ID, pattern) of every projector with captures
+    let projectors: Vec<_> = module
+        .parts
+        .iter()
+        .filter_map(|part| match &part.part_type {
+            ModulePartType::Output(OutputType::Projector { id, .. })
+                if root.join(format!("output_{}", id)).is_dir() =>
+            {
+                Some((part.id, *id, pattern_for(app, *id)))
+            }
+            _ => None,
+        })
+        .collect();
+    if projectors.is_empty() {
+        return Err(anyhow!(
+            "No output_<id> capture folders for this module in {}",
+            root.display()
+        ));
+    }
+
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    let root = root.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = tx.send(CalibrationResult {
+            module_id,
+            projectors: calibrate(&projectors, &root),
+        });
+    });
+    app.structured_light_calibration = Some(rx);
+    Ok(())
+}
+
+/// Decode the captures of each projector and write its blend mask.
+fn calibrate(
+    projectors: &[(ModulePartId, OutputId, GrayCodePattern)],
+    root: &Path,
+) -> Result<Vec<(ModulePartId, OutputId, String, MeshType)>> {
+    let mut maps = Vec::with_capacity(projectors.len());
+    for (_, output_id, pattern) in projectors {
+        let frames = load_captures(root.join(format!("output_{}", output_id)))?;
+        let map = decode(pattern, &frames, &DecodeSettings::default())
+            .with_context(|| format!("Output {}", output_id))?;
+        info!(
+            "Output {}: projector visible in {:.0}% of the camera image",
+            output_id,
+            map.coverage() * 100.0
+        );
+        maps.push(map);
+    }
+    let warps = generate_warps(&maps, &WarpSettings::default())?;
+
+    let mut calibrated = Vec::with_capacity(warps.len());
+    for ((part_id, output_id, _), warp) in projectors.iter().zip(&warps) {
+        let mask_path = root.join(format!("output_{}_blend.png", output_id));
+        warp.blend_mask.save(&mask_path)?;
+        calibrated.push((
+            *part_id,
+            *output_id,
+            mask_path.display().to_string(),
+            warp.to_mesh_type(),
+        ));
+    }
+    Ok(calibrated)
+}
+
+/// Apply a finished calibration: the warps replace the meshes of the layers
+/// feeding each projector; returns the number of calibrated outputs.
+pub fn poll_calibration(app: &mut App) -> Option<Result<usize>> {
+    let received = app.structured_light_calibration.as_ref()?.try_recv();
+    let result = match received {
+        Ok(result) => result,
+        Err(crossbeam_channel::TryRecvError::Empty) => return None,
+        Err(crossbeam_channel::TryRecvError::Disconnected) => {
+            app.structured_light_calibration = None;
+            return Some(Err(anyhow!("The calibration thread stopped")));
+        }
+    };
+    app.structured_light_calibration = None;
+    Some(apply_calibration(app, result))
+}
+
+fn apply_calibration(app: &mut App, result: CalibrationResult) -> Result<usize> {
+    let projectors = result.projectors?;
+    let module = app
+        .state
+        .module_manager_mut()
+        .get_module_mut(result.module_id)
+        .ok_or_else(|| anyhow!("Module {} not found", result.module_id))?;
+    for (part_id, _, _, mesh_type) in &projectors {
+        let layers: Vec<_> = module
+            .connections
+            .iter()
+            .filter(|connection| connection.to_part == *part_id)
+            .map(|connection| connection.from_part)
+            .collect();
+        for part in module.parts.iter_mut().filter(|p| layers.contains(&p.id)) {
+            if let ModulePartType::Layer(
+                LayerType::Single { mesh, .. } | LayerType::Group { mesh, .. },
+            ) = &mut part.part_type
+            {
+                *mesh = mesh_type.clone();
+            }
+        }
+    }
+
+    // Overlaps are blended by the masks from now on
+    for (_, output_id, mask_path, _) in &projectors {
+        if let Some(output) = app.state.output_manager_mut().get_output_mut(*output_id) {
+            output.edge_blend.mask = Some(mask_path.clone());
+        }
+    }
+    app.edge_blend_masks.clear();
+    app.state.dirty = true;
+    Ok(projectors.len())
+}
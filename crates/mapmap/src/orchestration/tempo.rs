@@ -0,0 +1,112 @@
+//! Global tempo clock.
+//!
+//! Feeds every tempo source (audio analysis, MIDI clock, controller taps and
+//! tempo controls) into the tempo service and hands the resulting beat grid
+//! to the module evaluator and the UI.
+
+use crate::app::core::app_struct::App;
+use mapmap_control::ControlTarget;
+use mapmap_core::audio::analyzer_v2::AudioAnalysisV2;
+use mapmap_core::audio::beat_grid::BeatGridState;
+use mapmap_core::audio::tempo::TempoService;
+use mapmap_core::ModuleEvaluator;
+use tracing::debug;
+
+/// Execute tempo controls (tap, BPM, nudge, resync) collected by the control manager.
+pub fn apply_tempo_controls(app: &mut App) {
+    let controls = std::mem::take(&mut app.control_manager.tempo_controls);
+
+    for (target, value) in controls {
+        match target {
+            ControlTarget::TapTempo if value.as_bool().unwrap_or(false) => app.tempo.tap(),
+            ControlTarget::TempoResync if value.as_bool().unwrap_or(false) => app.tempo.resync(),
+            ControlTarget::TempoNudge => {
+                if let Some(beats) = value.as_float() {
+                    app.tempo.nudge(beats as f64);
+                }
+            }
+            ControlTarget::TempoBpm => {
+                if let Some(bpm) = value.as_float() {
+                    app.tempo.set_manual_bpm(bpm);
+                    app.state.audio_config.tempo.manual_bpm = app.tempo.config.manual_bpm;
+                }
+            }
+            // Button releases
+            ControlTarget::TapTempo | ControlTarget::TempoResync => {}
+            other => debug!("Not a tempo control: {:?}", other),
+        }
+    }
+}
+
+/// Report this frame's tempo sources and advance the shared clock by `dt` seconds.
+pub fn update_tempo(app: &mut App, analysis: &AudioAnalysisV2, dt: f32) {
+    app.tempo.audio(
+        analysis.tempo_bpm,
+        analysis.beat_detected.then_some(analysis.beat_strength),
+    );
+
+    #[cfg(feature = "midi")]
+    {
+        let clock = &app.control_manager.midi_clock;
+        if clock.is_running() {
+            app.tempo
+                .midi_clock(clock.get_tempo_bpm(), clock.get_beat_position() as f64);
+        }
+    }
+
+    app.ui_state.beat_grid = advance_beat_grid(&mut app.tempo, &mut app.module_evaluator, dt);
+    app.ui_state.tempo_source = app.tempo.active_source();
+    app.ui_state.current_bpm = app.tempo.grid().bpm();
+}
+
+/// Advance the shared clock by `dt` seconds and hand the beat grid to the
+/// module evaluator, which fires the beat and beat-grid triggers.
+pub fn advance_beat_grid(
+    tempo: &mut TempoService,
+    evaluator: &mut ModuleEvaluator,
+    dt: f32,
+) -> BeatGridState {
+    let state = *tempo.advance(dt);
+    evaluator.update_beat_grid(&state);
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mapmap_core::audio::beat_grid::BeatDivision;
+    use mapmap_core::audio::tempo::TempoSource;
+    use mapmap_core::module::{ModuleManager, ModulePartType, TriggerType};
+
+    #[test]
+    fn test_frame_update_fires_beat_grid_triggers() {
+        let mut manager = ModuleManager::new();
+        let module_id = manager.create_module("Beats".to_string());
+        let part_id = manager
+            .get_module_mut(module_id)
+            .unwrap()
+            .add_part_with_type(
+                ModulePartType::Trigger(TriggerType::BeatGrid {
+                    division: BeatDivision::Bar,
+                }),
+                (0.0, 0.0),
+            );
+
+        let mut tempo = TempoService::default();
+        tempo.config.priority = vec![TempoSource::Manual];
+        let mut evaluator = ModuleEvaluator::new();
+        let module = manager.get_module(module_id).unwrap();
+
+        // Four seconds at 120 BPM in 4/4 in exact quarter-second frames: the
+        // first downbeat, then the bar lines at 2 s and 4 s
+        let mut fired = Vec::new();
+        for frame in 0..16 {
+            advance_beat_grid(&mut tempo, &mut evaluator, 0.25);
+            let result = evaluator.evaluate(module, &manager.shared_media, manager.graph_revision);
+            if result.trigger_values[&part_id][0] > 0.5 {
+                fired.push(frame);
+            }
+        }
+        assert_eq!(fired, vec![0, 7, 15]);
+    }
+}
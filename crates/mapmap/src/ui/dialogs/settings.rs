@@ -1,5 +1,6 @@
 use egui::{Color32, Context, RichText, Window};
 use mapmap_control::hue::controller::HueController;
+use mapmap_control::hue::mock::{MockBridge, VirtualLamp};
 use mapmap_core::AppState;
 use mapmap_render::WgpuBackend;
 use mapmap_ui::core::config::{AppLogLevel, ToolbarMetricMode};
@@ -18,6 +19,8 @@ pub struct SettingsContext<'a> {
     pub backend: &'a WgpuBackend,
     /// Hue Controller
     pub hue_controller: &'a mut HueController,
+    /// Virtual Hue Bridge
+    pub hue_virtual_bridge: &'a Option<MockBridge>,
     /// MIDI Handler
     #[cfg(feature = "midi")]
     pub midi_handler: &'a mut Option<MidiInputHandler>,
@@ -586,6 +589,158 @@ pub fn show(ctx: &Context, context: SettingsContext) {
                         });
                 });
                 ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    use mapmap_core::audio::AudioSource;
+                    ui.label("Analysis Source:");
+                    let source = context.ui_state.audio_source.clone();
+                    if ui
+                        .selectable_label(source == AudioSource::SystemInput, "Live Input")
+                        .clicked()
+                        && source != AudioSource::SystemInput
+                    {
+                        context.ui_state.actions.push(UIAction::UseSystemAudioInput);
+                    }
+                    if ui
+                        .selectable_label(source == AudioSource::AudioFile, "Audio File...")
+                        .on_hover_text("Analyze a WAV, FLAC or MP3 file instead of a live input")
+                        .clicked()
+                    {
+                        context
+                            .ui_state
+                            .actions
+                            .push(UIAction::SelectAudioFile(String::new()));
+                    }
+                    if source != AudioSource::SystemInput {
+                        if let Some(file) = &context.ui_state.audio_source_file {
+                            ui.label(RichText::new(file).weak());
+                        }
+                    }
+                });
+                ui.add_space(4.0);
+                ui.label("Channel Analyzers:")
+                    .on_hover_text("Analyze input channels separately, e.g. one per stem");
+                let mut routes = context.state.audio_config.channel_routes.clone();
+                let mut routes_changed = false;
+                let mut remove_route = None;
+                let channel_count = context.ui_state.audio_input_channels.max(1);
+                for (index, route) in routes.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        routes_changed |= ui
+                            .add(egui::TextEdit::singleline(&mut route.name).desired_width(100.0))
+                            .changed();
+                        for channel in 0..channel_count {
+                            let selected = route.channels.contains(&channel);
+                            if ui
+                                .selectable_label(selected, format!("{}", channel + 1))
+                                .clicked()
+                            {
+                                if selected {
+                                    route.channels.retain(|&c| c != channel);
+                                } else {
+                                    route.channels.push(channel);
+                                    route.channels.sort_unstable();
+                                }
+                                routes_changed = true;
+                            }
+                        }
+                        if route.channels.is_empty() {
+                            ui.label(RichText::new("all").weak());
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            remove_route = Some(index);
+                        }
+                    });
+                }
+                if ui.button("+ Add Analyzer").clicked() {
+                    let channel = routes.len() as u16 % channel_count;
+                    routes.push(mapmap_core::audio::routing::AudioChannelRoute::new(
+                        format!("Channel {}", channel + 1),
+                        vec![channel],
+                    ));
+                    routes_changed = true;
+                }
+                if let Some(index) = remove_route {
+                    routes.remove(index);
+                    routes_changed = true;
+                }
+                if routes_changed {
+                    let mut cfg = context.state.audio_config.clone();
+                    cfg.channel_routes = routes;
+                    context
+                        .ui_state
+                        .actions
+                        .push(UIAction::UpdateAudioConfig(cfg));
+                }
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Beat Grid:");
+                    let mut grid = context.state.audio_config.beat_grid.clone();
+                    ui.add(
+                        egui::DragValue::new(&mut grid.beats_per_bar)
+                            .range(2..=12)
+                            .suffix(" beats/bar"),
+                    );
+                    egui::ComboBox::from_id_salt("beat_grid_phrase_selector")
+                        .selected_text(format!("{} bar phrase", grid.phrase_bars))
+                        .show_ui(ui, |ui| {
+                            for bars in [8_u32, 16, 32] {
+                                ui.selectable_value(
+                                    &mut grid.phrase_bars,
+                                    bars,
+                                    format!("{} bars", bars),
+                                );
+                            }
+                        });
+                    ui.checkbox(&mut grid.auto_downbeat, "Auto Downbeat")
+                        .on_hover_text("Move beat 1 to the most accented beat of the bar");
+                    if grid != context.state.audio_config.beat_grid {
+                        let mut cfg = context.state.audio_config.clone();
+                        cfg.beat_grid = grid;
+                        context
+                            .ui_state
+                            .actions
+                            .push(UIAction::UpdateAudioConfig(cfg));
+                    }
+                });
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Tempo:");
+                    let mut tempo = context.state.audio_config.tempo.clone();
+                    let primary = tempo.priority.first().copied().unwrap_or_default();
+                    egui::ComboBox::from_id_salt("tempo_source_selector")
+                        .selected_text(primary.name())
+                        .show_ui(ui, |ui| {
+                            for source in mapmap_core::audio::tempo::TempoSource::all() {
+                                if ui
+                                    .selectable_label(primary == source, source.name())
+                                    .clicked()
+                                {
+                                    tempo.priority.retain(|s| *s != source);
+                                    tempo.priority.insert(0, source);
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Preferred tempo source; lost sources fall back in priority order. \
+                             The manual tempo is only used once selected here",
+                        );
+                    ui.add(
+                        egui::DragValue::new(&mut tempo.manual_bpm)
+                            .range(40.0..=300.0)
+                            .speed(0.1)
+                            .suffix(" BPM manual"),
+                    );
+                    if tempo != context.state.audio_config.tempo {
+                        let mut cfg = context.state.audio_config.clone();
+                        cfg.tempo = tempo;
+                        context
+                            .ui_state
+                            .actions
+                            .push(UIAction::UpdateAudioConfig(cfg));
+                    }
+                });
+                ui.add_space(4.0);
                 ui.horizontal(|ui| {
                     ui.label("Sample Rate:");
                     let mut sample_rate = context.state.audio_config.sample_rate;
@@ -656,6 +811,89 @@ pub fn show(ctx: &Context, context: SettingsContext) {
                             }
                         });
                 });
+                ui.add_space(10.0);
+                ui.separator();
+                ui.heading(RichText::new("Philips Hue").color(Color32::WHITE));
+                ui.add_space(4.0);
+                let mut hue_changed = false;
+                let hue = &mut context.ui_state.user_config.hue_config;
+                hue_changed |= ui
+                    .checkbox(&mut hue.virtual_bridge, "Virtual Bridge")
+                    .on_hover_text("Simulated bridge with four lamps, no hardware needed")
+                    .changed();
+                if !hue.virtual_bridge {
+                    ui.horizontal(|ui| {
+                        ui.label("Bridge IP:");
+                        hue_changed |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut hue.bridge_ip).desired_width(140.0),
+                            )
+                            .changed();
+                        if ui.button("Discover").clicked() {
+                            context.ui_state.actions.push(UIAction::DiscoverHueBridges);
+                        }
+                        if ui
+                            .button("Link")
+                            .on_hover_text("Press the link button on the bridge first")
+                            .clicked()
+                        {
+                            context.ui_state.actions.push(UIAction::RegisterHue);
+                        }
+                    });
+                    for bridge in &context.ui_state.discovered_hue_bridges {
+                        if ui
+                            .selectable_label(hue.bridge_ip == bridge.ip, &bridge.ip)
+                            .clicked()
+                        {
+                            hue.bridge_ip = bridge.ip.clone();
+                            hue_changed = true;
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Entertainment Area:");
+                        let selected = context
+                            .ui_state
+                            .available_hue_groups
+                            .iter()
+                            .find(|(id, _)| *id == hue.entertainment_area)
+                            .map(|(_, name)| name.clone())
+                            .unwrap_or_else(|| hue.entertainment_area.clone());
+                        egui::ComboBox::from_id_salt("hue_area_selector")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (id, name) in &context.ui_state.available_hue_groups {
+                                    if ui
+                                        .selectable_label(hue.entertainment_area == *id, name)
+                                        .clicked()
+                                    {
+                                        hue.entertainment_area = id.clone();
+                                        hue_changed = true;
+                                    }
+                                }
+                            });
+                        if ui.button("Refresh").clicked() {
+                            context.ui_state.actions.push(UIAction::FetchHueGroups);
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    if context.hue_controller.is_connected() {
+                        ui.label(RichText::new("Connected").color(Color32::GREEN));
+                        if ui.button("Disconnect").clicked() {
+                            context.ui_state.actions.push(UIAction::DisconnectHue);
+                        }
+                    } else if ui.button("Connect").clicked() {
+                        context.ui_state.actions.push(UIAction::ConnectHue);
+                    }
+                });
+                if hue_changed {
+                    let _ = context.ui_state.user_config.save();
+                }
+                if let Some(bridge) = context.hue_virtual_bridge {
+                    show_virtual_lamps(ui, &bridge.lamps());
+                    ctx.request_repaint();
+                }
+
                 ui.add_space(20.0);
                 ui.separator();
                 ui.vertical_centered(|ui| {
@@ -676,3 +914,35 @@ pub fn show(ctx: &Context, context: SettingsContext) {
     });
     context.ui_state.show_settings = show_settings;
 }
+
+/// Top view of the virtual bridge's lamps in their current colors.
+fn show_virtual_lamps(ui: &mut egui::Ui, lamps: &[VirtualLamp]) {
+    let (response, painter) = ui.allocate_painter(egui::vec2(200.0, 200.0), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 4.0, Color32::from_gray(30));
+    painter.text(
+        rect.center_top() + egui::vec2(0.0, 6.0),
+        egui::Align2::CENTER_TOP,
+        "Front",
+        egui::FontId::proportional(11.0),
+        Color32::GRAY,
+    );
+
+    for lamp in lamps {
+        // Bridge room coordinates: -1.0 - 1.0, y pointing to the front
+        let pos = egui::pos2(
+            rect.min.x + (lamp.node.x as f32 + 1.0) * 0.5 * rect.width(),
+            rect.min.y + (1.0 - lamp.node.y as f32) * 0.5 * rect.height(),
+        );
+        let (r, g, b) = lamp.color;
+        painter.circle_filled(pos, 10.0, Color32::from_rgb(r, g, b));
+        painter.circle_stroke(pos, 10.0, egui::Stroke::new(1.0, Color32::WHITE));
+        painter.text(
+            pos + egui::vec2(0.0, 13.0),
+            egui::Align2::CENTER_TOP,
+            lamp.node.channel_id.to_string(),
+            egui::FontId::proportional(10.0),
+            Color32::WHITE,
+        );
+    }
+}
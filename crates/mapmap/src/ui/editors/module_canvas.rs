@@ -10,6 +10,8 @@ pub struct ModuleCanvasContext<'a> {
     pub ui_state: &'a mut AppUI,
     /// Reference to the app state.
     pub state: &'a mut AppState,
+    /// Key bindings used for canvas shortcuts and the command palette.
+    pub key_bindings: &'a mapmap_control::shortcuts::KeyBindings,
 }
 
 /// Renders the module canvas inside the provided UI.
@@ -28,6 +30,7 @@ pub fn show(ui: &mut Ui, context: ModuleCanvasContext) {
         context.state.module_manager_mut(),
         &context.ui_state.i18n,
         &mut context.ui_state.actions,
+        context.key_bindings,
         mapmap_ui::ModuleCanvasRenderOptions::from(&context.ui_state.user_config),
     );
 }
@@ -455,6 +455,25 @@ impl WindowManager {
                 .configure(&backend.device, &context.surface_config);
         }
     }
+
+    /// Switches an output window between an HDR surface, where its display
+    /// offers one, and the default sRGB surface.
+    pub fn set_hdr_surface(&mut self, backend: &WgpuBackend, output_id: OutputId, hdr: bool) {
+        let Some(context) = self.windows.get_mut(&output_id) else {
+            return;
+        };
+        let format = hdr
+            .then(|| backend.hdr_surface_format(&context.surface))
+            .flatten()
+            .unwrap_or_else(|| backend.surface_format());
+        if context.surface_config.format != format {
+            info!("Output {} surface switched to {:?}", output_id, format);
+            context.surface_config.format = format;
+            context
+                .surface
+                .configure(&backend.device, &context.surface_config);
+        }
+    }
 }
 
 /// Helper function to load the application icon.
@@ -1,5 +1,6 @@
 // Color Calibration Shader for Per-Output Color Correction
 // Phase 2 Feature: Brightness, contrast, gamma, color temperature, and saturation control
+// followed by an optional correction 3D LUT (multi-point color matching)
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
@@ -19,7 +20,7 @@ struct ColorCalibration {
     gamma_b: f32,          // Blue channel gamma
     color_temp: f32,       // 2000K to 10000K
     saturation: f32,       // 0.0 to 2.0
-    padding: f32,          // Alignment padding
+    lut_size: f32,         // Correction LUT size, 0 when disabled
 }
 
 @group(0) @binding(0)
@@ -28,6 +29,10 @@ var t_input: texture_2d<f32>;
 @group(0) @binding(1)
 var s_input: sampler;
 
+// Correction LUT as a 2D atlas: width=size, height=size*size (B-slices of G rows)
+@group(0) @binding(2)
+var t_lut: texture_2d<f32>;
+
 @group(1) @binding(0)
 var<uniform> calibration: ColorCalibration;
 
@@ -72,6 +77,25 @@ fn color_temp_to_rgb(kelvin: f32) -> vec3<f32> {
     return vec3<f32>(r, g, b);
 }
 
+fn lut_cell(cell: vec3<u32>, size: u32) -> vec3<f32> {
+    return textureLoad(t_lut, vec2<u32>(cell.r, cell.b * size + cell.g), 0).rgb;
+}
+
+// Trilinear lookup in the correction LUT
+fn apply_lut(color: vec3<f32>) -> vec3<f32> {
+    let size = u32(calibration.lut_size);
+    let scaled = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)) * f32(size - 1u);
+    let c0 = vec3<u32>(floor(scaled));
+    let c1 = min(c0 + vec3<u32>(1u), vec3<u32>(size - 1u));
+    let f = fract(scaled);
+
+    let c00 = mix(lut_cell(c0, size), lut_cell(vec3<u32>(c1.r, c0.g, c0.b), size), f.r);
+    let c10 = mix(lut_cell(vec3<u32>(c0.r, c1.g, c0.b), size), lut_cell(vec3<u32>(c1.r, c1.g, c0.b), size), f.r);
+    let c01 = mix(lut_cell(vec3<u32>(c0.r, c0.g, c1.b), size), lut_cell(vec3<u32>(c1.r, c0.g, c1.b), size), f.r);
+    let c11 = mix(lut_cell(vec3<u32>(c0.r, c1.g, c1.b), size), lut_cell(c1, size), f.r);
+    return mix(mix(c00, c10, f.g), mix(c01, c11, f.g), f.b);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var color = textureSample(t_input, s_input, in.texcoord);
@@ -94,8 +118,14 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let luminance = dot(adjusted, vec3<f32>(0.299, 0.587, 0.114));
     adjusted = mix(vec3<f32>(luminance), adjusted, calibration.saturation);
 
-    // Clamp final output to valid range
-    adjusted = clamp(adjusted, vec3<f32>(0.0), vec3<f32>(1.0));
+    // Apply correction LUT (matches the projector to the common target)
+    if (calibration.lut_size >= 2.0) {
+        adjusted = apply_lut(adjusted);
+    }
+
+    // Values above 1.0 are highlights the output transform tone-maps or
+    // encodes as HDR, so only negatives are clipped
+    adjusted = max(adjusted, vec3<f32>(0.0));
 
     return vec4<f32>(adjusted, color.a);
 }
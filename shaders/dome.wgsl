@@ -0,0 +1,98 @@
+// Dome Resampling Shader
+// Treats the composed output content as a spherical image and resamples it
+// through a warp mesh whose vertices carry view directions (fisheye
+// domemaster, equirectangular or projector slice output).
+// Dome space: +Y zenith, -Z front, +X right.
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,   // Output position (0-1, origin top-left)
+    @location(1) direction: vec3<f32>,  // View direction in content space
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) direction: vec3<f32>,
+}
+
+struct DomeUniforms {
+    source_mode: u32,      // 0 = equirectangular, 1 = cubemap cross, 2 = fisheye
+    source_fov: f32,       // Fisheye source field of view in radians
+    padding: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var t_input: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_input: sampler;
+
+@group(1) @binding(0)
+var<uniform> dome: DomeUniforms;
+
+const PI: f32 = 3.14159265358979;
+const TAU: f32 = 6.28318530717959;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(in.position.x * 2.0 - 1.0, 1.0 - in.position.y * 2.0, 0.0, 1.0);
+    out.direction = in.direction;
+    return out;
+}
+
+// Texture coordinate of the content for a direction (see DomeSource::sample_uv)
+fn source_uv(d: vec3<f32>) -> vec3<f32> {
+    if (dome.source_mode == 1u) {
+        // Horizontal cubemap cross: cell (column, row) and face coordinates
+        let a = abs(d);
+        var cell: vec2<f32>;
+        var st: vec2<f32>;
+        if (a.x >= a.y && a.x >= a.z) {
+            if (d.x > 0.0) {
+                cell = vec2<f32>(2.0, 1.0);
+                st = vec2<f32>(d.z, d.y) / a.x;
+            } else {
+                cell = vec2<f32>(0.0, 1.0);
+                st = vec2<f32>(-d.z, d.y) / a.x;
+            }
+        } else if (a.y >= a.z) {
+            if (d.y > 0.0) {
+                cell = vec2<f32>(1.0, 0.0);
+                st = vec2<f32>(d.x, d.z) / a.y;
+            } else {
+                cell = vec2<f32>(1.0, 2.0);
+                st = vec2<f32>(d.x, -d.z) / a.y;
+            }
+        } else if (d.z < 0.0) {
+            cell = vec2<f32>(1.0, 1.0);
+            st = vec2<f32>(d.x, d.y) / a.z;
+        } else {
+            cell = vec2<f32>(3.0, 1.0);
+            st = vec2<f32>(-d.x, d.y) / a.z;
+        }
+        let face = vec2<f32>((st.x + 1.0) * 0.5, (1.0 - st.y) * 0.5);
+        return vec3<f32>((cell + face) / vec2<f32>(4.0, 3.0), 1.0);
+    }
+
+    let azimuth = atan2(d.x, -d.z);
+    if (dome.source_mode == 2u) {
+        // Fisheye domemaster: zenith in the center, front at the bottom
+        let zenith_angle = acos(clamp(d.y, -1.0, 1.0));
+        let max_angle = dome.source_fov * 0.5;
+        let radius = zenith_angle / max_angle * 0.5;
+        let uv = vec2<f32>(0.5) + vec2<f32>(sin(azimuth), cos(azimuth)) * radius;
+        return vec3<f32>(uv, select(0.0, 1.0, zenith_angle <= max_angle));
+    }
+
+    // Equirectangular
+    let elevation = asin(clamp(d.y, -1.0, 1.0));
+    return vec3<f32>(azimuth / TAU + 0.5, 0.5 - elevation / PI, 1.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let lookup = source_uv(normalize(in.direction));
+    // Explicit level: coordinates jump at the source seams, where derivatives are meaningless
+    let color = textureSampleLevel(t_input, s_input, lookup.xy, 0.0);
+    return vec4<f32>(color.rgb * lookup.z, color.a);
+}
@@ -12,12 +12,9 @@ struct VertexOutput {
 }
 
 struct EdgeBlendUniforms {
-    left_width: f32,      // Blend zone width (0.0-0.5)
-    right_width: f32,
-    top_width: f32,
-    bottom_width: f32,
-    gamma: f32,           // Blend curve gamma (typically 2.2)
-    padding: vec3<f32>,   // Alignment padding
+    widths: vec4<f32>,       // Blend zone widths (left, right, top, bottom; 0.0-0.5)
+    gamma: vec4<f32>,        // Blend curve gamma per channel (rgb, typically 2.2)
+    black_level: vec4<f32>,  // Black level lift (rgb) and its feather (a)
 }
 
 @group(0) @binding(0)
@@ -26,6 +23,10 @@ var t_input: texture_2d<f32>;
 @group(0) @binding(1)
 var s_input: sampler;
 
+// Grayscale blend mask (white when the output has none)
+@group(0) @binding(2)
+var t_mask: texture_2d<f32>;
+
 @group(1) @binding(0)
 var<uniform> edge_blend: EdgeBlendUniforms;
 
@@ -45,32 +46,40 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var blend_factor: f32 = 1.0;
 
     // Left edge blending
-    if (edge_blend.left_width > 0.0) {
-        let left_blend = smoothstep(0.0, edge_blend.left_width, in.texcoord.x);
+    if (edge_blend.widths.x > 0.0) {
+        let left_blend = smoothstep(0.0, edge_blend.widths.x, in.texcoord.x);
         blend_factor = blend_factor * left_blend;
     }
 
     // Right edge blending
-    if (edge_blend.right_width > 0.0) {
-        let right_blend = smoothstep(1.0, 1.0 - edge_blend.right_width, in.texcoord.x);
+    if (edge_blend.widths.y > 0.0) {
+        let right_blend = smoothstep(1.0, 1.0 - edge_blend.widths.y, in.texcoord.x);
         blend_factor = blend_factor * right_blend;
     }
 
     // Top edge blending
-    if (edge_blend.top_width > 0.0) {
-        let top_blend = smoothstep(0.0, edge_blend.top_width, in.texcoord.y);
+    if (edge_blend.widths.z > 0.0) {
+        let top_blend = smoothstep(0.0, edge_blend.widths.z, in.texcoord.y);
         blend_factor = blend_factor * top_blend;
     }
 
     // Bottom edge blending
-    if (edge_blend.bottom_width > 0.0) {
-        let bottom_blend = smoothstep(1.0, 1.0 - edge_blend.bottom_width, in.texcoord.y);
+    if (edge_blend.widths.w > 0.0) {
+        let bottom_blend = smoothstep(1.0, 1.0 - edge_blend.widths.w, in.texcoord.y);
         blend_factor = blend_factor * bottom_blend;
     }
 
+    // Per-pixel mask for curved screens and irregular overlaps
+    blend_factor = blend_factor * textureSample(t_mask, s_input, in.texcoord).r;
+
     // Apply gamma correction to blend curve for perceptually linear blending
-    let gamma_corrected = pow(blend_factor, edge_blend.gamma);
+    let gamma_corrected = pow(vec3<f32>(blend_factor), edge_blend.gamma.rgb);
+
+    // Lift the black level outside the overlaps to match the doubled black
+    // light where projectors overlap
+    let feather = max(edge_blend.black_level.a, 0.0001);
+    let lift = edge_blend.black_level.rgb * smoothstep(1.0 - feather, 1.0, blend_factor);
 
     // Apply blending to RGB, preserve alpha
-    return vec4<f32>(color.rgb * gamma_corrected, color.a);
+    return vec4<f32>(lift + color.rgb * gamma_corrected * (1.0 - lift), color.a);
 }
@@ -0,0 +1,34 @@
+// Mask Shader
+// Multiplies the alpha of a layer source by a rasterized mask coverage
+// texture before the source is mapped onto its mesh.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var t_mask: texture_2d<f32>;
+
+@group(0) @binding(2)
+var s_linear: sampler;
+
+// Fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_linear, in.uv);
+    let coverage = textureSample(t_mask, s_linear, in.uv).r;
+    return vec4<f32>(color.rgb, color.a * coverage);
+}
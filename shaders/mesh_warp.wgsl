@@ -101,7 +101,8 @@ fn apply_color_correction(color: vec4<f32>) -> vec4<f32> {
         rgb = hsv_to_rgb(hsv);
     }
 
-    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+    // Float targets keep light above white for the output transform
+    return vec4<f32>(max(rgb, vec3<f32>(0.0)), color.a);
 }
 
 // Vertex shader
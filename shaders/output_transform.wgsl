@@ -0,0 +1,160 @@
+// Output Transform Shader
+// Turns the linear Rec.709 float composition of an output (1.0 = reference
+// white) into its display signal: exposure, tone mapping and sRGB/Rec.709
+// encoding for SDR, Rec.2020 with PQ or HLG encoding for HDR.
+// Mirrors mapmap_core::hdr.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+struct OutputTransform {
+    transform: u32,         // 0 sRGB, 1 Rec.709, 2 Rec.2020 PQ, 3 HLG
+    tone_mapping: u32,      // 0 none, 1 Reinhard, 2 ACES
+    exposure_gain: f32,
+    paper_white_nits: f32,
+    peak_nits: f32,
+    target_encoding: u32,   // 0 signal, 1 sRGB target, 2 float (scRGB) target
+    _padding0: f32,
+    _padding1: f32,
+}
+
+@group(0) @binding(0)
+var t_input: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_input: sampler;
+
+@group(1) @binding(0)
+var<uniform> params: OutputTransform;
+
+// Rec.709 to Rec.2020 primaries (columns)
+const REC709_TO_REC2020 = mat3x3<f32>(
+    vec3<f32>(0.6274, 0.0691, 0.0164),
+    vec3<f32>(0.3293, 0.9195, 0.0880),
+    vec3<f32>(0.0433, 0.0114, 0.8956),
+);
+const REC2020_LUMA = vec3<f32>(0.2627, 0.6780, 0.0593);
+
+// Fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn tone_map(c: vec3<f32>) -> vec3<f32> {
+    switch params.tone_mapping {
+        case 1u: {
+            return c / (vec3<f32>(1.0) + c);
+        }
+        case 2u: {
+            return (c * (c * 2.51 + 0.03)) / (c * (c * 2.43 + 0.59) + 0.14);
+        }
+        default: {
+            return c;
+        }
+    }
+}
+
+fn srgb_oetf(v: vec3<f32>) -> vec3<f32> {
+    let low = v * 12.92;
+    let high = 1.055 * pow(v, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, v <= vec3<f32>(0.0031308));
+}
+
+fn srgb_eotf(v: vec3<f32>) -> vec3<f32> {
+    let low = v / 12.92;
+    let high = pow((v + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, v <= vec3<f32>(0.04045));
+}
+
+fn rec709_oetf(v: vec3<f32>) -> vec3<f32> {
+    let low = v * 4.5;
+    let high = 1.099 * pow(v, vec3<f32>(0.45)) - 0.099;
+    return select(high, low, v < vec3<f32>(0.018));
+}
+
+fn pq_oetf(v: vec3<f32>) -> vec3<f32> {
+    let m1 = 2610.0 / 16384.0;
+    let m2 = 2523.0 / 4096.0 * 128.0;
+    let c1 = 3424.0 / 4096.0;
+    let c2 = 2413.0 / 4096.0 * 32.0;
+    let c3 = 2392.0 / 4096.0 * 32.0;
+    let p = pow(clamp(v, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(m1));
+    return pow((c1 + c2 * p) / (1.0 + c3 * p), vec3<f32>(m2));
+}
+
+fn hlg_oetf(v: vec3<f32>) -> vec3<f32> {
+    let a = 0.17883277;
+    let b = 0.28466892;
+    let c = 0.55991073;
+    let e = clamp(v, vec3<f32>(0.0), vec3<f32>(1.0));
+    let low = sqrt(3.0 * e);
+    let high = a * log(max(12.0 * e - b, vec3<f32>(1e-6))) + c;
+    return select(high, low, e <= vec3<f32>(1.0 / 12.0));
+}
+
+// Rec.2020 display light in nits, limited to the display's peak
+fn rec2020_nits(c: vec3<f32>) -> vec3<f32> {
+    let nits = REC709_TO_REC2020 * c * max(params.paper_white_nits, 1.0);
+    return clamp(nits, vec3<f32>(0.0), vec3<f32>(max(params.peak_nits, 1.0)));
+}
+
+// Linear Rec.709 in units of 80 nits for float (scRGB) surfaces, whose
+// display encodes the light itself
+fn scrgb(linear: vec3<f32>) -> vec3<f32> {
+    let c = max(linear, vec3<f32>(0.0)) * params.exposure_gain;
+    let nits = c * max(params.paper_white_nits, 1.0);
+    return min(nits, vec3<f32>(max(params.peak_nits, 1.0))) / 80.0;
+}
+
+fn encode(linear: vec3<f32>) -> vec3<f32> {
+    let c = max(linear, vec3<f32>(0.0)) * params.exposure_gain;
+    switch params.transform {
+        case 1u: {
+            return rec709_oetf(clamp(tone_map(c), vec3<f32>(0.0), vec3<f32>(1.0)));
+        }
+        case 2u: {
+            return pq_oetf(rec2020_nits(c) / 10000.0);
+        }
+        case 3u: {
+            let peak = max(params.peak_nits, 1.0);
+            let display = rec2020_nits(c) / peak;
+            // Undo the HLG system gamma of the display
+            let gamma = 1.2 + 0.42 * log2(peak / 1000.0) / log2(10.0);
+            let luminance = dot(display, REC2020_LUMA);
+            let scene = select(
+                vec3<f32>(0.0),
+                display * pow(max(luminance, 1e-6), (1.0 - gamma) / gamma),
+                luminance > 0.0,
+            );
+            return hlg_oetf(scene);
+        }
+        default: {
+            return srgb_oetf(clamp(tone_map(c), vec3<f32>(0.0), vec3<f32>(1.0)));
+        }
+    }
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_input, s_input, in.uv);
+    var signal = encode(color.rgb);
+    switch params.target_encoding {
+        // An sRGB target encodes on write; decode first so the stored
+        // values are exactly the signal
+        case 1u: {
+            signal = srgb_eotf(signal);
+        }
+        case 2u: {
+            signal = scrgb(color.rgb);
+        }
+        default: {}
+    }
+    return vec4<f32>(signal, color.a);
+}
@@ -0,0 +1,36 @@
+// Output Slicing Shader
+// Composes input slices of an output's content onto the output raster.
+// Each slice is a quad whose corners carry the content position shown
+// there, so crop, flip and rotation are baked into the texture coordinates.
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,   // Output position (0-1, origin top-left)
+    @location(1) uv: vec2<f32>,         // Content position (0-1)
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var t_input: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_input: sampler;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(in.position.x * 2.0 - 1.0, 1.0 - in.position.y * 2.0, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(t_input, s_input, in.uv, 0.0);
+    // Parts of a slice outside the output's canvas region stay black
+    let inside = all(in.uv >= vec2<f32>(0.0)) && all(in.uv <= vec2<f32>(1.0));
+    return select(vec4<f32>(0.0, 0.0, 0.0, 1.0), color, inside);
+}